@@ -0,0 +1,381 @@
+//! Automatic ticket allocation for a single game.
+//!
+//! The engine is pure: it takes the game's tickets, its requests, and each
+//! member's prior allocations for the season, and proposes which tickets go to
+//! which request. Writing the result is left to the caller.
+//!
+//! A party is seated side by side when the game has a run of adjacent free
+//! seats long enough — same section and row, consecutive seat numbers — and
+//! its [`Proposal::seating`] says how far apart it ended up otherwise.
+
+use gtm_models::{AllocationRound, GameTicketDetail, TicketId, TicketRequest, UserId};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// How competing requests are prioritised when a game is oversubscribed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    /// Hand out one seat per request per pass, members with the fewest
+    /// prior allocations first.
+    RoundRobin,
+    /// Draw requests at random, weighted toward members with fewer prior
+    /// allocations; each draw is filled as fully as possible.
+    WeightedLottery,
+    /// Longest-standing members (lowest user id) are filled first.
+    Seniority,
+}
+
+impl Strategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Strategy::RoundRobin => "round-robin",
+            Strategy::WeightedLottery => "weighted-lottery",
+            Strategy::Seniority => "seniority",
+        }
+    }
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round-robin" => Ok(Strategy::RoundRobin),
+            "weighted-lottery" | "lottery" => Ok(Strategy::WeightedLottery),
+            "seniority" => Ok(Strategy::Seniority),
+            other => Err(format!(
+                "unknown allocation strategy '{other}' (expected round-robin, weighted-lottery, or seniority)"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a party's seats sit relative to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Seating {
+    /// Side by side in one row, or a single seat.
+    Together,
+    /// In one section, but not side by side.
+    Apart,
+    /// Across more than one section.
+    Split,
+}
+
+impl Seating {
+    /// How `tickets`, one party's seats, sit.
+    pub fn of(tickets: &[&GameTicketDetail]) -> Seating {
+        let sections: HashSet<&str> = tickets.iter().map(|t| t.section.as_str()).collect();
+        if sections.len() > 1 {
+            return Seating::Split;
+        }
+        let mut sorted = tickets.to_vec();
+        sorted.sort_by(|a, b| seat_sort_key(a).cmp(&seat_sort_key(b)));
+        if sorted.windows(2).all(|pair| beside(pair[0], pair[1])) {
+            Seating::Together
+        } else {
+            Seating::Apart
+        }
+    }
+}
+
+/// Tickets proposed for one request.
+#[derive(Debug, Clone, Serialize)]
+pub struct Proposal {
+    pub request_id: i64,
+    pub user_id: UserId,
+    pub seats_requested: i64,
+    pub ticket_ids: Vec<TicketId>,
+    pub seating: Seating,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Allocation {
+    pub strategy: Strategy,
+    /// RNG seed used, so a lottery can be replayed exactly.
+    pub seed: u64,
+    /// One entry per pending request, in priority order. Requests that
+    /// received nothing have an empty `ticket_ids`.
+    pub proposals: Vec<Proposal>,
+}
+
+impl Allocation {
+    /// `(game_ticket_id, user_id, request_id)` triples for every proposed seat.
+    pub fn assignments(&self) -> Vec<(TicketId, UserId, i64)> {
+        self.proposals
+            .iter()
+            .flat_map(|p| p.ticket_ids.iter().map(|t| (*t, p.user_id, p.request_id)))
+            .collect()
+    }
+}
+
+/// Propose an allocation of the game's `available` tickets across its
+/// `pending` requests. Other statuses are ignored.
+///
+/// `prior_allocations` maps user id → seats already assigned this season.
+pub fn allocate(
+    tickets: &[GameTicketDetail],
+    requests: &[TicketRequest],
+    prior_allocations: &HashMap<UserId, i64>,
+    strategy: Strategy,
+    seed: u64,
+) -> Allocation {
+    let mut available: Vec<&GameTicketDetail> =
+        tickets.iter().filter(|t| t.status == "available").collect();
+    available.sort_by(|a, b| seat_sort_key(a).cmp(&seat_sort_key(b)));
+
+    let pending: Vec<&TicketRequest> = requests
+        .iter()
+        .filter(|r| r.status == "pending" && r.seats_requested > 0)
+        .collect();
+    let prior = |r: &TicketRequest| prior_allocations.get(&r.user_id).copied().unwrap_or(0);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut supply = available.len() as i64;
+
+    // Step 1: decide priority order and how many seats each request gets.
+    let (order, counts): (Vec<&TicketRequest>, Vec<i64>) = match strategy {
+        Strategy::RoundRobin => {
+            let mut order = pending.clone();
+            // Stable sort keeps request creation order among equals.
+            order.sort_by_key(|r| prior(r));
+            let mut counts = vec![0i64; order.len()];
+            while supply > 0 {
+                let mut progressed = false;
+                for (i, r) in order.iter().enumerate() {
+                    if supply == 0 {
+                        break;
+                    }
+                    if counts[i] < r.seats_requested {
+                        counts[i] += 1;
+                        supply -= 1;
+                        progressed = true;
+                    }
+                }
+                if !progressed {
+                    break;
+                }
+            }
+            (order, counts)
+        }
+        Strategy::WeightedLottery => {
+            let mut pool = pending.clone();
+            let mut order = Vec::with_capacity(pool.len());
+            while !pool.is_empty() {
+                let weights: Vec<f64> =
+                    pool.iter().map(|r| 1.0 / (1.0 + prior(r) as f64)).collect();
+                let total: f64 = weights.iter().sum();
+                let mut pick = rng.r#gen::<f64>() * total;
+                let mut idx = pool.len() - 1;
+                for (i, w) in weights.iter().enumerate() {
+                    if pick < *w {
+                        idx = i;
+                        break;
+                    }
+                    pick -= w;
+                }
+                order.push(pool.remove(idx));
+            }
+            let counts = fill_in_order(&order, &mut supply);
+            (order, counts)
+        }
+        Strategy::Seniority => {
+            let mut order = pending.clone();
+            order.sort_by_key(|r| r.user_id);
+            let counts = fill_in_order(&order, &mut supply);
+            (order, counts)
+        }
+    };
+
+    // Step 2: seat each party together where a run of seats allows.
+    let mut free = available;
+    let proposals = order
+        .iter()
+        .zip(counts)
+        .map(|(r, n)| {
+            let seats = take_seats(&mut free, n as usize);
+            Proposal {
+                request_id: r.id,
+                user_id: r.user_id,
+                seats_requested: r.seats_requested,
+                ticket_ids: seats.iter().map(|t| t.id).collect(),
+                seating: Seating::of(&seats),
+            }
+        })
+        .collect();
+
+    Allocation {
+        strategy,
+        seed,
+        proposals,
+    }
+}
+
+/// Rules a season is replayed under by [`simulate`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Policy {
+    pub strategy: Strategy,
+    /// Most seats one request can receive.
+    pub max_seats_per_game: Option<i64>,
+    /// Most seats one member can receive across the season.
+    pub max_seats_per_season: Option<i64>,
+}
+
+/// Replay `rounds` in order under `policy` and return the seats each member
+/// would have received.
+///
+/// Every ticket counts as available and every request that wasn't withdrawn
+/// as pending, trimmed to the policy's caps. Seats won in earlier rounds are
+/// the prior allocations for later ones. Round `i` draws with `seed + i`, so
+/// the same seed replays the same season.
+pub fn simulate(rounds: &[AllocationRound], policy: &Policy, seed: u64) -> HashMap<UserId, i64> {
+    let mut won: HashMap<UserId, i64> = HashMap::new();
+    for (i, round) in rounds.iter().enumerate() {
+        let tickets: Vec<GameTicketDetail> = round
+            .tickets
+            .iter()
+            .map(|t| GameTicketDetail {
+                status: "available".to_string(),
+                assigned_to: None,
+                ..t.clone()
+            })
+            .collect();
+        let requests: Vec<TicketRequest> = round
+            .requests
+            .iter()
+            .filter(|r| r.status != "withdrawn")
+            .map(|r| {
+                let mut seats = r.seats_requested;
+                if let Some(cap) = policy.max_seats_per_game {
+                    seats = seats.min(cap);
+                }
+                if let Some(cap) = policy.max_seats_per_season {
+                    seats = seats.min(cap - won.get(&r.user_id).copied().unwrap_or(0));
+                }
+                TicketRequest {
+                    seats_requested: seats.max(0),
+                    status: "pending".to_string(),
+                    ..r.clone()
+                }
+            })
+            .collect();
+        let allocation = allocate(
+            &tickets,
+            &requests,
+            &won,
+            policy.strategy,
+            seed.wrapping_add(i as u64),
+        );
+        for p in allocation.proposals {
+            *won.entry(p.user_id).or_insert(0) += p.ticket_ids.len() as i64;
+        }
+    }
+    won
+}
+
+/// Give each request as many seats as it asked for, in order, until supply runs out.
+fn fill_in_order(order: &[&TicketRequest], supply: &mut i64) -> Vec<i64> {
+    order
+        .iter()
+        .map(|r| {
+            let n = r.seats_requested.min(*supply);
+            *supply -= n;
+            n
+        })
+        .collect()
+}
+
+/// `count` of the available `tickets` side by side, from the shortest run of
+/// adjacent seats that fits (the earliest in seat order among equals), so
+/// longer runs stay free for bigger parties. `None` if no run is long
+/// enough.
+pub fn find_adjacent(tickets: &[GameTicketDetail], count: usize) -> Option<Vec<TicketId>> {
+    best_run(&sorted_available(tickets), count).map(|run| run.iter().map(|t| t.id).collect())
+}
+
+/// `count` of the available `tickets` for one party: side by side as
+/// [`find_adjacent`] picks them when possible, otherwise the first free
+/// seats in seat order.
+pub fn pick_seats(tickets: &[GameTicketDetail], count: usize) -> Vec<TicketId> {
+    let mut free = sorted_available(tickets);
+    take_seats(&mut free, count)
+        .into_iter()
+        .map(|t| t.id)
+        .collect()
+}
+
+fn sorted_available(tickets: &[GameTicketDetail]) -> Vec<&GameTicketDetail> {
+    let mut available: Vec<&GameTicketDetail> =
+        tickets.iter().filter(|t| t.status == "available").collect();
+    available.sort_by(|a, b| seat_sort_key(a).cmp(&seat_sort_key(b)));
+    available
+}
+
+/// Remove `count` seats from `free` (in seat order) for one party.
+fn take_seats<'a>(free: &mut Vec<&'a GameTicketDetail>, count: usize) -> Vec<&'a GameTicketDetail> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let picked =
+        best_run(free, count).unwrap_or_else(|| free.iter().take(count).copied().collect());
+    let ids: HashSet<TicketId> = picked.iter().map(|t| t.id).collect();
+    free.retain(|t| !ids.contains(&t.id));
+    picked
+}
+
+/// The first `count` seats of the shortest run in `sorted` long enough.
+fn best_run<'a>(
+    sorted: &[&'a GameTicketDetail],
+    count: usize,
+) -> Option<Vec<&'a GameTicketDetail>> {
+    if count == 0 {
+        return Some(Vec::new());
+    }
+    let mut best: Option<&[&GameTicketDetail]> = None;
+    let mut start = 0;
+    for end in 1..=sorted.len() {
+        if end < sorted.len() && beside(sorted[end - 1], sorted[end]) {
+            continue;
+        }
+        let run = &sorted[start..end];
+        if run.len() >= count && best.is_none_or(|b| run.len() < b.len()) {
+            best = Some(run);
+        }
+        start = end;
+    }
+    best.map(|run| run[..count].to_vec())
+}
+
+/// Whether `b` is the seat right after `a` in the same row.
+fn beside(a: &GameTicketDetail, b: &GameTicketDetail) -> bool {
+    a.section == b.section
+        && a.row == b.row
+        && matches!(
+            (a.seat.parse::<u32>(), b.seat.parse::<u32>()),
+            (Ok(x), Ok(y)) if y == x + 1
+        )
+}
+
+/// Section, row, then seat — numerically when the seat is a number.
+fn seat_sort_key(t: &GameTicketDetail) -> (&str, &str, u32, &str) {
+    (
+        &t.section,
+        &t.row,
+        t.seat.parse().unwrap_or(u32::MAX),
+        &t.seat,
+    )
+}
+
+/// A fresh random seed for callers that don't need reproducibility.
+pub fn random_seed() -> u64 {
+    rand::thread_rng().r#gen()
+}
@@ -0,0 +1,275 @@
+use gtm_alloc::{Policy, Seating, Strategy, allocate, find_adjacent, pick_seats, simulate};
+use gtm_models::{
+    AllocationRound, GamePk, GameTicketDetail, SeatId, TicketId, TicketRequest, UserId,
+};
+use std::collections::HashMap;
+
+fn ticket(id: i64, seat: &str) -> GameTicketDetail {
+    GameTicketDetail {
+        id: TicketId(id),
+        game_pk: GamePk(1),
+        seat_id: SeatId(id),
+        section: "VR313".to_string(),
+        row: "A".to_string(),
+        seat: seat.to_string(),
+        status: "available".to_string(),
+        notes: None,
+        assigned_to: None,
+    }
+}
+
+fn ticket_in(id: i64, section: &str, row: &str, seat: &str) -> GameTicketDetail {
+    GameTicketDetail {
+        section: section.to_string(),
+        row: row.to_string(),
+        ..ticket(id, seat)
+    }
+}
+
+/// Seats 1-3 and 5-6 of one row, with seat 4 missing.
+fn gapped_row() -> Vec<GameTicketDetail> {
+    vec![
+        ticket(1, "1"),
+        ticket(2, "2"),
+        ticket(3, "3"),
+        ticket(5, "5"),
+        ticket(6, "6"),
+    ]
+}
+
+fn ids(ids: &[i64]) -> Vec<TicketId> {
+    ids.iter().map(|&id| TicketId(id)).collect()
+}
+
+fn request(id: i64, user_id: i64, seats: i64) -> TicketRequest {
+    TicketRequest {
+        id,
+        user_id: UserId(user_id),
+        game_pk: GamePk(1),
+        seats_requested: seats,
+        status: "pending".to_string(),
+        notes: None,
+        flag_reason: None,
+    }
+}
+
+fn four_seats() -> Vec<GameTicketDetail> {
+    // Out of order on purpose: seats should be handed out 1, 2, 3, 10
+    vec![
+        ticket(13, "10"),
+        ticket(10, "1"),
+        ticket(11, "2"),
+        ticket(12, "3"),
+    ]
+}
+
+fn seats_for(alloc: &gtm_alloc::Allocation, request_id: i64) -> usize {
+    alloc
+        .proposals
+        .iter()
+        .find(|p| p.request_id == request_id)
+        .map(|p| p.ticket_ids.len())
+        .unwrap_or(0)
+}
+
+#[test]
+fn round_robin_spreads_seats_fewest_prior_first() {
+    let requests = vec![request(1, 100, 4), request(2, 200, 4), request(3, 300, 1)];
+    let prior = HashMap::from([(UserId(100), 10), (UserId(200), 0), (UserId(300), 5)]);
+    let alloc = allocate(&four_seats(), &requests, &prior, Strategy::RoundRobin, 0);
+
+    // Priority: user 200 (0 prior), 300 (5), 100 (10)
+    let order: Vec<i64> = alloc.proposals.iter().map(|p| p.user_id.0).collect();
+    assert_eq!(order, vec![200, 300, 100]);
+    // Pass 1: 200, 300, 100 get one each; pass 2: 200 gets the last seat
+    assert_eq!(seats_for(&alloc, 2), 2);
+    assert_eq!(seats_for(&alloc, 3), 1);
+    assert_eq!(seats_for(&alloc, 1), 1);
+    // First in priority gets the first seats, contiguous
+    assert_eq!(
+        alloc.proposals[0].ticket_ids,
+        vec![TicketId(10), TicketId(11)]
+    );
+}
+
+#[test]
+fn seniority_fills_lowest_user_id_first() {
+    let requests = vec![request(1, 300, 2), request(2, 100, 3), request(3, 200, 2)];
+    let alloc = allocate(
+        &four_seats(),
+        &requests,
+        &HashMap::new(),
+        Strategy::Seniority,
+        0,
+    );
+    assert_eq!(seats_for(&alloc, 2), 3);
+    assert_eq!(seats_for(&alloc, 3), 1);
+    assert_eq!(seats_for(&alloc, 1), 0);
+}
+
+#[test]
+fn lottery_is_reproducible_by_seed() {
+    let requests: Vec<TicketRequest> = (1..=6).map(|i| request(i, i * 100, 2)).collect();
+    let a = allocate(
+        &four_seats(),
+        &requests,
+        &HashMap::new(),
+        Strategy::WeightedLottery,
+        42,
+    );
+    let b = allocate(
+        &four_seats(),
+        &requests,
+        &HashMap::new(),
+        Strategy::WeightedLottery,
+        42,
+    );
+    assert_eq!(a.assignments(), b.assignments());
+    assert_eq!(a.assignments().len(), 4);
+}
+
+#[test]
+fn lottery_favours_members_with_fewer_prior_allocations() {
+    let requests = vec![request(1, 100, 4), request(2, 200, 4)];
+    let prior = HashMap::from([(UserId(100), 30), (UserId(200), 0)]);
+    let mut wins = 0;
+    for seed in 0..200 {
+        let alloc = allocate(
+            &four_seats(),
+            &requests,
+            &prior,
+            Strategy::WeightedLottery,
+            seed,
+        );
+        if alloc.proposals[0].user_id == UserId(200) {
+            wins += 1;
+        }
+    }
+    // Weight 1 vs 1/31: user 200 should win the vast majority of draws
+    assert!(wins > 170, "user 200 won only {wins}/200 draws");
+}
+
+#[test]
+fn ignores_non_pending_requests_and_unavailable_tickets() {
+    let mut tickets = four_seats();
+    tickets[1].status = "assigned".to_string();
+    let mut withdrawn = request(2, 200, 2);
+    withdrawn.status = "withdrawn".to_string();
+    let requests = vec![request(1, 100, 4), withdrawn];
+
+    let alloc = allocate(&tickets, &requests, &HashMap::new(), Strategy::Seniority, 0);
+    assert_eq!(alloc.proposals.len(), 1);
+    assert_eq!(
+        alloc.proposals[0].ticket_ids,
+        vec![TicketId(11), TicketId(12), TicketId(13)]
+    );
+}
+
+#[test]
+fn simulation_replays_every_request_under_the_policy_caps() {
+    // Both games actually went to user 100; the replay ignores that
+    let mut tickets = four_seats();
+    for t in &mut tickets {
+        t.status = "assigned".to_string();
+        t.assigned_to = Some(UserId(100));
+    }
+    let mut approved = request(1, 100, 4);
+    approved.status = "approved".to_string();
+    let mut declined = request(2, 200, 4);
+    declined.status = "declined".to_string();
+    let round = AllocationRound {
+        game_pk: GamePk(1),
+        tickets,
+        requests: vec![approved, declined],
+    };
+    let rounds = vec![round.clone(), round];
+
+    let policy = Policy {
+        strategy: Strategy::Seniority,
+        max_seats_per_game: None,
+        max_seats_per_season: None,
+    };
+    let seats = simulate(&rounds, &policy, 0);
+    assert_eq!(seats.get(&UserId(100)), Some(&8));
+    assert_eq!(seats.get(&UserId(200)), Some(&0));
+
+    // Two seats a game: each member gets two per game
+    let per_game = Policy {
+        max_seats_per_game: Some(2),
+        ..policy
+    };
+    let seats = simulate(&rounds, &per_game, 0);
+    assert_eq!((seats[&UserId(100)], seats[&UserId(200)]), (4, 4));
+
+    // Five a season: user 100 takes four, then one, and 200 the rest
+    let per_season = Policy {
+        max_seats_per_season: Some(5),
+        ..policy
+    };
+    let seats = simulate(&rounds, &per_season, 0);
+    assert_eq!((seats[&UserId(100)], seats[&UserId(200)]), (5, 3));
+}
+
+#[test]
+fn strategy_parses_from_config_strings() {
+    assert_eq!(
+        "round-robin".parse::<Strategy>().unwrap(),
+        Strategy::RoundRobin
+    );
+    assert_eq!(
+        "lottery".parse::<Strategy>().unwrap(),
+        Strategy::WeightedLottery
+    );
+    assert!("fifo".parse::<Strategy>().is_err());
+}
+
+#[test]
+fn adjacent_seats_come_from_the_shortest_run_that_fits() {
+    let seats = gapped_row();
+    assert_eq!(find_adjacent(&seats, 2), Some(ids(&[5, 6])));
+    assert_eq!(find_adjacent(&seats, 3), Some(ids(&[1, 2, 3])));
+    assert_eq!(find_adjacent(&seats, 4), None);
+    // With no run long enough, seats still come in seat order
+    assert_eq!(pick_seats(&seats, 4), ids(&[1, 2, 3, 5]));
+
+    // A different row or section breaks a run
+    let mut rows = gapped_row();
+    rows[1] = ticket_in(2, "VR313", "B", "2");
+    assert_eq!(find_adjacent(&rows, 3), None);
+}
+
+#[test]
+fn seating_tells_side_by_side_from_apart_and_split() {
+    let a1 = ticket(1, "1");
+    let a2 = ticket(2, "2");
+    let a5 = ticket(5, "5");
+    let other = ticket_in(9, "VR314", "A", "3");
+    assert_eq!(Seating::of(&[&a1]), Seating::Together);
+    assert_eq!(Seating::of(&[&a2, &a1]), Seating::Together);
+    assert_eq!(Seating::of(&[&a1, &a5]), Seating::Apart);
+    assert_eq!(Seating::of(&[&a1, &other]), Seating::Split);
+}
+
+#[test]
+fn allocation_seats_parties_side_by_side() {
+    // Seniority fills user 100's pair first, but it shouldn't break up the
+    // run of three that user 200's party needs
+    let requests = vec![request(1, 100, 2), request(2, 200, 3)];
+    let alloc = allocate(
+        &gapped_row(),
+        &requests,
+        &HashMap::new(),
+        Strategy::Seniority,
+        0,
+    );
+    let pair = alloc.proposals.iter().find(|p| p.request_id == 1).unwrap();
+    assert_eq!(pair.ticket_ids, ids(&[5, 6]));
+    let three = alloc.proposals.iter().find(|p| p.request_id == 2).unwrap();
+    assert_eq!(three.ticket_ids, ids(&[1, 2, 3]));
+    assert!(
+        alloc
+            .proposals
+            .iter()
+            .all(|p| p.seating == Seating::Together)
+    );
+}
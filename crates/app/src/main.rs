@@ -1,23 +1,29 @@
 use axum::http::{StatusCode, request::Parts};
 use axum::{
     Json, Router,
-    extract::{FromRef, FromRequestParts, Path, Query, State},
-    routing::{delete, get, patch, post},
+    extract::{Form, FromRef, FromRequestParts, Path, Query, State},
+    routing::{delete, get, patch, post, put},
 };
 use chrono::{Datelike, Local, Utc};
-use chrono_tz::US::Pacific;
 use clap::{Parser, Subcommand, ValueEnum};
+use gtm_models::{EntityKind, GamePk, GameType, Hydration, SeatId, TicketId, UserId, WithRefs};
+use gtm_notify::templates::Locale;
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use service::Guarded;
 use sqlx::AnyPool;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
-use tracing::{info, warn};
+use tracing::{debug, error, info, warn};
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::fmt::time::OffsetTime;
 
+mod output;
+mod service;
+mod tui;
+
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const GIT_HASH: &str = env!("GTM_GIT_HASH");
 
@@ -65,6 +71,17 @@ struct Cli {
     #[arg(long, global = true)]
     db_url: Option<String>,
 
+    /// How listing commands print: table, json or csv (`export` writes csv
+    /// only)
+    #[arg(long, global = true, value_enum)]
+    format: Option<output::Format>,
+
+    /// The admin that allocate, revoke, requests, import-prices and tui act
+    /// as, recorded in the audit log and allocation batches (default: the
+    /// only entry in `admin_emails`)
+    #[arg(long = "as", global = true, value_name = "EMAIL")]
+    as_admin: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -77,19 +94,59 @@ enum Commands {
         #[arg(short, long)]
         port: Option<u16>,
     },
+    /// Run background jobs only (schedule scrape, alerts, notification
+    /// delivery), without the HTTP server
+    Worker,
     /// Display a hello world message
     Hello,
-    /// Scrape the Giants schedule from the MLB Stats API
+    /// Scrape the team's schedule from the MLB Stats API
     ScrapeSchedule {
-        /// Season year to fetch (default: current year)
-        #[arg(short, long, default_value_t = chrono::Local::now().year() as u32)]
-        season: u32,
+        /// Season year to fetch (default: the current year in `display_timezone`)
+        #[arg(short, long)]
+        season: Option<u32>,
+        /// Game types to fetch: R, S, P, F, D, L, W; repeatable or
+        /// comma-separated (default: the configured ticketed types)
+        #[arg(long = "game-type", value_delimiter = ',')]
+        game_types: Vec<GameType>,
+        /// Optional sections to fetch: promotions, tickets, venue, weather,
+        /// probablePitcher, broadcasts; comma-separated (default:
+        /// `scrape_hydrate`)
+        #[arg(long, value_delimiter = ',')]
+        hydrate: Vec<Hydration>,
+        /// Sync even if the scrape returns far less than is stored
+        #[arg(long)]
+        force: bool,
     },
     /// List upcoming games
     ListGames {
         /// Filter by month (1-12)
         #[arg(long)]
         month: Option<u32>,
+        /// Only games of these types: R, S, P, F, D, L, W; repeatable or
+        /// comma-separated
+        #[arg(long = "game-type", value_delimiter = ',')]
+        game_types: Vec<GameType>,
+        /// Apply a member's saved view by name instead
+        #[arg(long, conflicts_with = "month")]
+        view: Option<String>,
+        /// Email of the member whose view to use, when several share the name
+        #[arg(long, requires = "view")]
+        user: Option<String>,
+    },
+    /// Search promotions (giveaways, theme nights) across the schedule
+    ListPromotions {
+        /// Kind of promotion, matched against type and name (e.g. "bobblehead")
+        #[arg(long = "type")]
+        offer_type: Option<String>,
+        /// Text in the promotion name
+        #[arg(long)]
+        name: Option<String>,
+        /// Who gets it (e.g. "first 20,000")
+        #[arg(long)]
+        distribution: Option<String>,
+        /// Filter by month (1-12)
+        #[arg(long)]
+        month: Option<u32>,
     },
     /// Add a season ticket seat
     AddSeat {
@@ -106,20 +163,186 @@ enum Commands {
         #[arg(long)]
         notes: Option<String>,
     },
+    /// Add a member who logs in as a local user (for `auth_mode = "none"`)
+    AddUser {
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        name: String,
+    },
+    /// Add many seats from a CSV file (`section,row,seat[,notes][,face_value]`)
+    /// or, for a `.json` file, an array of seats
+    ImportSeats {
+        #[arg(long)]
+        file: std::path::PathBuf,
+    },
+    /// Set per-game ticket prices from the account manager's CSV export
+    /// (`date,opponent,price` plus optional `game_number`, `section`, `row`,
+    /// `seat`), audited as the `--as` admin
+    ImportPrices {
+        #[arg(long)]
+        file: std::path::PathBuf,
+        /// Show what each line matches without writing prices
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// List all season ticket seats
     ListSeats,
+    /// Remove every seat in a section/row (assigned tickets are kept)
+    DeleteSeatGroup {
+        #[arg(long)]
+        section: String,
+        #[arg(long)]
+        row: String,
+        /// Confirm deleting more than `bulk_confirm_threshold` seats
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Create any missing game tickets for every seat in a section/row
+    RegenerateSeatGroupTickets {
+        #[arg(long)]
+        section: String,
+        #[arg(long)]
+        row: String,
+    },
     /// List ticket inventory for all home games
     ListTickets,
+    /// Automatically allocate a game's available seats across pending
+    /// requests, apply a plan file, or give one member seats by hand
+    Allocate {
+        /// Game to allocate
+        #[arg(long, required_unless_present = "plan")]
+        game: Option<GamePk>,
+        /// Member (by id) to give `--seats` seats to, instead of running the
+        /// engine; fills their pending request for the game, if any
+        #[arg(long, requires = "seats", conflicts_with_all = ["strategy", "seed", "plan"])]
+        user: Option<UserId>,
+        /// How many seats to give `--user`, side by side where possible
+        #[arg(long, requires = "user")]
+        seats: Option<usize>,
+        /// Strategy: round-robin, weighted-lottery, or seniority (default from config)
+        #[arg(long)]
+        strategy: Option<String>,
+        /// RNG seed, to replay a previous lottery exactly
+        #[arg(long)]
+        seed: Option<u64>,
+        /// CSV plan (game_pk,user_email,seats) to apply instead of the engine
+        #[arg(long, conflicts_with_all = ["game", "strategy", "seed"])]
+        plan: Option<std::path::PathBuf>,
+        /// Show the proposed allocation without writing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Assign even if a member's seats would span sections
+        #[arg(long)]
+        allow_split: bool,
+    },
+    /// Take a ticket back from the member holding it
+    Revoke {
+        #[arg(long)]
+        ticket: TicketId,
+    },
+    /// List, approve or deny members' ticket requests
+    Requests {
+        #[command(subcommand)]
+        command: RequestsCommand,
+    },
+    /// Terminal dashboard of upcoming home games, requests and promotions,
+    /// for assigning and releasing tickets
+    Tui,
+    /// Export a season for spreadsheets, to stdout
+    Export {
+        /// tickets: one line per ticket; allocation: one line per game, a column per seat
+        #[arg(value_enum, default_value_t = ExportKind::Tickets)]
+        what: ExportKind,
+        /// Season (default: current year)
+        #[arg(long)]
+        season: Option<String>,
+    },
+    /// Database maintenance: migrations, status, reset and backup
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+    /// Write, show or check the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Write a commented config file, every setting at its default, to
+    /// ~/.gtm/config.toml
+    Init {
+        /// Replace an existing file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print each effective setting and where it came from (default, file,
+    /// env or cli)
+    Show,
+    /// Check the config file and settings, database connectivity, Auth0
+    /// and whether the port is free
+    Validate,
+}
+
+#[derive(Subcommand)]
+enum RequestsCommand {
+    /// List requests, pending ones by default
+    List {
+        /// Only requests for this game
+        #[arg(long)]
+        game: Option<GamePk>,
+        /// pending, approved, declined or withdrawn; `all` for every status
+        #[arg(long, default_value = "pending")]
+        status: String,
+    },
+    /// Approve a pending request by assigning it the seats it asks for,
+    /// side by side where possible
+    Approve {
+        id: i64,
+        /// Assign even if the member's seats would span sections
+        #[arg(long)]
+        allow_split: bool,
+    },
+    /// Decline a pending request
+    Deny { id: i64 },
+}
+
+#[derive(Subcommand)]
+enum DbCommand {
+    /// Apply pending migrations
+    Migrate,
+    /// Show applied and pending migrations and each table's row count
+    Status,
+    /// Drop every table and migrate from scratch
+    Reset {
+        /// Confirm that every row will be deleted
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Copy the database to PATH (the SQLite file, or a pg_dump script)
+    Backup { path: std::path::PathBuf },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ExportKind {
+    Tickets,
+    Allocation,
 }
 
 // --- Logging ---
 
+/// Logs go to stderr, leaving stdout to command output (`--format json`,
+/// `export`) that may be piped elsewhere.
 fn init_logging(config: &gtm_config::Config) {
     let filter = EnvFilter::new(&config.log_level);
 
     if config.log_json {
         tracing_subscriber::fmt()
             .with_env_filter(filter)
+            .with_writer(std::io::stderr)
             .json()
             .with_target(true)
             .with_timer(OffsetTime::new(
@@ -132,6 +355,7 @@ fn init_logging(config: &gtm_config::Config) {
     } else if config.utc {
         tracing_subscriber::fmt()
             .with_env_filter(filter)
+            .with_writer(std::io::stderr)
             .with_timer(OffsetTime::new(
                 time::UtcOffset::UTC,
                 time::macros::format_description!(
@@ -142,6 +366,7 @@ fn init_logging(config: &gtm_config::Config) {
     } else {
         tracing_subscriber::fmt()
             .with_env_filter(filter)
+            .with_writer(std::io::stderr)
             .with_timer(LocalTimer)
             .init();
     }
@@ -161,8 +386,21 @@ impl tracing_subscriber::fmt::time::FormatTime for LocalTimer {
 #[derive(Clone)]
 struct AppState {
     pool: AnyPool,
+    /// List and report queries; the replica when one is configured and up.
+    read_pool: gtm_db::replica::ReadPool,
     auth: Arc<AuthConfig>,
     spa_html: Arc<String>,
+    config: Arc<gtm_config::Config>,
+    notifier: Arc<gtm_notify::Notifier>,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    payments: Arc<gtm_payments::Payments>,
+    /// Past report snapshots for `as_of` queries.
+    history: Arc<gtm_db::history::SnapshotCache>,
+    /// Request counts for the usage report, written out every
+    /// [`USAGE_FLUSH_INTERVAL`].
+    usage: Arc<gtm_db::usage::UsageCounter>,
+    /// Promotion images served through `/api/promotions/{offer_id}/image`.
+    images: Arc<gtm_scraper::images::ImageCache>,
 }
 
 impl axum::extract::FromRef<AppState> for AnyPool {
@@ -171,10 +409,160 @@ impl axum::extract::FromRef<AppState> for AnyPool {
     }
 }
 
+impl axum::extract::FromRef<AppState> for gtm_db::replica::ReadPool {
+    fn from_ref(state: &AppState) -> gtm_db::replica::ReadPool {
+        state.read_pool.clone()
+    }
+}
+
+/// How often the read replica is pinged to decide where reads go.
+const REPLICA_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How often the in-memory request counts are written to `api_usage`.
+const USAGE_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How often the signing keys are re-fetched in the background.
+const JWKS_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Least time between fetches triggered by tokens signed with a key we
+/// don't have, so a flood of bad tokens can't hammer Auth0.
+const JWKS_MIN_REFETCH: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Longest a JWKS fetch may take; requests with an unknown kid wait for it.
+const JWKS_FETCH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Name of Auth0's circuit breaker.
+const JWKS_BREAKER: &str = "auth0_jwks";
+
+/// Header naming the user (by id) a request acts as with `auth_mode = "none"`.
+const LOCAL_USER_HEADER: &str = "x-gtm-user";
+
 struct AuthConfig {
-    jwks_keys: Vec<JwkKey>,
+    domain: String,
+    jwks: tokio::sync::RwLock<JwksCache>,
+    /// Held for a whole JWKS fetch, so concurrent misses make one fetch.
+    /// `jwks` itself is only locked to swap the keys in.
+    refreshing: tokio::sync::Mutex<()>,
     audience: String,
     issuer: String,
+    breaker: Arc<gtm_scraper::breaker::CircuitBreaker>,
+    /// Set with `auth_mode = "none"`: the emails of local users who are
+    /// admins. Requests then name their user instead of logging in.
+    local_admins: Option<Vec<String>>,
+}
+
+/// The signing keys we know, and when we last tried to fetch them.
+struct JwksCache {
+    keys: Vec<JwkKey>,
+    fetched_at: std::time::Instant,
+}
+
+impl AuthConfig {
+    /// Starts without keys if Auth0 is unreachable, so the server still
+    /// comes up; logins fail until a later fetch succeeds.
+    async fn new(domain: &str, audience: &str) -> Self {
+        let auth = Self {
+            domain: domain.to_string(),
+            jwks: tokio::sync::RwLock::new(JwksCache {
+                keys: Vec::new(),
+                fetched_at: std::time::Instant::now(),
+            }),
+            refreshing: tokio::sync::Mutex::new(()),
+            audience: audience.to_string(),
+            issuer: format!("https://{domain}/"),
+            breaker: gtm_scraper::breaker::shared(JWKS_BREAKER),
+            local_admins: None,
+        };
+        auth.refresh().await;
+        if !auth.has_keys().await {
+            warn!("Starting without JWKS keys; logins will fail until {domain} answers");
+        }
+        auth
+    }
+
+    /// Without an identity provider: never fetches keys, and every request
+    /// acts as the local user named in [`LOCAL_USER_HEADER`].
+    fn local(admin_emails: &[String]) -> Self {
+        Self {
+            domain: String::new(),
+            jwks: tokio::sync::RwLock::new(JwksCache {
+                keys: Vec::new(),
+                fetched_at: std::time::Instant::now(),
+            }),
+            refreshing: tokio::sync::Mutex::new(()),
+            audience: String::new(),
+            issuer: String::new(),
+            breaker: gtm_scraper::breaker::shared(JWKS_BREAKER),
+            local_admins: Some(admin_emails.iter().map(|e| e.to_lowercase()).collect()),
+        }
+    }
+
+    async fn has_keys(&self) -> bool {
+        !self.jwks.read().await.keys.is_empty()
+    }
+
+    /// The decoding key for `kid`. An unknown kid usually means Auth0 has
+    /// rotated its keys, so the set is re-fetched, at most once per
+    /// [`JWKS_MIN_REFETCH`].
+    async fn key_for(&self, kid: &str) -> Option<DecodingKey> {
+        if let Some(key) = self.jwks.read().await.find(kid) {
+            return Some(key);
+        }
+        let _refreshing = self.refreshing.lock().await;
+        // Another request may have refreshed while we waited for the lock.
+        {
+            let cache = self.jwks.read().await;
+            if let Some(key) = cache.find(kid) {
+                return Some(key);
+            }
+            if cache.fetched_at.elapsed() < JWKS_MIN_REFETCH {
+                return None;
+            }
+        }
+        info!(kid, "Unknown JWK kid; refreshing keys");
+        self.fetch_keys().await;
+        self.jwks.read().await.find(kid)
+    }
+
+    /// Re-fetch the signing keys, keeping the old ones if that fails or
+    /// Auth0's breaker is open.
+    async fn refresh(&self) {
+        let _refreshing = self.refreshing.lock().await;
+        self.fetch_keys().await;
+    }
+
+    /// Fetch the keys, then lock the cache only to swap them in, so requests
+    /// with keys we have never wait on Auth0. Callers hold `refreshing`.
+    async fn fetch_keys(&self) {
+        self.jwks.write().await.fetched_at = std::time::Instant::now();
+        if let Err(open) = self.breaker.check() {
+            warn!("Not refreshing JWKS: {open}");
+            return;
+        }
+        match fetch_jwks(&self.domain).await {
+            Ok(keys) if !keys.is_empty() => {
+                self.breaker.record_success();
+                self.jwks.write().await.keys = keys;
+            }
+            Ok(_) => {
+                self.breaker.record_success();
+                warn!("JWKS response had no usable keys; keeping the current ones");
+            }
+            Err(e) => {
+                self.breaker.record_failure();
+                warn!("Failed to refresh JWKS: {e}");
+            }
+        }
+    }
+}
+
+impl JwksCache {
+    fn find(&self, kid: &str) -> Option<DecodingKey> {
+        self.keys
+            .iter()
+            .find(|k| k.kid == kid)
+            .map(|k| k.decoding_key.clone())
+    }
 }
 
 #[derive(Clone)]
@@ -197,7 +585,14 @@ struct Claims {
 /// Fetch JWKS from Auth0 and extract RSA decoding keys
 async fn fetch_jwks(domain: &str) -> anyhow::Result<Vec<JwkKey>> {
     let url = format!("https://{domain}/.well-known/jwks.json");
-    let resp: serde_json::Value = reqwest::get(&url).await?.json().await?;
+    let resp: serde_json::Value = reqwest::Client::new()
+        .get(&url)
+        .timeout(JWKS_FETCH_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
     let keys = resp["keys"]
         .as_array()
         .ok_or_else(|| anyhow::anyhow!("No keys in JWKS response"))?;
@@ -219,921 +614,3897 @@ async fn fetch_jwks(domain: &str) -> anyhow::Result<Vec<JwkKey>> {
     Ok(result)
 }
 
-/// Axum extractor that validates a JWT Bearer token and returns the claims.
-/// Returns 401 if the token is missing or invalid.
+/// Axum extractor that validates a JWT Bearer token and returns the claims,
+/// or accepts a member's API token (`Authorization: Token <value>`) in its
+/// place. With `auth_mode = "none"` the local user named in
+/// [`LOCAL_USER_HEADER`] stands in for the Bearer token. Returns 401 if the
+/// token is missing or invalid. The claims are
+/// cached in the request extensions, so extracting again (in a route layer
+/// and then the handler) validates only once.
+#[derive(Clone)]
 struct AuthUser {
     sub: String,
     email: Option<String>,
     name: Option<String>,
     roles: Vec<String>,
+    /// Signed in with an API token, whose roles come from the database
+    /// rather than proving them afresh.
+    via_token: bool,
 }
 
 impl<S> FromRequestParts<S> for AuthUser
 where
     S: Send + Sync,
     Arc<AuthConfig>: axum::extract::FromRef<S>,
+    AnyPool: FromRef<S>,
 {
-    type Rejection = (StatusCode, String);
+    type Rejection = ApiError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(auth_user) = parts.extensions.get::<AuthUser>() {
+            return Ok(auth_user.clone());
+        }
         let auth_config = Arc::<AuthConfig>::from_ref(state);
 
         let auth_header = parts
             .headers
             .get("authorization")
-            .and_then(|v| v.to_str().ok())
-            .ok_or((
-                StatusCode::UNAUTHORIZED,
-                "Missing Authorization header".to_string(),
+            .and_then(|v| v.to_str().ok());
+        if let Some(admins) = &auth_config.local_admins
+            && !auth_header.is_some_and(|h| h.starts_with("Token "))
+        {
+            let auth_user = local_auth_user(parts, &AnyPool::from_ref(state), admins).await?;
+            parts.extensions.insert(auth_user.clone());
+            return Ok(auth_user);
+        }
+        let auth_header =
+            auth_header.ok_or(ApiError::unauthorized("Missing Authorization header"))?;
+
+        if let Some(token) = auth_header.strip_prefix("Token ") {
+            let (user, scope) = gtm_db::api_tokens::authenticate(
+                &AnyPool::from_ref(state),
+                token.trim(),
+                Utc::now(),
+            )
+            .await?
+            .ok_or(ApiError::unauthorized(
+                "Invalid, expired or revoked API token",
             ))?;
+            // Without Auth0, the admin role is admin_emails as configured now
+            let admin = scope == gtm_db::api_tokens::ADMIN_SCOPE
+                && auth_config
+                    .local_admins
+                    .as_ref()
+                    .is_none_or(|admins| admins.contains(&user.email.to_lowercase()));
+            let roles = if admin {
+                vec!["admin".to_string()]
+            } else {
+                Vec::new()
+            };
+            let auth_user = AuthUser {
+                sub: user.auth0_sub,
+                email: Some(user.email),
+                name: Some(user.name),
+                roles,
+                via_token: true,
+            };
+            parts.extensions.insert(auth_user.clone());
+            return Ok(auth_user);
+        }
 
-        let token = auth_header.strip_prefix("Bearer ").ok_or((
-            StatusCode::UNAUTHORIZED,
-            "Invalid Authorization header format".to_string(),
-        ))?;
+        let token = auth_header
+            .strip_prefix("Bearer ")
+            .ok_or(ApiError::unauthorized(
+                "Invalid Authorization header format",
+            ))?;
 
         // Decode header to get kid
-        let header = decode_header(token).map_err(|e| {
-            (
-                StatusCode::UNAUTHORIZED,
-                format!("Invalid token header: {e}"),
-            )
-        })?;
+        let header = decode_header(token)
+            .map_err(|e| ApiError::unauthorized(format!("Invalid token header: {e}")))?;
 
         let kid = header
             .kid
-            .ok_or((StatusCode::UNAUTHORIZED, "Token missing kid".to_string()))?;
+            .ok_or(ApiError::unauthorized("Token missing kid"))?;
 
         // Find matching key
-        let jwk_key = auth_config.jwks_keys.iter().find(|k| k.kid == kid).ok_or((
-            StatusCode::UNAUTHORIZED,
-            "No matching JWK for kid".to_string(),
-        ))?;
+        let decoding_key = auth_config
+            .key_for(&kid)
+            .await
+            .ok_or(ApiError::unauthorized("No matching JWK for kid"))?;
 
         // Validate token
         let mut validation = Validation::new(Algorithm::RS256);
         validation.set_audience(&[&auth_config.audience]);
         validation.set_issuer(&[&auth_config.issuer]);
 
-        let token_data =
-            decode::<Claims>(token, &jwk_key.decoding_key, &validation).map_err(|e| {
-                (
-                    StatusCode::UNAUTHORIZED,
-                    format!("Token validation failed: {e}"),
-                )
-            })?;
+        let token_data = decode::<Claims>(token, &decoding_key, &validation)
+            .map_err(|e| ApiError::unauthorized(format!("Token validation failed: {e}")))?;
 
-        Ok(AuthUser {
+        let auth_user = AuthUser {
             sub: token_data.claims.sub,
             email: token_data.claims.email,
             name: token_data.claims.name,
             roles: token_data.claims.roles,
-        })
+            via_token: false,
+        };
+        parts.extensions.insert(auth_user.clone());
+        Ok(auth_user)
     }
 }
 
-impl axum::extract::FromRef<AppState> for Arc<AuthConfig> {
-    fn from_ref(state: &AppState) -> Arc<AuthConfig> {
-        state.auth.clone()
+/// The local user named in [`LOCAL_USER_HEADER`], an admin if their email
+/// is in `admins`.
+async fn local_auth_user(
+    parts: &Parts,
+    pool: &AnyPool,
+    admins: &[String],
+) -> Result<AuthUser, ApiError> {
+    let user_id: UserId = parts
+        .headers
+        .get(LOCAL_USER_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .ok_or(ApiError::unauthorized(
+            "Pick a user: send their id in the X-GTM-User header",
+        ))?;
+    let user = gtm_db::get_user(pool, user_id)
+        .await?
+        .ok_or(ApiError::unauthorized("No such local user"))?;
+    let roles = if admins.contains(&user.email.to_lowercase()) {
+        vec!["admin".to_string()]
+    } else {
+        Vec::new()
+    };
+    Ok(AuthUser {
+        sub: user.auth0_sub,
+        email: Some(user.email),
+        name: Some(user.name),
+        roles,
+        via_token: false,
+    })
+}
+
+impl AuthUser {
+    fn is_admin(&self) -> bool {
+        self.roles.iter().any(|r| r == "admin")
     }
 }
 
-// --- Server ---
+/// Extractor for a logged-in member: validates the JWT and resolves the local
+/// user (creating it on first login). 401 without a valid token.
+#[derive(Clone)]
+struct RequireMember(gtm_models::User);
 
-async fn health() -> Json<serde_json::Value> {
-    Json(json!({
-        "status": "ok",
-        "message": "Hello, Giants!",
-        "version": version_string()
-    }))
-}
+impl<S> FromRequestParts<S> for RequireMember
+where
+    S: Send + Sync,
+    Arc<AuthConfig>: FromRef<S>,
+    AnyPool: FromRef<S>,
+{
+    type Rejection = ApiError;
 
-#[derive(Deserialize)]
-struct GamesQuery {
-    month: Option<u32>,
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(member) = parts.extensions.get::<RequireMember>() {
+            return Ok(member.clone());
+        }
+        let auth_user = AuthUser::from_request_parts(parts, state).await?;
+        let user = resolve_user(&auth_user, &AnyPool::from_ref(state)).await?;
+        let member = RequireMember(user);
+        parts.extensions.insert(member.clone());
+        Ok(member)
+    }
 }
 
-async fn api_list_games(
-    State(pool): State<AnyPool>,
-    Query(params): Query<GamesQuery>,
-) -> Result<Json<Vec<gtm_models::Game>>, (axum::http::StatusCode, String)> {
-    gtm_db::list_games(&pool, params.month)
-        .await
-        .map(Json)
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
-}
+/// Extractor for a logged-in member with the admin role. 401 without a valid
+/// token, 403 without the role.
+#[derive(Clone)]
+struct RequireAdmin(gtm_models::User);
 
-async fn api_get_game(
-    State(pool): State<AnyPool>,
-    Path(game_pk): Path<i64>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
-    match gtm_db::get_game(&pool, game_pk).await {
-        Ok(Some(game)) => Ok(Json(serde_json::to_value(game).unwrap())),
-        Ok(None) => Err((
-            axum::http::StatusCode::NOT_FOUND,
-            "Game not found".to_string(),
-        )),
-        Err(e) => Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+impl<S> FromRequestParts<S> for RequireAdmin
+where
+    S: Send + Sync,
+    Arc<AuthConfig>: FromRef<S>,
+    AnyPool: FromRef<S>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth_user = AuthUser::from_request_parts(parts, state).await?;
+        if !auth_user.is_admin() {
+            warn!(sub = %auth_user.sub, roles = ?auth_user.roles, "Admin access denied");
+            return Err(ApiError::forbidden("Admin access required"));
+        }
+        let RequireMember(user) = RequireMember::from_request_parts(parts, state).await?;
+        Ok(RequireAdmin(user))
     }
 }
 
-async fn api_get_game_promotions(
-    State(pool): State<AnyPool>,
-    Path(game_pk): Path<i64>,
-) -> Result<Json<Vec<gtm_models::Promotion>>, (axum::http::StatusCode, String)> {
-    gtm_db::get_promotions_for_game(&pool, game_pk)
-        .await
-        .map(Json)
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+/// Route layer for the member sub-router: rejects the request before it
+/// reaches a handler unless the caller is a logged-in member.
+async fn member_gate(
+    RequireMember(member): RequireMember,
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let mut response = next.run(req).await;
+    response.extensions_mut().insert(UsageMember(member.id));
+    response
 }
 
-#[derive(Deserialize)]
-struct AddSeatRequest {
-    section: String,
-    row: String,
-    seat: String,
-    notes: Option<String>,
+/// Route layer for the admin sub-router.
+async fn admin_gate(
+    RequireAdmin(admin): RequireAdmin,
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let mut response = next.run(req).await;
+    response.extensions_mut().insert(UsageMember(admin.id));
+    response
 }
 
-async fn api_add_seat(
-    State(pool): State<AnyPool>,
-    Json(body): Json<AddSeatRequest>,
-) -> Result<Json<gtm_models::Seat>, (axum::http::StatusCode, String)> {
-    let seat = gtm_db::add_seat(
-        &pool,
-        &body.section,
-        &body.row,
-        &body.seat,
-        body.notes.as_deref(),
-    )
-    .await
-    .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?;
-    let count = gtm_db::generate_tickets_for_seat(&pool, seat.id)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    info!("Seat {} added, {} game tickets generated", seat.id, count);
-    Ok(Json(seat))
+/// The member a response was for, left by the member and admin gates so
+/// [`count_usage`] can attribute the request.
+#[derive(Clone, Copy)]
+struct UsageMember(UserId);
+
+/// Count every matched API request towards today's usage: the member's when
+/// a gate identified one, the public count otherwise. Only bumps an
+/// in-memory counter; a background task writes the totals out.
+async fn count_usage(
+    State(usage): State<Arc<gtm_db::usage::UsageCounter>>,
+    State(config): State<Arc<gtm_config::Config>>,
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    // Unmatched paths aren't endpoints, and would fill the table with
+    // whatever scanners try
+    let endpoint = req
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|path| format!("{} {}", req.method(), path.as_str()));
+    let response = next.run(req).await;
+    // The zone was checked at startup
+    if let Some(endpoint) = endpoint
+        && let Ok(tz) = display_tz(&config)
+    {
+        let member = response.extensions().get::<UsageMember>().map(|m| m.0);
+        let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+        usage.bump(&today, member, &endpoint);
+    }
+    response
 }
 
-async fn api_list_seats(
-    State(pool): State<AnyPool>,
-) -> Result<Json<Vec<gtm_models::Seat>>, (axum::http::StatusCode, String)> {
-    gtm_db::list_seats(&pool)
-        .await
-        .map(Json)
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+impl axum::extract::FromRef<AppState> for Arc<gtm_db::usage::UsageCounter> {
+    fn from_ref(state: &AppState) -> Arc<gtm_db::usage::UsageCounter> {
+        state.usage.clone()
+    }
 }
 
-#[derive(Deserialize)]
-struct AddSeatBatchRequest {
-    section: String,
-    row: String,
-    seat_start: u32,
-    seat_end: u32,
-    notes: Option<String>,
+impl axum::extract::FromRef<AppState> for Arc<gtm_notify::Notifier> {
+    fn from_ref(state: &AppState) -> Arc<gtm_notify::Notifier> {
+        state.notifier.clone()
+    }
 }
 
-async fn api_add_seat_batch(
-    State(pool): State<AnyPool>,
-    Json(body): Json<AddSeatBatchRequest>,
-) -> Result<Json<Vec<gtm_models::Seat>>, (axum::http::StatusCode, String)> {
-    if body.seat_start > body.seat_end {
-        return Err((
-            axum::http::StatusCode::BAD_REQUEST,
-            "seat_start must be <= seat_end".to_string(),
-        ));
-    }
-    if body.seat_end - body.seat_start >= 50 {
-        return Err((
-            axum::http::StatusCode::BAD_REQUEST,
-            "Maximum 50 seats per batch".to_string(),
-        ));
-    }
-    let mut seats = Vec::new();
-    for n in body.seat_start..=body.seat_end {
-        let seat = gtm_db::add_seat(
-            &pool,
-            &body.section,
-            &body.row,
-            &n.to_string(),
-            body.notes.as_deref(),
-        )
-        .await
-        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e.to_string()))?;
-        gtm_db::generate_tickets_for_seat(&pool, seat.id)
-            .await
-            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        seats.push(seat);
+impl axum::extract::FromRef<AppState> for Arc<gtm_gcal::GoogleCalendar> {
+    fn from_ref(state: &AppState) -> Arc<gtm_gcal::GoogleCalendar> {
+        state.gcal.clone()
     }
-    info!(
-        "{} seats batch-added (Section {} Row {} Seats {}-{})",
-        seats.len(),
-        body.section,
-        body.row,
-        body.seat_start,
-        body.seat_end
-    );
-    Ok(Json(seats))
 }
 
-#[derive(Deserialize)]
-struct UpdateSeatGroupRequest {
-    section: String,
-    row: String,
-    notes: Option<String>,
+impl axum::extract::FromRef<AppState> for Arc<gtm_payments::Payments> {
+    fn from_ref(state: &AppState) -> Arc<gtm_payments::Payments> {
+        state.payments.clone()
+    }
 }
 
-async fn api_update_seat_group(
-    State(pool): State<AnyPool>,
-    Json(body): Json<UpdateSeatGroupRequest>,
-) -> Result<Json<Vec<gtm_models::Seat>>, (axum::http::StatusCode, String)> {
-    let updated =
-        gtm_db::update_seat_group_notes(&pool, &body.section, &body.row, body.notes.as_deref())
-            .await
-            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    if updated == 0 {
-        return Err((
-            axum::http::StatusCode::NOT_FOUND,
-            "No seats found for that section/row".to_string(),
-        ));
+impl axum::extract::FromRef<AppState> for Arc<gtm_db::history::SnapshotCache> {
+    fn from_ref(state: &AppState) -> Arc<gtm_db::history::SnapshotCache> {
+        state.history.clone()
     }
-    info!(
-        "Updated notes for {} seats in Section {} Row {}",
-        updated, body.section, body.row
-    );
-    let seats = gtm_db::list_seats(&pool)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    Ok(Json(seats))
 }
 
-async fn api_delete_seat(
-    State(pool): State<AnyPool>,
-    Path(seat_id): Path<i64>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
-    let deleted = gtm_db::delete_seat(&pool, seat_id)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    if deleted {
-        Ok(Json(json!({ "status": "ok" })))
-    } else {
-        Err((
-            axum::http::StatusCode::NOT_FOUND,
-            "Seat not found".to_string(),
-        ))
+impl axum::extract::FromRef<AppState> for Arc<gtm_scraper::images::ImageCache> {
+    fn from_ref(state: &AppState) -> Arc<gtm_scraper::images::ImageCache> {
+        state.images.clone()
     }
 }
 
-async fn api_get_game_tickets(
-    State(pool): State<AnyPool>,
-    Path(game_pk): Path<i64>,
-) -> Result<Json<Vec<gtm_models::GameTicketDetail>>, (axum::http::StatusCode, String)> {
-    gtm_db::list_tickets_for_game(&pool, game_pk)
-        .await
-        .map(Json)
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+impl axum::extract::FromRef<AppState> for Arc<gtm_config::Config> {
+    fn from_ref(state: &AppState) -> Arc<gtm_config::Config> {
+        state.config.clone()
+    }
 }
 
-#[derive(Deserialize)]
-struct UpdateTicketRequest {
-    status: String,
-    notes: Option<String>,
+impl axum::extract::FromRef<AppState> for Arc<AuthConfig> {
+    fn from_ref(state: &AppState) -> Arc<AuthConfig> {
+        state.auth.clone()
+    }
 }
 
-async fn api_update_ticket(
-    State(pool): State<AnyPool>,
-    Path(ticket_id): Path<i64>,
-    Json(body): Json<UpdateTicketRequest>,
-) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, String)> {
-    let updated =
-        gtm_db::update_ticket_status(&pool, ticket_id, &body.status, body.notes.as_deref())
-            .await
-            .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    if updated {
-        Ok(Json(json!({ "status": "ok" })))
-    } else {
-        Err((
-            axum::http::StatusCode::NOT_FOUND,
-            "Ticket not found".to_string(),
-        ))
-    }
-}
+// --- Server ---
 
-async fn api_ticket_summary(
-    State(pool): State<AnyPool>,
-) -> Result<Json<Vec<serde_json::Value>>, (axum::http::StatusCode, String)> {
-    let summary = gtm_db::ticket_summary_for_games(&pool)
-        .await
-        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let result: Vec<serde_json::Value> = summary
-        .into_iter()
-        .map(|(game_pk, total, available)| {
-            json!({ "game_pk": game_pk, "total": total, "available": available })
-        })
-        .collect();
-    Ok(Json(result))
+/// `status` is `degraded` while an upstream's circuit breaker is open.
+async fn health() -> Json<serde_json::Value> {
+    let upstreams = gtm_scraper::breaker::statuses();
+    let degraded = upstreams
+        .iter()
+        .any(|b| b.state != gtm_scraper::breaker::State::Closed);
+    Json(json!({
+        "status": if degraded { "degraded" } else { "ok" },
+        "message": "Hello, Giants!",
+        "version": version_string(),
+        "slow_queries": gtm_db::timing::slow_queries(),
+        "upstreams": upstreams,
+    }))
 }
 
-// --- User API endpoints ---
+/// Settings the SPA needs before anyone signs in: the club's team, so the
+/// schedule can tell home games from away ones.
+async fn api_config(State(config): State<Arc<gtm_config::Config>>) -> Json<serde_json::Value> {
+    Json(json!({
+        "team_id": config.team_id,
+        "team_name": config.team_name,
+    }))
+}
 
-#[derive(Serialize)]
-struct MeResponse {
-    id: i64,
-    auth0_sub: String,
-    email: String,
-    name: String,
-    role: String,
+/// Liveness: the process is up and answering. Checks nothing else, so a
+/// database outage doesn't get the server restarted.
+async fn health_live() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok", "version": version_string() }))
 }
 
-async fn api_get_me(
-    auth_user: AuthUser,
+/// How long the readiness check waits for the database.
+const READY_DB_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Readiness: whether this server can serve traffic. 503 `not_ready` if the
+/// database doesn't answer. With Auth0 login and no JWKS signing keys
+/// loaded it's `degraded` but still ready, so new servers come up during an
+/// Auth0 outage and serve the public pages, as does a scheduled scrape that
+/// hasn't succeeded within `health_max_scrape_age_hours`. The endpoint is
+/// public, so failures are logged rather than described.
+async fn health_ready(
     State(pool): State<AnyPool>,
-) -> Result<Json<MeResponse>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    let role = if auth_user.roles.contains(&"admin".to_string()) {
-        "admin"
+    State(auth): State<Arc<AuthConfig>>,
+    State(config): State<Arc<gtm_config::Config>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let database = match tokio::time::timeout(READY_DB_TIMEOUT, gtm_db::ping(&pool)).await {
+        Ok(Ok(())) => json!({ "status": "ok" }),
+        Ok(Err(e)) => {
+            warn!("Readiness: database check failed: {e:#}");
+            json!({ "status": "failed" })
+        }
+        Err(_) => {
+            warn!("Readiness: database check timed out");
+            json!({ "status": "failed" })
+        }
+    };
+
+    let jwks = if auth.local_admins.is_some() {
+        json!({ "status": "skipped" })
     } else {
-        "member"
+        let keys = auth.jwks.read().await.keys.len();
+        json!({ "status": if keys > 0 { "ok" } else { "degraded" }, "keys": keys })
     };
-    info!(sub = %auth_user.sub, jwt_roles = ?auth_user.roles, resolved_role = %role, "GET /api/users/me");
-    Ok(Json(MeResponse {
-        id: user.id,
-        auth0_sub: user.auth0_sub,
-        email: user.email,
-        name: user.name,
-        role: role.to_string(),
-    }))
-}
 
-async fn api_list_users(
-    _auth_user: AuthUser,
-    State(pool): State<AnyPool>,
-) -> Result<Json<Vec<gtm_models::User>>, (StatusCode, String)> {
-    gtm_db::list_users(&pool)
-        .await
-        .map(Json)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
-}
+    let schedule = if config.health_max_scrape_age_hours == 0 {
+        json!({ "status": "skipped" })
+    } else {
+        match gtm_db::jobs::get(&pool, gtm_db::jobs::SCHEDULE_SCRAPE).await {
+            Ok(run) => {
+                let last_ok_at = run.and_then(|r| r.last_ok_at);
+                let age_hours = last_ok_at
+                    .as_deref()
+                    .and_then(gtm_db::parse_timestamp)
+                    .map(|t| (Utc::now() - t).num_hours());
+                let status = match age_hours {
+                    None => "unknown",
+                    Some(h) if h > config.health_max_scrape_age_hours as i64 => "stale",
+                    Some(_) => "ok",
+                };
+                json!({ "status": status, "last_ok_at": last_ok_at, "age_hours": age_hours })
+            }
+            Err(e) => {
+                warn!("Readiness: schedule check failed: {e:#}");
+                json!({ "status": "unknown" })
+            }
+        }
+    };
 
-#[derive(Deserialize)]
-struct ScrapeScheduleRequest {
-    season: Option<u32>,
+    let (code, status) = if database["status"] == "failed" {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    } else if jwks["status"] == "degraded" || schedule["status"] == "stale" {
+        (StatusCode::OK, "degraded")
+    } else {
+        (StatusCode::OK, "ready")
+    };
+    (
+        code,
+        Json(json!({
+            "status": status,
+            "version": version_string(),
+            "checks": { "database": database, "jwks": jwks, "schedule": schedule },
+        })),
+    )
 }
 
-#[derive(Serialize, Deserialize)]
-struct ScrapeScheduleResponse {
-    games: usize,
-    promotions: usize,
-    tickets: usize,
+/// What a running server is actually using: logged once by `gtm serve`
+/// and returned by `GET /api/admin/config`. Secrets are redacted.
+#[derive(Serialize)]
+struct ConfigSummary {
+    version: String,
+    address: String,
+    db_backend: &'static str,
+    /// Newest applied migration.
+    migration_version: Option<i64>,
+    pending_migrations: usize,
+    jwks_keys: usize,
+    /// Optional integrations and behaviours that are switched on.
+    features: Vec<&'static str>,
+    config: serde_json::Map<String, serde_json::Value>,
 }
 
-/// Shared scrape logic: fetch schedule from MLB API, upsert games/promotions, generate tickets.
-async fn run_scrape(pool: &AnyPool, season: u32) -> anyhow::Result<(usize, usize, u64)> {
-    let data = gtm_scraper::fetch_schedule(season).await?;
-    for game in &data.games {
-        gtm_db::upsert_game(pool, game).await?;
-    }
-    for promo in &data.promotions {
-        gtm_db::upsert_promotion(pool, promo).await?;
-    }
-    let ticket_count = gtm_db::generate_tickets_for_all_seats(pool).await?;
-    info!(
-        "{} games, {} promotions upserted, {} tickets generated",
-        data.games.len(),
-        data.promotions.len(),
-        ticket_count
-    );
-    Ok((data.games.len(), data.promotions.len(), ticket_count))
+async fn config_summary(state: &AppState) -> anyhow::Result<ConfigSummary> {
+    let config = &state.config;
+    let migrations = gtm_db::maintenance::migrations(&state.pool, &config.db_url).await?;
+    let features = [
+        (
+            "email",
+            state.notifier.is_enabled(gtm_notify::Channel::Email),
+        ),
+        ("sms", state.notifier.is_enabled(gtm_notify::Channel::Sms)),
+        ("google_calendar", state.gcal.is_enabled()),
+        ("payments", state.payments.is_enabled()),
+        ("read_replica", state.read_pool.using_replica()),
+        ("run_jobs", config.run_jobs),
+        ("live_scores", config.live_scores),
+        ("schema_drift", config.schema_drift),
+        ("auto_migrate", config.auto_migrate),
+    ];
+    Ok(ConfigSummary {
+        version: version_string(),
+        address: format!("0.0.0.0:{}", config.port),
+        db_backend: gtm_db::Backend::from_url(&config.db_url)
+            .map(gtm_db::Backend::name)
+            .unwrap_or("unknown"),
+        migration_version: migrations
+            .iter()
+            .filter(|m| m.applied)
+            .map(|m| m.version)
+            .max(),
+        pending_migrations: migrations.iter().filter(|m| !m.applied).count(),
+        jwks_keys: state.auth.jwks.read().await.keys.len(),
+        features: features
+            .into_iter()
+            .filter(|(_, on)| *on)
+            .map(|(name, _)| name)
+            .collect(),
+        config: config
+            .redacted()
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.into()))
+            .collect(),
+    })
 }
 
-async fn api_scrape_schedule(
-    _auth_user: AuthUser,
-    State(pool): State<AnyPool>,
-    Json(body): Json<ScrapeScheduleRequest>,
-) -> Result<Json<ScrapeScheduleResponse>, (StatusCode, String)> {
-    let season = body.season.unwrap_or(chrono::Local::now().year() as u32);
-    let (games, promotions, tickets) = run_scrape(&pool, season)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    Ok(Json(ScrapeScheduleResponse {
-        games,
-        promotions,
-        tickets: tickets as usize,
-    }))
+/// `GET /api/admin/config` — the effective configuration, redacted.
+async fn api_admin_config(State(state): State<AppState>) -> Result<Json<ConfigSummary>, ApiError> {
+    Ok(Json(config_summary(&state).await?))
 }
 
-// --- Helper: resolve AuthUser → local User ---
+#[derive(Deserialize)]
+struct SavedViewQuery {
+    view: Option<i64>,
+}
 
-async fn resolve_user(
-    auth_user: &AuthUser,
-    pool: &AnyPool,
-) -> Result<gtm_models::User, (StatusCode, String)> {
-    let name = auth_user.name.as_deref().unwrap_or("Unknown");
-    let email = auth_user.email.as_deref().unwrap_or("unknown@example.com");
-    gtm_db::upsert_user(pool, &auth_user.sub, email, name)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+#[derive(Deserialize)]
+struct GamesIncludeQuery {
+    /// Comma-separated: `promotions`, `tickets`.
+    include: Option<String>,
 }
 
-fn require_admin(auth_user: &AuthUser) -> Result<(), (StatusCode, String)> {
-    if !auth_user.roles.contains(&"admin".to_string()) {
-        warn!(sub = %auth_user.sub, roles = ?auth_user.roles, "Admin access denied");
-        Err((StatusCode::FORBIDDEN, "Admin access required".to_string()))
-    } else {
-        Ok(())
+/// `GET /api/games` — filtered by query params (see `gtm_db::GameFilter`),
+/// or by a saved view's filter with `?view=<id>` (paging params still
+/// apply). `?include=promotions,tickets` adds each game's promotion names
+/// and ticket counts. The total match count, before paging, is returned in
+/// `X-Total-Count`.
+/// Pair `entities` with their external refs when `caller` is an admin.
+/// Public endpoints serve everyone else the bare entities: the refs are
+/// ids in outside systems, which aren't the public's business.
+async fn attach_refs_for<T>(
+    pool: &AnyPool,
+    caller: &Result<AuthUser, ApiError>,
+    kind: EntityKind,
+    entities: Vec<T>,
+    id_of: impl Fn(&T) -> i64,
+) -> Result<Vec<WithRefs<T>>, ApiError> {
+    if caller.as_ref().is_ok_and(AuthUser::is_admin) {
+        return Ok(gtm_db::external_refs::attach(pool, kind, entities, id_of).await?);
     }
+    Ok(entities
+        .into_iter()
+        .map(|entity| WithRefs {
+            entity,
+            external_refs: Vec::new(),
+        })
+        .collect())
 }
 
-// --- Member: Ticket Requests ---
+async fn api_list_games(
+    State(primary): State<AnyPool>,
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(mut filter): Query<gtm_db::GameFilter>,
+    Query(params): Query<SavedViewQuery>,
+    Query(include): Query<GamesIncludeQuery>,
+    caller: Result<AuthUser, ApiError>,
+) -> Result<
+    (
+        [(&'static str, String); 1],
+        Json<Vec<WithRefs<gtm_models::GameDetails>>>,
+    ),
+    ApiError,
+> {
+    let (mut promotions, mut tickets) = (false, false);
+    for part in include.include.as_deref().unwrap_or_default().split(',') {
+        match part.trim() {
+            "" => {}
+            "promotions" => promotions = true,
+            "tickets" => tickets = true,
+            other => {
+                return Err(ApiError::bad_request(format!(
+                    "Unknown include {other:?}; expected promotions or tickets"
+                )));
+            }
+        }
+    }
 
-#[derive(Deserialize)]
-struct CreateRequestBody {
-    game_pk: i64,
-    seats_requested: i64,
-    notes: Option<String>,
+    if let Some(id) = params.view {
+        // Views are private to the member who saved them
+        let Ok(auth_user) = &caller else {
+            return Err(ApiError::unauthorized("Sign in to use a saved view"));
+        };
+        let member = resolve_user(auth_user, &primary).await?;
+        let view = read
+            .run(|pool| async move { gtm_db::saved_views::get(&pool, id).await })
+            .await?
+            .filter(|v| v.user_id == member.id)
+            .ok_or_else(|| ApiError::not_found("Saved view not found"))?;
+        filter = gtm_db::GameFilter {
+            limit: filter.limit.or(view.filter.limit),
+            offset: filter.offset.or(view.filter.offset),
+            ..view.filter
+        };
+    }
+    filter.team_id = config.team_id;
+    for code in filter.game_types() {
+        code.parse::<GameType>().map_err(ApiError::bad_request)?;
+    }
+    filter.days().map_err(ApiError::bad_request)?;
+    let (mut games, policy, total) = read
+        .run(|pool| {
+            let filter = &filter;
+            async move {
+                let games: Vec<gtm_models::GameDetails> = if promotions || tickets {
+                    gtm_db::list_games_with_details(&pool, filter).await?
+                } else {
+                    gtm_db::list_games(&pool, filter)
+                        .await?
+                        .into_iter()
+                        .map(|game| gtm_models::GameDetails {
+                            game,
+                            promotions: None,
+                            tickets: None,
+                        })
+                        .collect()
+                };
+                let policy = gtm_db::seat_limits::SeatLimitPolicy::load(&pool).await?;
+                let total = gtm_db::count_games(&pool, filter).await?;
+                anyhow::Ok((games, policy, total))
+            }
+        })
+        .await?;
+    let tz = display_tz(&config)?;
+    for g in &mut games {
+        g.game.max_seats = Some(policy.max_seats(&g.game));
+        g.game.game_type_label = Some(GameType::label_for(&g.game.game_type).to_string());
+        g.game.set_local_time(tz);
+        if !promotions {
+            g.promotions = None;
+        }
+        if !tickets {
+            g.tickets = None;
+        }
+    }
+    let games = attach_refs_for(&primary, &caller, EntityKind::Game, games, |g| {
+        g.game.game_pk.0
+    })
+    .await?;
+    Ok(([("x-total-count", total.to_string())], Json(games)))
 }
 
+/// `GET /api/games/search` parameters. Dates are inclusive; everything is
+/// optional.
 #[derive(Deserialize)]
-struct CreateRequestBatchBody {
-    requests: Vec<CreateRequestBody>,
+struct GameSearchQuery {
+    /// Substring of the opponent's name.
+    q: Option<String>,
+    /// Earliest date, YYYY-MM-DD.
+    after: Option<String>,
+    /// Latest date, YYYY-MM-DD.
+    before: Option<String>,
+    season: Option<String>,
+    /// Comma-separated days of the week, e.g. `fri,sat`.
+    days: Option<String>,
+    weekend: Option<bool>,
+    /// `day` or `night`.
+    day_night: Option<String>,
+    /// Only games with (or without) promotions.
+    promotions: Option<bool>,
+    #[serde(default)]
+    home_only: bool,
+    limit: Option<i64>,
 }
 
-async fn api_my_requests_list(
-    auth_user: AuthUser,
-    State(pool): State<AnyPool>,
-) -> Result<Json<Vec<gtm_models::TicketRequest>>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    gtm_db::list_requests_for_user(&pool, user.id)
-        .await
-        .map(Json)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+/// Find games worth requesting: opponent, date range, day of the week,
+/// day/night and promotions, with each game's promotion names and ticket
+/// counts.
+async fn api_search_games(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<GameSearchQuery>,
+) -> Result<Json<Vec<gtm_models::GameDetails>>, ApiError> {
+    for date in [&params.after, &params.before].into_iter().flatten() {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+            ApiError::bad_request(format!("Invalid date {date:?}; expected YYYY-MM-DD"))
+        })?;
+    }
+    if let Some(day_night) = &params.day_night
+        && !["day", "night"].contains(&day_night.to_lowercase().as_str())
+    {
+        return Err(ApiError::bad_request(format!(
+            "Unknown day_night {day_night:?}; expected day or night"
+        )));
+    }
+    let filter = gtm_db::GameFilter {
+        season: params.season,
+        from: params.after,
+        to: params.before,
+        opponent: params.q.filter(|q| !q.trim().is_empty()),
+        home_only: params.home_only,
+        team_id: config.team_id,
+        days: params.days,
+        weekend: params.weekend,
+        day_night: params.day_night,
+        has_promotions: params.promotions,
+        limit: Some(params.limit.unwrap_or(gtm_db::MAX_GAMES_PAGE)),
+        ..Default::default()
+    };
+    filter.days().map_err(ApiError::bad_request)?;
+
+    let (mut games, policy) = read
+        .run(|pool| {
+            let filter = &filter;
+            async move {
+                let games = gtm_db::list_games_with_details(&pool, filter).await?;
+                let policy = gtm_db::seat_limits::SeatLimitPolicy::load(&pool).await?;
+                anyhow::Ok((games, policy))
+            }
+        })
+        .await?;
+    let tz = display_tz(&config)?;
+    for g in &mut games {
+        g.game.max_seats = Some(policy.max_seats(&g.game));
+        g.game.game_type_label = Some(GameType::label_for(&g.game.game_type).to_string());
+        g.game.set_local_time(tz);
+    }
+    Ok(Json(games))
 }
 
-async fn api_my_requests_create(
-    auth_user: AuthUser,
-    State(pool): State<AnyPool>,
-    Json(body): Json<CreateRequestBatchBody>,
-) -> Result<Json<Vec<gtm_models::TicketRequest>>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    let mut results = Vec::new();
-    for req in &body.requests {
-        if req.seats_requested < 1 || req.seats_requested > 4 {
-            return Err((
-                StatusCode::BAD_REQUEST,
-                format!(
-                    "seats_requested must be 1-4 (got {} for game_pk {})",
-                    req.seats_requested, req.game_pk
-                ),
-            ));
-        }
-        let tr = gtm_db::create_ticket_request(
-            &pool,
-            user.id,
-            req.game_pk,
-            req.seats_requested,
-            req.notes.as_deref(),
-        )
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        results.push(tr);
-    }
-    Ok(Json(results))
+/// `GET /api/promotions` — search by `type`, `name`, `distribution`,
+/// `month` and `from` (see `gtm_db::PromotionFilter`).
+async fn api_search_promotions(
+    State(read): State<gtm_db::replica::ReadPool>,
+    Query(filter): Query<gtm_db::PromotionFilter>,
+) -> Result<Json<Vec<gtm_models::PromotionListing>>, ApiError> {
+    read.run(|pool| {
+        let filter = &filter;
+        async move { gtm_db::search_promotions(&pool, filter).await }
+    })
+    .await
+    .map(Json)
+    .map_err(ApiError::from)
 }
 
 #[derive(Deserialize)]
-struct UpdateRequestBody {
-    seats_requested: i64,
+struct PromotionImageParams {
+    /// `thumbnail` for the small image; the full image otherwise.
+    size: Option<String>,
 }
 
-async fn api_my_requests_update(
-    auth_user: AuthUser,
+/// `GET /api/promotions/{offer_id}/image` — the promotion's image, fetched
+/// from MLB once and served from the image cache after that. Falls back to
+/// the other size when the promotion has only one.
+async fn api_promotion_image(
     State(pool): State<AnyPool>,
-    Path(request_id): Path<i64>,
-    Json(body): Json<UpdateRequestBody>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    if body.seats_requested < 1 || body.seats_requested > 4 {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            "seats_requested must be 1-4".to_string(),
-        ));
-    }
-    let updated = gtm_db::update_ticket_request(&pool, request_id, user.id, body.seats_requested)
+    State(images): State<Arc<gtm_scraper::images::ImageCache>>,
+    Path(offer_id): Path<i64>,
+    Query(params): Query<PromotionImageParams>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+
+    let promo = gtm_db::get_promotion(&pool, offer_id)
+        .await?
+        .ok_or(ApiError::not_found("Promotion not found"))?;
+    let thumbnail = params.size.as_deref() == Some("thumbnail");
+    let (size, url) = match (thumbnail, promo.thumbnail_url, promo.image_url) {
+        (true, Some(url), _) | (true, None, Some(url)) => ("thumbnail", url),
+        (false, _, Some(url)) | (false, Some(url), None) => ("image", url),
+        _ => return Err(ApiError::not_found("This promotion has no image")),
+    };
+    let image = images
+        .get(&format!("{offer_id}-{size}"), &url)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    if updated {
-        Ok(Json(json!({ "status": "ok" })))
-    } else {
-        Err((
-            StatusCode::NOT_FOUND,
-            "Request not found or not pending".to_string(),
-        ))
-    }
+        .map_err(|e| {
+            warn!(offer_id, error = %e, "Promotion image fetch failed");
+            ApiError::upstream("Could not fetch the promotion image")
+        })?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, image.content_type),
+            (
+                header::CACHE_CONTROL,
+                format!("public, max-age={}", images.ttl().as_secs()),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        image.bytes,
+    )
+        .into_response())
 }
 
-async fn api_my_requests_withdraw(
-    auth_user: AuthUser,
+async fn api_get_game(
     State(pool): State<AnyPool>,
-    Path(request_id): Path<i64>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    let withdrawn = gtm_db::withdraw_ticket_request(&pool, request_id, user.id)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    if withdrawn {
-        Ok(Json(json!({ "status": "ok" })))
-    } else {
-        Err((
-            StatusCode::NOT_FOUND,
-            "Request not found or not pending".to_string(),
-        ))
-    }
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(game_pk): Path<GamePk>,
+    caller: Result<AuthUser, ApiError>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let mut game = gtm_db::get_game(&pool, game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+    let policy = gtm_db::seat_limits::SeatLimitPolicy::load(&pool).await?;
+    game.max_seats = Some(policy.max_seats(&game));
+    game.game_type_label = Some(GameType::label_for(&game.game_type).to_string());
+    game.set_local_time(display_tz(&config)?);
+    let game = attach_refs_for(&pool, &caller, EntityKind::Game, vec![game], |g| {
+        g.game_pk.0
+    })
+    .await?;
+    Ok(Json(serde_json::to_value(&game[0]).unwrap()))
 }
 
-// --- Member: My Games ---
-
-async fn api_my_games(
-    auth_user: AuthUser,
+async fn api_get_game_promotions(
     State(pool): State<AnyPool>,
-) -> Result<Json<Vec<gtm_models::GameTicketDetail>>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    gtm_db::list_tickets_for_user(&pool, user.id)
+    Path(game_pk): Path<GamePk>,
+) -> Result<Json<Vec<gtm_models::Promotion>>, ApiError> {
+    gtm_db::get_promotions_for_game(&pool, game_pk)
         .await
         .map(Json)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        .map_err(ApiError::from)
 }
 
-async fn api_my_games_release(
-    auth_user: AuthUser,
+async fn api_get_game_preview(
     State(pool): State<AnyPool>,
-    Path(game_pk): Path<i64>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    let count = gtm_db::release_tickets_for_game(&pool, game_pk, user.id)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    Ok(Json(json!({ "status": "ok", "released": count })))
+    Path(game_pk): Path<GamePk>,
+) -> Result<Json<gtm_models::GamePreview>, ApiError> {
+    gtm_db::previews::get(&pool, game_pk)
+        .await?
+        .map(Json)
+        .ok_or(ApiError::not_found("No preview for this game yet"))
 }
 
-// --- Member: My Game Tags ---
+/// Warning for seats added before any ticketed games are on the schedule.
+const NO_TICKETS_GENERATED: &str =
+    "No ticketed games on the schedule yet; tickets will be generated by the next scrape";
 
-async fn api_my_game_tags(
-    auth_user: AuthUser,
-    State(pool): State<AnyPool>,
-) -> Result<Json<Vec<gtm_models::GameTag>>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    gtm_db::list_game_tags_for_user(&pool, user.id)
-        .await
-        .map(Json)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+#[derive(Deserialize)]
+struct AddSeatRequest {
+    section: String,
+    row: String,
+    seat: String,
+    notes: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct GameTagBody {
-    shortlist: bool,
-    cant_go: bool,
+async fn api_add_seat(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Json(body): Json<AddSeatRequest>,
+) -> Result<Json<Envelope<gtm_models::Seat>>, ApiError> {
+    let (seat, count) = service::add_seat(pool, config, body).await?;
+    let mut warnings = gtm_db::Warnings::new();
+    if count == 0 {
+        warnings.push(NO_TICKETS_GENERATED);
+    }
+    Ok(Envelope::with_warnings(seat, warnings))
 }
 
-async fn api_my_game_tags_put(
-    auth_user: AuthUser,
+async fn api_list_seats(
     State(pool): State<AnyPool>,
-    Path(game_pk): Path<i64>,
-    Json(body): Json<GameTagBody>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let user = resolve_user(&auth_user, &pool).await?;
-    gtm_db::upsert_game_tag(&pool, user.id, game_pk, body.shortlist, body.cant_go)
+) -> Result<Json<Vec<gtm_models::Seat>>, ApiError> {
+    gtm_db::list_seats(&pool)
         .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    Ok(Json(json!({ "status": "ok" })))
+        .map(Json)
+        .map_err(ApiError::from)
 }
 
-// --- Admin: Allocation ---
-
-#[derive(Serialize)]
-struct AllocationSummaryRow {
-    game_pk: i64,
-    official_date: String,
-    away_team_name: String,
-    total_seats: i64,
-    assigned: i64,
-    available: i64,
-    total_requested: i64,
-    oversubscribed: bool,
+#[derive(Deserialize)]
+struct AddSeatBatchRequest {
+    section: String,
+    row: String,
+    seat_start: u32,
+    seat_end: u32,
+    notes: Option<String>,
 }
 
-async fn api_admin_allocation(
-    auth_user: AuthUser,
+async fn api_add_seat_batch(
     State(pool): State<AnyPool>,
-) -> Result<Json<Vec<AllocationSummaryRow>>, (StatusCode, String)> {
-    let _user = resolve_user(&auth_user, &pool).await.inspect_err(|e| {
-        warn!(error = %e.1, "allocation: resolve_user failed");
-    })?;
-    require_admin(&auth_user)?;
-
-    let summary = gtm_db::allocation_summary(&pool).await.map_err(|e| {
-        warn!(error = %e, "allocation: allocation_summary query failed");
-        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-    })?;
-
-    let games = gtm_db::list_games(&pool, None).await.map_err(|e| {
-        warn!(error = %e, "allocation: list_games query failed");
-        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
-    })?;
-    let game_map: std::collections::HashMap<i64, &gtm_models::Game> =
-        games.iter().map(|g| (g.game_pk, g)).collect();
-
-    let rows: Vec<AllocationSummaryRow> = summary
-        .into_iter()
-        .filter_map(
-            |(game_pk, total_seats, assigned, available, total_requested)| {
-                let g = game_map.get(&game_pk)?;
-                Some(AllocationSummaryRow {
-                    game_pk,
-                    official_date: g.official_date.clone(),
-                    away_team_name: g.away_team_name.clone(),
-                    total_seats,
-                    assigned,
-                    available,
-                    total_requested,
-                    oversubscribed: total_requested > available,
-                })
-            },
-        )
-        .collect();
-
-    Ok(Json(rows))
+    State(config): State<Arc<gtm_config::Config>>,
+    Json(body): Json<AddSeatBatchRequest>,
+) -> Result<Json<Envelope<Vec<gtm_models::Seat>>>, ApiError> {
+    if body.seat_start > body.seat_end {
+        return Err(ApiError::bad_request("seat_start must be <= seat_end"));
+    }
+    if body.seat_end - body.seat_start >= 50 {
+        return Err(ApiError::bad_request("Maximum 50 seats per batch"));
+    }
+    let (seats, generated) = service::add_seat_batch(pool, config, body).await?;
+    let mut warnings = gtm_db::Warnings::new();
+    if generated == 0 {
+        warnings.push(NO_TICKETS_GENERATED);
+    }
+    Ok(Envelope::with_warnings(seats, warnings))
 }
 
-#[derive(Serialize)]
-struct GameAllocationDetail {
-    game: gtm_models::Game,
-    tickets: Vec<GameTicketWithUser>,
-    requests: Vec<RequestWithUser>,
+/// Import seats from a CSV body (`Content-Type: text/csv`) or a JSON array.
+/// Nothing is added unless every seat is valid and new.
+async fn api_import_seats(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    headers: axum::http::HeaderMap,
+    body: String,
+) -> Result<Json<Envelope<gtm_models::SeatImport>>, ApiError> {
+    let is_csv = headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/csv"));
+    let rows: Vec<gtm_models::SeatImportRow> = if is_csv {
+        gtm_db::import::parse_seats_csv(&body).map_err(ApiError::invalid)?
+    } else {
+        serde_json::from_str(&body).map_err(ApiError::invalid)?
+    };
+    let import =
+        gtm_db::import::import_seats(&pool, &rows, config.team_id, &ticketed_game_types(&config))
+            .await
+            .map_err(ApiError::invalid)?;
+    info!(
+        "{} seats imported, {} game tickets generated",
+        import.seats.len(),
+        import.tickets
+    );
+    let mut warnings = gtm_db::Warnings::new();
+    if import.tickets == 0 {
+        warnings.push(NO_TICKETS_GENERATED);
+    }
+    Ok(Envelope::with_warnings(import, warnings))
 }
 
-#[derive(Serialize)]
-struct GameTicketWithUser {
-    id: i64,
-    seat_id: i64,
+#[derive(Deserialize)]
+struct UpdateSeatGroupRequest {
     section: String,
     row: String,
-    seat: String,
-    status: String,
-    assigned_to: Option<i64>,
-    assigned_user_name: Option<String>,
+    notes: Option<String>,
 }
 
-#[derive(Serialize)]
-struct RequestWithUser {
-    id: i64,
-    user_id: i64,
-    user_name: String,
-    seats_requested: i64,
-    status: String,
-    notes: Option<String>,
-}
-
-async fn api_admin_allocation_game(
-    auth_user: AuthUser,
+async fn api_update_seat_group(
     State(pool): State<AnyPool>,
-    Path(game_pk): Path<i64>,
-) -> Result<Json<GameAllocationDetail>, (StatusCode, String)> {
-    let _user = resolve_user(&auth_user, &pool).await?;
-    require_admin(&auth_user)?;
+    Json(body): Json<UpdateSeatGroupRequest>,
+) -> Result<Json<Envelope<Vec<gtm_models::Seat>>>, ApiError> {
+    let updated =
+        gtm_db::update_seat_group_notes(&pool, &body.section, &body.row, body.notes.as_deref())
+            .await?;
+    if updated == 0 {
+        return Err(ApiError::not_found("No seats found for that section/row"));
+    }
+    info!(
+        "Updated notes for {} seats in Section {} Row {}",
+        updated, body.section, body.row
+    );
+    let seats = gtm_db::list_seats(&pool).await?;
+    Ok(Envelope::ok(seats))
+}
 
-    let game = gtm_db::get_game(&pool, game_pk)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
-        .ok_or((StatusCode::NOT_FOUND, "Game not found".to_string()))?;
+#[derive(Deserialize)]
+struct SeatGroupRequest {
+    section: String,
+    row: String,
+}
 
-    let tickets = gtm_db::list_tickets_for_game(&pool, game_pk)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+#[derive(Deserialize)]
+struct DeleteSeatGroupRequest {
+    section: String,
+    row: String,
+    /// Report what would be deleted (and a confirm token) without deleting.
+    #[serde(default)]
+    dry_run: bool,
+    confirm_token: Option<String>,
+}
 
-    let requests = gtm_db::list_requests_for_game(&pool, game_pk)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+async fn api_delete_seat_group(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Json(body): Json<DeleteSeatGroupRequest>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::response::IntoResponse;
+
+    let seats = gtm_db::list_seats_in_group(&pool, &body.section, &body.row).await?;
+    let scope = format!("{}/{}", body.section, body.row);
+    let guard = BulkGuardQuery {
+        dry_run: body.dry_run,
+        confirm_token: body.confirm_token,
+    };
+    let (section, row) = (body.section.clone(), body.row.clone());
+    let work = {
+        let pool = pool.clone();
+        async move { Ok(gtm_db::delete_seat_group(&pool, &section, &row).await?) }
+    };
+    let outcomes = match service::guarded(
+        pool,
+        config,
+        "seats.delete_group",
+        scope,
+        seats.len() as i64,
+        guard,
+        work,
+    )
+    .await?
+    {
+        Guarded::Preview(preview) => return Ok(Json(preview).into_response()),
+        Guarded::Done(outcomes) => outcomes,
+    };
+    if outcomes.is_empty() {
+        return Err(ApiError::not_found("No seats found for that section/row"));
+    }
+    info!(
+        "Deleted {} seats in Section {} Row {}",
+        outcomes.len(),
+        body.section,
+        body.row
+    );
+    Ok(Json(outcomes).into_response())
+}
 
-    let users = gtm_db::list_users(&pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let user_map: std::collections::HashMap<i64, &gtm_models::User> =
-        users.iter().map(|u| (u.id, u)).collect();
+// --- Bulk-operation guard ---
 
-    let tickets_with_user: Vec<GameTicketWithUser> = tickets
-        .into_iter()
-        .map(|t| GameTicketWithUser {
-            id: t.id,
-            seat_id: t.seat_id,
-            section: t.section,
-            row: t.row,
-            seat: t.seat,
-            status: t.status,
-            assigned_to: t.assigned_to,
-            assigned_user_name: t
-                .assigned_to
-                .and_then(|uid| user_map.get(&uid).map(|u| u.name.clone())),
-        })
-        .collect();
+/// How long a dry run's confirm token stays valid.
+const BULK_CONFIRM_TTL_MINUTES: i64 = 10;
 
-    let requests_with_user: Vec<RequestWithUser> = requests
-        .into_iter()
-        .map(|r| RequestWithUser {
-            id: r.id,
-            user_id: r.user_id,
-            user_name: user_map
-                .get(&r.user_id)
-                .map(|u| u.name.clone())
-                .unwrap_or_default(),
-            seats_requested: r.seats_requested,
-            status: r.status,
-            notes: r.notes,
-        })
-        .collect();
+/// What a bulk operation would do, returned instead of doing it.
+#[derive(Serialize)]
+struct BulkPreview {
+    operation: String,
+    affected: i64,
+    threshold: i64,
+    /// Pass back as `confirm_token` to go ahead; only issued when `affected`
+    /// is over the threshold.
+    confirm_token: Option<String>,
+}
 
-    Ok(Json(GameAllocationDetail {
-        game,
-        tickets: tickets_with_user,
-        requests: requests_with_user,
-    }))
+/// Safety check for destructive bulk endpoints. Returns `Some(preview)` for a
+/// dry run, `None` when the operation may proceed, and 428 when it touches
+/// more than `bulk_confirm_threshold` rows without a matching, unexpired
+/// token from a dry run of the same `operation` and `scope`. A token is also
+/// rejected if the operation now matches more rows than when it was issued.
+async fn guard_bulk_operation(
+    pool: &AnyPool,
+    config: &gtm_config::Config,
+    operation: &str,
+    scope: &str,
+    affected: i64,
+    dry_run: bool,
+    confirm_token: Option<&str>,
+) -> Result<Option<BulkPreview>, ApiError> {
+    use rand::Rng;
+
+    let threshold = config.bulk_confirm_threshold;
+    if dry_run {
+        let confirm_token = if affected > threshold {
+            let token = format!("{:032x}", rand::thread_rng().r#gen::<u128>());
+            let expires_at =
+                rfc3339_utc(Utc::now() + chrono::Duration::minutes(BULK_CONFIRM_TTL_MINUTES));
+            gtm_db::create_bulk_confirmation(pool, &token, operation, scope, affected, &expires_at)
+                .await?;
+            Some(token)
+        } else {
+            None
+        };
+        return Ok(Some(BulkPreview {
+            operation: operation.to_string(),
+            affected,
+            threshold,
+            confirm_token,
+        }));
+    }
+    if affected <= threshold {
+        return Ok(None);
+    }
+    let Some(token) = confirm_token else {
+        return Err(ApiError::precondition_required(format!(
+            "This would affect {affected} rows (limit {threshold}); \
+             repeat with dry_run to get a confirm_token"
+        ))
+        .with_details(json!({ "affected": affected, "limit": threshold })));
+    };
+    let confirmed =
+        gtm_db::take_bulk_confirmation(pool, token, operation, scope, &rfc3339_utc(Utc::now()))
+            .await?;
+    match confirmed {
+        Some(n) if affected <= n => {
+            info!(operation, scope, affected, "Bulk operation confirmed");
+            Ok(None)
+        }
+        Some(n) => Err(ApiError::conflict(format!(
+            "Now affects {affected} rows but {n} were confirmed; run the dry run again"
+        ))),
+        None => Err(ApiError::precondition_required(
+            "confirm_token is invalid, expired, or for a different operation",
+        )),
+    }
 }
 
-#[derive(Deserialize)]
-struct AllocateBody {
-    game_ticket_id: i64,
-    user_id: i64,
-    request_id: Option<i64>,
+async fn api_regenerate_seat_group_tickets(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Json(body): Json<SeatGroupRequest>,
+) -> Result<Json<Envelope<Vec<gtm_models::SeatTicketOutcome>>>, ApiError> {
+    let outcomes = gtm_db::regenerate_seat_group_tickets(
+        &pool,
+        &body.section,
+        &body.row,
+        config.team_id,
+        &ticketed_game_types(&config),
+    )
+    .await?;
+    if outcomes.is_empty() {
+        return Err(ApiError::not_found("No seats found for that section/row"));
+    }
+    let generated: u64 = outcomes.iter().map(|o| o.tickets_generated).sum();
+    info!(
+        "Regenerated {} tickets for {} seats in Section {} Row {}",
+        generated,
+        outcomes.len(),
+        body.section,
+        body.row
+    );
+    Ok(Envelope::ok(outcomes))
 }
 
 #[derive(Deserialize)]
-struct AllocateBatchBody {
-    assignments: Vec<AllocateBody>,
+struct BulkGuardQuery {
+    /// Report what would be deleted (and a confirm token) without deleting.
+    #[serde(default)]
+    dry_run: bool,
+    confirm_token: Option<String>,
 }
 
-async fn api_admin_allocate(
-    auth_user: AuthUser,
+/// Deletes the seat with every game ticket it has, assigned or not, so it
+/// goes through the bulk guard counting those tickets.
+async fn api_delete_seat(
     State(pool): State<AnyPool>,
-    Json(body): Json<AllocateBatchBody>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let _user = resolve_user(&auth_user, &pool).await?;
-    require_admin(&auth_user)?;
-
-    let mut assigned_count = 0u64;
-    let mut approved_requests: std::collections::HashSet<i64> = std::collections::HashSet::new();
-
-    for a in &body.assignments {
-        let ok = gtm_db::assign_ticket(&pool, a.game_ticket_id, a.user_id)
-            .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-        if ok {
-            assigned_count += 1;
-            if let Some(rid) = a.request_id {
-                approved_requests.insert(rid);
-            }
-        }
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(seat_id): Path<SeatId>,
+    Query(query): Query<BulkGuardQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::response::IntoResponse;
+
+    let tickets = gtm_db::count_seat_tickets(&pool, seat_id).await?;
+    let work = {
+        let pool = pool.clone();
+        async move { Ok(gtm_db::delete_seat(&pool, seat_id).await?) }
+    };
+    let deleted = match service::guarded(
+        pool,
+        config,
+        "seats.delete",
+        seat_id.to_string(),
+        tickets,
+        query,
+        work,
+    )
+    .await?
+    {
+        Guarded::Preview(preview) => return Ok(Json(preview).into_response()),
+        Guarded::Done(deleted) => deleted,
+    };
+    if deleted {
+        info!(%seat_id, tickets, "Deleted seat and its tickets");
+        Ok(Json(json!({ "status": "ok" })).into_response())
+    } else {
+        Err(ApiError::not_found("Seat not found"))
     }
+}
 
-    // Update request statuses to approved
-    for request_id in &approved_requests {
-        gtm_db::update_request_approval(&pool, *request_id, "approved")
-            .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    }
+async fn api_get_game_tickets(
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+    caller: Result<AuthUser, ApiError>,
+) -> Result<Json<Vec<WithRefs<gtm_models::GameTicketDetail>>>, ApiError> {
+    let tickets = gtm_db::list_tickets_for_game(&pool, game_pk).await?;
+    Ok(Json(
+        attach_refs_for(&pool, &caller, EntityKind::Ticket, tickets, |t| t.id.0).await?,
+    ))
+}
 
-    Ok(Json(json!({ "status": "ok", "assigned": assigned_count })))
+#[derive(Deserialize)]
+struct UpdateTicketRequest {
+    status: gtm_models::TicketStatus,
+    notes: Option<String>,
 }
 
-async fn api_admin_revoke(
-    auth_user: AuthUser,
+async fn api_update_ticket(
     State(pool): State<AnyPool>,
-    Path(game_ticket_id): Path<i64>,
-) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    let _user = resolve_user(&auth_user, &pool).await?;
-    require_admin(&auth_user)?;
-
-    let ok = gtm_db::revoke_ticket(&pool, game_ticket_id)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    if ok {
-        Ok(Json(json!({ "status": "ok" })))
+    RequireAdmin(admin): RequireAdmin,
+    Path(ticket_id): Path<TicketId>,
+    Json(body): Json<UpdateTicketRequest>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let updated = gtm_db::update_ticket_status(
+        &pool,
+        ticket_id,
+        body.status,
+        body.notes.as_deref(),
+        Some(admin.id),
+    )
+    .await?;
+    if updated {
+        Ok(Envelope::ok(json!({ "status": "ok" })))
     } else {
-        Err((
-            StatusCode::NOT_FOUND,
-            "Ticket not found or not assigned".to_string(),
-        ))
+        Err(ApiError::not_found("Ticket not found"))
     }
 }
 
-async fn api_admin_allocation_by_user(
-    auth_user: AuthUser,
-    State(pool): State<AnyPool>,
-    Path(target_user_id): Path<i64>,
-) -> Result<Json<Vec<gtm_models::GameTicketDetail>>, (StatusCode, String)> {
-    let _user = resolve_user(&auth_user, &pool).await?;
-    require_admin(&auth_user)?;
+#[derive(Deserialize)]
+struct TicketSummaryQuery {
+    /// Only games from today (in the display time zone) onward.
+    #[serde(default)]
+    future_only: bool,
+    season: Option<String>,
+    month: Option<u32>,
+}
 
-    gtm_db::list_tickets_for_user(&pool, target_user_id)
+async fn api_ticket_summary(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<TicketSummaryQuery>,
+) -> Result<Json<Vec<gtm_models::TicketSummaryRow>>, ApiError> {
+    let tz = display_tz(&config)?;
+    let from = params
+        .future_only
+        .then(|| Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string());
+    let summary = read
+        .run(|pool| {
+            let (season, from) = (params.season.as_deref(), from.as_deref());
+            async move { gtm_db::ticket_summary_for_games(&pool, season, params.month, from).await }
+        })
+        .await?;
+    Ok(Json(summary))
+}
+
+// --- Seasons ---
+
+async fn api_list_seasons(
+    State(read): State<gtm_db::replica::ReadPool>,
+) -> Result<Json<Vec<gtm_models::Season>>, ApiError> {
+    read.run(|pool| async move { gtm_db::seasons::list(&pool).await })
         .await
         .map(Json)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        .map_err(ApiError::from)
 }
 
-// --- Admin: Allocation by users ---
+/// Freeze a finished season's tickets and requests. A season with games
+/// today or later can't be closed.
+async fn api_admin_close_season(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(season): Path<String>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let tz = display_tz(&config)?;
+    let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+    let found = gtm_db::seasons::list(&pool)
+        .await?
+        .into_iter()
+        .find(|s| s.season == season)
+        .ok_or_else(|| ApiError::not_found("Season not found"))?;
+    if found.last_date >= today {
+        return Err(ApiError::conflict(format!(
+            "The {season} season has games on or after {today}; only finished seasons can be closed"
+        )));
+    }
+    if !gtm_db::seasons::close(&pool, &season, Some(admin.id)).await? {
+        return Err(ApiError::conflict("Season already closed"));
+    }
+    info!(season, admin_id = %admin.id, "Season closed");
+    Ok(Envelope::ok(json!({ "status": "ok", "season": season })))
+}
+
+// --- User API endpoints ---
 
 #[derive(Serialize)]
-struct UserTicketInfo {
-    ticket_id: i64,
-    section: String,
-    row: String,
-    seat: String,
+struct MeResponse {
+    id: UserId,
+    auth0_sub: String,
+    email: String,
+    name: String,
+    role: String,
+    /// Notification language code; null for English.
+    locale: Option<String>,
 }
 
-#[derive(Serialize)]
-struct UserAllocationEntry {
-    request_id: i64,
-    game_pk: i64,
-    official_date: String,
-    away_team_name: String,
-    day_night: Option<String>,
-    seats_requested: i64,
-    status: String,
-    notes: Option<String>,
-    game_total_seats: i64,
-    game_available: i64,
-    user_tickets: Vec<UserTicketInfo>,
+async fn api_get_me(
+    RequireMember(user): RequireMember,
+    auth_user: AuthUser,
+    State(pool): State<AnyPool>,
+) -> Result<Json<MeResponse>, ApiError> {
+    let role = if auth_user.is_admin() {
+        "admin"
+    } else {
+        "member"
+    };
+    info!(sub = %auth_user.sub, jwt_roles = ?auth_user.roles, resolved_role = %role, "GET /api/users/me");
+    Ok(Json(MeResponse {
+        id: user.id,
+        auth0_sub: user.auth0_sub,
+        email: user.email,
+        name: user.name,
+        role: role.to_string(),
+        locale: gtm_db::get_user_locale(&pool, user.id).await?,
+    }))
 }
 
+/// A user the caller may act as when login is off.
 #[derive(Serialize)]
-struct UserAllocationSection {
-    user_id: i64,
-    user_name: String,
-    total_allocated: i64,
-    total_requested: i64,
-    games_allocated: i64,
-    games_requested: i64,
-    entries: Vec<UserAllocationEntry>,
+struct LocalUser {
+    id: UserId,
+    name: String,
+    email: String,
+    admin: bool,
 }
 
-async fn api_admin_allocation_by_users(
-    auth_user: AuthUser,
+/// Everyone the SPA's user picker offers with `auth_mode = "none"`; 404
+/// when logging in through Auth0.
+async fn api_local_users(
+    State(auth): State<Arc<AuthConfig>>,
     State(pool): State<AnyPool>,
-) -> Result<Json<Vec<UserAllocationSection>>, (StatusCode, String)> {
-    let _user = resolve_user(&auth_user, &pool).await?;
-    require_admin(&auth_user)?;
+) -> Result<Json<Vec<LocalUser>>, ApiError> {
+    let Some(admins) = &auth.local_admins else {
+        return Err(ApiError::not_found("Login is through Auth0"));
+    };
+    let users = gtm_db::list_users(&pool).await?;
+    Ok(Json(
+        users
+            .into_iter()
+            .map(|u| LocalUser {
+                admin: admins.contains(&u.email.to_lowercase()),
+                id: u.id,
+                name: u.name,
+                email: u.email,
+            })
+            .collect(),
+    ))
+}
 
-    let requests = gtm_db::list_all_active_requests(&pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let assigned_tickets = gtm_db::list_all_assigned_tickets(&pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let games = gtm_db::list_games(&pool, None)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let users = gtm_db::list_users(&pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-    let summary = gtm_db::allocation_summary(&pool)
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+async fn api_list_users(
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<WithRefs<gtm_models::User>>>, ApiError> {
+    let users = gtm_db::list_users(&pool).await?;
+    Ok(Json(
+        gtm_db::external_refs::attach(&pool, EntityKind::User, users, |u| u.id.0).await?,
+    ))
+}
 
-    let game_map: std::collections::HashMap<i64, &gtm_models::Game> =
-        games.iter().map(|g| (g.game_pk, g)).collect();
-    let user_map: std::collections::HashMap<i64, &gtm_models::User> =
-        users.iter().map(|u| (u.id, u)).collect();
-    // game_pk -> (total_seats, assigned, available)
-    let summary_map: std::collections::HashMap<i64, (i64, i64, i64)> = summary
-        .into_iter()
-        .map(|(gp, total, assigned, available, _req)| (gp, (total, assigned, available)))
-        .collect();
-    // (user_id, game_pk) -> Vec<ticket>
-    let mut ticket_map: std::collections::HashMap<(i64, i64), Vec<&gtm_models::GameTicketDetail>> =
-        std::collections::HashMap::new();
-    for t in &assigned_tickets {
-        if let Some(uid) = t.assigned_to {
-            ticket_map.entry((uid, t.game_pk)).or_default().push(t);
-        }
+#[derive(Deserialize)]
+struct ScrapeScheduleRequest {
+    season: Option<u32>,
+    /// Game types to fetch (default: the configured ticketed types).
+    game_types: Option<Vec<GameType>>,
+    /// Optional schedule sections to fetch (default: `scrape_hydrate`).
+    hydrate: Option<Vec<Hydration>>,
+    /// Sync even if the scrape breaks the sync thresholds.
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ScrapeScheduleResponse {
+    games: usize,
+    promotions: usize,
+    tickets: usize,
+    /// Human-readable diff against the stored schedule.
+    changes: Vec<String>,
+    /// Fields the MLB API sent that we don't know about.
+    drift: Vec<String>,
+}
+
+/// Timeouts and retry schedule for MLB and weather API calls.
+fn http_policy(config: &gtm_config::Config) -> gtm_scraper::http::RetryPolicy {
+    gtm_scraper::http::RetryPolicy {
+        connect_timeout: std::time::Duration::from_secs(config.http_connect_timeout_secs),
+        timeout: std::time::Duration::from_secs(config.http_timeout_secs),
+        max_attempts: config.http_max_attempts,
+        initial_backoff: std::time::Duration::from_millis(config.http_backoff_ms),
+        max_backoff: std::time::Duration::from_millis(config.http_max_backoff_ms),
     }
+}
 
-    // Group requests by user
-    let mut user_requests: std::collections::HashMap<i64, Vec<&gtm_models::TicketRequest>> =
-        std::collections::HashMap::new();
-    for r in &requests {
-        user_requests.entry(r.user_id).or_default().push(r);
+fn breaker_policy(config: &gtm_config::Config) -> gtm_scraper::breaker::BreakerPolicy {
+    gtm_scraper::breaker::BreakerPolicy {
+        failures: config.breaker_failures,
+        cooldown: std::time::Duration::from_secs(config.breaker_cooldown_secs),
+        ..Default::default()
     }
+}
 
-    let mut sections: Vec<UserAllocationSection> = user_requests
-        .into_iter()
-        .map(|(uid, reqs)| {
-            let user_name = user_map
-                .get(&uid)
-                .map(|u| u.name.clone())
-                .unwrap_or_default();
+/// Database pool size and SQLite pragmas.
+fn connect_options(config: &gtm_config::Config) -> gtm_db::ConnectOptions {
+    gtm_db::ConnectOptions {
+        max_connections: config.db_max_connections,
+        acquire_timeout: std::time::Duration::from_secs(config.db_acquire_timeout_secs),
+        sqlite_wal: config.sqlite_wal,
+        sqlite_busy_timeout: std::time::Duration::from_millis(config.sqlite_busy_timeout_ms),
+        statement_timeout: (config.db_statement_timeout_secs > 0)
+            .then(|| std::time::Duration::from_secs(config.db_statement_timeout_secs)),
+        slow_query: (config.db_slow_query_ms > 0)
+            .then(|| std::time::Duration::from_millis(config.db_slow_query_ms)),
+        log_queries: config.db_log_queries,
+    }
+}
 
-            let entries: Vec<UserAllocationEntry> = reqs
-                .iter()
-                .map(|r| {
-                    let game = game_map.get(&r.game_pk);
+/// Game types we hold seats for, from `ticketed_game_types` in config or,
+/// when that's unset, each type's default. Unknown codes are logged and
+/// skipped.
+fn ticketed_game_types(config: &gtm_config::Config) -> Vec<GameType> {
+    let Some(codes) = &config.ticketed_game_types else {
+        return GameType::ALL
+            .into_iter()
+            .filter(|t| t.ticketed_by_default())
+            .collect();
+    };
+    codes
+        .iter()
+        .filter_map(|code| {
+            code.parse()
+                .inspect_err(|e| warn!(error = %e, "Ignoring ticketed game type"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Schedule sections the scrape fetches, from `scrape_hydrate` in config.
+/// Unknown names are logged and skipped.
+fn scrape_hydration(config: &gtm_config::Config) -> Vec<Hydration> {
+    config
+        .scrape_hydrate
+        .iter()
+        .filter_map(|name| {
+            name.parse()
+                .inspect_err(|e| warn!(error = %e, "Ignoring scrape hydration"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Shared scrape logic: fetch schedule from MLB API, upsert games/promotions, generate tickets.
+/// Fetches `game_types`, or the ticketed types when `None`, with the
+/// `hydrate` sections, or the configured ones when `None`. Returns `None` if
+/// another process is already syncing the schedule. The drift report is
+/// empty unless `schema_drift` is on. A scrape that breaks the sync
+/// thresholds, unless `force`d, is only applied: games and promotions are
+/// upserted but no seats or requests move, admins are emailed about
+/// anomalies they haven't heard of, and the result carries the anomalies.
+async fn run_scrape(
+    pool: &AnyPool,
+    config: &gtm_config::Config,
+    season: u32,
+    game_types: Option<&[GameType]>,
+    hydrate: Option<&[Hydration]>,
+    force: bool,
+) -> anyhow::Result<
+    Option<(
+        gtm_db::schedule_sync::ScheduleSync,
+        gtm_scraper::DriftReport,
+    )>,
+> {
+    gtm_db::with_lock(pool, SCHEDULE_SYNC_LOCK, JOB_LOCK_TTL, || {
+        run_scrape_locked(pool, config, season, game_types, hydrate, force)
+    })
+    .await
+}
+
+async fn run_scrape_locked(
+    pool: &AnyPool,
+    config: &gtm_config::Config,
+    season: u32,
+    game_types: Option<&[GameType]>,
+    hydrate: Option<&[Hydration]>,
+    force: bool,
+) -> anyhow::Result<(
+    gtm_db::schedule_sync::ScheduleSync,
+    gtm_scraper::DriftReport,
+)> {
+    let ticketed = ticketed_game_types(config);
+    let game_types = game_types.unwrap_or(&ticketed);
+    let configured = scrape_hydration(config);
+    let hydrate = hydrate.unwrap_or(&configured);
+    let (data, drift) = if config.schema_drift {
+        gtm_scraper::fetch_schedule_with_drift(config.team_id, season, game_types, hydrate).await?
+    } else {
+        let data = gtm_scraper::fetch_schedule(config.team_id, season, game_types, hydrate).await?;
+        (data, gtm_scraper::DriftReport::default())
+    };
+    for line in drift.lines() {
+        warn!("MLB API schema drift: {line}");
+    }
+    let thresholds = gtm_db::schedule_sync::SyncThresholds {
+        min_games: config.sync_min_games,
+        promotion_drop_pct: config.sync_promotion_drop_pct,
+    };
+    let anomalies = if force {
+        Vec::new()
+    } else {
+        gtm_db::schedule_sync::check_scrape(
+            pool,
+            &data,
+            season,
+            game_types,
+            hydrate.contains(&Hydration::Promotions),
+            &thresholds,
+        )
+        .await?
+    };
+    if !anomalies.is_empty() {
+        for anomaly in &anomalies {
+            warn!("Suspect schedule scrape, applied without moving seats: {anomaly}");
+        }
+        match gtm_db::schedule_sync::unreported_anomalies(pool, &anomalies).await {
+            Ok(new) if new.is_empty() => info!("Suspect-scrape anomalies already reported"),
+            Ok(_) => send_suspect_scrape_alert(config, season, &anomalies).await,
+            Err(e) => warn!("Could not check reported anomalies: {e}"),
+        }
+        let mut sync =
+            gtm_db::schedule_sync::apply_schedule(pool, &data, config.team_id, &ticketed).await?;
+        sync.anomalies = anomalies;
+        return Ok((sync, drift));
+    }
+    let sync = gtm_db::schedule_sync::sync_schedule(pool, &data, config.team_id, &ticketed).await?;
+    let result = sync.upsert;
+    info!(
+        "{} games, {} promotions upserted, {} tickets generated, {} change(s)",
+        result.games,
+        result.promotions,
+        result.tickets,
+        sync.changes.len()
+    );
+    for change in &sync.changes {
+        info!("Schedule change: {change}");
+    }
+    if !sync.makeups.is_empty() {
+        info!(
+            "{} member(s) moved from postponed games to their makeups",
+            sync.makeups.len()
+        );
+        for m in sync.makeups.iter().filter(|m| m.problem.is_some()) {
+            warn!(user_id = %m.user_id, game_pk = %m.from, "Makeup move incomplete: {}", m.problem.as_deref().unwrap_or_default());
+        }
+        if let Err(e) = queue_makeup_emails(pool, &sync.makeups).await {
+            warn!("Could not queue makeup emails: {e}");
+        }
+    }
+    if !sync.carryovers.is_empty() {
+        info!(
+            "{} pending request(s) affected by schedule changes",
+            sync.carryovers.len()
+        );
+        if let Err(e) = queue_carryover_emails(pool, config, &sync.carryovers).await {
+            warn!("Could not queue schedule-change emails: {e}");
+        }
+    }
+    Ok((sync, drift))
+}
+
+/// Email admins that a schedule scrape looked suspect.
+async fn send_suspect_scrape_alert(config: &gtm_config::Config, season: u32, anomalies: &[String]) {
+    let (subject, body) = gtm_notify::templates::email_suspect_scrape(season, anomalies);
+    let notifier = build_notifier(config);
+    for to in &config.admin_emails {
+        if let Err(e) = notifier
+            .send(&gtm_notify::Notification::email(to, &subject, &body))
+            .await
+        {
+            warn!(to = %to, "Suspect-scrape alert failed: {e}");
+        }
+    }
+}
+
+async fn api_scrape_schedule(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Json(body): Json<ScrapeScheduleRequest>,
+) -> Result<Json<Envelope<ScrapeScheduleResponse>>, ApiError> {
+    let season = match body.season {
+        Some(season) => season,
+        None => Utc::now().with_timezone(&display_tz(&config)?).year() as u32,
+    };
+    let (sync, drift) = service::scrape(
+        pool,
+        config,
+        season,
+        body.game_types,
+        body.hydrate,
+        body.force,
+    )
+    .await?
+    .ok_or(ApiError::conflict("A schedule sync is already running"))?;
+    let mut warnings = gtm_db::Warnings::new();
+    for anomaly in &sync.anomalies {
+        warnings.push(format!(
+            "Scrape suspect, games updated but no seats or requests moved: {anomaly}"
+        ));
+    }
+    for m in &sync.makeups {
+        if let Some(problem) = &m.problem {
+            warnings.push(format!(
+                "User {} not fully moved from postponed game {} to {}: {problem}",
+                m.user_id, m.from, m.to
+            ));
+        }
+    }
+    if !sync.carryovers.is_empty() {
+        warnings.push(format!(
+            "{} pending request(s) affected by schedule changes",
+            sync.carryovers.len()
+        ));
+    }
+    Ok(Envelope::with_warnings(
+        ScrapeScheduleResponse {
+            games: sync.upsert.games,
+            promotions: sync.upsert.promotions,
+            tickets: sync.upsert.tickets as usize,
+            changes: sync.changes.iter().map(|c| c.to_string()).collect(),
+            drift: drift.lines(),
+        },
+        warnings,
+    ))
+}
+
+// --- Helper: response envelope ---
+
+/// Body of every POST/PATCH response: the result, plus warnings for anything
+/// the request only partly did (seats skipped, an email not queued).
+#[derive(Serialize)]
+struct Envelope<T> {
+    data: T,
+    warnings: gtm_db::Warnings,
+}
+
+impl<T> Envelope<T> {
+    fn ok(data: T) -> Json<Self> {
+        Self::with_warnings(data, gtm_db::Warnings::new())
+    }
+
+    fn with_warnings(data: T, warnings: gtm_db::Warnings) -> Json<Self> {
+        if !warnings.is_empty() {
+            info!(count = warnings.len(), "Responding with warnings");
+        }
+        Json(Envelope { data, warnings })
+    }
+}
+
+/// The request's `Idempotency-Key` header, if it sent one.
+fn idempotency_key(headers: &axum::http::HeaderMap) -> Result<Option<String>, ApiError> {
+    headers
+        .get("idempotency-key")
+        .map(|v| {
+            v.to_str()
+                .map(str::to_string)
+                .map_err(|_| ApiError::bad_request("Idempotency-Key must be ASCII"))
+        })
+        .transpose()
+}
+
+// --- Helper: API errors ---
+
+/// Body of every failed API call: `{ "code", "message", "details"? }`.
+/// `code` is stable and machine-readable, so the frontend can tell a
+/// validation error from a conflict without parsing `message`.
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    details: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "validation", message)
+    }
+
+    fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+
+    fn not_acceptable(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_ACCEPTABLE, "not_acceptable", message)
+    }
+
+    fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, "conflict", message)
+    }
+
+    fn precondition_required(message: impl Into<String>) -> Self {
+        Self::new(
+            StatusCode::PRECONDITION_REQUIRED,
+            "precondition_required",
+            message,
+        )
+    }
+
+    fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", message)
+    }
+
+    /// An upstream service (Google, Twilio) failed or refused.
+    fn upstream(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_GATEWAY, "upstream", message)
+    }
+
+    fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::SERVICE_UNAVAILABLE, "unavailable", message)
+    }
+
+    /// A `gtm_db` call that rejected its input: database errors keep their
+    /// usual mapping, anything else (a `bail!`) is the caller's fault.
+    fn invalid(e: impl Into<anyhow::Error>) -> Self {
+        let e = e.into();
+        if e.chain().any(|c| c.is::<sqlx::Error>()) {
+            Self::from(e)
+        } else {
+            Self::bad_request(e.to_string())
+        }
+    }
+}
+
+/// Database errors by kind: unique violations are conflicts, other
+/// constraint failures are 422s, a missing row is a 404; the rest are 500s.
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        use sqlx::error::ErrorKind;
+        let db_kind = e
+            .chain()
+            .filter_map(|c| c.downcast_ref::<sqlx::Error>())
+            .find_map(|db| match db {
+                sqlx::Error::RowNotFound => Some(None),
+                sqlx::Error::Database(db) => Some(Some(db.kind())),
+                _ => None,
+            });
+        match db_kind {
+            Some(None) => Self::not_found("Not found"),
+            _ if e.downcast_ref::<gtm_db::InvalidTransition>().is_some() => {
+                Self::new(StatusCode::CONFLICT, "invalid_transition", e.to_string())
+            }
+            _ if e.downcast_ref::<gtm_db::seasons::SeasonClosed>().is_some() => {
+                Self::new(StatusCode::CONFLICT, "season_closed", e.to_string())
+            }
+            _ if let Some(split) = e.downcast_ref::<gtm_db::seating::SplitParty>() => {
+                Self::new(StatusCode::CONFLICT, "split_party", e.to_string()).with_details(json!({
+                    "game_pk": split.game_pk,
+                    "user_id": split.user_id,
+                    "sections": split.sections,
+                    "together": split.together,
+                }))
+            }
+            _ if e.downcast_ref::<gtm_db::timing::QueryTimeout>().is_some() => Self::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "query_timeout",
+                e.to_string(),
+            ),
+            // The database's own text names tables and columns, so it goes
+            // to the log rather than the client
+            Some(Some(ErrorKind::UniqueViolation)) => {
+                info!("Unique violation: {e:#}");
+                Self::conflict("That already exists")
+            }
+            Some(Some(
+                ErrorKind::ForeignKeyViolation
+                | ErrorKind::NotNullViolation
+                | ErrorKind::CheckViolation,
+            )) => {
+                info!("Constraint violation: {e:#}");
+                Self::new(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "constraint_violation",
+                    "That refers to something missing or breaks a rule of the data",
+                )
+            }
+            _ => {
+                error!("Internal error: {e:#}");
+                Self::internal("Internal error")
+            }
+        }
+    }
+}
+
+impl axum::response::IntoResponse for ApiError {
+    fn into_response(self) -> axum::response::Response {
+        if self.status.is_server_error() {
+            warn!(code = self.code, "{}", self.message);
+        }
+        let mut body = json!({ "code": self.code, "message": self.message });
+        if let Some(details) = self.details {
+            body["details"] = details;
+        }
+        (self.status, Json(body)).into_response()
+    }
+}
+
+/// axum answers a body, path or query it can't parse with plain text;
+/// rewrap those rejections in the [`ApiError`] body like every other error.
+async fn rejections_as_api_errors(
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let resp = next.run(req).await;
+    let plain_text = resp
+        .headers()
+        .get(axum::http::header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"text/plain"));
+    if !resp.status().is_client_error() || !plain_text {
+        return resp;
+    }
+    let status = resp.status();
+    let message = axum::body::to_bytes(resp.into_body(), 64 * 1024)
+        .await
+        .map(|b| String::from_utf8_lossy(&b).into_owned())
+        .unwrap_or_default();
+    ApiError::new(status, "validation", message).into_response()
+}
+
+// --- Helper: resolve AuthUser → local User ---
+
+async fn resolve_user(auth_user: &AuthUser, pool: &AnyPool) -> Result<gtm_models::User, ApiError> {
+    let name = auth_user.name.as_deref().unwrap_or("Unknown");
+    let email = auth_user.email.as_deref().unwrap_or("unknown@example.com");
+    let user = gtm_db::upsert_user(pool, &auth_user.sub, email, name).await?;
+    if !auth_user.via_token {
+        gtm_db::api_tokens::record_role(pool, user.id, auth_user.is_admin()).await?;
+    }
+    Ok(user)
+}
+
+// --- Member: Ticket Requests ---
+
+#[derive(Serialize, Deserialize)]
+struct CreateRequestBody {
+    game_pk: GamePk,
+    /// Defaults to the member's `default_seats` preference.
+    #[serde(default)]
+    seats_requested: Option<i64>,
+    notes: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CreateRequestBatchBody {
+    requests: Vec<CreateRequestBody>,
+}
+
+/// A member's request with the latest comment on it.
+#[derive(Serialize)]
+struct MyRequest {
+    #[serde(flatten)]
+    request: gtm_models::TicketRequest,
+    latest_comment: Option<gtm_models::RequestComment>,
+}
+
+async fn api_my_requests_list(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<Vec<MyRequest>>, ApiError> {
+    let requests = gtm_db::list_requests_for_user(&pool, user.id, params.season.as_deref()).await?;
+    let ids: Vec<i64> = requests.iter().map(|r| r.id).collect();
+    let mut latest = gtm_db::request_comments::latest(&pool, &ids).await?;
+    Ok(Json(
+        requests
+            .into_iter()
+            .map(|request| MyRequest {
+                latest_comment: latest.remove(&request.id),
+                request,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Deserialize)]
+struct CommentBody {
+    body: String,
+}
+
+/// The member's own request, or 404 so others' requests stay hidden.
+async fn own_request(
+    pool: &AnyPool,
+    user_id: UserId,
+    request_id: i64,
+) -> Result<gtm_models::TicketRequest, ApiError> {
+    gtm_db::get_ticket_request(pool, request_id)
+        .await?
+        .filter(|r| r.user_id == user_id)
+        .ok_or(ApiError::not_found("Request not found"))
+}
+
+async fn api_my_request_comments(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(request_id): Path<i64>,
+) -> Result<Json<Vec<gtm_models::RequestComment>>, ApiError> {
+    own_request(&pool, user.id, request_id).await?;
+    Ok(Json(
+        gtm_db::request_comments::list(&pool, request_id).await?,
+    ))
+}
+
+async fn api_my_request_comment_add(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(request_id): Path<i64>,
+    Json(body): Json<CommentBody>,
+) -> Result<Json<Envelope<gtm_models::RequestComment>>, ApiError> {
+    own_request(&pool, user.id, request_id).await?;
+    let comment = gtm_db::request_comments::create(&pool, request_id, user.id, false, &body.body)
+        .await
+        .map_err(ApiError::invalid)?;
+    Ok(Envelope::ok(comment))
+}
+
+/// Takes an `Idempotency-Key` header: a retry with the same key gets the
+/// first call's result back.
+async fn api_my_requests_create(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    headers: axum::http::HeaderMap,
+    Json(body): Json<CreateRequestBatchBody>,
+) -> Result<Json<Envelope<Vec<gtm_models::TicketRequest>>>, ApiError> {
+    let key = idempotency_key(&headers)?;
+    let request = serde_json::to_string(&body).map_err(ApiError::invalid)?;
+    let results = service::idempotent(
+        pool.clone(),
+        user.id,
+        "POST /my/requests",
+        key,
+        request,
+        service::create_requests(pool, user.id, body.requests),
+    )
+    .await?;
+    Ok(Envelope::ok(results))
+}
+
+#[derive(Deserialize)]
+struct BulkRequestBody {
+    /// Which games, as for `GET /games`; only upcoming home games count.
+    #[serde(default)]
+    filter: gtm_db::GameFilter,
+    /// Defaults to the member's `default_seats` preference.
+    #[serde(default)]
+    seats_requested: Option<i64>,
+    notes: Option<String>,
+}
+
+/// A standing request: the same seats at every upcoming home game matching
+/// a filter, e.g. `{ "filter": { "weekend": true }, "seats_requested": 2 }`.
+async fn api_my_requests_bulk(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Json(body): Json<BulkRequestBody>,
+) -> Result<Json<Envelope<gtm_db::BulkRequests>>, ApiError> {
+    // The member's default is capped at each game's limit, as for single
+    // requests; a number they ask for is not
+    let (seats_requested, capped) = match body.seats_requested {
+        Some(n) => (n, false),
+        None => (
+            gtm_db::preferences::get(&pool, user.id)
+                .await?
+                .default_seats
+                .ok_or(ApiError::bad_request(
+                    "seats_requested is required (or set default_seats in /my/preferences)",
+                ))?,
+            true,
+        ),
+    };
+    if seats_requested < 1 {
+        return Err(ApiError::bad_request("seats_requested must be at least 1"));
+    }
+    let mut filter = body.filter;
+    filter.team_id = config.team_id;
+    filter
+        .game_types()
+        .iter()
+        .try_for_each(|code| code.parse::<GameType>().map(drop))
+        .map_err(ApiError::bad_request)?;
+    filter.days().map_err(ApiError::bad_request)?;
+    let today = Utc::now()
+        .with_timezone(&display_tz(&config)?)
+        .format("%Y-%m-%d")
+        .to_string();
+    let result = gtm_db::create_requests_for_games(
+        &pool,
+        user.id,
+        &filter,
+        seats_requested,
+        capped,
+        body.notes.as_deref(),
+        &today,
+    )
+    .await?;
+    info!(
+        user_id = %user.id,
+        created = result.created.len(),
+        skipped = result.skipped.len(),
+        "Bulk requests created"
+    );
+    Ok(Envelope::ok(result))
+}
+
+#[derive(Deserialize)]
+struct UpdateRequestBody {
+    seats_requested: i64,
+}
+
+async fn api_my_requests_update(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(request_id): Path<i64>,
+    Json(body): Json<UpdateRequestBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let request = gtm_db::list_requests_for_user(&pool, user.id, None)
+        .await?
+        .into_iter()
+        .find(|r| r.id == request_id)
+        .ok_or(ApiError::not_found("Request not found or not pending"))?;
+    let max = gtm_db::seat_limits::max_seats_for_game(&pool, request.game_pk).await?;
+    if body.seats_requested < 1 || body.seats_requested > max {
+        return Err(ApiError::bad_request(format!(
+            "seats_requested must be 1-{max}"
+        )));
+    }
+    let updated =
+        gtm_db::update_ticket_request(&pool, request_id, user.id, body.seats_requested).await?;
+    if updated {
+        Ok(Envelope::ok(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Request not found or not pending"))
+    }
+}
+
+async fn api_my_requests_withdraw(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(request_id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let withdrawn = gtm_db::withdraw_ticket_request(&pool, request_id, user.id).await?;
+    if withdrawn {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Request not found or not pending"))
+    }
+}
+
+// --- Member: My Games ---
+
+async fn api_my_games(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<Vec<gtm_models::GameTicketDetail>>, ApiError> {
+    gtm_db::list_tickets_for_user(&pool, user.id, params.season.as_deref())
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize)]
+struct CalendarQuery {
+    season: Option<String>,
+    /// 1-12; the current month (in the display time zone) if omitted.
+    month: Option<u32>,
+}
+
+/// The member's month-view calendar: every day of the month with its home
+/// games, their seat counts and the member's own standing.
+async fn api_availability_calendar(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<CalendarQuery>,
+) -> Result<Json<gtm_models::AvailabilityCalendar>, ApiError> {
+    let tz = display_tz(&config)?;
+    let month = params
+        .month
+        .unwrap_or_else(|| Utc::now().with_timezone(&tz).month());
+    if !(1..=12).contains(&month) {
+        return Err(ApiError::bad_request("month must be 1-12"));
+    }
+    let season = season_or_current(params.season, tz);
+    if season.parse::<i32>().is_err() {
+        return Err(ApiError::bad_request("season must be a year"));
+    }
+    gtm_db::calendar::month(&pool, config.team_id, user.id, &season, month)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn api_my_games_release(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    State(notifier): State<Arc<gtm_notify::Notifier>>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+    Path(game_pk): Path<GamePk>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let count = gtm_db::release_tickets_for_game(&pool, game_pk, user.id, Some(user.id)).await?;
+    if count > 0 {
+        spawn_calendar_sync(pool.clone(), gcal, vec![user.id]);
+        spawn_day_of_release_sms(
+            pool,
+            notifier,
+            display_tz(&config)?,
+            game_pk,
+            user.id,
+            count,
+        );
+    }
+    Ok(Envelope::ok(json!({ "status": "ok", "released": count })))
+}
+
+#[derive(Deserialize)]
+struct TransferTicketsBody {
+    to_user_id: UserId,
+    /// Seats to hand over; all of the member's seats for the game if omitted.
+    ticket_ids: Option<Vec<TicketId>>,
+}
+
+async fn api_my_games_transfer(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+    Path(game_pk): Path<GamePk>,
+    Json(body): Json<TransferTicketsBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    if body.to_user_id == user.id {
+        return Err(ApiError::bad_request("Can't transfer seats to yourself"));
+    }
+    let target = gtm_db::get_user(&pool, body.to_user_id)
+        .await?
+        .ok_or(ApiError::not_found("Member not found"))?;
+    let mut warnings = gtm_db::Warnings::new();
+    let moved = gtm_db::transfer_ticket(
+        &pool,
+        game_pk,
+        user.id,
+        target.id,
+        body.ticket_ids.as_deref(),
+        Some(user.id),
+        &mut warnings,
+    )
+    .await?;
+    if moved.is_empty() {
+        return Err(ApiError::not_found(
+            "You hold no matching seats for this game",
+        ));
+    }
+    spawn_calendar_sync(pool.clone(), gcal, vec![user.id, target.id]);
+    if let Err(e) = queue_transfer_email(&pool, &user.name, target.id, game_pk, &moved).await {
+        warn!(%game_pk, error = %e, "Could not queue transfer email");
+        warnings.push(format!(
+            "Could not email {} about the transfer",
+            target.name
+        ));
+    }
+    Ok(Envelope::with_warnings(
+        json!({ "status": "ok", "transferred": moved.len(), "to_user_id": target.id }),
+        warnings,
+    ))
+}
+
+/// If released tickets are for a game being played today, text every other
+/// member who has a verified phone and hasn't opted out of SMS or of
+/// day-of release notices. "Today" is in `tz`.
+fn spawn_day_of_release_sms(
+    pool: AnyPool,
+    notifier: Arc<gtm_notify::Notifier>,
+    tz: chrono_tz::Tz,
+    game_pk: GamePk,
+    released_by: UserId,
+    seats: u64,
+) {
+    tokio::spawn(async move {
+        let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+        let game = match gtm_db::get_game(&pool, game_pk).await {
+            Ok(Some(g)) if g.official_date == today => g,
+            Ok(_) => return,
+            Err(e) => {
+                warn!(%game_pk, error = %e, "day-of release: game lookup failed");
+                return;
+            }
+        };
+        let recipients = match gtm_db::list_sms_recipients(&pool).await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!(%game_pk, error = %e, "day-of release: recipient lookup failed");
+                return;
+            }
+        };
+        let locales = match gtm_db::list_user_locales(&pool).await {
+            Ok(l) => l,
+            Err(e) => {
+                warn!(%game_pk, error = %e, "day-of release: locale lookup failed");
+                Default::default()
+            }
+        };
+        let prefs = match gtm_db::preferences::list(&pool).await {
+            Ok(p) => p,
+            Err(e) => {
+                warn!(%game_pk, error = %e, "day-of release: preference lookup failed");
+                return;
+            }
+        };
+        let wants = |user_id| {
+            prefs
+                .get(&user_id)
+                .is_none_or(|p| p.wants(gtm_db::notifications::DAY_OF_RELEASE))
+        };
+        let label = gtm_notify::templates::game_label(&game.official_date, &game.away_team_name);
+        for r in recipients
+            .iter()
+            .filter(|r| r.user_id != released_by && wants(r.user_id))
+        {
+            let locale = Locale::for_member(locales.get(&r.user_id).map(String::as_str));
+            let body = gtm_notify::templates::sms_day_of_release(locale, &label, seats);
+            if let Err(e) = notifier
+                .send(&gtm_notify::Notification::sms(&r.phone, &body))
+                .await
+            {
+                warn!(user_id = %r.user_id, error = %e, "day-of release SMS failed");
+            }
+        }
+    });
+}
+
+#[derive(Deserialize)]
+struct AttendanceBody {
+    attended: bool,
+}
+
+async fn api_my_games_attendance(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+    Json(body): Json<AttendanceBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let game = gtm_db::get_game(&pool, game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+    let recorded = gtm_db::attendance::upsert_own(&pool, &game, user.id, body.attended)
+        .await
+        .map_err(ApiError::invalid)?;
+    if !recorded {
+        return Err(ApiError::not_found("You hold no seats for this game"));
+    }
+    Ok(Envelope::ok(
+        json!({ "status": "ok", "attended": body.attended }),
+    ))
+}
+
+// --- Member: My Game Tags ---
+
+async fn api_my_game_tags(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::GameTag>>, ApiError> {
+    gtm_db::list_game_tags_for_user(&pool, user.id)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize)]
+struct GameTagBody {
+    shortlist: bool,
+    cant_go: bool,
+}
+
+async fn api_my_game_tags_put(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+    Json(body): Json<GameTagBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    gtm_db::upsert_game_tag(&pool, user.id, game_pk, body.shortlist, body.cant_go).await?;
+    Ok(Envelope::ok(json!({ "status": "ok" })))
+}
+
+// --- Member: Promotion interest ---
+
+#[derive(Deserialize)]
+struct PromotionInterestQuery {
+    /// Needed only when the promotion runs at more than one game.
+    game_pk: Option<GamePk>,
+}
+
+/// The game a promotion interest refers to: `game_pk` if given (and the
+/// promotion runs then), otherwise the promotion's only game.
+async fn promotion_game(
+    pool: &AnyPool,
+    offer_id: i64,
+    game_pk: Option<GamePk>,
+) -> Result<GamePk, ApiError> {
+    let games = gtm_db::promotion_game_pks(pool, offer_id).await?;
+    match (game_pk, games.as_slice()) {
+        (_, []) => Err(ApiError::not_found("Promotion not found")),
+        (Some(g), _) if games.contains(&g) => Ok(g),
+        (Some(_), _) => Err(ApiError::not_found("Promotion does not run at that game")),
+        (None, [g]) => Ok(*g),
+        (None, _) => Err(ApiError::bad_request(
+            "Promotion runs at several games; pass game_pk",
+        )),
+    }
+}
+
+async fn api_promotion_interest_add(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(offer_id): Path<i64>,
+    Query(params): Query<PromotionInterestQuery>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let game_pk = promotion_game(&pool, offer_id, params.game_pk).await?;
+    let added = gtm_db::add_promotion_interest(&pool, user.id, offer_id, game_pk).await?;
+    Ok(Envelope::ok(
+        json!({ "status": "ok", "offer_id": offer_id, "game_pk": game_pk, "added": added }),
+    ))
+}
+
+async fn api_promotion_interest_remove(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(offer_id): Path<i64>,
+    Query(params): Query<PromotionInterestQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let game_pk = promotion_game(&pool, offer_id, params.game_pk).await?;
+    let removed = gtm_db::remove_promotion_interest(&pool, user.id, offer_id, game_pk).await?;
+    if removed {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("No interest to remove"))
+    }
+}
+
+/// The member's flagged promotions; `suggest_request` marks games whose seats
+/// are open but that the member hasn't requested yet.
+async fn api_my_promotion_interests(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+) -> Result<Json<Vec<gtm_models::PromotionInterest>>, ApiError> {
+    let tz = display_tz(&config)?;
+    let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+    gtm_db::list_promotion_interests_for_user(&pool, user.id, &today)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+// --- Member: Availability polls ---
+
+/// Open polls on upcoming games, with the member's answers.
+async fn api_my_polls(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+) -> Result<Json<Vec<gtm_models::MemberPoll>>, ApiError> {
+    let tz = display_tz(&config)?;
+    let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+    gtm_db::polls::open_for_user(&pool, user.id, &today)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize)]
+struct PollAnswerBody {
+    answer: String,
+    /// Seats wanted; ignored for "no".
+    #[serde(default)]
+    seats: i64,
+}
+
+async fn api_poll_respond(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(poll_id): Path<i64>,
+    Json(body): Json<PollAnswerBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    if !gtm_db::polls::ANSWERS.contains(&body.answer.as_str()) {
+        return Err(ApiError::bad_request("answer must be yes, no or maybe"));
+    }
+    let seats = if body.answer == "no" { 0 } else { body.seats };
+    if body.answer != "no" && !(1..=4).contains(&seats) {
+        return Err(ApiError::bad_request("seats must be 1-4"));
+    }
+    let recorded = gtm_db::polls::respond(&pool, poll_id, user.id, &body.answer, seats).await?;
+    if !recorded {
+        return Err(ApiError::conflict("Poll not found or already closed"));
+    }
+    Ok(Envelope::ok(
+        json!({ "status": "ok", "poll_id": poll_id, "answer": body.answer, "seats": seats }),
+    ))
+}
+
+// --- Member: Blackout dates ---
+
+async fn api_my_blackouts(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<String>>, ApiError> {
+    gtm_db::list_blackouts(&pool, user.id)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+fn parse_blackout_date(date: &str) -> Result<(), ApiError> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|_| ())
+        .map_err(|_| ApiError::bad_request("Date must be YYYY-MM-DD"))
+}
+
+async fn api_add_blackout(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(date): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    parse_blackout_date(&date)?;
+    let added = gtm_db::add_blackout(&pool, user.id, &date).await?;
+    Ok(Json(json!({ "status": "ok", "added": added })))
+}
+
+async fn api_remove_blackout(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(date): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let removed = gtm_db::remove_blackout(&pool, user.id, &date).await?;
+    if removed {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("No blackout on that date"))
+    }
+}
+
+// --- Member: Saved views ---
+
+async fn api_my_saved_views(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_db::saved_views::SavedView>>, ApiError> {
+    Ok(Json(gtm_db::saved_views::list(&pool, user.id).await?))
+}
+
+#[derive(Deserialize)]
+struct SaveViewRequest {
+    name: String,
+    filter: gtm_db::GameFilter,
+}
+
+async fn api_save_view(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Json(body): Json<SaveViewRequest>,
+) -> Result<Json<Envelope<gtm_db::saved_views::SavedView>>, ApiError> {
+    let view = gtm_db::saved_views::save(&pool, user.id, &body.name, &body.filter)
+        .await
+        .map_err(ApiError::invalid)?;
+    Ok(Envelope::ok(view))
+}
+
+async fn api_delete_view(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if gtm_db::saved_views::delete(&pool, user.id, id).await? {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Saved view not found"))
+    }
+}
+
+// --- Member: Phone (SMS) ---
+
+/// How long an SMS verification code stays valid.
+const PHONE_CODE_TTL_MINUTES: i64 = 10;
+
+fn rfc3339_utc(dt: chrono::DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+async fn api_my_phone(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<Option<gtm_models::UserPhone>>, ApiError> {
+    gtm_db::get_user_phone(&pool, user.id)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize)]
+struct SetPhoneBody {
+    phone: String,
+}
+
+/// Set a phone number and text it a verification code.
+async fn api_my_phone_put(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(notifier): State<Arc<gtm_notify::Notifier>>,
+    Json(body): Json<SetPhoneBody>,
+) -> Result<Json<gtm_models::UserPhone>, ApiError> {
+    use rand::Rng;
+
+    let phone: String = body
+        .phone
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.'))
+        .collect();
+    if !gtm_notify::is_valid_phone(&phone) {
+        return Err(ApiError::bad_request(
+            "Phone must be in international format, e.g. +14155550123",
+        ));
+    }
+    let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+    let expires_at = rfc3339_utc(Utc::now() + chrono::Duration::minutes(PHONE_CODE_TTL_MINUTES));
+    let result = gtm_db::set_user_phone(&pool, user.id, &phone, &code, &expires_at).await?;
+    notifier
+        .send(&gtm_notify::Notification::sms(
+            &phone,
+            &gtm_notify::templates::sms_verification_code(&code),
+        ))
+        .await
+        .map_err(|e| {
+            warn!(user_id = %user.id, error = %e, "verification SMS failed");
+            ApiError::upstream("Could not send verification SMS")
+        })?;
+    info!(user_id = %user.id, "Phone set, verification code sent");
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+struct VerifyPhoneBody {
+    code: String,
+}
+
+async fn api_my_phone_verify(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Json(body): Json<VerifyPhoneBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let now = rfc3339_utc(Utc::now());
+    let verified = gtm_db::verify_user_phone(&pool, user.id, body.code.trim(), &now).await?;
+    if verified {
+        Ok(Envelope::ok(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::bad_request(
+            "Invalid or expired verification code",
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+struct PhonePrefsBody {
+    sms_opt_in: bool,
+}
+
+async fn api_my_phone_patch(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Json(body): Json<PhonePrefsBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let updated = gtm_db::set_sms_opt_in(&pool, user.id, body.sms_opt_in).await?;
+    if updated {
+        Ok(Envelope::ok(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("No phone on file"))
+    }
+}
+
+async fn api_my_phone_delete(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    gtm_db::delete_user_phone(&pool, user.id).await?;
+    Ok(Json(json!({ "status": "ok" })))
+}
+
+// --- Member: notification language ---
+
+#[derive(Deserialize)]
+struct LocaleBody {
+    /// A language tag like `es` or `es-MX`; null for the default, English.
+    locale: Option<String>,
+}
+
+/// Choose the language allocation and reminder notifications come in.
+async fn api_my_locale_put(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Json(body): Json<LocaleBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let locale = match body.locale.as_deref().filter(|l| !l.trim().is_empty()) {
+        Some(tag) => Some(Locale::parse(tag).ok_or_else(|| {
+            let codes: Vec<&str> = Locale::ALL.iter().map(|l| l.code()).collect();
+            ApiError::bad_request(format!(
+                "Unsupported locale `{tag}`; use one of {}",
+                codes.join(", ")
+            ))
+        })?),
+        None => None,
+    };
+    gtm_db::update_user_locale(&pool, user.id, locale.map(Locale::code)).await?;
+    Ok(Envelope::ok(json!({ "status": "ok" })))
+}
+
+// --- Member: preferences ---
+
+/// Deserialize a field that may be left out, `null` or set, as `None`,
+/// `Some(None)` or `Some(Some(_))` respectively.
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+struct PreferencesBody {
+    #[serde(default, deserialize_with = "present")]
+    default_seats: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "present")]
+    contact_email: Option<Option<String>>,
+    /// Notification kinds to turn on (`true`) or off (`false`).
+    #[serde(default)]
+    notifications: std::collections::BTreeMap<String, bool>,
+}
+
+async fn api_my_preferences(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<gtm_models::UserPreferences>, ApiError> {
+    Ok(Json(gtm_db::preferences::get(&pool, user.id).await?))
+}
+
+/// Change some preferences, leaving out the rest; `null` clears a setting,
+/// e.g. `{ "default_seats": 2, "notifications": { "game_packet": false } }`.
+/// A new `contact_email` is mailed a confirmation link and only used once
+/// it's followed.
+async fn api_my_preferences_patch(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    State(notifier): State<Arc<gtm_notify::Notifier>>,
+    Json(body): Json<PreferencesBody>,
+) -> Result<Json<Envelope<gtm_models::UserPreferences>>, ApiError> {
+    use rand::Rng;
+
+    let new_email = matches!(body.contact_email, Some(Some(_)));
+    let token = format!("{:032x}", rand::thread_rng().r#gen::<u128>());
+    let changes = gtm_db::preferences::PreferencesUpdate {
+        default_seats: body.default_seats,
+        contact_email: body.contact_email,
+        confirm_token: new_email.then(|| token.clone()),
+        notifications: body.notifications,
+    };
+    let prefs = gtm_db::preferences::update(&pool, user.id, &changes)
+        .await
+        .map_err(ApiError::invalid)?;
+    let mut warnings = gtm_db::Warnings::new();
+    if new_email && let Some(to) = &prefs.pending_contact_email {
+        let url = format!("{}/api/contact-email/{token}", config.public_url);
+        let (subject, body) = gtm_notify::templates::email_confirm_contact_email(&url);
+        match notifier
+            .send(&gtm_notify::Notification::email(to, &subject, &body))
+            .await
+        {
+            Ok(()) => info!(user_id = %user.id, "Contact email confirmation sent"),
+            Err(e) => {
+                warn!(user_id = %user.id, "Contact email confirmation failed: {e}");
+                warnings
+                    .push("Could not send the confirmation email; save the address again to retry");
+            }
+        }
+    }
+    Ok(Envelope::with_warnings(prefs, warnings))
+}
+
+/// The page a contact email confirmation link opens: a button that posts
+/// back, so a mail scanner following the link doesn't confirm it.
+async fn api_contact_email_confirm(
+    State(pool): State<AnyPool>,
+    Path(token): Path<String>,
+) -> Result<axum::response::Html<String>, ApiError> {
+    gtm_db::preferences::pending_contact_email(&pool, &token)
+        .await?
+        .ok_or(ApiError::not_found("Link not found or already used"))?;
+    Ok(axum::response::Html(
+        "<p>Send your GTM email to this address?</p>\
+         <form method=\"post\"><button type=\"submit\">Use this address</button></form>"
+            .to_string(),
+    ))
+}
+
+async fn api_contact_email_respond(
+    State(pool): State<AnyPool>,
+    Path(token): Path<String>,
+) -> Result<axum::response::Html<String>, ApiError> {
+    let (user_id, _) = gtm_db::preferences::confirm_contact_email(&pool, &token)
+        .await?
+        .ok_or(ApiError::not_found("Link not found or already used"))?;
+    info!(%user_id, "Contact email confirmed");
+    Ok(axum::response::Html(
+        "<p>Done — your GTM email will go to this address.</p>".to_string(),
+    ))
+}
+
+// --- Member: API tokens ---
+
+#[derive(Deserialize)]
+struct MintTokenRequest {
+    name: String,
+    /// Give the token admin access; only admins may.
+    #[serde(default)]
+    admin: bool,
+    /// Days until the token expires; defaults to the longest allowed.
+    expires_in_days: Option<i64>,
+}
+
+/// Longest a member token may last, in days.
+const TOKEN_MAX_DAYS: i64 = 90;
+/// Longest an admin token may last, in days.
+const ADMIN_TOKEN_MAX_DAYS: i64 = 30;
+
+/// A newly minted token: the only time its value is shown.
+#[derive(Serialize)]
+struct MintedToken {
+    token: String,
+    #[serde(flatten)]
+    info: gtm_models::ApiToken,
+}
+
+async fn api_my_tokens(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::ApiToken>>, ApiError> {
+    Ok(Json(
+        gtm_db::api_tokens::list_for_user(&pool, user.id).await?,
+    ))
+}
+
+async fn api_my_token_mint(
+    auth_user: AuthUser,
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Json(body): Json<MintTokenRequest>,
+) -> Result<Json<Envelope<MintedToken>>, ApiError> {
+    use rand::Rng;
+
+    let (scope, max_days) = if body.admin {
+        if !auth_user.is_admin() {
+            return Err(ApiError::forbidden("Only admins can mint admin tokens"));
+        }
+        (gtm_db::api_tokens::ADMIN_SCOPE, ADMIN_TOKEN_MAX_DAYS)
+    } else {
+        (gtm_db::api_tokens::MEMBER_SCOPE, TOKEN_MAX_DAYS)
+    };
+    let days = body.expires_in_days.unwrap_or(max_days);
+    if !(1..=max_days).contains(&days) {
+        return Err(ApiError::bad_request(format!(
+            "expires_in_days must be between 1 and {max_days}"
+        )));
+    }
+    let expires_at = rfc3339_utc(Utc::now() + chrono::Duration::days(days));
+    let token = format!("gtm_{:032x}", rand::thread_rng().r#gen::<u128>());
+    let info = gtm_db::api_tokens::create(&pool, user.id, &body.name, scope, &token, &expires_at)
+        .await
+        .map_err(ApiError::invalid)?;
+    info!(user_id = %user.id, token_id = info.id, %scope, "API token minted");
+    Ok(Envelope::ok(MintedToken { token, info }))
+}
+
+async fn api_my_token_revoke(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if gtm_db::api_tokens::revoke(&pool, id, user.id).await? {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Token not found"))
+    }
+}
+
+// --- Member: Google Calendar ---
+
+const OAUTH_STATE_TTL_MINUTES: i64 = 15;
+const GAME_DURATION_HOURS: i64 = 3;
+
+async fn api_my_google(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let account = gtm_db::get_google_account(&pool, user.id).await?;
+    Ok(Json(json!({
+        "enabled": gcal.is_enabled(),
+        "connected": account.is_some(),
+    })))
+}
+
+/// Start the OAuth flow: returns the Google consent URL to redirect to.
+async fn api_my_google_connect(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    use rand::Rng;
+
+    if !gcal.is_enabled() {
+        return Err(ApiError::unavailable(
+            "Google Calendar sync is not configured",
+        ));
+    }
+    let state = format!("{:032x}", rand::thread_rng().r#gen::<u128>());
+    let expires_at = rfc3339_utc(Utc::now() + chrono::Duration::minutes(OAUTH_STATE_TTL_MINUTES));
+    gtm_db::create_oauth_state(&pool, &state, user.id, &expires_at).await?;
+    let url = gcal.authorize_url(&state)?;
+    Ok(Envelope::ok(json!({ "url": url })))
+}
+
+#[derive(Deserialize)]
+struct GoogleCallbackQuery {
+    code: Option<String>,
+    state: String,
+    error: Option<String>,
+}
+
+/// OAuth redirect target. Google sends the browser here without our JWT, so
+/// the member is identified by the one-time `state`.
+async fn api_google_callback(
+    State(pool): State<AnyPool>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+    Query(params): Query<GoogleCallbackQuery>,
+) -> Result<axum::response::Redirect, ApiError> {
+    let linked = service::link_google(pool, gcal, params.state, params.code, params.error).await?;
+    Ok(axum::response::Redirect::to(if linked {
+        "/?google=connected"
+    } else {
+        "/?google=declined"
+    }))
+}
+
+/// Unlink Google: remove the events GTM created, then forget the tokens.
+async fn api_my_google_delete(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    service::unlink_google(pool, gcal, user.id).await?;
+    Ok(Json(json!({ "status": "ok" })))
+}
+
+/// A valid access token for the account, refreshing it if it's about to expire.
+async fn google_access_token(
+    pool: &AnyPool,
+    gcal: &gtm_gcal::GoogleCalendar,
+    account: &gtm_models::GoogleAccount,
+) -> anyhow::Result<String> {
+    let fresh = account
+        .access_expires_at
+        .as_deref()
+        .and_then(gtm_db::parse_timestamp)
+        .is_some_and(|exp| exp > Utc::now() + chrono::Duration::seconds(60));
+    if let (true, Some(token)) = (fresh, &account.access_token) {
+        return Ok(token.clone());
+    }
+    let tokens = gcal.refresh(&account.refresh_token).await?;
+    let expires_at = rfc3339_utc(Utc::now() + chrono::Duration::seconds(tokens.expires_in));
+    gtm_db::update_google_access_token(pool, account.user_id, &tokens.access_token, &expires_at)
+        .await?;
+    Ok(tokens.access_token)
+}
+
+fn calendar_event_for(
+    game: &gtm_models::Game,
+    tickets: &[gtm_models::GameTicketDetail],
+    tz: chrono_tz::Tz,
+) -> gtm_gcal::EventSpec {
+    let start = gtm_models::parse_game_time(&game.game_date)
+        .unwrap_or_else(Utc::now)
+        .with_timezone(&tz);
+    let end = start + chrono::Duration::hours(GAME_DURATION_HOURS);
+    let seats = tickets
+        .iter()
+        .map(|t| format!("Section {} Row {} Seat {}", t.section, t.row, t.seat))
+        .collect::<Vec<_>>()
+        .join("\n");
+    gtm_gcal::EventSpec {
+        summary: format!("{} vs {}", game.home_team_name, game.away_team_name),
+        location: game.venue_name.clone(),
+        description: format!("Your seats (via GTM):\n{seats}"),
+        start: start.to_rfc3339(),
+        end: end.to_rfc3339(),
+        time_zone: tz.name().to_string(),
+    }
+}
+
+/// Bring a member's calendar in line with their assigned tickets: create
+/// events for new games, update ones whose details changed, and delete ones
+/// for games they no longer hold seats for.
+async fn sync_google_calendar(
+    pool: &AnyPool,
+    gcal: &gtm_gcal::GoogleCalendar,
+    user_id: UserId,
+) -> anyhow::Result<()> {
+    let Some(account) = gtm_db::get_google_account(pool, user_id).await? else {
+        return Ok(());
+    };
+    let token = google_access_token(pool, gcal, &account).await?;
+    // The calendar's zone is `display_timezone`, set when it's configured
+    let name = gcal
+        .time_zone()
+        .ok_or_else(|| anyhow::anyhow!("Google Calendar isn't configured"))?;
+    let tz: chrono_tz::Tz = name
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid calendar time zone {name:?}"))?;
+
+    let mut wanted: std::collections::BTreeMap<GamePk, Vec<gtm_models::GameTicketDetail>> =
+        std::collections::BTreeMap::new();
+    for t in gtm_db::list_tickets_for_user(pool, user_id, None).await? {
+        wanted.entry(t.game_pk).or_default().push(t);
+    }
+    let existing: std::collections::HashMap<GamePk, gtm_models::CalendarEventSync> =
+        gtm_db::list_calendar_events(pool, user_id)
+            .await?
+            .into_iter()
+            .map(|e| (e.game_pk, e))
+            .collect();
+
+    for (game_pk, tickets) in &wanted {
+        let Some(game) = gtm_db::get_game(pool, *game_pk).await? else {
+            continue;
+        };
+        let event = calendar_event_for(&game, tickets, tz);
+        let hash = event.content_hash();
+        match existing.get(game_pk) {
+            Some(synced) if synced.content_hash == hash => {}
+            Some(synced) => {
+                gcal.update_event(&token, &account.calendar_id, &synced.event_id, &event)
+                    .await?;
+                gtm_db::upsert_calendar_event(pool, user_id, *game_pk, &synced.event_id, &hash)
+                    .await?;
+            }
+            None => {
+                let event_id = gcal
+                    .insert_event(&token, &account.calendar_id, &event)
+                    .await?;
+                gtm_db::upsert_calendar_event(pool, user_id, *game_pk, &event_id, &hash).await?;
+            }
+        }
+    }
+    for (game_pk, synced) in &existing {
+        if !wanted.contains_key(game_pk) {
+            gcal.delete_event(&token, &account.calendar_id, &synced.event_id)
+                .await?;
+            gtm_db::delete_calendar_event(pool, user_id, *game_pk).await?;
+        }
+    }
+    Ok(())
+}
+
+/// [`sync_google_calendar`] under the member's calendar lock. If another
+/// sync holds it, wait for that one to finish and sync again, since it may
+/// have read the member's seats before the change this sync is for.
+async fn sync_google_calendar_locked(
+    pool: &AnyPool,
+    gcal: &gtm_gcal::GoogleCalendar,
+    user_id: UserId,
+) -> anyhow::Result<()> {
+    let name = format!("{CALENDAR_SYNC_LOCK}:{user_id}");
+    for _ in 0..CALENDAR_SYNC_ATTEMPTS {
+        let run = gtm_db::with_lock(pool, &name, JOB_LOCK_TTL, || {
+            sync_google_calendar(pool, gcal, user_id)
+        })
+        .await?;
+        if run.is_some() {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    anyhow::bail!("Another calendar sync is still running")
+}
+
+/// How many times [`sync_google_calendar_locked`] tries for the lock, a
+/// second apart.
+const CALENDAR_SYNC_ATTEMPTS: u32 = 60;
+
+async fn remove_calendar_events(
+    pool: &AnyPool,
+    gcal: &gtm_gcal::GoogleCalendar,
+    user_id: UserId,
+) -> anyhow::Result<()> {
+    let Some(account) = gtm_db::get_google_account(pool, user_id).await? else {
+        return Ok(());
+    };
+    let token = google_access_token(pool, gcal, &account).await?;
+    for synced in gtm_db::list_calendar_events(pool, user_id).await? {
+        gcal.delete_event(&token, &account.calendar_id, &synced.event_id)
+            .await?;
+        gtm_db::delete_calendar_event(pool, user_id, synced.game_pk).await?;
+    }
+    Ok(())
+}
+
+/// Calendar syncs started by [`spawn_calendar_sync`] that may still be
+/// running, so a CLI command can wait for them before it exits.
+static CALENDAR_SYNCS: std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>> =
+    std::sync::Mutex::new(Vec::new());
+
+/// Re-sync the calendars of members whose seats just changed, in the
+/// background so the triggering request isn't held up by Google.
+fn spawn_calendar_sync(
+    pool: AnyPool,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    mut user_ids: Vec<UserId>,
+) {
+    if !gcal.is_enabled() || user_ids.is_empty() {
+        return;
+    }
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    let handle = tokio::spawn(async move {
+        for user_id in user_ids {
+            if let Err(e) = sync_google_calendar_locked(&pool, &gcal, user_id).await {
+                warn!(%user_id, "Google Calendar sync failed: {e}");
+            }
+        }
+    });
+    let mut syncs = CALENDAR_SYNCS.lock().unwrap_or_else(|e| e.into_inner());
+    syncs.retain(|h| !h.is_finished());
+    syncs.push(handle);
+}
+
+/// Wait for the calendar syncs still running. The runtime drops unfinished
+/// tasks when `main` returns, so a CLI command calls this before exiting.
+async fn finish_calendar_syncs() {
+    let syncs = std::mem::take(&mut *CALENDAR_SYNCS.lock().unwrap_or_else(|e| e.into_inner()));
+    for handle in syncs {
+        if let Err(e) = handle.await {
+            warn!("Google Calendar sync task failed: {e}");
+        }
+    }
+}
+
+// --- Allocation emails ---
+
+/// How often the background task drains the notification queue.
+const NOTIFICATION_POLL_SECS: u64 = 30;
+/// Most notifications delivered per pass.
+const NOTIFICATION_BATCH: i64 = 50;
+
+fn seat_label(t: &gtm_models::GameTicketDetail) -> String {
+    format!("Section {} Row {} Seat {}", t.section, t.row, t.seat)
+}
+
+async fn queue_email(
+    pool: &AnyPool,
+    user_id: UserId,
+    game_pk: GamePk,
+    kind: &str,
+    (subject, body): (String, String),
+) -> anyhow::Result<()> {
+    let Some(user) = gtm_db::get_user(pool, user_id).await? else {
+        return Ok(());
+    };
+    let Some(recipient) = gtm_db::preferences::email_for(pool, &user, kind).await? else {
+        return Ok(());
+    };
+    gtm_db::notifications::enqueue(
+        pool,
+        &gtm_db::notifications::NewNotification {
+            user_id: Some(user_id),
+            game_pk: Some(game_pk),
+            kind,
+            channel: gtm_db::notifications::EMAIL,
+            recipient: &recipient,
+            subject: &subject,
+            body: &body,
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+/// Queue emails after seats for a game are handed out: each assignee gets the
+/// seats they now hold, and once nothing is left, members whose requests are
+/// still pending hear the game is fully allocated (once per game).
+async fn queue_allocation_emails(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    assignees: &[UserId],
+) -> anyhow::Result<()> {
+    let Some(game) = gtm_db::get_game(pool, game_pk).await? else {
+        return Ok(());
+    };
+    let label = gtm_notify::templates::game_label(&game.official_date, &game.away_team_name);
+    let tickets = gtm_db::list_tickets_for_game(pool, game_pk).await?;
+    let locales = gtm_db::list_user_locales(pool).await?;
+    let locale = |user_id| Locale::for_member(locales.get(&user_id).map(String::as_str));
+
+    let mut notified = std::collections::HashSet::new();
+    for &user_id in assignees {
+        if !notified.insert(user_id) {
+            continue;
+        }
+        let seats: Vec<String> = tickets
+            .iter()
+            .filter(|t| t.assigned_to == Some(user_id))
+            .map(seat_label)
+            .collect();
+        if seats.is_empty() {
+            continue;
+        }
+        let email = gtm_notify::templates::email_tickets_assigned(locale(user_id), &label, &seats);
+        queue_email(
+            pool,
+            user_id,
+            game_pk,
+            gtm_db::notifications::TICKETS_ASSIGNED,
+            email,
+        )
+        .await?;
+    }
+
+    if tickets.is_empty() || tickets.iter().any(|t| t.status == "available") {
+        return Ok(());
+    }
+    for r in gtm_db::list_requests_for_game(pool, game_pk).await? {
+        if r.status != "pending"
+            || gtm_db::notifications::already_queued(
+                pool,
+                r.user_id,
+                game_pk,
+                gtm_db::notifications::GAME_FULLY_ALLOCATED,
+            )
+            .await?
+        {
+            continue;
+        }
+        let email = gtm_notify::templates::email_game_fully_allocated(locale(r.user_id), &label);
+        queue_email(
+            pool,
+            r.user_id,
+            game_pk,
+            gtm_db::notifications::GAME_FULLY_ALLOCATED,
+            email,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Queue the "your seats were reassigned" email for a revoked ticket.
+async fn queue_revocation_email(
+    pool: &AnyPool,
+    user_id: UserId,
+    ticket: &gtm_models::GameTicketDetail,
+) -> anyhow::Result<()> {
+    let Some(game) = gtm_db::get_game(pool, ticket.game_pk).await? else {
+        return Ok(());
+    };
+    let locale = Locale::for_member(gtm_db::get_user_locale(pool, user_id).await?.as_deref());
+    let label = gtm_notify::templates::game_label(&game.official_date, &game.away_team_name);
+    let email = gtm_notify::templates::email_tickets_revoked(locale, &label, &[seat_label(ticket)]);
+    queue_email(
+        pool,
+        user_id,
+        ticket.game_pk,
+        gtm_db::notifications::TICKETS_REVOKED,
+        email,
+    )
+    .await
+}
+
+/// Tell members what a schedule change did to their pending requests.
+async fn queue_carryover_emails(
+    pool: &AnyPool,
+    config: &gtm_config::Config,
+    carryovers: &[gtm_db::schedule_sync::RequestCarryover],
+) -> anyhow::Result<()> {
+    use gtm_db::schedule_sync::CarryoverOutcome;
+    use gtm_notify::templates;
+
+    let tz = display_tz(config)?;
+    for c in carryovers {
+        let Some(game) = gtm_db::get_game(pool, c.game_pk).await? else {
+            continue;
+        };
+        let label = templates::game_label(&game.official_date, &game.away_team_name);
+        let (kind, email) = match &c.outcome {
+            CarryoverOutcome::Moved { from } => {
+                let previous = gtm_db::parse_timestamp(from)
+                    .map(|t| t.with_timezone(&tz).format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| from.clone());
+                (
+                    gtm_db::notifications::REQUEST_MOVED,
+                    templates::email_request_moved(
+                        &label,
+                        &templates::game_label(&previous, &game.away_team_name),
+                    ),
+                )
+            }
+            CarryoverOutcome::Postponed => (
+                gtm_db::notifications::REQUEST_POSTPONED,
+                templates::email_request_postponed(&label),
+            ),
+            CarryoverOutcome::Flagged { reason } => (
+                gtm_db::notifications::REQUEST_FLAGGED,
+                templates::email_request_flagged(&label, reason),
+            ),
+        };
+        queue_email(pool, c.user_id, c.game_pk, kind, email).await?;
+    }
+    Ok(())
+}
+
+/// Tell members their seats and requests moved to a postponed game's makeup.
+async fn queue_makeup_emails(
+    pool: &AnyPool,
+    makeups: &[gtm_db::postponements::MakeupMove],
+) -> anyhow::Result<()> {
+    use gtm_notify::templates;
+
+    for m in makeups {
+        let (Some(from), Some(to)) = (
+            gtm_db::get_game(pool, m.from).await?,
+            gtm_db::get_game(pool, m.to).await?,
+        ) else {
+            continue;
+        };
+        let email = templates::email_game_made_up(
+            &templates::game_label(&to.official_date, &to.away_team_name),
+            &templates::game_label(&from.official_date, &from.away_team_name),
+            m.seats,
+            m.request_id.is_some(),
+            m.problem.as_deref(),
+        );
+        queue_email(
+            pool,
+            m.user_id,
+            m.to,
+            gtm_db::notifications::GAME_MADE_UP,
+            email,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Tell the recipient of a transfer which seats they now hold.
+async fn queue_transfer_email(
+    pool: &AnyPool,
+    from_name: &str,
+    to_user_id: UserId,
+    game_pk: GamePk,
+    ticket_ids: &[TicketId],
+) -> anyhow::Result<()> {
+    let Some(game) = gtm_db::get_game(pool, game_pk).await? else {
+        return Ok(());
+    };
+    let mut seats = Vec::new();
+    for &id in ticket_ids {
+        if let Some(t) = gtm_db::get_ticket(pool, id).await? {
+            seats.push(seat_label(&t));
+        }
+    }
+    let locale = Locale::for_member(gtm_db::get_user_locale(pool, to_user_id).await?.as_deref());
+    let label = gtm_notify::templates::game_label(&game.official_date, &game.away_team_name);
+    let email = gtm_notify::templates::email_tickets_transferred(locale, &label, from_name, &seats);
+    queue_email(
+        pool,
+        to_user_id,
+        game_pk,
+        gtm_db::notifications::TICKETS_TRANSFERRED,
+        email,
+    )
+    .await
+}
+
+/// Send one batch of queued notifications. Returns how many were delivered.
+async fn deliver_notifications(
+    pool: &AnyPool,
+    notifier: &gtm_notify::Notifier,
+) -> anyhow::Result<usize> {
+    let pending = gtm_db::notifications::list_pending(pool, NOTIFICATION_BATCH).await?;
+    let mut sent = 0;
+    for n in pending {
+        let message = if n.channel == gtm_db::notifications::SMS {
+            gtm_notify::Notification::sms(&n.recipient, &n.body)
+        } else {
+            gtm_notify::Notification::email(&n.recipient, &n.subject, &n.body)
+        };
+        match notifier.send(&message).await {
+            Ok(()) => {
+                gtm_db::notifications::mark_sent(pool, n.id).await?;
+                sent += 1;
+            }
+            Err(e) => {
+                warn!(id = n.id, to = %n.recipient, attempts = n.attempts + 1, "Notification delivery failed: {e}");
+                gtm_db::notifications::mark_failed(pool, n.id, &e.to_string()).await?;
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// Post due webhook deliveries. Any 2xx answer counts as delivered.
+async fn deliver_webhooks(pool: &AnyPool) -> anyhow::Result<usize> {
+    let due = gtm_db::webhooks::list_due(pool, NOTIFICATION_BATCH).await?;
+    let mut delivered = 0;
+    for d in due {
+        let sent = gtm_notify::webhooks::post(&d.url, &d.secret, &d.event, &d.payload).await;
+        match sent {
+            Ok(status) if (200..300).contains(&status) => {
+                gtm_db::webhooks::mark_delivered(pool, d.id, i64::from(status)).await?;
+                delivered += 1;
+            }
+            Ok(status) => {
+                warn!(id = d.id, url = %d.url, attempts = d.attempts + 1, status, "Webhook endpoint refused delivery");
+                gtm_db::webhooks::mark_failed(
+                    pool,
+                    d.id,
+                    Some(i64::from(status)),
+                    &format!("HTTP {status}"),
+                )
+                .await?;
+            }
+            Err(e) => {
+                warn!(id = d.id, url = %d.url, attempts = d.attempts + 1, "Webhook delivery failed: {e}");
+                gtm_db::webhooks::mark_failed(pool, d.id, None, &e.to_string()).await?;
+            }
+        }
+    }
+    Ok(delivered)
+}
+
+// --- Admin: Allocation ---
+
+async fn api_admin_allocation(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    State(history): State<Arc<gtm_db::history::SnapshotCache>>,
+    Query(params): Query<AsOfQuery>,
+) -> Result<Json<Vec<gtm_models::AllocationSummaryRow>>, ApiError> {
+    let tz = display_tz(&config)?;
+    if let Some(cutoff) = as_of_cutoff(params.as_of.as_deref(), tz)? {
+        let season = season_or_current(params.season, tz);
+        let snapshot = history.get(&pool, &season, &cutoff).await?;
+        return Ok(Json(
+            gtm_db::history::allocation_summary(&pool, &snapshot, config.team_id).await?,
+        ));
+    }
+    gtm_db::allocation_summary(&pool, config.team_id, params.season.as_deref())
+        .await
+        .map(Json)
+        .map_err(|e| {
+            warn!(error = %e, "allocation: allocation_summary query failed");
+            ApiError::from(e)
+        })
+}
+
+#[derive(Serialize)]
+struct GameAllocationDetail {
+    game: gtm_models::Game,
+    tickets: Vec<GameTicketWithUser>,
+    requests: Vec<RequestWithUser>,
+}
+
+#[derive(Serialize)]
+struct GameTicketWithUser {
+    id: TicketId,
+    seat_id: SeatId,
+    section: String,
+    row: String,
+    seat: String,
+    status: String,
+    assigned_to: Option<UserId>,
+    assigned_user_name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RequestWithUser {
+    id: i64,
+    user_id: UserId,
+    user_name: String,
+    seats_requested: i64,
+    status: String,
+    notes: Option<String>,
+}
+
+async fn api_admin_allocation_game(
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+) -> Result<Json<GameAllocationDetail>, ApiError> {
+    let game = gtm_db::get_game(&pool, game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+
+    let tickets = gtm_db::list_tickets_for_game(&pool, game_pk).await?;
+
+    let requests = gtm_db::list_requests_for_game(&pool, game_pk).await?;
+
+    let users = gtm_db::list_users(&pool).await?;
+    let user_map: std::collections::HashMap<UserId, &gtm_models::User> =
+        users.iter().map(|u| (u.id, u)).collect();
+
+    let tickets_with_user: Vec<GameTicketWithUser> = tickets
+        .into_iter()
+        .map(|t| GameTicketWithUser {
+            id: t.id,
+            seat_id: t.seat_id,
+            section: t.section,
+            row: t.row,
+            seat: t.seat,
+            status: t.status,
+            assigned_to: t.assigned_to,
+            assigned_user_name: t
+                .assigned_to
+                .and_then(|uid| user_map.get(&uid).map(|u| u.name.clone())),
+        })
+        .collect();
+
+    let requests_with_user: Vec<RequestWithUser> = requests
+        .into_iter()
+        .map(|r| RequestWithUser {
+            id: r.id,
+            user_id: r.user_id,
+            user_name: user_map
+                .get(&r.user_id)
+                .map(|u| u.name.clone())
+                .unwrap_or_default(),
+            seats_requested: r.seats_requested,
+            status: r.status,
+            notes: r.notes,
+        })
+        .collect();
+
+    Ok(Json(GameAllocationDetail {
+        game,
+        tickets: tickets_with_user,
+        requests: requests_with_user,
+    }))
+}
+
+#[derive(Serialize, Deserialize)]
+struct AllocateBody {
+    game_ticket_id: TicketId,
+    user_id: UserId,
+    request_id: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AllocateBatchBody {
+    assignments: Vec<AllocateBody>,
+    /// Assign even if it leaves a member's seats in more than one section.
+    #[serde(default)]
+    allow_split: bool,
+}
+
+/// Takes an `Idempotency-Key` header, like `POST /my/requests`.
+async fn api_admin_allocate(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+    headers: axum::http::HeaderMap,
+    Json(body): Json<AllocateBatchBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let key = idempotency_key(&headers)?;
+    let request = serde_json::to_string(&body).map_err(ApiError::invalid)?;
+    let (assigned_count, batch_id, warnings) = service::idempotent(
+        pool.clone(),
+        admin.id,
+        "POST /admin/allocate",
+        key,
+        request,
+        service::allocate(
+            pool,
+            gcal,
+            Some(admin.id),
+            body.assignments,
+            body.allow_split,
+        ),
+    )
+    .await?;
+    Ok(Envelope::with_warnings(
+        json!({ "status": "ok", "assigned": assigned_count, "batch_id": batch_id }),
+        warnings,
+    ))
+}
+
+#[derive(Deserialize)]
+struct AutoAllocateBody {
+    strategy: Option<gtm_alloc::Strategy>,
+    seed: Option<u64>,
+    /// Commit even if a member's seats would span sections.
+    #[serde(default)]
+    allow_split: bool,
+}
+
+#[derive(Serialize)]
+struct AutoAllocateResponse {
+    #[serde(flatten)]
+    allocation: gtm_alloc::Allocation,
+    assigned: u64,
+    /// The recorded batch, at `/admin/allocation/batches/{id}`.
+    batch_id: Option<i64>,
+}
+
+/// Load the game's tickets, requests, and season history and run the
+/// engine. Writes nothing; the preview endpoint returns this as is.
+async fn plan_auto_allocation(
+    pool: &AnyPool,
+    game: &gtm_models::Game,
+    strategy: gtm_alloc::Strategy,
+    seed: Option<u64>,
+) -> anyhow::Result<gtm_alloc::Allocation> {
+    let tickets = gtm_db::list_tickets_for_game(pool, game.game_pk).await?;
+    let requests = gtm_db::list_requests_for_game(pool, game.game_pk).await?;
+    let prior: std::collections::HashMap<UserId, i64> =
+        gtm_db::assigned_seat_counts_for_season(pool, &game.season)
+            .await?
+            .into_iter()
+            .collect();
+    let seed = seed.unwrap_or_else(gtm_alloc::random_seed);
+    Ok(gtm_alloc::allocate(
+        &tickets, &requests, &prior, strategy, seed,
+    ))
+}
+
+/// Whether [`run_auto_allocation`] applies what it plans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AutoRun {
+    Preview,
+    /// Apply it; fails with [`gtm_db::seating::SplitParty`] if a member's
+    /// seats would span sections, unless `allow_split`.
+    Commit {
+        allow_split: bool,
+    },
+}
+
+/// Shared auto-allocation path for the API and CLI: plan the allocation
+/// and (unless previewing) apply the result with its batch record. Returns
+/// the proposal, the number of seats assigned and the batch id.
+async fn run_auto_allocation(
+    pool: &AnyPool,
+    game: &gtm_models::Game,
+    strategy: gtm_alloc::Strategy,
+    seed: Option<u64>,
+    run: AutoRun,
+    actor_id: Option<UserId>,
+    warnings: &mut gtm_db::Warnings,
+) -> anyhow::Result<(gtm_alloc::Allocation, u64, Option<i64>)> {
+    let allocation = plan_auto_allocation(pool, game, strategy, seed).await?;
+
+    let (assigned, batch_id) = if let AutoRun::Commit { allow_split } = run {
+        let assignments: Vec<(TicketId, UserId, Option<i64>)> = allocation
+            .assignments()
+            .into_iter()
+            .map(|(t, u, r)| (t, u, Some(r)))
+            .collect();
+        let draft = gtm_db::allocation_batches::Draft {
+            kind: gtm_db::allocation_batches::AUTO,
+            game_pk: Some(game.game_pk),
+            request: json!({ "game_pk": game.game_pk, "strategy": strategy, "seed": seed }),
+            proposal: serde_json::to_value(&allocation)?,
+        };
+        let (assigned, batch_id) = gtm_db::commit_allocation_batch(
+            pool,
+            &assignments,
+            actor_id,
+            allow_split,
+            &draft,
+            warnings,
+        )
+        .await?;
+        (assigned, Some(batch_id))
+    } else {
+        (0, None)
+    };
+    info!(
+        game_pk = %game.game_pk,
+        strategy = %strategy,
+        seed,
+        proposed = allocation.assignments().len(),
+        assigned,
+        "Auto-allocation {}",
+        if run == AutoRun::Preview {
+            "previewed"
+        } else {
+            "committed"
+        }
+    );
+    Ok((allocation, assigned, batch_id))
+}
+
+async fn api_admin_allocate_auto(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+    Path(game_pk): Path<GamePk>,
+    Json(body): Json<AutoAllocateBody>,
+) -> Result<Json<Envelope<AutoAllocateResponse>>, ApiError> {
+    let strategy = match body.strategy {
+        Some(s) => s,
+        None => config
+            .allocation_strategy
+            .parse()
+            .map_err(ApiError::internal)?,
+    };
+    let game = gtm_db::get_game(&pool, game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+
+    let (allocation, assigned, batch_id, warnings) = service::auto_allocate(
+        pool,
+        gcal,
+        game,
+        strategy,
+        body.seed,
+        admin.id,
+        body.allow_split,
+    )
+    .await?;
+    Ok(Envelope::with_warnings(
+        AutoAllocateResponse {
+            allocation,
+            assigned,
+            batch_id,
+        },
+        warnings,
+    ))
+}
+
+#[derive(Deserialize)]
+struct BatchQuery {
+    game_pk: Option<GamePk>,
+}
+
+#[derive(Serialize)]
+struct AllocationBatchDetail {
+    #[serde(flatten)]
+    batch: gtm_models::AllocationBatch,
+    /// `{ request, proposal, assignments, warnings }` as committed.
+    snapshot: serde_json::Value,
+}
+
+/// Committed allocation batches, newest first; `?game_pk=` for one game's.
+async fn api_admin_allocation_batches(
+    State(pool): State<AnyPool>,
+    Query(params): Query<BatchQuery>,
+) -> Result<Json<Vec<gtm_models::AllocationBatch>>, ApiError> {
+    Ok(Json(
+        gtm_db::allocation_batches::list(&pool, params.game_pk).await?,
+    ))
+}
+
+async fn api_admin_allocation_batch(
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+) -> Result<Json<AllocationBatchDetail>, ApiError> {
+    let (batch, snapshot) = gtm_db::allocation_batches::get(&pool, id)
+        .await?
+        .ok_or(ApiError::not_found("Allocation batch not found"))?;
+    Ok(Json(AllocationBatchDetail { batch, snapshot }))
+}
+
+/// The allocation `auto` would commit with the same strategy and seed,
+/// without writing anything. Pass the returned seed to `auto` to commit
+/// exactly this proposal.
+async fn api_admin_allocation_preview(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(game_pk): Path<GamePk>,
+    Json(body): Json<AutoAllocateBody>,
+) -> Result<Json<Envelope<gtm_alloc::Allocation>>, ApiError> {
+    let strategy = match body.strategy {
+        Some(s) => s,
+        None => config
+            .allocation_strategy
+            .parse()
+            .map_err(ApiError::internal)?,
+    };
+    let game = gtm_db::get_game(&pool, game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+    let allocation = plan_auto_allocation(&pool, &game, strategy, body.seed).await?;
+    Ok(Envelope::ok(allocation))
+}
+
+#[derive(Deserialize)]
+struct SimulateBody {
+    season: Option<String>,
+    strategy: Option<gtm_alloc::Strategy>,
+    max_seats_per_game: Option<i64>,
+    max_seats_per_season: Option<i64>,
+    seed: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct SimulatedMember {
+    user_id: UserId,
+    user_name: String,
+    requests: i64,
+    seats_requested: i64,
+    actual_seats: i64,
+    simulated_seats: i64,
+}
+
+#[derive(Serialize)]
+struct SimulationResponse {
+    season: String,
+    policy: gtm_alloc::Policy,
+    seed: u64,
+    members: Vec<SimulatedMember>,
+}
+
+/// Replay a season's requests under a hypothetical policy and compare each
+/// member's seats with what they were actually given. Writes nothing.
+async fn api_admin_allocation_simulate(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Json(body): Json<SimulateBody>,
+) -> Result<Json<Envelope<SimulationResponse>>, ApiError> {
+    let tz = display_tz(&config)?;
+    if body
+        .max_seats_per_game
+        .into_iter()
+        .chain(body.max_seats_per_season)
+        .any(|cap| cap < 1)
+    {
+        return Err(ApiError::bad_request("Seat caps must be at least 1"));
+    }
+    let strategy = match body.strategy {
+        Some(s) => s,
+        None => config
+            .allocation_strategy
+            .parse()
+            .map_err(ApiError::internal)?,
+    };
+    let policy = gtm_alloc::Policy {
+        strategy,
+        max_seats_per_game: body.max_seats_per_game,
+        max_seats_per_season: body.max_seats_per_season,
+    };
+    let season = season_or_current(body.season, tz);
+    let seed = body.seed.unwrap_or_else(gtm_alloc::random_seed);
+
+    let (rounds, actual, members) = read
+        .run(|pool| {
+            let season = &season;
+            async move {
+                let rounds = gtm_db::reports::allocation_rounds(&pool, season).await?;
+                let actual = gtm_db::assigned_seat_counts_for_season(&pool, season).await?;
+                let members = gtm_db::list_users(&pool).await?;
+                anyhow::Ok((rounds, actual, members))
+            }
+        })
+        .await?;
+    let mut simulated = gtm_alloc::simulate(&rounds, &policy, seed);
+    let mut actual: std::collections::HashMap<UserId, i64> = actual.into_iter().collect();
+    let mut requested: std::collections::HashMap<UserId, (i64, i64)> =
+        std::collections::HashMap::new();
+    for r in rounds.iter().flat_map(|round| &round.requests) {
+        if r.status != "withdrawn" {
+            let entry = requested.entry(r.user_id).or_default();
+            entry.0 += 1;
+            entry.1 += r.seats_requested;
+        }
+    }
+
+    let members = members
+        .into_iter()
+        .filter_map(|u| {
+            let (requests, seats_requested) = requested.remove(&u.id).unwrap_or_default();
+            let actual_seats = actual.remove(&u.id).unwrap_or(0);
+            let simulated_seats = simulated.remove(&u.id).unwrap_or(0);
+            (requests > 0 || actual_seats > 0).then_some(SimulatedMember {
+                user_id: u.id,
+                user_name: u.name,
+                requests,
+                seats_requested,
+                actual_seats,
+                simulated_seats,
+            })
+        })
+        .collect();
+    Ok(Envelope::ok(SimulationResponse {
+        season,
+        policy,
+        seed,
+        members,
+    }))
+}
+
+async fn api_admin_revoke(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+    Path(game_ticket_id): Path<TicketId>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let ticket = gtm_db::get_ticket(&pool, game_ticket_id).await?;
+    let holder = ticket.as_ref().and_then(|t| t.assigned_to);
+    let ok = gtm_db::revoke_ticket(&pool, game_ticket_id, Some(admin.id)).await?;
+    if ok {
+        if let (Some(user_id), Some(t)) = (holder, &ticket)
+            && let Err(e) = queue_revocation_email(&pool, user_id, t).await
+        {
+            warn!(%game_ticket_id, "Failed to queue revocation email: {e}");
+        }
+        spawn_calendar_sync(pool, gcal, holder.into_iter().collect());
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Ticket not found or not assigned"))
+    }
+}
+
+async fn api_admin_allocation_by_user(
+    State(pool): State<AnyPool>,
+    Path(target_user_id): Path<UserId>,
+) -> Result<Json<Vec<gtm_models::GameTicketDetail>>, ApiError> {
+    gtm_db::list_tickets_for_user(&pool, target_user_id, None)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+// --- Admin: Allocation by users ---
+
+#[derive(Serialize)]
+struct UserTicketInfo {
+    ticket_id: TicketId,
+    section: String,
+    row: String,
+    seat: String,
+}
+
+#[derive(Serialize)]
+struct UserAllocationEntry {
+    request_id: i64,
+    game_pk: GamePk,
+    official_date: String,
+    away_team_name: String,
+    day_night: Option<String>,
+    seats_requested: i64,
+    status: String,
+    notes: Option<String>,
+    game_total_seats: i64,
+    game_available: i64,
+    user_tickets: Vec<UserTicketInfo>,
+}
+
+#[derive(Serialize)]
+struct UserAllocationSection {
+    user_id: UserId,
+    user_name: String,
+    total_allocated: i64,
+    total_requested: i64,
+    games_allocated: i64,
+    games_requested: i64,
+    entries: Vec<UserAllocationEntry>,
+}
+
+async fn api_admin_allocation_by_users(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+) -> Result<Json<Vec<UserAllocationSection>>, ApiError> {
+    let requests = gtm_db::list_all_active_requests(&pool).await?;
+    let assigned_tickets = gtm_db::list_all_assigned_tickets(&pool).await?;
+    let games = gtm_db::list_games(&pool, &Default::default()).await?;
+    let users = gtm_db::list_users(&pool).await?;
+    let summary = gtm_db::allocation_summary(&pool, config.team_id, None).await?;
+
+    let game_map: std::collections::HashMap<GamePk, &gtm_models::Game> =
+        games.iter().map(|g| (g.game_pk, g)).collect();
+    let user_map: std::collections::HashMap<UserId, &gtm_models::User> =
+        users.iter().map(|u| (u.id, u)).collect();
+    // game_pk -> (total_seats, assigned, available)
+    let summary_map: std::collections::HashMap<GamePk, (i64, i64, i64)> = summary
+        .into_iter()
+        .map(|r| (r.game_pk, (r.total_seats, r.assigned, r.available)))
+        .collect();
+    // (user_id, game_pk) -> Vec<ticket>
+    let mut ticket_map: std::collections::HashMap<
+        (UserId, GamePk),
+        Vec<&gtm_models::GameTicketDetail>,
+    > = std::collections::HashMap::new();
+    for t in &assigned_tickets {
+        if let Some(uid) = t.assigned_to {
+            ticket_map.entry((uid, t.game_pk)).or_default().push(t);
+        }
+    }
+
+    // Group requests by user
+    let mut user_requests: std::collections::HashMap<UserId, Vec<&gtm_models::TicketRequest>> =
+        std::collections::HashMap::new();
+    for r in &requests {
+        user_requests.entry(r.user_id).or_default().push(r);
+    }
+
+    let mut sections: Vec<UserAllocationSection> = user_requests
+        .into_iter()
+        .map(|(uid, reqs)| {
+            let user_name = user_map
+                .get(&uid)
+                .map(|u| u.name.clone())
+                .unwrap_or_default();
+
+            let entries: Vec<UserAllocationEntry> = reqs
+                .iter()
+                .map(|r| {
+                    let game = game_map.get(&r.game_pk);
                     let (total_seats, _assigned, available) =
                         summary_map.get(&r.game_pk).copied().unwrap_or((0, 0, 0));
                     let user_tix = ticket_map
@@ -1141,66 +4512,2209 @@ async fn api_admin_allocation_by_users(
                         .cloned()
                         .unwrap_or_default();
 
-                    UserAllocationEntry {
-                        request_id: r.id,
-                        game_pk: r.game_pk,
-                        official_date: game.map(|g| g.official_date.clone()).unwrap_or_default(),
-                        away_team_name: game.map(|g| g.away_team_name.clone()).unwrap_or_default(),
-                        day_night: game.and_then(|g| g.day_night.clone()),
-                        seats_requested: r.seats_requested,
-                        status: r.status.clone(),
-                        notes: r.notes.clone(),
-                        game_total_seats: total_seats,
-                        game_available: available,
-                        user_tickets: user_tix
-                            .iter()
-                            .map(|t| UserTicketInfo {
-                                ticket_id: t.id,
-                                section: t.section.clone(),
-                                row: t.row.clone(),
-                                seat: t.seat.clone(),
-                            })
-                            .collect(),
-                    }
-                })
-                .collect();
+                    UserAllocationEntry {
+                        request_id: r.id,
+                        game_pk: r.game_pk,
+                        official_date: game.map(|g| g.official_date.clone()).unwrap_or_default(),
+                        away_team_name: game.map(|g| g.away_team_name.clone()).unwrap_or_default(),
+                        day_night: game.and_then(|g| g.day_night.clone()),
+                        seats_requested: r.seats_requested,
+                        status: r.status.clone(),
+                        notes: r.notes.clone(),
+                        game_total_seats: total_seats,
+                        game_available: available,
+                        user_tickets: user_tix
+                            .iter()
+                            .map(|t| UserTicketInfo {
+                                ticket_id: t.id,
+                                section: t.section.clone(),
+                                row: t.row.clone(),
+                                seat: t.seat.clone(),
+                            })
+                            .collect(),
+                    }
+                })
+                .collect();
+
+            let total_allocated: i64 = entries.iter().map(|e| e.user_tickets.len() as i64).sum();
+            let total_requested: i64 = entries.iter().map(|e| e.seats_requested).sum();
+            let games_allocated = entries
+                .iter()
+                .filter(|e| !e.user_tickets.is_empty())
+                .count() as i64;
+            let games_requested = entries.len() as i64;
+
+            UserAllocationSection {
+                user_id: uid,
+                user_name,
+                total_allocated,
+                total_requested,
+                games_allocated,
+                games_requested,
+                entries,
+            }
+        })
+        .collect();
+
+    sections.sort_by(|a, b| a.user_name.cmp(&b.user_name));
+
+    Ok(Json(sections))
+}
+
+/// A pending request annotated with how long it has been waiting.
+#[derive(Serialize)]
+struct AdminPendingRequest {
+    #[serde(flatten)]
+    request: gtm_models::PendingRequest,
+    pending_hours: f64,
+    /// When the request breaches the response SLA (RFC 3339, UTC).
+    respond_by: Option<String>,
+    overdue: bool,
+    latest_comment: Option<gtm_models::RequestComment>,
+}
+
+async fn api_admin_requests(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<Vec<AdminPendingRequest>>, ApiError> {
+    let requests =
+        gtm_db::list_pending_requests_oldest_first(&pool, params.season.as_deref()).await?;
+    let ids: Vec<i64> = requests.iter().map(|r| r.id).collect();
+    let mut latest = gtm_db::request_comments::latest(&pool, &ids).await?;
+
+    let now = chrono::Utc::now();
+    let sla = chrono::Duration::hours(config.request_sla_hours);
+    let rows = requests
+        .into_iter()
+        .map(|request| {
+            let created = gtm_db::parse_timestamp(&request.created_at);
+            let pending_hours = created
+                .map(|c| (now - c).num_minutes().max(0) as f64 / 60.0)
+                .unwrap_or(0.0);
+            let deadline = created.map(|c| c + sla);
+            AdminPendingRequest {
+                latest_comment: latest.remove(&request.id),
+                request,
+                pending_hours,
+                respond_by: deadline.map(rfc3339_utc),
+                overdue: deadline.is_some_and(|d| d < now),
+            }
+        })
+        .collect();
+    Ok(Json(rows))
+}
+
+async fn api_admin_request_comments(
+    State(pool): State<AnyPool>,
+    Path(request_id): Path<i64>,
+) -> Result<Json<Vec<gtm_models::RequestComment>>, ApiError> {
+    gtm_db::get_ticket_request(&pool, request_id)
+        .await?
+        .ok_or(ApiError::not_found("Request not found"))?;
+    Ok(Json(
+        gtm_db::request_comments::list(&pool, request_id).await?,
+    ))
+}
+
+async fn api_admin_request_comment_add(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    Path(request_id): Path<i64>,
+    Json(body): Json<CommentBody>,
+) -> Result<Json<Envelope<gtm_models::RequestComment>>, ApiError> {
+    gtm_db::get_ticket_request(&pool, request_id)
+        .await?
+        .ok_or(ApiError::not_found("Request not found"))?;
+    let comment = gtm_db::request_comments::create(&pool, request_id, admin.id, true, &body.body)
+        .await
+        .map_err(ApiError::invalid)?;
+    Ok(Envelope::ok(comment))
+}
+
+async fn api_admin_response_times(
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::MonthlyResponseTime>>, ApiError> {
+    gtm_db::response_time_report(&pool)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+// --- Admin: audit ---
+
+#[derive(Deserialize)]
+struct AuditQuery {
+    game_pk: Option<GamePk>,
+    user_id: Option<UserId>,
+}
+
+async fn api_admin_audit(
+    State(pool): State<AnyPool>,
+    Query(params): Query<AuditQuery>,
+) -> Result<Json<Vec<gtm_models::AuditEntry>>, ApiError> {
+    gtm_db::audit::list(&pool, params.game_pk, params.user_id)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize)]
+struct TransferHistoryQuery {
+    game_pk: Option<GamePk>,
+    season: Option<String>,
+}
+
+async fn api_admin_transfers(
+    State(pool): State<AnyPool>,
+    Query(params): Query<TransferHistoryQuery>,
+) -> Result<Json<Vec<gtm_models::TicketTransfer>>, ApiError> {
+    gtm_db::list_ticket_transfers(&pool, params.game_pk, params.season.as_deref())
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+// --- Admin: alerts ---
+
+#[derive(Deserialize)]
+struct UnassignedAlertQuery {
+    days: Option<i64>,
+}
+
+/// Home games from today (in `tz`) through `days` ahead with unclaimed seats.
+async fn find_unassigned_games(
+    pool: &AnyPool,
+    team_id: u32,
+    tz: chrono_tz::Tz,
+    days: i64,
+) -> anyhow::Result<Vec<gtm_models::UnassignedGame>> {
+    let today = Utc::now().with_timezone(&tz).date_naive();
+    let until = today + chrono::Duration::days(days);
+    gtm_db::list_unassigned_games(
+        pool,
+        team_id,
+        &today.format("%Y-%m-%d").to_string(),
+        &until.format("%Y-%m-%d").to_string(),
+    )
+    .await
+}
+
+async fn api_admin_alerts_unassigned(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<UnassignedAlertQuery>,
+) -> Result<Json<Vec<gtm_models::UnassignedGame>>, ApiError> {
+    let days = params.days.unwrap_or(config.unassigned_alert_days);
+    find_unassigned_games(&pool, config.team_id, display_tz(&config)?, days)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Email the unassigned-seat digest to admins, and to every member when
+/// `unassigned_alert_group` is set. Returns the number of games reported.
+async fn send_unassigned_alert(
+    pool: &AnyPool,
+    notifier: &gtm_notify::Notifier,
+    config: &gtm_config::Config,
+) -> anyhow::Result<usize> {
+    let games = find_unassigned_games(
+        pool,
+        config.team_id,
+        display_tz(config)?,
+        config.unassigned_alert_days,
+    )
+    .await?;
+    if games.is_empty() {
+        return Ok(0);
+    }
+
+    let lines: Vec<(String, i64)> = games
+        .iter()
+        .map(|g| {
+            (
+                gtm_notify::templates::game_label(&g.official_date, &g.away_team_name),
+                g.available,
+            )
+        })
+        .collect();
+    let (subject, body) = gtm_notify::templates::email_unassigned_seats(&lines);
+
+    let mut recipients = config.admin_emails.clone();
+    if config.unassigned_alert_group {
+        let prefs = gtm_db::preferences::list(pool).await?;
+        for user in gtm_db::list_users(pool).await? {
+            match prefs.get(&user.id) {
+                None => recipients.push(user.email),
+                Some(p) if p.wants(gtm_db::notifications::UNASSIGNED_SEATS) => {
+                    recipients.push(p.email(&user.email).to_string())
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    recipients.sort_by_key(|e| e.to_ascii_lowercase());
+    recipients.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+    for to in &recipients {
+        if let Err(e) = notifier
+            .send(&gtm_notify::Notification::email(to, &subject, &body))
+            .await
+        {
+            warn!(to = %to, "Unassigned-seat alert failed: {e}");
+        }
+    }
+    Ok(games.len())
+}
+
+// --- Weather release prompts ---
+
+/// Days ahead (including today) to check the rain forecast for.
+const RAIN_PROMPT_DAYS: i64 = 2;
+
+/// Email every member holding seats for a soon-to-be-rainy home game a
+/// one-click release/keep prompt, unless they've turned these off. Each member is prompted at most once per
+/// game. Returns the number of prompts sent.
+async fn send_rain_release_prompts(
+    pool: &AnyPool,
+    notifier: &gtm_notify::Notifier,
+    config: &gtm_config::Config,
+) -> anyhow::Result<usize> {
+    use rand::Rng;
+
+    let today = Utc::now().with_timezone(&display_tz(config)?).date_naive();
+    let users: std::collections::HashMap<UserId, gtm_models::User> = gtm_db::list_users(pool)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u))
+        .collect();
+    let locales = gtm_db::list_user_locales(pool).await?;
+    let prefs = gtm_db::preferences::list(pool).await?;
+
+    let mut sent = 0;
+    for offset in 0..=RAIN_PROMPT_DAYS {
+        let date = (today + chrono::Duration::days(offset))
+            .format("%Y-%m-%d")
+            .to_string();
+        for game in gtm_db::list_home_games_on_date(pool, config.team_id, &date).await? {
+            let Some(rain) = gtm_scraper::weather::rain_probability(
+                config.venue_latitude,
+                config.venue_longitude,
+                &game.game_date,
+            )
+            .await?
+            else {
+                continue;
+            };
+            if rain < config.rain_release_threshold {
+                continue;
+            }
+            let mut holders: Vec<UserId> = gtm_db::list_tickets_for_game(pool, game.game_pk)
+                .await?
+                .into_iter()
+                .filter_map(|t| t.assigned_to)
+                .collect();
+            holders.sort_unstable();
+            holders.dedup();
+
+            let preview = gtm_db::previews::get(pool, game.game_pk).await?;
+            let label =
+                gtm_notify::templates::game_label(&game.official_date, &game.away_team_name);
+            for user_id in holders {
+                let Some(user) = users.get(&user_id) else {
+                    continue;
+                };
+                let to = match prefs.get(&user_id) {
+                    None => user.email.as_str(),
+                    Some(p) if p.wants(gtm_db::notifications::RAIN_RELEASE_PROMPT) => {
+                        p.email(&user.email)
+                    }
+                    Some(_) => continue,
+                };
+                let token = format!("{:032x}", rand::thread_rng().r#gen::<u128>());
+                if !gtm_db::create_release_prompt(pool, game.game_pk, user_id, rain, &token).await?
+                {
+                    continue;
+                }
+                let base = format!("{}/api/release-prompts/{token}", config.public_url);
+                let locale = Locale::for_member(locales.get(&user_id).map(String::as_str));
+                let (subject, body) = gtm_notify::templates::email_rain_release_prompt(
+                    locale,
+                    &label,
+                    rain,
+                    &format!("{base}/release"),
+                    &format!("{base}/keep"),
+                    preview.as_ref().map(|p| p.body.as_str()),
+                );
+                match notifier
+                    .send(&gtm_notify::Notification::email(to, &subject, &body))
+                    .await
+                {
+                    Ok(()) => sent += 1,
+                    Err(e) => warn!(to = %to, "Rain release prompt failed: {e}"),
+                }
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// Text every member who still hasn't answered a rain prompt for one of
+/// today's home games that the offer lapses at first pitch, unless they've
+/// turned rain prompts off. Returns the number of texts sent.
+async fn send_offer_expiring_sms(
+    pool: &AnyPool,
+    notifier: &gtm_notify::Notifier,
+    config: &gtm_config::Config,
+) -> anyhow::Result<usize> {
+    let now = Utc::now();
+    let today = now
+        .with_timezone(&display_tz(config)?)
+        .format("%Y-%m-%d")
+        .to_string();
+    let phones: std::collections::HashMap<UserId, String> = gtm_db::list_sms_recipients(pool)
+        .await?
+        .into_iter()
+        .map(|p| (p.user_id, p.phone))
+        .collect();
+    let locales = gtm_db::list_user_locales(pool).await?;
+    let prefs = gtm_db::preferences::list(pool).await?;
+
+    let mut sent = 0;
+    for game in gtm_db::list_home_games_on_date(pool, config.team_id, &today).await? {
+        let Some(first_pitch) = gtm_db::parse_timestamp(&game.game_date) else {
+            continue;
+        };
+        let hours = (first_pitch - now).num_hours();
+        if hours < 1 {
+            continue;
+        }
+        let label = gtm_notify::templates::game_label(&game.official_date, &game.away_team_name);
+        for user_id in gtm_db::list_unanswered_release_prompts(pool, game.game_pk).await? {
+            let Some(phone) = phones.get(&user_id) else {
+                continue;
+            };
+            if prefs
+                .get(&user_id)
+                .is_some_and(|p| !p.wants(gtm_db::notifications::RAIN_RELEASE_PROMPT))
+            {
+                continue;
+            }
+            let locale = Locale::for_member(locales.get(&user_id).map(String::as_str));
+            let expires_in = match locale {
+                Locale::En => format!("{hours} hours"),
+                Locale::Es => format!("{hours} horas"),
+            };
+            let body = gtm_notify::templates::sms_offer_expiring(locale, &label, &expires_in);
+            match notifier
+                .send(&gtm_notify::Notification::sms(phone, &body))
+                .await
+            {
+                Ok(()) => sent += 1,
+                Err(e) => warn!(user_id = %user_id, "Offer-expiring SMS failed: {e}"),
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// Seconds between linescore polls while one of today's games is on.
+const LIVE_POLL_SECS: u64 = 60;
+/// Start polling this many minutes before the first scheduled pitch.
+const LIVE_LEAD_MINUTES: i64 = 15;
+
+/// When the live score poller should next run: every [`LIVE_POLL_SECS`]
+/// once today's first game is about to start, until every game is final;
+/// then not until tomorrow morning.
+async fn next_live_poll(pool: &AnyPool, tz: chrono_tz::Tz) -> anyhow::Result<std::time::Duration> {
+    let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+    let filter = gtm_db::GameFilter {
+        from: Some(today.clone()),
+        to: Some(today),
+        ..Default::default()
+    };
+    let first_pitch = gtm_db::list_games(pool, &filter)
+        .await?
+        .iter()
+        .filter(|g| g.status_abstract != "Final")
+        .filter_map(|g| gtm_db::parse_timestamp(&g.game_date))
+        .min();
+    let poll = std::time::Duration::from_secs(LIVE_POLL_SECS);
+    Ok(match first_pitch {
+        None => delay_until(tz, 6, 0),
+        Some(start) => (start - chrono::Duration::minutes(LIVE_LEAD_MINUTES) - Utc::now())
+            .to_std()
+            .map_or(poll, |wait| wait.max(poll)),
+    })
+}
+
+/// Copy today's status, scores and innings from the linescore onto the
+/// games table. Returns how many of today's games aren't final yet.
+async fn refresh_live_games(
+    pool: &AnyPool,
+    team_id: u32,
+    tz: chrono_tz::Tz,
+) -> anyhow::Result<usize> {
+    let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+    let mut unfinished = 0;
+    for game in gtm_scraper::fetch_live_games(team_id, &today).await? {
+        if !gtm_db::update_live_game(pool, &game).await? {
+            debug!(game_pk = %game.game_pk, "Live update for a game we haven't scraped");
+        }
+        if !game.is_final() {
+            unfinished += 1;
+        }
+    }
+    Ok(unfinished)
+}
+
+/// Days ahead (including today) that game previews are kept fresh.
+const PREVIEW_DAYS: i64 = 3;
+/// Most promotions named in a preview.
+const PREVIEW_PROMOTIONS: usize = 3;
+
+/// Regenerate the preview of each home game in the next [`PREVIEW_DAYS`]
+/// days. A failed Stats API lookup leaves that part out rather than
+/// skipping the game. Returns how many previews were written.
+async fn refresh_game_previews(
+    pool: &AnyPool,
+    team_id: u32,
+    tz: chrono_tz::Tz,
+) -> anyhow::Result<usize> {
+    let api = gtm_scraper::StatsApiClient::shared();
+    let today = Utc::now().with_timezone(&tz).date_naive();
+    let standings = match api.standings(today.year() as u32).await {
+        Ok(standings) => standings,
+        Err(e) => {
+            warn!("Standings lookup failed: {e}");
+            Default::default()
+        }
+    };
+
+    let mut written = 0;
+    for offset in 0..PREVIEW_DAYS {
+        let date = (today + chrono::Duration::days(offset))
+            .format("%Y-%m-%d")
+            .to_string();
+        for game in gtm_db::list_home_games_on_date(pool, team_id, &date).await? {
+            let pitchers = match api.probable_pitchers(game.game_pk).await {
+                Ok(pitchers) => pitchers,
+                Err(e) => {
+                    warn!(game_pk = %game.game_pk, "Probable pitchers lookup failed: {e}");
+                    Default::default()
+                }
+            };
+            let promotions = gtm_db::get_promotions_for_game(pool, game.game_pk).await?;
+            let preview = build_game_preview(&game, pitchers, &standings, &promotions);
+            gtm_db::previews::upsert(pool, &preview).await?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
+
+/// Assemble a game's preview and render its text, e.g.
+/// "Los Angeles Dodgers (12-8, W3) at San Francisco Giants (10-10, L1)".
+fn build_game_preview(
+    game: &gtm_models::Game,
+    pitchers: gtm_scraper::preview::ProbablePitchers,
+    standings: &std::collections::HashMap<i64, gtm_scraper::preview::TeamStanding>,
+    promotions: &[gtm_models::Promotion],
+) -> gtm_models::GamePreview {
+    let away = standings.get(&game.away_team_id);
+    let home = standings.get(&game.home_team_id);
+    let team = |name: &str, standing: Option<&gtm_scraper::preview::TeamStanding>| match standing {
+        Some(s) => match &s.streak {
+            Some(streak) => format!("{name} ({}, {streak})", s.record()),
+            None => format!("{name} ({})", s.record()),
+        },
+        None => name.to_string(),
+    };
+    let mut body = format!(
+        "{} at {}\nProbable pitchers: {} vs {}",
+        team(&game.away_team_name, away),
+        team(&game.home_team_name, home),
+        pitchers.away.as_deref().unwrap_or("TBD"),
+        pitchers.home.as_deref().unwrap_or("TBD"),
+    );
+    if !promotions.is_empty() {
+        let names: Vec<&str> = promotions
+            .iter()
+            .take(PREVIEW_PROMOTIONS)
+            .map(|p| p.name.as_str())
+            .collect();
+        body.push_str(&format!("\nPromotions: {}", names.join(", ")));
+    }
+    gtm_models::GamePreview {
+        game_pk: game.game_pk,
+        away_probable_pitcher: pitchers.away,
+        home_probable_pitcher: pitchers.home,
+        away_record: away.map(|s| s.record()),
+        home_record: home.map(|s| s.record()),
+        away_streak: away.and_then(|s| s.streak.clone()),
+        home_streak: home.and_then(|s| s.streak.clone()),
+        body,
+        generated_at: String::new(),
+    }
+}
+
+/// One-click handler for the links in a rain prompt. The token is the
+/// credential, so this route sits outside JWT auth.
+/// The page a rain prompt's link opens: it asks the member to confirm, and
+/// only the form's POST changes anything, so mail scanners and link
+/// prefetchers following the link don't answer for them.
+async fn api_release_prompt_confirm(
+    State(pool): State<AnyPool>,
+    Path((token, action)): Path<(String, String)>,
+) -> Result<axum::response::Html<String>, ApiError> {
+    if action != "release" && action != "keep" {
+        return Err(ApiError::not_found("Unknown action"));
+    }
+    let prompt = gtm_db::get_release_prompt(&pool, &token)
+        .await?
+        .ok_or(ApiError::not_found("Link not found"))?;
+    if let Some(previous) = prompt.response {
+        return Ok(axum::response::Html(format!(
+            "<p>You already chose to {previous} your seats for this game.</p>"
+        )));
+    }
+    let game = gtm_db::get_game(&pool, prompt.game_pk)
+        .await?
+        .map(|g| gtm_notify::templates::game_label(&g.official_date, &g.away_team_name))
+        .unwrap_or_else(|| "this game".to_string());
+    let (question, button) = if action == "release" {
+        (
+            format!("Release your seats for {game} so someone else can use them?"),
+            "Release my seats",
+        )
+    } else {
+        (format!("Keep your seats for {game}?"), "Keep my seats")
+    };
+    Ok(axum::response::Html(format!(
+        "<p>{question}</p><form method=\"post\"><button type=\"submit\">{button}</button></form>"
+    )))
+}
+
+async fn api_release_prompt_respond(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    State(notifier): State<Arc<gtm_notify::Notifier>>,
+    State(gcal): State<Arc<gtm_gcal::GoogleCalendar>>,
+    Path((token, action)): Path<(String, String)>,
+) -> Result<axum::response::Html<String>, ApiError> {
+    if action != "release" && action != "keep" {
+        return Err(ApiError::not_found("Unknown action"));
+    }
+    let prompt = gtm_db::get_release_prompt(&pool, &token)
+        .await?
+        .ok_or(ApiError::not_found("Link not found"))?;
+
+    let Some(count) = gtm_db::respond_release_prompt(&pool, &token, &action).await? else {
+        let previous = prompt.response.unwrap_or_default();
+        return Ok(axum::response::Html(format!(
+            "<p>You already chose to {previous} your seats for this game.</p>"
+        )));
+    };
+
+    if action == "keep" {
+        return Ok(axum::response::Html(
+            "<p>Got it — enjoy the game, and bring a jacket.</p>".to_string(),
+        ));
+    }
+    if count > 0 {
+        spawn_calendar_sync(pool.clone(), gcal, vec![prompt.user_id]);
+        spawn_day_of_release_sms(
+            pool,
+            notifier,
+            display_tz(&config)?,
+            prompt.game_pk,
+            prompt.user_id,
+            count,
+        );
+    }
+    Ok(axum::response::Html(format!(
+        "<p>Released {count} seat(s). Thanks for letting someone else have them.</p>"
+    )))
+}
+
+#[derive(Deserialize)]
+struct SeasonQuery {
+    season: Option<String>,
+}
+
+fn season_or_current(season: Option<String>, tz: chrono_tz::Tz) -> String {
+    season.unwrap_or_else(|| Utc::now().with_timezone(&tz).year().to_string())
+}
+
+/// [`season_or_current`] for a download, whose filename it goes into: it
+/// must be a four-digit year.
+fn download_season(season: Option<String>, tz: chrono_tz::Tz) -> Result<String, ApiError> {
+    let season = season_or_current(season, tz);
+    if season.len() != 4 || !season.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ApiError::bad_request(format!(
+            "season must be a year like 2026 (got {season:?})"
+        )));
+    }
+    Ok(season)
+}
+
+/// `season` plus `as_of` (YYYY-MM-DD) to see a report as it stood at the
+/// end of that day.
+#[derive(Deserialize)]
+struct AsOfQuery {
+    season: Option<String>,
+    as_of: Option<String>,
+}
+
+/// The UTC cutoff for a report `as_of` a date: midnight in `tz` at the end
+/// of that day. `None` without a date, or for today or later, which is the
+/// live data.
+fn as_of_cutoff(as_of: Option<&str>, tz: chrono_tz::Tz) -> Result<Option<String>, ApiError> {
+    let Some(as_of) = as_of else {
+        return Ok(None);
+    };
+    let date = chrono::NaiveDate::parse_from_str(as_of, "%Y-%m-%d")
+        .map_err(|_| ApiError::bad_request("as_of must be YYYY-MM-DD"))?;
+    if date >= Utc::now().with_timezone(&tz).date_naive() {
+        return Ok(None);
+    }
+    let midnight = (date + chrono::Duration::days(1)).and_time(chrono::NaiveTime::MIN);
+    let cutoff = chrono::TimeZone::from_local_datetime(&tz, &midnight)
+        .earliest()
+        .ok_or_else(|| ApiError::bad_request("as_of has no midnight in the display time zone"))?;
+    Ok(Some(
+        cutoff
+            .with_timezone(&Utc)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string(),
+    ))
+}
+
+async fn api_admin_fairness(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(history): State<Arc<gtm_db::history::SnapshotCache>>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<AsOfQuery>,
+) -> Result<Json<Vec<gtm_models::FairnessRow>>, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = season_or_current(params.season, tz);
+    if let Some(cutoff) = as_of_cutoff(params.as_of.as_deref(), tz)? {
+        let report = read
+            .run(|pool| {
+                let (history, season, cutoff) = (&history, &season, &cutoff);
+                async move {
+                    let snapshot = history.get(&pool, season, cutoff).await?;
+                    gtm_db::history::fairness_report(&pool, &snapshot).await
+                }
+            })
+            .await?;
+        return Ok(Json(report));
+    }
+    read.run(|pool| {
+        let season = &season;
+        async move { gtm_db::fairness_report(&pool, season).await }
+    })
+    .await
+    .map(Json)
+    .map_err(ApiError::from)
+}
+
+async fn api_admin_fairness_timeline(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<gtm_models::FairnessTimeline>, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = season_or_current(params.season, tz);
+    Ok(Json(
+        read.run(|pool| {
+            let season = &season;
+            async move { gtm_db::reports::fairness_timeline(&pool, season).await }
+        })
+        .await?,
+    ))
+}
+
+async fn api_my_history(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<gtm_models::MemberHistory>, ApiError> {
+    Ok(Json(gtm_db::reports::member_history(&pool, &user).await?))
+}
+
+async fn api_transparency(
+    RequireMember(user): RequireMember,
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<gtm_models::Transparency>, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = season_or_current(params.season, tz);
+    let named = config.transparency_named;
+    Ok(Json(
+        read.run(|pool| {
+            let season = &season;
+            async move { gtm_db::reports::transparency(&pool, season, user.id, named).await }
+        })
+        .await?,
+    ))
+}
+
+async fn api_admin_user_history(
+    State(pool): State<AnyPool>,
+    Path(user_id): Path<UserId>,
+) -> Result<Json<gtm_models::MemberHistory>, ApiError> {
+    let user = gtm_db::get_user(&pool, user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("User not found"))?;
+    Ok(Json(gtm_db::reports::member_history(&pool, &user).await?))
+}
+
+async fn renewal_report(
+    pool: &AnyPool,
+    season: Option<String>,
+    tz: chrono_tz::Tz,
+) -> anyhow::Result<gtm_models::RenewalReport> {
+    let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+    gtm_db::reports::renewal(pool, &season_or_current(season, tz), &today).await
+}
+
+#[derive(Deserialize)]
+struct UsageQuery {
+    /// First day (YYYY-MM-DD); default 30 days before `to`.
+    from: Option<String>,
+    /// Last day (YYYY-MM-DD); default today.
+    to: Option<String>,
+}
+
+/// How each background job's last run went.
+async fn api_admin_jobs(
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_db::jobs::JobRun>>, ApiError> {
+    Ok(Json(gtm_db::jobs::list(&pool).await?))
+}
+
+/// Daily API usage by member and endpoint.
+async fn api_admin_usage(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<UsageQuery>,
+) -> Result<Json<gtm_db::usage::UsageReport>, ApiError> {
+    let tz = display_tz(&config)?;
+    let parse = |d: &str| {
+        chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+            .map_err(|_| ApiError::bad_request(format!("Invalid date {d:?}; expected YYYY-MM-DD")))
+    };
+    let to = match &params.to {
+        Some(d) => parse(d)?,
+        None => Utc::now().with_timezone(&tz).date_naive(),
+    };
+    let from = match &params.from {
+        Some(d) => parse(d)?,
+        None => to - chrono::Duration::days(30),
+    };
+    if from > to {
+        return Err(ApiError::bad_request("`from` is after `to`"));
+    }
+    let (from, to) = (
+        from.format("%Y-%m-%d").to_string(),
+        to.format("%Y-%m-%d").to_string(),
+    );
+    let report = read
+        .run(|pool| {
+            let (from, to) = (&from, &to);
+            async move { gtm_db::usage::report(&pool, from, to).await }
+        })
+        .await?;
+    Ok(Json(report))
+}
+
+/// Ticketed home games with no tickets generated.
+async fn api_admin_ticket_coverage(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+) -> Result<Json<gtm_models::TicketCoverage>, ApiError> {
+    let (team_id, game_types) = (config.team_id, ticketed_game_types(&config));
+    Ok(Json(
+        read.run(|pool| {
+            let game_types = &game_types;
+            async move { gtm_db::reports::ticket_coverage(&pool, team_id, game_types).await }
+        })
+        .await?,
+    ))
+}
+
+async fn api_admin_renewal_report(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<gtm_models::RenewalReport>, ApiError> {
+    let tz = display_tz(&config)?;
+    Ok(Json(
+        read.run(|pool| {
+            let season = params.season.clone();
+            async move { renewal_report(&pool, season, tz).await }
+        })
+        .await?,
+    ))
+}
+
+async fn api_admin_renewal_report_csv(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+
+    let tz = display_tz(&config)?;
+    let season = download_season(params.season, tz)?;
+    let report = read
+        .run(|pool| {
+            let season = Some(season.clone());
+            async move { renewal_report(&pool, season, tz).await }
+        })
+        .await?;
+    let filename = format!("gtm-{}-renewal.csv", report.season);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        gtm_db::export::renewal_csv(&report),
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+struct AttendanceGridQuery {
+    season: Option<String>,
+    /// `json` (the default) or `csv`.
+    format: Option<String>,
+}
+
+/// `GET /api/admin/export/attendance-grid` — games down, members across,
+/// seat counts in the cells.
+async fn api_admin_attendance_grid(
+    State(read): State<gtm_db::replica::ReadPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<AttendanceGridQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+
+    let tz = display_tz(&config)?;
+    let csv = match params.format.as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(other) => {
+            return Err(ApiError::bad_request(format!(
+                "Unknown format {other:?}; expected json or csv"
+            )));
+        }
+    };
+    let season = download_season(params.season, tz)?;
+    let grid = read
+        .run(|pool| {
+            let season = &season;
+            async move { gtm_db::reports::attendance_grid(&pool, season).await }
+        })
+        .await?;
+    if !csv {
+        return Ok(Json(grid).into_response());
+    }
+    let filename = format!("gtm-{season}-attendance.csv");
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        gtm_db::export::attendance_grid_csv(&grid),
+    )
+        .into_response())
+}
+
+// --- Export ---
+
+/// A season export as CSV, read and rendered one game at a time: the header
+/// first, then each game's lines.
+fn export_csv(
+    pool: AnyPool,
+    season: String,
+    kind: ExportKind,
+) -> impl futures_util::Stream<Item = anyhow::Result<String>> {
+    use gtm_db::export;
+
+    type Pending = (Vec<String>, std::vec::IntoIter<GamePk>);
+    futures_util::stream::try_unfold(None, move |state: Option<Pending>| {
+        let pool = pool.clone();
+        let season = season.clone();
+        async move {
+            let Some((seats, mut games)) = state else {
+                let seats = export::seat_labels(&pool).await?;
+                let games = export::export_games(&pool, &season).await?;
+                let header = match kind {
+                    ExportKind::Tickets => export::tickets_csv_header(),
+                    ExportKind::Allocation => export::allocation_csv_header(&seats),
+                };
+                return Ok(Some((header, Some((seats, games.into_iter())))));
+            };
+            let Some(game_pk) = games.next() else {
+                return Ok(None);
+            };
+            let rows = export::ticket_rows(&pool, game_pk).await?;
+            let lines = match kind {
+                ExportKind::Tickets => export::tickets_csv(&rows),
+                ExportKind::Allocation => export::allocation_csv(&seats, &rows),
+            };
+            Ok(Some((lines, Some((seats, games)))))
+        }
+    })
+}
+
+/// Stream an export as a CSV download.
+fn csv_download(pool: AnyPool, season: String, kind: ExportKind) -> axum::response::Response {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+
+    let filename = match kind {
+        ExportKind::Tickets => format!("gtm-{season}-tickets.csv"),
+        ExportKind::Allocation => format!("gtm-{season}-allocation.csv"),
+    };
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        axum::body::Body::from_stream(export_csv(pool, season, kind)),
+    )
+        .into_response()
+}
+
+async fn api_admin_allocation_export(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = download_season(params.season, tz)?;
+    Ok(csv_download(pool, season, ExportKind::Allocation))
+}
+
+async fn api_admin_tickets_export(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<axum::response::Response, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = download_season(params.season, tz)?;
+    Ok(csv_download(pool, season, ExportKind::Tickets))
+}
+
+// --- Game packets ---
+
+/// Each assigned member's packet for a game, in seat order, or `None` if
+/// the game doesn't exist.
+async fn game_packets(
+    pool: &AnyPool,
+    config: &gtm_config::Config,
+    game_pk: GamePk,
+) -> anyhow::Result<Option<Vec<(UserId, gtm_notify::packet::Packet)>>> {
+    use gtm_notify::packet::{Packet, PacketPromotion};
+
+    let tz = display_tz(config)?;
+    let Some(game) = gtm_db::get_game(pool, game_pk).await? else {
+        return Ok(None);
+    };
+    let start = if game.start_time_tbd != 0 {
+        chrono::NaiveDate::parse_from_str(&game.official_date, "%Y-%m-%d")
+            .map(|d| format!("{}, time TBD", d.format("%a %b %-d")))
+            .unwrap_or_else(|_| game.official_date.clone())
+    } else {
+        gtm_db::parse_timestamp(&game.game_date)
+            .map(|t| {
+                t.with_timezone(&tz)
+                    .format("%a %b %-d, %-I:%M %p")
+                    .to_string()
+            })
+            .unwrap_or_else(|| game.official_date.clone())
+    };
+    let promotions: Vec<PacketPromotion> = gtm_db::get_promotions_for_game(pool, game_pk)
+        .await?
+        .into_iter()
+        .map(|p| PacketPromotion {
+            name: p.name,
+            distribution: p.distribution,
+        })
+        .collect();
+
+    let tickets = gtm_db::list_tickets_for_game(pool, game_pk).await?;
+    let mut seats: std::collections::BTreeMap<UserId, Vec<String>> = Default::default();
+    for t in &tickets {
+        if let Some(user_id) = t.assigned_to {
+            seats.entry(user_id).or_default().push(seat_label(t));
+        }
+    }
+    let mut packets = Vec::new();
+    for (user_id, seats) in seats {
+        let Some(user) = gtm_db::get_user(pool, user_id).await? else {
+            continue;
+        };
+        packets.push((
+            user_id,
+            Packet {
+                member: user.name,
+                game: gtm_notify::templates::game_label(&game.official_date, &game.away_team_name),
+                start: start.clone(),
+                venue: game.venue_name.clone(),
+                seats,
+                gate: config.gate_info.clone(),
+                parking: config.parking_info.clone(),
+                promotions: promotions.clone(),
+            },
+        ));
+    }
+    Ok(Some(packets))
+}
+
+/// A ZIP with one PDF packet per assigned member.
+async fn api_admin_game_packets_zip(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(game_pk): Path<GamePk>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::http::header;
+    use axum::response::IntoResponse;
+
+    let packets = game_packets(&pool, &config, game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+    let files: Vec<(String, Vec<u8>)> = packets
+        .iter()
+        .map(|(user_id, packet)| {
+            let name: String = packet
+                .member
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect();
+            (format!("{user_id}-{name}.pdf"), packet.pdf())
+        })
+        .collect();
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"gtm-{game_pk}-packets.zip\""),
+            ),
+        ],
+        gtm_notify::packet::zip(&files),
+    )
+        .into_response())
+}
+
+/// Email each assigned member their packet.
+async fn api_admin_send_game_packets(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(game_pk): Path<GamePk>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let packets = game_packets(&pool, &config, game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+    for (user_id, packet) in &packets {
+        queue_email(
+            &pool,
+            *user_id,
+            game_pk,
+            gtm_db::notifications::GAME_PACKET,
+            packet.email(),
+        )
+        .await?;
+    }
+    info!(%game_pk, queued = packets.len(), "Game packets queued");
+    Ok(Envelope::ok(json!({ "queued": packets.len() })))
+}
+
+// --- Ledger ---
+
+/// Every member's ledger plus the season totals across the group.
+#[derive(Serialize)]
+struct AdminLedgerResponse {
+    season: String,
+    members: Vec<gtm_models::MemberLedger>,
+    seats: i64,
+    amount: i64,
+}
+
+async fn api_admin_ledger(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<AdminLedgerResponse>, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = season_or_current(params.season, tz);
+    let members = gtm_db::ledger::member_ledgers(&pool, &season, None).await?;
+    Ok(Json(AdminLedgerResponse {
+        seats: members.iter().map(|m| m.seats).sum(),
+        amount: members.iter().map(|m| m.amount).sum(),
+        season,
+        members,
+    }))
+}
+
+async fn api_my_ledger(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<gtm_models::MemberLedger>, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = season_or_current(params.season, tz);
+    let ledger = gtm_db::ledger::member_ledgers(&pool, &season, Some(user.id))
+        .await?
+        .pop()
+        .unwrap_or(gtm_models::MemberLedger {
+            user_id: user.id,
+            user_name: user.name,
+            season,
+            games: Vec::new(),
+            seats: 0,
+            amount: 0,
+        });
+    Ok(Json(ledger))
+}
+
+async fn api_admin_split_rules(
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::SplitRule>>, ApiError> {
+    gtm_db::ledger::split_rules(&pool)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize)]
+struct SplitShare {
+    user_id: UserId,
+    /// Percent of the cost, e.g. 50 or 33.33.
+    percent: f64,
+}
+
+#[derive(Deserialize)]
+struct SplitRulesRequest {
+    /// The seat the shares apply to; omit for the plan-wide split.
+    seat_id: Option<SeatId>,
+    /// Must add up to 100; empty removes the rules.
+    shares: Vec<SplitShare>,
+}
+
+/// Replace the split rules for one seat, or the plan-wide ones.
+async fn api_admin_set_split_rules(
+    State(pool): State<AnyPool>,
+    Json(body): Json<SplitRulesRequest>,
+) -> Result<Json<Envelope<Vec<gtm_models::SplitRule>>>, ApiError> {
+    let shares: Vec<(UserId, i64)> = body
+        .shares
+        .iter()
+        .map(|s| (s.user_id, (s.percent * 100.0).round() as i64))
+        .collect();
+    gtm_db::ledger::set_split_rules(&pool, body.seat_id, &shares)
+        .await
+        .map_err(ApiError::invalid)?;
+    let rules = gtm_db::ledger::split_rules(&pool).await?;
+    Ok(Envelope::ok(rules))
+}
+
+// --- Seat limits ---
+
+async fn api_admin_seat_limits(
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::SeatLimitRule>>, ApiError> {
+    gtm_db::seat_limits::rules(&pool)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize)]
+struct SeatLimitRuleBody {
+    /// MLB game-type code (R, S, P, ...); omit to match every type.
+    game_type: Option<String>,
+    /// Game tag, e.g. premium or weekday; omit to match every game.
+    tag: Option<String>,
+    max_seats: i64,
+}
+
+/// Replace the seat limit rules.
+async fn api_admin_set_seat_limits(
+    State(pool): State<AnyPool>,
+    Json(body): Json<Vec<SeatLimitRuleBody>>,
+) -> Result<Json<Envelope<Vec<gtm_models::SeatLimitRule>>>, ApiError> {
+    let rules: Vec<gtm_db::seat_limits::NewRule> = body
+        .into_iter()
+        .map(|r| gtm_db::seat_limits::NewRule {
+            game_type: r.game_type,
+            tag: r.tag,
+            max_seats: r.max_seats,
+        })
+        .collect();
+    let rules = gtm_db::seat_limits::set_rules(&pool, &rules)
+        .await
+        .map_err(ApiError::invalid)?;
+    Ok(Envelope::ok(rules))
+}
+
+#[derive(Deserialize)]
+struct GameSeatLimitBody {
+    /// Null goes back to the rules.
+    max_seats: Option<i64>,
+}
+
+async fn api_admin_set_game_seat_limit(
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+    Json(body): Json<GameSeatLimitBody>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let updated = gtm_db::seat_limits::set_game_override(&pool, game_pk, body.max_seats)
+        .await
+        .map_err(ApiError::invalid)?;
+    if updated {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Game not found"))
+    }
+}
+
+async fn api_admin_game_attendance(
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+) -> Result<Json<Vec<gtm_models::GameAttendance>>, ApiError> {
+    gtm_db::attendance::for_game(&pool, game_pk)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+async fn api_admin_set_game_attendance(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    Path((game_pk, user_id)): Path<(GamePk, UserId)>,
+    Json(body): Json<AttendanceBody>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if gtm_db::get_game(&pool, game_pk).await?.is_none() {
+        return Err(ApiError::not_found("Game not found"));
+    }
+    if gtm_db::get_user(&pool, user_id).await?.is_none() {
+        return Err(ApiError::not_found("User not found"));
+    }
+    gtm_db::attendance::upsert(&pool, game_pk, user_id, body.attended, Some(admin.id)).await?;
+    Ok(Json(json!({ "status": "ok" })))
+}
+
+// --- Admin: seating parties together ---
+
+#[derive(Deserialize)]
+struct AdjacentSeatsQuery {
+    count: usize,
+}
+
+/// `count` free seats at a game side by side, for seating a party by hand.
+/// `tickets` is null if no run of free seats is long enough.
+async fn api_admin_adjacent_seats(
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+    Query(params): Query<AdjacentSeatsQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if params.count == 0 {
+        return Err(ApiError::bad_request("count must be at least 1"));
+    }
+    if gtm_db::get_game(&pool, game_pk).await?.is_none() {
+        return Err(ApiError::not_found("Game not found"));
+    }
+    let tickets = gtm_db::seating::find_adjacent_available(&pool, game_pk, params.count).await?;
+    Ok(Json(json!({ "count": params.count, "tickets": tickets })))
+}
+
+// --- Admin: ticket forwarding ---
+
+/// A game's assigned tickets and whether each has been forwarded to the
+/// member holding it.
+async fn api_admin_game_forwarding(
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+) -> Result<Json<Vec<gtm_models::TicketForward>>, ApiError> {
+    if gtm_db::get_game(&pool, game_pk).await?.is_none() {
+        return Err(ApiError::not_found("Game not found"));
+    }
+    Ok(Json(gtm_db::forwarding::checklist(&pool, game_pk).await?))
+}
+
+#[derive(Deserialize)]
+struct ForwardBody {
+    /// One of `gtm_db::forwarding::METHODS`.
+    method: String,
+    /// YYYY-MM-DD; today (in the display time zone) if omitted.
+    forwarded_on: Option<String>,
+    /// MLB's id or barcode for the ticket; ignored for a whole game.
+    external_ticket_id: Option<String>,
+}
+
+impl ForwardBody {
+    fn forward(&self, tz: chrono_tz::Tz) -> Result<gtm_db::forwarding::Forward, ApiError> {
+        let today = Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string();
+        gtm_db::forwarding::Forward::new(
+            self.forwarded_on.as_deref().unwrap_or(&today),
+            &self.method,
+            self.external_ticket_id.as_deref(),
+        )
+        .map_err(ApiError::invalid)
+    }
+}
+
+/// Record that every ticket for the game still to forward has been sent.
+async fn api_admin_forward_game(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(game_pk): Path<GamePk>,
+    Json(body): Json<ForwardBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let forward = body.forward(display_tz(&config)?)?;
+    if gtm_db::get_game(&pool, game_pk).await?.is_none() {
+        return Err(ApiError::not_found("Game not found"));
+    }
+    let forwarded =
+        gtm_db::forwarding::record_game(&pool, game_pk, &forward, Some(admin.id)).await?;
+    info!(%game_pk, forwarded, method = %forward.method, "Game tickets forwarded");
+    Ok(Envelope::ok(
+        json!({ "status": "ok", "forwarded": forwarded }),
+    ))
+}
+
+/// Record that a ticket was forwarded to the member holding it.
+async fn api_admin_forward_ticket(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(ticket_id): Path<TicketId>,
+    Json(body): Json<ForwardBody>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let forward = body.forward(display_tz(&config)?)?;
+    let ticket = gtm_db::get_ticket(&pool, ticket_id)
+        .await?
+        .ok_or(ApiError::not_found("Ticket not found"))?;
+    if ticket.assigned_to.is_none() {
+        return Err(ApiError::bad_request(
+            "Only assigned tickets can be forwarded",
+        ));
+    }
+    if !gtm_db::forwarding::record(&pool, ticket_id, &forward, Some(admin.id)).await? {
+        return Err(ApiError::conflict("The ticket was released meanwhile"));
+    }
+    Ok(Json(json!({ "status": "ok" })))
+}
+
+async fn api_admin_unforward_ticket(
+    State(pool): State<AnyPool>,
+    Path(ticket_id): Path<TicketId>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if gtm_db::forwarding::clear(&pool, ticket_id).await? {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Ticket not found"))
+    }
+}
+
+/// A game's tags: the ones admins set, and all that seat limit rules see.
+#[derive(Serialize)]
+struct GameTagsResponse {
+    tags: Vec<String>,
+    effective: Vec<String>,
+    max_seats: i64,
+}
+
+async fn game_tags_response(pool: &AnyPool, game_pk: GamePk) -> Result<GameTagsResponse, ApiError> {
+    let game = gtm_db::get_game(pool, game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+    let policy = gtm_db::seat_limits::SeatLimitPolicy::load(pool).await?;
+    let mut effective: Vec<String> = policy.tags_for(&game).into_iter().collect();
+    effective.sort();
+    Ok(GameTagsResponse {
+        tags: gtm_db::seat_limits::game_tags(pool, game_pk).await?,
+        effective,
+        max_seats: policy.max_seats(&game),
+    })
+}
+
+async fn api_admin_game_tags(
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+) -> Result<Json<GameTagsResponse>, ApiError> {
+    game_tags_response(&pool, game_pk).await.map(Json)
+}
+
+#[derive(Deserialize)]
+struct GameTagsBody {
+    tags: Vec<String>,
+}
+
+/// Replace the tags admins set on a game.
+async fn api_admin_set_game_tags(
+    State(pool): State<AnyPool>,
+    Path(game_pk): Path<GamePk>,
+    Json(body): Json<GameTagsBody>,
+) -> Result<Json<Envelope<GameTagsResponse>>, ApiError> {
+    if gtm_db::get_game(&pool, game_pk).await?.is_none() {
+        return Err(ApiError::not_found("Game not found"));
+    }
+    gtm_db::seat_limits::set_game_tags(&pool, game_pk, &body.tags).await?;
+    Ok(Envelope::ok(game_tags_response(&pool, game_pk).await?))
+}
+
+/// Members' computed shares for a season, by invoice period.
+#[derive(Serialize)]
+struct CostSplitResponse {
+    season: String,
+    periods: Vec<gtm_models::CostSplitPeriod>,
+}
+
+async fn api_admin_cost_split(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<CostSplitResponse>, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = season_or_current(params.season, tz);
+    let periods = gtm_db::ledger::cost_split(&pool, &season, None).await?;
+    Ok(Json(CostSplitResponse { season, periods }))
+}
+
+/// The member's own shares by invoice period; period totals are the group's.
+async fn api_my_cost_split(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Query(params): Query<SeasonQuery>,
+) -> Result<Json<CostSplitResponse>, ApiError> {
+    let tz = display_tz(&config)?;
+    let season = season_or_current(params.season, tz);
+    let periods = gtm_db::ledger::cost_split(&pool, &season, Some(user.id)).await?;
+    Ok(Json(CostSplitResponse { season, periods }))
+}
+
+// --- Invoices ---
+
+#[derive(Deserialize)]
+struct InvoiceListQuery {
+    period: Option<String>,
+}
+
+async fn api_admin_invoices(
+    State(pool): State<AnyPool>,
+    Query(params): Query<InvoiceListQuery>,
+) -> Result<Json<Vec<gtm_models::Invoice>>, ApiError> {
+    gtm_db::invoices::list(&pool, params.period.as_deref(), None)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize)]
+struct GenerateInvoicesBody {
+    /// YYYY-MM.
+    period: String,
+}
+
+/// Bill each member their cost-split shares for a period. Members already
+/// invoiced for it are left alone.
+async fn api_admin_generate_invoices(
+    State(pool): State<AnyPool>,
+    Json(body): Json<GenerateInvoicesBody>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let run = gtm_db::invoices::generate(&pool, &body.period)
+        .await
+        .map_err(ApiError::invalid)?;
+    info!(period = %body.period, created = run.created, existing = run.existing, "Invoices generated");
+    Ok(Envelope::ok(json!({
+        "period": body.period,
+        "created": run.created,
+        "existing": run.existing,
+    })))
+}
+
+async fn api_admin_invoice(
+    State(pool): State<AnyPool>,
+    Path(invoice_id): Path<i64>,
+) -> Result<Json<gtm_models::InvoiceDetail>, ApiError> {
+    gtm_db::invoices::detail(&pool, invoice_id)
+        .await?
+        .map(Json)
+        .ok_or(ApiError::not_found("Invoice not found"))
+}
+
+/// Delete an invoice with no payments, so its period can be regenerated.
+async fn api_admin_delete_invoice(
+    State(pool): State<AnyPool>,
+    Path(invoice_id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let deleted = gtm_db::invoices::delete_unpaid(&pool, invoice_id).await?;
+    if deleted {
+        info!(invoice_id, "Invoice deleted");
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::conflict("Invoice not found or has payments"))
+    }
+}
+
+#[derive(Deserialize)]
+struct RecordPaymentBody {
+    /// Cents.
+    amount: i64,
+    method: Option<String>,
+    note: Option<String>,
+    /// Lines to pay; omitted pays the oldest unpaid lines first.
+    #[serde(default)]
+    line_ids: Vec<i64>,
+}
+
+async fn api_admin_record_payment(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    Path(invoice_id): Path<i64>,
+    Json(body): Json<RecordPaymentBody>,
+) -> Result<Json<Envelope<gtm_models::InvoiceDetail>>, ApiError> {
+    let payment = gtm_db::invoices::NewPayment {
+        amount: body.amount,
+        method: body.method.as_deref(),
+        note: body.note.as_deref(),
+        recorded_by: Some(admin.id),
+        line_ids: &body.line_ids,
+    };
+    let invoice = gtm_db::invoices::record_payment(&pool, invoice_id, &payment)
+        .await
+        .map_err(ApiError::invalid)?
+        .ok_or(ApiError::not_found("Invoice not found"))?;
+    info!(invoice_id, amount = body.amount, status = %invoice.invoice.status, "Payment recorded");
+    Ok(Envelope::ok(invoice))
+}
 
-            let total_allocated: i64 = entries.iter().map(|e| e.user_tickets.len() as i64).sum();
-            let total_requested: i64 = entries.iter().map(|e| e.seats_requested).sum();
-            let games_allocated = entries
-                .iter()
-                .filter(|e| !e.user_tickets.is_empty())
-                .count() as i64;
-            let games_requested = entries.len() as i64;
+/// The member's invoices with their lines and payments, newest first.
+async fn api_my_invoices(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::InvoiceDetail>>, ApiError> {
+    gtm_db::invoices::for_user(&pool, user.id)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
 
-            UserAllocationSection {
-                user_id: uid,
-                user_name,
-                total_allocated,
-                total_requested,
-                games_allocated,
-                games_requested,
-                entries,
-            }
-        })
-        .collect();
+// --- Online payment (Stripe) ---
 
-    sections.sort_by(|a, b| a.user_name.cmp(&b.user_name));
+/// Create a checkout link for what's still owed on `invoice` and remember
+/// it on the invoice.
+async fn create_invoice_checkout(
+    pool: &AnyPool,
+    payments: &gtm_payments::Payments,
+    invoice: &gtm_models::Invoice,
+) -> Result<gtm_payments::CheckoutLink, ApiError> {
+    if !payments.is_enabled() {
+        return Err(ApiError::unavailable("Online payment is not configured"));
+    }
+    let owed = invoice.total - invoice.paid;
+    if owed <= 0 {
+        return Err(ApiError::conflict("Invoice is already paid"));
+    }
+    let month = chrono::NaiveDate::parse_from_str(&format!("{}-01", invoice.period), "%Y-%m-%d")
+        .map(|d| d.format("%B %Y").to_string())
+        .unwrap_or_else(|_| invoice.period.clone());
+    let email = gtm_db::get_user(pool, invoice.user_id)
+        .await?
+        .map(|u| u.email);
+    let charge = gtm_payments::InvoiceCharge {
+        invoice_id: invoice.id,
+        amount: owed,
+        description: format!("Season tickets, {month}"),
+        email,
+    };
+    let link = payments
+        .checkout_link(&charge)
+        .await
+        .map_err(|e| ApiError::upstream(e.to_string()))?;
+    gtm_db::invoices::set_checkout(pool, invoice.id, &link.id, &link.url).await?;
+    Ok(link)
+}
 
-    Ok(Json(sections))
+/// Start paying one of the member's own invoices online.
+async fn api_my_invoice_pay(
+    RequireMember(user): RequireMember,
+    State(pool): State<AnyPool>,
+    State(payments): State<Arc<gtm_payments::Payments>>,
+    Path(invoice_id): Path<i64>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let invoice = gtm_db::invoices::get(&pool, invoice_id)
+        .await?
+        .filter(|i| i.user_id == user.id)
+        .ok_or(ApiError::not_found("Invoice not found"))?;
+    let link = create_invoice_checkout(&pool, &payments, &invoice).await?;
+    Ok(Envelope::ok(json!({ "url": link.url })))
 }
 
-async fn api_admin_requests(
-    auth_user: AuthUser,
+/// A checkout link for any invoice, e.g. to send to the member.
+async fn api_admin_invoice_payment_link(
+    State(pool): State<AnyPool>,
+    State(payments): State<Arc<gtm_payments::Payments>>,
+    Path(invoice_id): Path<i64>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let invoice = gtm_db::invoices::get(&pool, invoice_id)
+        .await?
+        .ok_or(ApiError::not_found("Invoice not found"))?;
+    let link = create_invoice_checkout(&pool, &payments, &invoice).await?;
+    Ok(Envelope::ok(json!({ "url": link.url })))
+}
+
+/// Stripe webhook: a completed checkout records a payment on its invoice.
+/// Other events are acknowledged and ignored.
+async fn api_stripe_webhook(
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    State(payments): State<Arc<gtm_payments::Payments>>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    // Stripe retries anything but a 2xx for days, so only a bad signature
+    // or a failure worth retrying (the database, missing config) is an error;
+    // events that can never be recorded are acknowledged and logged.
+    if !payments.is_enabled() {
+        warn!("Stripe webhook received but online payment is not configured");
+        return Err(ApiError::unavailable("Online payment is not configured"));
+    }
+    let signature = headers
+        .get("stripe-signature")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    payments
+        .verify_webhook(signature, &body, Utc::now().timestamp())
+        .map_err(|e| {
+            warn!("Stripe webhook rejected: {e}");
+            ApiError::bad_request("Invalid signature")
+        })?;
+    let checkout = match gtm_payments::parse_completed_checkout(&body) {
+        Ok(Some(checkout)) => checkout,
+        Ok(None) => return Ok(Json(json!({ "status": "ignored" }))),
+        Err(e) => {
+            warn!("Unusable Stripe event: {e:#}");
+            return Ok(Json(json!({ "status": "ignored" })));
+        }
+    };
+    let Some(recorded) = gtm_db::invoices::record_online_payment(
+        &pool,
+        checkout.invoice_id,
+        &checkout.session_id,
+        checkout.amount,
+    )
+    .await?
+    else {
+        warn!(
+            invoice_id = checkout.invoice_id,
+            session = %checkout.session_id,
+            "Stripe payment for an unknown invoice; refund it in Stripe"
+        );
+        return Ok(Json(json!({ "status": "ignored" })));
+    };
+    if recorded.duplicate {
+        return Ok(Json(json!({ "status": "ok" })));
+    }
+    let invoice = &recorded.invoice.invoice;
+    info!(
+        invoice_id = invoice.id,
+        amount = checkout.amount,
+        status = %invoice.status,
+        "Online payment received"
+    );
+    if recorded.overpaid > 0 {
+        warn!(
+            invoice_id = invoice.id,
+            overpaid = recorded.overpaid,
+            "Online payment was more than the invoice owed"
+        );
+        send_overpayment_alert(&config, invoice, recorded.overpaid).await;
+    }
+    Ok(Json(json!({ "status": "ok" })))
+}
+
+/// Email admins that an online payment needs a partial refund.
+async fn send_overpayment_alert(
+    config: &gtm_config::Config,
+    invoice: &gtm_models::Invoice,
+    overpaid: i64,
+) {
+    let (subject, body) = gtm_notify::templates::email_online_overpayment(
+        &invoice.user_name,
+        &invoice.period,
+        overpaid,
+    );
+    let notifier = build_notifier(config);
+    for to in &config.admin_emails {
+        if let Err(e) = notifier
+            .send(&gtm_notify::Notification::email(to, &subject, &body))
+            .await
+        {
+            warn!(to = %to, "Overpayment alert failed: {e}");
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SeatGroupFaceValueRequest {
+    section: String,
+    row: String,
+    /// Cents per game; null clears it.
+    face_value: Option<i64>,
+}
+
+async fn api_admin_seat_group_face_value(
+    State(pool): State<AnyPool>,
+    Json(body): Json<SeatGroupFaceValueRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let updated =
+        gtm_db::update_seat_group_face_value(&pool, &body.section, &body.row, body.face_value)
+            .await?;
+    if updated == 0 {
+        return Err(ApiError::not_found("No seats found for that section/row"));
+    }
+    Ok(Json(json!({ "status": "ok", "updated": updated })))
+}
+
+#[derive(Deserialize)]
+struct TicketCostRequest {
+    /// Cents; null falls back to the seat's face value.
+    cost: Option<i64>,
+}
+
+async fn api_admin_ticket_cost(
+    State(pool): State<AnyPool>,
+    Path(ticket_id): Path<TicketId>,
+    Json(body): Json<TicketCostRequest>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let updated = gtm_db::set_ticket_cost(&pool, ticket_id, body.cost).await?;
+    if updated {
+        Ok(Envelope::ok(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Ticket not found"))
+    }
+}
+
+#[derive(Deserialize)]
+struct TicketResaleRequest {
+    /// Cents; null clears the resale.
+    amount: Option<i64>,
+}
+
+async fn api_admin_ticket_resale(
+    State(pool): State<AnyPool>,
+    Path(ticket_id): Path<TicketId>,
+    Json(body): Json<TicketResaleRequest>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    if body.amount.is_some_and(|a| a < 0) {
+        return Err(ApiError::bad_request("Resale amount can't be negative"));
+    }
+    let updated = gtm_db::set_ticket_resale(&pool, ticket_id, body.amount).await?;
+    if updated {
+        Ok(Envelope::ok(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Ticket not found"))
+    }
+}
+
+// --- Note templates ---
+
+#[derive(Deserialize)]
+struct NoteTemplateRequest {
+    name: String,
+    body: String,
+}
+
+async fn api_admin_note_templates(
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::NoteTemplate>>, ApiError> {
+    Ok(Json(gtm_db::note_templates::list(&pool).await?))
+}
+
+async fn api_admin_create_note_template(
+    State(pool): State<AnyPool>,
+    Json(body): Json<NoteTemplateRequest>,
+) -> Result<Json<Envelope<gtm_models::NoteTemplate>>, ApiError> {
+    let template = gtm_db::note_templates::create(&pool, &body.name, &body.body)
+        .await
+        .map_err(ApiError::invalid)?;
+    Ok(Envelope::ok(template))
+}
+
+async fn api_admin_update_note_template(
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+    Json(body): Json<NoteTemplateRequest>,
+) -> Result<Json<Envelope<gtm_models::NoteTemplate>>, ApiError> {
+    gtm_db::note_templates::update(&pool, id, &body.name, &body.body)
+        .await
+        .map_err(ApiError::invalid)?
+        .map(Envelope::ok)
+        .ok_or(ApiError::not_found("Note template not found"))
+}
+
+async fn api_admin_delete_note_template(
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if gtm_db::note_templates::delete(&pool, id).await? {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Note template not found"))
+    }
+}
+
+#[derive(Deserialize)]
+struct ApplyNoteTemplateRequest {
+    /// Tickets to set the note on; or give `game_pk` instead.
+    ticket_ids: Option<Vec<TicketId>>,
+    game_pk: Option<GamePk>,
+    /// With `game_pk`, only tickets with this status.
+    status: Option<String>,
+    #[serde(default)]
+    dry_run: bool,
+    confirm_token: Option<String>,
+}
+
+/// The tickets `body` names: its `ticket_ids`, or `game_pk` with an
+/// optional `status`.
+fn note_target(
+    body: &ApplyNoteTemplateRequest,
+) -> Result<gtm_db::note_templates::NoteTarget<'_>, ApiError> {
+    use gtm_db::note_templates::NoteTarget;
+
+    match (&body.ticket_ids, body.game_pk) {
+        (Some(ids), None) => Ok(NoteTarget::Tickets(ids)),
+        (None, Some(game_pk)) => Ok(NoteTarget::Game {
+            game_pk,
+            status: body.status.as_deref(),
+        }),
+        _ => Err(ApiError::bad_request("Give either ticket_ids or game_pk")),
+    }
+}
+
+async fn api_admin_apply_note_template(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    State(config): State<Arc<gtm_config::Config>>,
+    Path(id): Path<i64>,
+    Json(body): Json<ApplyNoteTemplateRequest>,
+) -> Result<axum::response::Response, ApiError> {
+    use axum::response::IntoResponse;
+    use gtm_db::note_templates::NoteTarget;
+
+    let target = note_target(&body)?;
+    let scope = match target {
+        NoteTarget::Tickets(ids) => format!("template {id}: {} tickets", ids.len()),
+        NoteTarget::Game { game_pk, status } => {
+            format!("template {id}: game {game_pk} {}", status.unwrap_or("all"))
+        }
+    };
+    let affected = gtm_db::note_templates::count_targets(&pool, target).await?;
+    let guard = BulkGuardQuery {
+        dry_run: body.dry_run,
+        confirm_token: body.confirm_token.clone(),
+    };
+    let today = Utc::now()
+        .with_timezone(&display_tz(&config)?)
+        .date_naive()
+        .to_string();
+    let work = {
+        let pool = pool.clone();
+        async move {
+            let target = note_target(&body)?;
+            Ok(gtm_db::note_templates::apply(&pool, id, target, Some(admin.id), &today).await?)
+        }
+    };
+    let applied = match service::guarded(
+        pool,
+        config,
+        "tickets.apply_note",
+        scope,
+        affected as i64,
+        guard,
+        work,
+    )
+    .await?
+    {
+        Guarded::Preview(preview) => return Ok(Json(preview).into_response()),
+        Guarded::Done(applied) => applied.ok_or(ApiError::not_found("Note template not found"))?,
+    };
+    info!(template_id = id, applied, "Note template applied");
+    Ok(Envelope::ok(json!({ "applied": applied })).into_response())
+}
+
+async fn api_admin_promotion_interest(
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::PromotionInterestCount>>, ApiError> {
+    gtm_db::promotion_interest_counts(&pool)
+        .await
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+// --- External refs ---
+
+#[derive(Deserialize)]
+struct CreateExternalRefRequest {
+    entity_type: EntityKind,
+    entity_id: i64,
+    system: String,
+    external_id: String,
+}
+
+#[derive(Deserialize)]
+struct UpdateExternalRefRequest {
+    external_id: String,
+}
+
+/// `GET /api/admin/external-refs` — filter by `entity_type`, `entity_id`,
+/// `system` and `external_id`, e.g. to find the game a Notion page mirrors.
+async fn api_admin_external_refs(
+    State(pool): State<AnyPool>,
+    Query(filter): Query<gtm_db::external_refs::RefFilter>,
+) -> Result<Json<Vec<gtm_models::ExternalRef>>, ApiError> {
+    Ok(Json(gtm_db::external_refs::list(&pool, &filter).await?))
+}
+
+async fn api_admin_create_external_ref(
+    State(pool): State<AnyPool>,
+    Json(body): Json<CreateExternalRefRequest>,
+) -> Result<Json<Envelope<gtm_models::ExternalRef>>, ApiError> {
+    gtm_db::external_refs::create(
+        &pool,
+        body.entity_type,
+        body.entity_id,
+        &body.system,
+        &body.external_id,
+    )
+    .await
+    .map_err(ApiError::invalid)?
+    .map(Envelope::ok)
+    .ok_or_else(|| ApiError::not_found(format!("No {} {}", body.entity_type, body.entity_id)))
+}
+
+async fn api_admin_update_external_ref(
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+    Json(body): Json<UpdateExternalRefRequest>,
+) -> Result<Json<Envelope<gtm_models::ExternalRef>>, ApiError> {
+    gtm_db::external_refs::update(&pool, id, &body.external_id)
+        .await
+        .map_err(ApiError::invalid)?
+        .map(Envelope::ok)
+        .ok_or(ApiError::not_found("External ref not found"))
+}
+
+async fn api_admin_delete_external_ref(
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if gtm_db::external_refs::delete(&pool, id).await? {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("External ref not found"))
+    }
+}
+
+// --- Admin: Webhooks ---
+
+/// Most deliveries listed per webhook.
+const WEBHOOK_DELIVERY_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+struct CreateWebhookRequest {
+    url: String,
+    events: Vec<String>,
+}
+
+/// A newly registered webhook: the only time its signing secret is shown.
+#[derive(Serialize)]
+struct CreatedWebhook {
+    secret: String,
+    #[serde(flatten)]
+    webhook: gtm_models::Webhook,
+}
+
+async fn api_admin_webhooks(
     State(pool): State<AnyPool>,
-) -> Result<Json<Vec<gtm_models::TicketRequest>>, (StatusCode, String)> {
-    let _user = resolve_user(&auth_user, &pool).await?;
-    require_admin(&auth_user)?;
+) -> Result<Json<Vec<gtm_models::Webhook>>, ApiError> {
+    Ok(Json(gtm_db::webhooks::list(&pool).await?))
+}
+
+async fn api_admin_create_webhook(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    Json(body): Json<CreateWebhookRequest>,
+) -> Result<Json<Envelope<CreatedWebhook>>, ApiError> {
+    use rand::Rng;
+
+    gtm_notify::webhooks::resolve_target(body.url.trim())
+        .await
+        .map_err(ApiError::invalid)?;
+    let secret = format!("whsec_{:032x}", rand::thread_rng().r#gen::<u128>());
+    let webhook = gtm_db::webhooks::create(&pool, &body.url, &body.events, &secret, Some(admin.id))
+        .await
+        .map_err(ApiError::invalid)?;
+    info!(webhook_id = webhook.id, url = %webhook.url, "Webhook registered");
+    Ok(Envelope::ok(CreatedWebhook { secret, webhook }))
+}
+
+async fn api_admin_delete_webhook(
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    if gtm_db::webhooks::delete(&pool, id).await? {
+        Ok(Json(json!({ "status": "ok" })))
+    } else {
+        Err(ApiError::not_found("Webhook not found"))
+    }
+}
+
+/// `GET /api/admin/webhooks/{id}/deliveries` — the webhook's most recent
+/// deliveries, newest first, with each attempt's outcome.
+async fn api_admin_webhook_deliveries(
+    State(pool): State<AnyPool>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<gtm_models::WebhookDelivery>>, ApiError> {
+    Ok(Json(
+        gtm_db::webhooks::list_deliveries(&pool, id, WEBHOOK_DELIVERY_LIMIT).await?,
+    ))
+}
 
-    gtm_db::list_all_pending_requests(&pool)
+// --- Admin: Availability polls ---
+
+#[derive(Deserialize)]
+struct CreatePollBody {
+    game_pk: GamePk,
+    question: Option<String>,
+}
+
+async fn api_admin_create_poll(
+    RequireAdmin(admin): RequireAdmin,
+    State(pool): State<AnyPool>,
+    Json(body): Json<CreatePollBody>,
+) -> Result<Json<Envelope<gtm_models::PollSummary>>, ApiError> {
+    gtm_db::get_game(&pool, body.game_pk)
+        .await?
+        .ok_or(ApiError::not_found("Game not found"))?;
+    let poll = gtm_db::polls::create(&pool, body.game_pk, body.question.as_deref(), admin.id)
+        .await?
+        .ok_or(ApiError::conflict("That game already has a poll"))?;
+    info!(poll_id = poll.id, game_pk = %body.game_pk, "Availability poll opened");
+    Ok(Envelope::ok(poll))
+}
+
+async fn api_admin_polls(
+    State(pool): State<AnyPool>,
+) -> Result<Json<Vec<gtm_models::PollSummary>>, ApiError> {
+    gtm_db::polls::list(&pool)
         .await
         .map(Json)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        .map_err(ApiError::from)
+}
+
+async fn api_admin_poll_results(
+    State(pool): State<AnyPool>,
+    Path(poll_id): Path<i64>,
+) -> Result<Json<gtm_models::PollResults>, ApiError> {
+    gtm_db::polls::results(&pool, poll_id)
+        .await?
+        .map(Json)
+        .ok_or(ApiError::not_found("Poll not found"))
+}
+
+/// Close a poll, turning its "yes" answers into pending requests for the
+/// allocation engine.
+async fn api_admin_close_poll(
+    State(pool): State<AnyPool>,
+    Path(poll_id): Path<i64>,
+) -> Result<Json<Envelope<serde_json::Value>>, ApiError> {
+    let created = gtm_db::polls::close(&pool, poll_id)
+        .await?
+        .ok_or(ApiError::conflict("Poll not found or already closed"))?;
+    info!(poll_id, created, "Availability poll closed");
+    Ok(Envelope::ok(
+        json!({ "status": "ok", "poll_id": poll_id, "requests_created": created }),
+    ))
+}
+
+// --- Inbound email ---
+
+/// Fields posted by a Mailgun route (`forward()` action). SES relays should
+/// post the same form fields.
+#[derive(Deserialize)]
+struct InboundEmailForm {
+    sender: String,
+    #[serde(default)]
+    subject: String,
+    #[serde(default, rename = "body-plain")]
+    body_plain: String,
+    #[serde(rename = "stripped-text")]
+    stripped_text: Option<String>,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default)]
+    token: String,
+    #[serde(default)]
+    signature: String,
+}
+
+/// Extract the bare address from `Name <addr@example.com>` or `addr@example.com`.
+fn email_address(sender: &str) -> &str {
+    match (sender.find('<'), sender.rfind('>')) {
+        (Some(start), Some(end)) if start < end => sender[start + 1..end].trim(),
+        _ => sender.trim(),
+    }
+}
+
+async fn api_inbound_email(
+    State(state): State<AppState>,
+    Form(form): Form<InboundEmailForm>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let Some(signing_key) = state.config.mailgun_signing_key.as_deref() else {
+        warn!("Inbound email received but no signing key is configured");
+        return Err(ApiError::unavailable("Inbound email is not configured"));
+    };
+    if !gtm_notify::inbound::verify_mailgun_signature(
+        signing_key,
+        &form.timestamp,
+        &form.token,
+        &form.signature,
+        chrono::Utc::now(),
+    ) {
+        warn!(sender = %form.sender, "Inbound email signature verification failed");
+        return Err(ApiError::unauthorized("Invalid signature"));
+    }
+    let (applied, errors) =
+        service::inbound_email(state.pool, state.config, state.notifier, state.gcal, form).await?;
+    Ok(Json(json!({
+        "status": "ok",
+        "applied": applied,
+        "errors": errors,
+    })))
 }
 
 async fn serve_spa(State(state): State<AppState>) -> axum::response::Html<String> {
@@ -1214,7 +6728,9 @@ async fn run_server(port: u16, pool: AnyPool, config: &gtm_config::Config) -> an
     let index_path = "frontend/dist/index.html";
     let raw_html = std::fs::read_to_string(index_path)
         .unwrap_or_else(|_| "<html><body>Frontend not built</body></html>".to_string());
+    let local_auth = local_auth(config)?;
     let config_json = serde_json::json!({
+        "auth_mode": config.auth_mode,
         "auth0_domain": config.auth0_domain,
         "auth0_client_id": config.auth0_client_id,
         "auth0_audience": config.auth0_audience,
@@ -1223,57 +6739,245 @@ async fn run_server(port: u16, pool: AnyPool, config: &gtm_config::Config) -> an
     let spa_html = Arc::new(raw_html.replace("</head>", &format!("{config_script}</head>")));
     info!("SPA config injected into {index_path}");
 
-    // Fetch JWKS from Auth0 at startup
-    let jwks_keys = fetch_jwks(&config.auth0_domain).await?;
-    let auth_config = Arc::new(AuthConfig {
-        jwks_keys,
-        audience: config.auth0_audience.clone(),
-        issuer: format!("https://{}/", config.auth0_domain),
-    });
+    let auth_config = if local_auth {
+        warn_local_auth(config);
+        Arc::new(AuthConfig::local(&config.admin_emails))
+    } else {
+        // Fetch JWKS from Auth0 at startup, then keep it fresh so rotated
+        // keys are picked up without a restart
+        let auth_config =
+            Arc::new(AuthConfig::new(&config.auth0_domain, &config.auth0_audience).await);
+        let jwks_auth = auth_config.clone();
+        tokio::spawn(async move {
+            loop {
+                // Without keys nobody can log in, so keep trying sooner
+                let wait = if jwks_auth.has_keys().await {
+                    JWKS_REFRESH_INTERVAL
+                } else {
+                    JWKS_MIN_REFETCH
+                };
+                tokio::time::sleep(wait).await;
+                jwks_auth.refresh().await;
+            }
+        });
+        auth_config
+    };
+
+    let read_pool = match &config.db_read_url {
+        Some(url) if !config.db_url.starts_with("sqlite:") => {
+            let read_pool = gtm_db::replica::ReadPool::with_replica(
+                pool.clone(),
+                url,
+                &connect_options(config),
+            )
+            .await?;
+            let checked = read_pool.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(REPLICA_CHECK_INTERVAL);
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    checked.check().await;
+                }
+            });
+            read_pool
+        }
+        Some(_) => {
+            warn!(
+                "db_read_url is only used with Postgres; reading from {}",
+                config.db_url
+            );
+            gtm_db::replica::ReadPool::primary_only(pool.clone())
+        }
+        None => gtm_db::replica::ReadPool::primary_only(pool.clone()),
+    };
 
     let state = AppState {
         pool,
+        read_pool,
         auth: auth_config,
         spa_html,
+        config: Arc::new(config.clone()),
+        notifier: Arc::new(build_notifier(config)),
+        gcal: Arc::new(build_google_calendar(config)),
+        payments: Arc::new(build_payments(config)),
+        history: Arc::new(gtm_db::history::SnapshotCache::new()),
+        usage: Arc::new(gtm_db::usage::UsageCounter::new()),
+        images: Arc::new(gtm_scraper::images::ImageCache::new(
+            &config.image_cache_dir,
+            std::time::Duration::from_secs(config.image_cache_ttl_hours.saturating_mul(3600)),
+        )),
     };
 
-    let scrape_pool = state.pool.clone();
+    parse_scrape_cron(config)?;
+    display_tz(config)?;
+    let summary = config_summary(&state).await?;
+    info!(
+        version = %summary.version,
+        address = %summary.address,
+        db_backend = summary.db_backend,
+        migration_version = summary.migration_version,
+        jwks_keys = summary.jwks_keys,
+        features = %summary.features.join(","),
+        config = %serde_json::Value::Object(summary.config),
+        "Startup configuration"
+    );
+    let state_pool = state.pool.clone();
+    let state_config = state.config.clone();
+    let state_notifier = state.notifier.clone();
+    let state_gcal = state.gcal.clone();
+
+    let usage = state.usage.clone();
+    let usage_pool = state.pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(USAGE_FLUSH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(e) = usage.flush(&usage_pool).await {
+                warn!("Failed to record API usage: {e}");
+            }
+        }
+    });
 
     let cors = CorsLayer::permissive();
 
-    let api_routes = Router::new()
+    // Authorization is by construction: a route's access level is the
+    // sub-router it is registered in. Public routes check their own
+    // token, state or signature where they need one.
+    let public_routes = Router::new()
         .route("/health", get(health))
+        .route("/health/live", get(health_live))
+        .route("/health/ready", get(health_ready))
+        .route("/auth/local-users", get(api_local_users))
+        .route("/config", get(api_config))
         .route("/games", get(api_list_games))
+        .route("/games/search", get(api_search_games))
         .route("/games/{id}", get(api_get_game))
         .route("/games/{id}/promotions", get(api_get_game_promotions))
+        .route("/games/{id}/preview", get(api_get_game_preview))
         .route("/games/{id}/tickets", get(api_get_game_tickets))
-        .route("/seats", get(api_list_seats).post(api_add_seat))
-        .route("/seats/batch", post(api_add_seat_batch))
-        .route("/seats/group", patch(api_update_seat_group))
-        .route("/seats/{id}", delete(api_delete_seat))
-        .route("/tickets/{id}", patch(api_update_ticket))
+        .route("/promotions", get(api_search_promotions))
+        .route("/promotions/{offer_id}/image", get(api_promotion_image))
+        .route("/seats", get(api_list_seats))
         .route("/tickets/summary", get(api_ticket_summary))
+        .route("/seasons", get(api_list_seasons))
+        .route("/google/callback", get(api_google_callback))
+        // Rain release prompt links (token-authenticated, not JWT)
+        .route(
+            "/release-prompts/{token}/{action}",
+            get(api_release_prompt_confirm).post(api_release_prompt_respond),
+        )
+        // Contact email confirmation links (token-authenticated, not JWT)
+        .route(
+            "/contact-email/{token}",
+            get(api_contact_email_confirm).post(api_contact_email_respond),
+        )
+        // Inbound email webhook (Mailgun-signed, not JWT)
+        .route("/inbound/email", post(api_inbound_email))
+        // Stripe payment webhook (Stripe-signed, not JWT)
+        .route("/payments/stripe/webhook", post(api_stripe_webhook));
+
+    let member_routes = Router::new()
         .route("/users/me", get(api_get_me))
         .route("/users", get(api_list_users))
-        .route("/admin/scrape-schedule", post(api_scrape_schedule))
         // Member: ticket requests
         .route(
             "/my/requests",
             get(api_my_requests_list).post(api_my_requests_create),
         )
+        .route("/my/requests/bulk", post(api_my_requests_bulk))
         .route(
             "/my/requests/{id}",
             patch(api_my_requests_update).delete(api_my_requests_withdraw),
         )
+        .route(
+            "/my/requests/{id}/comments",
+            get(api_my_request_comments).post(api_my_request_comment_add),
+        )
         // Member: my games (allocated tickets)
         .route("/my/games", get(api_my_games))
+        .route("/availability/calendar", get(api_availability_calendar))
         .route("/my/games/{game_pk}/release", post(api_my_games_release))
+        .route("/my/games/{game_pk}/transfer", post(api_my_games_transfer))
+        .route(
+            "/my/games/{game_pk}/attendance",
+            post(api_my_games_attendance),
+        )
         // Member: game tags (shortlist / can't go)
         .route("/my/game-tags", get(api_my_game_tags))
+        .route("/my/game-tags/{game_pk}", put(api_my_game_tags_put))
+        // Member: promotion interest
+        .route(
+            "/promotions/{offer_id}/interest",
+            post(api_promotion_interest_add).delete(api_promotion_interest_remove),
+        )
+        .route("/my/promotion-interests", get(api_my_promotion_interests))
+        // Member: availability polls
+        .route("/my/polls", get(api_my_polls))
+        .route("/polls/{id}/response", put(api_poll_respond))
+        // Member: season ledger
+        .route("/my/ledger", get(api_my_ledger))
+        .route("/my/history", get(api_my_history))
+        .route("/transparency", get(api_transparency))
+        .route("/my/cost-split", get(api_my_cost_split))
+        .route("/my/invoices", get(api_my_invoices))
+        .route("/my/invoices/{id}/pay", post(api_my_invoice_pay))
+        // Member: blackout dates
+        .route("/my/blackouts", get(api_my_blackouts))
+        .route(
+            "/my/saved-views",
+            get(api_my_saved_views).post(api_save_view),
+        )
+        .route("/my/saved-views/{id}", delete(api_delete_view))
+        .route(
+            "/my/blackouts/{date}",
+            put(api_add_blackout).delete(api_remove_blackout),
+        )
+        // Member: phone for SMS notifications
+        .route(
+            "/my/phone",
+            get(api_my_phone)
+                .put(api_my_phone_put)
+                .patch(api_my_phone_patch)
+                .delete(api_my_phone_delete),
+        )
+        .route("/my/phone/verify", post(api_my_phone_verify))
+        .route("/my/locale", put(api_my_locale_put))
+        .route(
+            "/my/preferences",
+            get(api_my_preferences).patch(api_my_preferences_patch),
+        )
+        // Member: Google Calendar sync
+        .route(
+            "/my/google",
+            get(api_my_google).delete(api_my_google_delete),
+        )
+        .route("/my/google/connect", post(api_my_google_connect))
+        // Member: API tokens for scripts
+        .route("/my/tokens", get(api_my_tokens).post(api_my_token_mint))
+        .route("/my/tokens/{id}", delete(api_my_token_revoke))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            member_gate,
+        ));
+
+    let admin_routes = Router::new()
+        // Admin: seat and ticket inventory
+        .route("/seats", post(api_add_seat))
+        .route("/seats/batch", post(api_add_seat_batch))
+        .route("/seats/import", post(api_import_seats))
         .route(
-            "/my/game-tags/{game_pk}",
-            axum::routing::put(api_my_game_tags_put),
+            "/seats/group",
+            patch(api_update_seat_group).delete(api_delete_seat_group),
         )
+        .route(
+            "/seats/group/regenerate-tickets",
+            post(api_regenerate_seat_group_tickets),
+        )
+        .route("/seats/{id}", delete(api_delete_seat))
+        .route("/tickets/{id}", patch(api_update_ticket))
+        .route("/admin/scrape-schedule", post(api_scrape_schedule))
         // Admin: allocation
         .route("/admin/allocation", get(api_admin_allocation))
         .route(
@@ -1284,78 +6988,1274 @@ async fn run_server(port: u16, pool: AnyPool, config: &gtm_config::Config) -> an
             "/admin/allocation/by-users",
             get(api_admin_allocation_by_users),
         )
+        .route(
+            "/admin/allocation/{game_pk}/auto",
+            post(api_admin_allocate_auto),
+        )
+        .route(
+            "/admin/allocation/{game_pk}/preview",
+            post(api_admin_allocation_preview),
+        )
+        .route(
+            "/admin/allocation/simulate",
+            post(api_admin_allocation_simulate),
+        )
+        .route(
+            "/admin/allocation/batches",
+            get(api_admin_allocation_batches),
+        )
+        .route(
+            "/admin/allocation/batches/{id}",
+            get(api_admin_allocation_batch),
+        )
         .route("/admin/allocate", post(api_admin_allocate))
         .route("/admin/allocate/{id}", delete(api_admin_revoke))
         .route(
             "/admin/allocation/by-user/{user_id}",
             get(api_admin_allocation_by_user),
         )
-        .route("/admin/requests", get(api_admin_requests));
+        .route("/admin/config", get(api_admin_config))
+        .route("/admin/requests", get(api_admin_requests))
+        .route(
+            "/admin/requests/{id}/comments",
+            get(api_admin_request_comments).post(api_admin_request_comment_add),
+        )
+        .route(
+            "/admin/reports/response-times",
+            get(api_admin_response_times),
+        )
+        .route("/admin/alerts/unassigned", get(api_admin_alerts_unassigned))
+        .route("/admin/audit", get(api_admin_audit))
+        .route("/admin/transfers", get(api_admin_transfers))
+        .route("/admin/reports/fairness", get(api_admin_fairness))
+        .route("/admin/users/{id}/history", get(api_admin_user_history))
+        .route(
+            "/admin/reports/fairness-timeline",
+            get(api_admin_fairness_timeline),
+        )
+        .route("/admin/reports/renewal", get(api_admin_renewal_report))
+        .route("/admin/tickets/coverage", get(api_admin_ticket_coverage))
+        .route("/admin/usage", get(api_admin_usage))
+        .route("/admin/jobs", get(api_admin_jobs))
+        .route(
+            "/admin/reports/renewal.csv",
+            get(api_admin_renewal_report_csv),
+        )
+        // Admin: costs
+        .route("/admin/ledger", get(api_admin_ledger))
+        .route(
+            "/admin/split-rules",
+            get(api_admin_split_rules).put(api_admin_set_split_rules),
+        )
+        .route(
+            "/admin/seat-limits",
+            get(api_admin_seat_limits).put(api_admin_set_seat_limits),
+        )
+        .route(
+            "/admin/games/{id}/seat-limit",
+            put(api_admin_set_game_seat_limit),
+        )
+        .route(
+            "/admin/games/{id}/packets",
+            get(api_admin_game_packets_zip).post(api_admin_send_game_packets),
+        )
+        .route(
+            "/admin/games/{id}/tags",
+            get(api_admin_game_tags).put(api_admin_set_game_tags),
+        )
+        .route(
+            "/admin/games/{id}/attendance",
+            get(api_admin_game_attendance),
+        )
+        .route(
+            "/admin/games/{id}/attendance/{user_id}",
+            put(api_admin_set_game_attendance),
+        )
+        .route(
+            "/admin/games/{id}/adjacent-seats",
+            get(api_admin_adjacent_seats),
+        )
+        .route(
+            "/admin/games/{id}/forwarding",
+            get(api_admin_game_forwarding).post(api_admin_forward_game),
+        )
+        .route("/admin/cost-split", get(api_admin_cost_split))
+        .route(
+            "/admin/invoices",
+            get(api_admin_invoices).post(api_admin_generate_invoices),
+        )
+        .route(
+            "/admin/invoices/{id}",
+            get(api_admin_invoice).delete(api_admin_delete_invoice),
+        )
+        .route(
+            "/admin/invoices/{id}/payments",
+            post(api_admin_record_payment),
+        )
+        .route(
+            "/admin/invoices/{id}/payment-link",
+            post(api_admin_invoice_payment_link),
+        )
+        .route(
+            "/admin/seats/group/face-value",
+            put(api_admin_seat_group_face_value),
+        )
+        .route("/admin/tickets/{id}/cost", put(api_admin_ticket_cost))
+        .route("/admin/tickets/{id}/resale", put(api_admin_ticket_resale))
+        .route(
+            "/admin/tickets/{id}/forwarding",
+            put(api_admin_forward_ticket).delete(api_admin_unforward_ticket),
+        )
+        .route(
+            "/admin/note-templates",
+            get(api_admin_note_templates).post(api_admin_create_note_template),
+        )
+        .route(
+            "/admin/note-templates/{id}",
+            put(api_admin_update_note_template).delete(api_admin_delete_note_template),
+        )
+        .route(
+            "/admin/note-templates/{id}/apply",
+            post(api_admin_apply_note_template),
+        )
+        // Admin: IDs in other systems
+        .route(
+            "/admin/external-refs",
+            get(api_admin_external_refs).post(api_admin_create_external_ref),
+        )
+        .route(
+            "/admin/external-refs/{id}",
+            put(api_admin_update_external_ref).delete(api_admin_delete_external_ref),
+        )
+        // Admin: outbound webhooks
+        .route(
+            "/admin/webhooks",
+            get(api_admin_webhooks).post(api_admin_create_webhook),
+        )
+        .route("/admin/webhooks/{id}", delete(api_admin_delete_webhook))
+        .route(
+            "/admin/webhooks/{id}/deliveries",
+            get(api_admin_webhook_deliveries),
+        )
+        // Admin: exports
+        .route(
+            "/admin/allocation/export.csv",
+            get(api_admin_allocation_export),
+        )
+        .route("/admin/tickets/export.csv", get(api_admin_tickets_export))
+        .route(
+            "/admin/export/attendance-grid",
+            get(api_admin_attendance_grid),
+        )
+        .route(
+            "/admin/promotions/interest",
+            get(api_admin_promotion_interest),
+        )
+        // Admin: availability polls
+        .route(
+            "/admin/polls",
+            get(api_admin_polls).post(api_admin_create_poll),
+        )
+        .route("/admin/polls/{id}", get(api_admin_poll_results))
+        .route("/admin/polls/{id}/close", post(api_admin_close_poll))
+        .route(
+            "/admin/seasons/{season}/close",
+            post(api_admin_close_season),
+        )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            admin_gate,
+        ));
+
+    let api_routes = Router::new()
+        .merge(public_routes)
+        .merge(member_routes)
+        .merge(admin_routes)
+        .layer(axum::middleware::from_fn(rejections_as_api_errors))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            count_usage,
+        ));
+
+    let app = Router::new()
+        .nest("/api", api_routes)
+        .fallback_service(
+            ServeDir::new("frontend/dist")
+                .append_index_html_on_directories(false)
+                .not_found_service(get(serve_spa).with_state(state.clone())),
+        )
+        .layer(cors)
+        .with_state(state);
+
+    let addr = format!("0.0.0.0:{port}");
+    info!("Listening on http://{addr}");
+
+    if config.run_jobs {
+        tokio::spawn(run_jobs_as_leader(
+            state_pool,
+            state_config,
+            state_notifier,
+            state_gcal,
+        ));
+    } else {
+        info!("Background jobs disabled (run_jobs = false); expecting a `gtm worker`");
+    }
+
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+// --- Background jobs ---
+
+/// `gtm worker`: run the job scheduler against the database, no HTTP server.
+async fn run_worker(pool: AnyPool, config: &gtm_config::Config) -> anyhow::Result<()> {
+    info!("GTM v{} worker", version_string());
+    parse_scrape_cron(config)?;
+    display_tz(config)?;
+    run_jobs_as_leader(
+        pool,
+        Arc::new(config.clone()),
+        Arc::new(build_notifier(config)),
+        Arc::new(build_google_calendar(config)),
+    )
+    .await;
+    Ok(())
+}
+
+/// How often a standby process retries for leadership, and the leader checks
+/// it still holds the lock.
+const LEADER_CHECK_SECS: u64 = 30;
+
+/// Per-job locks, so a job never runs twice at once even if two processes
+/// (or an admin and the scheduler) start it together.
+const SCHEDULE_SYNC_LOCK: &str = "schedule_sync";
+const UNASSIGNED_ALERT_LOCK: &str = "unassigned_alert";
+const RAIN_CHECK_LOCK: &str = "rain_check";
+const OFFER_EXPIRING_LOCK: &str = "offer_expiring";
+const GAME_PREVIEW_LOCK: &str = "game_previews";
+const LIVE_SCORES_LOCK: &str = "live_scores";
+const NOTIFICATION_LOCK: &str = "notification_delivery";
+const WEBHOOK_LOCK: &str = "webhook_delivery";
+/// Prefix of the per-member lock around a calendar sync, so two syncs for
+/// the same member never both create an event for a game.
+const CALENDAR_SYNC_LOCK: &str = "calendar_sync";
+
+/// How long a job lock outlives a crashed holder (SQLite only).
+const JOB_LOCK_TTL: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// Whether login is off (`auth_mode = "none"`).
+fn local_auth(config: &gtm_config::Config) -> anyhow::Result<bool> {
+    match config.auth_mode.as_str() {
+        "auth0" => Ok(false),
+        "none" => Ok(true),
+        other => anyhow::bail!("Invalid auth_mode {other:?}; expected auth0 or none"),
+    }
+}
+
+/// Say loudly, at startup, that anyone who can reach the server can act as
+/// any member.
+fn warn_local_auth(config: &gtm_config::Config) {
+    warn!("==============================================================");
+    warn!("AUTHENTICATION IS OFF (auth_mode = \"none\")");
+    warn!(
+        "Anyone who can reach port {} can act as any member.",
+        config.port
+    );
+    warn!("Use this only on a network no one untrusted can reach.");
+    warn!("==============================================================");
+    if config.admin_emails.is_empty() {
+        warn!("No admin_emails are set, so no local user is an admin");
+    }
+}
+
+/// `display_timezone`, which game times are shown in.
+fn display_tz(config: &gtm_config::Config) -> anyhow::Result<chrono_tz::Tz> {
+    config.display_timezone.parse().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid display_timezone {:?}; expected an IANA name like America/Los_Angeles",
+            config.display_timezone
+        )
+    })
+}
+
+fn parse_scrape_cron(config: &gtm_config::Config) -> anyhow::Result<cron::Schedule> {
+    config
+        .scrape_cron
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid scrape_cron {:?}: {e}", config.scrape_cron))
+}
+
+/// Compete for the job lock and run the scheduler while holding it. Runs
+/// forever: see [`gtm_db::leader::lead`].
+async fn run_jobs_as_leader(
+    pool: AnyPool,
+    config: Arc<gtm_config::Config>,
+    notifier: Arc<gtm_notify::Notifier>,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+) {
+    gtm_db::leader::lead(
+        &pool,
+        &config.db_url,
+        gtm_db::leader::JOBS_LOCK_KEY,
+        std::time::Duration::from_secs(LEADER_CHECK_SECS),
+        || spawn_background_jobs(&pool, &config, &notifier, &gcal),
+    )
+    .await
+}
+
+/// Spawn the scheduled jobs: schedule scrape, unassigned-seat alert, game
+/// previews, live scores, rain check, offer-expiring reminders and
+/// notification delivery.
+fn spawn_background_jobs(
+    pool: &AnyPool,
+    config: &Arc<gtm_config::Config>,
+    notifier: &Arc<gtm_notify::Notifier>,
+    gcal: &Arc<gtm_gcal::GoogleCalendar>,
+) -> anyhow::Result<Vec<tokio::task::JoinHandle<()>>> {
+    let scrape_schedule = parse_scrape_cron(config)?;
+    let tz = display_tz(config)?;
+    let (scrape_pool, scrape_config, scrape_gcal) = (pool.clone(), config.clone(), gcal.clone());
+    let (alert_pool, alert_config, alert_notifier) =
+        (pool.clone(), config.clone(), notifier.clone());
+    let (rain_pool, rain_config, rain_notifier) = (pool.clone(), config.clone(), notifier.clone());
+    let (offer_pool, offer_config, offer_notifier) =
+        (pool.clone(), config.clone(), notifier.clone());
+    let preview_pool = pool.clone();
+    let live_pool = pool.clone();
+    let team_id = config.team_id;
+    let (outbox_pool, outbox_notifier) = (pool.clone(), notifier.clone());
+    let webhook_pool = pool.clone();
+    let mut jobs = Vec::new();
+
+    // Spawn scheduled scrape task (scrape_cron, 12:15 AM by default)
+    jobs.push(tokio::spawn(async move {
+        loop {
+            let delay = delay_until_next(&scrape_schedule, tz);
+            info!(
+                "Scheduled scrape in {:.1} hours",
+                delay.as_secs_f64() / 3600.0
+            );
+            tokio::time::sleep(delay).await;
+
+            let season = Utc::now().with_timezone(&tz).year() as u32;
+            info!("Starting scheduled scrape for {season} season");
+            let run = run_scrape(&scrape_pool, &scrape_config, season, None, None, false).await;
+            let (status, detail) = match &run {
+                Ok(None) => (None, None),
+                Ok(Some((sync, _))) if !sync.anomalies.is_empty() => {
+                    let detail = gtm_db::schedule_sync::anomaly_detail(&sync.anomalies);
+                    (Some(gtm_db::jobs::SUSPECT), Some(detail))
+                }
+                Ok(Some(_)) => (Some(gtm_db::jobs::OK), None),
+                Err(e) => (Some(gtm_db::jobs::FAILED), Some(format!("{e:#}"))),
+            };
+            if let Some(status) = status
+                && let Err(e) = gtm_db::jobs::record(
+                    &scrape_pool,
+                    gtm_db::jobs::SCHEDULE_SCRAPE,
+                    status,
+                    detail.as_deref(),
+                )
+                .await
+            {
+                warn!("Could not record scrape status: {e}");
+            }
+            match run {
+                Ok(None) => info!("Schedule sync already running elsewhere; skipped"),
+                Ok(Some((sync, _))) => {
+                    if !sync.anomalies.is_empty() {
+                        warn!("Scheduled scrape suspect; no seats or requests moved");
+                    }
+                    info!(
+                        "Scheduled scrape complete: {} games, {} promotions, {} tickets, {} change(s)",
+                        sync.upsert.games,
+                        sync.upsert.promotions,
+                        sync.upsert.tickets,
+                        sync.changes.len()
+                    );
+                    // Pick up rescheduled games in linked calendars
+                    match gtm_db::list_google_linked_user_ids(&scrape_pool).await {
+                        Ok(ids) => {
+                            spawn_calendar_sync(scrape_pool.clone(), scrape_gcal.clone(), ids)
+                        }
+                        Err(e) => warn!("Could not list Google-linked members: {e}"),
+                    }
+                }
+                Err(e) => {
+                    warn!("Scheduled scrape failed: {e}");
+                }
+            }
+        }
+    }));
+
+    // Spawn daily unassigned-seat alert (9:00 AM)
+    jobs.push(tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay_until(tz, 9, 0)).await;
+            let run = gtm_db::with_lock(&alert_pool, UNASSIGNED_ALERT_LOCK, JOB_LOCK_TTL, || {
+                send_unassigned_alert(&alert_pool, &alert_notifier, &alert_config)
+            });
+            match run.await {
+                Ok(None) => info!("Unassigned-seat check already running elsewhere; skipped"),
+                Ok(Some(0)) => info!("Unassigned-seat check: nothing to report"),
+                Ok(Some(n)) => info!("Unassigned-seat alert sent for {n} game(s)"),
+                Err(e) => warn!("Unassigned-seat check failed: {e}"),
+            }
+        }
+    }));
+
+    // Spawn daily game preview refresh (7:30 AM, ahead of the rain check)
+    jobs.push(tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay_until(tz, 7, 30)).await;
+            let run = gtm_db::with_lock(&preview_pool, GAME_PREVIEW_LOCK, JOB_LOCK_TTL, || {
+                refresh_game_previews(&preview_pool, team_id, tz)
+            });
+            match run.await {
+                Ok(None) => info!("Game preview refresh already running elsewhere; skipped"),
+                Ok(Some(n)) => info!("Refreshed {n} game preview(s)"),
+                Err(e) => warn!("Game preview refresh failed: {e}"),
+            }
+        }
+    }));
+
+    // Spawn live score poller (every minute while today's games are on)
+    if config.live_scores {
+        jobs.push(tokio::spawn(async move {
+            loop {
+                let delay = next_live_poll(&live_pool, tz).await.unwrap_or_else(|e| {
+                    warn!("Could not look up today's games: {e}");
+                    std::time::Duration::from_secs(LIVE_POLL_SECS)
+                });
+                tokio::time::sleep(delay).await;
+                let run = gtm_db::with_lock(&live_pool, LIVE_SCORES_LOCK, JOB_LOCK_TTL, || {
+                    refresh_live_games(&live_pool, team_id, tz)
+                });
+                match run.await {
+                    Ok(None) => debug!("Live score poll already running elsewhere; skipped"),
+                    Ok(Some(n)) => debug!("Live scores refreshed; {n} game(s) not final"),
+                    Err(e) => warn!("Live score poll failed: {e}"),
+                }
+            }
+        }));
+    }
+
+    // Spawn daily rain check (8:00 AM)
+    jobs.push(tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay_until(tz, 8, 0)).await;
+            let run = gtm_db::with_lock(&rain_pool, RAIN_CHECK_LOCK, JOB_LOCK_TTL, || {
+                send_rain_release_prompts(&rain_pool, &rain_notifier, &rain_config)
+            });
+            match run.await {
+                Ok(None) => info!("Rain check already running elsewhere; skipped"),
+                Ok(Some(n)) => info!("Rain check complete: {n} release prompt(s) sent"),
+                Err(e) => warn!("Rain check failed: {e}"),
+            }
+        }
+    }));
+
+    // Spawn reminder texts for unanswered rain prompts (noon)
+    jobs.push(tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(delay_until(tz, 12, 0)).await;
+            let run = gtm_db::with_lock(&offer_pool, OFFER_EXPIRING_LOCK, JOB_LOCK_TTL, || {
+                send_offer_expiring_sms(&offer_pool, &offer_notifier, &offer_config)
+            });
+            match run.await {
+                Ok(None) => info!("Offer-expiring reminders already running elsewhere; skipped"),
+                Ok(Some(n)) => info!("Offer-expiring reminders sent: {n}"),
+                Err(e) => warn!("Offer-expiring reminders failed: {e}"),
+            }
+        }
+    }));
+
+    // Spawn notification delivery (drains the queue every 30 seconds)
+    jobs.push(tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(NOTIFICATION_POLL_SECS)).await;
+            let run = gtm_db::with_lock(&outbox_pool, NOTIFICATION_LOCK, JOB_LOCK_TTL, || {
+                deliver_notifications(&outbox_pool, &outbox_notifier)
+            });
+            match run.await {
+                Ok(None | Some(0)) => {}
+                Ok(Some(n)) => info!("Delivered {n} queued notification(s)"),
+                Err(e) => warn!("Notification delivery pass failed: {e}"),
+            }
+        }
+    }));
+
+    // Spawn webhook delivery (posts due deliveries every 30 seconds)
+    jobs.push(tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(NOTIFICATION_POLL_SECS)).await;
+            let run = gtm_db::with_lock(&webhook_pool, WEBHOOK_LOCK, JOB_LOCK_TTL, || {
+                deliver_webhooks(&webhook_pool)
+            });
+            match run.await {
+                Ok(None | Some(0)) => {}
+                Ok(Some(n)) => info!("Delivered {n} webhook event(s)"),
+                Err(e) => warn!("Webhook delivery pass failed: {e}"),
+            }
+        }
+    }));
+
+    Ok(jobs)
+}
+
+// --- CLI listings ---
+
+#[derive(Serialize)]
+struct GameListing {
+    game_pk: GamePk,
+    date: String,
+    time: String,
+    #[serde(rename = "type")]
+    game_type: String,
+    home_away: &'static str,
+    opponent: String,
+    status: String,
+    venue: String,
+    promotions: Vec<String>,
+}
+
+impl output::Listing for GameListing {
+    const COLUMNS: &'static [(&'static str, &'static str, usize)] = &[
+        ("game_pk", "GamePK", 10),
+        ("date", "Date", 12),
+        ("time", "Time", 22),
+        ("type", "Type", 16),
+        ("home_away", "H/A", 6),
+        ("opponent", "Opponent", 25),
+        ("status", "Status", 10),
+        ("venue", "Venue", 20),
+        ("promotions", "Promotions", 0),
+    ];
+}
+
+#[derive(Serialize)]
+struct PromotionListing {
+    game_pk: GamePk,
+    date: String,
+    opponent: String,
+    promotion: String,
+    offer_type: Option<String>,
+    distribution: Option<String>,
+}
+
+impl output::Listing for PromotionListing {
+    const COLUMNS: &'static [(&'static str, &'static str, usize)] = &[
+        ("game_pk", "GamePK", 10),
+        ("date", "Date", 12),
+        ("opponent", "Opponent", 25),
+        ("promotion", "Promotion", 35),
+        ("offer_type", "Type", 15),
+        ("distribution", "Distribution", 0),
+    ];
+}
+
+#[derive(Serialize)]
+struct SeatListing {
+    id: SeatId,
+    section: String,
+    row: String,
+    seat: String,
+    notes: Option<String>,
+}
+
+impl output::Listing for SeatListing {
+    const COLUMNS: &'static [(&'static str, &'static str, usize)] = &[
+        ("id", "ID", 6),
+        ("section", "Section", 10),
+        ("row", "Row", 6),
+        ("seat", "Seat", 6),
+        ("notes", "Notes", 0),
+    ];
+}
+
+/// A home game's ticket inventory.
+#[derive(Serialize)]
+struct TicketListing {
+    game_pk: GamePk,
+    date: String,
+    opponent: String,
+    available: usize,
+    total: usize,
+    /// `section:rowseat [status]` for each ticket.
+    tickets: Vec<String>,
+}
+
+impl output::Listing for TicketListing {
+    const COLUMNS: &'static [(&'static str, &'static str, usize)] = &[
+        ("game_pk", "GamePK", 10),
+        ("date", "Date", 12),
+        ("opponent", "Opponent", 25),
+        ("available", "Available", 10),
+        ("total", "Total", 6),
+        ("tickets", "Tickets", 0),
+    ];
+}
+
+#[derive(Serialize)]
+struct RequestListing {
+    id: i64,
+    game_pk: GamePk,
+    date: Option<String>,
+    opponent: Option<String>,
+    member: Option<String>,
+    seats: i64,
+    status: String,
+    /// The flag reason, if flagged, or the member's notes.
+    notes: Option<String>,
+}
+
+impl output::Listing for RequestListing {
+    const COLUMNS: &'static [(&'static str, &'static str, usize)] = &[
+        ("id", "ID", 6),
+        ("game_pk", "Game", 10),
+        ("date", "Date", 12),
+        ("opponent", "Opponent", 22),
+        ("member", "Member", 22),
+        ("seats", "Seats", 6),
+        ("status", "Status", 10),
+        ("notes", "Notes", 0),
+    ];
+}
+
+impl Commands {
+    /// Whether `--format` means anything to the command.
+    fn takes_format(&self) -> bool {
+        matches!(
+            self,
+            Commands::ListGames { .. }
+                | Commands::ListPromotions { .. }
+                | Commands::ListSeats
+                | Commands::ListTickets
+                | Commands::Requests {
+                    command: RequestsCommand::List { .. }
+                }
+                | Commands::Export { .. }
+        )
+    }
+}
+
+/// The plan table for `gtm allocate --plan`: each line's game, member and
+/// seats, then the seats picked (dry run) or `assigned` per line.
+fn print_allocation_plan(rows: &[gtm_db::allocation_plan::PlannedRow], assigned: Option<&[usize]>) {
+    println!(
+        "{:<6} {:<10} {:<12} {:<30} {:<6} {}",
+        "Line",
+        "Game",
+        "Date",
+        "Member",
+        "Seats",
+        if assigned.is_some() {
+            "Assigned"
+        } else {
+            "Tickets"
+        }
+    );
+    println!("{}", "-".repeat(90));
+    for (i, row) in rows.iter().enumerate() {
+        let result = match (&row.problem, assigned) {
+            (Some(problem), _) => format!("ERROR: {problem}"),
+            (None, Some(assigned)) => assigned
+                .get(i)
+                .map_or_else(|| "?".to_string(), ToString::to_string),
+            (None, None) => row
+                .tickets
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        println!(
+            "{:<6} {:<10} {:<12} {:<30} {:<6} {}",
+            row.line.line,
+            row.line.game_pk,
+            row.official_date.as_deref().unwrap_or("?"),
+            row.line.user_email,
+            row.line.seats,
+            result
+        );
+    }
+}
+
+fn print_price_import(rows: &[gtm_db::prices::PricedRow]) {
+    println!(
+        "{:<6} {:<12} {:<28} {:<10} {:<10} {:<8} Tickets",
+        "Line", "Date", "Opponent", "Game", "Seat", "Price"
+    );
+    println!("{}", "-".repeat(90));
+    for row in rows {
+        let line = &row.line;
+        let seat = match (&line.section, &line.row, &line.seat) {
+            (Some(section), Some(row), Some(seat)) => format!("{section}-{row}-{seat}"),
+            (Some(section), Some(row), None) => format!("{section}-{row}"),
+            (Some(section), None, _) => section.clone(),
+            _ => "all".to_string(),
+        };
+        let result = match &row.problem {
+            Some(problem) => format!("ERROR: {problem}"),
+            None => row.tickets.len().to_string(),
+        };
+        println!(
+            "{:<6} {:<12} {:<28} {:<10} {:<10} {:<8} {}",
+            line.line,
+            line.date,
+            row.opponent.as_deref().unwrap_or(&line.opponent),
+            row.game_pk.map(|g| g.to_string()).unwrap_or_default(),
+            seat,
+            format!("{}.{:02}", line.price / 100, line.price % 100),
+            result
+        );
+    }
+}
 
-    let app = Router::new()
-        .nest("/api", api_routes)
-        .fallback_service(
-            ServeDir::new("frontend/dist")
-                .append_index_html_on_directories(false)
-                .not_found_service(get(serve_spa).with_state(state.clone())),
-        )
-        .layer(cors)
-        .with_state(state);
+/// Time from now until the next `hour:minute` in `tz`.
+fn delay_until(tz: chrono_tz::Tz, hour: u32, minute: u32) -> std::time::Duration {
+    let now = Utc::now().with_timezone(&tz);
+    let at = chrono::NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+    let target = if now.time() < at {
+        now.date_naive()
+    } else {
+        now.date_naive() + chrono::Duration::days(1)
+    };
+    let target_dt = target
+        .and_time(at)
+        .and_local_timezone(tz)
+        .earliest()
+        .unwrap_or_else(|| now + chrono::Duration::hours(24));
+    (target_dt - now)
+        .to_std()
+        .unwrap_or(std::time::Duration::from_secs(60))
+}
 
-    let addr = format!("0.0.0.0:{port}");
-    info!("Listening on http://{addr}");
+/// Time from now until `schedule` next fires, evaluated in `tz`.
+fn delay_until_next(schedule: &cron::Schedule, tz: chrono_tz::Tz) -> std::time::Duration {
+    let now = Utc::now().with_timezone(&tz);
+    schedule
+        .after(&now)
+        .next()
+        .and_then(|at| (at - now).to_std().ok())
+        .unwrap_or(std::time::Duration::from_secs(24 * 60 * 60))
+}
 
-    // Spawn nightly scrape task (12:15 AM Pacific)
-    tokio::spawn(async move {
-        loop {
-            let now = Utc::now().with_timezone(&Pacific);
-            let tonight = now.date_naive().and_hms_opt(0, 15, 0).unwrap();
-            let target = if now.time() < tonight.time() {
-                now.date_naive()
-            } else {
-                now.date_naive() + chrono::Duration::days(1)
-            };
-            let target_dt = target
-                .and_hms_opt(0, 15, 0)
-                .unwrap()
-                .and_local_timezone(Pacific)
-                .unwrap();
-            let delay = (target_dt - now)
-                .to_std()
-                .unwrap_or(std::time::Duration::from_secs(60));
-            info!(
-                "Nightly scrape scheduled in {:.1} hours",
-                delay.as_secs_f64() / 3600.0
+// --- Notifier ---
+
+fn build_notifier(config: &gtm_config::Config) -> gtm_notify::Notifier {
+    let mut notifier = gtm_notify::Notifier::new();
+    if let (Some(host), Some(username), Some(password)) = (
+        &config.smtp_host,
+        &config.smtp_username,
+        &config.smtp_password,
+    ) {
+        let smtp = gtm_notify::SmtpConfig {
+            host: host.clone(),
+            port: config.smtp_port,
+            username: username.clone(),
+            password: password.clone(),
+            from: config.mail_from.clone(),
+        };
+        match notifier.clone().with_smtp(smtp) {
+            Ok(n) => notifier = n,
+            Err(e) => warn!("Invalid SMTP settings, not using SMTP: {e}"),
+        }
+    }
+    match (&config.mailgun_domain, &config.mailgun_api_key) {
+        (Some(domain), Some(api_key)) => {
+            notifier = notifier.with_mailgun(gtm_notify::MailgunConfig {
+                domain: domain.clone(),
+                api_key: api_key.clone(),
+                from: config.mail_from.clone(),
+            });
+        }
+        _ if notifier.is_enabled(gtm_notify::Channel::Email) => {}
+        _ => info!("No email backend configured; emails will be logged only"),
+    }
+    match (
+        &config.twilio_account_sid,
+        &config.twilio_auth_token,
+        &config.twilio_from_number,
+    ) {
+        (Some(sid), Some(token), Some(from)) => {
+            notifier = notifier.with_twilio(gtm_notify::TwilioConfig {
+                account_sid: sid.clone(),
+                auth_token: token.clone(),
+                from_number: from.clone(),
+            });
+        }
+        _ => info!("No SMS backend configured; text messages will be logged only"),
+    }
+    notifier
+}
+
+fn build_google_calendar(config: &gtm_config::Config) -> gtm_gcal::GoogleCalendar {
+    match (&config.google_client_id, &config.google_client_secret) {
+        (Some(id), Some(secret)) => {
+            gtm_gcal::GoogleCalendar::new().with_config(gtm_gcal::GoogleConfig {
+                client_id: id.clone(),
+                client_secret: secret.clone(),
+                redirect_uri: format!("{}/api/google/callback", config.public_url),
+                time_zone: config.display_timezone.clone(),
+            })
+        }
+        _ => {
+            info!("No Google OAuth client configured; calendar sync disabled");
+            gtm_gcal::GoogleCalendar::new()
+        }
+    }
+}
+
+fn build_payments(config: &gtm_config::Config) -> gtm_payments::Payments {
+    match (&config.stripe_secret_key, &config.stripe_webhook_secret) {
+        (Some(key), Some(secret)) => {
+            gtm_payments::Payments::new().with_stripe(gtm_payments::StripeConfig {
+                secret_key: key.clone(),
+                webhook_secret: secret.clone(),
+                currency: config.stripe_currency.clone(),
+                success_url: format!("{}/?payment=success", config.public_url),
+                cancel_url: format!("{}/?payment=cancelled", config.public_url),
+            })
+        }
+        _ => {
+            info!("No Stripe keys configured; online invoice payment disabled");
+            gtm_payments::Payments::new()
+        }
+    }
+}
+
+// --- DB helper ---
+
+/// Connect, then migrate if `auto_migrate` is on. Otherwise refuse to run
+/// against a schema with pending migrations.
+async fn connect_db(config: &gtm_config::Config) -> anyhow::Result<AnyPool> {
+    let pool = gtm_db::connect(&config.db_url, &connect_options(config)).await?;
+    if config.auto_migrate {
+        gtm_db::migrate(&pool, &config.db_url).await?;
+    } else {
+        let pending = gtm_db::maintenance::pending_migrations(&pool, &config.db_url).await?;
+        if !pending.is_empty() {
+            anyhow::bail!(
+                "The database has {} pending migration(s); run `gtm db migrate` \
+                 (or set GTM_AUTO_MIGRATE=true)",
+                pending.len()
             );
-            tokio::time::sleep(delay).await;
+        }
+    }
+    Ok(pool)
+}
 
-            let season = Utc::now().with_timezone(&Pacific).year() as u32;
-            info!("Starting nightly scrape for {season} season");
-            match run_scrape(&scrape_pool, season).await {
-                Ok((games, promos, tickets)) => {
-                    info!(
-                        "Nightly scrape complete: {games} games, {promos} promotions, {tickets} tickets"
-                    );
+async fn run_db_command(config: &gtm_config::Config, command: DbCommand) -> anyhow::Result<()> {
+    let url = &config.db_url;
+    let pool = gtm_db::connect(url, &connect_options(config)).await?;
+    match command {
+        DbCommand::Migrate => {
+            let pending = gtm_db::maintenance::pending_migrations(&pool, url).await?;
+            gtm_db::migrate(&pool, url).await?;
+            if pending.is_empty() {
+                println!("Already up to date.");
+            }
+            for m in &pending {
+                println!("Applied {} {}", m.version, m.description);
+            }
+        }
+        DbCommand::Status => {
+            let migrations = gtm_db::maintenance::migrations(&pool, url).await?;
+            let pending: Vec<_> = migrations.iter().filter(|m| !m.applied).collect();
+            println!(
+                "{} of {} migrations applied",
+                migrations.len() - pending.len(),
+                migrations.len()
+            );
+            for m in &pending {
+                println!("  pending: {} {}", m.version, m.description);
+            }
+            println!("\n{:<30} Rows", "Table");
+            println!("{}", "-".repeat(40));
+            for (table, rows) in gtm_db::maintenance::table_counts(&pool, url).await? {
+                println!("{table:<30} {rows}");
+            }
+        }
+        DbCommand::Reset { yes } => {
+            if !yes {
+                anyhow::bail!("This deletes every row in {url}; pass --yes to confirm");
+            }
+            gtm_db::maintenance::reset(&pool, url).await?;
+            println!("Database reset and migrated.");
+        }
+        DbCommand::Backup { path } => {
+            let bytes = gtm_db::maintenance::backup(&pool, url, &path).await?;
+            println!("Backed up to {} ({bytes} bytes)", path.display());
+        }
+    }
+    Ok(())
+}
+
+async fn run_config_command(
+    config: &gtm_config::Config,
+    cli_settings: &[&str],
+    command: ConfigCommand,
+) -> anyhow::Result<()> {
+    match command {
+        ConfigCommand::Init { force } => {
+            let path = gtm_config::Config::write_template(force).map_err(|e| {
+                if e.kind() == std::io::ErrorKind::AlreadyExists {
+                    anyhow::anyhow!("{e}; pass --force to replace it")
+                } else {
+                    e.into()
                 }
-                Err(e) => {
-                    warn!("Nightly scrape failed: {e}");
+            })?;
+            println!("Wrote {}", path.display());
+        }
+        ConfigCommand::Show => {
+            println!("Config file: {}", gtm_config::Config::file_path().display());
+            println!("\n{:<28} {:<8} Value", "Setting", "Source");
+            println!("{}", "-".repeat(80));
+            for (name, value, source) in config.sources(cli_settings) {
+                println!("{name:<28} {source:<8} {value}");
+            }
+        }
+        ConfigCommand::Validate => {
+            let checks = validate_config(config).await;
+            for (check, result) in &checks {
+                match result {
+                    Ok(detail) => println!("ok    {check}: {detail}"),
+                    Err(problem) => println!("FAIL  {check}: {problem}"),
                 }
             }
+            let failed = checks.iter().filter(|(_, r)| r.is_err()).count();
+            if failed > 0 {
+                anyhow::bail!("{failed} of {} checks failed", checks.len());
+            }
         }
-    });
+    }
+    Ok(())
+}
 
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+/// The admin a CLI write acts as, and the calendar to sync the seats it
+/// changes to.
+struct CliAdmin {
+    user: gtm_models::User,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+}
+
+/// The admin named by `--as`, or the only entry in `admin_emails`. They
+/// must have a user row, and be an admin by config or by the role recorded
+/// at their last sign-in.
+async fn cli_admin(
+    db: &AnyPool,
+    config: &gtm_config::Config,
+    as_admin: Option<&str>,
+) -> anyhow::Result<CliAdmin> {
+    let email = match (as_admin, config.admin_emails.as_slice()) {
+        (Some(email), _) => email,
+        (None, [only]) => only.as_str(),
+        (None, _) => anyhow::bail!("Pass --as EMAIL to say which admin is making this change"),
+    };
+    let Some(user) = gtm_db::get_user_by_email(db, email).await? else {
+        anyhow::bail!("No user with email {email}");
+    };
+    let configured = config
+        .admin_emails
+        .iter()
+        .any(|a| a.eq_ignore_ascii_case(email));
+    if !configured && !gtm_db::api_tokens::has_admin_role(db, user.id).await? {
+        anyhow::bail!("{email} isn't an admin");
+    }
+    Ok(CliAdmin {
+        user,
+        gcal: Arc::new(build_google_calendar(config)),
+    })
+}
+
+/// Give `member` `seats` available seats at `game_pk`, side by side where
+/// possible, approving `request_id` if given, as `admin`: the batch, emails
+/// and calendar updates are recorded as the web allocate does. Without an
+/// `admin` it's a dry run that only shows the seats. Returns how many were
+/// assigned.
+async fn assign_by_hand(
+    db: &AnyPool,
+    admin: Option<&CliAdmin>,
+    game_pk: GamePk,
+    member: &gtm_models::User,
+    seats: usize,
+    request_id: Option<i64>,
+    allow_split: bool,
+) -> anyhow::Result<u64> {
+    let tickets = gtm_db::list_tickets_for_game(db, game_pk).await?;
+    if tickets.is_empty() {
+        anyhow::bail!("Game {game_pk} has no tickets");
+    }
+    let available = tickets.iter().filter(|t| t.status == "available").count();
+    if seats == 0 || seats > available {
+        anyhow::bail!("Asked for {seats} seat(s); game {game_pk} has {available} available");
+    }
+    let picked = gtm_alloc::pick_seats(&tickets, seats);
+    for ticket in tickets.iter().filter(|t| picked.contains(&t.id)) {
+        println!(
+            "{} (ticket {}) → {}",
+            seat_label(ticket),
+            ticket.id,
+            member.name
+        );
+    }
+    let (assigned, warnings) = match admin {
+        None => {
+            let parties: Vec<(TicketId, UserId)> = picked.iter().map(|t| (*t, member.id)).collect();
+            let mut warnings = gtm_db::Warnings::new();
+            for party in gtm_db::seating::check_parties(db, &parties, allow_split).await? {
+                warnings.push(gtm_db::seating::warning(party));
+            }
+            println!("\nDry run: {} seat(s) would be assigned", picked.len());
+            (0, warnings)
+        }
+        Some(admin) => {
+            let assignments: Vec<_> = picked.iter().map(|t| (*t, member.id, request_id)).collect();
+            let request = serde_json::json!({
+                "game_pk": game_pk,
+                "user_id": member.id,
+                "seats": seats,
+                "request_id": request_id,
+            });
+            let (assigned, _, warnings) = service::commit_assignments(
+                db.clone(),
+                admin.gcal.clone(),
+                admin.user.id,
+                assignments,
+                allow_split,
+                request,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!(e.message))?;
+            (assigned, warnings)
+        }
+    };
+    for warning in warnings.iter() {
+        println!("Warning: {warning}");
+    }
+    Ok(assigned)
+}
 
+async fn run_requests_command(
+    db: &AnyPool,
+    config: &gtm_config::Config,
+    as_admin: Option<&str>,
+    format: output::Format,
+    command: RequestsCommand,
+) -> anyhow::Result<()> {
+    match command {
+        RequestsCommand::List { game, status } => {
+            let status = (status != "all").then_some(status.as_str());
+            let requests = gtm_db::list_requests(db, game, status).await?;
+            let users: std::collections::HashMap<UserId, String> = gtm_db::list_users(db)
+                .await?
+                .into_iter()
+                .map(|u| (u.id, u.name))
+                .collect();
+            let mut games = std::collections::HashMap::new();
+            let mut rows = Vec::with_capacity(requests.len());
+            for r in requests {
+                if let std::collections::hash_map::Entry::Vacant(e) = games.entry(r.game_pk) {
+                    e.insert(gtm_db::get_game(db, r.game_pk).await?);
+                }
+                let game = games[&r.game_pk].as_ref();
+                rows.push(RequestListing {
+                    id: r.id,
+                    game_pk: r.game_pk,
+                    date: game.map(|g| g.official_date.clone()),
+                    opponent: game.map(|g| g.away_team_name.clone()),
+                    member: users.get(&r.user_id).cloned(),
+                    seats: r.seats_requested,
+                    status: r.status,
+                    notes: r.flag_reason.or(r.notes),
+                });
+            }
+            output::print(
+                format,
+                &rows,
+                "No requests found.",
+                &format!("{} request(s)", rows.len()),
+            )?;
+        }
+        RequestsCommand::Approve { id, allow_split } => {
+            let request = pending_request(db, id).await?;
+            let admin = cli_admin(db, config, as_admin).await?;
+            let Some(member) = gtm_db::get_user(db, request.user_id).await? else {
+                anyhow::bail!("User {} not found", request.user_id);
+            };
+            let assigned = assign_by_hand(
+                db,
+                Some(&admin),
+                request.game_pk,
+                &member,
+                request.seats_requested as usize,
+                Some(id),
+                allow_split,
+            )
+            .await?;
+            println!(
+                "\nRequest {id} approved: {assigned} seat(s) assigned to {}",
+                member.name
+            );
+        }
+        RequestsCommand::Deny { id } => {
+            let request = pending_request(db, id).await?;
+            let admin = cli_admin(db, config, as_admin).await?;
+            gtm_db::update_request_approval(db, id, "declined", Some(admin.user.id)).await?;
+            if let Some(game) = gtm_db::get_game(db, request.game_pk).await? {
+                let label =
+                    gtm_notify::templates::game_label(&game.official_date, &game.away_team_name);
+                queue_email(
+                    db,
+                    request.user_id,
+                    request.game_pk,
+                    gtm_db::notifications::REQUEST_DECLINED,
+                    gtm_notify::templates::email_request_declined(&label),
+                )
+                .await?;
+            }
+            println!("Request {id} declined");
+        }
+    }
     Ok(())
 }
 
-// --- DB helper ---
+/// Request `id`, if it's still pending.
+async fn pending_request(db: &AnyPool, id: i64) -> anyhow::Result<gtm_models::TicketRequest> {
+    match gtm_db::get_ticket_request(db, id).await? {
+        Some(r) if r.status == "pending" => Ok(r),
+        Some(r) => anyhow::bail!("Request {id} is {}, not pending", r.status),
+        None => anyhow::bail!("Request {id} not found"),
+    }
+}
 
-async fn connect_db(config: &gtm_config::Config) -> anyhow::Result<AnyPool> {
-    let pool = gtm_db::connect(&config.db_url).await?;
-    gtm_db::migrate(&pool, &config.db_url).await?;
-    Ok(pool)
+/// Each `gtm config validate` check with what it found, or what's wrong.
+async fn validate_config(
+    config: &gtm_config::Config,
+) -> Vec<(&'static str, Result<String, String>)> {
+    let mut checks = Vec::new();
+
+    let path = gtm_config::Config::file_path();
+    checks.push((
+        "config file",
+        match gtm_config::Config::check_file() {
+            Ok(_) if !path.exists() => Ok(format!("none at {}; using defaults", path.display())),
+            Ok(unknown) if unknown.is_empty() => Ok(path.display().to_string()),
+            Ok(unknown) => Err(format!("unknown setting(s): {}", unknown.join(", "))),
+            Err(e) => Err(e),
+        },
+    ));
+
+    let mut problems = Vec::new();
+    if let Err(e) = parse_scrape_cron(config) {
+        problems.push(e.to_string());
+    }
+    if let Err(e) = config.allocation_strategy.parse::<gtm_alloc::Strategy>() {
+        problems.push(format!("allocation_strategy: {e}"));
+    }
+    let bad_codes = |codes: &[String], what: &str, valid: &dyn Fn(&str) -> bool| {
+        let bad: Vec<&str> = codes
+            .iter()
+            .map(String::as_str)
+            .filter(|c| !valid(c))
+            .collect();
+        (!bad.is_empty()).then(|| format!("{what}: unknown {}", bad.join(", ")))
+    };
+    problems.extend(bad_codes(
+        config.ticketed_game_types.as_deref().unwrap_or_default(),
+        "ticketed_game_types",
+        &|c| c.parse::<GameType>().is_ok(),
+    ));
+    problems.extend(bad_codes(&config.scrape_hydrate, "scrape_hydrate", &|c| {
+        c.parse::<Hydration>().is_ok()
+    }));
+    checks.push((
+        "settings",
+        if problems.is_empty() {
+            Ok("valid".to_string())
+        } else {
+            Err(problems.join("; "))
+        },
+    ));
+
+    let url = &config.db_url;
+    // Shown without any password in it
+    let shown = config
+        .redacted()
+        .into_iter()
+        .find_map(|(name, value)| (name == "db_url").then_some(value))
+        .unwrap_or_default();
+    checks.push((
+        "database",
+        match gtm_db::connect(url, &connect_options(config)).await {
+            Ok(pool) => match gtm_db::maintenance::pending_migrations(&pool, url).await {
+                Ok(pending) if pending.is_empty() => {
+                    Ok(format!("connected to {shown}; up to date"))
+                }
+                Ok(pending) => Ok(format!(
+                    "connected to {shown}; {} pending migration(s), run `gtm db migrate`",
+                    pending.len()
+                )),
+                Err(e) => Err(format!("connected but couldn't read migrations: {e}")),
+            },
+            Err(e) => Err(format!("{shown}: {e}")),
+        },
+    ));
+
+    checks.push((
+        "auth0",
+        match local_auth(config) {
+            Ok(true) => Ok(
+                "off (auth_mode = none); anyone who can reach the server can act as any member"
+                    .to_string(),
+            ),
+            Ok(false) => match fetch_jwks(&config.auth0_domain).await {
+                Ok(keys) => Ok(format!(
+                    "{} serves {} key(s)",
+                    config.auth0_domain,
+                    keys.len()
+                )),
+                Err(e) => Err(format!("{}: {e}", config.auth0_domain)),
+            },
+            Err(e) => Err(e.to_string()),
+        },
+    ));
+
+    checks.push((
+        "port",
+        match std::net::TcpListener::bind(("0.0.0.0", config.port)) {
+            Ok(_) => Ok(format!("{} is free", config.port)),
+            Err(e) => Err(format!("{}: {e}", config.port)),
+        },
+    ));
+    checks
+}
+
+/// The filter of the saved view `name`, for `gtm list-games --view`. With
+/// several members' views of that name, `user_email` picks one.
+async fn saved_view_filter(
+    pool: &AnyPool,
+    name: &str,
+    user_email: Option<&str>,
+) -> anyhow::Result<gtm_db::GameFilter> {
+    let user_id = match user_email {
+        Some(email) => Some(
+            gtm_db::get_user_by_email(pool, email)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No user with email {email}"))?
+                .id,
+        ),
+        None => None,
+    };
+    let mut views = gtm_db::saved_views::find_by_name(pool, name, user_id).await?;
+    match views.len() {
+        0 => anyhow::bail!("No saved view named {name:?}"),
+        1 => Ok(views.remove(0).filter),
+        n => anyhow::bail!("{n} members have a view named {name:?}; pick one with --user"),
+    }
 }
 
 // --- Main ---
@@ -1363,6 +8263,9 @@ async fn connect_db(config: &gtm_config::Config) -> anyhow::Result<AnyPool> {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    if cli.format.is_some() && !cli.command.takes_format() {
+        anyhow::bail!("--format only applies to the list commands, `requests list` and `export`");
+    }
 
     // Load config: defaults → file → env
     let mut config = gtm_config::Config::load();
@@ -1381,78 +8284,172 @@ async fn main() -> anyhow::Result<()> {
         config.port = *p;
     }
 
+    let cli_settings: Vec<&str> = [
+        ("log_level", cli.log_level.is_some()),
+        ("utc", cli.utc),
+        ("db_url", cli.db_url.is_some()),
+        (
+            "port",
+            matches!(cli.command, Commands::Serve { port: Some(_) }),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, set)| set.then_some(name))
+    .collect();
+
     init_logging(&config);
+    gtm_scraper::http::configure(http_policy(&config))?;
+    gtm_scraper::breaker::configure(breaker_policy(&config));
 
     // Connect to DB for commands that need it (CLI always uses direct DB)
-    let needs_db = !matches!(cli.command, Commands::Hello);
+    let needs_db = !matches!(
+        cli.command,
+        Commands::Hello | Commands::Db { .. } | Commands::Config { .. }
+    );
     let pool = if needs_db {
         Some(connect_db(&config).await?)
     } else {
         None
     };
 
+    let format = cli.format.unwrap_or_default();
     match cli.command {
         Commands::Hello => {
             println!("Hello, Giants! 🏟️");
         }
+        Commands::Db { command } => {
+            run_db_command(&config, command).await?;
+        }
+        Commands::Config { command } => {
+            run_config_command(&config, &cli_settings, command).await?;
+        }
         Commands::Serve { .. } => {
             run_server(config.port, pool.unwrap(), &config).await?;
         }
-        Commands::ScrapeSchedule { season } => {
+        Commands::Worker => {
+            run_worker(pool.unwrap(), &config).await?;
+        }
+        Commands::ScrapeSchedule {
+            season,
+            game_types,
+            hydrate,
+            force,
+        } => {
             let db = pool.as_ref().unwrap();
-            run_scrape(db, season).await?;
+            let season = match season {
+                Some(season) => season,
+                None => Utc::now().with_timezone(&display_tz(&config)?).year() as u32,
+            };
+            let game_types = (!game_types.is_empty()).then_some(game_types.as_slice());
+            let hydrate = (!hydrate.is_empty()).then_some(hydrate.as_slice());
+            match run_scrape(db, &config, season, game_types, hydrate, force).await? {
+                None => println!("A schedule sync is already running; try again later."),
+                Some((sync, _)) if !sync.anomalies.is_empty() => {
+                    println!("Scrape looks suspect; games updated but no seats or requests moved:");
+                    for anomaly in &sync.anomalies {
+                        println!("  {anomaly}");
+                    }
+                    println!("Re-run with --force if the change is real.");
+                }
+                Some(_) => {}
+            }
         }
-        Commands::ListGames { month } => {
+        Commands::ListPromotions {
+            offer_type,
+            name,
+            distribution,
+            month,
+        } => {
             let db = pool.as_ref().unwrap();
-            let games = gtm_db::list_games(db, month).await?;
-            if games.is_empty() {
-                println!("No games found.");
-            } else {
-                println!(
-                    "{:<10} {:<12} {:<22} {:<6} {:<25} {:<10} {:<20} Promotions",
-                    "GamePK", "Date", "Time", "H/A", "Opponent", "Status", "Venue"
-                );
-                println!("{}", "-".repeat(140));
-                for g in &games {
-                    let home_away = if g.home_team_name == "San Francisco Giants" {
-                        "home"
+            let filter = gtm_db::PromotionFilter {
+                name,
+                offer_type,
+                distribution,
+                month,
+                ..Default::default()
+            };
+            let promos = gtm_db::search_promotions(db, &filter).await?;
+            let rows: Vec<PromotionListing> = promos
+                .into_iter()
+                .map(|p| PromotionListing {
+                    game_pk: p.game_pk,
+                    date: p.official_date,
+                    opponent: if p.home_team_name == config.team_name {
+                        p.away_team_name
                     } else {
-                        "away"
-                    };
-                    let opponent = if home_away == "home" {
+                        p.home_team_name
+                    },
+                    promotion: p.name,
+                    offer_type: p.offer_type,
+                    distribution: p.distribution,
+                })
+                .collect();
+            output::print(
+                format,
+                &rows,
+                "No promotions found.",
+                &format!("{} promotion(s) total", rows.len()),
+            )?;
+        }
+        Commands::ListGames {
+            month,
+            game_types,
+            view,
+            user,
+        } => {
+            let db = pool.as_ref().unwrap();
+            let mut filter = match view {
+                Some(name) => saved_view_filter(db, &name, user.as_deref()).await?,
+                None => gtm_db::GameFilter {
+                    month,
+                    ..Default::default()
+                },
+            };
+            filter.team_id = config.team_id;
+            if !game_types.is_empty() {
+                let codes: Vec<&str> = game_types.iter().map(|t| t.code()).collect();
+                filter.game_type = Some(codes.join(","));
+            }
+            let games = gtm_db::list_games_with_details(db, &filter).await?;
+            let tz = display_tz(&config)?;
+            let rows: Vec<GameListing> = games
+                .into_iter()
+                .map(|details| {
+                    let g = details.game;
+                    let home = g.home_team_id == i64::from(config.team_id);
+                    let opponent = if home {
                         &g.away_team_name
                     } else {
                         &g.home_team_name
                     };
-                    let time_display = if g.start_time_tbd != 0 {
-                        "TBD".to_string()
-                    } else {
-                        g.game_date.clone()
+                    let opponent = match g.double_header_label() {
+                        Some(label) => format!("{opponent} ({label})"),
+                        None => opponent.clone(),
                     };
-                    let promos = gtm_db::get_promotions_for_game(db, g.game_pk).await?;
-                    let promo_display = if promos.is_empty() {
-                        String::new()
-                    } else {
-                        promos
-                            .iter()
-                            .map(|p| p.name.as_str())
-                            .collect::<Vec<_>>()
-                            .join(", ")
+                    let time = match g.local_first_pitch(tz) {
+                        Some(t) => t.format("%-I:%M %p %Z").to_string(),
+                        None if g.start_time_tbd != 0 => "TBD".to_string(),
+                        None => g.game_date.clone(),
                     };
-                    println!(
-                        "{:<10} {:<12} {:<22} {:<6} {:<25} {:<10} {:<20} {}",
-                        g.game_pk,
-                        g.official_date,
-                        time_display,
-                        home_away,
+                    GameListing {
+                        game_pk: g.game_pk,
+                        date: g.official_date,
+                        time,
+                        game_type: GameType::label_for(&g.game_type).to_string(),
+                        home_away: if home { "home" } else { "away" },
                         opponent,
-                        g.status_detailed,
-                        g.venue_name,
-                        promo_display,
-                    );
-                }
-                println!("\n{} game(s) total", games.len());
-            }
+                        status: g.status_detailed,
+                        venue: g.venue_name,
+                        promotions: details.promotions.unwrap_or_default(),
+                    }
+                })
+                .collect();
+            output::print(
+                format,
+                &rows,
+                "No games found.",
+                &format!("{} game(s) total", rows.len()),
+            )?;
         }
         Commands::AddSeat {
             section,
@@ -1462,78 +8459,418 @@ async fn main() -> anyhow::Result<()> {
         } => {
             let db = pool.as_ref().unwrap();
             let new_seat = gtm_db::add_seat(db, &section, &row, &seat, notes.as_deref()).await?;
-            let count = gtm_db::generate_tickets_for_seat(db, new_seat.id).await?;
+            let count = gtm_db::generate_tickets_for_seat(
+                db,
+                new_seat.id,
+                config.team_id,
+                &ticketed_game_types(&config),
+            )
+            .await?;
             println!(
                 "Added seat: Section {} Row {} Seat {} (id={})",
                 new_seat.section, new_seat.row, new_seat.seat, new_seat.id
             );
             println!("{count} game tickets generated for home games");
         }
+        Commands::AddUser { email, name } => {
+            let db = pool.as_ref().unwrap();
+            if let Some(user) = gtm_db::get_user_by_email(db, &email).await? {
+                anyhow::bail!("{email} is already a member (id={})", user.id);
+            }
+            let user = gtm_db::upsert_user(db, &format!("local|{email}"), &email, &name).await?;
+            println!(
+                "Added user: {} <{}> (id={})",
+                user.name, user.email, user.id
+            );
+        }
+        Commands::ImportSeats { file } => {
+            let db = pool.as_ref().unwrap();
+            let text = std::fs::read_to_string(&file)?;
+            let rows = if file
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+            {
+                serde_json::from_str(&text)?
+            } else {
+                gtm_db::import::parse_seats_csv(&text)?
+            };
+            let import = gtm_db::import::import_seats(
+                db,
+                &rows,
+                config.team_id,
+                &ticketed_game_types(&config),
+            )
+            .await?;
+            println!("Imported {} seat(s)", import.seats.len());
+            println!("{} game tickets generated for home games", import.tickets);
+        }
+        Commands::ImportPrices { file, dry_run } => {
+            let db = pool.as_ref().unwrap();
+            let lines = gtm_db::prices::parse_csv(&std::fs::read_to_string(&file)?)?;
+            let rows = gtm_db::prices::resolve(db, config.team_id, &lines).await?;
+            print_price_import(&rows);
+            let unmatched = rows.iter().filter(|r| r.problem.is_some()).count();
+            if dry_run {
+                println!("Dry run: no prices written");
+            } else {
+                let admin = cli_admin(db, &config, cli.as_admin.as_deref()).await?;
+                let changed = gtm_db::prices::apply(db, &rows, admin.user.id).await?;
+                println!("{changed} ticket price(s) changed");
+            }
+            if unmatched > 0 {
+                println!("{unmatched} line(s) skipped; see ERROR above");
+            }
+        }
         Commands::ListSeats => {
             let db = pool.as_ref().unwrap();
             let seats = gtm_db::list_seats(db).await?;
-            if seats.is_empty() {
-                println!("No seats registered. Use `gtm add-seat` to add one.");
-            } else {
-                println!(
-                    "{:<6} {:<10} {:<6} {:<6} Notes",
-                    "ID", "Section", "Row", "Seat"
+            let rows: Vec<SeatListing> = seats
+                .into_iter()
+                .map(|s| SeatListing {
+                    id: s.id,
+                    section: s.section,
+                    row: s.row,
+                    seat: s.seat,
+                    notes: s.notes,
+                })
+                .collect();
+            output::print(
+                format,
+                &rows,
+                "No seats registered. Use `gtm add-seat` to add one.",
+                &format!("{} seat(s) total", rows.len()),
+            )?;
+        }
+        Commands::DeleteSeatGroup { section, row, yes } => {
+            let db = pool.as_ref().unwrap();
+            let seats = gtm_db::list_seats_in_group(db, &section, &row).await?;
+            if seats.len() as i64 > config.bulk_confirm_threshold && !yes {
+                anyhow::bail!(
+                    "This deletes {} seats in Section {section} Row {row} (limit {}); \
+                     pass --yes to confirm",
+                    seats.len(),
+                    config.bulk_confirm_threshold
                 );
-                println!("{}", "-".repeat(50));
-                for s in &seats {
+            }
+            let outcomes = gtm_db::delete_seat_group(db, &section, &row).await?;
+            if outcomes.is_empty() {
+                println!("No seats found in Section {section} Row {row}.");
+            } else {
+                for o in &outcomes {
+                    println!(
+                        "Deleted seat {} (id={}): {} tickets removed, {} kept",
+                        o.seat.seat, o.seat.id, o.tickets_removed, o.tickets_kept
+                    );
+                }
+                println!("\n{} seat(s) deleted", outcomes.len());
+            }
+        }
+        Commands::RegenerateSeatGroupTickets { section, row } => {
+            let db = pool.as_ref().unwrap();
+            let outcomes = gtm_db::regenerate_seat_group_tickets(
+                db,
+                &section,
+                &row,
+                config.team_id,
+                &ticketed_game_types(&config),
+            )
+            .await?;
+            if outcomes.is_empty() {
+                println!("No seats found in Section {section} Row {row}.");
+            } else {
+                for o in &outcomes {
                     println!(
-                        "{:<6} {:<10} {:<6} {:<6} {}",
-                        s.id,
-                        s.section,
-                        s.row,
-                        s.seat,
-                        s.notes.as_deref().unwrap_or("")
+                        "Seat {} (id={}): {} tickets generated",
+                        o.seat.seat, o.seat.id, o.tickets_generated
                     );
                 }
-                println!("\n{} seat(s) total", seats.len());
             }
         }
         Commands::ListTickets => {
             let db = pool.as_ref().unwrap();
             let seats = gtm_db::list_seats(db).await?;
-            if seats.is_empty() {
-                println!("No seats registered. Use `gtm add-seat` to add one.");
+            let mut rows = Vec::new();
+            if !seats.is_empty() {
+                let games = gtm_db::list_games(db, &Default::default()).await?;
+                for g in games
+                    .into_iter()
+                    .filter(|g| g.home_team_id == i64::from(config.team_id))
+                {
+                    let tickets = gtm_db::list_tickets_for_game(db, g.game_pk).await?;
+                    rows.push(TicketListing {
+                        game_pk: g.game_pk,
+                        date: g.official_date,
+                        opponent: g.away_team_name,
+                        available: tickets.iter().filter(|t| t.status == "available").count(),
+                        total: tickets.len(),
+                        tickets: tickets
+                            .iter()
+                            .map(|t| format!("{}:{}{} [{}]", t.section, t.row, t.seat, t.status))
+                            .collect(),
+                    });
+                }
+            }
+            output::print(
+                format,
+                &rows,
+                if seats.is_empty() {
+                    "No seats registered. Use `gtm add-seat` to add one."
+                } else {
+                    "No home games found."
+                },
+                &format!("{} home game(s), {} seat(s)", rows.len(), seats.len()),
+            )?;
+        }
+        Commands::Allocate {
+            game: Some(game),
+            user: Some(user),
+            seats: Some(seats),
+            dry_run,
+            allow_split,
+            ..
+        } => {
+            let db = pool.as_ref().unwrap();
+            let Some(member) = gtm_db::get_user(db, user).await? else {
+                anyhow::bail!("User {user} not found");
+            };
+            let request_id = gtm_db::list_requests(db, Some(game), Some("pending"))
+                .await?
+                .into_iter()
+                .find(|r| r.user_id == user)
+                .map(|r| r.id);
+            let admin = if dry_run {
+                None
             } else {
-                let games = gtm_db::list_games(db, None).await?;
-                let home_games: Vec<_> = games
+                Some(cli_admin(db, &config, cli.as_admin.as_deref()).await?)
+            };
+            let assigned = assign_by_hand(
+                db,
+                admin.as_ref(),
+                game,
+                &member,
+                seats,
+                request_id,
+                allow_split,
+            )
+            .await?;
+            if !dry_run {
+                println!("\n{assigned} seat(s) assigned to {}", member.name);
+            }
+        }
+        Commands::Allocate {
+            plan: Some(plan),
+            dry_run,
+            allow_split,
+            ..
+        } => {
+            let db = pool.as_ref().unwrap();
+            let lines = gtm_db::allocation_plan::parse_csv(&std::fs::read_to_string(&plan)?)?;
+            let rows = gtm_db::allocation_plan::resolve(db, &lines).await?;
+            print_allocation_plan(&rows, None);
+            let assignments = gtm_db::allocation_plan::assignments(&rows)?;
+            if dry_run {
+                let parties: Vec<(TicketId, UserId)> =
+                    assignments.iter().map(|(t, u, _)| (*t, *u)).collect();
+                println!("\nDry run: {} seat(s) would be assigned", assignments.len());
+                for party in gtm_db::seating::check_parties(db, &parties, allow_split).await? {
+                    println!("Warning: {}", gtm_db::seating::warning(party));
+                }
+                return Ok(());
+            }
+            let admin = cli_admin(db, &config, cli.as_admin.as_deref()).await?;
+            let request = serde_json::json!({
+                "plan": plan.display().to_string(),
+                "lines": lines
                     .iter()
-                    .filter(|g| g.home_team_name == "San Francisco Giants")
-                    .collect();
-                println!(
-                    "{:<10} {:<12} {:<25} Tickets (available/total)",
-                    "GamePK", "Date", "Opponent"
-                );
-                println!("{}", "-".repeat(80));
-                for g in &home_games {
-                    let tickets = gtm_db::list_tickets_for_game(db, g.game_pk).await?;
-                    let available = tickets.iter().filter(|t| t.status == "available").count();
-                    let detail: Vec<String> = tickets
-                        .iter()
-                        .map(|t| format!("{}:{}{} [{}]", t.section, t.row, t.seat, t.status))
-                        .collect();
-                    println!(
-                        "{:<10} {:<12} {:<25} {}/{} — {}",
-                        g.game_pk,
-                        g.official_date,
-                        g.away_team_name,
-                        available,
-                        tickets.len(),
-                        detail.join(", "),
+                    .map(|l| serde_json::json!({
+                        "game_pk": l.game_pk,
+                        "user_email": l.user_email,
+                        "seats": l.seats,
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            let (assigned, _, warnings) = service::commit_assignments(
+                db.clone(),
+                admin.gcal,
+                admin.user.id,
+                assignments,
+                allow_split,
+                request,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!(e.message))?;
+
+            // Report what each line got, from the tickets as they are now
+            let mut holders = std::collections::HashMap::new();
+            for row in &rows {
+                if let std::collections::hash_map::Entry::Vacant(e) =
+                    holders.entry(row.line.game_pk)
+                {
+                    let tickets = gtm_db::list_tickets_for_game(db, row.line.game_pk).await?;
+                    e.insert(
+                        tickets
+                            .into_iter()
+                            .map(|t| (t.id, t.assigned_to))
+                            .collect::<std::collections::HashMap<_, _>>(),
                     );
                 }
+            }
+            let got: Vec<usize> = rows
+                .iter()
+                .map(|row| {
+                    let held = &holders[&row.line.game_pk];
+                    let user_id = row.user.as_ref().map(|u| u.id);
+                    row.tickets
+                        .iter()
+                        .filter(|t| held.get(t).copied().flatten() == user_id)
+                        .count()
+                })
+                .collect();
+            println!();
+            print_allocation_plan(&rows, Some(&got));
+            println!("\n{assigned} seat(s) assigned");
+            for warning in warnings.iter() {
+                println!("Warning: {warning}");
+            }
+        }
+        Commands::Allocate {
+            game,
+            strategy,
+            seed,
+            dry_run,
+            allow_split,
+            plan: None,
+            ..
+        } => {
+            let game = game.expect("clap requires --game without --plan");
+            let db = pool.as_ref().unwrap();
+            let strategy: gtm_alloc::Strategy = strategy
+                .as_deref()
+                .unwrap_or(&config.allocation_strategy)
+                .parse()
+                .map_err(|e: String| anyhow::anyhow!(e))?;
+            let Some(g) = gtm_db::get_game(db, game).await? else {
+                anyhow::bail!("Game {game} not found");
+            };
+            let (allocation, assigned, warnings) = if dry_run {
+                let mut warnings = gtm_db::Warnings::new();
+                let (allocation, assigned, _) = run_auto_allocation(
+                    db,
+                    &g,
+                    strategy,
+                    seed,
+                    AutoRun::Preview,
+                    None,
+                    &mut warnings,
+                )
+                .await?;
+                (allocation, assigned, warnings)
+            } else {
+                let admin = cli_admin(db, &config, cli.as_admin.as_deref()).await?;
+                let (allocation, assigned, _, warnings) = service::auto_allocate(
+                    db.clone(),
+                    admin.gcal,
+                    g.clone(),
+                    strategy,
+                    seed,
+                    admin.user.id,
+                    allow_split,
+                )
+                .await
+                .map_err(|e| anyhow::anyhow!(e.message))?;
+                (allocation, assigned, warnings)
+            };
+            let users = gtm_db::list_users(db).await?;
+            let user_map: std::collections::HashMap<UserId, &str> =
+                users.iter().map(|u| (u.id, u.name.as_str())).collect();
+
+            println!(
+                "{} vs {} — strategy {}, seed {}",
+                g.official_date, g.away_team_name, allocation.strategy, allocation.seed
+            );
+            println!(
+                "{:<8} {:<25} {:<10} Tickets",
+                "Request", "Member", "Requested"
+            );
+            println!("{}", "-".repeat(70));
+            for p in &allocation.proposals {
+                let tickets: Vec<String> = p.ticket_ids.iter().map(|t| t.to_string()).collect();
+                println!(
+                    "{:<8} {:<25} {:<10} {}",
+                    p.request_id,
+                    user_map.get(&p.user_id).copied().unwrap_or("?"),
+                    p.seats_requested,
+                    if tickets.is_empty() {
+                        "—".to_string()
+                    } else {
+                        tickets.join(", ")
+                    }
+                );
+            }
+            if dry_run {
                 println!(
-                    "\n{} home game(s), {} seat(s)",
-                    home_games.len(),
-                    seats.len()
+                    "\nDry run: {} seat(s) would be assigned",
+                    allocation.assignments().len()
                 );
+            } else {
+                println!("\n{assigned} seat(s) assigned");
+            }
+            for warning in warnings.iter() {
+                println!("Warning: {warning}");
+            }
+        }
+        Commands::Revoke { ticket } => {
+            let db = pool.as_ref().unwrap();
+            let Some(detail) = gtm_db::get_ticket(db, ticket).await? else {
+                anyhow::bail!("Ticket {ticket} not found");
+            };
+            let Some(holder) = detail.assigned_to else {
+                anyhow::bail!("Ticket {ticket} isn't assigned");
+            };
+            let admin = cli_admin(db, &config, cli.as_admin.as_deref()).await?;
+            if !gtm_db::revoke_ticket(db, ticket, Some(admin.user.id)).await? {
+                anyhow::bail!("Ticket {ticket} isn't assigned");
+            }
+            queue_revocation_email(db, holder, &detail).await?;
+            spawn_calendar_sync(db.clone(), admin.gcal, vec![holder]);
+            println!("Revoked {} from user {holder}", seat_label(&detail));
+        }
+        Commands::Requests { command } => {
+            run_requests_command(
+                pool.as_ref().unwrap(),
+                &config,
+                cli.as_admin.as_deref(),
+                format,
+                command,
+            )
+            .await?;
+        }
+        Commands::Tui => {
+            let db = pool.as_ref().unwrap();
+            let admin = cli_admin(db, &config, cli.as_admin.as_deref()).await?;
+            tui::run(db, admin, display_tz(&config)?).await?;
+        }
+        Commands::Export { what, season } => {
+            use futures_util::TryStreamExt;
+
+            if cli.format.is_some_and(|f| f != output::Format::Csv) {
+                anyhow::bail!("export only writes CSV");
+            }
+            use std::io::Write;
+
+            let db = pool.as_ref().unwrap().clone();
+            let mut stdout = std::io::stdout().lock();
+            let mut chunks = std::pin::pin!(export_csv(
+                db,
+                season_or_current(season, display_tz(&config)?),
+                what
+            ));
+            while let Some(chunk) = chunks.try_next().await? {
+                stdout.write_all(chunk.as_bytes())?;
             }
         }
     }
 
+    finish_calendar_syncs().await;
     Ok(())
 }
@@ -0,0 +1,336 @@
+//! How the listing commands print: a padded table for reading, or JSON or
+//! CSV for piping into `jq` or a spreadsheet (`gtm --format json ...`).
+//!
+//! Each listing builds rows of a [`Listing`] type and hands them to
+//! [`print`]. The JSON is the rows as serde serializes them; the table and
+//! CSV have one column per field, in declaration order, with lists joined
+//! by commas. CSV fields are escaped as `gtm export` escapes them, formulas
+//! included.
+
+use std::io::Write;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde::ser::{Impossible, SerializeStruct, Serializer};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+/// A row of a listing. Must serialize as a struct.
+pub trait Listing: Serialize {
+    /// Each field's name as serialized, with the table's header and width
+    /// for it, in declaration order; the last column isn't padded. The
+    /// names head the CSV even when there are no rows.
+    const COLUMNS: &'static [(&'static str, &'static str, usize)];
+}
+
+/// Print `rows` in `format`. A table ends with `footer`, or is just `empty`
+/// if there are no rows.
+pub fn print<T: Listing>(
+    format: Format,
+    rows: &[T],
+    empty: &str,
+    footer: &str,
+) -> anyhow::Result<()> {
+    write(&mut std::io::stdout().lock(), format, rows, empty, footer)
+}
+
+/// [`print`] to `out`.
+fn write<T: Listing>(
+    out: &mut impl Write,
+    format: Format,
+    rows: &[T],
+    empty: &str,
+    footer: &str,
+) -> anyhow::Result<()> {
+    match format {
+        Format::Json => writeln!(out, "{}", serde_json::to_string_pretty(rows)?)?,
+        Format::Csv => {
+            writeln!(
+                out,
+                "{}",
+                csv_line(T::COLUMNS.iter().map(|(name, _, _)| *name))
+            )?;
+            for row in rows {
+                let fields = row.serialize(FieldSerializer)?;
+                debug_assert!(
+                    fields
+                        .iter()
+                        .map(|(name, _)| name)
+                        .eq(T::COLUMNS.iter().map(|(name, _, _)| name)),
+                    "COLUMNS doesn't match the serialized fields"
+                );
+                writeln!(out, "{}", csv_line(fields.iter().map(|(_, v)| v.as_str())))?;
+            }
+        }
+        Format::Table if rows.is_empty() => writeln!(out, "{empty}")?,
+        Format::Table => {
+            let headers: Vec<&str> = T::COLUMNS.iter().map(|(_, h, _)| *h).collect();
+            writeln!(out, "{}", table_line(T::COLUMNS, &headers))?;
+            let width: usize = T::COLUMNS.iter().map(|(_, _, w)| w + 1).sum();
+            writeln!(out, "{}", "-".repeat(width + 20))?;
+            for row in rows {
+                let fields = row.serialize(FieldSerializer)?;
+                let cells: Vec<&str> = fields.iter().map(|(_, v)| v.as_str()).collect();
+                writeln!(out, "{}", table_line(T::COLUMNS, &cells))?;
+            }
+            writeln!(out, "\n{footer}")?;
+        }
+    }
+    Ok(())
+}
+
+fn table_line(columns: &[(&str, &str, usize)], cells: &[&str]) -> String {
+    let last = cells.len().saturating_sub(1);
+    cells
+        .iter()
+        .enumerate()
+        .map(|(i, cell)| match columns.get(i) {
+            Some((_, _, width)) if i < last => format!("{cell:<width$}"),
+            _ => cell.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    fields
+        .map(gtm_db::export::csv_field)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// How a field shows in a table cell or CSV field.
+fn cell(value: serde_json::Value) -> String {
+    use serde_json::Value;
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s,
+        Value::Array(items) => items.into_iter().map(cell).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// Serializes a struct to its field names and cells, in order.
+struct FieldSerializer;
+
+type Fields = Vec<(&'static str, String)>;
+
+struct FieldCollector(Fields);
+
+impl SerializeStruct for FieldCollector {
+    type Ok = Fields;
+    type Error = serde_json::Error;
+
+    fn serialize_field<V: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &V,
+    ) -> Result<(), Self::Error> {
+        self.0.push((key, cell(serde_json::to_value(value)?)));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Fields, Self::Error> {
+        Ok(self.0)
+    }
+}
+
+fn not_a_struct() -> serde_json::Error {
+    serde::ser::Error::custom("listing rows must be structs")
+}
+
+macro_rules! reject {
+    ($($method:ident($($arg:ty),*);)*) => {
+        $(fn $method(self, $(_: $arg),*) -> Result<Fields, serde_json::Error> {
+            Err(not_a_struct())
+        })*
+    };
+}
+
+impl Serializer for FieldSerializer {
+    type Ok = Fields;
+    type Error = serde_json::Error;
+    type SerializeSeq = Impossible<Fields, serde_json::Error>;
+    type SerializeTuple = Impossible<Fields, serde_json::Error>;
+    type SerializeTupleStruct = Impossible<Fields, serde_json::Error>;
+    type SerializeTupleVariant = Impossible<Fields, serde_json::Error>;
+    type SerializeMap = Impossible<Fields, serde_json::Error>;
+    type SerializeStruct = FieldCollector;
+    type SerializeStructVariant = Impossible<Fields, serde_json::Error>;
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<FieldCollector, serde_json::Error> {
+        Ok(FieldCollector(Vec::with_capacity(len)))
+    }
+
+    reject! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_some<V: Serialize + ?Sized>(self, _: &V) -> Result<Fields, serde_json::Error> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_newtype_struct<V: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: &V,
+    ) -> Result<Fields, serde_json::Error> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_newtype_variant<V: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &V,
+    ) -> Result<Fields, serde_json::Error> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, serde_json::Error> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, serde_json::Error> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, serde_json::Error> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, serde_json::Error> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, serde_json::Error> {
+        Err(not_a_struct())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, serde_json::Error> {
+        Err(not_a_struct())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        id: i64,
+        name: String,
+        note: Option<String>,
+        tags: Vec<String>,
+    }
+
+    impl Listing for Row {
+        const COLUMNS: &'static [(&'static str, &'static str, usize)] = &[
+            ("id", "ID", 4),
+            ("name", "Name", 8),
+            ("note", "Note", 10),
+            ("tags", "Tags", 0),
+        ];
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                id: 1,
+                name: "Ann".into(),
+                note: None,
+                tags: vec!["a".into(), "b".into()],
+            },
+            Row {
+                id: 2,
+                name: "=1+1".into(),
+                note: Some("x, \"y\"".into()),
+                tags: vec![],
+            },
+        ]
+    }
+
+    fn render(format: Format, rows: &[Row]) -> String {
+        let mut out = Vec::new();
+        write(&mut out, format, rows, "Nothing.", "2 row(s)").unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn tables_pad_every_column_but_the_last() {
+        let table = render(Format::Table, &rows());
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "ID   Name     Note       Tags");
+        assert_eq!(lines[2], "1    Ann                 a, b");
+        assert_eq!(lines[3], "2    =1+1     x, \"y\"     ");
+        assert_eq!(lines[5], "2 row(s)");
+        assert_eq!(render(Format::Table, &[]), "Nothing.\n");
+    }
+
+    #[test]
+    fn json_is_the_rows_as_serialized() {
+        let json: serde_json::Value = serde_json::from_str(&render(Format::Json, &rows())).unwrap();
+        assert_eq!(json[0]["note"], serde_json::Value::Null);
+        assert_eq!(json[0]["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(render(Format::Json, &[]), "[]\n");
+    }
+
+    #[test]
+    fn csv_escapes_fields_and_always_has_a_header() {
+        assert_eq!(
+            render(Format::Csv, &rows()),
+            "id,name,note,tags\n1,Ann,,\"a, b\"\n2,'=1+1,\"x, \"\"y\"\"\",\n"
+        );
+        assert_eq!(render(Format::Csv, &[]), "id,name,note,tags\n");
+    }
+
+    #[test]
+    fn rows_must_be_structs() {
+        assert!(42.serialize(FieldSerializer).is_err());
+        assert!(Some("x").serialize(FieldSerializer).is_err());
+    }
+}
@@ -0,0 +1,670 @@
+//! Multi-step mutations behind the HTTP handlers.
+//!
+//! Axum drops a handler's future when its client disconnects, so a handler
+//! making several writes could stop between two of them and leave half a
+//! change behind. Each function here runs its writes through [`detached`]:
+//! the handler waits for the result, but the work finishes even if the
+//! handler is dropped. A mutation that is a single `gtm_db` call (one
+//! statement or one transaction) is already all-or-nothing and doesn't need
+//! this. `tests/cancellation.rs` fails the build when a handler makes more
+//! than one write without coming through here.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use chrono::{Datelike, Utc};
+use gtm_models::{GamePk, GameType, Hydration, TicketId, UserId};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use sqlx::AnyPool;
+use tracing::{info, warn};
+
+use crate::{
+    AddSeatBatchRequest, AddSeatRequest, AllocateBody, ApiError, AutoRun, BulkGuardQuery,
+    BulkPreview, CreateRequestBody, InboundEmailForm, display_tz, email_address,
+    guard_bulk_operation, queue_allocation_emails, remove_calendar_events, rfc3339_utc,
+    run_auto_allocation, run_scrape, spawn_calendar_sync, spawn_day_of_release_sms,
+    ticketed_game_types,
+};
+
+/// Run `work` as its own task and wait for it. If the caller is dropped the
+/// task carries on to the end; a panic in it is re-raised in the caller,
+/// and a task cancelled by the runtime shutting down is an error.
+pub async fn detached<T, E, F>(work: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>> + Send + 'static,
+    T: Send + 'static,
+    E: From<anyhow::Error> + Send + 'static,
+{
+    match tokio::spawn(work).await {
+        Ok(output) => output,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(anyhow::Error::new(e)
+            .context("Cancelled while shutting down")
+            .into()),
+    }
+}
+
+/// Run `work` at most once per idempotency `key`. Without a key it just
+/// runs. With one, the first request runs `work` and stores its result; a
+/// retry with the same key and `request` body gets that result back, one
+/// with a different body is refused, and one arriving while the first is
+/// still running is a conflict. A failed run frees the key for a retry.
+pub async fn idempotent<T, F>(
+    pool: AnyPool,
+    user_id: UserId,
+    endpoint: &'static str,
+    key: Option<String>,
+    request: String,
+    work: F,
+) -> Result<T, ApiError>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    F: Future<Output = Result<T, ApiError>> + Send + 'static,
+{
+    use gtm_db::idempotency::{self, Claim};
+
+    let Some(key) = key else {
+        return work.await;
+    };
+    // Claim inside the task: a caller dropped between claiming
+    // and running would leave the key in progress until it expires.
+    detached(async move {
+        match idempotency::claim(&pool, user_id, endpoint, &key, &request)
+            .await
+            .map_err(ApiError::invalid)?
+        {
+            Claim::New => {}
+            Claim::Replay(response) => {
+                info!(%user_id, endpoint, "Replaying idempotent request");
+                return serde_json::from_str(&response)
+                    .map_err(|e| ApiError::internal(e.to_string()));
+            }
+            Claim::InProgress => {
+                return Err(ApiError::conflict(
+                    "A request with this Idempotency-Key is still in progress",
+                ));
+            }
+            Claim::Mismatch => {
+                return Err(ApiError::new(
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "idempotency_mismatch",
+                    "This Idempotency-Key was used with a different request",
+                ));
+            }
+        }
+        let result = work.await;
+        let stored = match &result {
+            Ok(value) => match serde_json::to_string(value) {
+                Ok(response) => {
+                    idempotency::complete(&pool, user_id, endpoint, &key, &response).await
+                }
+                Err(e) => Err(e.into()),
+            },
+            Err(_) => idempotency::abandon(&pool, user_id, endpoint, &key).await,
+        };
+        if let Err(e) = stored {
+            warn!(%user_id, endpoint, "Failed to record idempotency key: {e}");
+            let _ = idempotency::abandon(&pool, user_id, endpoint, &key).await;
+        }
+        result
+    })
+    .await
+}
+
+/// What a guarded bulk operation did.
+pub enum Guarded<T> {
+    /// A dry run: what would be affected, and nothing done.
+    Preview(BulkPreview),
+    Done(T),
+}
+
+/// Run a destructive bulk `work` behind [`guard_bulk_operation`]. A dry run
+/// returns the preview; otherwise the confirm token is taken and `work` run
+/// in one task, so a dropped request can't use up the token without doing
+/// the work.
+pub async fn guarded<T, F>(
+    pool: AnyPool,
+    config: Arc<gtm_config::Config>,
+    operation: &'static str,
+    scope: String,
+    affected: i64,
+    guard: BulkGuardQuery,
+    work: F,
+) -> Result<Guarded<T>, ApiError>
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T, ApiError>> + Send + 'static,
+{
+    detached(async move {
+        if let Some(preview) = guard_bulk_operation(
+            &pool,
+            &config,
+            operation,
+            &scope,
+            affected,
+            guard.dry_run,
+            guard.confirm_token.as_deref(),
+        )
+        .await?
+        {
+            return Ok(Guarded::Preview(preview));
+        }
+        Ok(Guarded::Done(work.await?))
+    })
+    .await
+}
+
+/// Add a seat and its tickets for the ticketed home games. Returns the seat
+/// and the number of tickets generated.
+pub async fn add_seat(
+    pool: AnyPool,
+    config: Arc<gtm_config::Config>,
+    body: AddSeatRequest,
+) -> Result<(gtm_models::Seat, u64), ApiError> {
+    detached(async move {
+        let seat = gtm_db::add_seat(
+            &pool,
+            &body.section,
+            &body.row,
+            &body.seat,
+            body.notes.as_deref(),
+        )
+        .await
+        .map_err(ApiError::invalid)?;
+        let count = gtm_db::generate_tickets_for_seat(
+            &pool,
+            seat.id,
+            config.team_id,
+            &ticketed_game_types(&config),
+        )
+        .await?;
+        info!("Seat {} added, {} game tickets generated", seat.id, count);
+        Ok((seat, count))
+    })
+    .await
+}
+
+/// Add a run of seats in one row, with their tickets. Returns the seats and
+/// the number of tickets generated.
+pub async fn add_seat_batch(
+    pool: AnyPool,
+    config: Arc<gtm_config::Config>,
+    body: AddSeatBatchRequest,
+) -> Result<(Vec<gtm_models::Seat>, u64), ApiError> {
+    detached(async move {
+        let ticketed = ticketed_game_types(&config);
+        let mut seats = Vec::new();
+        let mut generated = 0;
+        for n in body.seat_start..=body.seat_end {
+            let seat = gtm_db::add_seat(
+                &pool,
+                &body.section,
+                &body.row,
+                &n.to_string(),
+                body.notes.as_deref(),
+            )
+            .await
+            .map_err(ApiError::invalid)?;
+            generated +=
+                gtm_db::generate_tickets_for_seat(&pool, seat.id, config.team_id, &ticketed)
+                    .await?;
+            seats.push(seat);
+        }
+        info!(
+            "{} seats batch-added (Section {} Row {} Seats {}-{})",
+            seats.len(),
+            body.section,
+            body.row,
+            body.seat_start,
+            body.seat_end
+        );
+        Ok((seats, generated))
+    })
+    .await
+}
+
+/// File a member's requests. A request without a seat count takes the
+/// member's `default_seats`, capped at the game's limit. Every request is
+/// checked against its game's seat limit before any is saved.
+pub async fn create_requests(
+    pool: AnyPool,
+    user_id: UserId,
+    requests: Vec<CreateRequestBody>,
+) -> Result<Vec<gtm_models::TicketRequest>, ApiError> {
+    let policy = gtm_db::seat_limits::SeatLimitPolicy::load(&pool).await?;
+    let default_seats = gtm_db::preferences::get(&pool, user_id)
+        .await?
+        .default_seats;
+    let mut seats = Vec::with_capacity(requests.len());
+    for req in &requests {
+        let max = match gtm_db::get_game(&pool, req.game_pk).await? {
+            Some(game) => policy.max_seats(&game),
+            None => gtm_db::seat_limits::DEFAULT_MAX_SEATS,
+        };
+        let requested = match (req.seats_requested, default_seats) {
+            (Some(n), _) => n,
+            (None, Some(n)) => n.min(max),
+            (None, None) => {
+                return Err(ApiError::bad_request(format!(
+                    "seats_requested is required for game_pk {} (or set default_seats in /my/preferences)",
+                    req.game_pk
+                )));
+            }
+        };
+        if requested < 1 || requested > max {
+            return Err(ApiError::bad_request(format!(
+                "seats_requested must be 1-{max} (got {requested} for game_pk {})",
+                req.game_pk
+            )));
+        }
+        seats.push(requested);
+    }
+    detached(async move {
+        let mut results = Vec::new();
+        for (req, seats_requested) in requests.iter().zip(seats) {
+            let tr = gtm_db::create_ticket_request(
+                &pool,
+                user_id,
+                req.game_pk,
+                seats_requested,
+                req.notes.as_deref(),
+            )
+            .await?;
+            results.push(tr);
+        }
+        Ok(results)
+    })
+    .await
+}
+
+/// Assign tickets by hand, approve the requests they fill, record the
+/// batch, and queue the members' emails and calendar updates. Nothing is
+/// assigned if a member's seats would span sections, unless `allow_split`.
+/// `admin_id` is who to record as doing it, if known. Returns the number
+/// assigned and the batch id.
+pub async fn allocate(
+    pool: AnyPool,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    admin_id: Option<UserId>,
+    assignments: Vec<AllocateBody>,
+    allow_split: bool,
+) -> Result<(u64, Option<i64>, gtm_db::Warnings), ApiError> {
+    let request = json!({ "assignments": assignments });
+    let assignments = assignments
+        .iter()
+        .map(|a| (a.game_ticket_id, a.user_id, a.request_id))
+        .collect();
+    commit_batch(pool, gcal, admin_id, assignments, allow_split, request).await
+}
+
+/// Assign `(ticket, member, request)` triples all together, as the CLI
+/// does by hand or from a plan: approve the requests they fill, record the
+/// batch with `request` as what was asked for, and queue the members'
+/// emails and calendar updates. Nothing is assigned if a member's seats
+/// would span sections, unless `allow_split`. Returns the number assigned
+/// and the batch id.
+pub async fn commit_assignments(
+    pool: AnyPool,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    admin_id: UserId,
+    assignments: Vec<(TicketId, UserId, Option<i64>)>,
+    allow_split: bool,
+    request: serde_json::Value,
+) -> Result<(u64, Option<i64>, gtm_db::Warnings), ApiError> {
+    commit_batch(
+        pool,
+        gcal,
+        Some(admin_id),
+        assignments,
+        allow_split,
+        request,
+    )
+    .await
+}
+
+/// Commit a manual batch with its record, then queue the emails and
+/// calendar updates for the seats that were assigned.
+async fn commit_batch(
+    pool: AnyPool,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    admin_id: Option<UserId>,
+    assignments: Vec<(TicketId, UserId, Option<i64>)>,
+    allow_split: bool,
+    request: serde_json::Value,
+) -> Result<(u64, Option<i64>, gtm_db::Warnings), ApiError> {
+    detached(async move {
+        let mut warnings = gtm_db::Warnings::new();
+        let draft = gtm_db::allocation_batches::Draft {
+            kind: gtm_db::allocation_batches::MANUAL,
+            game_pk: None,
+            request,
+            proposal: serde_json::Value::Null,
+        };
+        let (assigned, batch_id) = gtm_db::commit_allocation_batch(
+            &pool,
+            &assignments,
+            admin_id,
+            allow_split,
+            &draft,
+            &mut warnings,
+        )
+        .await?;
+
+        let mut games: HashMap<GamePk, Vec<UserId>> = HashMap::new();
+        let mut assignees = Vec::new();
+        for (ticket_id, user_id, _) in &assignments {
+            if let Some(t) = gtm_db::get_ticket(&pool, *ticket_id).await?
+                && t.assigned_to == Some(*user_id)
+            {
+                games.entry(t.game_pk).or_default().push(*user_id);
+                assignees.push(*user_id);
+            }
+        }
+        for (game_pk, users) in &games {
+            if let Err(e) = queue_allocation_emails(&pool, *game_pk, users).await {
+                warn!(%game_pk, "Failed to queue allocation emails: {e}");
+                warnings.push(format!(
+                    "Could not queue allocation emails for game {game_pk}"
+                ));
+            }
+        }
+
+        spawn_calendar_sync(pool, gcal, assignees);
+        Ok((assigned, Some(batch_id), warnings))
+    })
+    .await
+}
+
+/// Run and commit the allocation engine for `game` with its batch record,
+/// then queue the members' emails and calendar updates. Nothing is
+/// committed if a member's seats would span sections, unless
+/// `allow_split`. Returns the allocation, the number of seats assigned and
+/// the batch id.
+pub async fn auto_allocate(
+    pool: AnyPool,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    game: gtm_models::Game,
+    strategy: gtm_alloc::Strategy,
+    seed: Option<u64>,
+    admin_id: UserId,
+    allow_split: bool,
+) -> Result<(gtm_alloc::Allocation, u64, Option<i64>, gtm_db::Warnings), ApiError> {
+    detached(async move {
+        let mut warnings = gtm_db::Warnings::new();
+        let (allocation, assigned, batch_id) = run_auto_allocation(
+            &pool,
+            &game,
+            strategy,
+            seed,
+            AutoRun::Commit { allow_split },
+            Some(admin_id),
+            &mut warnings,
+        )
+        .await?;
+        let assignees: Vec<UserId> = allocation.proposals.iter().map(|p| p.user_id).collect();
+        if let Err(e) = queue_allocation_emails(&pool, game.game_pk, &assignees).await {
+            warn!(game_pk = %game.game_pk, "Failed to queue allocation emails: {e}");
+            warnings.push("Could not queue allocation emails");
+        }
+        spawn_calendar_sync(pool, gcal, assignees);
+        Ok((allocation, assigned, batch_id, warnings))
+    })
+    .await
+}
+
+/// Fetch and store the schedule, as `gtm scrape` does. `None` if another
+/// sync holds the lock.
+pub async fn scrape(
+    pool: AnyPool,
+    config: Arc<gtm_config::Config>,
+    season: u32,
+    game_types: Option<Vec<GameType>>,
+    hydrate: Option<Vec<Hydration>>,
+    force: bool,
+) -> anyhow::Result<
+    Option<(
+        gtm_db::schedule_sync::ScheduleSync,
+        gtm_scraper::DriftReport,
+    )>,
+> {
+    detached(async move {
+        run_scrape(
+            &pool,
+            &config,
+            season,
+            game_types.as_deref(),
+            hydrate.as_deref(),
+            force,
+        )
+        .await
+    })
+    .await
+}
+
+/// Finish linking a member's Google Calendar: consume the one-time OAuth
+/// `state`, trade `code` for tokens and store them, then sync their seats.
+/// Returns false if they declined consent (`error` set).
+pub async fn link_google(
+    pool: AnyPool,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    state: String,
+    code: Option<String>,
+    error: Option<String>,
+) -> Result<bool, ApiError> {
+    detached(async move {
+        let user_id = gtm_db::take_oauth_state(&pool, &state, &rfc3339_utc(Utc::now()))
+            .await?
+            .ok_or(ApiError::bad_request("Unknown or expired OAuth state"))?;
+        if let Some(err) = error {
+            warn!(%user_id, "Google consent declined: {err}");
+            return Ok(false);
+        }
+        let code = code.ok_or(ApiError::bad_request("Missing code"))?;
+
+        let tokens = gcal
+            .exchange_code(&code)
+            .await
+            .map_err(|e| ApiError::upstream(e.to_string()))?;
+        let refresh_token = tokens
+            .refresh_token
+            .ok_or(ApiError::upstream("Google did not return a refresh token"))?;
+        let expires_at = rfc3339_utc(Utc::now() + chrono::Duration::seconds(tokens.expires_in));
+        gtm_db::upsert_google_account(
+            &pool,
+            user_id,
+            &refresh_token,
+            &tokens.access_token,
+            &expires_at,
+        )
+        .await?;
+        info!(%user_id, "Google Calendar linked");
+
+        spawn_calendar_sync(pool, gcal, vec![user_id]);
+        Ok(true)
+    })
+    .await
+}
+
+/// Act on a signed inbound email: claim its delivery token so it can't be
+/// replayed, apply each command for the member who sent it, and email them
+/// what happened. The claim and the commands run together, so a dropped
+/// request can't use up the token and skip commands. Returns how many
+/// commands were applied and how many lines couldn't be read.
+pub async fn inbound_email(
+    pool: AnyPool,
+    config: Arc<gtm_config::Config>,
+    notifier: Arc<gtm_notify::Notifier>,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    form: InboundEmailForm,
+) -> Result<(usize, usize), ApiError> {
+    detached(async move {
+        // A signed delivery is good for a few minutes; don't let it be
+        // replayed within them. 406 tells Mailgun not to retry.
+        if !gtm_db::inbound_tokens::claim(&pool, &form.token, Utc::now()).await? {
+            warn!(sender = %form.sender, "Inbound email token already used");
+            return Err(ApiError::not_acceptable("Token already used"));
+        }
+
+        // Only act on mail from addresses that belong to a known member.
+        // 406 tells Mailgun not to retry.
+        let address = email_address(&form.sender);
+        let user = gtm_db::get_user_by_email(&pool, address)
+            .await?
+            .ok_or_else(|| {
+                warn!(sender = %address, "Inbound email from unknown sender");
+                ApiError::not_acceptable("Unknown sender")
+            })?;
+
+        let body = form.stripped_text.as_deref().unwrap_or(&form.body_plain);
+        let year = Utc::now().with_timezone(&display_tz(&config)?).year();
+        let (commands, errors) = gtm_notify::inbound::parse_commands(&form.subject, body, year);
+        info!(
+            user_id = %user.id,
+            commands = commands.len(),
+            errors = errors.len(),
+            "Inbound email parsed"
+        );
+
+        let mut results = Vec::new();
+        for cmd in &commands {
+            match apply_inbound_command(&pool, &config, &notifier, &gcal, &user, cmd).await {
+                Ok(line) => results.push(line),
+                Err(e) => {
+                    warn!(user_id = %user.id, ?cmd, error = %e, "Inbound command failed");
+                    let (verb, date) = match cmd {
+                        gtm_notify::inbound::InboundCommand::Release { date } => ("release", date),
+                        gtm_notify::inbound::InboundCommand::Request { date, .. } => {
+                            ("request", date)
+                        }
+                    };
+                    results.push(format!(
+                        "Something went wrong with {} {} — nothing changed. Please try again in the app.",
+                        verb.to_uppercase(),
+                        date.format("%Y-%m-%d")
+                    ));
+                }
+            }
+        }
+        for err in &errors {
+            results.push(format!(
+                "Didn't understand \"{}\": {}",
+                err.line, err.reason
+            ));
+        }
+        if results.is_empty() {
+            results.push(
+                "No commands found. Reply with lines like \"RELEASE 6/12\" or \"REQUEST 6/12 2\"."
+                    .to_string(),
+            );
+        }
+
+        let reply = gtm_notify::Notification::email(
+            &user.email,
+            &format!("Re: {}", form.subject),
+            &format!("Hi {},\n\n{}\n\n— GTM", user.name, results.join("\n")),
+        );
+        if let Err(e) = notifier.send(&reply).await {
+            warn!(user_id = %user.id, error = %e, "Inbound confirmation reply failed");
+        }
+        Ok((commands.len(), errors.len()))
+    })
+    .await
+}
+
+/// Apply one emailed command for `user`, returning the line for the reply.
+async fn apply_inbound_command(
+    pool: &AnyPool,
+    config: &gtm_config::Config,
+    notifier: &Arc<gtm_notify::Notifier>,
+    gcal: &Arc<gtm_gcal::GoogleCalendar>,
+    user: &gtm_models::User,
+    cmd: &gtm_notify::inbound::InboundCommand,
+) -> anyhow::Result<String> {
+    use gtm_notify::inbound::InboundCommand;
+
+    let date = match cmd {
+        InboundCommand::Release { date } | InboundCommand::Request { date, .. } => {
+            date.format("%Y-%m-%d").to_string()
+        }
+    };
+    let games = gtm_db::list_home_games_on_date(pool, config.team_id, &date).await?;
+    if games.is_empty() {
+        return Ok(format!("No home game on {date} — nothing changed."));
+    }
+
+    let mut lines = Vec::new();
+    for g in &games {
+        match cmd {
+            InboundCommand::Release { .. } => {
+                let count =
+                    gtm_db::release_tickets_for_game(pool, g.game_pk, user.id, Some(user.id))
+                        .await?;
+                if count > 0 {
+                    spawn_calendar_sync(pool.clone(), gcal.clone(), vec![user.id]);
+                    spawn_day_of_release_sms(
+                        pool.clone(),
+                        notifier.clone(),
+                        display_tz(config)?,
+                        g.game_pk,
+                        user.id,
+                        count,
+                    );
+                }
+                lines.push(format!(
+                    "Released {count} ticket(s) for {date} vs {}.",
+                    g.away_team_name
+                ));
+            }
+            InboundCommand::Request { seats, .. } => {
+                let max = gtm_db::seat_limits::max_seats_for_game(pool, g.game_pk).await?;
+                if *seats > max {
+                    lines.push(format!(
+                        "{date} vs {} allows at most {max} seat(s) per request — nothing changed.",
+                        g.away_team_name
+                    ));
+                    continue;
+                }
+                // Email can start a request but not rewrite one: the reply
+                // would silently replace the seats and notes already set.
+                if let Some(existing) =
+                    gtm_db::get_request_for_user_game(pool, user.id, g.game_pk).await?
+                    && existing.status != "withdrawn"
+                {
+                    lines.push(format!(
+                        "You already requested {} seat(s) for {date} vs {} — change it in the app. Nothing changed.",
+                        existing.seats_requested, g.away_team_name
+                    ));
+                    continue;
+                }
+                gtm_db::create_ticket_request(pool, user.id, g.game_pk, *seats, Some("via email"))
+                    .await?;
+                lines.push(format!(
+                    "Requested {seats} seat(s) for {date} vs {}.",
+                    g.away_team_name
+                ));
+            }
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Unlink a member's Google Calendar: remove the events GTM created, then
+/// forget the tokens. Events that can't be removed are logged and left.
+pub async fn unlink_google(
+    pool: AnyPool,
+    gcal: Arc<gtm_gcal::GoogleCalendar>,
+    user_id: UserId,
+) -> Result<(), ApiError> {
+    detached(async move {
+        if let Err(e) = remove_calendar_events(&pool, &gcal, user_id).await {
+            warn!(%user_id, "Could not remove calendar events on unlink: {e}");
+        }
+        gtm_db::delete_google_account(&pool, user_id).await?;
+        Ok(())
+    })
+    .await
+}
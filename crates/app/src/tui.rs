@@ -0,0 +1,378 @@
+//! `gtm tui`: a terminal dashboard for managing allocations without the web
+//! frontend.
+//!
+//! Upcoming home games are listed with their ticket availability; the
+//! selected game's tickets, pending requests and promotions are shown below.
+//! The screen is redrawn after each command, typed at the prompt and ended
+//! with Enter, so it works in any terminal without raw mode.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use chrono::Utc;
+use gtm_models::{
+    GameTicketDetail, Promotion, TicketId, TicketRequest, TicketSummaryRow, User, UserId,
+};
+use sqlx::AnyPool;
+use tracing::warn;
+
+use crate::{AllocateBody, CliAdmin};
+
+const HELP: &str = "j/k next/previous game · <n> jump to game n · \
+     a <ticket> <request|email> assign · r <ticket> release · Enter refresh · q quit";
+
+/// Games shown at once; the list scrolls to keep the selection in view.
+const GAME_ROWS: usize = 12;
+
+struct Dashboard {
+    /// The display time zone; games from today there onward are listed.
+    tz: chrono_tz::Tz,
+    games: Vec<TicketSummaryRow>,
+    selected: usize,
+    tickets: Vec<GameTicketDetail>,
+    requests: Vec<TicketRequest>,
+    promotions: Vec<Promotion>,
+    /// Pending requests across every game.
+    pending_total: usize,
+    users: HashMap<UserId, User>,
+    /// Outcome of the last command.
+    message: String,
+}
+
+impl Dashboard {
+    /// Reload everything, keeping the selected game where it still exists.
+    async fn refresh(&mut self, pool: &AnyPool) -> anyhow::Result<()> {
+        let selected_pk = self.games.get(self.selected).map(|g| g.game_pk);
+        let today = Utc::now()
+            .with_timezone(&self.tz)
+            .format("%Y-%m-%d")
+            .to_string();
+        self.games = gtm_db::ticket_summary_for_games(pool, None, None, Some(&today)).await?;
+        self.selected = selected_pk
+            .and_then(|pk| self.games.iter().position(|g| g.game_pk == pk))
+            .unwrap_or(0);
+        self.pending_total = gtm_db::list_all_pending_requests(pool).await?.len();
+        self.users = gtm_db::list_users(pool)
+            .await?
+            .into_iter()
+            .map(|u| (u.id, u))
+            .collect();
+        self.load_game(pool).await
+    }
+
+    /// Reload the selected game's tickets, requests and promotions.
+    async fn load_game(&mut self, pool: &AnyPool) -> anyhow::Result<()> {
+        let Some(game) = self.games.get(self.selected) else {
+            self.tickets.clear();
+            self.requests.clear();
+            self.promotions.clear();
+            return Ok(());
+        };
+        self.tickets = gtm_db::list_tickets_for_game(pool, game.game_pk).await?;
+        self.requests = gtm_db::list_requests_for_game(pool, game.game_pk)
+            .await?
+            .into_iter()
+            .filter(|r| r.status == "pending")
+            .collect();
+        self.promotions = gtm_db::get_promotions_for_game(pool, game.game_pk).await?;
+        Ok(())
+    }
+
+    fn user_name(&self, id: UserId) -> &str {
+        self.users.get(&id).map_or("?", |u| u.name.as_str())
+    }
+
+    fn draw(&self, out: &mut impl Write) -> std::io::Result<()> {
+        // Clear the screen and home the cursor.
+        write!(out, "\x1b[2J\x1b[H")?;
+        writeln!(
+            out,
+            "Upcoming home games — {} pending request(s)\n",
+            self.pending_total
+        )?;
+        writeln!(
+            out,
+            "  {:>3}  {:<10} {:<12} {:<25} {:<9} Available",
+            "#", "GamePK", "Date", "Opponent", "Status"
+        )?;
+        writeln!(out, "  {}", "-".repeat(76))?;
+        let first = self.selected.saturating_sub(GAME_ROWS - 1);
+        for (i, g) in self.games.iter().enumerate().skip(first).take(GAME_ROWS) {
+            let opponent = match g.double_header_label() {
+                Some(label) => format!("{} ({label})", g.opponent),
+                None => g.opponent.clone(),
+            };
+            let line = format!(
+                "{:>3}  {:<10} {:<12} {:<25} {:<9} {}/{}",
+                i + 1,
+                g.game_pk,
+                g.official_date,
+                opponent,
+                g.status_abstract,
+                g.available,
+                g.total
+            );
+            if i == self.selected {
+                // Reverse video for the selected row.
+                writeln!(out, "> \x1b[7m{line}\x1b[0m")?;
+            } else {
+                writeln!(out, "  {line}")?;
+            }
+        }
+        if self.games.is_empty() {
+            writeln!(out, "  No upcoming home games with tickets.")?;
+        }
+
+        if let Some(game) = self.games.get(self.selected) {
+            write!(out, "\n{} vs {}", game.official_date, game.opponent)?;
+            match game.double_header_label() {
+                Some(label) => writeln!(out, ", {label}")?,
+                None => writeln!(out)?,
+            }
+            writeln!(out, "\n  Tickets")?;
+            for (i, t) in self.tickets.iter().enumerate() {
+                let holder = t.assigned_to.map_or("", |id| self.user_name(id));
+                writeln!(
+                    out,
+                    "  {:>3}  {}/{}/{:<6} {:<10} {}",
+                    i + 1,
+                    t.section,
+                    t.row,
+                    t.seat,
+                    t.status,
+                    holder
+                )?;
+            }
+            writeln!(out, "\n  Pending requests")?;
+            if self.requests.is_empty() {
+                writeln!(out, "       none")?;
+            }
+            for (i, r) in self.requests.iter().enumerate() {
+                writeln!(
+                    out,
+                    "  {:>3}  {:<25} {} seat(s){}",
+                    i + 1,
+                    self.user_name(r.user_id),
+                    r.seats_requested,
+                    r.flag_reason
+                        .as_deref()
+                        .map(|f| format!(" — flagged: {f}"))
+                        .unwrap_or_default()
+                )?;
+            }
+            if !self.promotions.is_empty() {
+                let names: Vec<&str> = self.promotions.iter().map(|p| p.name.as_str()).collect();
+                writeln!(out, "\n  Promotions: {}", names.join(", "))?;
+            }
+        }
+
+        writeln!(out, "\n{HELP}")?;
+        if !self.message.is_empty() {
+            writeln!(out, "{}", self.message)?;
+        }
+        write!(out, "> ")?;
+        out.flush()
+    }
+
+    /// The ticket at position `n` (1-based) of the selected game.
+    fn ticket(&self, n: &str) -> Result<&GameTicketDetail, String> {
+        n.parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| self.tickets.get(i))
+            .ok_or_else(|| format!("No ticket {n}"))
+    }
+
+    /// A member given as a pending request number or an email, with the
+    /// request they're being allocated for.
+    async fn member(
+        &self,
+        pool: &AnyPool,
+        who: &str,
+    ) -> anyhow::Result<Result<(UserId, Option<i64>), String>> {
+        if let Ok(n) = who.parse::<usize>() {
+            return Ok(n
+                .checked_sub(1)
+                .and_then(|i| self.requests.get(i))
+                .map(|r| (r.user_id, Some(r.id)))
+                .ok_or_else(|| format!("No pending request {n}")));
+        }
+        Ok(match gtm_db::get_user_by_email(pool, who).await? {
+            Some(user) => {
+                let request = self
+                    .requests
+                    .iter()
+                    .find(|r| r.user_id == user.id)
+                    .map(|r| r.id);
+                Ok((user.id, request))
+            }
+            None => Err(format!("No member with email {who}")),
+        })
+    }
+
+    /// Assign as the web app's allocate does, as `admin`: through
+    /// [`service::allocate`](crate::service::allocate), which checks the
+    /// member's party stays together and records the batch, emails and
+    /// calendar updates.
+    async fn assign(
+        &self,
+        pool: &AnyPool,
+        admin: &CliAdmin,
+        ticket: &str,
+        who: &str,
+    ) -> anyhow::Result<String> {
+        let ticket = match self.ticket(ticket) {
+            Ok(t) => t,
+            Err(msg) => return Ok(msg),
+        };
+        let (user_id, request_id) = match self.member(pool, who).await? {
+            Ok(m) => m,
+            Err(msg) => return Ok(msg),
+        };
+        let assignment = AllocateBody {
+            game_ticket_id: ticket.id,
+            user_id,
+            request_id,
+        };
+        let (assigned, _, warnings) = crate::service::allocate(
+            pool.clone(),
+            admin.gcal.clone(),
+            Some(admin.user.id),
+            vec![assignment],
+            false,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e.message))?;
+        if assigned == 0 {
+            return Ok(format!(
+                "{}/{}/{} is no longer available",
+                ticket.section, ticket.row, ticket.seat
+            ));
+        }
+        let mut message = format!(
+            "Assigned {}/{}/{} to {}",
+            ticket.section,
+            ticket.row,
+            ticket.seat,
+            self.user_name(user_id)
+        );
+        for warning in warnings.iter() {
+            message.push_str(" · ");
+            message.push_str(warning);
+        }
+        Ok(message)
+    }
+
+    /// Take a ticket back as `admin`, and queue the holder's email and
+    /// calendar update.
+    async fn release(
+        &self,
+        pool: &AnyPool,
+        admin: &CliAdmin,
+        ticket: &str,
+    ) -> anyhow::Result<String> {
+        let ticket = match self.ticket(ticket) {
+            Ok(t) => t,
+            Err(msg) => return Ok(msg),
+        };
+        let id: TicketId = ticket.id;
+        if !gtm_db::revoke_ticket(pool, id, Some(admin.user.id)).await? {
+            return Ok(format!(
+                "{}/{}/{} isn't assigned",
+                ticket.section, ticket.row, ticket.seat
+            ));
+        }
+        if let Some(user_id) = ticket.assigned_to
+            && let Err(e) = crate::queue_revocation_email(pool, user_id, ticket).await
+        {
+            warn!(%id, "Failed to queue revocation email: {e}");
+        }
+        if let Some(user_id) = ticket.assigned_to {
+            crate::spawn_calendar_sync(pool.clone(), admin.gcal.clone(), vec![user_id]);
+        }
+        Ok(format!(
+            "Released {}/{}/{}",
+            ticket.section, ticket.row, ticket.seat
+        ))
+    }
+
+    /// Carry out one typed command other than quitting.
+    async fn command(
+        &mut self,
+        pool: &AnyPool,
+        admin: &CliAdmin,
+        words: &[&str],
+    ) -> anyhow::Result<()> {
+        match words {
+            [] => self.refresh(pool).await?,
+            ["j"] => {
+                if self.selected + 1 < self.games.len() {
+                    self.selected += 1;
+                    self.load_game(pool).await?;
+                }
+            }
+            ["k"] => {
+                if self.selected > 0 {
+                    self.selected -= 1;
+                    self.load_game(pool).await?;
+                }
+            }
+            ["a", ticket, who] => {
+                self.message = self.assign(pool, admin, ticket, who).await?;
+                self.refresh(pool).await?;
+            }
+            ["r", ticket] => {
+                self.message = self.release(pool, admin, ticket).await?;
+                self.refresh(pool).await?;
+            }
+            [n] if n.parse::<usize>().is_ok() => {
+                match n.parse::<usize>().ok().and_then(|n| n.checked_sub(1)) {
+                    Some(i) if i < self.games.len() => {
+                        self.selected = i;
+                        self.load_game(pool).await?;
+                    }
+                    _ => self.message = format!("No game {n}"),
+                }
+            }
+            _ => self.message = format!("Unknown command {:?}", words.join(" ")),
+        }
+        Ok(())
+    }
+}
+
+/// Run the dashboard as `admin` until `q` or end of input. A command that
+/// fails shows its error in the status line and the dashboard carries on.
+pub async fn run(pool: &AnyPool, admin: CliAdmin, tz: chrono_tz::Tz) -> anyhow::Result<()> {
+    let mut dash = Dashboard {
+        tz,
+        games: Vec::new(),
+        selected: 0,
+        tickets: Vec::new(),
+        requests: Vec::new(),
+        promotions: Vec::new(),
+        pending_total: 0,
+        users: HashMap::new(),
+        message: String::new(),
+    };
+    dash.refresh(pool).await?;
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    let mut line = String::new();
+    loop {
+        dash.draw(&mut stdout)?;
+        line.clear();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        dash.message.clear();
+        if matches!(words.as_slice(), ["q"] | ["quit"]) {
+            break;
+        }
+        if let Err(e) = dash.command(pool, &admin, &words).await {
+            dash.message = format!("Error: {e}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,508 @@
+//! Authorization matrix for the HTTP API.
+//!
+//! The server lives in a binary crate, so instead of building the `Router`
+//! this reads `src/main.rs` and finds every `.route(...)` registered in the
+//! public, member and admin sub-routers under `/api`. A route's requirement
+//! is its sub-router's (the member and admin ones carry a gate layer), or
+//! its handler's if that takes a stronger `RequireMember`/`RequireAdmin`
+//! extractor. Every route must have an entry in [`MATRIX`], and the inferred
+//! requirement must match it — adding a route without declaring who may call
+//! it fails the build.
+//!
+//! The local-login bypass (`auth_mode = "none"`) is checked against a
+//! running server instead.
+
+mod common;
+
+use common::Server;
+use serde_json::Value;
+use std::process::Stdio;
+
+const MAIN_RS: &str = include_str!("../src/main.rs");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Access {
+    /// No credentials.
+    Public,
+    /// No JWT; the handler verifies its own token, state or signature.
+    Signed,
+    /// Any logged-in member.
+    Member,
+    /// Logged-in member with the admin role.
+    Admin,
+}
+
+/// What the router and handler enforce, weakest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Enforced {
+    Nothing,
+    Jwt,
+    JwtAdmin,
+}
+
+impl Access {
+    fn enforced(self) -> Enforced {
+        match self {
+            Access::Public | Access::Signed => Enforced::Nothing,
+            Access::Member => Enforced::Jwt,
+            Access::Admin => Enforced::JwtAdmin,
+        }
+    }
+}
+
+use Access::*;
+
+/// (method, path, access) for every API route.
+const MATRIX: &[(&str, &str, Access)] = &[
+    ("GET", "/api/health", Public),
+    ("GET", "/api/health/live", Public),
+    ("GET", "/api/health/ready", Public),
+    ("GET", "/api/auth/local-users", Public),
+    ("GET", "/api/config", Public),
+    ("GET", "/api/games", Public),
+    ("GET", "/api/games/search", Public),
+    ("GET", "/api/games/{id}", Public),
+    ("GET", "/api/games/{id}/promotions", Public),
+    ("GET", "/api/games/{id}/preview", Public),
+    ("GET", "/api/games/{id}/tickets", Public),
+    ("GET", "/api/promotions", Public),
+    ("GET", "/api/promotions/{offer_id}/image", Public),
+    ("GET", "/api/seats", Public),
+    ("POST", "/api/seats", Admin),
+    ("POST", "/api/seats/batch", Admin),
+    ("POST", "/api/seats/import", Admin),
+    ("PATCH", "/api/seats/group", Admin),
+    ("DELETE", "/api/seats/group", Admin),
+    ("POST", "/api/seats/group/regenerate-tickets", Admin),
+    ("DELETE", "/api/seats/{id}", Admin),
+    ("PATCH", "/api/tickets/{id}", Admin),
+    ("GET", "/api/tickets/summary", Public),
+    ("GET", "/api/seasons", Public),
+    ("GET", "/api/users/me", Member),
+    ("GET", "/api/users", Member),
+    ("POST", "/api/admin/scrape-schedule", Admin),
+    ("GET", "/api/my/requests", Member),
+    ("POST", "/api/my/requests", Member),
+    ("POST", "/api/my/requests/bulk", Member),
+    ("PATCH", "/api/my/requests/{id}", Member),
+    ("DELETE", "/api/my/requests/{id}", Member),
+    ("GET", "/api/my/requests/{id}/comments", Member),
+    ("POST", "/api/my/requests/{id}/comments", Member),
+    ("GET", "/api/my/games", Member),
+    ("GET", "/api/availability/calendar", Member),
+    ("POST", "/api/my/games/{game_pk}/release", Member),
+    ("POST", "/api/my/games/{game_pk}/transfer", Member),
+    ("POST", "/api/my/games/{game_pk}/attendance", Member),
+    ("GET", "/api/my/game-tags", Member),
+    ("PUT", "/api/my/game-tags/{game_pk}", Member),
+    ("POST", "/api/promotions/{offer_id}/interest", Member),
+    ("DELETE", "/api/promotions/{offer_id}/interest", Member),
+    ("GET", "/api/my/promotion-interests", Member),
+    ("GET", "/api/my/polls", Member),
+    ("PUT", "/api/polls/{id}/response", Member),
+    ("GET", "/api/my/ledger", Member),
+    ("GET", "/api/my/history", Member),
+    ("GET", "/api/transparency", Member),
+    ("GET", "/api/my/cost-split", Member),
+    ("GET", "/api/my/invoices", Member),
+    ("POST", "/api/my/invoices/{id}/pay", Member),
+    ("GET", "/api/my/blackouts", Member),
+    ("PUT", "/api/my/blackouts/{date}", Member),
+    ("DELETE", "/api/my/blackouts/{date}", Member),
+    ("GET", "/api/my/saved-views", Member),
+    ("POST", "/api/my/saved-views", Member),
+    ("DELETE", "/api/my/saved-views/{id}", Member),
+    ("GET", "/api/my/phone", Member),
+    ("PUT", "/api/my/phone", Member),
+    ("PATCH", "/api/my/phone", Member),
+    ("DELETE", "/api/my/phone", Member),
+    ("POST", "/api/my/phone/verify", Member),
+    ("PUT", "/api/my/locale", Member),
+    ("GET", "/api/my/preferences", Member),
+    ("PATCH", "/api/my/preferences", Member),
+    ("GET", "/api/my/google", Member),
+    ("DELETE", "/api/my/google", Member),
+    ("POST", "/api/my/google/connect", Member),
+    ("GET", "/api/my/tokens", Member),
+    ("POST", "/api/my/tokens", Member),
+    ("DELETE", "/api/my/tokens/{id}", Member),
+    ("GET", "/api/google/callback", Signed),
+    ("GET", "/api/admin/allocation", Admin),
+    ("GET", "/api/admin/allocation/{game_pk}", Admin),
+    ("GET", "/api/admin/allocation/by-users", Admin),
+    ("POST", "/api/admin/allocation/{game_pk}/auto", Admin),
+    ("POST", "/api/admin/allocation/{game_pk}/preview", Admin),
+    ("POST", "/api/admin/allocation/simulate", Admin),
+    ("GET", "/api/admin/allocation/batches", Admin),
+    ("GET", "/api/admin/allocation/batches/{id}", Admin),
+    ("POST", "/api/admin/allocate", Admin),
+    ("DELETE", "/api/admin/allocate/{id}", Admin),
+    ("GET", "/api/admin/allocation/by-user/{user_id}", Admin),
+    ("GET", "/api/admin/config", Admin),
+    ("GET", "/api/admin/requests", Admin),
+    ("GET", "/api/admin/requests/{id}/comments", Admin),
+    ("POST", "/api/admin/requests/{id}/comments", Admin),
+    ("GET", "/api/admin/reports/response-times", Admin),
+    ("GET", "/api/admin/alerts/unassigned", Admin),
+    ("GET", "/api/admin/audit", Admin),
+    ("GET", "/api/admin/transfers", Admin),
+    ("GET", "/api/admin/ledger", Admin),
+    ("GET", "/api/admin/split-rules", Admin),
+    ("PUT", "/api/admin/split-rules", Admin),
+    ("GET", "/api/admin/seat-limits", Admin),
+    ("PUT", "/api/admin/seat-limits", Admin),
+    ("PUT", "/api/admin/games/{id}/seat-limit", Admin),
+    ("GET", "/api/admin/games/{id}/packets", Admin),
+    ("POST", "/api/admin/games/{id}/packets", Admin),
+    ("GET", "/api/admin/games/{id}/tags", Admin),
+    ("PUT", "/api/admin/games/{id}/tags", Admin),
+    ("GET", "/api/admin/games/{id}/attendance", Admin),
+    ("PUT", "/api/admin/games/{id}/attendance/{user_id}", Admin),
+    ("GET", "/api/admin/games/{id}/adjacent-seats", Admin),
+    ("GET", "/api/admin/games/{id}/forwarding", Admin),
+    ("POST", "/api/admin/games/{id}/forwarding", Admin),
+    ("GET", "/api/admin/cost-split", Admin),
+    ("GET", "/api/admin/invoices", Admin),
+    ("POST", "/api/admin/invoices", Admin),
+    ("GET", "/api/admin/invoices/{id}", Admin),
+    ("DELETE", "/api/admin/invoices/{id}", Admin),
+    ("POST", "/api/admin/invoices/{id}/payments", Admin),
+    ("POST", "/api/admin/invoices/{id}/payment-link", Admin),
+    ("PUT", "/api/admin/seats/group/face-value", Admin),
+    ("PUT", "/api/admin/tickets/{id}/cost", Admin),
+    ("PUT", "/api/admin/tickets/{id}/resale", Admin),
+    ("PUT", "/api/admin/tickets/{id}/forwarding", Admin),
+    ("DELETE", "/api/admin/tickets/{id}/forwarding", Admin),
+    ("GET", "/api/admin/note-templates", Admin),
+    ("POST", "/api/admin/note-templates", Admin),
+    ("PUT", "/api/admin/note-templates/{id}", Admin),
+    ("DELETE", "/api/admin/note-templates/{id}", Admin),
+    ("POST", "/api/admin/note-templates/{id}/apply", Admin),
+    ("GET", "/api/admin/allocation/export.csv", Admin),
+    ("GET", "/api/admin/tickets/export.csv", Admin),
+    ("GET", "/api/admin/export/attendance-grid", Admin),
+    ("GET", "/api/admin/reports/fairness", Admin),
+    ("GET", "/api/admin/users/{id}/history", Admin),
+    ("GET", "/api/admin/reports/fairness-timeline", Admin),
+    ("GET", "/api/admin/reports/renewal", Admin),
+    ("GET", "/api/admin/tickets/coverage", Admin),
+    ("GET", "/api/admin/usage", Admin),
+    ("GET", "/api/admin/jobs", Admin),
+    ("GET", "/api/admin/external-refs", Admin),
+    ("POST", "/api/admin/external-refs", Admin),
+    ("PUT", "/api/admin/external-refs/{id}", Admin),
+    ("DELETE", "/api/admin/external-refs/{id}", Admin),
+    ("GET", "/api/admin/webhooks", Admin),
+    ("POST", "/api/admin/webhooks", Admin),
+    ("DELETE", "/api/admin/webhooks/{id}", Admin),
+    ("GET", "/api/admin/webhooks/{id}/deliveries", Admin),
+    ("GET", "/api/admin/reports/renewal.csv", Admin),
+    ("GET", "/api/admin/promotions/interest", Admin),
+    ("GET", "/api/admin/polls", Admin),
+    ("POST", "/api/admin/polls", Admin),
+    ("GET", "/api/admin/polls/{id}", Admin),
+    ("POST", "/api/admin/polls/{id}/close", Admin),
+    ("POST", "/api/admin/seasons/{season}/close", Admin),
+    ("GET", "/api/release-prompts/{token}/{action}", Signed),
+    ("POST", "/api/release-prompts/{token}/{action}", Signed),
+    ("GET", "/api/contact-email/{token}", Signed),
+    ("POST", "/api/contact-email/{token}", Signed),
+    ("POST", "/api/inbound/email", Signed),
+    ("POST", "/api/payments/stripe/webhook", Signed),
+];
+
+struct Route {
+    method: String,
+    path: String,
+    handler: String,
+    /// Enforced by the sub-router's gate layer.
+    router: Enforced,
+}
+
+/// The sub-routers `run_server` merges under `/api`, and what each enforces.
+const ROUTERS: &[(&str, Enforced)] = &[
+    ("public_routes", Enforced::Nothing),
+    ("member_routes", Enforced::Jwt),
+    ("admin_routes", Enforced::JwtAdmin),
+];
+
+/// Index just past the parenthesis matching the one at `open`.
+fn close_paren(src: &str, open: usize) -> usize {
+    let mut depth = 0;
+    for (i, c) in src[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return open + i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    panic!("unbalanced parentheses after byte {open}");
+}
+
+/// `(method, handler)` pairs in a method-router expression such as
+/// `get(list).post(create)`.
+fn method_handlers(expr: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    for method in ["get", "post", "put", "patch", "delete"] {
+        let needle = format!("{method}(");
+        let mut from = 0;
+        while let Some(at) = expr[from..].find(&needle) {
+            let start = from + at;
+            from = start + needle.len();
+            let preceded_by_ident = expr[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_');
+            if preceded_by_ident {
+                continue;
+            }
+            let rest = &expr[from..];
+            let handler: String = rest
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect();
+            found.push((method.to_uppercase(), handler));
+        }
+    }
+    found
+}
+
+fn api_routes() -> Vec<Route> {
+    let mut routes = Vec::new();
+    for (name, router) in ROUTERS {
+        let start = MAIN_RS
+            .find(&format!("let {name} = Router::new()"))
+            .unwrap_or_else(|| panic!("{name} builder not found in main.rs"));
+        let end = start + MAIN_RS[start..].find(';').unwrap();
+        let block = &MAIN_RS[start..end];
+
+        let mut from = 0;
+        while let Some(at) = block[from..].find(".route(") {
+            let open = from + at + ".route".len();
+            let close = close_paren(block, open);
+            let args = &block[open + 1..close - 1];
+            let path_start = args.find('"').unwrap() + 1;
+            let path_end = path_start + args[path_start..].find('"').unwrap();
+            let path = format!("/api{}", &args[path_start..path_end]);
+            for (method, handler) in method_handlers(&args[path_end + 1..]) {
+                routes.push(Route {
+                    method,
+                    path: path.clone(),
+                    handler,
+                    router: *router,
+                });
+            }
+            from = close;
+        }
+    }
+    routes
+}
+
+/// What `handler` checks on its own, from its parameter list.
+fn enforced_by(handler: &str) -> Enforced {
+    let needle = format!("async fn {handler}(");
+    let start = MAIN_RS
+        .find(&needle)
+        .unwrap_or_else(|| panic!("handler {handler} not found"));
+    let open = start + needle.len() - 1;
+    let params = &MAIN_RS[open..close_paren(MAIN_RS, open)];
+
+    if params.contains(": RequireAdmin") {
+        Enforced::JwtAdmin
+    } else if params.contains(": RequireMember") || params.contains(": AuthUser") {
+        Enforced::Jwt
+    } else {
+        Enforced::Nothing
+    }
+}
+
+fn declared(method: &str, path: &str) -> Option<Access> {
+    MATRIX
+        .iter()
+        .find(|(m, p, _)| *m == method && *p == path)
+        .map(|(_, _, a)| *a)
+}
+
+#[test]
+fn finds_the_registered_routes() {
+    let routes = api_routes();
+    assert!(
+        routes.len() >= MATRIX.len() / 2,
+        "route parsing looks broken"
+    );
+    assert!(routes.iter().any(|r| r.method == "DELETE"
+        && r.path == "/api/my/phone"
+        && r.handler == "api_my_phone_delete"));
+}
+
+#[test]
+fn every_route_declares_its_access() {
+    let missing: Vec<String> = api_routes()
+        .iter()
+        .filter(|r| declared(&r.method, &r.path).is_none())
+        .map(|r| format!("{} {} ({})", r.method, r.path, r.handler))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "routes without an authz entry in tests/authz.rs:\n  {}",
+        missing.join("\n  ")
+    );
+
+    let routes = api_routes();
+    let stale: Vec<String> = MATRIX
+        .iter()
+        .filter(|(m, p, _)| !routes.iter().any(|r| r.method == *m && r.path == *p))
+        .map(|(m, p, _)| format!("{m} {p}"))
+        .collect();
+    assert!(
+        stale.is_empty(),
+        "authz entries for routes that no longer exist:\n  {}",
+        stale.join("\n  ")
+    );
+}
+
+#[test]
+fn handlers_enforce_their_declared_access() {
+    let mismatches: Vec<String> = api_routes()
+        .iter()
+        .filter_map(|r| {
+            let expected = declared(&r.method, &r.path)?;
+            let actual = r.router.max(enforced_by(&r.handler));
+            (expected.enforced() != actual).then(|| {
+                format!(
+                    "{} {} ({}): declared {expected:?}, route enforces {actual:?}",
+                    r.method, r.path, r.handler
+                )
+            })
+        })
+        .collect();
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn admin_paths_require_admin() {
+    for (method, path, access) in MATRIX {
+        if path.starts_with("/api/admin/") {
+            assert_eq!(*access, Admin, "{method} {path} must be admin-only");
+        }
+    }
+}
+
+/// `GET /api/users/me` as the local user `header` names, if any.
+async fn me(server: &Server, header: Option<&str>) -> (u16, Value) {
+    let mut request = reqwest::Client::new().get(server.url("/api/users/me"));
+    if let Some(user) = header {
+        request = request.header("X-GTM-User", user);
+    }
+    let resp = request.send().await.unwrap();
+    let status = resp.status().as_u16();
+    (status, resp.json().await.unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn local_users_act_as_the_member_they_name() {
+    let server = Server::start(
+        "local-users",
+        &[
+            ("GTM_AUTH_MODE", "none"),
+            ("GTM_ADMIN_EMAILS", "Ann@Example.com"),
+        ],
+    )
+    .await;
+    for (email, name) in [("ann@example.com", "Ann"), ("bob@example.com", "Bob")] {
+        let out = server.cli(&["add-user", "--email", email, "--name", name]);
+        assert!(out.status.success(), "{out:?}");
+    }
+
+    // The picker lists everyone, with admins as configured
+    let users: Vec<Value> = reqwest::get(server.url("/api/auth/local-users"))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let find = |email: &str| {
+        users
+            .iter()
+            .find(|u| u["email"] == email)
+            .unwrap_or_else(|| panic!("{email} not listed: {users:?}"))
+            .clone()
+    };
+    let (ann, bob) = (find("ann@example.com"), find("bob@example.com"));
+    assert_eq!(ann["admin"], true);
+    assert_eq!(bob["admin"], false);
+    let (ann_id, bob_id) = (ann["id"].to_string(), bob["id"].to_string());
+
+    // The header names a user by id, surrounding spaces allowed
+    let (status, body) = me(&server, Some(&format!(" {ann_id} "))).await;
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["email"], "ann@example.com");
+    assert_eq!(body["role"], "admin");
+    let (status, body) = me(&server, Some(&bob_id)).await;
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["role"], "member");
+    for header in [
+        None,
+        Some(""),
+        Some("ann@example.com"),
+        Some("-1"),
+        Some("99999"),
+    ] {
+        let (status, body) = me(&server, header).await;
+        assert_eq!(status, 401, "{header:?}: {body}");
+    }
+
+    // Only configured admins get past the admin gate
+    let jobs = |user: String| {
+        reqwest::Client::new()
+            .get(server.url("/api/admin/jobs"))
+            .header("X-GTM-User", user)
+            .send()
+    };
+    assert_eq!(jobs(ann_id).await.unwrap().status().as_u16(), 200);
+    assert_eq!(jobs(bob_id).await.unwrap().status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn auth0_ignores_local_users() {
+    // Nothing listens on port 9, so no JWKS keys load
+    let server = Server::start(
+        "auth0-local",
+        &[
+            ("GTM_AUTH_MODE", "auth0"),
+            ("AUTH0_DOMAIN", "127.0.0.1:9"),
+            ("AUTH0_AUDIENCE", "https://gtm.example.com"),
+            ("GTM_ADMIN_EMAILS", "ann@example.com"),
+        ],
+    )
+    .await;
+    let out = server.cli(&["add-user", "--email", "ann@example.com", "--name", "Ann"]);
+    assert!(out.status.success(), "{out:?}");
+
+    let resp = reqwest::get(server.url("/api/auth/local-users"))
+        .await
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 404);
+    let (status, body) = me(&server, Some("1")).await;
+    assert_eq!(status, 401, "{body}");
+}
+
+#[test]
+fn serve_refuses_an_unknown_auth_mode() {
+    let dir = common::test_dir("bad-auth-mode");
+    let out = common::gtm(&dir)
+        .args(["serve", "--port", "0"])
+        .env("GTM_AUTH_MODE", "None")
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("Invalid auth_mode \"None\""), "{stderr}");
+}
@@ -0,0 +1,77 @@
+//! The bulk-delete guard on a running server: deleting more than
+//! `bulk_confirm_threshold` rows takes a dry run's confirm token, over HTTP,
+//! or `--yes` on the CLI.
+
+mod common;
+
+use common::Server;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn seat_group_deletes_need_a_confirm_token() {
+    let server = Server::start(
+        "bulk-guard",
+        &[
+            ("GTM_AUTH_MODE", "none"),
+            ("GTM_ADMIN_EMAILS", "ann@example.com"),
+        ],
+    )
+    .await;
+    let out = server.cli(&["add-user", "--email", "ann@example.com", "--name", "Ann"]);
+    assert!(out.status.success(), "{out:?}");
+    for (row, seat) in [("A", "1"), ("A", "2"), ("B", "1"), ("B", "2")] {
+        let out = server.cli(&["add-seat", "--section", "121", "--row", row, "--seat", seat]);
+        assert!(out.status.success(), "{out:?}");
+    }
+    let client = reqwest::Client::new();
+    let delete_group = |body: Value| {
+        client
+            .delete(server.url("/api/seats/group"))
+            .header("X-GTM-User", "1")
+            .json(&body)
+            .send()
+    };
+
+    let resp = delete_group(json!({ "section": "121", "row": "A" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 428);
+    let preview: Value = delete_group(json!({ "section": "121", "row": "A", "dry_run": true }))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(preview["affected"], 2);
+    assert_eq!(preview["threshold"], 1);
+    let token = preview["confirm_token"].as_str().unwrap();
+    let resp = delete_group(json!({ "section": "121", "row": "A", "confirm_token": token }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+    // A token is good once
+    let resp = delete_group(json!({ "section": "121", "row": "B", "confirm_token": token }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 428);
+
+    // The CLI asks for --yes instead
+    let args = ["delete-seat-group", "--section", "121", "--row", "B"];
+    assert!(!server.cli(&args).status.success());
+    let seats: Vec<Value> = reqwest::get(server.url("/api/seats"))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(seats.len(), 2);
+    let out = server.cli(&[&args[..], &["--yes"]].concat());
+    assert!(out.status.success(), "{out:?}");
+    let seats: Vec<Value> = reqwest::get(server.url("/api/seats"))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(seats.is_empty(), "{seats:?}");
+}
@@ -0,0 +1,148 @@
+//! Handlers must not make several writes themselves.
+//!
+//! A handler's future is dropped when its client disconnects, so writes made
+//! one after another in a handler can stop part-way. Multi-step mutations
+//! belong in `src/service.rs`, which finishes them in a detached task. Like
+//! `authz.rs`, this reads `src/main.rs`: each `api_*` handler may make at
+//! most one `gtm_db` write, counting those of the local functions it calls,
+//! not inside a loop, and must not call the multi-step helpers directly.
+
+const MAIN_RS: &str = include_str!("../src/main.rs");
+
+/// Name prefixes of `gtm_db` functions that write.
+const WRITE_PREFIXES: &[&str] = &[
+    "add_",
+    "assign_",
+    "claim",
+    "commit_",
+    "create_",
+    "delete_",
+    "generate_",
+    "insert_",
+    "regenerate_",
+    "release_",
+    "respond_",
+    "revoke_",
+    "take_",
+    "transfer_",
+    "update_",
+    "upsert_",
+];
+
+/// Helpers in `main.rs` that make several writes.
+const MULTI_STEP_HELPERS: &[&str] = &["run_scrape(", "run_auto_allocation("];
+
+/// `(name, body)` of every top-level function in `main.rs`.
+fn functions() -> Vec<(&'static str, &'static str)> {
+    let mut out = Vec::new();
+    let mut rest = MAIN_RS;
+    while let Some(start) = rest
+        .find("\nfn ")
+        .into_iter()
+        .chain(rest.find("\nasync fn "))
+        .min()
+    {
+        let after = &rest[start + 1..];
+        let after = &after[after.find("fn ").unwrap() + "fn ".len()..];
+        let name = &after[..after.find(['(', '<']).unwrap()];
+        let end = after.find("\n}\n").unwrap();
+        out.push((name, &after[..end]));
+        rest = &after[end..];
+    }
+    out
+}
+
+/// `(name, body)` of every `api_*` handler.
+fn handlers() -> Vec<(&'static str, &'static str)> {
+    functions()
+        .into_iter()
+        .filter(|(name, _)| name.starts_with("api_"))
+        .collect()
+}
+
+/// `body` followed by the bodies of the local functions it calls, and those
+/// they call. `spawn_*` helpers run in the background, so their writes
+/// aren't the caller's.
+fn with_callees(body: &'static str) -> Vec<&'static str> {
+    let helpers: Vec<_> = functions()
+        .into_iter()
+        .filter(|(name, _)| !name.starts_with("api_") && !name.starts_with("spawn_"))
+        .collect();
+    let mut bodies = vec![body];
+    let mut seen = Vec::new();
+    let mut i = 0;
+    while i < bodies.len() {
+        for (name, helper) in &helpers {
+            if !seen.contains(name) && calls(bodies[i], name) {
+                seen.push(*name);
+                bodies.push(helper);
+            }
+        }
+        i += 1;
+    }
+    bodies
+}
+
+/// Whether `body` calls the local function `name`, not a method or another
+/// module's function of the same name.
+fn calls(body: &str, name: &str) -> bool {
+    body.match_indices(&format!("{name}(")).any(|(i, _)| {
+        !body[..i].ends_with(|c: char| c.is_alphanumeric() || matches!(c, '_' | '.' | ':'))
+    })
+}
+
+/// Write calls in `body`, each with whether it sits inside a `for` loop.
+fn writes(body: &str) -> Vec<(String, bool)> {
+    let mut out = Vec::new();
+    for (i, _) in body.match_indices("gtm_db::") {
+        let path = &body[i + "gtm_db::".len()..];
+        let path = &path[..path.find('(').unwrap_or(0)];
+        let name = path.rsplit("::").next().unwrap_or(path);
+        if name.is_empty() || !WRITE_PREFIXES.iter().any(|p| name.starts_with(p)) {
+            continue;
+        }
+        let in_loop = body[..i]
+            .lines()
+            .any(|l| l.trim_start().starts_with("for "));
+        out.push((name.to_string(), in_loop));
+    }
+    out
+}
+
+#[test]
+fn finds_the_handlers() {
+    let names: Vec<&str> = handlers().iter().map(|(n, _)| *n).collect();
+    assert!(names.len() > 50, "found only {} handlers", names.len());
+    assert!(names.contains(&"api_admin_allocate"));
+}
+
+#[test]
+fn follows_calls_into_local_helpers() {
+    let (_, body) = handlers()
+        .into_iter()
+        .find(|(name, _)| *name == "api_list_games")
+        .unwrap();
+    let writes: Vec<_> = with_callees(body).into_iter().flat_map(writes).collect();
+    assert!(writes.iter().any(|(name, _)| name == "upsert_user"));
+}
+
+#[test]
+fn handlers_make_at_most_one_write() {
+    let mut offenders = Vec::new();
+    for (name, body) in handlers() {
+        let writes: Vec<_> = with_callees(body).into_iter().flat_map(writes).collect();
+        if writes.len() > 1 || writes.iter().any(|(_, in_loop)| *in_loop) {
+            offenders.push(format!("{name}: {writes:?}"));
+        }
+        for helper in MULTI_STEP_HELPERS {
+            if body.contains(helper) {
+                offenders.push(format!("{name}: calls {helper}..)"));
+            }
+        }
+    }
+    assert!(
+        offenders.is_empty(),
+        "move these writes into src/service.rs:\n{}",
+        offenders.join("\n")
+    );
+}
@@ -0,0 +1,106 @@
+// Shared by several test binaries; not every one uses every helper.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::Duration;
+
+/// A `gtm serve` started for one test, on its own SQLite database and
+/// home directory (so no `~/.gtm/config.toml` is read), with background
+/// jobs off. Killed when dropped.
+pub struct Server {
+    child: Child,
+    pub dir: PathBuf,
+    pub base: String,
+    env: Vec<(String, String)>,
+}
+
+/// A fresh, empty directory for one test's database and home.
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gtm-serve-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// `gtm` run in `dir`, on the SQLite database there, with only the
+/// defaults above set.
+pub fn gtm(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_gtm"));
+    command
+        .current_dir(dir)
+        .env_clear()
+        .env("HOME", dir)
+        .env("PATH", std::env::var("PATH").unwrap_or_default())
+        .env(
+            "GTM_DB_URL",
+            format!("sqlite:{}?mode=rwc", dir.join("gtm.db").display()),
+        )
+        .env("GTM_AUTO_MIGRATE", "true")
+        .env("GTM_RUN_JOBS", "false")
+        .env("GTM_HTTP_MAX_ATTEMPTS", "1")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    command
+}
+
+impl Server {
+    /// Start the server with `env` set on top of the defaults above, and
+    /// wait until it answers.
+    pub async fn start(name: &str, env: &[(&str, &str)]) -> Server {
+        let dir = test_dir(name);
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let child = gtm(&dir)
+            .args(["serve", "--port", &port.to_string()])
+            .envs(env.iter().copied())
+            .spawn()
+            .unwrap();
+        let server = Server {
+            child,
+            dir,
+            base: format!("http://127.0.0.1:{port}"),
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+        for _ in 0..300 {
+            if reqwest::get(server.url("/api/health/live")).await.is_ok() {
+                return server;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("gtm serve didn't start");
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base)
+    }
+
+    /// Run a `gtm` CLI command against the server's database and config.
+    pub fn cli(&self, args: &[&str]) -> Output {
+        gtm(&self.dir)
+            .args(args)
+            .envs(self.env.iter().map(|(k, v)| (k, v)))
+            .stdout(Stdio::piped())
+            .output()
+            .unwrap()
+    }
+
+    /// The server's database, for a test to change behind its back.
+    pub fn db_url(&self) -> String {
+        format!("sqlite:{}", self.dir.join("gtm.db").display())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
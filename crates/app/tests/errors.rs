@@ -0,0 +1,69 @@
+//! Failed API calls on a running server: bodies axum can't parse and
+//! database failures both come back as the `{ code, message }` envelope,
+//! without the database's own text.
+
+mod common;
+
+use common::Server;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn invoice_errors_use_the_error_envelope() {
+    let server = Server::start(
+        "errors",
+        &[
+            ("GTM_AUTH_MODE", "none"),
+            ("GTM_ADMIN_EMAILS", "ann@example.com"),
+        ],
+    )
+    .await;
+    let out = server.cli(&["add-user", "--email", "ann@example.com", "--name", "Ann"]);
+    assert!(out.status.success(), "{out:?}");
+    let client = reqwest::Client::new();
+    let send = |req: reqwest::RequestBuilder| async move {
+        let resp = req.header("X-GTM-User", "1").send().await.unwrap();
+        let status = resp.status().as_u16();
+        (status, resp.json::<Value>().await.unwrap())
+    };
+
+    // Rejections from axum's extractors
+    let (status, body) = send(
+        client
+            .post(server.url("/api/admin/invoices"))
+            .json(&json!({})),
+    )
+    .await;
+    assert_eq!(status, 422, "{body}");
+    assert_eq!(body["code"], "validation");
+    let (status, body) = send(client.get(server.url("/api/admin/invoices/abc"))).await;
+    assert_eq!(status, 400, "{body}");
+    assert_eq!(body["code"], "validation");
+
+    // Rejections from the invoice code
+    let (status, body) = send(
+        client
+            .post(server.url("/api/admin/invoices"))
+            .json(&json!({ "period": "2026" })),
+    )
+    .await;
+    assert_eq!(status, 400, "{body}");
+    assert!(
+        body["message"].as_str().unwrap().contains("YYYY-MM"),
+        "{body}"
+    );
+
+    // A database failure is logged, not echoed
+    sqlx::any::install_default_drivers();
+    let pool = sqlx::AnyPool::connect(&server.db_url()).await.unwrap();
+    sqlx::query("DROP TABLE invoices")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+    let (status, body) = send(client.get(server.url("/api/my/invoices"))).await;
+    assert_eq!(status, 500, "{body}");
+    assert_eq!(
+        body,
+        json!({ "code": "internal", "message": "Internal error" })
+    );
+}
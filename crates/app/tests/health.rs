@@ -0,0 +1,67 @@
+//! `/api/health/ready` on a running server: 200 while the database
+//! answers, 503 once it doesn't, and no error text either way.
+
+mod common;
+
+use common::Server;
+use serde_json::Value;
+
+async fn ready(server: &Server) -> (u16, Value) {
+    let resp = reqwest::get(server.url("/api/health/ready")).await.unwrap();
+    let status = resp.status().as_u16();
+    (status, resp.json().await.unwrap())
+}
+
+#[tokio::test]
+async fn not_ready_once_the_database_stops_answering() {
+    let server = Server::start(
+        "db",
+        &[
+            ("GTM_AUTH_MODE", "none"),
+            ("GTM_HEALTH_MAX_SCRAPE_AGE_HOURS", "0"),
+        ],
+    )
+    .await;
+    let (status, body) = ready(&server).await;
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["checks"]["database"]["status"], "ok");
+
+    // A database that isn't ours any more, e.g. restored empty
+    sqlx::any::install_default_drivers();
+    let pool = sqlx::AnyPool::connect(&server.db_url()).await.unwrap();
+    sqlx::query("DROP TABLE _sqlx_migrations")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+
+    let (status, body) = ready(&server).await;
+    assert_eq!(status, 503, "{body}");
+    assert_eq!(body["status"], "not_ready");
+    assert_eq!(
+        body["checks"]["database"],
+        serde_json::json!({ "status": "failed" })
+    );
+    assert!(!body.to_string().contains("_sqlx_migrations"), "{body}");
+}
+
+#[tokio::test]
+async fn missing_signing_keys_are_degraded_not_unready() {
+    // Nothing listens on port 9, so no JWKS keys load
+    let server = Server::start(
+        "jwks",
+        &[
+            ("GTM_AUTH_MODE", "auth0"),
+            ("AUTH0_DOMAIN", "127.0.0.1:9"),
+            ("AUTH0_AUDIENCE", "https://gtm.example.com"),
+            ("GTM_HEALTH_MAX_SCRAPE_AGE_HOURS", "0"),
+        ],
+    )
+    .await;
+    let (status, body) = ready(&server).await;
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["status"], "degraded");
+    assert_eq!(body["checks"]["jwks"]["status"], "degraded");
+    assert_eq!(body["checks"]["jwks"]["keys"], 0);
+}
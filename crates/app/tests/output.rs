@@ -0,0 +1,43 @@
+//! `--format` on the CLI: the listings print CSV with a header even when
+//! empty, and commands that don't list refuse the flag.
+
+mod common;
+
+use std::process::Stdio;
+
+use common::{gtm, test_dir};
+
+#[test]
+fn format_applies_to_listings_only() {
+    let dir = test_dir("format");
+
+    let out = gtm(&dir)
+        .args(["--format", "csv", "list-seats"])
+        .stdout(Stdio::piped())
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(
+        String::from_utf8(out.stdout).unwrap(),
+        "id,section,row,seat,notes\n"
+    );
+
+    let out = gtm(&dir)
+        .args([
+            "--format",
+            "json",
+            "add-user",
+            "--email",
+            "a@example.com",
+            "--name",
+            "A",
+        ])
+        .stderr(Stdio::piped())
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("--format only applies"),
+        "{out:?}"
+    );
+}
@@ -8,10 +8,35 @@ use std::path::PathBuf;
 #[derive(Debug, Clone)]
 pub struct Config {
     // Database
+    /// A `sqlite:`, `postgres:` or `mysql:` URL.
     pub db_url: String,
+    /// Postgres read replica for list and report queries. Reads fall back
+    /// to `db_url` while it is unreachable.
+    pub db_read_url: Option<String>,
+    /// Most connections the pool opens.
+    pub db_max_connections: u32,
+    /// Seconds to wait for a free pooled connection before failing.
+    pub db_acquire_timeout_secs: u64,
+    /// Seconds a report or list query may run before it is cancelled; 0 for
+    /// no limit.
+    pub db_statement_timeout_secs: u64,
+    /// Queries taking at least this many milliseconds are logged as slow; 0
+    /// to log none.
+    pub db_slow_query_ms: u64,
+    /// Log every SQL statement with its duration and row counts.
+    pub db_log_queries: bool,
+    /// Apply pending migrations whenever a command connects. Off by default:
+    /// run `gtm db migrate` after an upgrade instead.
+    pub auto_migrate: bool,
+    /// Put SQLite in WAL mode so readers don't block the writer.
+    pub sqlite_wal: bool,
+    /// Milliseconds a SQLite write waits on a lock before "database is locked".
+    pub sqlite_busy_timeout_ms: u64,
 
     // Server
     pub port: u16,
+    /// Base URL members reach the app at, used for links in notifications.
+    pub public_url: String,
 
     // Logging
     pub log_level: String,
@@ -19,9 +44,137 @@ pub struct Config {
     pub log_json: bool,
 
     // Auth0
+    /// `auth0`, or `none` to skip login: callers pick a local user instead.
+    /// Only for deployments no one untrusted can reach.
+    pub auth_mode: String,
     pub auth0_domain: String,
     pub auth0_client_id: String,
     pub auth0_audience: String,
+
+    // Email (Mailgun) — outbound notifications and inbound replies
+    pub mailgun_domain: Option<String>,
+    pub mailgun_api_key: Option<String>,
+    pub mailgun_signing_key: Option<String>,
+    pub mail_from: String,
+    /// SMTP relay for outbound email; preferred over Mailgun when set.
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+
+    // SMS (Twilio)
+    pub twilio_account_sid: Option<String>,
+    pub twilio_auth_token: Option<String>,
+    pub twilio_from_number: Option<String>,
+
+    // Google Calendar (OAuth client) — optional per-member calendar sync
+    pub google_client_id: Option<String>,
+    pub google_client_secret: Option<String>,
+
+    // Stripe — optional online payment of invoices
+    pub stripe_secret_key: Option<String>,
+    pub stripe_webhook_secret: Option<String>,
+    /// ISO currency code invoices are charged in.
+    pub stripe_currency: String,
+
+    // Team
+    /// MLB Stats API id of the team whose home games we hold seats for.
+    pub team_id: u32,
+    /// That team's full name, as the schedule reports it.
+    pub team_name: String,
+    /// IANA time zone game times are shown in, e.g. `America/Los_Angeles`.
+    /// MLB gives them in UTC. It also decides what "today" is and when the
+    /// daily jobs run.
+    pub display_timezone: String,
+    /// Where the home ballpark is, for the rain forecast.
+    pub venue_latitude: f64,
+    pub venue_longitude: f64,
+
+    // Allocation
+    pub allocation_strategy: String,
+    /// MLB game-type codes (R, S, P, F, D, L, W) of the home games we hold
+    /// seats for. Tickets are only generated for these, and the nightly
+    /// scrape fetches them (GTM_TICKETED_GAME_TYPES is comma-separated).
+    /// Unset, each game type's own default applies: regular season only.
+    pub ticketed_game_types: Option<Vec<String>>,
+    /// Report fields the MLB schedule API sends that we don't know about
+    /// after each scrape.
+    pub schema_drift: bool,
+    /// Optional schedule sections the scrape asks for: promotions, tickets,
+    /// venue, weather, probablePitcher, broadcasts (GTM_SCRAPE_HYDRATE is
+    /// comma-separated; empty fetches the bare schedule).
+    pub scrape_hydrate: Vec<String>,
+    /// Show members' names on the transparency page; otherwise everyone but
+    /// the viewer is "Member N".
+    pub transparency_named: bool,
+
+    // Background jobs
+    /// Run the job scheduler inside `gtm serve`. Turn off when a separate
+    /// `gtm worker` handles jobs; either way only one process runs them.
+    pub run_jobs: bool,
+    /// Poll the MLB linescore for scores and innings while today's games
+    /// are being played.
+    pub live_scores: bool,
+
+    // Schedule
+    /// When the schedule is re-scraped, as a cron expression with seconds
+    /// (`sec min hour day-of-month month day-of-week`) in `display_timezone`.
+    pub scrape_cron: String,
+    /// Seconds allowed to connect to an upstream API (MLB, Open-Meteo).
+    pub http_connect_timeout_secs: u64,
+    /// Seconds allowed for a whole upstream request, including the body.
+    pub http_timeout_secs: u64,
+    /// Attempts per upstream request before giving up (1 disables retry).
+    pub http_max_attempts: u32,
+    /// Delay before the first retry, in milliseconds; doubles each attempt.
+    pub http_backoff_ms: u64,
+    /// Upper bound on the retry delay, in milliseconds.
+    pub http_max_backoff_ms: u64,
+    /// Failed calls in a row (each after its retries) before an upstream's
+    /// circuit breaker opens and calls to it fail fast.
+    pub breaker_failures: u32,
+    /// Seconds a breaker stays open before a probe call; doubles after each
+    /// failed probe, up to ten minutes.
+    pub breaker_cooldown_secs: u64,
+    /// Where promotion images fetched for the SPA are kept.
+    pub image_cache_dir: String,
+    /// Hours a cached promotion image is served before it's fetched again.
+    pub image_cache_ttl_hours: u64,
+    /// Fewest games a schedule scrape may return, once games are stored,
+    /// before it's treated as suspect. 0 turns the check off.
+    pub sync_min_games: usize,
+    /// Drop in promotions against those stored, in percent, at which a
+    /// schedule scrape is treated as suspect. 0 turns the check off.
+    pub sync_promotion_drop_pct: u32,
+    /// Hours since the scheduled scrape last succeeded after which
+    /// `/api/health/ready` reports the schedule as stale. 0 turns the check
+    /// off.
+    pub health_max_scrape_age_hours: u64,
+
+    // Requests
+    /// Hours a request may sit pending before it's flagged overdue.
+    pub request_sla_hours: i64,
+
+    // Safety
+    /// Bulk deletes and edits touching more rows than this need a
+    /// `confirm_token` from a dry run first (the CLI asks for `--yes`).
+    pub bulk_confirm_threshold: i64,
+
+    // Alerts
+    /// Addresses that receive admin alerts (GTM_ADMIN_EMAILS is comma-separated).
+    pub admin_emails: Vec<String>,
+    /// How many days ahead the daily unassigned-seat check looks.
+    pub unassigned_alert_days: i64,
+    /// Also send the unassigned-seat alert to every member.
+    pub unassigned_alert_group: bool,
+    /// Chance of rain (percent) at which assigned members get a release prompt.
+    pub rain_release_threshold: i64,
+
+    // Game packets
+    /// Which gate to use, printed on each member's game packet.
+    pub gate_info: Option<String>,
+    /// Parking pass details, printed on each member's game packet.
+    pub parking_info: Option<String>,
 }
 
 /// Config file layout (~/.gtm/config.toml). All fields optional — they layer
@@ -29,13 +182,73 @@ pub struct Config {
 #[derive(Debug, Deserialize, Default)]
 struct FileConfig {
     db_url: Option<String>,
+    db_read_url: Option<String>,
+    db_max_connections: Option<u32>,
+    db_acquire_timeout_secs: Option<u64>,
+    db_statement_timeout_secs: Option<u64>,
+    db_slow_query_ms: Option<u64>,
+    db_log_queries: Option<bool>,
+    auto_migrate: Option<bool>,
+    sqlite_wal: Option<bool>,
+    sqlite_busy_timeout_ms: Option<u64>,
     port: Option<u16>,
+    public_url: Option<String>,
     log_level: Option<String>,
     utc: Option<bool>,
     log_json: Option<bool>,
+    auth_mode: Option<String>,
     auth0_domain: Option<String>,
     auth0_client_id: Option<String>,
     auth0_audience: Option<String>,
+    mailgun_domain: Option<String>,
+    mailgun_api_key: Option<String>,
+    mailgun_signing_key: Option<String>,
+    mail_from: Option<String>,
+    smtp_host: Option<String>,
+    smtp_port: Option<u16>,
+    smtp_username: Option<String>,
+    smtp_password: Option<String>,
+    twilio_account_sid: Option<String>,
+    twilio_auth_token: Option<String>,
+    twilio_from_number: Option<String>,
+    google_client_id: Option<String>,
+    google_client_secret: Option<String>,
+    stripe_secret_key: Option<String>,
+    stripe_webhook_secret: Option<String>,
+    stripe_currency: Option<String>,
+    team_id: Option<u32>,
+    team_name: Option<String>,
+    display_timezone: Option<String>,
+    venue_latitude: Option<f64>,
+    venue_longitude: Option<f64>,
+    allocation_strategy: Option<String>,
+    ticketed_game_types: Option<Vec<String>>,
+    schema_drift: Option<bool>,
+    scrape_hydrate: Option<Vec<String>>,
+    transparency_named: Option<bool>,
+    run_jobs: Option<bool>,
+    live_scores: Option<bool>,
+    scrape_cron: Option<String>,
+    http_connect_timeout_secs: Option<u64>,
+    http_timeout_secs: Option<u64>,
+    http_max_attempts: Option<u32>,
+    http_backoff_ms: Option<u64>,
+    http_max_backoff_ms: Option<u64>,
+    breaker_failures: Option<u32>,
+    breaker_cooldown_secs: Option<u64>,
+    image_cache_dir: Option<String>,
+    image_cache_ttl_hours: Option<u64>,
+    sync_min_games: Option<usize>,
+    sync_promotion_drop_pct: Option<u32>,
+    health_max_scrape_age_hours: Option<u64>,
+    request_sla_hours: Option<i64>,
+    bulk_confirm_threshold: Option<i64>,
+    admin_emails: Option<Vec<String>>,
+    unassigned_alert_days: Option<i64>,
+    unassigned_alert_group: Option<bool>,
+    rain_release_threshold: Option<i64>,
+    gate_info: Option<String>,
+    parking_info: Option<String>,
 }
 
 impl Config {
@@ -69,18 +282,274 @@ impl Config {
         config
     }
 
+    /// Every setting as `(name, value)`, safe to log or show an admin.
+    /// Keys, tokens and passwords read `<redacted>` when set (including a
+    /// password in a database URL); unset options read `<unset>`.
+    pub fn redacted(&self) -> Vec<(&'static str, String)> {
+        let opt = |v: &Option<String>| v.clone().unwrap_or_else(|| UNSET.to_string());
+        let secret = |v: &Option<String>| if v.is_some() { REDACTED } else { UNSET }.to_string();
+        vec![
+            ("db_url", redact_url(&self.db_url)),
+            (
+                "db_read_url",
+                self.db_read_url
+                    .as_deref()
+                    .map_or(UNSET.to_string(), redact_url),
+            ),
+            ("db_max_connections", self.db_max_connections.to_string()),
+            (
+                "db_acquire_timeout_secs",
+                self.db_acquire_timeout_secs.to_string(),
+            ),
+            (
+                "db_statement_timeout_secs",
+                self.db_statement_timeout_secs.to_string(),
+            ),
+            ("db_slow_query_ms", self.db_slow_query_ms.to_string()),
+            ("db_log_queries", self.db_log_queries.to_string()),
+            ("auto_migrate", self.auto_migrate.to_string()),
+            ("sqlite_wal", self.sqlite_wal.to_string()),
+            (
+                "sqlite_busy_timeout_ms",
+                self.sqlite_busy_timeout_ms.to_string(),
+            ),
+            ("port", self.port.to_string()),
+            ("public_url", self.public_url.clone()),
+            ("log_level", self.log_level.clone()),
+            ("utc", self.utc.to_string()),
+            ("log_json", self.log_json.to_string()),
+            ("auth_mode", self.auth_mode.clone()),
+            ("auth0_domain", self.auth0_domain.clone()),
+            ("auth0_client_id", self.auth0_client_id.clone()),
+            ("auth0_audience", self.auth0_audience.clone()),
+            ("mailgun_domain", opt(&self.mailgun_domain)),
+            ("mailgun_api_key", secret(&self.mailgun_api_key)),
+            ("mailgun_signing_key", secret(&self.mailgun_signing_key)),
+            ("mail_from", self.mail_from.clone()),
+            ("smtp_host", opt(&self.smtp_host)),
+            ("smtp_port", self.smtp_port.to_string()),
+            ("smtp_username", opt(&self.smtp_username)),
+            ("smtp_password", secret(&self.smtp_password)),
+            ("twilio_account_sid", opt(&self.twilio_account_sid)),
+            ("twilio_auth_token", secret(&self.twilio_auth_token)),
+            ("twilio_from_number", opt(&self.twilio_from_number)),
+            ("google_client_id", opt(&self.google_client_id)),
+            ("google_client_secret", secret(&self.google_client_secret)),
+            ("stripe_secret_key", secret(&self.stripe_secret_key)),
+            ("stripe_webhook_secret", secret(&self.stripe_webhook_secret)),
+            ("stripe_currency", self.stripe_currency.clone()),
+            ("team_id", self.team_id.to_string()),
+            ("team_name", self.team_name.clone()),
+            ("display_timezone", self.display_timezone.clone()),
+            ("venue_latitude", self.venue_latitude.to_string()),
+            ("venue_longitude", self.venue_longitude.to_string()),
+            ("allocation_strategy", self.allocation_strategy.clone()),
+            (
+                "ticketed_game_types",
+                self.ticketed_game_types
+                    .as_ref()
+                    .map_or_else(|| "(per game type)".to_string(), |v| v.join(",")),
+            ),
+            ("schema_drift", self.schema_drift.to_string()),
+            ("scrape_hydrate", self.scrape_hydrate.join(",")),
+            ("transparency_named", self.transparency_named.to_string()),
+            ("run_jobs", self.run_jobs.to_string()),
+            ("live_scores", self.live_scores.to_string()),
+            ("scrape_cron", self.scrape_cron.clone()),
+            (
+                "http_connect_timeout_secs",
+                self.http_connect_timeout_secs.to_string(),
+            ),
+            ("http_timeout_secs", self.http_timeout_secs.to_string()),
+            ("http_max_attempts", self.http_max_attempts.to_string()),
+            ("http_backoff_ms", self.http_backoff_ms.to_string()),
+            ("http_max_backoff_ms", self.http_max_backoff_ms.to_string()),
+            ("breaker_failures", self.breaker_failures.to_string()),
+            (
+                "breaker_cooldown_secs",
+                self.breaker_cooldown_secs.to_string(),
+            ),
+            ("image_cache_dir", self.image_cache_dir.clone()),
+            (
+                "image_cache_ttl_hours",
+                self.image_cache_ttl_hours.to_string(),
+            ),
+            ("sync_min_games", self.sync_min_games.to_string()),
+            (
+                "sync_promotion_drop_pct",
+                self.sync_promotion_drop_pct.to_string(),
+            ),
+            (
+                "health_max_scrape_age_hours",
+                self.health_max_scrape_age_hours.to_string(),
+            ),
+            ("request_sla_hours", self.request_sla_hours.to_string()),
+            (
+                "bulk_confirm_threshold",
+                self.bulk_confirm_threshold.to_string(),
+            ),
+            ("admin_emails", self.admin_emails.join(",")),
+            (
+                "unassigned_alert_days",
+                self.unassigned_alert_days.to_string(),
+            ),
+            (
+                "unassigned_alert_group",
+                self.unassigned_alert_group.to_string(),
+            ),
+            (
+                "rain_release_threshold",
+                self.rain_release_threshold.to_string(),
+            ),
+            ("gate_info", opt(&self.gate_info)),
+            ("parking_info", opt(&self.parking_info)),
+        ]
+    }
+
+    /// [`Self::redacted`] with where each value came from: the last layer
+    /// that set it. `cli` names the settings the command line set, since
+    /// the caller merges those.
+    pub fn sources(&self, cli: &[&str]) -> Vec<(&'static str, String, Source)> {
+        let file_keys = Self::read_file()
+            .ok()
+            .flatten()
+            .map(|table| table.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        self.redacted()
+            .into_iter()
+            .map(|(name, value)| {
+                let source = if cli.contains(&name) {
+                    Source::Cli
+                } else if env_var(name).is_some_and(|var| std::env::var(var).is_ok()) {
+                    Source::Env
+                } else if file_keys.iter().any(|k| k == name) {
+                    Source::File
+                } else {
+                    Source::Default
+                };
+                (name, value, source)
+            })
+            .collect()
+    }
+
+    /// Check the config file, if there is one: an error if [`Self::load`]
+    /// would ignore it, otherwise the keys in it that aren't settings.
+    pub fn check_file() -> Result<Vec<String>, String> {
+        let Some(table) = Self::read_file()? else {
+            return Ok(Vec::new());
+        };
+        let known: Vec<&str> = ENV_VARS.iter().map(|(name, _)| *name).collect();
+        Ok(table
+            .keys()
+            .filter(|k| !known.contains(&k.as_str()))
+            .cloned()
+            .collect())
+    }
+
+    /// Write [`TEMPLATE`] to [`Self::file_path`], creating the directory.
+    /// An existing file is only replaced when `force` is set.
+    pub fn write_template(force: bool) -> std::io::Result<PathBuf> {
+        let path = Self::file_path();
+        if path.exists() && !force {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()),
+            ));
+        }
+        std::fs::create_dir_all(Self::dir())?;
+        std::fs::write(&path, TEMPLATE)?;
+        Ok(path)
+    }
+
     // --- Private helpers ---
 
+    /// The config file's keys, or `None` without a file. Errors when the
+    /// file can't be read or doesn't parse as settings.
+    fn read_file() -> Result<Option<toml::Table>, String> {
+        let path = Self::file_path();
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        };
+        toml::from_str::<FileConfig>(&contents)
+            .and_then(|_| toml::from_str::<toml::Table>(&contents))
+            .map(Some)
+            .map_err(|e| format!("{}: {e}", path.display()))
+    }
+
     fn defaults() -> Self {
         Self {
             db_url: "sqlite:gtm.db".to_string(),
+            db_read_url: None,
+            db_max_connections: 10,
+            db_acquire_timeout_secs: 30,
+            db_statement_timeout_secs: 60,
+            db_slow_query_ms: 1_000,
+            db_log_queries: false,
+            auto_migrate: false,
+            sqlite_wal: true,
+            sqlite_busy_timeout_ms: 5_000,
             port: 3000,
+            public_url: "http://localhost:3000".to_string(),
             log_level: "info".to_string(),
             utc: false,
             log_json: false,
+            auth_mode: "auth0".to_string(),
             auth0_domain: "momentlabs.auth0.com".to_string(),
             auth0_client_id: "rNAdEOf5H8aQNcvK5wonHh8x0iI18knE".to_string(),
             auth0_audience: "https://gtm-api.momentlabs.io".to_string(),
+            mailgun_domain: None,
+            mailgun_api_key: None,
+            mailgun_signing_key: None,
+            mail_from: "GTM <gtm@momentlabs.io>".to_string(),
+            smtp_host: None,
+            smtp_port: 587,
+            smtp_username: None,
+            smtp_password: None,
+            twilio_account_sid: None,
+            twilio_auth_token: None,
+            twilio_from_number: None,
+            google_client_id: None,
+            google_client_secret: None,
+            stripe_secret_key: None,
+            stripe_webhook_secret: None,
+            stripe_currency: "usd".to_string(),
+            team_id: 137,
+            team_name: "San Francisco Giants".to_string(),
+            display_timezone: "America/Los_Angeles".to_string(),
+            venue_latitude: 37.7786,
+            venue_longitude: -122.3893,
+            allocation_strategy: "round-robin".to_string(),
+            ticketed_game_types: None,
+            schema_drift: true,
+            scrape_hydrate: ["promotions", "probablePitcher", "weather", "broadcasts"]
+                .map(String::from)
+                .to_vec(),
+            transparency_named: false,
+            run_jobs: true,
+            live_scores: true,
+            scrape_cron: "0 15 0 * * *".to_string(),
+            http_connect_timeout_secs: 10,
+            http_timeout_secs: 30,
+            http_max_attempts: 4,
+            http_backoff_ms: 500,
+            http_max_backoff_ms: 10_000,
+            breaker_failures: 5,
+            breaker_cooldown_secs: 30,
+            image_cache_dir: Self::dir().join("image-cache").display().to_string(),
+            image_cache_ttl_hours: 168,
+            sync_min_games: 1,
+            sync_promotion_drop_pct: 40,
+            health_max_scrape_age_hours: 48,
+            request_sla_hours: 72,
+            bulk_confirm_threshold: 1,
+            admin_emails: Vec::new(),
+            unassigned_alert_days: 3,
+            unassigned_alert_group: false,
+            rain_release_threshold: 60,
+            gate_info: None,
+            parking_info: None,
         }
     }
 
@@ -88,9 +557,39 @@ impl Config {
         if let Some(v) = file.db_url {
             self.db_url = v;
         }
+        if let Some(v) = file.db_read_url {
+            self.db_read_url = Some(v);
+        }
+        if let Some(v) = file.db_max_connections {
+            self.db_max_connections = v;
+        }
+        if let Some(v) = file.db_acquire_timeout_secs {
+            self.db_acquire_timeout_secs = v;
+        }
+        if let Some(v) = file.db_statement_timeout_secs {
+            self.db_statement_timeout_secs = v;
+        }
+        if let Some(v) = file.db_slow_query_ms {
+            self.db_slow_query_ms = v;
+        }
+        if let Some(v) = file.db_log_queries {
+            self.db_log_queries = v;
+        }
+        if let Some(v) = file.auto_migrate {
+            self.auto_migrate = v;
+        }
+        if let Some(v) = file.sqlite_wal {
+            self.sqlite_wal = v;
+        }
+        if let Some(v) = file.sqlite_busy_timeout_ms {
+            self.sqlite_busy_timeout_ms = v;
+        }
         if let Some(v) = file.port {
             self.port = v;
         }
+        if let Some(v) = file.public_url {
+            self.public_url = v;
+        }
         if let Some(v) = file.log_level {
             self.log_level = v;
         }
@@ -100,6 +599,9 @@ impl Config {
         if let Some(v) = file.log_json {
             self.log_json = v;
         }
+        if let Some(v) = file.auth_mode {
+            self.auth_mode = v;
+        }
         if let Some(v) = file.auth0_domain {
             self.auth0_domain = v;
         }
@@ -109,17 +611,204 @@ impl Config {
         if let Some(v) = file.auth0_audience {
             self.auth0_audience = v;
         }
+        if file.mailgun_domain.is_some() {
+            self.mailgun_domain = file.mailgun_domain;
+        }
+        if file.mailgun_api_key.is_some() {
+            self.mailgun_api_key = file.mailgun_api_key;
+        }
+        if file.mailgun_signing_key.is_some() {
+            self.mailgun_signing_key = file.mailgun_signing_key;
+        }
+        if let Some(v) = file.mail_from {
+            self.mail_from = v;
+        }
+        if file.smtp_host.is_some() {
+            self.smtp_host = file.smtp_host;
+        }
+        if let Some(v) = file.smtp_port {
+            self.smtp_port = v;
+        }
+        if file.smtp_username.is_some() {
+            self.smtp_username = file.smtp_username;
+        }
+        if file.smtp_password.is_some() {
+            self.smtp_password = file.smtp_password;
+        }
+        if file.twilio_account_sid.is_some() {
+            self.twilio_account_sid = file.twilio_account_sid;
+        }
+        if file.twilio_auth_token.is_some() {
+            self.twilio_auth_token = file.twilio_auth_token;
+        }
+        if file.twilio_from_number.is_some() {
+            self.twilio_from_number = file.twilio_from_number;
+        }
+        if file.google_client_id.is_some() {
+            self.google_client_id = file.google_client_id;
+        }
+        if file.google_client_secret.is_some() {
+            self.google_client_secret = file.google_client_secret;
+        }
+        if file.stripe_secret_key.is_some() {
+            self.stripe_secret_key = file.stripe_secret_key;
+        }
+        if file.stripe_webhook_secret.is_some() {
+            self.stripe_webhook_secret = file.stripe_webhook_secret;
+        }
+        if let Some(v) = file.stripe_currency {
+            self.stripe_currency = v.to_lowercase();
+        }
+        if let Some(v) = file.team_id {
+            self.team_id = v;
+        }
+        if let Some(v) = file.team_name {
+            self.team_name = v;
+        }
+        if let Some(v) = file.display_timezone {
+            self.display_timezone = v;
+        }
+        if let Some(v) = file.venue_latitude {
+            self.venue_latitude = v;
+        }
+        if let Some(v) = file.venue_longitude {
+            self.venue_longitude = v;
+        }
+        if let Some(v) = file.allocation_strategy {
+            self.allocation_strategy = v;
+        }
+        if let Some(v) = file.ticketed_game_types {
+            self.ticketed_game_types = Some(v);
+        }
+        if let Some(v) = file.schema_drift {
+            self.schema_drift = v;
+        }
+        if let Some(v) = file.scrape_hydrate {
+            self.scrape_hydrate = v;
+        }
+        if let Some(v) = file.transparency_named {
+            self.transparency_named = v;
+        }
+        if let Some(v) = file.run_jobs {
+            self.run_jobs = v;
+        }
+        if let Some(v) = file.live_scores {
+            self.live_scores = v;
+        }
+        if let Some(v) = file.scrape_cron {
+            self.scrape_cron = v;
+        }
+        if let Some(v) = file.http_connect_timeout_secs {
+            self.http_connect_timeout_secs = v;
+        }
+        if let Some(v) = file.http_timeout_secs {
+            self.http_timeout_secs = v;
+        }
+        if let Some(v) = file.http_max_attempts {
+            self.http_max_attempts = v;
+        }
+        if let Some(v) = file.http_backoff_ms {
+            self.http_backoff_ms = v;
+        }
+        if let Some(v) = file.http_max_backoff_ms {
+            self.http_max_backoff_ms = v;
+        }
+        if let Some(v) = file.breaker_failures {
+            self.breaker_failures = v;
+        }
+        if let Some(v) = file.breaker_cooldown_secs {
+            self.breaker_cooldown_secs = v;
+        }
+        if let Some(v) = file.image_cache_dir {
+            self.image_cache_dir = v;
+        }
+        if let Some(v) = file.image_cache_ttl_hours {
+            self.image_cache_ttl_hours = v;
+        }
+        if let Some(v) = file.sync_min_games {
+            self.sync_min_games = v;
+        }
+        if let Some(v) = file.sync_promotion_drop_pct {
+            self.sync_promotion_drop_pct = v;
+        }
+        if let Some(v) = file.health_max_scrape_age_hours {
+            self.health_max_scrape_age_hours = v;
+        }
+        if let Some(v) = file.request_sla_hours {
+            self.request_sla_hours = v;
+        }
+        if let Some(v) = file.bulk_confirm_threshold {
+            self.bulk_confirm_threshold = v;
+        }
+        if let Some(v) = file.admin_emails {
+            self.admin_emails = v;
+        }
+        if let Some(v) = file.unassigned_alert_days {
+            self.unassigned_alert_days = v;
+        }
+        if let Some(v) = file.unassigned_alert_group {
+            self.unassigned_alert_group = v;
+        }
+        if let Some(v) = file.rain_release_threshold {
+            self.rain_release_threshold = v;
+        }
+        if file.gate_info.is_some() {
+            self.gate_info = file.gate_info;
+        }
+        if file.parking_info.is_some() {
+            self.parking_info = file.parking_info;
+        }
     }
 
     fn apply_env(&mut self) {
         if let Ok(v) = std::env::var("GTM_DB_URL") {
             self.db_url = v;
         }
+        if let Ok(v) = std::env::var("GTM_DB_READ_URL") {
+            self.db_read_url = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_DB_MAX_CONNECTIONS")
+            && let Ok(n) = v.parse()
+        {
+            self.db_max_connections = n;
+        }
+        if let Ok(v) = std::env::var("GTM_DB_ACQUIRE_TIMEOUT_SECS")
+            && let Ok(n) = v.parse()
+        {
+            self.db_acquire_timeout_secs = n;
+        }
+        if let Ok(v) = std::env::var("GTM_DB_STATEMENT_TIMEOUT_SECS")
+            && let Ok(n) = v.parse()
+        {
+            self.db_statement_timeout_secs = n;
+        }
+        if let Ok(v) = std::env::var("GTM_DB_SLOW_QUERY_MS")
+            && let Ok(n) = v.parse()
+        {
+            self.db_slow_query_ms = n;
+        }
+        if let Ok(v) = std::env::var("GTM_DB_LOG_QUERIES") {
+            self.db_log_queries = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("GTM_AUTO_MIGRATE") {
+            self.auto_migrate = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("GTM_SQLITE_WAL") {
+            self.sqlite_wal = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("GTM_SQLITE_BUSY_TIMEOUT_MS")
+            && let Ok(n) = v.parse()
+        {
+            self.sqlite_busy_timeout_ms = n;
+        }
         if let Ok(v) = std::env::var("GTM_PORT")
             && let Ok(p) = v.parse()
         {
             self.port = p;
         }
+        if let Ok(v) = std::env::var("GTM_PUBLIC_URL") {
+            self.public_url = v;
+        }
         if let Ok(v) = std::env::var("GTM_LOG_LEVEL") {
             self.log_level = v;
         }
@@ -129,6 +818,9 @@ impl Config {
         if let Ok(v) = std::env::var("GTM_LOG_JSON") {
             self.log_json = v == "1" || v.eq_ignore_ascii_case("true");
         }
+        if let Ok(v) = std::env::var("GTM_AUTH_MODE") {
+            self.auth_mode = v;
+        }
         if let Ok(v) = std::env::var("AUTH0_DOMAIN") {
             self.auth0_domain = v;
         }
@@ -138,5 +830,328 @@ impl Config {
         if let Ok(v) = std::env::var("AUTH0_AUDIENCE") {
             self.auth0_audience = v;
         }
+        if let Ok(v) = std::env::var("GTM_MAILGUN_DOMAIN") {
+            self.mailgun_domain = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_MAILGUN_API_KEY") {
+            self.mailgun_api_key = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_MAILGUN_SIGNING_KEY") {
+            self.mailgun_signing_key = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_MAIL_FROM") {
+            self.mail_from = v;
+        }
+        if let Ok(v) = std::env::var("GTM_SMTP_HOST") {
+            self.smtp_host = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_SMTP_PORT")
+            && let Ok(p) = v.parse()
+        {
+            self.smtp_port = p;
+        }
+        if let Ok(v) = std::env::var("GTM_SMTP_USERNAME") {
+            self.smtp_username = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_SMTP_PASSWORD") {
+            self.smtp_password = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_TWILIO_ACCOUNT_SID") {
+            self.twilio_account_sid = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_TWILIO_AUTH_TOKEN") {
+            self.twilio_auth_token = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_TWILIO_FROM_NUMBER") {
+            self.twilio_from_number = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_GOOGLE_CLIENT_ID") {
+            self.google_client_id = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_GOOGLE_CLIENT_SECRET") {
+            self.google_client_secret = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_STRIPE_SECRET_KEY") {
+            self.stripe_secret_key = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_STRIPE_WEBHOOK_SECRET") {
+            self.stripe_webhook_secret = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_STRIPE_CURRENCY") {
+            self.stripe_currency = v.to_lowercase();
+        }
+        if let Ok(v) = std::env::var("GTM_TEAM_ID")
+            && let Ok(id) = v.parse()
+        {
+            self.team_id = id;
+        }
+        if let Ok(v) = std::env::var("GTM_TEAM_NAME") {
+            self.team_name = v;
+        }
+        if let Ok(v) = std::env::var("GTM_DISPLAY_TIMEZONE") {
+            self.display_timezone = v;
+        }
+        if let Ok(v) = std::env::var("GTM_VENUE_LATITUDE")
+            && let Ok(l) = v.parse()
+        {
+            self.venue_latitude = l;
+        }
+        if let Ok(v) = std::env::var("GTM_VENUE_LONGITUDE")
+            && let Ok(l) = v.parse()
+        {
+            self.venue_longitude = l;
+        }
+        if let Ok(v) = std::env::var("GTM_ALLOCATION_STRATEGY") {
+            self.allocation_strategy = v;
+        }
+        if let Ok(v) = std::env::var("GTM_TICKETED_GAME_TYPES") {
+            self.ticketed_game_types = Some(
+                v.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+            );
+        }
+        if let Ok(v) = std::env::var("GTM_SCHEMA_DRIFT") {
+            self.schema_drift = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("GTM_SCRAPE_HYDRATE") {
+            self.scrape_hydrate = v
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        if let Ok(v) = std::env::var("GTM_TRANSPARENCY_NAMED") {
+            self.transparency_named = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("GTM_RUN_JOBS") {
+            self.run_jobs = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("GTM_LIVE_SCORES") {
+            self.live_scores = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("GTM_SCRAPE_CRON") {
+            self.scrape_cron = v;
+        }
+        if let Ok(v) = std::env::var("GTM_HTTP_CONNECT_TIMEOUT_SECS")
+            && let Ok(n) = v.parse()
+        {
+            self.http_connect_timeout_secs = n;
+        }
+        if let Ok(v) = std::env::var("GTM_HTTP_TIMEOUT_SECS")
+            && let Ok(n) = v.parse()
+        {
+            self.http_timeout_secs = n;
+        }
+        if let Ok(v) = std::env::var("GTM_HTTP_MAX_ATTEMPTS")
+            && let Ok(n) = v.parse()
+        {
+            self.http_max_attempts = n;
+        }
+        if let Ok(v) = std::env::var("GTM_HTTP_BACKOFF_MS")
+            && let Ok(n) = v.parse()
+        {
+            self.http_backoff_ms = n;
+        }
+        if let Ok(v) = std::env::var("GTM_HTTP_MAX_BACKOFF_MS")
+            && let Ok(n) = v.parse()
+        {
+            self.http_max_backoff_ms = n;
+        }
+        if let Ok(v) = std::env::var("GTM_BREAKER_FAILURES")
+            && let Ok(n) = v.parse()
+        {
+            self.breaker_failures = n;
+        }
+        if let Ok(v) = std::env::var("GTM_BREAKER_COOLDOWN_SECS")
+            && let Ok(n) = v.parse()
+        {
+            self.breaker_cooldown_secs = n;
+        }
+        if let Ok(v) = std::env::var("GTM_IMAGE_CACHE_DIR") {
+            self.image_cache_dir = v;
+        }
+        if let Ok(v) = std::env::var("GTM_IMAGE_CACHE_TTL_HOURS")
+            && let Ok(n) = v.parse()
+        {
+            self.image_cache_ttl_hours = n;
+        }
+        if let Ok(v) = std::env::var("GTM_SYNC_MIN_GAMES")
+            && let Ok(n) = v.parse()
+        {
+            self.sync_min_games = n;
+        }
+        if let Ok(v) = std::env::var("GTM_SYNC_PROMOTION_DROP_PCT")
+            && let Ok(n) = v.parse()
+        {
+            self.sync_promotion_drop_pct = n;
+        }
+        if let Ok(v) = std::env::var("GTM_HEALTH_MAX_SCRAPE_AGE_HOURS")
+            && let Ok(n) = v.parse()
+        {
+            self.health_max_scrape_age_hours = n;
+        }
+        if let Ok(v) = std::env::var("GTM_REQUEST_SLA_HOURS")
+            && let Ok(h) = v.parse()
+        {
+            self.request_sla_hours = h;
+        }
+        if let Ok(v) = std::env::var("GTM_BULK_CONFIRM_THRESHOLD")
+            && let Ok(n) = v.parse()
+        {
+            self.bulk_confirm_threshold = n;
+        }
+        if let Ok(v) = std::env::var("GTM_ADMIN_EMAILS") {
+            self.admin_emails = v
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+        if let Ok(v) = std::env::var("GTM_UNASSIGNED_ALERT_DAYS")
+            && let Ok(d) = v.parse()
+        {
+            self.unassigned_alert_days = d;
+        }
+        if let Ok(v) = std::env::var("GTM_UNASSIGNED_ALERT_GROUP") {
+            self.unassigned_alert_group = v == "1" || v.eq_ignore_ascii_case("true");
+        }
+        if let Ok(v) = std::env::var("GTM_RAIN_RELEASE_THRESHOLD")
+            && let Ok(p) = v.parse()
+        {
+            self.rain_release_threshold = p;
+        }
+        if let Ok(v) = std::env::var("GTM_GATE_INFO") {
+            self.gate_info = Some(v);
+        }
+        if let Ok(v) = std::env::var("GTM_PARKING_INFO") {
+            self.parking_info = Some(v);
+        }
+    }
+}
+
+/// Commented config file written by `gtm config init`, every setting at its
+/// default.
+pub const TEMPLATE: &str = include_str!("config.template.toml");
+
+/// Which layer an effective setting came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File,
+    Env,
+    Cli,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Source::Default => "default",
+            Source::File => "file",
+            Source::Env => "env",
+            Source::Cli => "cli",
+        })
+    }
+}
+
+/// The environment variable that overrides setting `name`.
+pub fn env_var(name: &str) -> Option<&'static str> {
+    ENV_VARS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, var)| *var)
+}
+
+/// Each setting and the environment variable that overrides it.
+const ENV_VARS: &[(&str, &str)] = &[
+    ("db_url", "GTM_DB_URL"),
+    ("db_read_url", "GTM_DB_READ_URL"),
+    ("db_max_connections", "GTM_DB_MAX_CONNECTIONS"),
+    ("db_acquire_timeout_secs", "GTM_DB_ACQUIRE_TIMEOUT_SECS"),
+    ("db_statement_timeout_secs", "GTM_DB_STATEMENT_TIMEOUT_SECS"),
+    ("db_slow_query_ms", "GTM_DB_SLOW_QUERY_MS"),
+    ("db_log_queries", "GTM_DB_LOG_QUERIES"),
+    ("auto_migrate", "GTM_AUTO_MIGRATE"),
+    ("sqlite_wal", "GTM_SQLITE_WAL"),
+    ("sqlite_busy_timeout_ms", "GTM_SQLITE_BUSY_TIMEOUT_MS"),
+    ("port", "GTM_PORT"),
+    ("public_url", "GTM_PUBLIC_URL"),
+    ("log_level", "GTM_LOG_LEVEL"),
+    ("utc", "GTM_UTC"),
+    ("log_json", "GTM_LOG_JSON"),
+    ("auth_mode", "GTM_AUTH_MODE"),
+    ("auth0_domain", "AUTH0_DOMAIN"),
+    ("auth0_client_id", "AUTH0_CLIENT_ID"),
+    ("auth0_audience", "AUTH0_AUDIENCE"),
+    ("mailgun_domain", "GTM_MAILGUN_DOMAIN"),
+    ("mailgun_api_key", "GTM_MAILGUN_API_KEY"),
+    ("mailgun_signing_key", "GTM_MAILGUN_SIGNING_KEY"),
+    ("mail_from", "GTM_MAIL_FROM"),
+    ("smtp_host", "GTM_SMTP_HOST"),
+    ("smtp_port", "GTM_SMTP_PORT"),
+    ("smtp_username", "GTM_SMTP_USERNAME"),
+    ("smtp_password", "GTM_SMTP_PASSWORD"),
+    ("twilio_account_sid", "GTM_TWILIO_ACCOUNT_SID"),
+    ("twilio_auth_token", "GTM_TWILIO_AUTH_TOKEN"),
+    ("twilio_from_number", "GTM_TWILIO_FROM_NUMBER"),
+    ("google_client_id", "GTM_GOOGLE_CLIENT_ID"),
+    ("google_client_secret", "GTM_GOOGLE_CLIENT_SECRET"),
+    ("stripe_secret_key", "GTM_STRIPE_SECRET_KEY"),
+    ("stripe_webhook_secret", "GTM_STRIPE_WEBHOOK_SECRET"),
+    ("stripe_currency", "GTM_STRIPE_CURRENCY"),
+    ("team_id", "GTM_TEAM_ID"),
+    ("team_name", "GTM_TEAM_NAME"),
+    ("display_timezone", "GTM_DISPLAY_TIMEZONE"),
+    ("venue_latitude", "GTM_VENUE_LATITUDE"),
+    ("venue_longitude", "GTM_VENUE_LONGITUDE"),
+    ("allocation_strategy", "GTM_ALLOCATION_STRATEGY"),
+    ("ticketed_game_types", "GTM_TICKETED_GAME_TYPES"),
+    ("schema_drift", "GTM_SCHEMA_DRIFT"),
+    ("scrape_hydrate", "GTM_SCRAPE_HYDRATE"),
+    ("transparency_named", "GTM_TRANSPARENCY_NAMED"),
+    ("run_jobs", "GTM_RUN_JOBS"),
+    ("live_scores", "GTM_LIVE_SCORES"),
+    ("scrape_cron", "GTM_SCRAPE_CRON"),
+    ("http_connect_timeout_secs", "GTM_HTTP_CONNECT_TIMEOUT_SECS"),
+    ("http_timeout_secs", "GTM_HTTP_TIMEOUT_SECS"),
+    ("http_max_attempts", "GTM_HTTP_MAX_ATTEMPTS"),
+    ("http_backoff_ms", "GTM_HTTP_BACKOFF_MS"),
+    ("http_max_backoff_ms", "GTM_HTTP_MAX_BACKOFF_MS"),
+    ("breaker_failures", "GTM_BREAKER_FAILURES"),
+    ("breaker_cooldown_secs", "GTM_BREAKER_COOLDOWN_SECS"),
+    ("image_cache_dir", "GTM_IMAGE_CACHE_DIR"),
+    ("image_cache_ttl_hours", "GTM_IMAGE_CACHE_TTL_HOURS"),
+    ("sync_min_games", "GTM_SYNC_MIN_GAMES"),
+    ("sync_promotion_drop_pct", "GTM_SYNC_PROMOTION_DROP_PCT"),
+    (
+        "health_max_scrape_age_hours",
+        "GTM_HEALTH_MAX_SCRAPE_AGE_HOURS",
+    ),
+    ("request_sla_hours", "GTM_REQUEST_SLA_HOURS"),
+    ("bulk_confirm_threshold", "GTM_BULK_CONFIRM_THRESHOLD"),
+    ("admin_emails", "GTM_ADMIN_EMAILS"),
+    ("unassigned_alert_days", "GTM_UNASSIGNED_ALERT_DAYS"),
+    ("unassigned_alert_group", "GTM_UNASSIGNED_ALERT_GROUP"),
+    ("rain_release_threshold", "GTM_RAIN_RELEASE_THRESHOLD"),
+    ("gate_info", "GTM_GATE_INFO"),
+    ("parking_info", "GTM_PARKING_INFO"),
+];
+
+const REDACTED: &str = "<redacted>";
+const UNSET: &str = "<unset>";
+
+/// `url` with any password in its userinfo replaced by `<redacted>`.
+fn redact_url(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let Some(at) = rest[..authority_end].rfind('@') else {
+        return url.to_string();
+    };
+    match rest[..at].split_once(':') {
+        Some((user, _)) => format!("{scheme}://{user}:{REDACTED}{}", &rest[at..]),
+        None => url.to_string(),
     }
 }
@@ -0,0 +1,37 @@
+use gtm_config::{Config, env_var};
+
+/// Values to try for a setting's variable: one of them differs from any
+/// default and parses as the setting's type.
+const CANDIDATES: &[&str] = &["7", "0", "true", "false", "x@example.com"];
+
+/// `load` reads every setting's environment variable. The names live in a
+/// table apart from the code that parses them, so this catches the two
+/// drifting apart. Alone in its file: it sets process-wide variables.
+#[test]
+fn every_setting_is_read_from_its_env_var() {
+    let value = |name: &str| {
+        Config::load()
+            .redacted()
+            .into_iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v)
+            .unwrap()
+    };
+    for (name, default) in Config::load().redacted() {
+        let var = env_var(name).unwrap_or_else(|| panic!("{name} has no env var"));
+        let previous = std::env::var(var).ok();
+        let changed = CANDIDATES.iter().any(|candidate| {
+            // SAFETY: the only test in this binary, so nothing else reads
+            // the environment meanwhile
+            unsafe { std::env::set_var(var, candidate) };
+            value(name) != default
+        });
+        unsafe {
+            match &previous {
+                Some(v) => std::env::set_var(var, v),
+                None => std::env::remove_var(var),
+            }
+        }
+        assert!(changed, "{var} doesn't change {name}");
+    }
+}
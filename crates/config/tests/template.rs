@@ -0,0 +1,53 @@
+use gtm_config::{Config, TEMPLATE, env_var};
+
+/// The template's settings, uncommented, with the environment variable
+/// noted on the comment line above each.
+fn template_settings() -> Vec<(String, Option<String>)> {
+    let mut settings = Vec::new();
+    let mut note = None;
+    for line in TEMPLATE.lines() {
+        let Some(rest) = line.strip_prefix("# ") else {
+            continue;
+        };
+        if let Some((key, _)) = rest.split_once(" = ") {
+            settings.push((key.to_string(), note.take()));
+        } else {
+            note = rest
+                .rsplit_once('[')
+                .map(|(_, var)| var.trim_end_matches(']').to_string());
+        }
+    }
+    settings
+}
+
+#[test]
+fn template_lists_every_setting_with_its_env_var() {
+    let settings = template_settings();
+    let names: Vec<&str> = settings.iter().map(|(k, _)| k.as_str()).collect();
+    let expected: Vec<&str> = Config::load().redacted().iter().map(|(k, _)| *k).collect();
+    assert_eq!(names, expected);
+    for (key, var) in &settings {
+        let var = var
+            .as_deref()
+            .unwrap_or_else(|| panic!("{key} has no env var"));
+        assert_eq!(
+            var.trim_end_matches(", comma-separated"),
+            env_var(key).unwrap(),
+            "{key}"
+        );
+    }
+}
+
+#[test]
+fn uncommented_template_parses() {
+    let uncommented: String = TEMPLATE
+        .lines()
+        .map(|line| match line.strip_prefix("# ") {
+            Some(rest) if rest.contains(" = ") => rest,
+            _ => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let table: toml::Table = toml::from_str(&uncommented).unwrap();
+    assert_eq!(table.len(), template_settings().len());
+}
@@ -0,0 +1,102 @@
+//! A record of each committed allocation batch.
+//!
+//! When seats are assigned in a batch, by hand or by the allocation engine,
+//! the request the admin sent, the engine's proposal and the assignments
+//! that resulted are kept together as one JSON snapshot, so a later dispute
+//! about what was clicked can be settled from the record rather than memory.
+//! The record is written in the same transaction as the assignments.
+
+use anyhow::Result;
+use gtm_models::{AllocationBatch, GamePk, UserId};
+use sqlx::{AnyConnection, AnyPool};
+
+use crate::compat::{self, Arg};
+use crate::pg;
+
+pub const MANUAL: &str = "manual";
+pub const AUTO: &str = "auto";
+
+const BATCH_COLUMNS: &str =
+    "id, actor_id, kind, game_pk, assigned, CAST(created_at AS TEXT) AS created_at";
+
+/// A batch to record.
+#[derive(Debug)]
+pub struct NewBatch<'a> {
+    pub actor_id: Option<UserId>,
+    pub kind: &'a str,
+    pub game_pk: Option<GamePk>,
+    pub assigned: i64,
+    pub snapshot: &'a serde_json::Value,
+}
+
+/// What an admin asked for and the engine proposed, for
+/// [`crate::commit_allocation_batch`] to record with the assignments'
+/// outcome.
+#[derive(Debug)]
+pub struct Draft<'a> {
+    /// [`MANUAL`] or [`AUTO`].
+    pub kind: &'a str,
+    /// The game allocated; when `None`, the one game every assigned seat is
+    /// for, if there is one.
+    pub game_pk: Option<GamePk>,
+    pub request: serde_json::Value,
+    pub proposal: serde_json::Value,
+}
+
+pub async fn record(pool: &AnyPool, batch: &NewBatch<'_>) -> Result<i64> {
+    record_with(&mut *pool.acquire().await?, batch).await
+}
+
+/// [`record`] on `conn`, in the transaction that committed the batch.
+pub(crate) async fn record_with(conn: &mut AnyConnection, batch: &NewBatch<'_>) -> Result<i64> {
+    compat::insert_returning_id(
+        conn,
+        "INSERT INTO allocation_batches (actor_id, kind, game_pk, assigned, snapshot) \
+         VALUES (?, ?, ?, ?, ?)",
+        &[
+            Arg::OptInt(batch.actor_id.map(i64::from)),
+            Arg::Text(batch.kind),
+            Arg::OptInt(batch.game_pk.map(i64::from)),
+            Arg::Int(batch.assigned),
+            Arg::Text(&batch.snapshot.to_string()),
+        ],
+    )
+    .await
+}
+
+/// Batches newest first, optionally only those for `game_pk`.
+pub async fn list(pool: &AnyPool, game_pk: Option<GamePk>) -> Result<Vec<AllocationBatch>> {
+    let for_game = if game_pk.is_some() {
+        " WHERE game_pk = ?"
+    } else {
+        ""
+    };
+    let sql = pg(&format!(
+        "SELECT {BATCH_COLUMNS} FROM allocation_batches{for_game} ORDER BY id DESC"
+    ));
+    let mut query = sqlx::query_as::<_, AllocationBatch>(&sql);
+    if let Some(game_pk) = game_pk {
+        query = query.bind(game_pk);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+/// A batch and its snapshot.
+pub async fn get(pool: &AnyPool, id: i64) -> Result<Option<(AllocationBatch, serde_json::Value)>> {
+    let sql = pg(&format!(
+        "SELECT {BATCH_COLUMNS} FROM allocation_batches WHERE id = ?"
+    ));
+    let Some(batch) = sqlx::query_as::<_, AllocationBatch>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let snapshot: String =
+        sqlx::query_scalar(&pg("SELECT snapshot FROM allocation_batches WHERE id = ?"))
+            .bind(id)
+            .fetch_one(pool)
+            .await?;
+    Ok(Some((batch, serde_json::from_str(&snapshot)?)))
+}
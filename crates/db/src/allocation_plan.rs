@@ -0,0 +1,177 @@
+//! Allocation plans from a spreadsheet.
+//!
+//! A plan is CSV with the header `game_pk,user_email,seats`, one line per
+//! member and game. [`resolve`] looks up each line's game and member and
+//! picks that many of the game's available seats, side by side where it
+//! can, so the plan can be shown as a dry run; [`assignments`] turns a plan
+//! without problems into the batch [`crate::commit_allocation`] applies in
+//! one transaction.
+
+use anyhow::{Result, bail};
+use gtm_models::{GamePk, GameTicketDetail, TicketId, User, UserId};
+use sqlx::AnyPool;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use crate::import::csv_records;
+use crate::{get_game, get_user_by_email, list_requests_for_game, list_tickets_for_game};
+
+/// One line of a plan, as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanLine {
+    /// Line of the CSV it came from.
+    pub line: usize,
+    pub game_pk: GamePk,
+    pub user_email: String,
+    pub seats: i64,
+}
+
+/// A plan line resolved against the database.
+#[derive(Debug, Clone)]
+pub struct PlannedRow {
+    pub line: PlanLine,
+    pub official_date: Option<String>,
+    pub user: Option<User>,
+    /// Seats picked for the member; empty when `problem` is set.
+    pub tickets: Vec<TicketId>,
+    /// The member's request for the game, approved when the seats go in.
+    pub request_id: Option<i64>,
+    pub problem: Option<String>,
+}
+
+/// Parse a plan. Columns are matched by header name, in any order.
+pub fn parse_csv(text: &str) -> Result<Vec<PlanLine>> {
+    let mut records = csv_records(text)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        bail!("CSV is empty");
+    };
+    let mut columns = HashMap::new();
+    for (i, name) in header.iter().enumerate() {
+        let name = name.trim().to_ascii_lowercase();
+        if !matches!(name.as_str(), "game_pk" | "user_email" | "seats") {
+            bail!("Unknown CSV column `{name}`");
+        }
+        columns.insert(name, i);
+    }
+    for required in ["game_pk", "user_email", "seats"] {
+        if !columns.contains_key(required) {
+            bail!("CSV header is missing the `{required}` column");
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (line, fields) in records {
+        if fields.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let field = |name: &str| {
+            columns
+                .get(name)
+                .and_then(|&i| fields.get(i))
+                .map(|f| f.trim())
+                .unwrap_or_default()
+        };
+        let Ok(game_pk) = field("game_pk").parse::<i64>() else {
+            bail!("Line {line}: bad game_pk `{}`", field("game_pk"));
+        };
+        let seats = match field("seats").parse::<i64>() {
+            Ok(n) if n >= 1 => n,
+            _ => bail!("Line {line}: seats must be a whole number of at least 1"),
+        };
+        if field("user_email").is_empty() {
+            bail!("Line {line}: user_email is required");
+        }
+        lines.push(PlanLine {
+            line,
+            game_pk: GamePk(game_pk),
+            user_email: field("user_email").to_string(),
+            seats,
+        });
+    }
+    if lines.is_empty() {
+        bail!("The plan has no lines");
+    }
+    Ok(lines)
+}
+
+/// Resolve each line in order. Seats picked for earlier lines aren't
+/// offered to later ones; a line that can't be met gets a `problem` and no
+/// seats.
+pub async fn resolve(pool: &AnyPool, lines: &[PlanLine]) -> Result<Vec<PlannedRow>> {
+    let closed: HashSet<String> =
+        sqlx::query_scalar::<_, String>("SELECT season FROM closed_seasons")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+    let mut available: HashMap<GamePk, Vec<GameTicketDetail>> = HashMap::new();
+    let mut requests: HashMap<GamePk, HashMap<UserId, i64>> = HashMap::new();
+    let mut rows = Vec::new();
+    for line in lines {
+        let mut row = PlannedRow {
+            line: line.clone(),
+            official_date: None,
+            user: get_user_by_email(pool, &line.user_email).await?,
+            tickets: Vec::new(),
+            request_id: None,
+            problem: None,
+        };
+        let Some(game) = get_game(pool, line.game_pk).await? else {
+            row.problem = Some("No such game".to_string());
+            rows.push(row);
+            continue;
+        };
+        row.official_date = Some(game.official_date.clone());
+        let Some(user_id) = row.user.as_ref().map(|u| u.id) else {
+            row.problem = Some("No member with that email".to_string());
+            rows.push(row);
+            continue;
+        };
+        if closed.contains(&game.season) {
+            row.problem = Some(format!("The {} season is closed", game.season));
+            rows.push(row);
+            continue;
+        }
+        if let Entry::Vacant(entry) = available.entry(game.game_pk) {
+            let tickets = list_tickets_for_game(pool, game.game_pk)
+                .await?
+                .into_iter()
+                .filter(|t| t.status == "available")
+                .collect();
+            entry.insert(tickets);
+            let by_user = list_requests_for_game(pool, game.game_pk)
+                .await?
+                .into_iter()
+                .filter(|r| r.status != "withdrawn")
+                .map(|r| (r.user_id, r.id))
+                .collect();
+            requests.insert(game.game_pk, by_user);
+        }
+        let left = available.get_mut(&game.game_pk).expect("loaded above");
+        if (left.len() as i64) < line.seats {
+            row.problem = Some(format!("Only {} seat(s) left", left.len()));
+        } else {
+            row.tickets = gtm_alloc::pick_seats(left, line.seats as usize);
+            left.retain(|t| !row.tickets.contains(&t.id));
+            row.request_id = requests[&game.game_pk].get(&user_id).copied();
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// The `(ticket, member, request)` batch for a resolved plan. Fails if any
+/// line has a problem, so a plan is applied whole or not at all.
+pub fn assignments(rows: &[PlannedRow]) -> Result<Vec<(TicketId, UserId, Option<i64>)>> {
+    let problems = rows.iter().filter(|r| r.problem.is_some()).count();
+    if problems > 0 {
+        bail!("{problems} line(s) of the plan have problems; nothing was assigned");
+    }
+    Ok(rows
+        .iter()
+        .flat_map(|r| {
+            let user_id = r.user.as_ref().map(|u| u.id).expect("resolved");
+            r.tickets.iter().map(move |t| (*t, user_id, r.request_id))
+        })
+        .collect())
+}
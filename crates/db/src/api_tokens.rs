@@ -0,0 +1,170 @@
+//! API tokens: long-lived credentials members use from scripts instead of
+//! the Auth0 browser flow.
+//!
+//! Only a SHA-256 of each token is stored, so a token can't be recovered
+//! after it's minted. Revoking keeps the row, for the record of when the
+//! token was last used. Tokens expire, and an admin token only acts as admin
+//! while its member still has the admin role (see [`record_role`]).
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Duration, Utc};
+use gtm_models::{ApiToken, User, UserId};
+use sha2::{Digest, Sha256};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+pub const MEMBER_SCOPE: &str = "member";
+pub const ADMIN_SCOPE: &str = "admin";
+
+/// Characters of the token kept in the clear for listings.
+const PREFIX_LEN: usize = 8;
+
+/// How stale `last_used_at` may get before a use updates it, so a busy
+/// script doesn't write on every request.
+const LAST_USED_RESOLUTION: Duration = Duration::minutes(5);
+
+const TOKEN_SELECT: &str = "SELECT id, user_id, name, prefix, scope, \
+        CAST(created_at AS TEXT) AS created_at, CAST(last_used_at AS TEXT) AS last_used_at, \
+        CAST(revoked_at AS TEXT) AS revoked_at, expires_at \
+     FROM api_tokens";
+
+fn hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Store `token` for `user_id`, good until `expires_at` (RFC 3339 UTC).
+/// The caller generates the token and shows it to the member; only its hash
+/// is kept.
+pub async fn create(
+    pool: &AnyPool,
+    user_id: UserId,
+    name: &str,
+    scope: &str,
+    token: &str,
+    expires_at: &str,
+) -> Result<ApiToken> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Token name can't be empty");
+    }
+    if scope != MEMBER_SCOPE && scope != ADMIN_SCOPE {
+        bail!("Unknown token scope: {scope}");
+    }
+    let token_hash = hash(token);
+    sqlx::query(&pg(
+        "INSERT INTO api_tokens (user_id, name, token_hash, prefix, scope, expires_at) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+    ))
+    .bind(user_id)
+    .bind(name)
+    .bind(&token_hash)
+    .bind(token.chars().take(PREFIX_LEN).collect::<String>())
+    .bind(scope)
+    .bind(expires_at)
+    .execute(pool)
+    .await?;
+    let sql = pg(&format!("{TOKEN_SELECT} WHERE token_hash = ?"));
+    let created = sqlx::query_as::<_, ApiToken>(&sql)
+        .bind(&token_hash)
+        .fetch_one(pool)
+        .await?;
+    Ok(created)
+}
+
+/// A member's tokens, newest first, revoked ones included.
+pub async fn list_for_user(pool: &AnyPool, user_id: UserId) -> Result<Vec<ApiToken>> {
+    let sql = pg(&format!(
+        "{TOKEN_SELECT} WHERE user_id = ? ORDER BY created_at DESC, id DESC"
+    ));
+    let tokens = sqlx::query_as::<_, ApiToken>(&sql)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(tokens)
+}
+
+/// Revoke one of `user_id`'s tokens. Returns false if they have no such
+/// live token.
+pub async fn revoke(pool: &AnyPool, id: i64, user_id: UserId) -> Result<bool> {
+    let result = sqlx::query(&pg("UPDATE api_tokens SET revoked_at = CURRENT_TIMESTAMP \
+         WHERE id = ? AND user_id = ? AND revoked_at IS NULL"))
+    .bind(id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// The member a live, unexpired token belongs to, and the scope it acts
+/// with: [`ADMIN_SCOPE`] only if the token was minted with it and the member
+/// still has the admin role. Records the use, at most every few minutes.
+pub async fn authenticate(
+    pool: &AnyPool,
+    token: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<(User, String)>> {
+    let row: Option<(i64, String, Option<String>, i64)> = sqlx::query_as(&pg(
+        "SELECT t.id, t.scope, CAST(t.last_used_at AS TEXT), u.is_admin \
+         FROM api_tokens t JOIN users u ON u.id = t.user_id \
+         WHERE t.token_hash = ? AND t.revoked_at IS NULL AND t.expires_at > ?",
+    ))
+    .bind(hash(token))
+    .bind(now.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+    .fetch_optional(pool)
+    .await?;
+    let Some((id, scope, last_used_at, is_admin)) = row else {
+        return Ok(None);
+    };
+    let stale = last_used_at
+        .as_deref()
+        .and_then(crate::parse_timestamp)
+        .is_none_or(|used| now - used >= LAST_USED_RESOLUTION);
+    if stale {
+        sqlx::query(&pg(
+            "UPDATE api_tokens SET last_used_at = CURRENT_TIMESTAMP WHERE id = ?",
+        ))
+        .bind(id)
+        .execute(pool)
+        .await?;
+    }
+    let user = sqlx::query_as::<_, User>(&pg(
+        "SELECT u.id, u.auth0_sub, u.email, u.name FROM users u \
+         JOIN api_tokens t ON t.user_id = u.id WHERE t.id = ?",
+    ))
+    .bind(id)
+    .fetch_one(pool)
+    .await?;
+    let scope = if scope == ADMIN_SCOPE && is_admin == 0 {
+        MEMBER_SCOPE.to_string()
+    } else {
+        scope
+    };
+    Ok(Some((user, scope)))
+}
+
+/// Remember whether `user_id` has the admin role, as of a sign-in that
+/// proved it. Admin tokens check this on every use, so a demoted admin's
+/// tokens lose admin access at their next sign-in.
+pub async fn record_role(pool: &AnyPool, user_id: UserId, admin: bool) -> Result<()> {
+    let admin = i64::from(admin);
+    sqlx::query(&pg(
+        "UPDATE users SET is_admin = ? WHERE id = ? AND is_admin <> ?",
+    ))
+    .bind(admin)
+    .bind(user_id)
+    .bind(admin)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Whether `user_id` had the admin role at their last sign-in, as recorded
+/// by [`record_role`].
+pub async fn has_admin_role(pool: &AnyPool, user_id: UserId) -> Result<bool> {
+    let admin: Option<i64> = sqlx::query_scalar(&pg("SELECT is_admin FROM users WHERE id = ?"))
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(admin.unwrap_or(0) != 0)
+}
@@ -0,0 +1,120 @@
+//! Whether members made it to the games they held seats for.
+//!
+//! Once a game has started, a member holding seats for it records whether
+//! they went with [`upsert_own`]; an admin can record or correct anyone's
+//! with [`upsert`]. One row per member and game, the latest answer winning;
+//! each answer is audited.
+//! Games nobody recorded count as attended in
+//! [`crate::reports::member_history`] if the member still held seats when
+//! they were played, so what matters most is recording the no-shows.
+
+use anyhow::{Result, bail};
+use chrono::Utc;
+use gtm_models::{Game, GameAttendance, GamePk, UserId};
+use sqlx::AnyPool;
+
+use crate::{audit, parse_timestamp, pg};
+
+/// Everyone holding seats for `game_pk`, plus anyone with attendance
+/// recorded for it, by name.
+pub async fn for_game(pool: &AnyPool, game_pk: GamePk) -> Result<Vec<GameAttendance>> {
+    let sql = pg("SELECT g.game_pk, u.id AS user_id, u.name AS user_name, \
+            COALESCE(h.seats, 0) AS seats, a.attended, a.recorded_by, \
+            CAST(a.recorded_at AS TEXT) AS recorded_at \
+         FROM games g CROSS JOIN users u \
+         LEFT JOIN ( \
+             SELECT game_pk, assigned_to, COUNT(*) AS seats FROM game_tickets \
+             WHERE status = 'assigned' GROUP BY game_pk, assigned_to \
+         ) h ON h.game_pk = g.game_pk AND h.assigned_to = u.id \
+         LEFT JOIN game_attendance a ON a.game_pk = g.game_pk AND a.user_id = u.id \
+         WHERE g.game_pk = ? AND (h.seats IS NOT NULL OR a.user_id IS NOT NULL) \
+         ORDER BY u.name, u.id");
+    Ok(sqlx::query_as::<_, GameAttendance>(&sql)
+        .bind(game_pk)
+        .fetch_all(pool)
+        .await?)
+}
+
+/// Record whether `user_id` attended `game_pk`, as `recorded_by` says.
+pub async fn upsert(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    user_id: UserId,
+    attended: bool,
+    recorded_by: Option<UserId>,
+) -> Result<()> {
+    let label = |attended: bool| if attended { "attended" } else { "missed" };
+    let mut tx = pool.begin().await?;
+    let before: Option<i64> = sqlx::query_scalar(&pg(
+        "SELECT attended FROM game_attendance WHERE game_pk = ? AND user_id = ?",
+    ))
+    .bind(game_pk)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let after = label(attended);
+    let attended = if attended { 1i64 } else { 0 };
+    let sql = pg(
+        "INSERT INTO game_attendance (game_pk, user_id, attended, recorded_by) \
+         VALUES (?, ?, ?, ?) \
+         ON CONFLICT (game_pk, user_id) DO UPDATE SET \
+             attended = ?, recorded_by = ?, recorded_at = CURRENT_TIMESTAMP",
+    );
+    sqlx::query(&sql)
+        .bind(game_pk)
+        .bind(user_id)
+        .bind(attended)
+        .bind(recorded_by)
+        .bind(attended)
+        .bind(recorded_by)
+        .execute(&mut *tx)
+        .await?;
+    audit::record(
+        &mut *tx,
+        &audit::AuditEvent {
+            actor_id: recorded_by,
+            action: audit::ATTENDANCE,
+            game_pk: Some(game_pk),
+            user_id: Some(user_id),
+            before: before.map(|b| label(b != 0)),
+            after: Some(after),
+            ..Default::default()
+        },
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// A member recording their own attendance. Fails if the game hasn't
+/// started or wasn't played; returns `false` without recording if they
+/// hold no seats for it.
+pub async fn upsert_own(
+    pool: &AnyPool,
+    game: &Game,
+    user_id: UserId,
+    attended: bool,
+) -> Result<bool> {
+    if game.status_detailed.starts_with("Postponed")
+        || game.status_detailed.starts_with("Cancelled")
+    {
+        bail!("This game wasn't played");
+    }
+    let started = game.status_abstract == "Final"
+        || parse_timestamp(&game.game_date).is_some_and(|start| start <= Utc::now());
+    if !started {
+        bail!("Attendance can be recorded once the game has started");
+    }
+    let sql = pg("SELECT COUNT(*) FROM game_tickets \
+         WHERE game_pk = ? AND assigned_to = ? AND status = 'assigned'");
+    let seats: i64 = sqlx::query_scalar(&sql)
+        .bind(game.game_pk)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+    if seats == 0 {
+        return Ok(false);
+    }
+    upsert(pool, game.game_pk, user_id, attended, Some(user_id)).await?;
+    Ok(true)
+}
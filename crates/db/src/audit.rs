@@ -0,0 +1,104 @@
+//! Audit trail for ticket mutations.
+//!
+//! Every assign, revoke, release, transfer and status change, every
+//! attendance record and every imported price, writes one row to `audit_log` in the same transaction
+//! as the change itself, recording who
+//! did it (`actor_id`, `None` for system jobs) and the value before and after.
+
+use anyhow::Result;
+use gtm_models::{AuditEntry, GamePk, TicketId, UserId};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+pub const ASSIGN: &str = "assign";
+pub const REVOKE: &str = "revoke";
+pub const RELEASE: &str = "release";
+pub const TRANSFER: &str = "transfer";
+pub const TICKET_STATUS: &str = "ticket_status";
+pub const REQUEST_STATUS: &str = "request_status";
+/// Before and after are `attended` or `missed`; no before when first
+/// recorded.
+pub const ATTENDANCE: &str = "attendance";
+/// Before and after are the ticket's cost in dollars ("85.00"); no before
+/// when it had none.
+pub const PRICE: &str = "price";
+
+/// One change to record. `game_pk` may be left `None` when a ticket or
+/// request id is given; it is looked up from that row.
+#[derive(Debug, Default)]
+pub struct AuditEvent<'a> {
+    pub actor_id: Option<UserId>,
+    pub action: &'a str,
+    pub game_pk: Option<GamePk>,
+    pub game_ticket_id: Option<TicketId>,
+    pub request_id: Option<i64>,
+    /// The member whose seats or request changed.
+    pub user_id: Option<UserId>,
+    pub before: Option<&'a str>,
+    pub after: Option<&'a str>,
+}
+
+pub async fn record<'e, E>(executor: E, event: &AuditEvent<'_>) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    let sql = pg("INSERT INTO audit_log \
+            (actor_id, action, game_pk, game_ticket_id, request_id, user_id, before_value, after_value) \
+         VALUES (?, ?, COALESCE(?, \
+                    (SELECT game_pk FROM game_tickets WHERE id = ?), \
+                    (SELECT game_pk FROM ticket_requests WHERE id = ?)), \
+                 ?, ?, ?, ?, ?)");
+    sqlx::query(&sql)
+        .bind(event.actor_id)
+        .bind(event.action)
+        .bind(event.game_pk)
+        .bind(event.game_ticket_id)
+        .bind(event.request_id)
+        .bind(event.game_ticket_id)
+        .bind(event.request_id)
+        .bind(event.user_id)
+        .bind(event.before)
+        .bind(event.after)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Audit entries, newest first. `user_id` matches either the member affected
+/// or the actor who made the change.
+pub async fn list(
+    pool: &AnyPool,
+    game_pk: Option<GamePk>,
+    user_id: Option<UserId>,
+) -> Result<Vec<AuditEntry>> {
+    let mut sql = String::from(
+        "SELECT a.id, CAST(a.created_at AS TEXT) AS created_at, a.actor_id, \
+                actor.name AS actor_name, a.action, a.game_pk, a.game_ticket_id, \
+                a.request_id, a.user_id, member.name AS user_name, \
+                s.section, s.row, s.seat, a.before_value, a.after_value \
+         FROM audit_log a \
+         LEFT JOIN users actor ON actor.id = a.actor_id \
+         LEFT JOIN users member ON member.id = a.user_id \
+         LEFT JOIN game_tickets gt ON gt.id = a.game_ticket_id \
+         LEFT JOIN seats s ON s.id = gt.seat_id \
+         WHERE 1 = 1",
+    );
+    if game_pk.is_some() {
+        sql.push_str(" AND a.game_pk = ?");
+    }
+    if user_id.is_some() {
+        sql.push_str(" AND (a.user_id = ? OR a.actor_id = ?)");
+    }
+    sql.push_str(" ORDER BY a.id DESC");
+
+    let sql = pg(&sql);
+    let mut query = sqlx::query_as::<_, AuditEntry>(&sql);
+    if let Some(pk) = game_pk {
+        query = query.bind(pk);
+    }
+    if let Some(uid) = user_id {
+        query = query.bind(uid).bind(uid);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
@@ -0,0 +1,89 @@
+//! The member's month-view availability calendar.
+//!
+//! One query gathers each home game in the month with its seat counts, the
+//! pending demand on it, and the member's own seats, request and tags;
+//! [`month`] then lays the games out over every day of the month so the
+//! calendar can be drawn without further lookups.
+
+use anyhow::{Result, bail};
+use chrono::{Datelike, Months, NaiveDate};
+use gtm_models::{AvailabilityCalendar, CalendarDay, CalendarGame, UserId};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+/// Team `team_id`'s home games in `month` of `season` (a year), day by day,
+/// as `user_id` sees them.
+pub async fn month(
+    pool: &AnyPool,
+    team_id: u32,
+    user_id: UserId,
+    season: &str,
+    month: u32,
+) -> Result<AvailabilityCalendar> {
+    let Ok(year) = season.parse::<i32>() else {
+        bail!("Season must be a year, not `{season}`");
+    };
+    let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) else {
+        bail!("Month must be 1-12");
+    };
+    let next = first + Months::new(1);
+
+    let sql = pg("SELECT \
+            g.game_pk, g.official_date, g.game_date, g.away_team_name AS opponent, \
+            g.status_abstract, g.double_header, g.game_number, \
+            t.total, t.available, t.assigned, \
+            CASE WHEN t.available > COALESCE(r.requested, 0) \
+                 THEN t.available - COALESCE(r.requested, 0) ELSE 0 END AS claimable, \
+            t.mine AS my_seats, \
+            mr.status AS my_request_status, mr.seats_requested AS my_seats_requested, \
+            COALESCE(tag.shortlist, 0) AS shortlist, COALESCE(tag.cant_go, 0) AS cant_go \
+         FROM games g \
+         JOIN ( \
+             SELECT game_pk, \
+                 COUNT(*) AS total, \
+                 SUM(CASE WHEN status = 'available' THEN 1 ELSE 0 END) AS available, \
+                 SUM(CASE WHEN status = 'assigned' THEN 1 ELSE 0 END) AS assigned, \
+                 SUM(CASE WHEN status = 'assigned' AND assigned_to = ? THEN 1 ELSE 0 END) AS mine \
+             FROM game_tickets GROUP BY game_pk \
+         ) t ON t.game_pk = g.game_pk \
+         LEFT JOIN ( \
+             SELECT game_pk, SUM(seats_requested) AS requested \
+             FROM ticket_requests WHERE status = 'pending' GROUP BY game_pk \
+         ) r ON r.game_pk = g.game_pk \
+         LEFT JOIN ticket_requests mr ON mr.game_pk = g.game_pk AND mr.user_id = ? \
+         LEFT JOIN user_game_tags tag ON tag.game_pk = g.game_pk AND tag.user_id = ? \
+         WHERE g.home_team_id = ? AND g.official_date >= ? AND g.official_date < ? \
+         ORDER BY g.official_date, g.game_date, g.game_number");
+    let games = sqlx::query_as::<_, CalendarGame>(&sql)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(user_id)
+        .bind(i64::from(team_id))
+        .bind(first.format("%Y-%m-%d").to_string())
+        .bind(next.format("%Y-%m-%d").to_string())
+        .fetch_all(pool)
+        .await?;
+
+    let mut games = games.into_iter().peekable();
+    let days = first
+        .iter_days()
+        .take_while(|d| *d < next)
+        .map(|day| {
+            let date = day.format("%Y-%m-%d").to_string();
+            let mut on_day = Vec::new();
+            while let Some(game) = games.next_if(|g| g.official_date == date) {
+                on_day.push(game);
+            }
+            CalendarDay {
+                date,
+                games: on_day,
+            }
+        })
+        .collect();
+    Ok(AvailabilityCalendar {
+        season: first.year().to_string(),
+        month,
+        days,
+    })
+}
@@ -0,0 +1,144 @@
+//! Fallbacks for SQL features not every backend supports.
+//!
+//! `INSERT ... RETURNING` needs SQLite 3.35+. [`connect`](crate::connect)
+//! checks the SQLite version once; on older libraries inserts run without
+//! RETURNING and the row is read back by its unique key (or by
+//! `last_insert_rowid()` for plain inserts). Postgres always has RETURNING;
+//! MySQL never does, and reports new ids on the statement result.
+
+use anyhow::{Context, Result};
+use sqlx::any::{AnyArguments, AnyQueryResult, AnyRow};
+use sqlx::{Any, AnyConnection, FromRow};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::dialect::{self, Backend};
+use crate::pg;
+
+static RETURNING_SUPPORTED: AtomicBool = AtomicBool::new(true);
+
+/// Whether statements may use `RETURNING`.
+pub fn returning_supported() -> bool {
+    RETURNING_SUPPORTED.load(Ordering::Relaxed)
+}
+
+/// Override RETURNING detection (set automatically on connect).
+pub fn set_returning_supported(supported: bool) {
+    RETURNING_SUPPORTED.store(supported, Ordering::Relaxed);
+}
+
+/// Whether a `sqlite_version()` string is new enough for RETURNING (3.35.0).
+pub fn sqlite_supports_returning(version: &str) -> bool {
+    let mut parts = version
+        .trim()
+        .split('.')
+        .map(|p| p.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= (3, 35)
+}
+
+/// Whether an INSERT/upsert wrote a row. On MySQL a `DO UPDATE ... WHERE`
+/// that didn't match still counts as a matched row, so
+/// [`dialect::adapt`] marks it by setting the last insert id to -1.
+pub(crate) fn wrote_row(result: &AnyQueryResult) -> bool {
+    result.rows_affected() > 0
+        && !(dialect::backend() == Backend::MySql && result.last_insert_id() == Some(-1))
+}
+
+/// A bind parameter for the helpers below.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Arg<'a> {
+    Int(i64),
+    Text(&'a str),
+    OptInt(Option<i64>),
+    OptText(Option<&'a str>),
+}
+
+type Query<'q> = sqlx::query::Query<'q, Any, AnyArguments<'q>>;
+type QueryAs<'q, T> = sqlx::query::QueryAs<'q, Any, T, AnyArguments<'q>>;
+
+fn bind<'q>(mut query: Query<'q>, args: &[Arg<'q>]) -> Query<'q> {
+    for arg in args {
+        query = match *arg {
+            Arg::Int(v) => query.bind(v),
+            Arg::Text(v) => query.bind(v),
+            Arg::OptInt(v) => query.bind(v),
+            Arg::OptText(v) => query.bind(v),
+        };
+    }
+    query
+}
+
+fn bind_as<'q, T>(mut query: QueryAs<'q, T>, args: &[Arg<'q>]) -> QueryAs<'q, T> {
+    for arg in args {
+        query = match *arg {
+            Arg::Int(v) => query.bind(v),
+            Arg::Text(v) => query.bind(v),
+            Arg::OptInt(v) => query.bind(v),
+            Arg::OptText(v) => query.bind(v),
+        };
+    }
+    query
+}
+
+/// Run an INSERT/upsert and return the written row: `insert_sql` plus
+/// `RETURNING {columns}` where supported, otherwise `insert_sql` followed by
+/// `lookup_sql` (a SELECT of the same columns by unique key). Returns `None`
+/// when the statement wrote nothing, e.g. a `DO UPDATE ... WHERE` that
+/// didn't match.
+pub(crate) async fn insert_returning<'q, T>(
+    conn: &mut AnyConnection,
+    insert_sql: &str,
+    columns: &str,
+    args: &[Arg<'q>],
+    lookup_sql: &str,
+    lookup_args: &[Arg<'q>],
+) -> Result<Option<T>>
+where
+    T: for<'r> FromRow<'r, AnyRow> + Send + Unpin,
+{
+    if returning_supported() {
+        let sql = pg(&format!("{insert_sql} RETURNING {columns}"));
+        let row = bind_as(sqlx::query_as::<_, T>(&sql), args)
+            .fetch_optional(&mut *conn)
+            .await?;
+        return Ok(row);
+    }
+    let sql = pg(insert_sql);
+    let result = bind(sqlx::query(&sql), args).execute(&mut *conn).await?;
+    if !wrote_row(&result) {
+        return Ok(None);
+    }
+    let sql = pg(lookup_sql);
+    let row = bind_as(sqlx::query_as::<_, T>(&sql), lookup_args)
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(row)
+}
+
+/// Run a plain INSERT and return the new row's `id`.
+pub(crate) async fn insert_returning_id<'q>(
+    conn: &mut AnyConnection,
+    insert_sql: &str,
+    args: &[Arg<'q>],
+) -> Result<i64> {
+    if returning_supported() {
+        let sql = pg(&format!("{insert_sql} RETURNING id"));
+        let id = bind_as(sqlx::query_as::<_, (i64,)>(&sql), args)
+            .fetch_one(&mut *conn)
+            .await?;
+        return Ok(id.0);
+    }
+    // last_insert_rowid() is per connection, so read it on the one that inserted
+    let sql = pg(insert_sql);
+    let result = bind(sqlx::query(&sql), args).execute(&mut *conn).await?;
+    if dialect::backend() == Backend::MySql {
+        return result
+            .last_insert_id()
+            .context("MySQL reported no id for the inserted row");
+    }
+    let id = sqlx::query_scalar::<_, i64>("SELECT last_insert_rowid()")
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(id)
+}
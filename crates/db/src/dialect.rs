@@ -0,0 +1,337 @@
+//! SQL dialects of the supported backends.
+//!
+//! Queries are written once, in the dialect SQLite and Postgres share, with
+//! `?` placeholders. [`pg`](crate::pg) passes them through [`adapt`] for
+//! the backend the pool is connected to:
+//!
+//! - Postgres: `?` becomes `$1, $2, ...`.
+//! - MySQL/MariaDB: `ON CONFLICT ... DO NOTHING` becomes `INSERT IGNORE`,
+//!   `ON CONFLICT ... DO UPDATE SET` becomes `ON DUPLICATE KEY UPDATE` (a
+//!   `WHERE` on the update is folded into each assignment), `excluded.col`
+//!   becomes `VALUES(col)`, `CAST(... AS TEXT)` casts to a `CHAR`, `SUM` is
+//!   cast back to an integer, and the reserved words `row` and `system` are
+//!   quoted.
+//!
+//! MySQL applies `SET` assignments left to right, each seeing the ones
+//! before it. Assignments that read a column must therefore come before
+//! the one that writes it; SQLite and Postgres don't mind either order.
+
+use std::sync::OnceLock;
+
+/// A database backend, chosen by the scheme of the database URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Sqlite,
+    Postgres,
+    MySql,
+}
+
+impl Backend {
+    /// The backend for `database_url`, or `None` for an unknown scheme.
+    pub fn from_url(database_url: &str) -> Option<Backend> {
+        let scheme = database_url.split(':').next()?;
+        match scheme {
+            "sqlite" => Some(Backend::Sqlite),
+            "postgres" | "postgresql" => Some(Backend::Postgres),
+            "mysql" | "mariadb" => Some(Backend::MySql),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Sqlite => "sqlite",
+            Backend::Postgres => "postgres",
+            Backend::MySql => "mysql",
+        }
+    }
+}
+
+static BACKEND: OnceLock<Backend> = OnceLock::new();
+
+/// The backend [`connect`](crate::connect) connected to (SQLite before
+/// then).
+pub fn backend() -> Backend {
+    *BACKEND.get().unwrap_or(&Backend::Sqlite)
+}
+
+/// Record the process's backend. The first call wins.
+pub(crate) fn set_backend(backend: Backend) {
+    BACKEND.get_or_init(|| backend);
+}
+
+/// Quote a table or column name for `backend`.
+pub fn quote_ident(name: &str, backend: Backend) -> String {
+    match backend {
+        Backend::MySql => format!("`{}`", name.replace('`', "``")),
+        Backend::Sqlite | Backend::Postgres => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// Day of the week of the `YYYY-MM-DD` date in `column`, as an integer
+/// counted from Sunday (0).
+pub fn day_of_week(column: &str, backend: Backend) -> String {
+    match backend {
+        Backend::Sqlite => format!("CAST(strftime('%w', {column}) AS INTEGER)"),
+        Backend::Postgres => format!("CAST(EXTRACT(DOW FROM CAST({column} AS DATE)) AS INTEGER)"),
+        Backend::MySql => format!("(DAYOFWEEK({column}) - 1)"),
+    }
+}
+
+/// Suffix for a `SELECT` that locks the rows it reads until the
+/// transaction ends. SQLite has no row locks: its first write takes the
+/// database lock, and a transaction that read stale rows fails to write.
+pub fn for_update(backend: Backend) -> &'static str {
+    match backend {
+        Backend::Sqlite => "",
+        Backend::Postgres | Backend::MySql => " FOR UPDATE",
+    }
+}
+
+/// `ESCAPE` clause that makes a backslash the escape character of a
+/// `LIKE` pattern. MySQL reads a backslash in a string literal as an
+/// escape itself, so it needs two.
+pub fn like_escape(backend: Backend) -> &'static str {
+    match backend {
+        Backend::Sqlite | Backend::Postgres => r" ESCAPE '\'",
+        Backend::MySql => r" ESCAPE '\\'",
+    }
+}
+
+/// `text` with `LIKE` wildcards escaped, for use with [`like_escape`].
+pub fn escape_like(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '%' | '_') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Rewrite `sql` from the shared dialect into `backend`'s.
+pub fn adapt(sql: &str, backend: Backend) -> String {
+    match backend {
+        Backend::Sqlite => sql.to_string(),
+        Backend::Postgres => numbered_placeholders(sql),
+        Backend::MySql => {
+            let (masked, literals) = mask_literals(sql);
+            let rewritten = mysql_words(&mysql_upsert(&masked));
+            unmask_literals(&rewritten, &literals)
+        }
+    }
+}
+
+fn numbered_placeholders(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len() + 32);
+    let mut n = 0u32;
+    for ch in sql.chars() {
+        if ch == '?' {
+            n += 1;
+            out.push('$');
+            out.push_str(&n.to_string());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Longest VARCHAR a utf8mb4 `CAST(... AS CHAR(n))` can produce.
+const MAX_CHAR_CAST: u32 = 16383;
+
+/// Stands in for a string literal while the rest of a statement is
+/// rewritten.
+const LITERAL_MARK: char = '\u{1}';
+
+/// Replace every `'...'` literal with a numbered mark, so rewrites never
+/// touch quoted text.
+fn mask_literals(sql: &str) -> (String, Vec<String>) {
+    let mut masked = String::with_capacity(sql.len());
+    let mut literals = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\'' {
+            masked.push(ch);
+            continue;
+        }
+        let mut literal = String::from('\'');
+        while let Some(c) = chars.next() {
+            literal.push(c);
+            if c == '\'' {
+                // '' is an escaped quote inside the literal
+                if chars.peek() == Some(&'\'') {
+                    literal.push(chars.next().unwrap_or('\''));
+                    continue;
+                }
+                break;
+            }
+        }
+        masked.push(LITERAL_MARK);
+        masked.push_str(&literals.len().to_string());
+        masked.push(LITERAL_MARK);
+        literals.push(literal);
+    }
+    (masked, literals)
+}
+
+fn unmask_literals(sql: &str, literals: &[String]) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut parts = sql.split(LITERAL_MARK);
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    while let (Some(index), Some(rest)) = (parts.next(), parts.next()) {
+        if let Some(literal) = index.parse::<usize>().ok().and_then(|i| literals.get(i)) {
+            out.push_str(literal);
+        }
+        out.push_str(rest);
+    }
+    out
+}
+
+/// Byte offset of the first `needle` outside parentheses.
+fn find_top_level(sql: &str, needle: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    for (i, ch) in sql.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth == 0 && sql[i..].starts_with(needle) => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split on commas outside parentheses.
+fn split_top_level(sql: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, ch) in sql.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&sql[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&sql[start..]);
+    parts
+}
+
+/// Turn an `ON CONFLICT` clause into `INSERT IGNORE` or
+/// `ON DUPLICATE KEY UPDATE`.
+fn mysql_upsert(sql: &str) -> String {
+    let Some(at) = find_top_level(sql, " ON CONFLICT") else {
+        return sql.to_string();
+    };
+    let head = &sql[..at];
+    let tail = &sql[at..];
+    if let Some(rest) = tail
+        .find(" DO NOTHING")
+        .map(|i| &tail[i + " DO NOTHING".len()..])
+    {
+        let head = head.replacen("INSERT INTO", "INSERT IGNORE INTO", 1);
+        return format!("{head}{rest}");
+    }
+    let Some(set_at) = tail.find(" DO UPDATE SET ") else {
+        return sql.to_string();
+    };
+    let update = &tail[set_at + " DO UPDATE SET ".len()..];
+    let (assignments, condition) = match find_top_level(update, " WHERE ") {
+        Some(w) => (&update[..w], Some(update[w + " WHERE ".len()..].trim())),
+        None => (update, None),
+    };
+    let assignments: Vec<String> = split_top_level(assignments)
+        .into_iter()
+        .enumerate()
+        .map(|(i, assignment)| {
+            let Some(condition) = condition else {
+                return assignment.trim().to_string();
+            };
+            let (column, value) = assignment.split_once('=').unwrap_or((assignment, ""));
+            let (column, value) = (column.trim(), value.trim());
+            // MySQL can't skip the update, so a row that fails the condition
+            // is written back unchanged. The first assignment also sets
+            // LAST_INSERT_ID to -1, which compat::wrote_row reads as
+            // "nothing written".
+            let unchanged = if i == 0 {
+                format!("IF(LAST_INSERT_ID(-1), {column}, {column})")
+            } else {
+                column.to_string()
+            };
+            format!("{column} = IF({condition}, {value}, {unchanged})")
+        })
+        .collect();
+    format!("{head} ON DUPLICATE KEY UPDATE {}", assignments.join(", "))
+}
+
+/// Word-level rewrites: reserved names, `excluded.`, casts and `SUM`.
+fn mysql_words(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len() + 32);
+    let mut rest = sql;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len());
+        let word = &rest[..end];
+        rest = &rest[end..];
+        // Literal marks are digits between \u{1}s; leave them alone
+        if out.ends_with(LITERAL_MARK) {
+            out.push_str(word);
+            continue;
+        }
+        match word {
+            "row" | "system" => out.push_str(&quote_ident(word, Backend::MySql)),
+            // A bare CHAR cast can come back as LONGTEXT, which the Any driver
+            // can't decode; a bounded one is a VARCHAR
+            "TEXT" if out.ends_with("AS ") && rest.starts_with(')') => {
+                out.push_str(&format!("CHAR({MAX_CHAR_CAST})"))
+            }
+            "SUM" if rest.starts_with('(') => {
+                let close = matching_paren(rest).unwrap_or(rest.len() - 1);
+                let inner = mysql_words(&rest[1..close]);
+                out.push_str(&format!("CAST(SUM({inner}) AS SIGNED)"));
+                rest = &rest[close + 1..];
+            }
+            _ => match word.strip_prefix("excluded.") {
+                Some(column) => {
+                    let column = match column {
+                        "row" | "system" => quote_ident(column, Backend::MySql),
+                        _ => column.to_string(),
+                    };
+                    out.push_str(&format!("VALUES({column})"));
+                }
+                None => out.push_str(word),
+            },
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Index of the `)` closing the `(` that `sql` starts with.
+fn matching_paren(sql: &str) -> Option<usize> {
+    let mut depth = 0i32;
+    for (i, ch) in sql.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
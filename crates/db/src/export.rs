@@ -0,0 +1,258 @@
+//! Season exports for spreadsheets.
+//!
+//! Rows are read one game at a time so a caller can stream the output
+//! instead of holding the whole season. Two CSV layouts are rendered here:
+//! one line per ticket, and an allocation grid with one line per game and
+//! one column per seat. The renewal report and the attendance grid are
+//! rendered here too.
+
+use anyhow::Result;
+use gtm_models::{AttendanceGrid, GamePk, RenewalReport, TicketExportRow};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+/// Home games with tickets in `season`, in date order.
+pub async fn export_games(pool: &AnyPool, season: &str) -> Result<Vec<GamePk>> {
+    let sql = pg("SELECT g.game_pk FROM games g \
+         WHERE g.season = ? AND EXISTS (SELECT 1 FROM game_tickets gt WHERE gt.game_pk = g.game_pk) \
+         ORDER BY g.game_date, g.game_pk");
+    let games = sqlx::query_scalar::<_, GamePk>(&sql)
+        .bind(season)
+        .fetch_all(pool)
+        .await?;
+    Ok(games)
+}
+
+/// Every ticket for one game, by seat.
+pub async fn ticket_rows(pool: &AnyPool, game_pk: GamePk) -> Result<Vec<TicketExportRow>> {
+    let sql = pg(
+        "SELECT gt.id AS ticket_id, g.game_pk, g.official_date, g.game_date, g.away_team_name, \
+                s.section, s.row, s.seat, gt.status, \
+                u.name AS assignee_name, u.email AS assignee_email, \
+                COALESCE(gt.cost, s.face_value) AS cost, gt.notes \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         JOIN seats s ON s.id = gt.seat_id \
+         LEFT JOIN users u ON u.id = gt.assigned_to \
+         WHERE gt.game_pk = ? \
+         ORDER BY s.section, s.row, s.seat",
+    );
+    let rows = sqlx::query_as::<_, TicketExportRow>(&sql)
+        .bind(game_pk)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+/// Seat labels (`section/row/seat`) in grid column order.
+pub async fn seat_labels(pool: &AnyPool) -> Result<Vec<String>> {
+    let seats = crate::list_seats(pool).await?;
+    Ok(seats
+        .iter()
+        .map(|s| seat_label(&s.section, &s.row, &s.seat))
+        .collect())
+}
+
+fn seat_label(section: &str, row: &str, seat: &str) -> String {
+    format!("{section}/{row}/{seat}")
+}
+
+/// Quote a CSV field if it holds a comma, quote or line break. A field a
+/// spreadsheet would run as a formula (starting `=`, `+`, `-`, `@`, tab or
+/// carriage return) is prefixed with `'` so it shows as text; numbers like
+/// `-12.50` are left alone.
+pub fn csv_field(value: &str) -> String {
+    let value =
+        if value.starts_with(['=', '+', '-', '@', '\t', '\r']) && value.parse::<f64>().is_err() {
+            format!("'{value}")
+        } else {
+            value.to_string()
+        };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn csv_line<I, S>(fields: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut line = fields
+        .into_iter()
+        .map(|f| csv_field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+pub fn tickets_csv_header() -> String {
+    csv_line([
+        "date",
+        "first_pitch",
+        "opponent",
+        "game_pk",
+        "section",
+        "row",
+        "seat",
+        "status",
+        "assignee",
+        "assignee_email",
+        "cost",
+        "notes",
+    ])
+}
+
+/// One line per ticket.
+pub fn tickets_csv(rows: &[TicketExportRow]) -> String {
+    rows.iter()
+        .map(|r| {
+            csv_line([
+                r.official_date.clone(),
+                r.game_date.clone(),
+                r.away_team_name.clone(),
+                r.game_pk.to_string(),
+                r.section.clone(),
+                r.row.clone(),
+                r.seat.clone(),
+                r.status.clone(),
+                r.assignee_name.clone().unwrap_or_default(),
+                r.assignee_email.clone().unwrap_or_default(),
+                r.cost.map(format_cents).unwrap_or_default(),
+                r.notes.clone().unwrap_or_default(),
+            ])
+        })
+        .collect()
+}
+
+pub fn allocation_csv_header(seats: &[String]) -> String {
+    csv_line(
+        ["date", "opponent", "game_pk"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(seats.iter().cloned()),
+    )
+}
+
+/// One line for a game's tickets: each seat's column holds the assignee's
+/// name, or the ticket status when nobody holds it.
+pub fn allocation_csv(seats: &[String], rows: &[TicketExportRow]) -> String {
+    let Some(first) = rows.first() else {
+        return String::new();
+    };
+    let cells = seats.iter().map(|label| {
+        rows.iter()
+            .find(|r| seat_label(&r.section, &r.row, &r.seat) == *label)
+            .map(|r| r.assignee_name.clone().unwrap_or_else(|| r.status.clone()))
+            .unwrap_or_default()
+    });
+    csv_line(
+        [
+            first.official_date.clone(),
+            first.away_team_name.clone(),
+            first.game_pk.to_string(),
+        ]
+        .into_iter()
+        .chain(cells),
+    )
+}
+
+/// The renewal report as CSV: a seat table, then a member table, then the
+/// season totals, separated by blank lines.
+pub fn renewal_csv(report: &RenewalReport) -> String {
+    let mut out = csv_line([
+        "seat",
+        "face_value",
+        "games",
+        "used",
+        "resold",
+        "unused",
+        "utilization_pct",
+        "cost",
+        "unused_loss",
+        "resale_total",
+    ]);
+    for s in &report.seats {
+        out.push_str(&csv_line([
+            seat_label(&s.section, &s.row, &s.seat),
+            s.face_value.map(format_cents).unwrap_or_default(),
+            s.games.to_string(),
+            s.used.to_string(),
+            s.resold.to_string(),
+            s.unused.to_string(),
+            format!("{:.1}", s.utilization_pct),
+            format_cents(s.cost),
+            format_cents(s.unused_loss),
+            format_cents(s.resale_total),
+        ]));
+    }
+    out.push_str("\r\n");
+    out.push_str(&csv_line([
+        "member",
+        "games",
+        "seats",
+        "face_value",
+        "spend",
+    ]));
+    for m in &report.members {
+        out.push_str(&csv_line([
+            m.user_name.clone(),
+            m.games.to_string(),
+            m.seats.to_string(),
+            format_cents(m.face_value),
+            format_cents(m.spend),
+        ]));
+    }
+    out.push_str("\r\n");
+    for (label, value) in [
+        ("season", report.season.clone()),
+        ("as_of", report.as_of.clone()),
+        ("total_cost", format_cents(report.total_cost)),
+        ("unused_loss", format_cents(report.unused_loss)),
+        ("resold", report.resold.to_string()),
+        ("resale_total", format_cents(report.resale_total)),
+        (
+            "resale_recovery_pct",
+            report
+                .resale_recovery_pct
+                .map(|p| format!("{p:.1}"))
+                .unwrap_or_default(),
+        ),
+    ] {
+        out.push_str(&csv_line([label.to_string(), value]));
+    }
+    out
+}
+
+/// The attendance grid as CSV: a line per game, a column per member. Cells
+/// are seat counts, blank when the member isn't going, so the grid reads
+/// cleanly when pasted into a chat or a spreadsheet.
+pub fn attendance_grid_csv(grid: &AttendanceGrid) -> String {
+    let mut out = csv_line(
+        ["date", "opponent"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(grid.members.iter().map(|m| m.name.clone()))
+            .chain(["open".to_string()]),
+    );
+    let count = |n: i64| if n == 0 { String::new() } else { n.to_string() };
+    for row in &grid.games {
+        out.push_str(&csv_line(
+            [row.official_date.clone(), row.opponent.clone()]
+                .into_iter()
+                .chain(row.seats.iter().map(|&n| count(n)))
+                .chain([count(row.unassigned)]),
+        ));
+    }
+    out
+}
+
+pub(crate) fn format_cents(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{sign}{}.{:02}", cents / 100, cents % 100)
+}
@@ -0,0 +1,183 @@
+//! IDs GTM records have in other systems, for two-way sync scripts.
+//!
+//! A ref ties one game, ticket or user to one ID in a named system (e.g.
+//! `notion`). Each record has at most one ID per system, and each ID in a
+//! system names at most one record of a kind.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use gtm_models::{EntityKind, ExternalRef, WithRefs};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+const REF_SELECT: &str = "SELECT id, entity_type, entity_id, system, external_id, \
+        CAST(created_at AS TEXT) AS created_at \
+     FROM external_refs";
+
+/// Filters for [`list`]; all optional.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct RefFilter {
+    pub entity_type: Option<EntityKind>,
+    pub entity_id: Option<i64>,
+    pub system: Option<String>,
+    pub external_id: Option<String>,
+}
+
+pub async fn list(pool: &AnyPool, filter: &RefFilter) -> Result<Vec<ExternalRef>> {
+    let mut conditions = Vec::new();
+    if filter.entity_type.is_some() {
+        conditions.push("entity_type = ?");
+    }
+    if filter.entity_id.is_some() {
+        conditions.push("entity_id = ?");
+    }
+    if filter.system.is_some() {
+        conditions.push("system = ?");
+    }
+    if filter.external_id.is_some() {
+        conditions.push("external_id = ?");
+    }
+    let mut sql = REF_SELECT.to_string();
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    sql.push_str(" ORDER BY entity_type, entity_id, system");
+    let sql = pg(&sql);
+    let mut query = sqlx::query_as::<_, ExternalRef>(&sql);
+    if let Some(kind) = filter.entity_type {
+        query = query.bind(kind.as_str());
+    }
+    if let Some(id) = filter.entity_id {
+        query = query.bind(id);
+    }
+    if let Some(system) = &filter.system {
+        query = query.bind(system.trim());
+    }
+    if let Some(external_id) = &filter.external_id {
+        query = query.bind(external_id.trim());
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+pub async fn get(pool: &AnyPool, id: i64) -> Result<Option<ExternalRef>> {
+    let sql = pg(&format!("{REF_SELECT} WHERE id = ?"));
+    let found = sqlx::query_as::<_, ExternalRef>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(found)
+}
+
+fn validate(system: &str, external_id: &str) -> Result<()> {
+    if system.trim().is_empty() {
+        bail!("System can't be empty");
+    }
+    if external_id.trim().is_empty() {
+        bail!("External id can't be empty");
+    }
+    Ok(())
+}
+
+/// Whether the game, ticket or user exists.
+async fn entity_exists(pool: &AnyPool, kind: EntityKind, entity_id: i64) -> Result<bool> {
+    let sql = match kind {
+        EntityKind::Game => "SELECT COUNT(*) FROM games WHERE game_pk = ?",
+        EntityKind::Ticket => "SELECT COUNT(*) FROM game_tickets WHERE id = ?",
+        EntityKind::User => "SELECT COUNT(*) FROM users WHERE id = ?",
+    };
+    let n: i64 = sqlx::query_scalar(&pg(sql))
+        .bind(entity_id)
+        .fetch_one(pool)
+        .await?;
+    Ok(n > 0)
+}
+
+/// Add a ref. `None` if the entity doesn't exist; a unique violation if it
+/// already has an ID in `system`, or the ID is taken.
+pub async fn create(
+    pool: &AnyPool,
+    kind: EntityKind,
+    entity_id: i64,
+    system: &str,
+    external_id: &str,
+) -> Result<Option<ExternalRef>> {
+    validate(system, external_id)?;
+    if !entity_exists(pool, kind, entity_id).await? {
+        return Ok(None);
+    }
+    let (system, external_id) = (system.trim(), external_id.trim());
+    sqlx::query(&pg(
+        "INSERT INTO external_refs (entity_type, entity_id, system, external_id) \
+         VALUES (?, ?, ?, ?)",
+    ))
+    .bind(kind.as_str())
+    .bind(entity_id)
+    .bind(system)
+    .bind(external_id)
+    .execute(pool)
+    .await?;
+    let sql = pg(&format!(
+        "{REF_SELECT} WHERE entity_type = ? AND entity_id = ? AND system = ?"
+    ));
+    let created = sqlx::query_as::<_, ExternalRef>(&sql)
+        .bind(kind.as_str())
+        .bind(entity_id)
+        .bind(system)
+        .fetch_one(pool)
+        .await?;
+    Ok(Some(created))
+}
+
+/// Point a ref at a new external ID. `None` if there's no such ref.
+pub async fn update(pool: &AnyPool, id: i64, external_id: &str) -> Result<Option<ExternalRef>> {
+    if external_id.trim().is_empty() {
+        bail!("External id can't be empty");
+    }
+    let result = sqlx::query(&pg("UPDATE external_refs SET external_id = ? WHERE id = ?"))
+        .bind(external_id.trim())
+        .bind(id)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+    get(pool, id).await
+}
+
+pub async fn delete(pool: &AnyPool, id: i64) -> Result<bool> {
+    let result = sqlx::query(&pg("DELETE FROM external_refs WHERE id = ?"))
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Pair each of `entities` with its refs; `id_of` gives the entity's id.
+/// Loads every ref of `kind`, which stays small next to the entities.
+pub async fn attach<T>(
+    pool: &AnyPool,
+    kind: EntityKind,
+    entities: Vec<T>,
+    id_of: impl Fn(&T) -> i64,
+) -> Result<Vec<WithRefs<T>>> {
+    let mut refs: HashMap<i64, Vec<ExternalRef>> = HashMap::new();
+    if !entities.is_empty() {
+        let filter = RefFilter {
+            entity_type: Some(kind),
+            ..Default::default()
+        };
+        for r in list(pool, &filter).await? {
+            refs.entry(r.entity_id).or_default().push(r);
+        }
+    }
+    Ok(entities
+        .into_iter()
+        .map(|entity| WithRefs {
+            external_refs: refs.remove(&id_of(&entity)).unwrap_or_default(),
+            entity,
+        })
+        .collect())
+}
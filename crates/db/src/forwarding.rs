@@ -0,0 +1,145 @@
+//! Getting the actual tickets to the members holding them.
+//!
+//! Assigning a seat doesn't put the ticket in anyone's hands: an admin
+//! still forwards it from the account's MLB Ballpark app, or emails or
+//! prints it. [`record`] notes that a ticket went to its current holder,
+//! when and how, along with MLB's id for it; [`checklist`] lists a game's
+//! assigned tickets with what was recorded, so admins can see which are
+//! still to send. A ticket reassigned after it was forwarded needs
+//! forwarding again, since the new holder doesn't have it.
+
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use gtm_models::{GamePk, TicketForward, TicketId, UserId};
+use sqlx::AnyPool;
+
+use crate::{pg, seasons};
+
+/// Ways a ticket gets forwarded.
+pub const METHODS: [&str; 4] = ["ballpark_app", "email", "print", "other"];
+
+/// How and when a ticket was forwarded, checked by [`Forward::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forward {
+    /// YYYY-MM-DD.
+    pub forwarded_on: String,
+    pub method: String,
+    /// MLB's id or barcode for the ticket; `None` keeps any already stored.
+    pub external_ticket_id: Option<String>,
+}
+
+impl Forward {
+    pub fn new(forwarded_on: &str, method: &str, external_ticket_id: Option<&str>) -> Result<Self> {
+        if NaiveDate::parse_from_str(forwarded_on, "%Y-%m-%d").is_err() {
+            bail!("Bad date `{forwarded_on}`; use YYYY-MM-DD");
+        }
+        let method = method.trim().to_ascii_lowercase();
+        if !METHODS.contains(&method.as_str()) {
+            bail!(
+                "Unknown forwarding method `{method}`; use one of {}",
+                METHODS.join(", ")
+            );
+        }
+        Ok(Forward {
+            forwarded_on: forwarded_on.to_string(),
+            method,
+            external_ticket_id: external_ticket_id
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+const CHECKLIST_COLUMNS: &str = "gt.id AS ticket_id, gt.game_pk, s.section, s.row, s.seat, \
+     gt.assigned_to, u.name AS user_name, gt.external_ticket_id, gt.forwarded_to, \
+     gt.forwarded_on, gt.forward_method, gt.forwarded_by, \
+     CASE WHEN gt.forwarded_to = gt.assigned_to THEN 1 ELSE 0 END AS forwarded";
+
+/// A game's assigned tickets, those still to forward first, then by seat.
+pub async fn checklist(pool: &AnyPool, game_pk: GamePk) -> Result<Vec<TicketForward>> {
+    let sql = pg(&format!(
+        "SELECT {CHECKLIST_COLUMNS} \
+         FROM game_tickets gt \
+         JOIN seats s ON s.id = gt.seat_id \
+         JOIN users u ON u.id = gt.assigned_to \
+         WHERE gt.game_pk = ? AND gt.status = 'assigned' \
+         ORDER BY forwarded, s.section, s.row, s.seat"
+    ));
+    Ok(sqlx::query_as::<_, TicketForward>(&sql)
+        .bind(game_pk)
+        .fetch_all(pool)
+        .await?)
+}
+
+/// Record that `ticket_id` was forwarded to whoever holds it now. Returns
+/// false if there's no such ticket or nobody holds it.
+pub async fn record(
+    pool: &AnyPool,
+    ticket_id: TicketId,
+    forward: &Forward,
+    recorded_by: Option<UserId>,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_ticket_open(&mut *tx, ticket_id).await?;
+    let sql = pg(
+        "UPDATE game_tickets SET forwarded_to = assigned_to, forwarded_on = ?, \
+            forward_method = ?, forwarded_by = ?, \
+            external_ticket_id = COALESCE(?, external_ticket_id), \
+            updated_at = CURRENT_TIMESTAMP \
+         WHERE id = ? AND status = 'assigned' AND assigned_to IS NOT NULL",
+    );
+    let result = sqlx::query(&sql)
+        .bind(&forward.forwarded_on)
+        .bind(&forward.method)
+        .bind(recorded_by)
+        .bind(forward.external_ticket_id.as_deref())
+        .bind(ticket_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Record every ticket for `game_pk` still to forward as forwarded, for
+/// when an admin sends them all at once. The external ids are left alone.
+/// Returns how many were recorded.
+pub async fn record_game(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    forward: &Forward,
+    recorded_by: Option<UserId>,
+) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_game_open(&mut *tx, game_pk).await?;
+    let sql = pg(
+        "UPDATE game_tickets SET forwarded_to = assigned_to, forwarded_on = ?, \
+            forward_method = ?, forwarded_by = ?, updated_at = CURRENT_TIMESTAMP \
+         WHERE game_pk = ? AND status = 'assigned' AND assigned_to IS NOT NULL \
+           AND (forwarded_to IS NULL OR forwarded_to <> assigned_to)",
+    );
+    let result = sqlx::query(&sql)
+        .bind(&forward.forwarded_on)
+        .bind(&forward.method)
+        .bind(recorded_by)
+        .bind(game_pk)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+/// Undo [`record`], keeping the external id. Returns false if there's no
+/// such ticket.
+pub async fn clear(pool: &AnyPool, ticket_id: TicketId) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_ticket_open(&mut *tx, ticket_id).await?;
+    let sql = pg(
+        "UPDATE game_tickets SET forwarded_to = NULL, forwarded_on = NULL, \
+            forward_method = NULL, forwarded_by = NULL, updated_at = CURRENT_TIMESTAMP \
+         WHERE id = ?",
+    );
+    let result = sqlx::query(&sql).bind(ticket_id).execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
@@ -0,0 +1,283 @@
+//! Allocation reports as they stood at an earlier time.
+//!
+//! The audit log records every ticket assignment, revocation, release,
+//! transfer and status change, and every request status change, with the
+//! value before it. A [`Snapshot`] starts from a season's tickets and
+//! requests as they are now and undoes, newest first, each audited change
+//! made at or after the cutoff; tickets and requests created after the
+//! cutoff are left out. Changes the log doesn't record (a member withdrawing
+//! or resizing a request) appear as they are now.
+//!
+//! Because of those unrecorded changes a past snapshot can still change, so
+//! [`SnapshotCache`] only keeps one for [`CACHE_TTL`] of repeated report
+//! views.
+
+use anyhow::Result;
+use gtm_models::{AllocationSummaryRow, FairnessRow, GamePk, TicketId, UserId};
+use sqlx::AnyPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::timing::timed;
+use crate::{audit, pg};
+
+/// Snapshots kept by a [`SnapshotCache`] before it starts over.
+const MAX_CACHED: usize = 32;
+
+/// How long a cached snapshot is served before it is rebuilt.
+pub const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Action, ticket, request, member and value before, of one audit entry.
+type AuditRow = (
+    String,
+    Option<TicketId>,
+    Option<i64>,
+    Option<UserId>,
+    Option<String>,
+);
+
+/// A ticket and who held it at the cutoff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TicketState {
+    pub id: TicketId,
+    pub game_pk: GamePk,
+    pub holder: Option<UserId>,
+}
+
+/// A request and its status at the cutoff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestState {
+    pub id: i64,
+    pub user_id: UserId,
+    pub game_pk: GamePk,
+    pub seats_requested: i64,
+    pub status: String,
+}
+
+/// A season's tickets and requests at `cutoff`, a UTC timestamp
+/// (`YYYY-MM-DD HH:MM:SS`).
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub season: String,
+    pub cutoff: String,
+    pub tickets: Vec<TicketState>,
+    pub requests: Vec<RequestState>,
+}
+
+/// Rebuild `season`'s tickets and requests as they were just before
+/// `cutoff`.
+pub async fn snapshot(pool: &AnyPool, season: &str, cutoff: &str) -> Result<Snapshot> {
+    let sql = pg(
+        "SELECT gt.id, gt.game_pk, gt.assigned_to FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         WHERE g.season = ? AND CAST(gt.created_at AS TEXT) < ? \
+         ORDER BY gt.id",
+    );
+    let tickets: Vec<(TicketId, GamePk, Option<UserId>)> = timed(
+        "history tickets",
+        sqlx::query_as(&sql)
+            .bind(season)
+            .bind(cutoff)
+            .fetch_all(pool),
+    )
+    .await?;
+    let sql = pg(
+        "SELECT r.id, r.user_id, r.game_pk, r.seats_requested, r.status FROM ticket_requests r \
+         JOIN games g ON g.game_pk = r.game_pk \
+         WHERE g.season = ? AND CAST(r.created_at AS TEXT) < ? \
+         ORDER BY r.id",
+    );
+    let requests: Vec<(i64, UserId, GamePk, i64, String)> = timed(
+        "history requests",
+        sqlx::query_as(&sql)
+            .bind(season)
+            .bind(cutoff)
+            .fetch_all(pool),
+    )
+    .await?;
+    let sql = pg(
+        "SELECT a.action, a.game_ticket_id, a.request_id, a.user_id, a.before_value \
+         FROM audit_log a JOIN games g ON g.game_pk = a.game_pk \
+         WHERE g.season = ? AND CAST(a.created_at AS TEXT) >= ? \
+         ORDER BY a.id DESC",
+    );
+    let events: Vec<AuditRow> = timed(
+        "history events",
+        sqlx::query_as(&sql)
+            .bind(season)
+            .bind(cutoff)
+            .fetch_all(pool),
+    )
+    .await?;
+
+    let mut holders: HashMap<TicketId, Option<UserId>> = tickets
+        .iter()
+        .map(|(id, _, holder)| (*id, *holder))
+        .collect();
+    let mut statuses: HashMap<i64, String> = requests
+        .iter()
+        .map(|(id, _, _, _, status)| (*id, status.clone()))
+        .collect();
+    for (action, ticket_id, request_id, user_id, before) in events {
+        if action == audit::REQUEST_STATUS {
+            if let (Some(status), Some(before)) =
+                (request_id.and_then(|id| statuses.get_mut(&id)), before)
+            {
+                *status = before;
+            }
+            continue;
+        }
+        let Some(holder) = ticket_id.and_then(|id| holders.get_mut(&id)) else {
+            continue;
+        };
+        *holder = match action.as_str() {
+            audit::ASSIGN => None,
+            audit::REVOKE | audit::RELEASE => user_id,
+            audit::TRANSFER => before.and_then(|b| b.parse().ok()).map(UserId),
+            audit::TICKET_STATUS if before.as_deref() == Some("assigned") => user_id,
+            audit::TICKET_STATUS => None,
+            _ => *holder,
+        };
+    }
+
+    Ok(Snapshot {
+        season: season.to_string(),
+        cutoff: cutoff.to_string(),
+        tickets: tickets
+            .into_iter()
+            .map(|(id, game_pk, _)| TicketState {
+                id,
+                game_pk,
+                holder: holders[&id],
+            })
+            .collect(),
+        requests: requests
+            .into_iter()
+            .map(|(id, user_id, game_pk, seats_requested, _)| RequestState {
+                id,
+                user_id,
+                game_pk,
+                seats_requested,
+                status: statuses.remove(&id).unwrap_or_default(),
+            })
+            .collect(),
+    })
+}
+
+/// [`crate::allocation_summary`] for the snapshot's season at its cutoff.
+pub async fn allocation_summary(
+    pool: &AnyPool,
+    snapshot: &Snapshot,
+    team_id: u32,
+) -> Result<Vec<AllocationSummaryRow>> {
+    let sql = pg(
+        "SELECT game_pk, official_date, away_team_name, double_header, game_number FROM games \
+         WHERE home_team_id = ? AND season = ? ORDER BY game_date, game_number",
+    );
+    let games: Vec<(GamePk, String, String, String, i64)> = sqlx::query_as(&sql)
+        .bind(i64::from(team_id))
+        .bind(&snapshot.season)
+        .fetch_all(pool)
+        .await?;
+
+    let mut seats: HashMap<GamePk, (i64, i64)> = HashMap::new();
+    for t in &snapshot.tickets {
+        let (total, assigned) = seats.entry(t.game_pk).or_default();
+        *total += 1;
+        *assigned += i64::from(t.holder.is_some());
+    }
+    let mut requested: HashMap<GamePk, i64> = HashMap::new();
+    for r in snapshot.requests.iter().filter(|r| r.status == "pending") {
+        *requested.entry(r.game_pk).or_default() += r.seats_requested;
+    }
+    Ok(games
+        .into_iter()
+        .filter_map(
+            |(game_pk, official_date, away_team_name, double_header, game_number)| {
+                let (total_seats, assigned) = *seats.get(&game_pk)?;
+                let available = total_seats - assigned;
+                let total_requested = requested.get(&game_pk).copied().unwrap_or(0);
+                Some(AllocationSummaryRow {
+                    game_pk,
+                    official_date,
+                    away_team_name,
+                    double_header,
+                    game_number,
+                    total_seats,
+                    assigned,
+                    available,
+                    total_requested,
+                    oversubscribed: i64::from(total_requested > available),
+                })
+            },
+        )
+        .collect())
+}
+
+/// [`crate::fairness_report`] at the snapshot's cutoff: seats held then, and
+/// rain prompts answered by then.
+pub async fn fairness_report(pool: &AnyPool, snapshot: &Snapshot) -> Result<Vec<FairnessRow>> {
+    let sql = pg("SELECT rp.user_id, COUNT(*) FROM release_prompts rp \
+         JOIN games g ON g.game_pk = rp.game_pk \
+         WHERE rp.response = 'release' AND g.season = ? \
+           AND CAST(rp.responded_at AS TEXT) < ? \
+         GROUP BY rp.user_id");
+    let releases: HashMap<UserId, i64> = sqlx::query_as::<_, (UserId, i64)>(&sql)
+        .bind(&snapshot.season)
+        .bind(&snapshot.cutoff)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+    let mut held: HashMap<UserId, i64> = HashMap::new();
+    for holder in snapshot.tickets.iter().filter_map(|t| t.holder) {
+        *held.entry(holder).or_default() += 1;
+    }
+    Ok(crate::list_users(pool)
+        .await?
+        .into_iter()
+        .map(|u| FairnessRow {
+            seats_assigned: held.get(&u.id).copied().unwrap_or(0),
+            weather_releases: releases.get(&u.id).copied().unwrap_or(0),
+            user_id: u.id,
+            user_name: u.name,
+        })
+        .collect())
+}
+
+/// A snapshot and when it was built.
+type CachedSnapshot = (Instant, Arc<Snapshot>);
+
+/// Snapshots by season and cutoff, each kept for [`CACHE_TTL`]. Only
+/// cutoffs already past are kept, since later ones change with every write.
+#[derive(Debug, Default)]
+pub struct SnapshotCache {
+    entries: Mutex<HashMap<(String, String), CachedSnapshot>>,
+}
+
+impl SnapshotCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn get(&self, pool: &AnyPool, season: &str, cutoff: &str) -> Result<Arc<Snapshot>> {
+        let at = chrono::NaiveDateTime::parse_from_str(cutoff, "%Y-%m-%d %H:%M:%S")
+            .map_err(|e| anyhow::anyhow!("Bad snapshot cutoff {cutoff:?}: {e}"))?;
+        let key = (season.to_string(), cutoff.to_string());
+        if let Some((loaded_at, hit)) = self.entries.lock().unwrap().get(&key)
+            && loaded_at.elapsed() < CACHE_TTL
+        {
+            return Ok(hit.clone());
+        }
+        let loaded = Arc::new(snapshot(pool, season, cutoff).await?);
+        if at <= chrono::Utc::now().naive_utc() {
+            let mut entries = self.entries.lock().unwrap();
+            if entries.len() >= MAX_CACHED {
+                entries.clear();
+            }
+            entries.insert(key, (Instant::now(), loaded.clone()));
+        }
+        Ok(loaded)
+    }
+}
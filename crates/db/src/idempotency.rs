@@ -0,0 +1,125 @@
+//! `Idempotency-Key` results for mutations that mustn't run twice.
+//!
+//! A client retrying a request (or a double-clicked button) sends the same
+//! key again. The first request [`claim`]s the key, does its work and
+//! [`complete`]s the key with its response; later ones get that response
+//! back instead of running again. Keys belong to a member and an endpoint,
+//! and are forgotten after [`KEY_TTL_HOURS`].
+
+use anyhow::{Result, bail};
+use gtm_models::UserId;
+use sha2::{Digest, Sha256};
+use sqlx::AnyPool;
+
+use crate::{compat, pg};
+
+/// How long a key's response is kept for replay.
+pub const KEY_TTL_HOURS: i64 = 24;
+
+/// Longest key accepted.
+pub const MAX_KEY_LEN: usize = 255;
+
+/// What to do with a request carrying an idempotency key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Claim {
+    /// First use of the key: do the work, then [`complete`] or [`abandon`].
+    New,
+    /// The key's earlier request finished with this (JSON) response.
+    Replay(String),
+    /// The key's earlier request is still running.
+    InProgress,
+    /// The key was used for a different request body.
+    Mismatch,
+}
+
+fn hash(request: &str) -> String {
+    hex::encode(Sha256::digest(request.as_bytes()))
+}
+
+/// Claim `key` for `user_id`'s call to `endpoint` with the body `request`.
+pub async fn claim(
+    pool: &AnyPool,
+    user_id: UserId,
+    endpoint: &str,
+    key: &str,
+    request: &str,
+) -> Result<Claim> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        bail!("Idempotency-Key must be 1-{MAX_KEY_LEN} characters");
+    }
+    let expired = (chrono::Utc::now() - chrono::Duration::hours(KEY_TTL_HOURS))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    sqlx::query(&pg(
+        "DELETE FROM idempotency_keys WHERE CAST(created_at AS TEXT) < ?",
+    ))
+    .bind(&expired)
+    .execute(pool)
+    .await?;
+
+    let request_hash = hash(request);
+    let sql = pg(
+        "INSERT INTO idempotency_keys (user_id, endpoint, idempotency_key, request_hash) \
+         VALUES (?, ?, ?, ?) ON CONFLICT (user_id, endpoint, idempotency_key) DO NOTHING",
+    );
+    let inserted = sqlx::query(&sql)
+        .bind(user_id)
+        .bind(endpoint)
+        .bind(key)
+        .bind(&request_hash)
+        .execute(pool)
+        .await?;
+    if compat::wrote_row(&inserted) {
+        return Ok(Claim::New);
+    }
+
+    let sql = pg("SELECT request_hash, response FROM idempotency_keys \
+         WHERE user_id = ? AND endpoint = ? AND idempotency_key = ?");
+    let (stored_hash, response): (String, Option<String>) = sqlx::query_as(&sql)
+        .bind(user_id)
+        .bind(endpoint)
+        .bind(key)
+        .fetch_one(pool)
+        .await?;
+    Ok(if stored_hash != request_hash {
+        Claim::Mismatch
+    } else {
+        match response {
+            Some(response) => Claim::Replay(response),
+            None => Claim::InProgress,
+        }
+    })
+}
+
+/// Store the response of a claimed key's request, for replay.
+pub async fn complete(
+    pool: &AnyPool,
+    user_id: UserId,
+    endpoint: &str,
+    key: &str,
+    response: &str,
+) -> Result<()> {
+    let sql = pg("UPDATE idempotency_keys SET response = ? \
+         WHERE user_id = ? AND endpoint = ? AND idempotency_key = ?");
+    sqlx::query(&sql)
+        .bind(response)
+        .bind(user_id)
+        .bind(endpoint)
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Give up a claimed key whose request failed, so a retry runs afresh.
+pub async fn abandon(pool: &AnyPool, user_id: UserId, endpoint: &str, key: &str) -> Result<()> {
+    let sql = pg("DELETE FROM idempotency_keys \
+         WHERE user_id = ? AND endpoint = ? AND idempotency_key = ? AND response IS NULL");
+    sqlx::query(&sql)
+        .bind(user_id)
+        .bind(endpoint)
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
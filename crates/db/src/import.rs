@@ -0,0 +1,244 @@
+//! Bulk seat import.
+//!
+//! Seats come from a CSV file (header `section,row,seat` plus optional
+//! `notes` and `face_value` columns, face value in dollars) or a JSON array
+//! of [`SeatImportRow`]. The whole import is checked first, then inserted
+//! in one transaction: either every seat is added or none is.
+
+use anyhow::{Result, bail};
+use gtm_models::{GameType, Seat, SeatImport, SeatImportRow};
+use sqlx::AnyPool;
+use std::collections::HashMap;
+
+use crate::{OWN_ADMISSION, compat, game_type_placeholders, pg};
+
+/// Most seats accepted in one import.
+pub const MAX_IMPORT_SEATS: usize = 500;
+
+/// Parse CSV text into import rows. Columns are matched by header name, in
+/// any order; unknown columns are an error so typos aren't silently dropped.
+pub fn parse_seats_csv(text: &str) -> Result<Vec<SeatImportRow>> {
+    let mut records = csv_records(text)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        bail!("CSV is empty");
+    };
+    let mut columns = HashMap::new();
+    for (i, name) in header.iter().enumerate() {
+        let name = name.trim().to_ascii_lowercase();
+        if !matches!(
+            name.as_str(),
+            "section" | "row" | "seat" | "notes" | "face_value"
+        ) {
+            bail!("Unknown CSV column `{name}`");
+        }
+        columns.insert(name, i);
+    }
+    for required in ["section", "row", "seat"] {
+        if !columns.contains_key(required) {
+            bail!("CSV header is missing the `{required}` column");
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (line, fields) in records {
+        if fields.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let field = |name: &str| {
+            columns
+                .get(name)
+                .and_then(|&i| fields.get(i))
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+        };
+        let face_value = match field("face_value") {
+            Some(v) => Some(
+                parse_dollars(&v)
+                    .ok_or_else(|| anyhow::anyhow!("Line {line}: bad face value `{v}`"))?,
+            ),
+            None => None,
+        };
+        rows.push(SeatImportRow {
+            section: field("section").unwrap_or_default(),
+            row: field("row").unwrap_or_default(),
+            seat: field("seat").unwrap_or_default(),
+            notes: field("notes"),
+            face_value,
+        });
+    }
+    Ok(rows)
+}
+
+/// Check an import before touching the database: every seat has a section,
+/// row and number, and no seat is listed twice.
+pub fn validate(rows: &[SeatImportRow]) -> Result<()> {
+    if rows.is_empty() {
+        bail!("No seats to import");
+    }
+    if rows.len() > MAX_IMPORT_SEATS {
+        bail!("Maximum {MAX_IMPORT_SEATS} seats per import");
+    }
+    let mut problems = Vec::new();
+    let mut seen: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    for (i, r) in rows.iter().enumerate() {
+        let entry = i + 1;
+        if r.section.trim().is_empty() || r.row.trim().is_empty() || r.seat.trim().is_empty() {
+            problems.push(format!("Entry {entry}: section, row and seat are required"));
+            continue;
+        }
+        if r.face_value.is_some_and(|v| v < 0) {
+            problems.push(format!("Entry {entry}: face value can't be negative"));
+        }
+        if let Some(first) = seen.insert((&r.section, &r.row, &r.seat), entry) {
+            problems.push(format!(
+                "Entry {entry}: {} is already listed at entry {first}",
+                label(r)
+            ));
+        }
+    }
+    if !problems.is_empty() {
+        bail!("{}", problems.join("; "));
+    }
+    Ok(())
+}
+
+/// Validate and insert `rows`, then generate their game tickets for every
+/// team `team_id` home game of the `ticketed` types in one statement. Fails
+/// without adding anything if any seat already exists; soft-deleted seats
+/// are restored.
+pub async fn import_seats(
+    pool: &AnyPool,
+    rows: &[SeatImportRow],
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<SeatImport> {
+    validate(rows)?;
+    let mut tx = pool.begin().await?;
+
+    let insert = pg(
+        "INSERT INTO seats (section, row, seat, notes, face_value) VALUES (?, ?, ?, ?, ?) \
+         ON CONFLICT(section, row, seat) DO UPDATE SET \
+            notes = excluded.notes, \
+            face_value = excluded.face_value, \
+            updated_at = CURRENT_TIMESTAMP, \
+            deleted_at = NULL \
+         WHERE seats.deleted_at IS NOT NULL",
+    );
+    let mut existing = Vec::new();
+    for r in rows {
+        let result = sqlx::query(&insert)
+            .bind(&r.section)
+            .bind(&r.row)
+            .bind(&r.seat)
+            .bind(r.notes.as_deref())
+            .bind(r.face_value)
+            .execute(&mut *tx)
+            .await?;
+        if !compat::wrote_row(&result) {
+            existing.push(label(r));
+        }
+    }
+    if !existing.is_empty() {
+        bail!("Seats already exist: {}", existing.join(", "));
+    }
+
+    let select = pg(
+        "SELECT id, section, row, seat, notes, face_value FROM seats \
+         WHERE section = ? AND row = ? AND seat = ?",
+    );
+    let mut seats = Vec::with_capacity(rows.len());
+    for r in rows {
+        let seat = sqlx::query_as::<_, Seat>(&select)
+            .bind(&r.section)
+            .bind(&r.row)
+            .bind(&r.seat)
+            .fetch_one(&mut *tx)
+            .await?;
+        seats.push(seat);
+    }
+
+    let tickets = if ticketed.is_empty() {
+        0
+    } else {
+        let sql = pg(&format!(
+            "INSERT INTO game_tickets (game_pk, seat_id, status) \
+             SELECT g.game_pk, s.id, 'available' \
+             FROM games g CROSS JOIN seats s \
+             WHERE g.home_team_id = ? AND g.game_type IN ({}) AND {OWN_ADMISSION} \
+               AND s.id IN ({}) \
+             ON CONFLICT DO NOTHING",
+            game_type_placeholders(ticketed),
+            vec!["?"; seats.len()].join(", ")
+        ));
+        let mut query = sqlx::query(&sql).bind(i64::from(team_id));
+        for t in ticketed {
+            query = query.bind(t.code());
+        }
+        for s in &seats {
+            query = query.bind(s.id);
+        }
+        query.execute(&mut *tx).await?.rows_affected()
+    };
+
+    tx.commit().await?;
+    Ok(SeatImport { seats, tickets })
+}
+
+fn label(r: &SeatImportRow) -> String {
+    format!("Section {} Row {} Seat {}", r.section, r.row, r.seat)
+}
+
+/// "42.50", "$42.5" or "42" as cents.
+pub(crate) fn parse_dollars(value: &str) -> Option<i64> {
+    let value = value.trim().trim_start_matches('$');
+    let (whole, frac) = value.split_once('.').unwrap_or((value, ""));
+    if frac.len() > 2 || !frac.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let whole: i64 = whole.parse().ok()?;
+    let frac: i64 = format!("{frac:0<2}").parse().ok()?;
+    Some(whole * 100 + frac)
+}
+
+/// Split CSV text into records, each with the line it starts on. Handles
+/// quoted fields with embedded commas, doubled quotes and line breaks.
+pub(crate) fn csv_records(text: &str) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                fields.push(std::mem::take(&mut field));
+                records.push((start, std::mem::take(&mut fields)));
+                line += 1;
+                start = line;
+            }
+            ('\n', true) => {
+                field.push(c);
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        bail!("Line {start}: unterminated quoted field");
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    Ok(records)
+}
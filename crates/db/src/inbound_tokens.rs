@@ -0,0 +1,36 @@
+//! Mailgun webhook tokens already acted on.
+//!
+//! Every signed Mailgun delivery carries a random token. A delivery whose
+//! signature checks out is only applied if [`claim`] hasn't seen its token
+//! before, so a captured request can't be replayed inside the signature's
+//! freshness window. Tokens are forgotten after [`TOKEN_TTL_MINUTES`], well
+//! after the signature itself stops being accepted.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::AnyPool;
+
+use crate::{compat, pg};
+
+/// How long a seen token is remembered.
+pub const TOKEN_TTL_MINUTES: i64 = 60;
+
+/// Record `token` as used at `now`. Returns false if it was already used.
+pub async fn claim(pool: &AnyPool, token: &str, now: DateTime<Utc>) -> Result<bool> {
+    let expired = (now - chrono::Duration::minutes(TOKEN_TTL_MINUTES))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    sqlx::query(&pg("DELETE FROM inbound_tokens WHERE seen_at < ?"))
+        .bind(&expired)
+        .execute(pool)
+        .await?;
+
+    let sql = pg("INSERT INTO inbound_tokens (token, seen_at) VALUES (?, ?) \
+         ON CONFLICT (token) DO NOTHING");
+    let inserted = sqlx::query(&sql)
+        .bind(token)
+        .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+        .execute(pool)
+        .await?;
+    Ok(compat::wrote_row(&inserted))
+}
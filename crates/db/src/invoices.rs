@@ -0,0 +1,408 @@
+//! Invoices generated from the cost split, and payments against them.
+//!
+//! Generating a period bills each member their [`cost_split`] shares for
+//! that month, one line per ticket. Lines are a snapshot: later changes to
+//! costs or split rules don't touch an existing invoice, but an invoice with
+//! no payments can be deleted and generated again. A payment is recorded
+//! against specific lines, or spread over the oldest unpaid lines first.
+//!
+//! [`cost_split`]: crate::ledger::cost_split
+
+use anyhow::{Context, Result, bail};
+use gtm_models::{Invoice, InvoiceDetail, InvoiceLine, InvoicePayment, UserId};
+use sqlx::{Any, AnyPool, Transaction};
+
+use crate::{dialect, pg};
+
+/// Row counts from [`generate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InvoiceRun {
+    pub created: usize,
+    /// Members who already had an invoice for the period.
+    pub existing: usize,
+}
+
+/// A payment to record with [`record_payment`].
+#[derive(Debug)]
+pub struct NewPayment<'a> {
+    /// Cents.
+    pub amount: i64,
+    pub method: Option<&'a str>,
+    pub note: Option<&'a str>,
+    /// `None` for payments made online.
+    pub recorded_by: Option<UserId>,
+    /// Lines to pay, oldest first; empty means any unpaid line.
+    pub line_ids: &'a [i64],
+}
+
+const INVOICE_SELECT: &str = "SELECT i.id, i.user_id, u.name AS user_name, i.period, i.status, \
+        i.total, COALESCE(SUM(p.amount), 0) AS paid, CAST(i.created_at AS TEXT) AS created_at, \
+        i.payment_url \
+     FROM invoices i \
+     JOIN users u ON u.id = i.user_id \
+     LEFT JOIN invoice_payments p ON p.invoice_id = i.id";
+
+const INVOICE_GROUP: &str =
+    " GROUP BY i.id, i.user_id, u.name, i.period, i.status, i.total, i.created_at, i.payment_url";
+
+/// Bill every member with a share in `period` (YYYY-MM) who doesn't have an
+/// invoice for it yet.
+pub async fn generate(pool: &AnyPool, period: &str) -> Result<InvoiceRun> {
+    if chrono::NaiveDate::parse_from_str(&format!("{period}-01"), "%Y-%m-%d").is_err() {
+        bail!("Period must be YYYY-MM, got {period:?}");
+    }
+    let splits = crate::ledger::cost_split(pool, &period[..4], None).await?;
+    let Some(split) = splits.into_iter().find(|p| p.period == period) else {
+        return Ok(InvoiceRun::default());
+    };
+
+    let mut run = InvoiceRun::default();
+    let mut tx = pool.begin().await?;
+    for share in split.members.into_iter().filter(|m| m.amount > 0) {
+        let inserted = sqlx::query(&pg(
+            "INSERT INTO invoices (user_id, period, total) VALUES (?, ?, ?) \
+             ON CONFLICT (user_id, period) DO NOTHING",
+        ))
+        .bind(share.user_id)
+        .bind(period)
+        .bind(share.amount)
+        .execute(&mut *tx)
+        .await?;
+        if inserted.rows_affected() == 0 {
+            run.existing += 1;
+            continue;
+        }
+        let invoice_id: i64 = sqlx::query_scalar(&pg(
+            "SELECT id FROM invoices WHERE user_id = ? AND period = ?",
+        ))
+        .bind(share.user_id)
+        .bind(period)
+        .fetch_one(&mut *tx)
+        .await?;
+        for line in &share.lines {
+            sqlx::query(&pg("INSERT INTO invoice_lines \
+                    (invoice_id, ticket_id, game_pk, official_date, away_team_name, \
+                     seat_id, basis, amount) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)"))
+            .bind(invoice_id)
+            .bind(line.ticket_id)
+            .bind(line.game_pk)
+            .bind(&line.official_date)
+            .bind(&line.away_team_name)
+            .bind(line.seat_id)
+            .bind(&line.basis)
+            .bind(line.amount)
+            .execute(&mut *tx)
+            .await?;
+        }
+        run.created += 1;
+    }
+    tx.commit().await?;
+    Ok(run)
+}
+
+/// Invoices, newest period first, optionally for one period or member.
+pub async fn list(
+    pool: &AnyPool,
+    period: Option<&str>,
+    user_id: Option<UserId>,
+) -> Result<Vec<Invoice>> {
+    let mut conds = Vec::new();
+    if period.is_some() {
+        conds.push("i.period = ?");
+    }
+    if user_id.is_some() {
+        conds.push("i.user_id = ?");
+    }
+    let where_sql = if conds.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conds.join(" AND "))
+    };
+    let sql = pg(&format!(
+        "{INVOICE_SELECT}{where_sql}{INVOICE_GROUP} ORDER BY i.period DESC, u.name"
+    ));
+    let mut query = sqlx::query_as::<_, Invoice>(&sql);
+    if let Some(period) = period {
+        query = query.bind(period);
+    }
+    if let Some(user_id) = user_id {
+        query = query.bind(user_id);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+pub async fn get(pool: &AnyPool, id: i64) -> Result<Option<Invoice>> {
+    let sql = pg(&format!("{INVOICE_SELECT} WHERE i.id = ?{INVOICE_GROUP}"));
+    let invoice = sqlx::query_as::<_, Invoice>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(invoice)
+}
+
+/// An invoice with its lines (in date order) and payments.
+pub async fn detail(pool: &AnyPool, id: i64) -> Result<Option<InvoiceDetail>> {
+    let Some(invoice) = get(pool, id).await? else {
+        return Ok(None);
+    };
+    let lines = lines(pool, id).await?;
+    let payments = sqlx::query_as::<_, InvoicePayment>(&pg(
+        "SELECT id, invoice_id, line_id, amount, method, note, recorded_by, \
+            CAST(created_at AS TEXT) AS created_at \
+         FROM invoice_payments WHERE invoice_id = ? ORDER BY id",
+    ))
+    .bind(id)
+    .fetch_all(pool)
+    .await?;
+    Ok(Some(InvoiceDetail {
+        invoice,
+        lines,
+        payments,
+    }))
+}
+
+/// A member's invoices in full, newest period first.
+pub async fn for_user(pool: &AnyPool, user_id: UserId) -> Result<Vec<InvoiceDetail>> {
+    let mut out = Vec::new();
+    for invoice in list(pool, None, Some(user_id)).await? {
+        if let Some(detail) = detail(pool, invoice.id).await? {
+            out.push(detail);
+        }
+    }
+    Ok(out)
+}
+
+async fn lines<'e, E>(executor: E, invoice_id: i64) -> Result<Vec<InvoiceLine>>
+where
+    E: sqlx::Executor<'e, Database = Any>,
+{
+    let sql = pg(
+        "SELECT l.id, l.invoice_id, l.ticket_id, l.game_pk, l.official_date, \
+            l.away_team_name, l.seat_id, l.basis, l.amount, COALESCE(SUM(p.amount), 0) AS paid \
+         FROM invoice_lines l \
+         LEFT JOIN invoice_payments p ON p.line_id = l.id \
+         WHERE l.invoice_id = ? \
+         GROUP BY l.id, l.invoice_id, l.ticket_id, l.game_pk, l.official_date, \
+            l.away_team_name, l.seat_id, l.basis, l.amount \
+         ORDER BY l.official_date, l.id",
+    );
+    let lines = sqlx::query_as::<_, InvoiceLine>(&sql)
+        .bind(invoice_id)
+        .fetch_all(executor)
+        .await?;
+    Ok(lines)
+}
+
+/// Delete an invoice nothing has been paid on, so the period can be
+/// generated again. Returns false if it doesn't exist or has payments.
+pub async fn delete_unpaid(pool: &AnyPool, id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let paid: i64 = sqlx::query_scalar(&pg(
+        "SELECT COUNT(*) FROM invoice_payments WHERE invoice_id = ?",
+    ))
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if paid > 0 {
+        return Ok(false);
+    }
+    sqlx::query(&pg("DELETE FROM invoice_lines WHERE invoice_id = ?"))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    let deleted = sqlx::query(&pg("DELETE FROM invoices WHERE id = ?"))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(deleted.rows_affected() > 0)
+}
+
+/// Record a payment on an invoice, split over the chosen lines (or every
+/// line) oldest first, and update the invoice's status. Fails if it's more
+/// than those lines still owe. Returns `None` if the invoice doesn't exist.
+pub async fn record_payment(
+    pool: &AnyPool,
+    invoice_id: i64,
+    payment: &NewPayment<'_>,
+) -> Result<Option<InvoiceDetail>> {
+    let mut tx = pool.begin().await?;
+    if !pay(&mut tx, invoice_id, payment).await? {
+        return Ok(None);
+    }
+    tx.commit().await?;
+    detail(pool, invoice_id).await
+}
+
+/// [`record_payment`] within `tx`. Returns false if the invoice doesn't
+/// exist.
+async fn pay(
+    tx: &mut Transaction<'_, Any>,
+    invoice_id: i64,
+    payment: &NewPayment<'_>,
+) -> Result<bool> {
+    if payment.amount <= 0 {
+        bail!("Payment amount must be positive");
+    }
+    let Some(total) = lock(tx, invoice_id).await? else {
+        return Ok(false);
+    };
+    let lines = lines(&mut **tx, invoice_id).await?;
+    if let Some(id) = payment
+        .line_ids
+        .iter()
+        .find(|id| !lines.iter().any(|l| l.id == **id))
+    {
+        bail!("Line {id} is not on invoice {invoice_id}");
+    }
+    let targets: Vec<&InvoiceLine> = lines
+        .iter()
+        .filter(|l| payment.line_ids.is_empty() || payment.line_ids.contains(&l.id))
+        .filter(|l| l.amount > l.paid)
+        .collect();
+    let owed: i64 = targets.iter().map(|l| l.amount - l.paid).sum();
+    if payment.amount > owed {
+        bail!(
+            "Payment of {} cents is more than the {owed} cents outstanding",
+            payment.amount
+        );
+    }
+
+    let mut left = payment.amount;
+    for line in targets {
+        if left == 0 {
+            break;
+        }
+        let amount = left.min(line.amount - line.paid);
+        sqlx::query(&pg("INSERT INTO invoice_payments \
+                (invoice_id, line_id, amount, method, note, recorded_by) \
+             VALUES (?, ?, ?, ?, ?, ?)"))
+        .bind(invoice_id)
+        .bind(line.id)
+        .bind(amount)
+        .bind(payment.method)
+        .bind(payment.note)
+        .bind(payment.recorded_by)
+        .execute(&mut **tx)
+        .await?;
+        left -= amount;
+    }
+    let paid: i64 = lines.iter().map(|l| l.paid).sum();
+    let status = if paid + payment.amount >= total {
+        "paid"
+    } else {
+        "partial"
+    };
+    sqlx::query(&pg("UPDATE invoices SET status = ? WHERE id = ?"))
+        .bind(status)
+        .bind(invoice_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(true)
+}
+
+/// Lock an invoice's row for the rest of `tx`, so payments on it are
+/// checked against what's owed one at a time. Returns its total, or `None`
+/// if it doesn't exist.
+async fn lock(tx: &mut Transaction<'_, Any>, invoice_id: i64) -> Result<Option<i64>> {
+    let sql = pg(&format!(
+        "SELECT total FROM invoices WHERE id = ?{}",
+        dialect::for_update(dialect::backend())
+    ));
+    Ok(sqlx::query_scalar(&sql)
+        .bind(invoice_id)
+        .fetch_optional(&mut **tx)
+        .await?)
+}
+
+/// Remember the checkout session created to pay an invoice online.
+pub async fn set_checkout(pool: &AnyPool, id: i64, session_id: &str, url: &str) -> Result<()> {
+    sqlx::query(&pg(
+        "UPDATE invoices SET checkout_session_id = ?, payment_url = ? WHERE id = ?",
+    ))
+    .bind(session_id)
+    .bind(url)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Method recorded on payments made through online checkout.
+pub const ONLINE_METHOD: &str = "stripe";
+
+/// A completed online checkout, as recorded by [`record_online_payment`].
+#[derive(Debug, Clone)]
+pub struct OnlinePayment {
+    pub invoice: InvoiceDetail,
+    /// Cents paid beyond what the invoice owed, to refund.
+    pub overpaid: i64,
+    /// The session was recorded before: this is a webhook retry.
+    pub duplicate: bool,
+}
+
+/// Record a completed online checkout against an invoice, at most what's
+/// still owed; anything more is kept as [`OnlinePayment::overpaid`]. Each
+/// session is recorded once, however often Stripe delivers it. Returns
+/// `None` if the invoice doesn't exist.
+pub async fn record_online_payment(
+    pool: &AnyPool,
+    invoice_id: i64,
+    session_id: &str,
+    amount: i64,
+) -> Result<Option<OnlinePayment>> {
+    let mut tx = pool.begin().await?;
+    if lock(&mut tx, invoice_id).await?.is_none() {
+        return Ok(None);
+    }
+    let owed: Option<i64> = sqlx::query_scalar(&pg(
+        "SELECT i.total - COALESCE(SUM(p.amount), 0) FROM invoices i \
+         LEFT JOIN invoice_payments p ON p.invoice_id = i.id \
+         WHERE i.id = ? GROUP BY i.id, i.total",
+    ))
+    .bind(invoice_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(owed) = owed else {
+        return Ok(None);
+    };
+    let applied = amount.min(owed).max(0);
+    let inserted = sqlx::query(&pg(
+        "INSERT INTO stripe_checkouts (session_id, invoice_id, amount, applied) \
+         VALUES (?, ?, ?, ?) ON CONFLICT (session_id) DO NOTHING",
+    ))
+    .bind(session_id)
+    .bind(invoice_id)
+    .bind(amount)
+    .bind(applied)
+    .execute(&mut *tx)
+    .await?;
+    let duplicate = inserted.rows_affected() == 0;
+    if !duplicate && applied > 0 {
+        let payment = NewPayment {
+            amount: applied,
+            method: Some(ONLINE_METHOD),
+            note: Some(session_id),
+            recorded_by: None,
+            line_ids: &[],
+        };
+        pay(&mut tx, invoice_id, &payment).await?;
+    }
+    tx.commit().await?;
+
+    let overpaid: i64 = sqlx::query_scalar(&pg(
+        "SELECT amount - applied FROM stripe_checkouts WHERE session_id = ?",
+    ))
+    .bind(session_id)
+    .fetch_one(pool)
+    .await?;
+    let invoice = detail(pool, invoice_id)
+        .await?
+        .context("Invoice deleted while recording its payment")?;
+    Ok(Some(OnlinePayment {
+        invoice,
+        overpaid,
+        duplicate,
+    }))
+}
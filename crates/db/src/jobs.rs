@@ -0,0 +1,70 @@
+//! How the background jobs' last runs went.
+//!
+//! Each job keeps one row, replaced by [`record`] when a run finishes, so
+//! admins can see from the job status API whether last night's scrape
+//! worked, failed or looked suspect. The row also keeps when the
+//! job last finished [`OK`], which the readiness check reads.
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::AnyPool;
+
+use crate::pg;
+
+/// The scheduled schedule scrape.
+pub const SCHEDULE_SCRAPE: &str = "schedule_scrape";
+
+/// The run finished normally.
+pub const OK: &str = "ok";
+/// The run finished, but its results looked wrong, so games were only
+/// upserted; nobody's seats or requests were moved.
+pub const SUSPECT: &str = "suspect";
+/// The run failed with an error.
+pub const FAILED: &str = "failed";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct JobRun {
+    pub job: String,
+    /// [`OK`], [`SUSPECT`] or [`FAILED`].
+    pub status: String,
+    /// What went wrong, for suspect and failed runs.
+    pub detail: Option<String>,
+    pub finished_at: String,
+    /// When the job last finished [`OK`], if it ever has.
+    pub last_ok_at: Option<String>,
+}
+
+/// Record that `job` just finished with `status`, replacing its last run.
+pub async fn record(pool: &AnyPool, job: &str, status: &str, detail: Option<&str>) -> Result<()> {
+    let sql = pg("INSERT INTO job_runs (job, status, detail, last_ok_at) \
+         VALUES (?, ?, ?, CASE WHEN ? = 'ok' THEN CURRENT_TIMESTAMP END) \
+         ON CONFLICT(job) DO UPDATE SET status = excluded.status, \
+            detail = excluded.detail, finished_at = CURRENT_TIMESTAMP, \
+            last_ok_at = COALESCE(excluded.last_ok_at, job_runs.last_ok_at)");
+    sqlx::query(&sql)
+        .bind(job)
+        .bind(status)
+        .bind(detail)
+        .bind(status)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// `job`'s last run, if it has run.
+pub async fn get(pool: &AnyPool, job: &str) -> Result<Option<JobRun>> {
+    let sql = pg(&format!("SELECT {COLUMNS} FROM job_runs WHERE job = ?"));
+    Ok(sqlx::query_as::<_, JobRun>(&sql)
+        .bind(job)
+        .fetch_optional(pool)
+        .await?)
+}
+
+const COLUMNS: &str = "job, status, detail, CAST(finished_at AS TEXT) AS finished_at, \
+     CAST(last_ok_at AS TEXT) AS last_ok_at";
+
+/// Every job's last run, by job name.
+pub async fn list(pool: &AnyPool) -> Result<Vec<JobRun>> {
+    let sql = pg(&format!("SELECT {COLUMNS} FROM job_runs ORDER BY job"));
+    Ok(sqlx::query_as::<_, JobRun>(&sql).fetch_all(pool).await?)
+}
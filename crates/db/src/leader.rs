@@ -0,0 +1,161 @@
+//! Leader election for background jobs.
+//!
+//! Every process that can run jobs (`gtm serve` and `gtm worker`) competes
+//! for one Postgres session-level advisory lock (a named `GET_LOCK` on
+//! MySQL); only the holder runs the scheduler. The lock lives on a dedicated
+//! connection and is released by the server when that connection closes, so
+//! a crashed leader hands over automatically. SQLite is single-host, so
+//! there the lock is an exclusive lock on a file next to the database,
+//! which the OS releases when the process exits. An in-memory database
+//! belongs to one process, whose every caller leads.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use sqlx::AnyPool;
+use sqlx::pool::PoolConnection;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::dialect::{self, Backend};
+use crate::lock::mysql_lock_name;
+use crate::maintenance::sqlite_path;
+
+/// Advisory lock key for the job scheduler ("gtm_jobs" as ASCII).
+pub const JOBS_LOCK_KEY: i64 = 0x67_74_6d_5f_6a_6f_62_73;
+
+/// A held leadership lock. Dropping it closes the connection (or the lock
+/// file) and releases the lock.
+pub struct LeaderLock {
+    conn: Option<PoolConnection<sqlx::Any>>,
+    _file: Option<File>,
+}
+
+/// Try to become leader for `key` on the database at `database_url`.
+/// Returns `None` if another process holds it.
+pub async fn try_acquire(
+    pool: &AnyPool,
+    database_url: &str,
+    key: i64,
+) -> Result<Option<LeaderLock>> {
+    let backend = dialect::backend();
+    if backend == Backend::Sqlite {
+        let Some(db) = sqlite_path(database_url) else {
+            return Ok(Some(LeaderLock {
+                conn: None,
+                _file: None,
+            }));
+        };
+        let path = db.with_extension(format!("{key:x}.lock"));
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Could not open {}", path.display()))?;
+        return match file.try_lock() {
+            Ok(()) => Ok(Some(LeaderLock {
+                conn: None,
+                _file: Some(file),
+            })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => {
+                Err(e).with_context(|| format!("Could not lock {}", path.display()))
+            }
+        };
+    }
+    let mut conn = pool.acquire().await?;
+    let locked = match backend {
+        Backend::MySql => {
+            sqlx::query_scalar::<_, i64>("SELECT GET_LOCK(?, 0)")
+                .bind(mysql_lock_name(&format!("{key:x}")))
+                .fetch_one(&mut *conn)
+                .await?
+                == 1
+        }
+        _ => {
+            sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock($1)")
+                .bind(key)
+                .fetch_one(&mut *conn)
+                .await?
+        }
+    };
+    if !locked {
+        return Ok(None);
+    }
+    // Session locks outlive a connection returned to the pool, so keep this
+    // one out of it for good.
+    Ok(Some(LeaderLock {
+        conn: Some(conn),
+        _file: None,
+    }))
+}
+
+impl LeaderLock {
+    /// Whether the lock is still held, i.e. its connection is still alive.
+    pub async fn is_held(&mut self) -> bool {
+        match self.conn.as_mut() {
+            Some(conn) => sqlx::query("SELECT 1").execute(&mut **conn).await.is_ok(),
+            None => true,
+        }
+    }
+}
+
+impl Drop for LeaderLock {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            // Closing (rather than releasing) the connection ends the session,
+            // which is what frees the advisory lock.
+            drop(conn.detach());
+        }
+    }
+}
+
+/// Compete for leadership of `key` and keep the jobs `start` spawns running
+/// while holding it. Runs forever, checking every `check`: a standby
+/// retries for the lock, a leader whose `start` fails gives the lock up and
+/// tries again, and a leader that loses the lock stops its jobs and rejoins
+/// the election.
+pub async fn lead<F>(pool: &AnyPool, database_url: &str, key: i64, check: Duration, mut start: F)
+where
+    F: FnMut() -> Result<Vec<JoinHandle<()>>>,
+{
+    let mut standby_logged = false;
+    loop {
+        let mut lock = match try_acquire(pool, database_url, key).await {
+            Ok(Some(lock)) => lock,
+            Ok(None) => {
+                if !standby_logged {
+                    info!("Another process is running background jobs; standing by");
+                    standby_logged = true;
+                }
+                tokio::time::sleep(check).await;
+                continue;
+            }
+            Err(e) => {
+                warn!("Job leader election failed: {e}");
+                tokio::time::sleep(check).await;
+                continue;
+            }
+        };
+        standby_logged = false;
+        info!("Acquired job lock; starting background jobs");
+        let jobs = match start() {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                warn!("Could not start background jobs: {e}; retrying");
+                drop(lock);
+                tokio::time::sleep(check).await;
+                continue;
+            }
+        };
+        while lock.is_held().await {
+            tokio::time::sleep(check).await;
+        }
+        warn!("Lost job lock; stopping background jobs");
+        for job in jobs {
+            job.abort();
+        }
+    }
+}
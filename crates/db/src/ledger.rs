@@ -0,0 +1,301 @@
+//! Per-member season ledger and cost split.
+//!
+//! By default the group splits the season-ticket bill by games used: each
+//! assigned ticket costs its own `game_tickets.cost` if set, otherwise the
+//! seat's `face_value`. Split rules override that for seats some members
+//! own outright: a seat's own rules, else the plan-wide rules, divide every
+//! game in it by share whoever goes. All amounts are integer cents.
+
+use anyhow::{Result, bail};
+use gtm_models::{
+    CostShareLine, CostSplitPeriod, CostTicket, LedgerGame, MemberLedger, MemberShare, SeatId,
+    SplitRule, UserId,
+};
+use sqlx::AnyPool;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::pg;
+
+/// Ledgers for one season, by member name. `user_id` limits it to one member.
+/// Members with no assigned seats that season are left out.
+pub async fn member_ledgers(
+    pool: &AnyPool,
+    season: &str,
+    user_id: Option<UserId>,
+) -> Result<Vec<MemberLedger>> {
+    let mut sql = String::from(
+        "SELECT gt.assigned_to AS user_id, g.game_pk, g.official_date, g.away_team_name, \
+                COUNT(*) AS seats, \
+                SUM(CASE WHEN COALESCE(gt.cost, s.face_value) IS NULL THEN 1 ELSE 0 END) \
+                    AS unpriced_seats, \
+                SUM(COALESCE(gt.cost, s.face_value, 0)) AS amount \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         JOIN seats s ON s.id = gt.seat_id \
+         WHERE gt.assigned_to IS NOT NULL AND g.season = ?",
+    );
+    if user_id.is_some() {
+        sql.push_str(" AND gt.assigned_to = ?");
+    }
+    sql.push_str(
+        " GROUP BY gt.assigned_to, g.game_pk, g.official_date, g.away_team_name, g.game_date \
+         ORDER BY gt.assigned_to, g.game_date",
+    );
+    let sql = pg(&sql);
+    let mut query = sqlx::query_as::<_, LedgerGame>(&sql).bind(season);
+    if let Some(uid) = user_id {
+        query = query.bind(uid);
+    }
+    let games = query.fetch_all(pool).await?;
+
+    let users = crate::list_users(pool).await?;
+    let mut ledgers: Vec<MemberLedger> = Vec::new();
+    for game in games {
+        if ledgers.last().is_none_or(|l| l.user_id != game.user_id) {
+            let user_name = users
+                .iter()
+                .find(|u| u.id == game.user_id)
+                .map(|u| u.name.clone())
+                .unwrap_or_default();
+            ledgers.push(MemberLedger {
+                user_id: game.user_id,
+                user_name,
+                season: season.to_string(),
+                games: Vec::new(),
+                seats: 0,
+                amount: 0,
+            });
+        }
+        let ledger = ledgers.last_mut().expect("pushed above");
+        ledger.seats += game.seats;
+        ledger.amount += game.amount;
+        ledger.games.push(game);
+    }
+    ledgers.sort_by(|a, b| a.user_name.cmp(&b.user_name));
+    Ok(ledgers)
+}
+
+/// What a set of split rules must add up to, in basis points (100%).
+pub const FULL_SHARE: i64 = 10_000;
+
+/// Every split rule: plan-wide first, then by seat.
+pub async fn split_rules(pool: &AnyPool) -> Result<Vec<SplitRule>> {
+    let rules = sqlx::query_as::<_, SplitRule>(
+        "SELECT r.id, r.seat_id, r.user_id, u.name AS user_name, r.basis_points \
+         FROM cost_split_rules r JOIN users u ON u.id = r.user_id \
+         ORDER BY CASE WHEN r.seat_id IS NULL THEN 0 ELSE 1 END, r.seat_id, r.id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rules)
+}
+
+/// Replace the rules for one seat, or the plan-wide rules when `seat_id` is
+/// `None`, with `shares` of `(user, basis points)`. The shares must total
+/// [`FULL_SHARE`]; an empty list removes the rules.
+pub async fn set_split_rules(
+    pool: &AnyPool,
+    seat_id: Option<SeatId>,
+    shares: &[(UserId, i64)],
+) -> Result<()> {
+    let mut users = HashSet::new();
+    for (user_id, bp) in shares {
+        if *bp <= 0 {
+            bail!("Each share must be more than 0%");
+        }
+        if !users.insert(*user_id) {
+            bail!("User {user_id} is listed twice");
+        }
+    }
+    let total: i64 = shares.iter().map(|(_, bp)| bp).sum();
+    if !shares.is_empty() && total != FULL_SHARE {
+        bail!(
+            "Shares add up to {}.{:02}%, not 100%",
+            total / 100,
+            total % 100
+        );
+    }
+
+    let mut tx = pool.begin().await?;
+    match seat_id {
+        Some(seat_id) => {
+            sqlx::query(&pg("DELETE FROM cost_split_rules WHERE seat_id = ?"))
+                .bind(seat_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        None => {
+            sqlx::query("DELETE FROM cost_split_rules WHERE seat_id IS NULL")
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+    let insert =
+        pg("INSERT INTO cost_split_rules (seat_id, user_id, basis_points) VALUES (?, ?, ?)");
+    for (user_id, bp) in shares {
+        sqlx::query(&insert)
+            .bind(seat_id.map(|s| s.0))
+            .bind(user_id)
+            .bind(bp)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Priced tickets in `season`, in date order. Unpriced tickets have nothing
+/// to split and are left out.
+pub async fn cost_tickets(pool: &AnyPool, season: &str) -> Result<Vec<CostTicket>> {
+    let sql = pg(
+        "SELECT gt.id AS ticket_id, g.game_pk, g.official_date, g.away_team_name, gt.seat_id, \
+                gt.assigned_to, COALESCE(gt.cost, s.face_value) AS cost \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         JOIN seats s ON s.id = gt.seat_id \
+         WHERE g.season = ? AND COALESCE(gt.cost, s.face_value) IS NOT NULL \
+         ORDER BY g.game_date, gt.seat_id",
+    );
+    let tickets = sqlx::query_as::<_, CostTicket>(&sql)
+        .bind(season)
+        .fetch_all(pool)
+        .await?;
+    Ok(tickets)
+}
+
+/// Apply `rules` to `tickets`: one line per member per ticket, plus the
+/// pay-per-use tickets nobody was assigned. Rule shares are rounded to the
+/// cent so each ticket's lines add up to its cost exactly.
+pub fn split_costs<'a>(
+    tickets: &'a [CostTicket],
+    rules: &[SplitRule],
+) -> (Vec<CostShareLine>, Vec<&'a CostTicket>) {
+    let mut by_seat: HashMap<SeatId, Vec<&SplitRule>> = HashMap::new();
+    let mut plan = Vec::new();
+    for rule in rules {
+        match rule.seat_id {
+            Some(seat_id) => by_seat.entry(seat_id).or_default().push(rule),
+            None => plan.push(rule),
+        }
+    }
+
+    let mut lines = Vec::new();
+    let mut unallocated = Vec::new();
+    for t in tickets {
+        let line = |user_id, amount, basis: &str| CostShareLine {
+            ticket_id: t.ticket_id,
+            game_pk: t.game_pk,
+            official_date: t.official_date.clone(),
+            away_team_name: t.away_team_name.clone(),
+            seat_id: t.seat_id,
+            user_id,
+            amount,
+            basis: basis.to_string(),
+        };
+        let (shares, basis) = match by_seat.get(&t.seat_id) {
+            Some(seat_rules) => (seat_rules.as_slice(), "seat"),
+            None => (plan.as_slice(), "plan"),
+        };
+        if !shares.is_empty() {
+            for (rule, amount) in shares.iter().zip(apportion(t.cost, shares)) {
+                lines.push(line(rule.user_id, amount, basis));
+            }
+        } else if let Some(user_id) = t.assigned_to {
+            lines.push(line(user_id, t.cost, "usage"));
+        } else {
+            unallocated.push(t);
+        }
+    }
+    (lines, unallocated)
+}
+
+/// Split `cost` by basis points, handing leftover cents to the largest
+/// remainders (earlier rules first on ties).
+fn apportion(cost: i64, shares: &[&SplitRule]) -> Vec<i64> {
+    let exact: Vec<(i64, i64)> = shares
+        .iter()
+        .map(|r| {
+            let scaled = cost * r.basis_points;
+            (scaled / FULL_SHARE, scaled % FULL_SHARE)
+        })
+        .collect();
+    let mut amounts: Vec<i64> = exact.iter().map(|(whole, _)| *whole).collect();
+    let mut left = cost - amounts.iter().sum::<i64>();
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(exact[i].1));
+    for i in order {
+        if left <= 0 {
+            break;
+        }
+        amounts[i] += 1;
+        left -= 1;
+    }
+    amounts
+}
+
+/// Each member's share of `season`'s costs per invoice period (calendar
+/// month of the game), by member name. `user_id` keeps only that member's
+/// shares; period totals are always the group's.
+pub async fn cost_split(
+    pool: &AnyPool,
+    season: &str,
+    user_id: Option<UserId>,
+) -> Result<Vec<CostSplitPeriod>> {
+    let tickets = cost_tickets(pool, season).await?;
+    let rules = split_rules(pool).await?;
+    let (lines, unallocated) = split_costs(&tickets, &rules);
+    let names: HashMap<UserId, String> = crate::list_users(pool)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.name))
+        .collect();
+
+    let mut periods: BTreeMap<String, CostSplitPeriod> = BTreeMap::new();
+    for t in unallocated {
+        let p = period_for(&mut periods, &t.official_date);
+        p.unallocated += t.cost;
+        p.total += t.cost;
+    }
+    for line in lines {
+        let p = period_for(&mut periods, &line.official_date);
+        p.total += line.amount;
+        if user_id.is_some_and(|u| u != line.user_id) {
+            continue;
+        }
+        let share = match p.members.iter().position(|m| m.user_id == line.user_id) {
+            Some(i) => &mut p.members[i],
+            None => {
+                p.members.push(MemberShare {
+                    user_id: line.user_id,
+                    user_name: names.get(&line.user_id).cloned().unwrap_or_default(),
+                    amount: 0,
+                    lines: Vec::new(),
+                });
+                p.members.last_mut().expect("pushed above")
+            }
+        };
+        share.amount += line.amount;
+        share.lines.push(line);
+    }
+    let mut periods: Vec<CostSplitPeriod> = periods.into_values().collect();
+    for p in &mut periods {
+        p.members.sort_by(|a, b| a.user_name.cmp(&b.user_name));
+    }
+    Ok(periods)
+}
+
+/// The invoice period an `official_date` falls in, added if new.
+fn period_for<'a>(
+    periods: &'a mut BTreeMap<String, CostSplitPeriod>,
+    official_date: &str,
+) -> &'a mut CostSplitPeriod {
+    let key = official_date.get(..7).unwrap_or(official_date).to_string();
+    periods
+        .entry(key.clone())
+        .or_insert_with(|| CostSplitPeriod {
+            period: key,
+            members: Vec::new(),
+            unallocated: 0,
+            total: 0,
+        })
+}
@@ -1,48 +1,274 @@
-use anyhow::Result;
-use gtm_models::{Game, GameTag, GameTicketDetail, Promotion, Seat, TicketRequest, User};
-use sqlx::AnyPool;
-use std::sync::OnceLock;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use compat::Arg;
+use gtm_models::{
+    AllocationSummaryRow, CalendarEventSync, FairnessRow, Game, GameDetails, GamePk, GameTag,
+    GameTicketDetail, GameType, GoogleAccount, LiveGame, MonthlyResponseTime, PendingRequest,
+    Promotion, PromotionInterest, PromotionInterestCount, PromotionListing, ReleasePrompt,
+    ScheduleData, Seat, SeatDeleteOutcome, SeatId, SeatTicketOutcome, TicketCounts, TicketId,
+    TicketRequest, TicketStatus, TicketSummaryRow, TicketTransfer, UnassignedGame, User, UserId,
+    UserPhone,
+};
+use sqlx::any::{AnyConnectOptions, AnyPoolOptions};
+use sqlx::{AnyPool, ConnectOptions as _};
+use std::collections::HashMap;
+use std::time::Duration;
+use timing::timed;
 use tracing::info;
 
+pub mod allocation_batches;
+pub mod allocation_plan;
+pub mod api_tokens;
+pub mod attendance;
+pub mod audit;
+pub mod calendar;
+pub mod compat;
+pub mod dialect;
+pub mod export;
+pub mod external_refs;
+pub mod forwarding;
+pub mod history;
+pub mod idempotency;
+pub mod import;
+pub mod inbound_tokens;
+pub mod invoices;
+pub mod jobs;
+pub mod leader;
+pub mod ledger;
+pub mod lock;
+pub mod maintenance;
+pub mod note_templates;
+pub mod notifications;
+pub mod polls;
+pub mod postponements;
+pub mod preferences;
+pub mod previews;
+pub mod prices;
+pub mod replica;
+pub mod reports;
+pub mod request_comments;
+pub mod saved_views;
+pub mod schedule_sync;
+pub mod seasons;
+pub mod seat_limits;
+pub mod seating;
+pub mod timing;
+pub mod usage;
+pub mod warnings;
+pub mod webhooks;
+
+pub use dialect::Backend;
+pub use lock::with_lock;
+pub use warnings::Warnings;
+
 static PG_MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../../migrations");
 static SQLITE_MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../../migrations-sqlite");
+static MYSQL_MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("../../migrations-mysql");
 
-static IS_POSTGRES: OnceLock<bool> = OnceLock::new();
-
-/// Translate `?` placeholders to `$1, $2, ...` when connected to Postgres.
-/// SQLite uses `?` natively; Postgres requires numbered `$N` parameters.
+/// Adapt a query written with `?` placeholders to the connected backend;
+/// see [`dialect`]. Postgres requires numbered `$N` parameters, and MySQL
+/// spells upserts and a few casts differently.
 pub fn pg(sql: &str) -> String {
-    if !*IS_POSTGRES.get().unwrap_or(&false) {
-        return sql.to_string();
-    }
-    let mut out = String::with_capacity(sql.len() + 32);
-    let mut n = 0u32;
-    for ch in sql.chars() {
-        if ch == '?' {
-            n += 1;
-            out.push('$');
-            out.push_str(&n.to_string());
+    dialect::adapt(sql, dialect::backend())
+}
+
+/// Parse a timestamp column read back as text.
+///
+/// SQLite stores `YYYY-MM-DD HH:MM:SS` (UTC); Postgres `TIMESTAMPTZ` cast to
+/// text adds fractional seconds and an offset. RFC 3339 is accepted too.
+pub fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%#z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// Pool settings for [`connect`].
+#[derive(Debug, Clone)]
+pub struct ConnectOptions {
+    pub max_connections: u32,
+    /// How long to wait for a free connection before failing.
+    pub acquire_timeout: Duration,
+    /// SQLite only: use WAL journaling so readers don't block the writer.
+    pub sqlite_wal: bool,
+    /// SQLite only: how long a write waits on a lock before failing with
+    /// "database is locked".
+    pub sqlite_busy_timeout: Duration,
+    /// Longest a [`timing::timed`] query may run; on a Postgres read
+    /// replica, every statement's `statement_timeout`.
+    pub statement_timeout: Option<Duration>,
+    /// Statements at least this slow are logged as warnings; [`timing::timed`]
+    /// queries are also counted.
+    pub slow_query: Option<Duration>,
+    /// Log every statement at `info` with its duration and row counts.
+    pub log_queries: bool,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        ConnectOptions {
+            max_connections: 10,
+            acquire_timeout: Duration::from_secs(30),
+            sqlite_wal: true,
+            sqlite_busy_timeout: Duration::from_secs(5),
+            statement_timeout: Some(Duration::from_secs(60)),
+            slow_query: Some(Duration::from_secs(1)),
+            log_queries: false,
+        }
+    }
+}
+
+impl ConnectOptions {
+    /// Pragmas run on every new SQLite connection.
+    pub fn sqlite_pragmas(&self) -> Vec<String> {
+        let mut pragmas = vec![format!(
+            "PRAGMA busy_timeout = {}",
+            self.sqlite_busy_timeout.as_millis()
+        )];
+        if self.sqlite_wal {
+            pragmas.push("PRAGMA journal_mode = WAL".to_string());
+            // WAL is durable across application crashes at NORMAL
+            pragmas.push("PRAGMA synchronous = NORMAL".to_string());
+        }
+        pragmas
+    }
+
+    /// Statements run on every new Postgres connection. Only a pool that
+    /// serves nothing but reads (`reads_only`, the replica) gets the
+    /// server-side `statement_timeout`: on the primary it would also cut
+    /// off migrations and long writes, so there it is enforced only by
+    /// [`timing::timed`].
+    pub fn postgres_session(&self, reads_only: bool) -> Vec<String> {
+        self.statement_timeout
+            .filter(|_| reads_only)
+            .map(|t| format!("SET statement_timeout = {}", t.as_millis()))
+            .into_iter()
+            .collect()
+    }
+
+    /// Statements run on every new MySQL connection. Timestamps are
+    /// written as UTC, double quotes and `||` mean what they do in standard
+    /// SQL, and `GROUP_CONCAT` isn't cut off at 1 KB. MySQL has no statement
+    /// timeout for writes, so `statement_timeout` is enforced only by
+    /// [`timing::timed`].
+    pub fn mysql_session(&self) -> Vec<String> {
+        vec![
+            "SET time_zone = '+00:00'".to_string(),
+            "SET SESSION sql_mode = CONCAT(@@sql_mode, ',ANSI_QUOTES,PIPES_AS_CONCAT')".to_string(),
+            "SET SESSION group_concat_max_len = 65535".to_string(),
+        ]
+    }
+
+    /// Connection settings for `database_url`, with statement logging. sqlx
+    /// logs each statement under the `sqlx::query` target, inside the
+    /// [`timing::timed`] span when there is one. Bind values are left out:
+    /// they carry members' emails, phone numbers and tokens.
+    pub(crate) fn connect_options(&self, database_url: &str) -> Result<AnyConnectOptions> {
+        let statements = if self.log_queries {
+            log::LevelFilter::Info
         } else {
-            out.push(ch);
+            log::LevelFilter::Off
+        };
+        let options = database_url
+            .parse::<AnyConnectOptions>()?
+            .log_statements(statements);
+        Ok(match self.slow_query {
+            Some(slow) => options.log_slow_statements(log::LevelFilter::Warn, slow),
+            None => options.log_slow_statements(log::LevelFilter::Off, Duration::MAX),
+        })
+    }
+
+    /// Pool settings for `backend`, running the SQLite pragmas or the
+    /// Postgres or MySQL session settings on each new connection.
+    /// `reads_only` is for a pool that never writes.
+    pub(crate) fn pool_options(&self, backend: Backend, reads_only: bool) -> AnyPoolOptions {
+        let setup = match backend {
+            Backend::Sqlite => self.sqlite_pragmas(),
+            Backend::Postgres => self.postgres_session(reads_only),
+            Backend::MySql => self.mysql_session(),
+        };
+        let pool_options = AnyPoolOptions::new()
+            .max_connections(self.max_connections)
+            .acquire_timeout(self.acquire_timeout);
+        if setup.is_empty() {
+            return pool_options;
         }
+        pool_options.after_connect(move |conn, _meta| {
+            let setup = setup.clone();
+            Box::pin(async move {
+                for statement in &setup {
+                    sqlx::query(statement).execute(&mut *conn).await?;
+                }
+                Ok(())
+            })
+        })
     }
-    out
 }
 
-pub async fn connect(database_url: &str) -> Result<AnyPool> {
+/// The backend `database_url` points at; an error for unsupported schemes.
+pub fn backend_for(database_url: &str) -> Result<Backend> {
+    Backend::from_url(database_url).with_context(|| {
+        let scheme = database_url.split(':').next().unwrap_or_default();
+        format!("Unsupported database URL scheme `{scheme}:`; use sqlite:, postgres: or mysql:")
+    })
+}
+
+pub async fn connect(database_url: &str, options: &ConnectOptions) -> Result<AnyPool> {
     sqlx::any::install_default_drivers();
-    IS_POSTGRES.get_or_init(|| !database_url.starts_with("sqlite:"));
-    let pool = AnyPool::connect(database_url).await?;
-    info!("Connected to database: {database_url}");
+    let backend = backend_for(database_url)?;
+    dialect::set_backend(backend);
+    timing::configure(options.statement_timeout, options.slow_query);
+    let pool = options
+        .pool_options(backend, false)
+        .connect_with(options.connect_options(database_url)?)
+        .await?;
+    info!(
+        max_connections = options.max_connections,
+        backend = backend.name(),
+        "Connected to database: {database_url}"
+    );
+    match backend {
+        Backend::Sqlite => {
+            let version: String = sqlx::query_scalar("SELECT sqlite_version()")
+                .fetch_one(&pool)
+                .await?;
+            let returning = compat::sqlite_supports_returning(&version);
+            compat::set_returning_supported(returning);
+            if !returning {
+                info!("SQLite {version} has no RETURNING; using insert-then-select");
+            }
+        }
+        Backend::MySql => compat::set_returning_supported(false),
+        Backend::Postgres => {}
+    }
     Ok(pool)
 }
 
-pub async fn migrate(pool: &AnyPool, database_url: &str) -> Result<()> {
-    if database_url.starts_with("sqlite:") {
-        SQLITE_MIGRATOR.run(pool).await?;
-    } else {
-        PG_MIGRATOR.run(pool).await?;
+/// The migrations for the backend `database_url` points at.
+pub(crate) fn migrator(database_url: &str) -> &'static sqlx::migrate::Migrator {
+    match Backend::from_url(database_url) {
+        Some(Backend::Sqlite) => &SQLITE_MIGRATOR,
+        Some(Backend::MySql) => &MYSQL_MIGRATOR,
+        _ => &PG_MIGRATOR,
     }
+}
+
+/// Check the database answers and has been migrated, for the readiness
+/// check.
+pub async fn ping(pool: &AnyPool) -> Result<()> {
+    sqlx::query("SELECT COUNT(*) FROM _sqlx_migrations")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn migrate(pool: &AnyPool, database_url: &str) -> Result<()> {
+    migrator(database_url).run(pool).await?;
     info!("Migrations applied");
     Ok(())
 }
@@ -53,29 +279,285 @@ const GAME_COLUMNS: &str = "game_pk, game_guid, game_type, season, game_date, of
     home_team_id, home_team_name, home_score, home_is_winner, \
     venue_id, venue_name, day_night, series_description, \
     series_game_number, games_in_series, double_header, game_number, \
-    scheduled_innings, is_tie";
+    scheduled_innings, is_tie, current_inning, inning_state, \
+    away_probable_pitcher, home_probable_pitcher, \
+    weather_condition, weather_temp, weather_wind, tv_broadcasts, \
+    reschedule_date, reschedule_official_date, rescheduled_to";
 
-pub async fn list_games(pool: &AnyPool, month: Option<u32>) -> Result<Vec<Game>> {
-    let games = match month {
-        Some(m) => {
-            let pattern = format!("%-{:02}-%", m);
-            let sql = pg(&format!(
-                "SELECT {GAME_COLUMNS} FROM games WHERE official_date LIKE ? ORDER BY game_date"
+/// Upper bound on `GameFilter::limit`, so one request can't ask for everything.
+pub const MAX_GAMES_PAGE: i64 = 500;
+
+/// Filters and paging for [`list_games`]. All fields are optional; the
+/// default lists every game in date order.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct GameFilter {
+    /// Season (year), e.g. `2026`.
+    pub season: Option<String>,
+    /// `game_type` codes, comma-separated: `S` for spring training,
+    /// `F,D,L,W` for the postseason rounds.
+    pub game_type: Option<String>,
+    /// Month of `official_date` (1-12).
+    pub month: Option<u32>,
+    /// Earliest `official_date` (YYYY-MM-DD), inclusive.
+    pub from: Option<String>,
+    /// Latest `official_date` (YYYY-MM-DD), inclusive.
+    pub to: Option<String>,
+    /// Case-insensitive substring of the opponent's name: the team that
+    /// isn't `team_id`.
+    pub opponent: Option<String>,
+    /// Only home games of `team_id`.
+    #[serde(default)]
+    pub home_only: bool,
+    /// The team `home_only` refers to. Not read from the query string;
+    /// callers set it from config.
+    #[serde(skip)]
+    pub team_id: u32,
+    /// `status_abstract`, e.g. Preview, Live, Final (case-insensitive).
+    pub status: Option<String>,
+    /// Days of the week of `official_date`, comma-separated: `fri,sat`.
+    pub days: Option<String>,
+    /// Only Saturday and Sunday games (`true`), or only weekday ones.
+    pub weekend: Option<bool>,
+    /// `day` or `night` (case-insensitive).
+    pub day_night: Option<String>,
+    /// Only games with (`true`) or without (`false`) promotions.
+    pub has_promotions: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+enum FilterArg {
+    Text(String),
+    Int(i64),
+}
+
+/// `?, ?, ...` for `n` bind values.
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+impl GameFilter {
+    /// `WHERE ...` clause (possibly empty) and its bind values, in order.
+    fn where_clause(&self) -> (String, Vec<FilterArg>) {
+        let game_types = self.game_types();
+        let days = self.days().unwrap_or_default();
+        let day_of_week = dialect::day_of_week("official_date", dialect::backend());
+        let mut conds: Vec<String> = Vec::new();
+        let mut args = Vec::new();
+        if let Some(season) = &self.season {
+            conds.push("season = ?".into());
+            args.push(FilterArg::Text(season.clone()));
+        }
+        if !game_types.is_empty() {
+            conds.push(format!("game_type IN ({})", placeholders(game_types.len())));
+            args.extend(game_types.into_iter().map(FilterArg::Text));
+        }
+        if let Some(m) = self.month {
+            conds.push("official_date LIKE ?".into());
+            args.push(FilterArg::Text(format!("%-{m:02}-%")));
+        }
+        if let Some(from) = &self.from {
+            conds.push("official_date >= ?".into());
+            args.push(FilterArg::Text(from.clone()));
+        }
+        if let Some(to) = &self.to {
+            conds.push("official_date <= ?".into());
+            args.push(FilterArg::Text(to.clone()));
+        }
+        if let Some(opp) = &self.opponent {
+            conds.push(format!(
+                "LOWER(CASE WHEN home_team_id = ? THEN away_team_name ELSE home_team_name END) \
+                 LIKE ?{}",
+                dialect::like_escape(dialect::backend())
             ));
-            sqlx::query_as::<_, Game>(&sql)
-                .bind(pattern)
-                .fetch_all(pool)
-                .await?
+            args.push(FilterArg::Int(self.team_id.into()));
+            args.push(FilterArg::Text(format!(
+                "%{}%",
+                dialect::escape_like(&opp.to_lowercase())
+            )));
         }
-        None => {
-            let sql = format!("SELECT {GAME_COLUMNS} FROM games ORDER BY game_date");
-            sqlx::query_as::<_, Game>(&sql).fetch_all(pool).await?
+        if self.home_only {
+            conds.push("home_team_id = ?".into());
+            args.push(FilterArg::Int(self.team_id.into()));
+        }
+        if let Some(status) = &self.status {
+            conds.push("LOWER(status_abstract) = ?".into());
+            args.push(FilterArg::Text(status.to_lowercase()));
+        }
+        if !days.is_empty() {
+            conds.push(format!("{day_of_week} IN ({})", placeholders(days.len())));
+            args.extend(days.into_iter().map(FilterArg::Int));
+        }
+        match self.weekend {
+            Some(true) => conds.push(format!("{day_of_week} IN (0, 6)")),
+            Some(false) => conds.push(format!("{day_of_week} NOT IN (0, 6)")),
+            None => {}
+        }
+        if let Some(day_night) = &self.day_night {
+            conds.push("LOWER(day_night) = ?".into());
+            args.push(FilterArg::Text(day_night.to_lowercase()));
+        }
+        match self.has_promotions {
+            Some(true) => conds.push("game_pk IN (SELECT game_pk FROM promotions)".into()),
+            Some(false) => conds.push("game_pk NOT IN (SELECT game_pk FROM promotions)".into()),
+            None => {}
+        }
+        if conds.is_empty() {
+            (String::new(), args)
+        } else {
+            (format!(" WHERE {}", conds.join(" AND ")), args)
+        }
+    }
+}
+
+impl GameFilter {
+    /// The codes in `game_type`, upper-cased.
+    pub fn game_types(&self) -> Vec<String> {
+        self.game_type
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(|code| code.trim().to_ascii_uppercase())
+            .filter(|code| !code.is_empty())
+            .collect()
+    }
+
+    /// The days in `days`, counted from Sunday (0). Names may be full or
+    /// three letters, in any case.
+    pub fn days(&self) -> Result<Vec<i64>, String> {
+        self.days
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|day| !day.is_empty())
+            .map(|day| {
+                day.parse::<chrono::Weekday>()
+                    .map(|d| i64::from(d.num_days_from_sunday()))
+                    .map_err(|_| format!("Unknown day of the week: {day}"))
+            })
+            .collect()
+    }
+
+    /// `SELECT` of the matching games in date order, paged, and its bind
+    /// values.
+    fn select(&self) -> (String, Vec<FilterArg>) {
+        let (where_sql, mut args) = self.where_clause();
+        let mut sql = format!(
+            "SELECT {GAME_COLUMNS} FROM games{where_sql} ORDER BY game_date, game_number, game_pk"
+        );
+        if self.limit.is_some() || self.offset.is_some() {
+            sql.push_str(" LIMIT ? OFFSET ?");
+            let limit = self
+                .limit
+                .unwrap_or(MAX_GAMES_PAGE)
+                .clamp(0, MAX_GAMES_PAGE);
+            args.push(FilterArg::Int(limit));
+            args.push(FilterArg::Int(self.offset.unwrap_or(0).max(0)));
+        }
+        (sql, args)
+    }
+}
+
+pub async fn list_games(pool: &AnyPool, filter: &GameFilter) -> Result<Vec<Game>> {
+    let (sql, args) = filter.select();
+    let sql = pg(&sql);
+    let mut query = sqlx::query_as::<_, Game>(&sql);
+    for arg in args {
+        query = match arg {
+            FilterArg::Text(v) => query.bind(v),
+            FilterArg::Int(v) => query.bind(v),
+        };
+    }
+    timed("list_games", query.fetch_all(pool)).await
+}
+
+/// Number of games matching `filter`, ignoring `limit`/`offset`.
+pub async fn count_games(pool: &AnyPool, filter: &GameFilter) -> Result<i64> {
+    let (where_sql, args) = filter.where_clause();
+    let sql = pg(&format!("SELECT COUNT(*) FROM games{where_sql}"));
+    let mut query = sqlx::query_scalar::<_, i64>(&sql);
+    for arg in args {
+        query = match arg {
+            FilterArg::Text(v) => query.bind(v),
+            FilterArg::Int(v) => query.bind(v),
+        };
+    }
+    timed("count_games", query.fetch_one(pool)).await
+}
+
+#[derive(sqlx::FromRow)]
+struct GameDetailsRow {
+    #[sqlx(flatten)]
+    game: Game,
+    /// JSON array of names.
+    promotion_names: Option<String>,
+    tickets_total: i64,
+    tickets_available: i64,
+}
+
+/// [`list_games`] with each game's promotion names and ticket counts,
+/// aggregated in the same query.
+pub async fn list_games_with_details(
+    pool: &AnyPool,
+    filter: &GameFilter,
+) -> Result<Vec<GameDetails>> {
+    let names = match dialect::backend() {
+        Backend::Postgres => "CAST(json_agg(name ORDER BY display_order, offer_id) AS TEXT)",
+        Backend::MySql => {
+            "CAST(CONCAT('[', GROUP_CONCAT(JSON_QUOTE(name) \
+             ORDER BY display_order, offer_id), ']') AS TEXT)"
         }
+        // json_group_array keeps the subquery's order
+        Backend::Sqlite => "json_group_array(name)",
     };
-    Ok(games)
+    let (games_sql, args) = filter.select();
+    let sql = pg(&format!(
+        "SELECT g.*, p.names AS promotion_names, \
+                COALESCE(t.total, 0) AS tickets_total, \
+                COALESCE(t.available, 0) AS tickets_available \
+         FROM ({games_sql}) g \
+         LEFT JOIN ( \
+             SELECT game_pk, {names} AS names FROM ( \
+                 SELECT game_pk, name, display_order, offer_id FROM promotions \
+                 ORDER BY game_pk, display_order, offer_id \
+             ) ordered GROUP BY game_pk \
+         ) p ON p.game_pk = g.game_pk \
+         LEFT JOIN ( \
+             SELECT game_pk, \
+                 COUNT(*) AS total, \
+                 SUM(CASE WHEN status = 'available' THEN 1 ELSE 0 END) AS available \
+             FROM game_tickets GROUP BY game_pk \
+         ) t ON t.game_pk = g.game_pk \
+         ORDER BY g.game_date, g.game_number, g.game_pk"
+    ));
+    let mut query = sqlx::query_as::<_, GameDetailsRow>(&sql);
+    for arg in args {
+        query = match arg {
+            FilterArg::Text(v) => query.bind(v),
+            FilterArg::Int(v) => query.bind(v),
+        };
+    }
+    let rows = timed("list_games_with_details", query.fetch_all(pool)).await?;
+    rows.into_iter()
+        .map(|r| {
+            let promotions = match r.promotion_names {
+                Some(json) => serde_json::from_str(&json)?,
+                None => Vec::new(),
+            };
+            Ok(GameDetails {
+                game: r.game,
+                promotions: Some(promotions),
+                tickets: Some(TicketCounts {
+                    total: r.tickets_total,
+                    available: r.tickets_available,
+                }),
+            })
+        })
+        .collect()
 }
 
-pub async fn get_game(pool: &AnyPool, game_pk: i64) -> Result<Option<Game>> {
+pub async fn get_game(pool: &AnyPool, game_pk: GamePk) -> Result<Option<Game>> {
     let sql = pg(&format!(
         "SELECT {GAME_COLUMNS} FROM games WHERE game_pk = ?"
     ));
@@ -86,7 +568,26 @@ pub async fn get_game(pool: &AnyPool, game_pk: i64) -> Result<Option<Game>> {
     Ok(game)
 }
 
-pub async fn get_promotions_for_game(pool: &AnyPool, game_pk: i64) -> Result<Vec<Promotion>> {
+/// Team `team_id`'s home games on a given `official_date` (YYYY-MM-DD); two
+/// rows for a double-header.
+pub async fn list_home_games_on_date(
+    pool: &AnyPool,
+    team_id: u32,
+    official_date: &str,
+) -> Result<Vec<Game>> {
+    let sql = pg(&format!(
+        "SELECT {GAME_COLUMNS} FROM games WHERE official_date = ? AND home_team_id = ? \
+         ORDER BY game_number"
+    ));
+    let games = sqlx::query_as::<_, Game>(&sql)
+        .bind(official_date)
+        .bind(i64::from(team_id))
+        .fetch_all(pool)
+        .await?;
+    Ok(games)
+}
+
+pub async fn get_promotions_for_game(pool: &AnyPool, game_pk: GamePk) -> Result<Vec<Promotion>> {
     let sql = pg(
         "SELECT offer_id, game_pk, name, offer_type, description, distribution, \
             presented_by, alt_page_url, ticket_link, thumbnail_url, image_url, display_order \
@@ -99,26 +600,266 @@ pub async fn get_promotions_for_game(pool: &AnyPool, game_pk: i64) -> Result<Vec
     Ok(promos)
 }
 
-pub async fn upsert_promotion(pool: &AnyPool, promo: &Promotion) -> Result<()> {
+/// Filters for [`search_promotions`]. Text filters are case-insensitive
+/// substrings; the default lists every promotion in game order.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct PromotionFilter {
+    pub name: Option<String>,
+    /// Kind of promotion, `type` in query strings. MLB files most giveaways
+    /// under a generic "Giveaway", so the name is searched too: `bobblehead`
+    /// finds bobblehead nights.
+    #[serde(rename = "type")]
+    pub offer_type: Option<String>,
+    /// Who gets it, e.g. "first 20,000 fans".
+    pub distribution: Option<String>,
+    /// Season (year) of the game.
+    pub season: Option<String>,
+    /// Month of the game's `official_date` (1-12).
+    pub month: Option<u32>,
+    /// Earliest `official_date` (YYYY-MM-DD), inclusive.
+    pub from: Option<String>,
+}
+
+pub async fn search_promotions(
+    pool: &AnyPool,
+    filter: &PromotionFilter,
+) -> Result<Vec<PromotionListing>> {
+    let mut conds = Vec::new();
+    let mut args = Vec::new();
+    if let Some(name) = &filter.name {
+        conds.push("LOWER(p.name) LIKE ?");
+        args.push(format!("%{}%", name.to_lowercase()));
+    }
+    if let Some(kind) = &filter.offer_type {
+        let pattern = format!("%{}%", kind.to_lowercase());
+        conds.push("(LOWER(p.offer_type) LIKE ? OR LOWER(p.name) LIKE ?)");
+        args.push(pattern.clone());
+        args.push(pattern);
+    }
+    if let Some(distribution) = &filter.distribution {
+        conds.push("LOWER(p.distribution) LIKE ?");
+        args.push(format!("%{}%", distribution.to_lowercase()));
+    }
+    if let Some(season) = &filter.season {
+        conds.push("g.season = ?");
+        args.push(season.clone());
+    }
+    if let Some(m) = filter.month {
+        conds.push("g.official_date LIKE ?");
+        args.push(format!("%-{m:02}-%"));
+    }
+    if let Some(from) = &filter.from {
+        conds.push("g.official_date >= ?");
+        args.push(from.clone());
+    }
+    let where_sql = if conds.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conds.join(" AND "))
+    };
+    let sql = pg(&format!(
+        "SELECT p.offer_id, p.game_pk, g.official_date, g.away_team_name, g.home_team_name, \
+            p.name, p.offer_type, p.description, p.distribution, p.presented_by \
+         FROM promotions p \
+         JOIN games g ON g.game_pk = p.game_pk{where_sql} \
+         ORDER BY g.game_date, p.display_order"
+    ));
+    let mut query = sqlx::query_as::<_, PromotionListing>(&sql);
+    for arg in args {
+        query = query.bind(arg);
+    }
+    timed("search_promotions", query.fetch_all(pool)).await
+}
+
+/// Games a promotion runs at (usually one).
+pub async fn promotion_game_pks(pool: &AnyPool, offer_id: i64) -> Result<Vec<GamePk>> {
+    let sql = pg("SELECT game_pk FROM promotions WHERE offer_id = ? ORDER BY game_pk");
+    let rows = sqlx::query_scalar::<_, GamePk>(&sql)
+        .bind(offer_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+/// A promotion by offer id. A promotion running at several games has a
+/// row for each; they share their images, so the first will do.
+pub async fn get_promotion(pool: &AnyPool, offer_id: i64) -> Result<Option<Promotion>> {
     let sql = pg(
-        "INSERT INTO promotions (offer_id, game_pk, name, offer_type, description, distribution, \
-            presented_by, alt_page_url, ticket_link, thumbnail_url, image_url, display_order) \
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
-         ON CONFLICT(offer_id, game_pk) DO UPDATE SET \
-            name = excluded.name, \
-            offer_type = excluded.offer_type, \
-            description = excluded.description, \
-            distribution = excluded.distribution, \
-            presented_by = excluded.presented_by, \
-            alt_page_url = excluded.alt_page_url, \
-            ticket_link = excluded.ticket_link, \
-            thumbnail_url = excluded.thumbnail_url, \
-            image_url = excluded.image_url, \
-            display_order = excluded.display_order, \
-            updated_at = CURRENT_TIMESTAMP",
+        "SELECT offer_id, game_pk, name, offer_type, description, distribution, \
+            presented_by, alt_page_url, ticket_link, thumbnail_url, image_url, display_order \
+         FROM promotions WHERE offer_id = ? ORDER BY game_pk LIMIT 1",
     );
-    sqlx::query(&sql)
-        .bind(promo.offer_id)
+    let promo = sqlx::query_as::<_, Promotion>(&sql)
+        .bind(offer_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(promo)
+}
+
+// --- Promotion interest ---
+
+/// Flag a member's interest in a promotion. Returns false if already flagged.
+pub async fn add_promotion_interest(
+    pool: &AnyPool,
+    user_id: UserId,
+    offer_id: i64,
+    game_pk: GamePk,
+) -> Result<bool> {
+    let sql = pg(
+        "INSERT INTO promotion_interests (user_id, offer_id, game_pk) VALUES (?, ?, ?) \
+         ON CONFLICT DO NOTHING",
+    );
+    let result = sqlx::query(&sql)
+        .bind(user_id)
+        .bind(offer_id)
+        .bind(game_pk)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn remove_promotion_interest(
+    pool: &AnyPool,
+    user_id: UserId,
+    offer_id: i64,
+    game_pk: GamePk,
+) -> Result<bool> {
+    let sql =
+        pg("DELETE FROM promotion_interests WHERE user_id = ? AND offer_id = ? AND game_pk = ?");
+    let result = sqlx::query(&sql)
+        .bind(user_id)
+        .bind(offer_id)
+        .bind(game_pk)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// A member's promotion interests in game order. `suggest_request` is set for
+/// games on or after `today` (YYYY-MM-DD) that still have available seats and
+/// no pending or approved request from the member.
+pub async fn list_promotion_interests_for_user(
+    pool: &AnyPool,
+    user_id: UserId,
+    today: &str,
+) -> Result<Vec<PromotionInterest>> {
+    let sql = pg(
+        "SELECT pi.offer_id, pi.game_pk, p.name, g.official_date, g.away_team_name, \
+                COALESCE(r.has_request, 0) AS has_request, \
+                CASE WHEN g.official_date >= ? \
+                      AND COALESCE(r.has_request, 0) = 0 \
+                      AND EXISTS (SELECT 1 FROM game_tickets t \
+                                  WHERE t.game_pk = pi.game_pk AND t.status = 'available') \
+                     THEN 1 ELSE 0 END AS suggest_request \
+         FROM promotion_interests pi \
+         JOIN promotions p ON p.offer_id = pi.offer_id AND p.game_pk = pi.game_pk \
+         JOIN games g ON g.game_pk = pi.game_pk \
+         LEFT JOIN ( \
+             SELECT user_id, game_pk, 1 AS has_request FROM ticket_requests \
+             WHERE status IN ('pending', 'approved') \
+         ) r ON r.user_id = pi.user_id AND r.game_pk = pi.game_pk \
+         WHERE pi.user_id = ? \
+         ORDER BY g.game_date, p.display_order",
+    );
+    let rows = sqlx::query_as::<_, PromotionInterest>(&sql)
+        .bind(today)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+/// Interest per promotion, most wanted first.
+pub async fn promotion_interest_counts(pool: &AnyPool) -> Result<Vec<PromotionInterestCount>> {
+    let sql = "SELECT pi.offer_id, pi.game_pk, p.name, g.official_date, g.away_team_name, \
+                COUNT(*) AS interested, \
+                SUM(CASE WHEN EXISTS (SELECT 1 FROM ticket_requests r \
+                                      WHERE r.user_id = pi.user_id AND r.game_pk = pi.game_pk \
+                                        AND r.status IN ('pending', 'approved')) \
+                         THEN 1 ELSE 0 END) AS requested \
+         FROM promotion_interests pi \
+         JOIN promotions p ON p.offer_id = pi.offer_id AND p.game_pk = pi.game_pk \
+         JOIN games g ON g.game_pk = pi.game_pk \
+         GROUP BY pi.offer_id, pi.game_pk, p.name, g.official_date, g.away_team_name, g.game_date \
+         ORDER BY interested DESC, g.game_date";
+    let rows = sqlx::query_as::<_, PromotionInterestCount>(sql)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+const PROMOTION_COLUMNS: &str = "offer_id, game_pk, name, offer_type, description, distribution, \
+    presented_by, alt_page_url, ticket_link, thumbnail_url, image_url, display_order";
+
+/// Rewrites only promotions whose data changed, so re-scraping a stable
+/// schedule leaves `updated_at` alone. Nullable columns compare NULLs as
+/// equal to each other (a portable `IS DISTINCT FROM`). `updated_at` comes
+/// first because MySQL re-checks the condition after each assignment.
+const PROMOTION_UPSERT_CONFLICT: &str = " ON CONFLICT(offer_id, game_pk) DO UPDATE SET \
+    updated_at = CURRENT_TIMESTAMP, \
+    name = excluded.name, \
+    offer_type = excluded.offer_type, \
+    description = excluded.description, \
+    distribution = excluded.distribution, \
+    presented_by = excluded.presented_by, \
+    alt_page_url = excluded.alt_page_url, \
+    ticket_link = excluded.ticket_link, \
+    thumbnail_url = excluded.thumbnail_url, \
+    image_url = excluded.image_url, \
+    display_order = excluded.display_order \
+    WHERE promotions.name <> excluded.name \
+    OR promotions.display_order <> excluded.display_order \
+    OR promotions.offer_type <> excluded.offer_type OR (promotions.offer_type IS NULL) <> (excluded.offer_type IS NULL) \
+    OR promotions.description <> excluded.description OR (promotions.description IS NULL) <> (excluded.description IS NULL) \
+    OR promotions.distribution <> excluded.distribution OR (promotions.distribution IS NULL) <> (excluded.distribution IS NULL) \
+    OR promotions.presented_by <> excluded.presented_by OR (promotions.presented_by IS NULL) <> (excluded.presented_by IS NULL) \
+    OR promotions.alt_page_url <> excluded.alt_page_url OR (promotions.alt_page_url IS NULL) <> (excluded.alt_page_url IS NULL) \
+    OR promotions.ticket_link <> excluded.ticket_link OR (promotions.ticket_link IS NULL) <> (excluded.ticket_link IS NULL) \
+    OR promotions.thumbnail_url <> excluded.thumbnail_url OR (promotions.thumbnail_url IS NULL) <> (excluded.thumbnail_url IS NULL) \
+    OR promotions.image_url <> excluded.image_url OR (promotions.image_url IS NULL) <> (excluded.image_url IS NULL)";
+
+/// Schedule scrapes carry no linescore, so `current_inning` and
+/// `inning_state` are left for [`update_live_game`] to maintain.
+/// Pitchers, weather and broadcasts are only sent when hydrated, so a
+/// scrape without them keeps what an earlier one stored. `rescheduled_to`
+/// is only set by [`postponements::reconcile`].
+const GAME_UPSERT_CONFLICT: &str = " ON CONFLICT(game_pk) DO UPDATE SET \
+    game_guid = excluded.game_guid, \
+    game_date = excluded.game_date, \
+    official_date = excluded.official_date, \
+    status_abstract = excluded.status_abstract, \
+    status_detailed = excluded.status_detailed, \
+    status_code = excluded.status_code, \
+    start_time_tbd = excluded.start_time_tbd, \
+    away_score = excluded.away_score, \
+    away_is_winner = excluded.away_is_winner, \
+    home_score = excluded.home_score, \
+    home_is_winner = excluded.home_is_winner, \
+    day_night = excluded.day_night, \
+    is_tie = excluded.is_tie, \
+    away_probable_pitcher = COALESCE(excluded.away_probable_pitcher, games.away_probable_pitcher), \
+    home_probable_pitcher = COALESCE(excluded.home_probable_pitcher, games.home_probable_pitcher), \
+    weather_condition = COALESCE(excluded.weather_condition, games.weather_condition), \
+    weather_temp = COALESCE(excluded.weather_temp, games.weather_temp), \
+    weather_wind = COALESCE(excluded.weather_wind, games.weather_wind), \
+    tv_broadcasts = COALESCE(excluded.tv_broadcasts, games.tv_broadcasts), \
+    reschedule_date = excluded.reschedule_date, \
+    reschedule_official_date = excluded.reschedule_official_date, \
+    updated_at = CURRENT_TIMESTAMP";
+
+const GAME_COLUMN_COUNT: usize = 39;
+const PROMOTION_COLUMN_COUNT: usize = 12;
+
+type AnyQuery<'q> = sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>>;
+
+/// `(?, ?, ...), (?, ?, ...)` for a multi-row INSERT.
+fn values_placeholders(rows: usize, columns: usize) -> String {
+    let row = format!("({})", vec!["?"; columns].join(", "));
+    vec![row; rows].join(", ")
+}
+
+fn bind_promotion<'q>(q: AnyQuery<'q>, promo: &'q Promotion) -> AnyQuery<'q> {
+    q.bind(promo.offer_id)
         .bind(promo.game_pk)
         .bind(&promo.name)
         .bind(&promo.offer_type)
@@ -130,38 +871,10 @@ pub async fn upsert_promotion(pool: &AnyPool, promo: &Promotion) -> Result<()> {
         .bind(&promo.thumbnail_url)
         .bind(&promo.image_url)
         .bind(promo.display_order)
-        .execute(pool)
-        .await?;
-    Ok(())
 }
 
-pub async fn upsert_game(pool: &AnyPool, game: &Game) -> Result<()> {
-    let sql = pg(
-        "INSERT INTO games (game_pk, game_guid, game_type, season, game_date, official_date, \
-            status_abstract, status_detailed, status_code, start_time_tbd, \
-            away_team_id, away_team_name, away_score, away_is_winner, \
-            home_team_id, home_team_name, home_score, home_is_winner, \
-            venue_id, venue_name, day_night, series_description, \
-            series_game_number, games_in_series, double_header, game_number, \
-            scheduled_innings, is_tie) \
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
-         ON CONFLICT(game_pk) DO UPDATE SET \
-            game_guid = excluded.game_guid, \
-            game_date = excluded.game_date, \
-            status_abstract = excluded.status_abstract, \
-            status_detailed = excluded.status_detailed, \
-            status_code = excluded.status_code, \
-            start_time_tbd = excluded.start_time_tbd, \
-            away_score = excluded.away_score, \
-            away_is_winner = excluded.away_is_winner, \
-            home_score = excluded.home_score, \
-            home_is_winner = excluded.home_is_winner, \
-            day_night = excluded.day_night, \
-            is_tie = excluded.is_tie, \
-            updated_at = CURRENT_TIMESTAMP",
-    );
-    sqlx::query(&sql)
-        .bind(game.game_pk)
+fn bind_game<'q>(q: AnyQuery<'q>, game: &'q Game) -> AnyQuery<'q> {
+    q.bind(game.game_pk)
         .bind(&game.game_guid)
         .bind(&game.game_type)
         .bind(&game.season)
@@ -189,13 +902,180 @@ pub async fn upsert_game(pool: &AnyPool, game: &Game) -> Result<()> {
         .bind(game.game_number)
         .bind(game.scheduled_innings)
         .bind(game.is_tie)
+        .bind(game.current_inning)
+        .bind(&game.inning_state)
+        .bind(&game.away_probable_pitcher)
+        .bind(&game.home_probable_pitcher)
+        .bind(&game.weather_condition)
+        .bind(&game.weather_temp)
+        .bind(&game.weather_wind)
+        .bind(&game.tv_broadcasts)
+        .bind(&game.reschedule_date)
+        .bind(&game.reschedule_official_date)
+        .bind(game.rescheduled_to)
+}
+
+pub async fn upsert_promotion(pool: &AnyPool, promo: &Promotion) -> Result<()> {
+    let sql = pg(&format!(
+        "INSERT INTO promotions ({PROMOTION_COLUMNS}) VALUES {}{PROMOTION_UPSERT_CONFLICT}",
+        values_placeholders(1, PROMOTION_COLUMN_COUNT)
+    ));
+    bind_promotion(sqlx::query(&sql), promo)
         .execute(pool)
         .await?;
     Ok(())
 }
 
+pub async fn upsert_game(pool: &AnyPool, game: &Game) -> Result<()> {
+    let sql = pg(&format!(
+        "INSERT INTO games ({GAME_COLUMNS}) VALUES {}{GAME_UPSERT_CONFLICT}",
+        values_placeholders(1, GAME_COLUMN_COUNT)
+    ));
+    bind_game(sqlx::query(&sql), game).execute(pool).await?;
+    Ok(())
+}
+
+/// Apply a live linescore update to its game. Returns false when the game
+/// isn't in the table (e.g. an opponent's game, or not yet scraped).
+pub async fn update_live_game(pool: &AnyPool, live: &LiveGame) -> Result<bool> {
+    let sql = pg(
+        "UPDATE games SET status_abstract = ?, status_detailed = ?, status_code = ?, \
+                away_score = ?, away_is_winner = ?, home_score = ?, home_is_winner = ?, \
+                current_inning = ?, inning_state = ?, updated_at = CURRENT_TIMESTAMP \
+         WHERE game_pk = ?",
+    );
+    let result = sqlx::query(&sql)
+        .bind(&live.status_abstract)
+        .bind(&live.status_detailed)
+        .bind(&live.status_code)
+        .bind(live.away_score)
+        .bind(live.away_is_winner)
+        .bind(live.home_score)
+        .bind(live.home_is_winner)
+        .bind(live.current_inning)
+        .bind(&live.inning_state)
+        .bind(live.game_pk)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// --- Schedule import ---
+
+/// Rows per multi-row INSERT; keeps bind counts well under SQLite's limit.
+const SCHEDULE_BATCH_ROWS: usize = 25;
+
+/// Row counts written by [`upsert_schedule`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScheduleUpsert {
+    pub games: usize,
+    pub promotions: usize,
+    /// Promotions inserted or changed; the rest were already up to date.
+    pub promotions_changed: u64,
+    /// Tickets created.
+    pub tickets: u64,
+    /// (game, seat) pairs that already had a ticket.
+    pub tickets_skipped: u64,
+}
+
+/// Upsert a full schedule and generate tickets in one transaction.
+///
+/// Games and promotions are written with multi-row INSERTs. If anything fails
+/// the transaction rolls back, so the schedule is never left half-updated.
+/// Tickets are only generated for team `team_id`'s home games of the
+/// `ticketed` game types.
+pub async fn upsert_schedule(
+    pool: &AnyPool,
+    data: &ScheduleData,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<ScheduleUpsert> {
+    let mut tx = pool.begin().await?;
+    let upsert = upsert_schedule_with(&mut tx, data, team_id, ticketed).await?;
+    tx.commit().await?;
+    Ok(upsert)
+}
+
+/// [`upsert_schedule`] on `conn`, for a caller that writes more in the same
+/// transaction.
+pub(crate) async fn upsert_schedule_with(
+    conn: &mut sqlx::AnyConnection,
+    data: &ScheduleData,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<ScheduleUpsert> {
+    // Postgres rejects a multi-row upsert that touches the same key twice, so
+    // keep only the last copy of each game / promotion.
+    let mut games: Vec<&Game> = Vec::new();
+    let mut game_idx: HashMap<GamePk, usize> = HashMap::new();
+    for g in &data.games {
+        match game_idx.get(&g.game_pk) {
+            Some(&i) => games[i] = g,
+            None => {
+                game_idx.insert(g.game_pk, games.len());
+                games.push(g);
+            }
+        }
+    }
+    let mut promotions: Vec<&Promotion> = Vec::new();
+    let mut promo_idx: HashMap<(i64, GamePk), usize> = HashMap::new();
+    for p in &data.promotions {
+        match promo_idx.get(&(p.offer_id, p.game_pk)) {
+            Some(&i) => promotions[i] = p,
+            None => {
+                promo_idx.insert((p.offer_id, p.game_pk), promotions.len());
+                promotions.push(p);
+            }
+        }
+    }
+
+    for chunk in games.chunks(SCHEDULE_BATCH_ROWS) {
+        let sql = pg(&format!(
+            "INSERT INTO games ({GAME_COLUMNS}) VALUES {}{GAME_UPSERT_CONFLICT}",
+            values_placeholders(chunk.len(), GAME_COLUMN_COUNT)
+        ));
+        let mut q = sqlx::query(&sql);
+        for g in chunk {
+            q = bind_game(q, g);
+        }
+        q.execute(&mut *conn).await?;
+    }
+
+    let mut promotions_changed = 0;
+    for chunk in promotions.chunks(SCHEDULE_BATCH_ROWS) {
+        let sql = pg(&format!(
+            "INSERT INTO promotions ({PROMOTION_COLUMNS}) VALUES {}{PROMOTION_UPSERT_CONFLICT}",
+            values_placeholders(chunk.len(), PROMOTION_COLUMN_COUNT)
+        ));
+        let mut q = sqlx::query(&sql);
+        for p in chunk {
+            q = bind_promotion(q, p);
+        }
+        promotions_changed += q.execute(&mut *conn).await?.rows_affected();
+    }
+
+    let tickets = generate_tickets_for_all_seats_with(conn, team_id, ticketed).await?;
+
+    info!(
+        "Schedule upserted: {} games, {} promotions ({} changed), {} tickets ({} already existed)",
+        games.len(),
+        promotions.len(),
+        promotions_changed,
+        tickets.created.len(),
+        tickets.skipped
+    );
+    Ok(ScheduleUpsert {
+        games: games.len(),
+        promotions: promotions.len(),
+        promotions_changed,
+        tickets: tickets.created.len() as u64,
+        tickets_skipped: tickets.skipped,
+    })
+}
+
 // --- Seats ---
 
+/// Add a seat. Re-adding a seat that was soft-deleted restores it.
 pub async fn add_seat(
     pool: &AnyPool,
     section: &str,
@@ -203,29 +1083,56 @@ pub async fn add_seat(
     seat: &str,
     notes: Option<&str>,
 ) -> Result<Seat> {
-    let sql = pg(
+    let result = compat::insert_returning::<Seat>(
+        &mut *pool.acquire().await?,
         "INSERT INTO seats (section, row, seat, notes) VALUES (?, ?, ?, ?) \
-         RETURNING id, section, row, seat, notes",
-    );
-    let result = sqlx::query_as::<_, Seat>(&sql)
-        .bind(section)
-        .bind(row)
-        .bind(seat)
-        .bind(notes)
-        .fetch_one(pool)
-        .await?;
-    Ok(result)
+         ON CONFLICT(section, row, seat) DO UPDATE SET \
+            notes = excluded.notes, \
+            updated_at = CURRENT_TIMESTAMP, \
+            deleted_at = NULL \
+         WHERE seats.deleted_at IS NOT NULL",
+        "id, section, row, seat, notes, face_value",
+        &[
+            Arg::Text(section),
+            Arg::Text(row),
+            Arg::Text(seat),
+            Arg::OptText(notes),
+        ],
+        "SELECT id, section, row, seat, notes, face_value FROM seats \
+         WHERE section = ? AND row = ? AND seat = ?",
+        &[Arg::Text(section), Arg::Text(row), Arg::Text(seat)],
+    )
+    .await?;
+    match result {
+        Some(s) => Ok(s),
+        None => bail!("Seat already exists: Section {section} Row {row} Seat {seat}"),
+    }
 }
 
 pub async fn list_seats(pool: &AnyPool) -> Result<Vec<Seat>> {
     let seats = sqlx::query_as::<_, Seat>(
-        "SELECT id, section, row, seat, notes FROM seats ORDER BY section, row, seat",
+        "SELECT id, section, row, seat, notes, face_value FROM seats \
+         WHERE deleted_at IS NULL ORDER BY section, row, seat",
     )
     .fetch_all(pool)
     .await?;
     Ok(seats)
 }
 
+/// Active seats in one section/row.
+pub async fn list_seats_in_group(pool: &AnyPool, section: &str, row: &str) -> Result<Vec<Seat>> {
+    let sql = pg(
+        "SELECT id, section, row, seat, notes, face_value FROM seats \
+         WHERE section = ? AND row = ? AND deleted_at IS NULL ORDER BY seat",
+    );
+    let seats = sqlx::query_as::<_, Seat>(&sql)
+        .bind(section)
+        .bind(row)
+        .fetch_all(pool)
+        .await?;
+    Ok(seats)
+}
+
 pub async fn update_seat_group_notes(
     pool: &AnyPool,
     section: &str,
@@ -233,7 +1140,8 @@ pub async fn update_seat_group_notes(
     notes: Option<&str>,
 ) -> Result<u64> {
     let sql = pg(
-        "UPDATE seats SET notes = ?, updated_at = CURRENT_TIMESTAMP WHERE section = ? AND row = ?",
+        "UPDATE seats SET notes = ?, updated_at = CURRENT_TIMESTAMP \
+         WHERE section = ? AND row = ? AND deleted_at IS NULL",
     );
     let result = sqlx::query(&sql)
         .bind(notes)
@@ -244,44 +1152,263 @@ pub async fn update_seat_group_notes(
     Ok(result.rows_affected())
 }
 
-pub async fn delete_seat(pool: &AnyPool, seat_id: i64) -> Result<bool> {
-    let sql1 = pg("DELETE FROM game_tickets WHERE seat_id = ?");
-    sqlx::query(&sql1).bind(seat_id).execute(pool).await?;
-    let sql2 = pg("DELETE FROM seats WHERE id = ?");
-    let result = sqlx::query(&sql2).bind(seat_id).execute(pool).await?;
-    Ok(result.rows_affected() > 0)
+/// Set the per-game face value (cents) of every seat in a section/row.
+pub async fn update_seat_group_face_value(
+    pool: &AnyPool,
+    section: &str,
+    row: &str,
+    face_value: Option<i64>,
+) -> Result<u64> {
+    let sql = pg(
+        "UPDATE seats SET face_value = ?, updated_at = CURRENT_TIMESTAMP \
+         WHERE section = ? AND row = ? AND deleted_at IS NULL",
+    );
+    let result = sqlx::query(&sql)
+        .bind(face_value)
+        .bind(section)
+        .bind(row)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
 }
 
-// --- Game Tickets ---
-
-const GIANTS_TEAM_NAME: &str = "San Francisco Giants";
-
-pub async fn generate_tickets_for_seat(pool: &AnyPool, seat_id: i64) -> Result<u64> {
-    let sql = pg("INSERT INTO game_tickets (game_pk, seat_id, status) \
-         SELECT game_pk, ?, 'available' FROM games WHERE home_team_name = ? \
-         ON CONFLICT DO NOTHING");
+/// Override one game ticket's cost (cents); `None` falls back to the seat's
+/// face value.
+pub async fn set_ticket_cost(
+    pool: &AnyPool,
+    ticket_id: TicketId,
+    cost: Option<i64>,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_ticket_open(&mut *tx, ticket_id).await?;
+    let sql = pg("UPDATE game_tickets SET cost = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?");
     let result = sqlx::query(&sql)
-        .bind(seat_id)
-        .bind(GIANTS_TEAM_NAME)
-        .execute(pool)
+        .bind(cost)
+        .bind(ticket_id)
+        .execute(&mut *tx)
         .await?;
-    Ok(result.rows_affected())
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
 }
 
-pub async fn generate_tickets_for_all_seats(pool: &AnyPool) -> Result<u64> {
-    let sql = pg("INSERT INTO game_tickets (game_pk, seat_id, status) \
-         SELECT g.game_pk, s.id, 'available' \
-         FROM games g CROSS JOIN seats s \
-         WHERE g.home_team_name = ? \
-         ON CONFLICT DO NOTHING");
+/// Record what a game ticket was resold for (cents), or clear it with `None`.
+pub async fn set_ticket_resale(
+    pool: &AnyPool,
+    ticket_id: TicketId,
+    amount: Option<i64>,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_ticket_open(&mut *tx, ticket_id).await?;
+    let sql = pg(
+        "UPDATE game_tickets SET resale_amount = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    );
     let result = sqlx::query(&sql)
-        .bind(GIANTS_TEAM_NAME)
-        .execute(pool)
+        .bind(amount)
+        .bind(ticket_id)
+        .execute(&mut *tx)
         .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// How many game tickets [`delete_seat`] would delete with the seat.
+pub async fn count_seat_tickets(pool: &AnyPool, seat_id: SeatId) -> Result<i64> {
+    let sql = pg("SELECT COUNT(*) FROM game_tickets WHERE seat_id = ?");
+    Ok(sqlx::query_scalar(&sql)
+        .bind(seat_id)
+        .fetch_one(pool)
+        .await?)
+}
+
+pub async fn delete_seat(pool: &AnyPool, seat_id: SeatId) -> Result<bool> {
+    let sql1 = pg("DELETE FROM game_tickets WHERE seat_id = ?");
+    sqlx::query(&sql1).bind(seat_id).execute(pool).await?;
+    let sql2 = pg("DELETE FROM seats WHERE id = ?");
+    let result = sqlx::query(&sql2).bind(seat_id).execute(pool).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Soft-delete every active seat in a section/row. Each seat's available
+/// tickets are dropped; tickets already assigned or otherwise handed out stay
+/// so history and the holder's games are unaffected.
+pub async fn delete_seat_group(
+    pool: &AnyPool,
+    section: &str,
+    row: &str,
+) -> Result<Vec<SeatDeleteOutcome>> {
+    let seats = list_seats_in_group(pool, section, row).await?;
+    let mut tx = pool.begin().await?;
+    let mut outcomes = Vec::with_capacity(seats.len());
+    for seat in seats {
+        let sql = pg("DELETE FROM game_tickets WHERE seat_id = ? AND status = 'available'");
+        let removed = sqlx::query(&sql)
+            .bind(seat.id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        let sql = pg("SELECT COUNT(*) FROM game_tickets WHERE seat_id = ?");
+        let kept: i64 = sqlx::query_scalar(&sql)
+            .bind(seat.id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let sql = pg(
+            "UPDATE seats SET deleted_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP \
+             WHERE id = ?",
+        );
+        sqlx::query(&sql).bind(seat.id).execute(&mut *tx).await?;
+        outcomes.push(SeatDeleteOutcome {
+            seat,
+            tickets_removed: removed,
+            tickets_kept: kept as u64,
+        });
+    }
+    tx.commit().await?;
+    Ok(outcomes)
+}
+
+/// Create any missing home-game tickets for every active seat in a section/row.
+pub async fn regenerate_seat_group_tickets(
+    pool: &AnyPool,
+    section: &str,
+    row: &str,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<Vec<SeatTicketOutcome>> {
+    let seats = list_seats_in_group(pool, section, row).await?;
+    let mut outcomes = Vec::with_capacity(seats.len());
+    for seat in seats {
+        let generated = generate_tickets_for_seat(pool, seat.id, team_id, ticketed).await?;
+        outcomes.push(SeatTicketOutcome {
+            seat,
+            tickets_generated: generated,
+        });
+    }
+    Ok(outcomes)
+}
+
+// --- Game Tickets ---
+
+/// `game_type IN (...)` placeholders for `ticketed`; callers bail out early
+/// when it's empty.
+pub(crate) fn game_type_placeholders(ticketed: &[GameType]) -> String {
+    vec!["?"; ticketed.len()].join(", ")
+}
+
+/// Condition on `g` leaving out the second game of a traditional
+/// double-header, which the first game's tickets admit to (see
+/// [`gtm_models::DoubleHeader::ticketed`]).
+pub(crate) const OWN_ADMISSION: &str = "NOT (g.double_header = 'Y' AND g.game_number > 1)";
+
+/// Create tickets for one seat at every team `team_id` home game of the
+/// `ticketed` types, skipping the second game of traditional double-headers.
+pub async fn generate_tickets_for_seat(
+    pool: &AnyPool,
+    seat_id: SeatId,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<u64> {
+    if ticketed.is_empty() {
+        return Ok(0);
+    }
+    let sql = pg(&format!(
+        "INSERT INTO game_tickets (game_pk, seat_id, status) \
+         SELECT g.game_pk, ?, 'available' FROM games g \
+         WHERE g.home_team_id = ? AND g.game_type IN ({}) AND {OWN_ADMISSION} \
+           AND g.season NOT IN (SELECT season FROM closed_seasons) \
+         ON CONFLICT DO NOTHING",
+        game_type_placeholders(ticketed)
+    ));
+    let mut query = sqlx::query(&sql).bind(seat_id).bind(i64::from(team_id));
+    for t in ticketed {
+        query = query.bind(t.code());
+    }
+    let result = query.execute(pool).await?;
     Ok(result.rows_affected())
 }
 
-pub async fn list_tickets_for_game(pool: &AnyPool, game_pk: i64) -> Result<Vec<GameTicketDetail>> {
+/// Outcome of [`generate_tickets_for_all_seats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TicketGeneration {
+    /// (game, seat) pairs given a ticket by this run.
+    pub created: Vec<(GamePk, SeatId)>,
+    /// Pairs that already had one.
+    pub skipped: u64,
+}
+
+pub async fn generate_tickets_for_all_seats(
+    pool: &AnyPool,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<TicketGeneration> {
+    let mut conn = pool.acquire().await?;
+    generate_tickets_for_all_seats_with(&mut conn, team_id, ticketed).await
+}
+
+/// One INSERT covers every active seat × ticketed home game; `RETURNING`
+/// reports the pairs it created, and the rest of the cross join is counted
+/// as skipped. Without RETURNING (see [`compat`]) the missing pairs are
+/// selected first.
+async fn generate_tickets_for_all_seats_with(
+    conn: &mut sqlx::AnyConnection,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<TicketGeneration> {
+    if ticketed.is_empty() {
+        return Ok(TicketGeneration::default());
+    }
+    let pairs = format!(
+        "FROM games g CROSS JOIN seats s \
+         WHERE g.home_team_id = ? AND g.game_type IN ({}) AND s.deleted_at IS NULL \
+           AND {OWN_ADMISSION} AND g.season NOT IN (SELECT season FROM closed_seasons)",
+        game_type_placeholders(ticketed)
+    );
+    let insert = format!(
+        "INSERT INTO game_tickets (game_pk, seat_id, status) \
+         SELECT g.game_pk, s.id, 'available' {pairs} \
+         ON CONFLICT DO NOTHING"
+    );
+    let created = if compat::returning_supported() {
+        let sql = pg(&format!("{insert} RETURNING game_pk, seat_id"));
+        let mut query = sqlx::query_as::<_, (GamePk, SeatId)>(&sql).bind(i64::from(team_id));
+        for t in ticketed {
+            query = query.bind(t.code());
+        }
+        query.fetch_all(&mut *conn).await?
+    } else {
+        let sql = pg(&format!(
+            "SELECT g.game_pk, s.id {pairs} AND NOT EXISTS \
+             (SELECT 1 FROM game_tickets gt WHERE gt.game_pk = g.game_pk AND gt.seat_id = s.id)"
+        ));
+        let mut query = sqlx::query_as::<_, (GamePk, SeatId)>(&sql).bind(i64::from(team_id));
+        for t in ticketed {
+            query = query.bind(t.code());
+        }
+        let missing = query.fetch_all(&mut *conn).await?;
+        let sql = pg(&insert);
+        let mut query = sqlx::query(&sql).bind(i64::from(team_id));
+        for t in ticketed {
+            query = query.bind(t.code());
+        }
+        query.execute(&mut *conn).await?;
+        missing
+    };
+
+    let sql = pg(&format!("SELECT COUNT(*) {pairs}"));
+    let mut query = sqlx::query_scalar::<_, i64>(&sql).bind(i64::from(team_id));
+    for t in ticketed {
+        query = query.bind(t.code());
+    }
+    let total = query.fetch_one(&mut *conn).await?;
+    Ok(TicketGeneration {
+        skipped: (total as u64).saturating_sub(created.len() as u64),
+        created,
+    })
+}
+
+pub async fn list_tickets_for_game(
+    pool: &AnyPool,
+    game_pk: GamePk,
+) -> Result<Vec<GameTicketDetail>> {
     let sql = pg(
         "SELECT gt.id, gt.game_pk, gt.seat_id, s.section, s.row, s.seat, gt.status, gt.notes, gt.assigned_to \
          FROM game_tickets gt \
@@ -296,117 +1423,857 @@ pub async fn list_tickets_for_game(pool: &AnyPool, game_pk: i64) -> Result<Vec<G
     Ok(tickets)
 }
 
+pub async fn get_ticket(pool: &AnyPool, ticket_id: TicketId) -> Result<Option<GameTicketDetail>> {
+    let sql = pg(
+        "SELECT gt.id, gt.game_pk, gt.seat_id, s.section, s.row, s.seat, gt.status, gt.notes, gt.assigned_to \
+         FROM game_tickets gt \
+         JOIN seats s ON s.id = gt.seat_id \
+         WHERE gt.id = ?",
+    );
+    let ticket = sqlx::query_as::<_, GameTicketDetail>(&sql)
+        .bind(ticket_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(ticket)
+}
+
+/// A ticket status change [`TicketStatus::can_become`] doesn't allow.
+#[derive(Debug)]
+pub struct InvalidTransition {
+    pub ticket_id: TicketId,
+    /// The stored status, which may predate [`TicketStatus`].
+    pub from: String,
+    pub to: TicketStatus,
+}
+
+impl std::fmt::Display for InvalidTransition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Ticket {} can't go from {} to {}",
+            self.ticket_id, self.from, self.to
+        )
+    }
+}
+
+impl std::error::Error for InvalidTransition {}
+
+/// Set a ticket's status and notes. Fails with [`InvalidTransition`] unless
+/// the move is allowed; making an assigned ticket available clears its
+/// holder in the same statement.
 pub async fn update_ticket_status(
     pool: &AnyPool,
-    ticket_id: i64,
-    status: &str,
+    ticket_id: TicketId,
+    status: TicketStatus,
     notes: Option<&str>,
+    actor_id: Option<UserId>,
 ) -> Result<bool> {
-    let sql = pg(
-        "UPDATE game_tickets SET status = ?, notes = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
-    );
+    let mut tx = pool.begin().await?;
+    let Some((before, assigned_to)) = ticket_state(&mut *tx, ticket_id).await? else {
+        return Ok(false);
+    };
+    seasons::ensure_ticket_open(&mut *tx, ticket_id).await?;
+    let allowed = before
+        .parse::<TicketStatus>()
+        .is_ok_and(|from| from.can_become(status));
+    if !allowed {
+        return Err(InvalidTransition {
+            ticket_id,
+            from: before,
+            to: status,
+        }
+        .into());
+    }
+    // The status guard keeps a concurrent assign from being overwritten
+    let sql = pg("UPDATE game_tickets SET \
+            assigned_to = CASE WHEN ? = 'available' THEN NULL ELSE assigned_to END, \
+            status = ?, notes = ?, updated_at = CURRENT_TIMESTAMP \
+         WHERE id = ? AND status = ?");
     let result = sqlx::query(&sql)
-        .bind(status)
+        .bind(status.as_str())
+        .bind(status.as_str())
         .bind(notes)
         .bind(ticket_id)
-        .execute(pool)
+        .bind(&before)
+        .execute(&mut *tx)
         .await?;
-    Ok(result.rows_affected() > 0)
-}
-
-pub async fn ticket_summary_for_games(pool: &AnyPool) -> Result<Vec<(i64, i64, i64)>> {
-    let rows = sqlx::query_as::<_, (i64, i64, i64)>(
-        "SELECT game_pk, \
-                COUNT(*) as total, \
-                SUM(CASE WHEN status = 'available' THEN 1 ELSE 0 END) as available \
-         FROM game_tickets GROUP BY game_pk",
+    if result.rows_affected() == 0 {
+        bail!("Ticket {ticket_id} changed while it was being updated; try again");
+    }
+    audit::record(
+        &mut *tx,
+        &audit::AuditEvent {
+            actor_id,
+            action: audit::TICKET_STATUS,
+            game_ticket_id: Some(ticket_id),
+            user_id: assigned_to,
+            before: Some(&before),
+            after: Some(status.as_str()),
+            ..Default::default()
+        },
     )
-    .fetch_all(pool)
     .await?;
-    Ok(rows)
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Current `(status, assigned_to)` of a ticket.
+async fn ticket_state<'e, E>(
+    executor: E,
+    ticket_id: TicketId,
+) -> Result<Option<(String, Option<UserId>)>>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    let sql = pg("SELECT status, assigned_to FROM game_tickets WHERE id = ?");
+    let row = sqlx::query_as::<_, (String, Option<UserId>)>(&sql)
+        .bind(ticket_id)
+        .fetch_optional(executor)
+        .await?;
+    Ok(row)
+}
+
+/// Seat counts per game joined with the game's season, date, opponent and
+/// status. `season` keeps one season's games; `month` filters on
+/// `official_date`'s month; `from` (YYYY-MM-DD) drops earlier games.
+pub async fn ticket_summary_for_games(
+    pool: &AnyPool,
+    season: Option<&str>,
+    month: Option<u32>,
+    from: Option<&str>,
+) -> Result<Vec<TicketSummaryRow>> {
+    let mut conds = Vec::new();
+    let mut args = Vec::new();
+    if let Some(season) = season {
+        conds.push("g.season = ?");
+        args.push(season.to_string());
+    }
+    if let Some(m) = month {
+        conds.push("g.official_date LIKE ?");
+        args.push(format!("%-{m:02}-%"));
+    }
+    if let Some(from) = from {
+        conds.push("g.official_date >= ?");
+        args.push(from.to_string());
+    }
+    let where_sql = if conds.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conds.join(" AND "))
+    };
+    let sql = pg(&format!(
+        "SELECT g.game_pk, g.season, g.official_date, g.game_date, \
+                g.away_team_name AS opponent, g.status_abstract, \
+                g.double_header, g.game_number, t.total, t.available \
+         FROM games g \
+         JOIN ( \
+             SELECT game_pk, \
+                 COUNT(*) AS total, \
+                 SUM(CASE WHEN status = 'available' THEN 1 ELSE 0 END) AS available \
+             FROM game_tickets GROUP BY game_pk \
+         ) t ON t.game_pk = g.game_pk{where_sql} \
+         ORDER BY g.game_date, g.game_number, g.game_pk"
+    ));
+    let mut query = sqlx::query_as::<_, TicketSummaryRow>(&sql);
+    for arg in args {
+        query = query.bind(arg);
+    }
+    timed("ticket_summary", query.fetch_all(pool)).await
 }
 
 // --- Users ---
 
 pub async fn upsert_user(pool: &AnyPool, auth0_sub: &str, email: &str, name: &str) -> Result<User> {
-    let sql = pg(
+    compat::insert_returning::<User>(
+        &mut *pool.acquire().await?,
         "INSERT INTO users (auth0_sub, email, name) VALUES (?, ?, ?) \
          ON CONFLICT(auth0_sub) DO UPDATE SET \
             email = excluded.email, \
             name = excluded.name, \
-            updated_at = CURRENT_TIMESTAMP \
-         RETURNING id, auth0_sub, email, name",
-    );
+            updated_at = CURRENT_TIMESTAMP",
+        "id, auth0_sub, email, name",
+        &[Arg::Text(auth0_sub), Arg::Text(email), Arg::Text(name)],
+        "SELECT id, auth0_sub, email, name FROM users WHERE auth0_sub = ?",
+        &[Arg::Text(auth0_sub)],
+    )
+    .await?
+    .context("User upsert wrote no row")
+}
+
+pub async fn get_user(pool: &AnyPool, id: UserId) -> Result<Option<User>> {
+    let sql = pg("SELECT id, auth0_sub, email, name FROM users WHERE id = ?");
+    let user = sqlx::query_as::<_, User>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(user)
+}
+
+pub async fn get_user_by_sub(pool: &AnyPool, auth0_sub: &str) -> Result<Option<User>> {
+    let sql = pg("SELECT id, auth0_sub, email, name FROM users WHERE auth0_sub = ?");
     let user = sqlx::query_as::<_, User>(&sql)
         .bind(auth0_sub)
+        .fetch_optional(pool)
+        .await?;
+    Ok(user)
+}
+
+/// Look up a user by email, ignoring case (used to verify inbound email senders).
+pub async fn get_user_by_email(pool: &AnyPool, email: &str) -> Result<Option<User>> {
+    let sql = pg("SELECT id, auth0_sub, email, name FROM users WHERE LOWER(email) = LOWER(?)");
+    let user = sqlx::query_as::<_, User>(&sql)
         .bind(email)
-        .bind(name)
-        .fetch_one(pool)
+        .fetch_optional(pool)
         .await?;
     Ok(user)
 }
 
-pub async fn get_user_by_sub(pool: &AnyPool, auth0_sub: &str) -> Result<Option<User>> {
-    let sql = pg("SELECT id, auth0_sub, email, name FROM users WHERE auth0_sub = ?");
-    let user = sqlx::query_as::<_, User>(&sql)
-        .bind(auth0_sub)
-        .fetch_optional(pool)
-        .await?;
-    Ok(user)
+pub async fn list_users(pool: &AnyPool) -> Result<Vec<User>> {
+    let users =
+        sqlx::query_as::<_, User>("SELECT id, auth0_sub, email, name FROM users ORDER BY name")
+            .fetch_all(pool)
+            .await?;
+    Ok(users)
+}
+
+/// The language a member gets notifications in (a code like "es"), if
+/// they chose one.
+pub async fn get_user_locale(pool: &AnyPool, id: UserId) -> Result<Option<String>> {
+    let sql = pg("SELECT locale FROM users WHERE id = ?");
+    let locale = sqlx::query_scalar::<_, Option<String>>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(locale.flatten())
+}
+
+/// Every member who chose a language, for jobs notifying many at once.
+pub async fn list_user_locales(pool: &AnyPool) -> Result<HashMap<UserId, String>> {
+    let rows = sqlx::query_as::<_, (UserId, String)>(
+        "SELECT id, locale FROM users WHERE locale IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Set or clear (`None`) a member's notification language.
+pub async fn update_user_locale(pool: &AnyPool, id: UserId, locale: Option<&str>) -> Result<()> {
+    let sql = pg("UPDATE users SET locale = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?");
+    sqlx::query(&sql)
+        .bind(locale)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// --- User Phones (SMS) ---
+
+/// Maximum wrong codes accepted before a new code must be requested.
+const MAX_PHONE_VERIFY_ATTEMPTS: i64 = 5;
+
+pub async fn get_user_phone(pool: &AnyPool, user_id: UserId) -> Result<Option<UserPhone>> {
+    let sql = pg("SELECT user_id, phone, verified, sms_opt_in FROM user_phones WHERE user_id = ?");
+    let phone = sqlx::query_as::<_, UserPhone>(&sql)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(phone)
+}
+
+/// Set (or replace) a user's phone number. The number starts unverified with a
+/// fresh verification code; `expires_at` is an RFC 3339 UTC timestamp.
+pub async fn set_user_phone(
+    pool: &AnyPool,
+    user_id: UserId,
+    phone: &str,
+    code: &str,
+    expires_at: &str,
+) -> Result<UserPhone> {
+    compat::insert_returning::<UserPhone>(
+        &mut *pool.acquire().await?,
+        "INSERT INTO user_phones (user_id, phone, verified, verification_code, code_expires_at) \
+         VALUES (?, ?, 0, ?, ?) \
+         ON CONFLICT(user_id) DO UPDATE SET \
+            phone = excluded.phone, \
+            verified = 0, \
+            verification_code = excluded.verification_code, \
+            code_expires_at = excluded.code_expires_at, \
+            verify_attempts = 0, \
+            updated_at = CURRENT_TIMESTAMP",
+        "user_id, phone, verified, sms_opt_in",
+        &[
+            Arg::Int(user_id.0),
+            Arg::Text(phone),
+            Arg::Text(code),
+            Arg::Text(expires_at),
+        ],
+        "SELECT user_id, phone, verified, sms_opt_in FROM user_phones WHERE user_id = ?",
+        &[Arg::Int(user_id.0)],
+    )
+    .await?
+    .context("Phone upsert wrote no row")
+}
+
+/// Check a verification code. `now` is an RFC 3339 UTC timestamp compared
+/// against the stored expiry. Wrong codes count toward the attempt limit.
+pub async fn verify_user_phone(
+    pool: &AnyPool,
+    user_id: UserId,
+    code: &str,
+    now: &str,
+) -> Result<bool> {
+    let sql = pg(
+        "UPDATE user_phones SET verified = 1, verification_code = NULL, code_expires_at = NULL, \
+            updated_at = CURRENT_TIMESTAMP \
+         WHERE user_id = ? AND verification_code = ? AND code_expires_at > ? AND verify_attempts < ?",
+    );
+    let result = sqlx::query(&sql)
+        .bind(user_id)
+        .bind(code)
+        .bind(now)
+        .bind(MAX_PHONE_VERIFY_ATTEMPTS)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() > 0 {
+        return Ok(true);
+    }
+    let sql = pg(
+        "UPDATE user_phones SET verify_attempts = verify_attempts + 1 \
+         WHERE user_id = ? AND verification_code IS NOT NULL",
+    );
+    sqlx::query(&sql).bind(user_id).execute(pool).await?;
+    Ok(false)
+}
+
+pub async fn set_sms_opt_in(pool: &AnyPool, user_id: UserId, opt_in: bool) -> Result<bool> {
+    let sql = pg(
+        "UPDATE user_phones SET sms_opt_in = ?, updated_at = CURRENT_TIMESTAMP WHERE user_id = ?",
+    );
+    let result = sqlx::query(&sql)
+        .bind(if opt_in { 1i64 } else { 0 })
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn delete_user_phone(pool: &AnyPool, user_id: UserId) -> Result<bool> {
+    let sql = pg("DELETE FROM user_phones WHERE user_id = ?");
+    let result = sqlx::query(&sql).bind(user_id).execute(pool).await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Verified, opted-in phones — the only numbers SMS may be sent to.
+pub async fn list_sms_recipients(pool: &AnyPool) -> Result<Vec<UserPhone>> {
+    let phones = sqlx::query_as::<_, UserPhone>(
+        "SELECT user_id, phone, verified, sms_opt_in FROM user_phones \
+         WHERE verified = 1 AND sms_opt_in = 1 ORDER BY user_id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(phones)
+}
+
+// --- User Blackouts ---
+
+/// A member's blackout dates (YYYY-MM-DD), soonest first.
+pub async fn list_blackouts(pool: &AnyPool, user_id: UserId) -> Result<Vec<String>> {
+    let sql =
+        pg("SELECT blackout_date FROM user_blackouts WHERE user_id = ? ORDER BY blackout_date");
+    let dates = sqlx::query_scalar::<_, String>(&sql)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(dates)
+}
+
+/// Mark a date the member can't attend. Returns false if already marked.
+pub async fn add_blackout(pool: &AnyPool, user_id: UserId, date: &str) -> Result<bool> {
+    let sql = pg(
+        "INSERT INTO user_blackouts (user_id, blackout_date) VALUES (?, ?) \
+         ON CONFLICT DO NOTHING",
+    );
+    let result = sqlx::query(&sql)
+        .bind(user_id)
+        .bind(date)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn remove_blackout(pool: &AnyPool, user_id: UserId, date: &str) -> Result<bool> {
+    let sql = pg("DELETE FROM user_blackouts WHERE user_id = ? AND blackout_date = ?");
+    let result = sqlx::query(&sql)
+        .bind(user_id)
+        .bind(date)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+// --- Bulk-operation confirmations ---
+
+/// Record a confirmation token handed out by a bulk operation's dry run.
+/// `scope` identifies exactly what the dry run matched (e.g. the section and
+/// row) and `affected` how many rows it would touch.
+pub async fn create_bulk_confirmation(
+    pool: &AnyPool,
+    token: &str,
+    operation: &str,
+    scope: &str,
+    affected: i64,
+    expires_at: &str,
+) -> Result<()> {
+    let sql = pg(
+        "INSERT INTO bulk_confirmations (token, operation, scope, affected, expires_at) \
+         VALUES (?, ?, ?, ?, ?)",
+    );
+    sqlx::query(&sql)
+        .bind(token)
+        .bind(operation)
+        .bind(scope)
+        .bind(affected)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Consume a confirmation token, returning the affected count it was issued
+/// for if it matches `operation`/`scope` and hasn't expired. Tokens are
+/// single-use; expired ones are cleared as a side effect.
+pub async fn take_bulk_confirmation(
+    pool: &AnyPool,
+    token: &str,
+    operation: &str,
+    scope: &str,
+    now: &str,
+) -> Result<Option<i64>> {
+    let mut tx = pool.begin().await?;
+    let sql = pg("SELECT affected FROM bulk_confirmations \
+         WHERE token = ? AND operation = ? AND scope = ? AND expires_at > ?");
+    let affected: Option<i64> = sqlx::query_scalar(&sql)
+        .bind(token)
+        .bind(operation)
+        .bind(scope)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let delete_sql = pg("DELETE FROM bulk_confirmations WHERE token = ? OR expires_at <= ?");
+    sqlx::query(&delete_sql)
+        .bind(token)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(affected)
+}
+
+// --- Google Calendar ---
+
+/// Remember an OAuth `state` so the callback can be tied back to the member
+/// who started the flow.
+pub async fn create_oauth_state(
+    pool: &AnyPool,
+    state: &str,
+    user_id: UserId,
+    expires_at: &str,
+) -> Result<()> {
+    let sql = pg("INSERT INTO oauth_states (state, user_id, expires_at) VALUES (?, ?, ?)");
+    sqlx::query(&sql)
+        .bind(state)
+        .bind(user_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Consume an OAuth `state`, returning its user if it exists and hasn't
+/// expired. A state can only be used once.
+pub async fn take_oauth_state(pool: &AnyPool, state: &str, now: &str) -> Result<Option<UserId>> {
+    let mut tx = pool.begin().await?;
+    let sql = pg("SELECT user_id FROM oauth_states WHERE state = ? AND expires_at > ?");
+    let user_id: Option<UserId> = sqlx::query_scalar(&sql)
+        .bind(state)
+        .bind(now)
+        .fetch_optional(&mut *tx)
+        .await?;
+    let delete_sql = pg("DELETE FROM oauth_states WHERE state = ? OR expires_at <= ?");
+    sqlx::query(&delete_sql)
+        .bind(state)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(user_id)
+}
+
+pub async fn get_google_account(pool: &AnyPool, user_id: UserId) -> Result<Option<GoogleAccount>> {
+    let sql = pg(
+        "SELECT user_id, refresh_token, access_token, access_expires_at, calendar_id \
+         FROM google_accounts WHERE user_id = ?",
+    );
+    let account = sqlx::query_as::<_, GoogleAccount>(&sql)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(account)
+}
+
+pub async fn upsert_google_account(
+    pool: &AnyPool,
+    user_id: UserId,
+    refresh_token: &str,
+    access_token: &str,
+    access_expires_at: &str,
+) -> Result<()> {
+    let sql = pg(
+        "INSERT INTO google_accounts (user_id, refresh_token, access_token, access_expires_at) \
+         VALUES (?, ?, ?, ?) \
+         ON CONFLICT (user_id) DO UPDATE SET \
+            refresh_token = excluded.refresh_token, \
+            access_token = excluded.access_token, \
+            access_expires_at = excluded.access_expires_at, \
+            updated_at = CURRENT_TIMESTAMP",
+    );
+    sqlx::query(&sql)
+        .bind(user_id)
+        .bind(refresh_token)
+        .bind(access_token)
+        .bind(access_expires_at)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn update_google_access_token(
+    pool: &AnyPool,
+    user_id: UserId,
+    access_token: &str,
+    access_expires_at: &str,
+) -> Result<()> {
+    let sql = pg(
+        "UPDATE google_accounts SET access_token = ?, access_expires_at = ?, \
+            updated_at = CURRENT_TIMESTAMP \
+         WHERE user_id = ?",
+    );
+    sqlx::query(&sql)
+        .bind(access_token)
+        .bind(access_expires_at)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Unlink a member's Google account and forget its synced events.
+pub async fn delete_google_account(pool: &AnyPool, user_id: UserId) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let events_sql = pg("DELETE FROM google_calendar_events WHERE user_id = ?");
+    sqlx::query(&events_sql)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    let sql = pg("DELETE FROM google_accounts WHERE user_id = ?");
+    let result = sqlx::query(&sql).bind(user_id).execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn list_google_linked_user_ids(pool: &AnyPool) -> Result<Vec<UserId>> {
+    let ids = sqlx::query_scalar("SELECT user_id FROM google_accounts ORDER BY user_id")
+        .fetch_all(pool)
+        .await?;
+    Ok(ids)
+}
+
+pub async fn list_calendar_events(
+    pool: &AnyPool,
+    user_id: UserId,
+) -> Result<Vec<CalendarEventSync>> {
+    let sql = pg("SELECT user_id, game_pk, event_id, content_hash \
+         FROM google_calendar_events WHERE user_id = ?");
+    let events = sqlx::query_as::<_, CalendarEventSync>(&sql)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(events)
+}
+
+pub async fn upsert_calendar_event(
+    pool: &AnyPool,
+    user_id: UserId,
+    game_pk: GamePk,
+    event_id: &str,
+    content_hash: &str,
+) -> Result<()> {
+    let sql = pg(
+        "INSERT INTO google_calendar_events (user_id, game_pk, event_id, content_hash) \
+         VALUES (?, ?, ?, ?) \
+         ON CONFLICT (user_id, game_pk) DO UPDATE SET \
+            event_id = excluded.event_id, \
+            content_hash = excluded.content_hash, \
+            updated_at = CURRENT_TIMESTAMP",
+    );
+    sqlx::query(&sql)
+        .bind(user_id)
+        .bind(game_pk)
+        .bind(event_id)
+        .bind(content_hash)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn delete_calendar_event(pool: &AnyPool, user_id: UserId, game_pk: GamePk) -> Result<()> {
+    let sql = pg("DELETE FROM google_calendar_events WHERE user_id = ? AND game_pk = ?");
+    sqlx::query(&sql)
+        .bind(user_id)
+        .bind(game_pk)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// --- Ticket Requests ---
+
+const REQUEST_COLUMNS: &str = "id, user_id, game_pk, seats_requested, status, notes, flag_reason";
+
+/// Insert a member's request for a game, or update it in place. A
+/// withdrawn request comes back as pending.
+const UPSERT_REQUEST: &str = "INSERT INTO ticket_requests (user_id, game_pk, seats_requested, notes) \
+     VALUES (?, ?, ?, ?) \
+     ON CONFLICT(user_id, game_pk) DO UPDATE SET \
+        seats_requested = excluded.seats_requested, \
+        notes = excluded.notes, \
+        responded_at = CASE WHEN ticket_requests.status = 'withdrawn' THEN NULL ELSE ticket_requests.responded_at END, \
+        status = CASE WHEN ticket_requests.status = 'withdrawn' THEN 'pending' ELSE ticket_requests.status END, \
+        flag_reason = NULL, \
+        updated_at = CURRENT_TIMESTAMP";
+
+pub async fn create_ticket_request(
+    pool: &AnyPool,
+    user_id: UserId,
+    game_pk: GamePk,
+    seats_requested: i64,
+    notes: Option<&str>,
+) -> Result<TicketRequest> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_game_open(&mut *tx, game_pk).await?;
+    let request = compat::insert_returning::<TicketRequest>(
+        &mut tx,
+        UPSERT_REQUEST,
+        REQUEST_COLUMNS,
+        &[
+            Arg::Int(user_id.0),
+            Arg::Int(game_pk.0),
+            Arg::Int(seats_requested),
+            Arg::OptText(notes),
+        ],
+        &format!("SELECT {REQUEST_COLUMNS} FROM ticket_requests WHERE user_id = ? AND game_pk = ?"),
+        &[Arg::Int(user_id.0), Arg::Int(game_pk.0)],
+    )
+    .await?
+    .context("Request upsert wrote no row")?;
+    webhooks::enqueue(
+        &mut tx,
+        webhooks::REQUEST_CREATED,
+        serde_json::json!({
+            "request_id": request.id,
+            "user_id": request.user_id,
+            "game_pk": request.game_pk,
+            "seats_requested": request.seats_requested,
+            "status": request.status,
+        }),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(request)
+}
+
+/// What [`create_requests_for_games`] made of a filter.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct BulkRequests {
+    pub created: Vec<TicketRequest>,
+    pub skipped: Vec<SkippedGame>,
 }
 
-pub async fn list_users(pool: &AnyPool) -> Result<Vec<User>> {
-    let users =
-        sqlx::query_as::<_, User>("SELECT id, auth0_sub, email, name FROM users ORDER BY name")
+/// A game matching a bulk request's filter that got no request, and why.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SkippedGame {
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub reason: String,
+}
+
+/// Request `seats_requested` seats at every home game matching `filter`
+/// from `today` (YYYY-MM-DD) on, in one transaction. Games already
+/// requested, without tickets, in a closed season, or whose seat limit is
+/// below `seats_requested` are skipped; with `capped` (the member's default
+/// seat count rather than a number they asked for), a game's limit lowers
+/// the count instead. A withdrawn request is renewed, as
+/// [`create_ticket_request`] does.
+pub async fn create_requests_for_games(
+    pool: &AnyPool,
+    user_id: UserId,
+    filter: &GameFilter,
+    seats_requested: i64,
+    capped: bool,
+    notes: Option<&str>,
+    today: &str,
+) -> Result<BulkRequests> {
+    let filter = GameFilter {
+        home_only: true,
+        from: Some(
+            filter
+                .from
+                .as_deref()
+                .map_or(today, |f| f.max(today))
+                .to_string(),
+        ),
+        limit: None,
+        offset: None,
+        ..filter.clone()
+    };
+    let games = list_games(pool, &filter).await?;
+    let policy = seat_limits::SeatLimitPolicy::load(pool).await?;
+    let closed: std::collections::HashSet<String> =
+        sqlx::query_scalar::<_, String>("SELECT season FROM closed_seasons")
             .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+    let ticketed: std::collections::HashSet<GamePk> =
+        sqlx::query_scalar::<_, GamePk>("SELECT DISTINCT game_pk FROM game_tickets")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+    let sql = pg("SELECT game_pk, status FROM ticket_requests WHERE user_id = ?");
+    let existing: HashMap<GamePk, String> = sqlx::query_as::<_, (GamePk, String)>(&sql)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut out = BulkRequests::default();
+    let mut tx = pool.begin().await?;
+    let upsert = pg(UPSERT_REQUEST);
+    let select = pg(&format!(
+        "SELECT {REQUEST_COLUMNS} FROM ticket_requests WHERE user_id = ? AND game_pk = ?"
+    ));
+    for game in games {
+        let max = policy.max_seats(&game);
+        let reason = if closed.contains(&game.season) {
+            Some(format!("The {} season is closed", game.season))
+        } else if !ticketed.contains(&game.game_pk) {
+            Some("No tickets for this game".to_string())
+        } else if existing
+            .get(&game.game_pk)
+            .is_some_and(|status| status != "withdrawn")
+        {
+            Some("Already requested".to_string())
+        } else if seats_requested > max && !capped {
+            Some(format!("At most {max} seats can be requested"))
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            out.skipped.push(SkippedGame {
+                game_pk: game.game_pk,
+                official_date: game.official_date,
+                reason,
+            });
+            continue;
+        }
+        sqlx::query(&upsert)
+            .bind(user_id)
+            .bind(game.game_pk)
+            .bind(seats_requested.min(max))
+            .bind(notes)
+            .execute(&mut *tx)
             .await?;
-    Ok(users)
+        let request = sqlx::query_as::<_, TicketRequest>(&select)
+            .bind(user_id)
+            .bind(game.game_pk)
+            .fetch_one(&mut *tx)
+            .await?;
+        webhooks::enqueue(
+            &mut tx,
+            webhooks::REQUEST_CREATED,
+            serde_json::json!({
+                "request_id": request.id,
+                "user_id": request.user_id,
+                "game_pk": request.game_pk,
+                "seats_requested": request.seats_requested,
+                "status": request.status,
+            }),
+        )
+        .await?;
+        out.created.push(request);
+    }
+    tx.commit().await?;
+    Ok(out)
 }
 
-// --- Ticket Requests ---
+/// `AND` condition keeping rows whose `game_pk` is a game of the bound
+/// season.
+const IN_SEASON: &str = " AND game_pk IN (SELECT game_pk FROM games WHERE season = ?)";
 
-pub async fn create_ticket_request(
+/// A member's requests, optionally for one season's games only.
+pub async fn list_requests_for_user(
     pool: &AnyPool,
-    user_id: i64,
-    game_pk: i64,
-    seats_requested: i64,
-    notes: Option<&str>,
-) -> Result<TicketRequest> {
+    user_id: UserId,
+    season: Option<&str>,
+) -> Result<Vec<TicketRequest>> {
+    let in_season = if season.is_some() { IN_SEASON } else { "" };
+    let sql = pg(&format!(
+        "SELECT id, user_id, game_pk, seats_requested, status, notes, flag_reason \
+         FROM ticket_requests WHERE user_id = ?{in_season} ORDER BY game_pk"
+    ));
+    let mut query = sqlx::query_as::<_, TicketRequest>(&sql).bind(user_id);
+    if let Some(season) = season {
+        query = query.bind(season);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+pub async fn get_ticket_request(pool: &AnyPool, id: i64) -> Result<Option<TicketRequest>> {
     let sql = pg(
-        "INSERT INTO ticket_requests (user_id, game_pk, seats_requested, notes) \
-         VALUES (?, ?, ?, ?) \
-         ON CONFLICT(user_id, game_pk) DO UPDATE SET \
-            seats_requested = excluded.seats_requested, \
-            notes = excluded.notes, \
-            status = CASE WHEN ticket_requests.status = 'withdrawn' THEN 'pending' ELSE ticket_requests.status END, \
-            updated_at = CURRENT_TIMESTAMP \
-         RETURNING id, user_id, game_pk, seats_requested, status, notes",
+        "SELECT id, user_id, game_pk, seats_requested, status, notes, flag_reason \
+         FROM ticket_requests WHERE id = ?",
     );
     let req = sqlx::query_as::<_, TicketRequest>(&sql)
-        .bind(user_id)
-        .bind(game_pk)
-        .bind(seats_requested)
-        .bind(notes)
-        .fetch_one(pool)
+        .bind(id)
+        .fetch_optional(pool)
         .await?;
     Ok(req)
 }
 
-pub async fn list_requests_for_user(pool: &AnyPool, user_id: i64) -> Result<Vec<TicketRequest>> {
-    let sql = pg(
-        "SELECT id, user_id, game_pk, seats_requested, status, notes \
-         FROM ticket_requests WHERE user_id = ? ORDER BY game_pk",
-    );
-    let reqs = sqlx::query_as::<_, TicketRequest>(&sql)
+/// A member's request for a game, whatever its status.
+pub async fn get_request_for_user_game(
+    pool: &AnyPool,
+    user_id: UserId,
+    game_pk: GamePk,
+) -> Result<Option<TicketRequest>> {
+    let sql = pg(&format!(
+        "SELECT {REQUEST_COLUMNS} FROM ticket_requests WHERE user_id = ? AND game_pk = ?"
+    ));
+    let req = sqlx::query_as::<_, TicketRequest>(&sql)
         .bind(user_id)
-        .fetch_all(pool)
+        .bind(game_pk)
+        .fetch_optional(pool)
         .await?;
-    Ok(reqs)
+    Ok(req)
 }
 
-pub async fn list_requests_for_game(pool: &AnyPool, game_pk: i64) -> Result<Vec<TicketRequest>> {
+pub async fn list_requests_for_game(pool: &AnyPool, game_pk: GamePk) -> Result<Vec<TicketRequest>> {
     let sql = pg(
-        "SELECT id, user_id, game_pk, seats_requested, status, notes \
+        "SELECT id, user_id, game_pk, seats_requested, status, notes, flag_reason \
          FROM ticket_requests WHERE game_pk = ? ORDER BY created_at",
     );
     let reqs = sqlx::query_as::<_, TicketRequest>(&sql)
@@ -416,9 +2283,42 @@ pub async fn list_requests_for_game(pool: &AnyPool, game_pk: i64) -> Result<Vec<
     Ok(reqs)
 }
 
+/// Requests with `status` (any status if `None`), optionally for one game
+/// only, by game then when they were made.
+pub async fn list_requests(
+    pool: &AnyPool,
+    game_pk: Option<GamePk>,
+    status: Option<&str>,
+) -> Result<Vec<TicketRequest>> {
+    let mut conditions = Vec::new();
+    if game_pk.is_some() {
+        conditions.push("game_pk = ?");
+    }
+    if status.is_some() {
+        conditions.push("status = ?");
+    }
+    let filter = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    let sql = pg(&format!(
+        "SELECT id, user_id, game_pk, seats_requested, status, notes, flag_reason \
+         FROM ticket_requests{filter} ORDER BY game_pk, created_at, id"
+    ));
+    let mut query = sqlx::query_as::<_, TicketRequest>(&sql);
+    if let Some(game_pk) = game_pk {
+        query = query.bind(game_pk);
+    }
+    if let Some(status) = status {
+        query = query.bind(status);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
 pub async fn list_all_pending_requests(pool: &AnyPool) -> Result<Vec<TicketRequest>> {
     let reqs = sqlx::query_as::<_, TicketRequest>(
-        "SELECT id, user_id, game_pk, seats_requested, status, notes \
+        "SELECT id, user_id, game_pk, seats_requested, status, notes, flag_reason \
          FROM ticket_requests WHERE status = 'pending' ORDER BY game_pk, created_at",
     )
     .fetch_all(pool)
@@ -426,12 +2326,73 @@ pub async fn list_all_pending_requests(pool: &AnyPool) -> Result<Vec<TicketReque
     Ok(reqs)
 }
 
+/// Pending requests, oldest first, with their submission time; optionally
+/// for one season's games only.
+pub async fn list_pending_requests_oldest_first(
+    pool: &AnyPool,
+    season: Option<&str>,
+) -> Result<Vec<PendingRequest>> {
+    let in_season = if season.is_some() { IN_SEASON } else { "" };
+    let sql = pg(&format!(
+        "SELECT id, user_id, game_pk, seats_requested, status, notes, flag_reason, \
+                CAST(created_at AS TEXT) AS created_at \
+         FROM ticket_requests WHERE status = 'pending'{in_season} ORDER BY created_at, id"
+    ));
+    let mut query = sqlx::query_as::<_, PendingRequest>(&sql);
+    if let Some(season) = season {
+        query = query.bind(season);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+/// Median request → response time per month the request was made.
+pub async fn response_time_report(pool: &AnyPool) -> Result<Vec<MonthlyResponseTime>> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        "SELECT CAST(created_at AS TEXT), CAST(responded_at AS TEXT) \
+         FROM ticket_requests WHERE responded_at IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_month: std::collections::BTreeMap<String, Vec<f64>> = Default::default();
+    for (created, responded) in rows {
+        let (Some(c), Some(r)) = (parse_timestamp(&created), parse_timestamp(&responded)) else {
+            continue;
+        };
+        let hours = (r - c).num_seconds().max(0) as f64 / 3600.0;
+        by_month
+            .entry(c.format("%Y-%m").to_string())
+            .or_default()
+            .push(hours);
+    }
+
+    Ok(by_month
+        .into_iter()
+        .map(|(month, mut hours)| {
+            hours.sort_by(|a, b| a.total_cmp(b));
+            let n = hours.len();
+            let median = if n % 2 == 1 {
+                hours[n / 2]
+            } else {
+                (hours[n / 2 - 1] + hours[n / 2]) / 2.0
+            };
+            MonthlyResponseTime {
+                month,
+                responded: n as i64,
+                median_hours: median,
+            }
+        })
+        .collect())
+}
+
 pub async fn update_ticket_request(
     pool: &AnyPool,
     request_id: i64,
-    user_id: i64,
+    user_id: UserId,
     seats_requested: i64,
 ) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_request_open(&mut *tx, request_id).await?;
     let sql = pg(
         "UPDATE ticket_requests SET seats_requested = ?, updated_at = CURRENT_TIMESTAMP \
          WHERE id = ? AND user_id = ? AND status = 'pending'",
@@ -440,16 +2401,19 @@ pub async fn update_ticket_request(
         .bind(seats_requested)
         .bind(request_id)
         .bind(user_id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+    tx.commit().await?;
     Ok(result.rows_affected() > 0)
 }
 
 pub async fn withdraw_ticket_request(
     pool: &AnyPool,
     request_id: i64,
-    user_id: i64,
+    user_id: UserId,
 ) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_request_open(&mut *tx, request_id).await?;
     let sql = pg(
         "UPDATE ticket_requests SET status = 'withdrawn', updated_at = CURRENT_TIMESTAMP \
          WHERE id = ? AND user_id = ? AND status = 'pending'",
@@ -457,14 +2421,38 @@ pub async fn withdraw_ticket_request(
     let result = sqlx::query(&sql)
         .bind(request_id)
         .bind(user_id)
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
+    tx.commit().await?;
     Ok(result.rows_affected() > 0)
 }
 
 // --- Allocation ---
 
-pub async fn assign_ticket(pool: &AnyPool, game_ticket_id: i64, user_id: i64) -> Result<bool> {
+/// Assign one available ticket. Fails with [`seating::SplitParty`] if it
+/// would put the member's seats at the game in more than one section; use
+/// [`commit_allocation`] to override that.
+pub async fn assign_ticket(
+    pool: &AnyPool,
+    game_ticket_id: TicketId,
+    user_id: UserId,
+    actor_id: Option<UserId>,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    seating::check_parties_with(&mut tx, &[(game_ticket_id, user_id)], false).await?;
+    let assigned = assign_ticket_with(&mut tx, game_ticket_id, user_id, actor_id).await?;
+    tx.commit().await?;
+    Ok(assigned)
+}
+
+/// Assign an available ticket and audit it, inside the caller's transaction.
+async fn assign_ticket_with(
+    tx: &mut sqlx::Transaction<'_, sqlx::Any>,
+    game_ticket_id: TicketId,
+    user_id: UserId,
+    actor_id: Option<UserId>,
+) -> Result<bool> {
+    seasons::ensure_ticket_open(&mut **tx, game_ticket_id).await?;
     let sql = pg(
         "UPDATE game_tickets SET assigned_to = ?, status = 'assigned', updated_at = CURRENT_TIMESTAMP \
          WHERE id = ? AND status = 'available'",
@@ -472,21 +2460,106 @@ pub async fn assign_ticket(pool: &AnyPool, game_ticket_id: i64, user_id: i64) ->
     let result = sqlx::query(&sql)
         .bind(user_id)
         .bind(game_ticket_id)
-        .execute(pool)
+        .execute(&mut **tx)
         .await?;
-    Ok(result.rows_affected() > 0)
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+    audit::record(
+        &mut **tx,
+        &audit::AuditEvent {
+            actor_id,
+            action: audit::ASSIGN,
+            game_ticket_id: Some(game_ticket_id),
+            user_id: Some(user_id),
+            before: Some("available"),
+            after: Some("assigned"),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let game_pk =
+        sqlx::query_scalar::<_, GamePk>(&pg("SELECT game_pk FROM game_tickets WHERE id = ?"))
+            .bind(game_ticket_id)
+            .fetch_one(&mut **tx)
+            .await?;
+    webhooks::enqueue(
+        tx,
+        webhooks::TICKET_ASSIGNED,
+        serde_json::json!({
+            "ticket_id": game_ticket_id,
+            "game_pk": game_pk,
+            "user_id": user_id,
+            "actor_id": actor_id,
+        }),
+    )
+    .await?;
+    Ok(true)
 }
 
-pub async fn revoke_ticket(pool: &AnyPool, game_ticket_id: i64) -> Result<bool> {
+pub async fn revoke_ticket(
+    pool: &AnyPool,
+    game_ticket_id: TicketId,
+    actor_id: Option<UserId>,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    let Some((_, assigned_to)) = ticket_state(&mut *tx, game_ticket_id).await? else {
+        return Ok(false);
+    };
+    seasons::ensure_ticket_open(&mut *tx, game_ticket_id).await?;
     let sql = pg(
         "UPDATE game_tickets SET assigned_to = NULL, status = 'available', updated_at = CURRENT_TIMESTAMP \
          WHERE id = ? AND status = 'assigned'",
     );
-    let result = sqlx::query(&sql).bind(game_ticket_id).execute(pool).await?;
-    Ok(result.rows_affected() > 0)
+    let result = sqlx::query(&sql)
+        .bind(game_ticket_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+    audit::record(
+        &mut *tx,
+        &audit::AuditEvent {
+            actor_id,
+            action: audit::REVOKE,
+            game_ticket_id: Some(game_ticket_id),
+            user_id: assigned_to,
+            before: Some("assigned"),
+            after: Some("available"),
+            ..Default::default()
+        },
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+pub async fn release_tickets_for_game(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    user_id: UserId,
+    actor_id: Option<UserId>,
+) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let released = release_tickets_for_game_with(&mut tx, game_pk, user_id, actor_id).await?;
+    tx.commit().await?;
+    Ok(released)
 }
 
-pub async fn release_tickets_for_game(pool: &AnyPool, game_pk: i64, user_id: i64) -> Result<u64> {
+async fn release_tickets_for_game_with(
+    conn: &mut sqlx::AnyConnection,
+    game_pk: GamePk,
+    user_id: UserId,
+    actor_id: Option<UserId>,
+) -> Result<u64> {
+    seasons::ensure_game_open(&mut *conn, game_pk).await?;
+    let ids_sql = pg("SELECT id FROM game_tickets WHERE game_pk = ? AND assigned_to = ?");
+    let ticket_ids: Vec<TicketId> = sqlx::query_scalar(&ids_sql)
+        .bind(game_pk)
+        .bind(user_id)
+        .fetch_all(&mut *conn)
+        .await?;
     let sql = pg(
         "UPDATE game_tickets SET assigned_to = NULL, status = 'available', updated_at = CURRENT_TIMESTAMP \
          WHERE game_pk = ? AND assigned_to = ?",
@@ -494,8 +2567,24 @@ pub async fn release_tickets_for_game(pool: &AnyPool, game_pk: i64, user_id: i64
     let result = sqlx::query(&sql)
         .bind(game_pk)
         .bind(user_id)
-        .execute(pool)
+        .execute(&mut *conn)
+        .await?;
+    for ticket_id in ticket_ids {
+        audit::record(
+            &mut *conn,
+            &audit::AuditEvent {
+                actor_id,
+                action: audit::RELEASE,
+                game_pk: Some(game_pk),
+                game_ticket_id: Some(ticket_id),
+                user_id: Some(user_id),
+                before: Some("assigned"),
+                after: Some("available"),
+                ..Default::default()
+            },
+        )
         .await?;
+    }
     let withdraw_sql = pg(
         "UPDATE ticket_requests SET status = 'withdrawn', updated_at = CURRENT_TIMESTAMP \
          WHERE game_pk = ? AND user_id = ? AND status IN ('pending', 'approved')",
@@ -503,32 +2592,208 @@ pub async fn release_tickets_for_game(pool: &AnyPool, game_pk: i64, user_id: i64
     sqlx::query(&withdraw_sql)
         .bind(game_pk)
         .bind(user_id)
-        .execute(pool)
+        .execute(&mut *conn)
         .await?;
     Ok(result.rows_affected())
 }
 
+/// Hand a member's seats for a game to another member, in one transaction.
+/// `ticket_ids` limits the transfer to those seats; `None` moves all of them.
+/// Each seat is audited and logged in `ticket_transfers`. Returns the ids of
+/// the tickets moved (empty if the member held none of them); requested seats
+/// the member doesn't hold are skipped with a warning, as is a receiver
+/// whose seats won't sit together: a member can't override a split party as
+/// an admin can, so a transfer only flags it.
+pub async fn transfer_ticket(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    from_user_id: UserId,
+    to_user_id: UserId,
+    ticket_ids: Option<&[TicketId]>,
+    actor_id: Option<UserId>,
+    warnings: &mut Warnings,
+) -> Result<Vec<TicketId>> {
+    let mut tx = pool.begin().await?;
+    seasons::ensure_game_open(&mut *tx, game_pk).await?;
+    let ids_sql =
+        pg("SELECT id FROM game_tickets WHERE game_pk = ? AND assigned_to = ? ORDER BY id");
+    let held: Vec<TicketId> = sqlx::query_scalar(&ids_sql)
+        .bind(game_pk)
+        .bind(from_user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+    let moving: Vec<TicketId> = match ticket_ids {
+        Some(wanted) => {
+            for id in wanted.iter().filter(|id| !held.contains(id)) {
+                warnings.push(format!("Ticket {id} isn't yours for this game; skipped"));
+            }
+            held.into_iter().filter(|id| wanted.contains(id)).collect()
+        }
+        None => held,
+    };
+    let parties: Vec<(TicketId, UserId)> = moving.iter().map(|t| (*t, to_user_id)).collect();
+    for party in seating::check_parties_with(&mut tx, &parties, true).await? {
+        warnings.push(seating::warning(party));
+    }
+
+    let update_sql = pg(
+        "UPDATE game_tickets SET assigned_to = ?, updated_at = CURRENT_TIMESTAMP \
+         WHERE id = ? AND assigned_to = ?",
+    );
+    let log_sql = pg(
+        "INSERT INTO ticket_transfers (game_pk, game_ticket_id, from_user_id, to_user_id) \
+         VALUES (?, ?, ?, ?)",
+    );
+    let (from, to) = (from_user_id.to_string(), to_user_id.to_string());
+    let mut moved = Vec::with_capacity(moving.len());
+    for ticket_id in moving {
+        // The holder may have changed since it was read; only move the
+        // tickets the update still finds with them
+        let result = sqlx::query(&update_sql)
+            .bind(to_user_id)
+            .bind(ticket_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            warnings.push(format!(
+                "Ticket {ticket_id} isn't yours for this game; skipped"
+            ));
+            continue;
+        }
+        moved.push(ticket_id);
+        sqlx::query(&log_sql)
+            .bind(game_pk)
+            .bind(ticket_id)
+            .bind(from_user_id)
+            .bind(to_user_id)
+            .execute(&mut *tx)
+            .await?;
+        audit::record(
+            &mut *tx,
+            &audit::AuditEvent {
+                actor_id,
+                action: audit::TRANSFER,
+                game_pk: Some(game_pk),
+                game_ticket_id: Some(ticket_id),
+                user_id: Some(from_user_id),
+                before: Some(&from),
+                after: Some(&to),
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(moved)
+}
+
+/// Seat transfers between members, newest first, optionally for one game or
+/// one season.
+pub async fn list_ticket_transfers(
+    pool: &AnyPool,
+    game_pk: Option<GamePk>,
+    season: Option<&str>,
+) -> Result<Vec<TicketTransfer>> {
+    let mut sql = String::from(
+        "SELECT t.id, CAST(t.created_at AS TEXT) AS created_at, t.game_pk, \
+                g.official_date, g.away_team_name, t.game_ticket_id, \
+                s.section, s.row, s.seat, \
+                t.from_user_id, f.name AS from_user_name, \
+                t.to_user_id, u.name AS to_user_name \
+         FROM ticket_transfers t \
+         JOIN games g ON g.game_pk = t.game_pk \
+         JOIN game_tickets gt ON gt.id = t.game_ticket_id \
+         JOIN seats s ON s.id = gt.seat_id \
+         JOIN users f ON f.id = t.from_user_id \
+         JOIN users u ON u.id = t.to_user_id",
+    );
+    let mut conds = Vec::new();
+    if game_pk.is_some() {
+        conds.push("t.game_pk = ?");
+    }
+    if season.is_some() {
+        conds.push("g.season = ?");
+    }
+    if !conds.is_empty() {
+        sql.push_str(&format!(" WHERE {}", conds.join(" AND ")));
+    }
+    sql.push_str(" ORDER BY t.id DESC");
+    let sql = pg(&sql);
+    let mut query = sqlx::query_as::<_, TicketTransfer>(&sql);
+    if let Some(pk) = game_pk {
+        query = query.bind(pk);
+    }
+    if let Some(season) = season {
+        query = query.bind(season);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+/// Set a request's status. The first move out of `pending` stamps
+/// `responded_at` for SLA tracking.
 pub async fn update_request_approval(
     pool: &AnyPool,
     request_id: i64,
     status: &str,
+    actor_id: Option<UserId>,
 ) -> Result<bool> {
-    let sql = pg(
-        "UPDATE ticket_requests SET status = ?, updated_at = CURRENT_TIMESTAMP \
-         WHERE id = ?",
-    );
-    let result = sqlx::query(&sql)
+    let mut tx = pool.begin().await?;
+    let approved = update_request_approval_with(&mut tx, request_id, status, actor_id).await?;
+    tx.commit().await?;
+    Ok(approved)
+}
+
+async fn update_request_approval_with(
+    tx: &mut sqlx::Transaction<'_, sqlx::Any>,
+    request_id: i64,
+    status: &str,
+    actor_id: Option<UserId>,
+) -> Result<bool> {
+    let before_sql = pg("SELECT status, user_id FROM ticket_requests WHERE id = ?");
+    let Some((before, user_id)) = sqlx::query_as::<_, (String, UserId)>(&before_sql)
+        .bind(request_id)
+        .fetch_optional(&mut **tx)
+        .await?
+    else {
+        return Ok(false);
+    };
+    seasons::ensure_request_open(&mut **tx, request_id).await?;
+    let sql = pg("UPDATE ticket_requests SET \
+            responded_at = CASE WHEN status = 'pending' AND ? <> 'pending' \
+                THEN CURRENT_TIMESTAMP ELSE responded_at END, \
+            status = ?, \
+            flag_reason = NULL, \
+            updated_at = CURRENT_TIMESTAMP \
+         WHERE id = ?");
+    sqlx::query(&sql)
+        .bind(status)
         .bind(status)
         .bind(request_id)
-        .execute(pool)
+        .execute(&mut **tx)
         .await?;
-    Ok(result.rows_affected() > 0)
+    if before != status {
+        audit::record(
+            &mut **tx,
+            &audit::AuditEvent {
+                actor_id,
+                action: audit::REQUEST_STATUS,
+                request_id: Some(request_id),
+                user_id: Some(user_id),
+                before: Some(&before),
+                after: Some(status),
+                ..Default::default()
+            },
+        )
+        .await?;
+    }
+    Ok(true)
 }
 
 /// All non-withdrawn/declined requests (pending + approved), ordered by user then game.
 pub async fn list_all_active_requests(pool: &AnyPool) -> Result<Vec<TicketRequest>> {
     let reqs = sqlx::query_as::<_, TicketRequest>(
-        "SELECT id, user_id, game_pk, seats_requested, status, notes \
+        "SELECT id, user_id, game_pk, seats_requested, status, notes, flag_reason \
          FROM ticket_requests WHERE status IN ('pending', 'approved') ORDER BY user_id, game_pk",
     )
     .fetch_all(pool)
@@ -551,39 +2816,356 @@ pub async fn list_all_assigned_tickets(pool: &AnyPool) -> Result<Vec<GameTicketD
     Ok(tickets)
 }
 
-pub async fn list_tickets_for_user(pool: &AnyPool, user_id: i64) -> Result<Vec<GameTicketDetail>> {
-    let sql = pg(
+/// Seats assigned to a member, optionally for one season's games only.
+pub async fn list_tickets_for_user(
+    pool: &AnyPool,
+    user_id: UserId,
+    season: Option<&str>,
+) -> Result<Vec<GameTicketDetail>> {
+    let in_season = if season.is_some() {
+        " AND gt.game_pk IN (SELECT game_pk FROM games WHERE season = ?)"
+    } else {
+        ""
+    };
+    let sql = pg(&format!(
         "SELECT gt.id, gt.game_pk, gt.seat_id, s.section, s.row, s.seat, gt.status, gt.notes, gt.assigned_to \
          FROM game_tickets gt \
          JOIN seats s ON s.id = gt.seat_id \
-         WHERE gt.assigned_to = ? \
-         ORDER BY gt.game_pk, s.section, s.row, s.seat",
+         WHERE gt.assigned_to = ?{in_season} \
+         ORDER BY gt.game_pk, s.section, s.row, s.seat"
+    ));
+    let mut query = sqlx::query_as::<_, GameTicketDetail>(&sql).bind(user_id);
+    if let Some(season) = season {
+        query = query.bind(season);
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+/// Seats assigned to each user across a season: `(user_id, seats)`.
+pub async fn assigned_seat_counts_for_season(
+    pool: &AnyPool,
+    season: &str,
+) -> Result<Vec<(UserId, i64)>> {
+    let sql = pg("SELECT gt.assigned_to, COUNT(*) \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         WHERE gt.status = 'assigned' AND gt.assigned_to IS NOT NULL AND g.season = ? \
+         GROUP BY gt.assigned_to");
+    let rows = sqlx::query_as::<_, (UserId, i64)>(&sql)
+        .bind(season)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+// --- Weather release prompts ---
+
+/// Record that `user_id` was prompted about rain for `game_pk`. Returns false
+/// if they were already prompted for this game.
+pub async fn create_release_prompt(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    user_id: UserId,
+    rain_probability: i64,
+    token: &str,
+) -> Result<bool> {
+    let sql = pg(
+        "INSERT INTO release_prompts (token, game_pk, user_id, rain_probability) \
+         VALUES (?, ?, ?, ?) \
+         ON CONFLICT (game_pk, user_id) DO NOTHING",
     );
-    let tickets = sqlx::query_as::<_, GameTicketDetail>(&sql)
+    let result = sqlx::query(&sql)
+        .bind(token)
+        .bind(game_pk)
         .bind(user_id)
+        .bind(rain_probability)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_release_prompt(pool: &AnyPool, token: &str) -> Result<Option<ReleasePrompt>> {
+    let sql = pg(
+        "SELECT id, token, game_pk, user_id, rain_probability, response \
+         FROM release_prompts WHERE token = ?",
+    );
+    let prompt = sqlx::query_as::<_, ReleasePrompt>(&sql)
+        .bind(token)
+        .fetch_optional(pool)
+        .await?;
+    Ok(prompt)
+}
+
+/// Store the member's answer (`release` or `keep`) and, for `release`, give
+/// back their seats for the game in the same transaction. Only the first
+/// answer counts: returns `None` if the prompt was already answered, else
+/// how many seats were released.
+pub async fn respond_release_prompt(
+    pool: &AnyPool,
+    token: &str,
+    response: &str,
+) -> Result<Option<u64>> {
+    let mut tx = pool.begin().await?;
+    let sql = pg(
+        "UPDATE release_prompts SET response = ?, responded_at = CURRENT_TIMESTAMP \
+         WHERE token = ? AND response IS NULL",
+    );
+    let result = sqlx::query(&sql)
+        .bind(response)
+        .bind(token)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+    let mut released = 0;
+    if response == "release" {
+        let sql = pg("SELECT game_pk, user_id FROM release_prompts WHERE token = ?");
+        let (game_pk, user_id): (GamePk, UserId) =
+            sqlx::query_as(&sql).bind(token).fetch_one(&mut *tx).await?;
+        released = release_tickets_for_game_with(&mut tx, game_pk, user_id, Some(user_id)).await?;
+    }
+    tx.commit().await?;
+    Ok(Some(released))
+}
+
+/// Members who haven't answered their rain prompt for `game_pk` yet.
+pub async fn list_unanswered_release_prompts(
+    pool: &AnyPool,
+    game_pk: GamePk,
+) -> Result<Vec<UserId>> {
+    let sql = pg(
+        "SELECT user_id FROM release_prompts WHERE game_pk = ? AND response IS NULL \
+         ORDER BY user_id",
+    );
+    let users = sqlx::query_scalar(&sql)
+        .bind(game_pk)
         .fetch_all(pool)
         .await?;
-    Ok(tickets)
+    Ok(users)
+}
+
+/// Seats held this season and games given back in response to a rain prompt,
+/// per member.
+pub async fn fairness_report(pool: &AnyPool, season: &str) -> Result<Vec<FairnessRow>> {
+    let sql = pg("SELECT u.id AS user_id, u.name AS user_name, \
+            (SELECT COUNT(*) FROM game_tickets gt \
+             JOIN games g ON g.game_pk = gt.game_pk \
+             WHERE gt.assigned_to = u.id AND gt.status = 'assigned' AND g.season = ?) AS seats_assigned, \
+            (SELECT COUNT(*) FROM release_prompts rp \
+             JOIN games g ON g.game_pk = rp.game_pk \
+             WHERE rp.user_id = u.id AND rp.response = 'release' AND g.season = ?) AS weather_releases \
+         FROM users u ORDER BY u.name");
+    let rows = timed(
+        "fairness_report",
+        sqlx::query_as::<_, FairnessRow>(&sql)
+            .bind(season)
+            .bind(season)
+            .fetch_all(pool),
+    )
+    .await?;
+    Ok(rows)
+}
+
+/// Apply a batch of `(game_ticket_id, user_id, request_id)` assignments in one
+/// transaction and mark each request that received seats as approved.
+///
+/// Tickets that are no longer available are skipped with a warning rather
+/// than failing the batch; the number actually assigned is returned.
+/// Nothing is assigned if a member's seats at a game would span sections
+/// ([`seating::SplitParty`]), unless `allow_split`; parties that won't sit
+/// side by side are warned about.
+pub async fn commit_allocation(
+    pool: &AnyPool,
+    assignments: &[(TicketId, UserId, Option<i64>)],
+    actor_id: Option<UserId>,
+    allow_split: bool,
+    warnings: &mut Warnings,
+) -> Result<u64> {
+    let mut tx = pool.begin().await?;
+    let outcomes =
+        commit_allocation_with(&mut tx, assignments, actor_id, allow_split, warnings).await?;
+    tx.commit().await?;
+    Ok(outcomes.iter().filter(|ok| **ok).count() as u64)
+}
+
+/// [`commit_allocation`], recording the batch in the same transaction so
+/// there's never a batch without its assignments or the other way round.
+/// The snapshot keeps `draft`'s request and proposal with each
+/// assignment's outcome and the `warnings` so far. Returns the number
+/// assigned and the batch id.
+pub async fn commit_allocation_batch(
+    pool: &AnyPool,
+    assignments: &[(TicketId, UserId, Option<i64>)],
+    actor_id: Option<UserId>,
+    allow_split: bool,
+    draft: &allocation_batches::Draft<'_>,
+    warnings: &mut Warnings,
+) -> Result<(u64, i64)> {
+    let mut tx = pool.begin().await?;
+    let outcomes =
+        commit_allocation_with(&mut tx, assignments, actor_id, allow_split, warnings).await?;
+    let assigned: Vec<TicketId> = assignments
+        .iter()
+        .zip(&outcomes)
+        .filter(|(_, ok)| **ok)
+        .map(|((ticket_id, _, _), _)| *ticket_id)
+        .collect();
+    let game_pk = match draft.game_pk {
+        Some(game_pk) => Some(game_pk),
+        None if assigned.is_empty() => None,
+        None => {
+            let sql = pg(&format!(
+                "SELECT DISTINCT game_pk FROM game_tickets WHERE id IN ({})",
+                vec!["?"; assigned.len()].join(", ")
+            ));
+            let mut query = sqlx::query_scalar::<_, GamePk>(&sql);
+            for ticket_id in &assigned {
+                query = query.bind(*ticket_id);
+            }
+            match query.fetch_all(&mut *tx).await?[..] {
+                [only] => Some(only),
+                _ => None,
+            }
+        }
+    };
+    let results: Vec<serde_json::Value> = assignments
+        .iter()
+        .zip(&outcomes)
+        .map(|((ticket_id, user_id, request_id), ok)| {
+            serde_json::json!({
+                "game_ticket_id": ticket_id,
+                "user_id": user_id,
+                "request_id": request_id,
+                "assigned": ok,
+            })
+        })
+        .collect();
+    let snapshot = serde_json::json!({
+        "request": draft.request,
+        "proposal": draft.proposal,
+        "assignments": results,
+        "warnings": warnings,
+    });
+    let batch = allocation_batches::NewBatch {
+        actor_id,
+        kind: draft.kind,
+        game_pk,
+        assigned: assigned.len() as i64,
+        snapshot: &snapshot,
+    };
+    let batch_id = allocation_batches::record_with(&mut tx, &batch).await?;
+    tx.commit().await?;
+    info!(
+        batch_id,
+        kind = draft.kind,
+        assigned = assigned.len(),
+        "Allocation batch committed"
+    );
+    Ok((assigned.len() as u64, batch_id))
+}
+
+/// Whether each assignment of a batch went through, in order.
+async fn commit_allocation_with(
+    tx: &mut sqlx::Transaction<'_, sqlx::Any>,
+    assignments: &[(TicketId, UserId, Option<i64>)],
+    actor_id: Option<UserId>,
+    allow_split: bool,
+    warnings: &mut Warnings,
+) -> Result<Vec<bool>> {
+    let parties: Vec<(TicketId, UserId)> = assignments.iter().map(|(t, u, _)| (*t, *u)).collect();
+    for party in seating::check_parties_with(tx, &parties, allow_split).await? {
+        warnings.push(seating::warning(party));
+    }
+    let mut outcomes = Vec::with_capacity(assignments.len());
+    let mut approved = std::collections::BTreeSet::new();
+    for (ticket_id, user_id, request_id) in assignments {
+        let ok = assign_ticket_with(tx, *ticket_id, *user_id, actor_id).await?;
+        if ok {
+            if let Some(rid) = request_id {
+                approved.insert(*rid);
+            }
+        } else {
+            warnings.push(format!(
+                "Ticket {ticket_id} is no longer available; not assigned to user {user_id}"
+            ));
+        }
+        outcomes.push(ok);
+    }
+    for rid in approved {
+        update_request_approval_with(tx, rid, "approved", actor_id).await?;
+    }
+    Ok(outcomes)
+}
+
+/// Per-home-game allocation summary for team `team_id` in one pass: ticket
+/// counts and pending demand are aggregated once each and joined to the game
+/// row. `season` keeps one season's games.
+pub async fn allocation_summary(
+    pool: &AnyPool,
+    team_id: u32,
+    season: Option<&str>,
+) -> Result<Vec<AllocationSummaryRow>> {
+    let in_season = if season.is_some() {
+        " AND g.season = ?"
+    } else {
+        ""
+    };
+    let sql = pg(&format!(
+        "SELECT \
+            g.game_pk, g.official_date, g.away_team_name, g.double_header, g.game_number, \
+            t.total_seats, t.assigned, t.available, \
+            COALESCE(r.total_requested, 0) AS total_requested, \
+            CASE WHEN COALESCE(r.total_requested, 0) > t.available THEN 1 ELSE 0 END AS oversubscribed \
+         FROM games g \
+         JOIN ( \
+             SELECT game_pk, \
+                 COUNT(*) AS total_seats, \
+                 SUM(CASE WHEN status = 'assigned' THEN 1 ELSE 0 END) AS assigned, \
+                 SUM(CASE WHEN status = 'available' THEN 1 ELSE 0 END) AS available \
+             FROM game_tickets GROUP BY game_pk \
+         ) t ON t.game_pk = g.game_pk \
+         LEFT JOIN ( \
+             SELECT game_pk, SUM(seats_requested) AS total_requested \
+             FROM ticket_requests WHERE status = 'pending' GROUP BY game_pk \
+         ) r ON r.game_pk = g.game_pk \
+         WHERE g.home_team_id = ?{in_season} \
+         ORDER BY g.game_date, g.game_number"
+    ));
+    let mut query = sqlx::query_as::<_, AllocationSummaryRow>(&sql).bind(i64::from(team_id));
+    if let Some(season) = season {
+        query = query.bind(season);
+    }
+    timed("allocation_summary", query.fetch_all(pool)).await
 }
 
-/// Per-game allocation summary: (game_pk, total_seats, assigned, available, total_requested)
-pub async fn allocation_summary(pool: &AnyPool) -> Result<Vec<(i64, i64, i64, i64, i64)>> {
-    let sql = pg("SELECT \
-            g.game_pk, \
-            COUNT(gt.id) as total_seats, \
-            SUM(CASE WHEN gt.status = 'assigned' THEN 1 ELSE 0 END) as assigned, \
-            SUM(CASE WHEN gt.status = 'available' THEN 1 ELSE 0 END) as available, \
-            COALESCE(( \
-                SELECT SUM(tr.seats_requested) FROM ticket_requests tr \
-                WHERE tr.game_pk = g.game_pk AND tr.status = 'pending' \
-            ), 0) as total_requested \
+/// Team `team_id`'s home games with `official_date` in `[from, to]` that
+/// still have available tickets and no pending requests to fill them.
+pub async fn list_unassigned_games(
+    pool: &AnyPool,
+    team_id: u32,
+    from: &str,
+    to: &str,
+) -> Result<Vec<UnassignedGame>> {
+    let sql = pg(
+        "SELECT g.game_pk, g.official_date, g.game_date, g.away_team_name, \
+            g.double_header, g.game_number, COUNT(gt.id) as available \
          FROM games g \
-         JOIN game_tickets gt ON gt.game_pk = g.game_pk \
-         WHERE g.home_team_name = ? \
-         GROUP BY g.game_pk, g.game_date \
-         ORDER BY g.game_date");
-    let rows = sqlx::query_as::<_, (i64, i64, i64, i64, i64)>(&sql)
-        .bind(GIANTS_TEAM_NAME)
+         JOIN game_tickets gt ON gt.game_pk = g.game_pk AND gt.status = 'available' \
+         WHERE g.home_team_id = ? \
+           AND g.official_date >= ? AND g.official_date <= ? \
+           AND NOT EXISTS ( \
+               SELECT 1 FROM ticket_requests tr \
+               WHERE tr.game_pk = g.game_pk AND tr.status = 'pending' \
+           ) \
+         GROUP BY g.game_pk, g.official_date, g.game_date, g.away_team_name, \
+                  g.double_header, g.game_number \
+         ORDER BY g.game_date, g.game_number",
+    );
+    let rows = sqlx::query_as::<_, UnassignedGame>(&sql)
+        .bind(i64::from(team_id))
+        .bind(from)
+        .bind(to)
         .fetch_all(pool)
         .await?;
     Ok(rows)
@@ -591,7 +3173,7 @@ pub async fn allocation_summary(pool: &AnyPool) -> Result<Vec<(i64, i64, i64, i6
 
 // --- User Game Tags ---
 
-pub async fn list_game_tags_for_user(pool: &AnyPool, user_id: i64) -> Result<Vec<GameTag>> {
+pub async fn list_game_tags_for_user(pool: &AnyPool, user_id: UserId) -> Result<Vec<GameTag>> {
     let sql = pg("SELECT user_id, game_pk, shortlist, cant_go \
          FROM user_game_tags \
          WHERE user_id = ?");
@@ -604,8 +3186,8 @@ pub async fn list_game_tags_for_user(pool: &AnyPool, user_id: i64) -> Result<Vec
 
 pub async fn upsert_game_tag(
     pool: &AnyPool,
-    user_id: i64,
-    game_pk: i64,
+    user_id: UserId,
+    game_pk: GamePk,
     shortlist: bool,
     cant_go: bool,
 ) -> Result<()> {
@@ -0,0 +1,189 @@
+//! Named locks that guarantee a job runs in only one process at a time.
+//!
+//! On Postgres a lock is a session advisory lock keyed by a hash of its
+//! name, held on a dedicated connection for the duration of the job; MySQL
+//! does the same with a named `GET_LOCK`. If the job is cancelled or panics
+//! before it can unlock, that connection is closed rather than returned to
+//! the pool, which ends the session and its lock with it. SQLite
+//! has no advisory locks, so there a row in `job_locks` stands in for one;
+//! its `expires_at` lets a lock abandoned by a crashed process be taken over
+//! once the TTL has passed.
+
+use anyhow::{Result, anyhow};
+use chrono::{SecondsFormat, Utc};
+use sqlx::any::AnyArguments;
+use sqlx::pool::PoolConnection;
+use sqlx::query::Query;
+use sqlx::{Any, AnyPool};
+use std::future::Future;
+use std::time::Duration;
+
+use crate::dialect::{self, Backend};
+use crate::pg;
+
+/// Stable 64-bit advisory lock key for a lock name (FNV-1a).
+pub fn lock_key(name: &str) -> i64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in name.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash as i64
+}
+
+/// Run `f` while holding the lock `name`. Returns `Ok(None)` without running
+/// `f` if another process holds it. `ttl` bounds how long the lock is held:
+/// `f` is cancelled with an error if it runs longer, and a SQLite lock left
+/// by a crashed holder can be taken over after it. Postgres and MySQL
+/// release locks when the session ends.
+pub async fn with_lock<T, F, Fut>(
+    pool: &AnyPool,
+    name: &str,
+    ttl: Duration,
+    f: F,
+) -> Result<Option<T>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    match dialect::backend() {
+        Backend::Postgres => return with_advisory_lock(pool, name, ttl, f).await,
+        Backend::MySql => return with_named_lock(pool, name, ttl, f).await,
+        Backend::Sqlite => {}
+    }
+
+    let holder = format!(
+        "{}-{}",
+        std::process::id(),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    );
+    let now = Utc::now();
+    let expires = now + chrono::Duration::from_std(ttl)?;
+    let acquire_sql = pg(
+        "INSERT INTO job_locks (name, holder, expires_at) VALUES (?, ?, ?) \
+         ON CONFLICT(name) DO UPDATE SET holder = excluded.holder, expires_at = excluded.expires_at \
+         WHERE job_locks.expires_at < ?",
+    );
+    let acquired = sqlx::query(&acquire_sql)
+        .bind(name)
+        .bind(&holder)
+        .bind(expires.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .bind(now.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .execute(pool)
+        .await?
+        .rows_affected()
+        > 0;
+    if !acquired {
+        return Ok(None);
+    }
+    let result = run_within(name, ttl, f).await;
+    let release_sql = pg("DELETE FROM job_locks WHERE name = ? AND holder = ?");
+    sqlx::query(&release_sql)
+        .bind(name)
+        .bind(&holder)
+        .execute(pool)
+        .await?;
+    result.map(Some)
+}
+
+/// Run `f`, cancelling it if it outlives `ttl`.
+async fn run_within<T, F, Fut>(name: &str, ttl: Duration, f: F) -> Result<T>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    tokio::time::timeout(ttl, f())
+        .await
+        .map_err(|_| anyhow!("Job `{name}` ran longer than its {ttl:?} lock TTL and was stopped"))?
+}
+
+/// A pooled connection holding a session lock. [`release`](Self::release)
+/// unlocks it and returns it to the pool; dropped without that (the job
+/// was cancelled or panicked), it's closed instead, so the lock doesn't
+/// outlive the job on a connection some other query borrows.
+struct SessionLock(Option<PoolConnection<Any>>);
+
+impl SessionLock {
+    fn conn(&mut self) -> &mut PoolConnection<Any> {
+        self.0.as_mut().expect("held until released")
+    }
+
+    /// Put the connection back in the pool; nothing was locked.
+    fn unused(mut self) {
+        self.0.take();
+    }
+
+    async fn release<'q>(mut self, unlock: Query<'q, Any, AnyArguments<'q>>) -> Result<()> {
+        let mut conn = self.0.take().expect("held until released");
+        if let Err(e) = unlock.execute(&mut *conn).await {
+            drop(conn.detach());
+            return Err(e.into());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        if let Some(conn) = self.0.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+async fn with_advisory_lock<T, F, Fut>(
+    pool: &AnyPool,
+    name: &str,
+    ttl: Duration,
+    f: F,
+) -> Result<Option<T>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let key = lock_key(name);
+    let mut lock = SessionLock(Some(pool.acquire().await?));
+    let locked = sqlx::query_scalar::<_, bool>("SELECT pg_try_advisory_lock($1)")
+        .bind(key)
+        .fetch_one(&mut **lock.conn())
+        .await?;
+    if !locked {
+        lock.unused();
+        return Ok(None);
+    }
+    let result = run_within(name, ttl, f).await;
+    lock.release(sqlx::query("SELECT pg_advisory_unlock($1)").bind(key))
+        .await?;
+    result.map(Some)
+}
+
+/// MySQL's `GET_LOCK` with no wait: 1 if taken, 0 if held elsewhere.
+async fn with_named_lock<T, F, Fut>(
+    pool: &AnyPool,
+    name: &str,
+    ttl: Duration,
+    f: F,
+) -> Result<Option<T>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut lock = SessionLock(Some(pool.acquire().await?));
+    let locked = sqlx::query_scalar::<_, i64>("SELECT GET_LOCK(?, 0)")
+        .bind(mysql_lock_name(name))
+        .fetch_one(&mut **lock.conn())
+        .await?;
+    if locked != 1 {
+        lock.unused();
+        return Ok(None);
+    }
+    let result = run_within(name, ttl, f).await;
+    lock.release(sqlx::query("SELECT RELEASE_LOCK(?)").bind(mysql_lock_name(name)))
+        .await?;
+    result.map(Some)
+}
+
+/// MySQL lock names are server-wide, so prefix them with the application.
+pub(crate) fn mysql_lock_name(name: &str) -> String {
+    format!("gtm:{name}")
+}
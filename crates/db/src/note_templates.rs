@@ -0,0 +1,230 @@
+//! Note templates: note text admins set on many tickets at once.
+//!
+//! Applying a template overwrites each target ticket's notes with the
+//! rendered body and logs one `note_applications` row per ticket, so the
+//! template in use can be reported on after the notes themselves change.
+
+use anyhow::{Result, bail};
+use gtm_models::{GamePk, NoteTemplate, TicketId, UserId};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+const TEMPLATE_SELECT: &str = "SELECT t.id, t.name, t.body, CAST(t.created_at AS TEXT) AS created_at, \
+        COUNT(a.id) AS applied, CAST(MAX(a.created_at) AS TEXT) AS last_applied_at \
+     FROM note_templates t \
+     LEFT JOIN note_applications a ON a.template_id = t.id";
+
+const TEMPLATE_GROUP: &str = " GROUP BY t.id, t.name, t.body, t.created_at";
+
+/// Which tickets to apply a template to.
+#[derive(Debug, Clone, Copy)]
+pub enum NoteTarget<'a> {
+    Tickets(&'a [TicketId]),
+    /// Every ticket for a game, optionally only those with this status.
+    Game {
+        game_pk: GamePk,
+        status: Option<&'a str>,
+    },
+}
+
+/// What a template's placeholders stand for on one ticket.
+#[derive(Debug, Clone, Default)]
+pub struct NoteContext {
+    /// `official_date` of the game (YYYY-MM-DD).
+    pub date: String,
+    pub opponent: String,
+    pub section: String,
+    pub row: String,
+    pub seat: String,
+}
+
+/// Fill in `{today}` and `{date}` (as M/D), `{opponent}` and `{seat}`
+/// (`section/row/seat`). Unknown placeholders are left as written.
+pub fn render(body: &str, today: &str, ctx: &NoteContext) -> String {
+    body.replace("{today}", &month_day(today))
+        .replace("{date}", &month_day(&ctx.date))
+        .replace("{opponent}", &ctx.opponent)
+        .replace(
+            "{seat}",
+            &format!("{}/{}/{}", ctx.section, ctx.row, ctx.seat),
+        )
+}
+
+/// `2026-06-01` as `6/1`; anything else unchanged.
+fn month_day(date: &str) -> String {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| d.format("%-m/%-d").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+pub async fn list(pool: &AnyPool) -> Result<Vec<NoteTemplate>> {
+    let sql = format!("{TEMPLATE_SELECT}{TEMPLATE_GROUP} ORDER BY t.name");
+    let templates = sqlx::query_as::<_, NoteTemplate>(&sql)
+        .fetch_all(pool)
+        .await?;
+    Ok(templates)
+}
+
+pub async fn get(pool: &AnyPool, id: i64) -> Result<Option<NoteTemplate>> {
+    let sql = pg(&format!("{TEMPLATE_SELECT} WHERE t.id = ?{TEMPLATE_GROUP}"));
+    let template = sqlx::query_as::<_, NoteTemplate>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(template)
+}
+
+fn validate(name: &str, body: &str) -> Result<()> {
+    if name.trim().is_empty() {
+        bail!("Template name can't be empty");
+    }
+    if body.trim().is_empty() {
+        bail!("Template body can't be empty");
+    }
+    Ok(())
+}
+
+pub async fn create(pool: &AnyPool, name: &str, body: &str) -> Result<NoteTemplate> {
+    validate(name, body)?;
+    sqlx::query(&pg("INSERT INTO note_templates (name, body) VALUES (?, ?)"))
+        .bind(name.trim())
+        .bind(body)
+        .execute(pool)
+        .await?;
+    let id: i64 = sqlx::query_scalar(&pg("SELECT id FROM note_templates WHERE name = ?"))
+        .bind(name.trim())
+        .fetch_one(pool)
+        .await?;
+    get(pool, id)
+        .await?
+        .ok_or_else(|| sqlx::Error::RowNotFound.into())
+}
+
+/// Returns `None` if the template doesn't exist.
+pub async fn update(
+    pool: &AnyPool,
+    id: i64,
+    name: &str,
+    body: &str,
+) -> Result<Option<NoteTemplate>> {
+    validate(name, body)?;
+    let result = sqlx::query(&pg(
+        "UPDATE note_templates SET name = ?, body = ? WHERE id = ?",
+    ))
+    .bind(name.trim())
+    .bind(body)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+    get(pool, id).await
+}
+
+/// Delete a template that has never been applied; one that has is kept for
+/// reporting and fails with a foreign key violation.
+pub async fn delete(pool: &AnyPool, id: i64) -> Result<bool> {
+    let result = sqlx::query(&pg("DELETE FROM note_templates WHERE id = ?"))
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+type TicketRow = (TicketId, String, String, String, String, String);
+
+/// The tickets `target` covers, with what their placeholders stand for.
+async fn target_tickets(pool: &AnyPool, target: NoteTarget<'_>) -> Result<Vec<TicketRow>> {
+    let mut sql = String::from(
+        "SELECT gt.id, g.official_date, g.away_team_name, s.section, s.row, s.seat \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         JOIN seats s ON s.id = gt.seat_id",
+    );
+    match target {
+        NoteTarget::Tickets(ids) => {
+            if ids.is_empty() {
+                return Ok(Vec::new());
+            }
+            sql.push_str(&format!(
+                " WHERE gt.id IN ({})",
+                vec!["?"; ids.len()].join(", ")
+            ));
+        }
+        NoteTarget::Game { status, .. } => {
+            sql.push_str(" WHERE gt.game_pk = ?");
+            if status.is_some() {
+                sql.push_str(" AND gt.status = ?");
+            }
+        }
+    }
+    sql.push_str(" ORDER BY gt.id");
+    let sql = pg(&sql);
+    let mut query = sqlx::query_as::<_, TicketRow>(&sql);
+    match target {
+        NoteTarget::Tickets(ids) => {
+            for id in ids {
+                query = query.bind(*id);
+            }
+        }
+        NoteTarget::Game { game_pk, status } => {
+            query = query.bind(game_pk);
+            if let Some(status) = status {
+                query = query.bind(status);
+            }
+        }
+    }
+    Ok(query.fetch_all(pool).await?)
+}
+
+/// How many tickets `target` covers.
+pub async fn count_targets(pool: &AnyPool, target: NoteTarget<'_>) -> Result<usize> {
+    Ok(target_tickets(pool, target).await?.len())
+}
+
+/// Set the rendered template as the notes of every ticket `target` covers,
+/// in one transaction, and log each. `today` (YYYY-MM-DD) fills `{today}`.
+/// Returns the number of tickets changed, or `None` if the template
+/// doesn't exist.
+pub async fn apply(
+    pool: &AnyPool,
+    template_id: i64,
+    target: NoteTarget<'_>,
+    applied_by: Option<UserId>,
+    today: &str,
+) -> Result<Option<usize>> {
+    let Some(template) = get(pool, template_id).await? else {
+        return Ok(None);
+    };
+    let tickets = target_tickets(pool, target).await?;
+    let mut tx = pool.begin().await?;
+    for (ticket_id, date, opponent, section, row, seat) in &tickets {
+        let ctx = NoteContext {
+            date: date.clone(),
+            opponent: opponent.clone(),
+            section: section.clone(),
+            row: row.clone(),
+            seat: seat.clone(),
+        };
+        let note = render(&template.body, today, &ctx);
+        sqlx::query(&pg(
+            "UPDATE game_tickets SET notes = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        ))
+        .bind(&note)
+        .bind(*ticket_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(&pg("INSERT INTO note_applications \
+                (template_id, game_ticket_id, note, applied_by) VALUES (?, ?, ?, ?)"))
+        .bind(template_id)
+        .bind(*ticket_id)
+        .bind(&note)
+        .bind(applied_by)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(Some(tickets.len()))
+}
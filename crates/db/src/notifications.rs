@@ -0,0 +1,135 @@
+//! Outbound notification queue.
+//!
+//! Messages are written to `notifications` when something happens and
+//! delivered later by a background task, so a slow or failing mail server
+//! never holds up an API request. Failed sends are retried up to
+//! [`MAX_ATTEMPTS`] times before the row is marked `failed`.
+
+use anyhow::Result;
+use gtm_models::{GamePk, QueuedNotification, UserId};
+use sqlx::AnyPool;
+
+use crate::compat::{self, Arg};
+use crate::pg;
+
+pub const TICKETS_ASSIGNED: &str = "tickets_assigned";
+pub const TICKETS_REVOKED: &str = "tickets_revoked";
+pub const TICKETS_TRANSFERRED: &str = "tickets_transferred";
+pub const GAME_FULLY_ALLOCATED: &str = "game_fully_allocated";
+pub const REQUEST_MOVED: &str = "request_moved";
+pub const REQUEST_POSTPONED: &str = "request_postponed";
+pub const REQUEST_FLAGGED: &str = "request_flagged";
+pub const REQUEST_DECLINED: &str = "request_declined";
+pub const GAME_MADE_UP: &str = "game_made_up";
+pub const GAME_PACKET: &str = "game_packet";
+/// Sent directly rather than queued, but named so members can turn them
+/// off in their preferences.
+pub const DAY_OF_RELEASE: &str = "day_of_release";
+pub const RAIN_RELEASE_PROMPT: &str = "rain_release_prompt";
+pub const UNASSIGNED_SEATS: &str = "unassigned_seats";
+
+pub const EMAIL: &str = "email";
+pub const SMS: &str = "sms";
+
+/// Delivery attempts before a notification is given up on.
+pub const MAX_ATTEMPTS: i64 = 5;
+
+/// A message to queue. `recipient` is an email address or E.164 number,
+/// depending on `channel`.
+#[derive(Debug)]
+pub struct NewNotification<'a> {
+    pub user_id: Option<UserId>,
+    pub game_pk: Option<GamePk>,
+    pub kind: &'a str,
+    pub channel: &'a str,
+    pub recipient: &'a str,
+    pub subject: &'a str,
+    pub body: &'a str,
+}
+
+pub async fn enqueue(pool: &AnyPool, n: &NewNotification<'_>) -> Result<i64> {
+    compat::insert_returning_id(
+        &mut *pool.acquire().await?,
+        "INSERT INTO notifications \
+            (user_id, game_pk, kind, channel, recipient, subject, body) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        &[
+            Arg::OptInt(n.user_id.map(i64::from)),
+            Arg::OptInt(n.game_pk.map(i64::from)),
+            Arg::Text(n.kind),
+            Arg::Text(n.channel),
+            Arg::Text(n.recipient),
+            Arg::Text(n.subject),
+            Arg::Text(n.body),
+        ],
+    )
+    .await
+}
+
+/// Whether a notification of `kind` about `game_pk` has already been queued
+/// for the member (failed deliveries don't count).
+pub async fn already_queued(
+    pool: &AnyPool,
+    user_id: UserId,
+    game_pk: GamePk,
+    kind: &str,
+) -> Result<bool> {
+    let sql = pg("SELECT COUNT(*) FROM notifications \
+         WHERE user_id = ? AND game_pk = ? AND kind = ? AND status != 'failed'");
+    let n = sqlx::query_scalar::<_, i64>(&sql)
+        .bind(user_id)
+        .bind(game_pk)
+        .bind(kind)
+        .fetch_one(pool)
+        .await?;
+    Ok(n > 0)
+}
+
+/// Oldest undelivered notifications first.
+pub async fn list_pending(pool: &AnyPool, limit: i64) -> Result<Vec<QueuedNotification>> {
+    let sql = pg(
+        "SELECT id, user_id, game_pk, kind, channel, recipient, subject, body, attempts \
+         FROM notifications WHERE status = 'pending' ORDER BY id LIMIT ?",
+    );
+    let rows = sqlx::query_as::<_, QueuedNotification>(&sql)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+pub async fn mark_sent(pool: &AnyPool, id: i64) -> Result<()> {
+    let sql = pg("UPDATE notifications \
+         SET status = 'sent', attempts = attempts + 1, sent_at = CURRENT_TIMESTAMP \
+         WHERE id = ?");
+    sqlx::query(&sql).bind(id).execute(pool).await?;
+    Ok(())
+}
+
+/// Record a failed attempt; the notification stays queued until it has
+/// failed [`MAX_ATTEMPTS`] times.
+///
+/// The new count and status are worked out here rather than in the
+/// `SET`, where MySQL would see `attempts` already incremented.
+pub async fn mark_failed(pool: &AnyPool, id: i64, error: &str) -> Result<()> {
+    let attempts =
+        sqlx::query_scalar::<_, i64>(&pg("SELECT attempts FROM notifications WHERE id = ?"))
+            .bind(id)
+            .fetch_one(pool)
+            .await?
+            + 1;
+    let status = if attempts >= MAX_ATTEMPTS {
+        "failed"
+    } else {
+        "pending"
+    };
+    let sql = pg("UPDATE notifications SET status = ?, attempts = ?, last_error = ? WHERE id = ?");
+    sqlx::query(&sql)
+        .bind(status)
+        .bind(attempts)
+        .bind(error)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
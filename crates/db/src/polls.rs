@@ -0,0 +1,179 @@
+//! Availability polls.
+//!
+//! A poll is a lighter-weight alternative to formal requests: an admin opens
+//! one on a game, members answer yes/no/maybe with a seat count, and closing
+//! it turns each "yes" into a pending request so the allocation engine
+//! picks it up like any other.
+
+use anyhow::{Result, bail};
+use gtm_models::{GamePk, MemberPoll, PollResponse, PollResults, PollSummary, UserId};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+pub const ANSWERS: &[&str] = &["yes", "no", "maybe"];
+
+/// Note on requests created from a poll.
+const POLL_REQUEST_NOTE: &str = "From availability poll";
+
+const SUMMARY_SELECT: &str = "SELECT p.id, p.game_pk, g.official_date, g.away_team_name, p.question, \
+        CAST(p.created_at AS TEXT) AS created_at, CAST(p.closed_at AS TEXT) AS closed_at, \
+        COALESCE(SUM(CASE WHEN r.answer = 'yes' THEN 1 ELSE 0 END), 0) AS yes, \
+        COALESCE(SUM(CASE WHEN r.answer = 'no' THEN 1 ELSE 0 END), 0) AS no, \
+        COALESCE(SUM(CASE WHEN r.answer = 'maybe' THEN 1 ELSE 0 END), 0) AS maybe, \
+        COALESCE(SUM(CASE WHEN r.answer = 'yes' THEN r.seats ELSE 0 END), 0) AS yes_seats, \
+        COALESCE(SUM(CASE WHEN r.answer = 'maybe' THEN r.seats ELSE 0 END), 0) AS maybe_seats \
+     FROM polls p \
+     JOIN games g ON g.game_pk = p.game_pk \
+     LEFT JOIN poll_responses r ON r.poll_id = p.id";
+
+const SUMMARY_GROUP: &str = " GROUP BY p.id, p.game_pk, g.official_date, g.away_team_name, \
+        p.question, p.created_at, p.closed_at, g.game_date";
+
+/// Open a poll on `game_pk`. Returns `None` if the game already has one.
+pub async fn create(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    question: Option<&str>,
+    created_by: UserId,
+) -> Result<Option<PollSummary>> {
+    let sql = pg(
+        "INSERT INTO polls (game_pk, question, created_by) VALUES (?, ?, ?) \
+         ON CONFLICT (game_pk) DO NOTHING",
+    );
+    let result = sqlx::query(&sql)
+        .bind(game_pk)
+        .bind(question)
+        .bind(created_by)
+        .execute(pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Ok(None);
+    }
+    let id: i64 = sqlx::query_scalar(&pg("SELECT id FROM polls WHERE game_pk = ?"))
+        .bind(game_pk)
+        .fetch_one(pool)
+        .await?;
+    summary(pool, id).await
+}
+
+pub async fn summary(pool: &AnyPool, id: i64) -> Result<Option<PollSummary>> {
+    let sql = pg(&format!("{SUMMARY_SELECT} WHERE p.id = ?{SUMMARY_GROUP}"));
+    let poll = sqlx::query_as::<_, PollSummary>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(poll)
+}
+
+/// Every poll, in game order.
+pub async fn list(pool: &AnyPool) -> Result<Vec<PollSummary>> {
+    let sql = format!("{SUMMARY_SELECT}{SUMMARY_GROUP} ORDER BY g.game_date, p.id");
+    let polls = sqlx::query_as::<_, PollSummary>(&sql)
+        .fetch_all(pool)
+        .await?;
+    Ok(polls)
+}
+
+/// A poll's tallies and answers, "yes" first.
+pub async fn results(pool: &AnyPool, id: i64) -> Result<Option<PollResults>> {
+    let Some(summary) = summary(pool, id).await? else {
+        return Ok(None);
+    };
+    let sql = pg("SELECT r.user_id, u.name AS user_name, r.answer, r.seats, \
+            CAST(r.updated_at AS TEXT) AS updated_at \
+         FROM poll_responses r \
+         JOIN users u ON u.id = r.user_id \
+         WHERE r.poll_id = ? \
+         ORDER BY CASE r.answer WHEN 'yes' THEN 0 WHEN 'maybe' THEN 1 ELSE 2 END, u.name");
+    let responses = sqlx::query_as::<_, PollResponse>(&sql)
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+    Ok(Some(PollResults { summary, responses }))
+}
+
+/// Record or change a member's answer. Returns false if the poll doesn't
+/// exist or is closed.
+pub async fn respond(
+    pool: &AnyPool,
+    poll_id: i64,
+    user_id: UserId,
+    answer: &str,
+    seats: i64,
+) -> Result<bool> {
+    if !ANSWERS.contains(&answer) {
+        bail!("Answer must be yes, no or maybe");
+    }
+    let sql = pg(
+        "INSERT INTO poll_responses (poll_id, user_id, answer, seats) \
+         SELECT ?, ?, ?, ? WHERE EXISTS (SELECT 1 FROM polls WHERE id = ? AND closed_at IS NULL) \
+         ON CONFLICT (poll_id, user_id) DO UPDATE SET \
+            answer = excluded.answer, \
+            seats = excluded.seats, \
+            updated_at = CURRENT_TIMESTAMP",
+    );
+    let result = sqlx::query(&sql)
+        .bind(poll_id)
+        .bind(user_id)
+        .bind(answer)
+        .bind(seats)
+        .bind(poll_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Open polls on games from `today` (YYYY-MM-DD) on, with the member's answers.
+pub async fn open_for_user(
+    pool: &AnyPool,
+    user_id: UserId,
+    today: &str,
+) -> Result<Vec<MemberPoll>> {
+    let sql = pg(
+        "SELECT p.id, p.game_pk, g.official_date, g.away_team_name, p.question, \
+            r.answer, r.seats \
+         FROM polls p \
+         JOIN games g ON g.game_pk = p.game_pk \
+         LEFT JOIN poll_responses r ON r.poll_id = p.id AND r.user_id = ? \
+         WHERE p.closed_at IS NULL AND g.official_date >= ? \
+         ORDER BY g.game_date",
+    );
+    let polls = sqlx::query_as::<_, MemberPoll>(&sql)
+        .bind(user_id)
+        .bind(today)
+        .fetch_all(pool)
+        .await?;
+    Ok(polls)
+}
+
+/// Close a poll and file a pending request for each "yes" answer, for the
+/// seats the member gave. Members who already have a request for the game
+/// keep it as is. Returns the number of requests created, or `None` if the
+/// poll doesn't exist or was already closed.
+pub async fn close(pool: &AnyPool, id: i64) -> Result<Option<u64>> {
+    let mut tx = pool.begin().await?;
+    crate::seasons::ensure_poll_open(&mut *tx, id).await?;
+    let closed = sqlx::query(&pg(
+        "UPDATE polls SET closed_at = CURRENT_TIMESTAMP WHERE id = ? AND closed_at IS NULL",
+    ))
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+    if closed.rows_affected() == 0 {
+        return Ok(None);
+    }
+    let created = sqlx::query(&pg(
+        "INSERT INTO ticket_requests (user_id, game_pk, seats_requested, notes) \
+         SELECT r.user_id, p.game_pk, r.seats, ? \
+         FROM poll_responses r JOIN polls p ON p.id = r.poll_id \
+         WHERE r.poll_id = ? AND r.answer = 'yes' AND r.seats > 0 \
+         ON CONFLICT (user_id, game_pk) DO NOTHING",
+    ))
+    .bind(POLL_REQUEST_NOTE)
+    .bind(id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(Some(created.rows_affected()))
+}
@@ -0,0 +1,237 @@
+//! Moving seats and requests from a postponed game to its makeup.
+//!
+//! When MLB postpones a game it keeps the game, marked "Postponed", sets
+//! `rescheduleDate` on it and may list the makeup as a new game with its
+//! own `game_pk`. Tickets and requests are keyed by `game_pk`, so they'd be
+//! left on the dead game. After each schedule sync, [`reconcile`] links
+//! every such game to its makeup, a newer game between the same teams on
+//! the reschedule date, and moves each member's seats to the same seats
+//! there and their request with them. A suspended game resumes under its
+//! own `game_pk`, so there's nothing to move.
+//!
+//! Whatever can't move stays on the postponed game: a seat that's missing
+//! or already held at the makeup, or a request when the member already has
+//! one for the makeup. The member's request is flagged so an admin sorts it
+//! out, as it is when the makeup falls on one of their blackout dates. A
+//! game is reconciled once; `rescheduled_to` records its makeup.
+
+use anyhow::Result;
+use gtm_models::{Game, GamePk, SeatId, TicketId, UserId};
+use sqlx::AnyPool;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::schedule_sync::has_blackout;
+use crate::{GAME_COLUMNS, assign_ticket_with, audit, pg};
+
+/// What happened to one member's seats and request when their game was
+/// made up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MakeupMove {
+    pub user_id: UserId,
+    /// The postponed game.
+    pub from: GamePk,
+    /// Its makeup.
+    pub to: GamePk,
+    /// Seats the member now holds at the makeup game.
+    pub seats: usize,
+    /// The member's request, if it moved to the makeup game.
+    pub request_id: Option<i64>,
+    /// What couldn't move or needs an admin's look, also set as the
+    /// request's `flag_reason`.
+    pub problem: Option<String>,
+}
+
+/// Link each postponed game with a reschedule date to its makeup, once the
+/// makeup is in the schedule, and move members' seats and requests there.
+/// Games in closed seasons are left alone.
+pub async fn reconcile(pool: &AnyPool) -> Result<Vec<MakeupMove>> {
+    let sql = pg(&format!(
+        "SELECT {GAME_COLUMNS} FROM games \
+         WHERE reschedule_date IS NOT NULL AND rescheduled_to IS NULL \
+           AND status_detailed LIKE 'Postponed%' \
+           AND season NOT IN (SELECT season FROM closed_seasons) \
+         ORDER BY game_date"
+    ));
+    let postponed = sqlx::query_as::<_, Game>(&sql).fetch_all(pool).await?;
+    let mut moves = Vec::new();
+    for game in postponed {
+        if let Some(makeup) = find_makeup(pool, &game).await? {
+            moves.extend(move_to_makeup(pool, game.game_pk, &makeup).await?);
+        }
+    }
+    Ok(moves)
+}
+
+fn is_off(status: &str) -> bool {
+    ["Postponed", "Suspended", "Cancelled"]
+        .iter()
+        .any(|s| status.starts_with(s))
+}
+
+/// The game `postponed` will be made up in: a newer game (a later
+/// `game_pk`) between the same home and away teams that isn't already some
+/// other game's makeup, at the reschedule time or, failing that, game 2 of
+/// a doubleheader on the reschedule's official date. The teams' regularly
+/// scheduled game that day is never taken for it; until the makeup is
+/// listed the game stays unlinked.
+async fn find_makeup(pool: &AnyPool, postponed: &Game) -> Result<Option<Game>> {
+    let Some(when) = postponed.reschedule_date.as_deref() else {
+        return Ok(None);
+    };
+    let sql = pg(&format!(
+        "SELECT {GAME_COLUMNS} FROM games \
+         WHERE home_team_id = ? AND away_team_id = ? AND game_pk > ? \
+           AND game_pk NOT IN (SELECT rescheduled_to FROM games WHERE rescheduled_to IS NOT NULL) \
+         ORDER BY game_date, game_number"
+    ));
+    let candidates: Vec<Game> = sqlx::query_as::<_, Game>(&sql)
+        .bind(postponed.home_team_id)
+        .bind(postponed.away_team_id)
+        .bind(postponed.game_pk)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .filter(|g| !is_off(&g.status_detailed))
+        .collect();
+    if let Some(exact) = candidates.iter().find(|g| g.game_date == when) {
+        return Ok(Some(exact.clone()));
+    }
+    let Some(day) = postponed.reschedule_official_date.as_deref() else {
+        return Ok(None);
+    };
+    Ok(candidates
+        .into_iter()
+        .find(|g| g.official_date == day && g.game_number > 1))
+}
+
+/// Move `from`'s assigned seats and open requests to `makeup` in one
+/// transaction, and record it as `from`'s makeup. A seat is given up at
+/// `from` only once it's been assigned at the makeup.
+async fn move_to_makeup(pool: &AnyPool, from: GamePk, makeup: &Game) -> Result<Vec<MakeupMove>> {
+    let to = makeup.game_pk;
+    let mut tx = pool.begin().await?;
+    let mut moves: BTreeMap<UserId, MakeupMove> = BTreeMap::new();
+    let mut stranded: HashMap<UserId, usize> = HashMap::new();
+    let entry = |moves: &mut BTreeMap<UserId, MakeupMove>, user_id| {
+        moves.entry(user_id).or_insert_with(|| MakeupMove {
+            user_id,
+            from,
+            to,
+            seats: 0,
+            request_id: None,
+            problem: None,
+        });
+    };
+
+    let held_sql = pg("SELECT id, seat_id, assigned_to FROM game_tickets \
+         WHERE game_pk = ? AND status = 'assigned' AND assigned_to IS NOT NULL ORDER BY id");
+    let held: Vec<(TicketId, SeatId, UserId)> = sqlx::query_as(&held_sql)
+        .bind(from)
+        .fetch_all(&mut *tx)
+        .await?;
+    let free_sql =
+        pg("SELECT seat_id, id FROM game_tickets WHERE game_pk = ? AND status = 'available'");
+    let mut free: HashMap<SeatId, TicketId> = sqlx::query_as::<_, (SeatId, TicketId)>(&free_sql)
+        .bind(to)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+    let release_sql = pg(
+        "UPDATE game_tickets SET assigned_to = NULL, status = 'available', \
+            updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    );
+    for (ticket_id, seat_id, user_id) in held {
+        entry(&mut moves, user_id);
+        let assigned = match free.remove(&seat_id) {
+            Some(makeup_ticket) => {
+                assign_ticket_with(&mut tx, makeup_ticket, user_id, None).await?
+            }
+            None => false,
+        };
+        if !assigned {
+            *stranded.entry(user_id).or_default() += 1;
+            continue;
+        }
+        sqlx::query(&release_sql)
+            .bind(ticket_id)
+            .execute(&mut *tx)
+            .await?;
+        audit::record(
+            &mut *tx,
+            &audit::AuditEvent {
+                action: audit::REVOKE,
+                game_ticket_id: Some(ticket_id),
+                user_id: Some(user_id),
+                before: Some("assigned"),
+                after: Some("available"),
+                ..Default::default()
+            },
+        )
+        .await?;
+        moves.get_mut(&user_id).expect("added above").seats += 1;
+    }
+    for (user_id, n) in stranded {
+        moves.get_mut(&user_id).expect("added above").problem = Some(format!(
+            "{n} seat(s) held for postponed game {from} are missing or taken at makeup game {to}"
+        ));
+    }
+
+    let requests_sql = pg("SELECT id, user_id FROM ticket_requests \
+         WHERE game_pk = ? AND status IN ('pending', 'approved') ORDER BY id");
+    let requests: Vec<(i64, UserId)> = sqlx::query_as(&requests_sql)
+        .bind(from)
+        .fetch_all(&mut *tx)
+        .await?;
+    let existing_sql = pg("SELECT user_id FROM ticket_requests WHERE game_pk = ?");
+    let existing: Vec<UserId> = sqlx::query_scalar(&existing_sql)
+        .bind(to)
+        .fetch_all(&mut *tx)
+        .await?;
+    let move_sql = pg("UPDATE ticket_requests SET game_pk = ?, \
+            flag_reason = COALESCE(?, flag_reason), updated_at = CURRENT_TIMESTAMP WHERE id = ?");
+    let flag_sql = pg(
+        "UPDATE ticket_requests SET flag_reason = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+    );
+    for (request_id, user_id) in requests {
+        entry(&mut moves, user_id);
+        let blackout = has_blackout(&mut *tx, user_id, &makeup.official_date).await?;
+        let member = moves.get_mut(&user_id).expect("added above");
+        let mut add_problem = |reason: String| {
+            member.problem = Some(match member.problem.take() {
+                Some(problem) => format!("{problem}; {reason}"),
+                None => reason,
+            });
+        };
+        if existing.contains(&user_id) {
+            add_problem(format!("Already requested makeup game {to}"));
+            sqlx::query(&flag_sql)
+                .bind(member.problem.as_deref())
+                .bind(request_id)
+                .execute(&mut *tx)
+                .await?;
+        } else {
+            if blackout {
+                add_problem(format!(
+                    "Rescheduled onto blackout date {}",
+                    makeup.official_date
+                ));
+            }
+            sqlx::query(&move_sql)
+                .bind(to)
+                .bind(member.problem.as_deref())
+                .bind(request_id)
+                .execute(&mut *tx)
+                .await?;
+            member.request_id = Some(request_id);
+        }
+    }
+
+    sqlx::query(&pg("UPDATE games SET rescheduled_to = ? WHERE game_pk = ?"))
+        .bind(to)
+        .bind(from)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(moves.into_values().collect())
+}
@@ -0,0 +1,255 @@
+//! Members' own settings: the seat count their requests default to, an
+//! email address for notifications other than the one they log in with,
+//! and which notifications they'd rather not get.
+//!
+//! Members who never saved any get [`UserPreferences::default`]. The muted
+//! notification kinds are stored comma-separated; only those in
+//! [`MUTABLE`] can be turned off, so admin alerts and the like always go
+//! out.
+//!
+//! A new contact address is only pending until the member follows the
+//! link mailed to it ([`confirm_contact_email`]); until then their email
+//! keeps going where it went before. Only a SHA-256 of the link's token is
+//! stored.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Result, bail};
+use gtm_models::{User, UserId, UserPreferences};
+use sha2::{Digest, Sha256};
+use sqlx::AnyPool;
+
+use crate::notifications::{
+    DAY_OF_RELEASE, GAME_FULLY_ALLOCATED, GAME_MADE_UP, GAME_PACKET, RAIN_RELEASE_PROMPT,
+    REQUEST_DECLINED, REQUEST_FLAGGED, REQUEST_MOVED, REQUEST_POSTPONED, TICKETS_ASSIGNED,
+    TICKETS_REVOKED, TICKETS_TRANSFERRED, UNASSIGNED_SEATS,
+};
+use crate::pg;
+
+/// Notification kinds a member can turn off.
+pub const MUTABLE: [&str; 13] = [
+    TICKETS_ASSIGNED,
+    TICKETS_REVOKED,
+    TICKETS_TRANSFERRED,
+    GAME_FULLY_ALLOCATED,
+    REQUEST_MOVED,
+    REQUEST_POSTPONED,
+    REQUEST_FLAGGED,
+    REQUEST_DECLINED,
+    GAME_MADE_UP,
+    GAME_PACKET,
+    DAY_OF_RELEASE,
+    RAIN_RELEASE_PROMPT,
+    UNASSIGNED_SEATS,
+];
+
+/// Longest contact address accepted, the width of the column on MySQL.
+pub const MAX_EMAIL_LEN: usize = 255;
+
+/// Changes to save with [`update`]. `None` leaves a setting alone;
+/// `Some(None)` clears it. A new `contact_email` is only pending until
+/// confirmed with `confirm_token`, which the caller generates and mails to
+/// it. `notifications` turns the kinds it names on or off and leaves the
+/// rest.
+#[derive(Debug, Clone, Default)]
+pub struct PreferencesUpdate {
+    pub default_seats: Option<Option<i64>>,
+    pub contact_email: Option<Option<String>>,
+    pub confirm_token: Option<String>,
+    pub notifications: BTreeMap<String, bool>,
+}
+
+type PrefsRow = (UserId, Option<i64>, Option<String>, Option<String>, String);
+
+fn from_row(
+    (_, default_seats, contact_email, pending_contact_email, muted): &PrefsRow,
+) -> UserPreferences {
+    let muted: Vec<&str> = muted.split(',').map(str::trim).collect();
+    UserPreferences {
+        default_seats: *default_seats,
+        contact_email: contact_email.clone(),
+        pending_contact_email: pending_contact_email.clone(),
+        notifications: MUTABLE
+            .iter()
+            .map(|kind| (kind.to_string(), !muted.contains(kind)))
+            .collect(),
+    }
+}
+
+fn defaults() -> UserPreferences {
+    from_row(&(UserId(0), None, None, None, String::new()))
+}
+
+fn hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+const PREFS_SELECT: &str = "SELECT user_id, default_seats, contact_email, pending_contact_email, \
+        muted_notifications \
+     FROM user_preferences";
+
+/// `user_id`'s preferences.
+pub async fn get(pool: &AnyPool, user_id: UserId) -> Result<UserPreferences> {
+    let sql = pg(&format!("{PREFS_SELECT} WHERE user_id = ?"));
+    let row: Option<PrefsRow> = sqlx::query_as(&sql)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.as_ref().map(from_row).unwrap_or_else(defaults))
+}
+
+/// The preferences of every member who has saved any, for sending to many
+/// members at once.
+pub async fn list(pool: &AnyPool) -> Result<HashMap<UserId, UserPreferences>> {
+    let rows: Vec<PrefsRow> = sqlx::query_as(PREFS_SELECT).fetch_all(pool).await?;
+    Ok(rows.iter().map(|row| (row.0, from_row(row))).collect())
+}
+
+/// Apply `changes` to `user_id`'s preferences and return the result. Fails
+/// on a seat count below 1, an address without an `@` or longer than
+/// [`MAX_EMAIL_LEN`], or a notification kind that can't be turned off.
+/// Clearing the contact address takes effect at once; a new one is left
+/// pending for [`confirm_contact_email`].
+pub async fn update(
+    pool: &AnyPool,
+    user_id: UserId,
+    changes: &PreferencesUpdate,
+) -> Result<UserPreferences> {
+    let mut prefs = get(pool, user_id).await?;
+    let sql = pg("SELECT contact_email_token FROM user_preferences WHERE user_id = ?");
+    let mut token_hash: Option<String> = sqlx::query_scalar(&sql)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .flatten();
+    if let Some(seats) = changes.default_seats {
+        if seats.is_some_and(|n| n < 1) {
+            bail!("default_seats must be at least 1");
+        }
+        prefs.default_seats = seats;
+    }
+    if let Some(email) = &changes.contact_email {
+        let email = email
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(str::to_string);
+        if email.as_deref().is_some_and(|e| !e.contains('@')) {
+            bail!("contact_email must be an email address");
+        }
+        if email.as_deref().is_some_and(|e| e.len() > MAX_EMAIL_LEN) {
+            bail!("contact_email must be at most {MAX_EMAIL_LEN} characters");
+        }
+        match email {
+            None => {
+                prefs.contact_email = None;
+                prefs.pending_contact_email = None;
+                token_hash = None;
+            }
+            Some(email) if prefs.contact_email.as_deref() == Some(email.as_str()) => {
+                prefs.pending_contact_email = None;
+                token_hash = None;
+            }
+            Some(email) => {
+                let Some(token) = &changes.confirm_token else {
+                    bail!("A new contact_email needs a confirmation token");
+                };
+                prefs.pending_contact_email = Some(email);
+                token_hash = Some(hash(token));
+            }
+        }
+    }
+    for (kind, on) in &changes.notifications {
+        if !MUTABLE.contains(&kind.as_str()) {
+            bail!(
+                "Unknown notification `{kind}`; use one of {}",
+                MUTABLE.join(", ")
+            );
+        }
+        prefs.notifications.insert(kind.clone(), *on);
+    }
+
+    let muted: Vec<&str> = prefs
+        .notifications
+        .iter()
+        .filter(|(_, on)| !**on)
+        .map(|(kind, _)| kind.as_str())
+        .collect();
+    let sql = pg(
+        "INSERT INTO user_preferences (user_id, default_seats, contact_email, \
+            pending_contact_email, contact_email_token, muted_notifications) \
+         VALUES (?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(user_id) DO UPDATE SET default_seats = excluded.default_seats, \
+            contact_email = excluded.contact_email, \
+            pending_contact_email = excluded.pending_contact_email, \
+            contact_email_token = excluded.contact_email_token, \
+            muted_notifications = excluded.muted_notifications, \
+            updated_at = CURRENT_TIMESTAMP",
+    );
+    sqlx::query(&sql)
+        .bind(user_id)
+        .bind(prefs.default_seats)
+        .bind(prefs.contact_email.as_deref())
+        .bind(prefs.pending_contact_email.as_deref())
+        .bind(token_hash.as_deref())
+        .bind(muted.join(","))
+        .execute(pool)
+        .await?;
+    Ok(prefs)
+}
+
+/// The pending contact address `token` confirms, if any, without using
+/// up the token.
+pub async fn pending_contact_email(pool: &AnyPool, token: &str) -> Result<Option<String>> {
+    let sql = pg("SELECT pending_contact_email FROM user_preferences \
+         WHERE contact_email_token = ? AND pending_contact_email IS NOT NULL");
+    Ok(sqlx::query_scalar::<_, Option<String>>(&sql)
+        .bind(hash(token))
+        .fetch_optional(pool)
+        .await?
+        .flatten())
+}
+
+/// Make the pending contact address `token` was mailed to the member's
+/// contact address. Returns the member and the address, or `None` if the
+/// token is unknown or already used.
+pub async fn confirm_contact_email(
+    pool: &AnyPool,
+    token: &str,
+) -> Result<Option<(UserId, String)>> {
+    let token_hash = hash(token);
+    let mut tx = pool.begin().await?;
+    let sql = pg(
+        "SELECT user_id, pending_contact_email FROM user_preferences \
+         WHERE contact_email_token = ? AND pending_contact_email IS NOT NULL",
+    );
+    let Some((user_id, email)) = sqlx::query_as::<_, (UserId, String)>(&sql)
+        .bind(&token_hash)
+        .fetch_optional(&mut *tx)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let sql = pg(
+        "UPDATE user_preferences SET contact_email = pending_contact_email, \
+            pending_contact_email = NULL, contact_email_token = NULL, \
+            updated_at = CURRENT_TIMESTAMP \
+         WHERE user_id = ? AND contact_email_token = ?",
+    );
+    let result = sqlx::query(&sql)
+        .bind(user_id)
+        .bind(&token_hash)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok((result.rows_affected() > 0).then_some((user_id, email)))
+}
+
+/// Where to email `user` a notification of `kind`: their contact address
+/// or account email, or `None` if they've turned `kind` off.
+pub async fn email_for(pool: &AnyPool, user: &User, kind: &str) -> Result<Option<String>> {
+    let prefs = get(pool, user.id).await?;
+    Ok(prefs
+        .wants(kind)
+        .then(|| prefs.email(&user.email).to_string()))
+}
@@ -0,0 +1,56 @@
+//! Stored game previews.
+//!
+//! A daily job regenerates the preview for each upcoming home game, so the
+//! API and reminder emails read it from here rather than calling the MLB
+//! Stats API themselves.
+
+use anyhow::Result;
+use gtm_models::{GamePk, GamePreview};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+/// Insert or replace the preview for `preview.game_pk`. `generated_at` is
+/// ignored and set to now.
+pub async fn upsert(pool: &AnyPool, preview: &GamePreview) -> Result<()> {
+    let sql = pg(
+        "INSERT INTO game_previews (game_pk, away_probable_pitcher, home_probable_pitcher, \
+            away_record, home_record, away_streak, home_streak, body) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT (game_pk) DO UPDATE SET \
+            away_probable_pitcher = excluded.away_probable_pitcher, \
+            home_probable_pitcher = excluded.home_probable_pitcher, \
+            away_record = excluded.away_record, \
+            home_record = excluded.home_record, \
+            away_streak = excluded.away_streak, \
+            home_streak = excluded.home_streak, \
+            body = excluded.body, \
+            generated_at = CURRENT_TIMESTAMP",
+    );
+    sqlx::query(&sql)
+        .bind(preview.game_pk)
+        .bind(preview.away_probable_pitcher.as_deref())
+        .bind(preview.home_probable_pitcher.as_deref())
+        .bind(preview.away_record.as_deref())
+        .bind(preview.home_record.as_deref())
+        .bind(preview.away_streak.as_deref())
+        .bind(preview.home_streak.as_deref())
+        .bind(&preview.body)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn get(pool: &AnyPool, game_pk: GamePk) -> Result<Option<GamePreview>> {
+    let sql = pg(
+        "SELECT game_pk, away_probable_pitcher, home_probable_pitcher, away_record, \
+            home_record, away_streak, home_streak, body, \
+            CAST(generated_at AS TEXT) AS generated_at \
+         FROM game_previews WHERE game_pk = ?",
+    );
+    let preview = sqlx::query_as::<_, GamePreview>(&sql)
+        .bind(game_pk)
+        .fetch_optional(pool)
+        .await?;
+    Ok(preview)
+}
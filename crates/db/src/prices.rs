@@ -0,0 +1,319 @@
+//! Per-game ticket prices from the account manager portal.
+//!
+//! The portal exports CSV with `date`, `opponent` and `price` columns (price
+//! in dollars), and optionally `game_number` for doubleheaders and
+//! `section`, `row` and `seat` to price one seat rather than every seat at
+//! the game. [`resolve`] matches each line to a home game by date and
+//! opponent and picks the tickets it prices; [`apply`] writes the matched
+//! lines' prices to `game_tickets.cost`, auditing each change, which the cost reports prefer to
+//! the seat's face value. Unmatched lines are reported and skipped, and
+//! running the same file again changes nothing.
+
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use gtm_models::{GamePk, TicketId, UserId};
+use sqlx::AnyPool;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use crate::audit::{self, AuditEvent};
+use crate::export::format_cents;
+use crate::import::{csv_records, parse_dollars};
+use crate::pg;
+
+/// One line of a price file, as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceLine {
+    /// Line of the CSV it came from.
+    pub line: usize,
+    pub date: NaiveDate,
+    pub opponent: String,
+    pub game_number: Option<i64>,
+    pub section: Option<String>,
+    pub row: Option<String>,
+    pub seat: Option<String>,
+    /// Cents.
+    pub price: i64,
+}
+
+/// A price line matched against the schedule and seats.
+#[derive(Debug, Clone)]
+pub struct PricedRow {
+    pub line: PriceLine,
+    pub game_pk: Option<GamePk>,
+    /// The opponent's full name, as the schedule has it.
+    pub opponent: Option<String>,
+    /// Tickets the line prices; empty when `problem` is set.
+    pub tickets: Vec<TicketId>,
+    pub problem: Option<String>,
+}
+
+const COLUMNS: [&str; 7] = [
+    "date",
+    "opponent",
+    "game_number",
+    "section",
+    "row",
+    "seat",
+    "price",
+];
+
+/// Parse a price file. Columns are matched by header name, in any order.
+pub fn parse_csv(text: &str) -> Result<Vec<PriceLine>> {
+    let mut records = csv_records(text)?.into_iter();
+    let Some((_, header)) = records.next() else {
+        bail!("CSV is empty");
+    };
+    let mut columns = HashMap::new();
+    for (i, name) in header.iter().enumerate() {
+        let name = name.trim().to_ascii_lowercase();
+        if !COLUMNS.contains(&name.as_str()) {
+            bail!("Unknown CSV column `{name}`");
+        }
+        columns.insert(name, i);
+    }
+    for required in ["date", "opponent", "price"] {
+        if !columns.contains_key(required) {
+            bail!("CSV header is missing the `{required}` column");
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (line, fields) in records {
+        if fields.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let field = |name: &str| {
+            columns
+                .get(name)
+                .and_then(|&i| fields.get(i))
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+        };
+        let date = field("date").unwrap_or_default();
+        let Some(date) = parse_date(&date) else {
+            bail!("Line {line}: bad date `{date}`; use YYYY-MM-DD or M/D/YYYY");
+        };
+        let Some(opponent) = field("opponent") else {
+            bail!("Line {line}: opponent is required");
+        };
+        let price = field("price").unwrap_or_default();
+        let Some(price) = parse_dollars(&price) else {
+            bail!("Line {line}: bad price `{price}`");
+        };
+        let game_number = match field("game_number") {
+            Some(n) => match n.parse::<i64>() {
+                Ok(n @ 1..=2) => Some(n),
+                _ => bail!("Line {line}: game_number must be 1 or 2"),
+            },
+            None => None,
+        };
+        let (section, row, seat) = (field("section"), field("row"), field("seat"));
+        if seat.is_some() && (section.is_none() || row.is_none()) {
+            bail!("Line {line}: a seat needs its section and row");
+        }
+        if row.is_some() && section.is_none() {
+            bail!("Line {line}: a row needs its section");
+        }
+        lines.push(PriceLine {
+            line,
+            date,
+            opponent,
+            game_number,
+            section,
+            row,
+            seat,
+            price,
+        });
+    }
+    if lines.is_empty() {
+        bail!("The file has no prices");
+    }
+    Ok(lines)
+}
+
+/// "2026-04-01" or "4/1/2026".
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%m/%d/%Y"))
+        .ok()
+}
+
+/// Whether `opponent`, as the portal writes it ("vs. Dodgers",
+/// "Los Angeles Dodgers"), names the team `name`: the whole name or its
+/// trailing words ("Dodgers", "Red Sox"), so a short or mistyped opponent
+/// matches nothing rather than the wrong game.
+fn names_team(opponent: &str, name: &str) -> bool {
+    let opponent = opponent.to_lowercase();
+    let opponent = opponent
+        .strip_prefix("vs.")
+        .or_else(|| opponent.strip_prefix("vs "))
+        .unwrap_or(&opponent)
+        .trim();
+    let name = name.to_lowercase();
+    !opponent.is_empty()
+        && (name == opponent
+            || name
+                .strip_suffix(opponent)
+                .is_some_and(|city| city.ends_with(' ')))
+}
+
+/// A home game a line may match.
+#[derive(sqlx::FromRow)]
+struct HomeGame {
+    game_pk: GamePk,
+    official_date: String,
+    away_team_name: String,
+    game_number: i64,
+    season: String,
+}
+
+/// A game's ticket and where its seat is.
+#[derive(sqlx::FromRow)]
+struct PricedTicket {
+    id: TicketId,
+    section: String,
+    row: String,
+    seat: String,
+}
+
+/// Match each line to one of team `team_id`'s home games and pick the
+/// tickets it prices. A line that matches no game, several games, no
+/// tickets or a closed season gets a `problem` and no tickets.
+pub async fn resolve(pool: &AnyPool, team_id: u32, lines: &[PriceLine]) -> Result<Vec<PricedRow>> {
+    let closed: HashSet<String> =
+        sqlx::query_scalar::<_, String>("SELECT season FROM closed_seasons")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .collect();
+    let sql = pg(
+        "SELECT game_pk, official_date, away_team_name, game_number, season \
+         FROM games WHERE home_team_id = ? ORDER BY game_date, game_number",
+    );
+    let games = sqlx::query_as::<_, HomeGame>(&sql)
+        .bind(i64::from(team_id))
+        .fetch_all(pool)
+        .await?;
+    let tickets_sql = pg("SELECT gt.id, s.section, s.row, s.seat \
+         FROM game_tickets gt JOIN seats s ON s.id = gt.seat_id \
+         WHERE gt.game_pk = ? ORDER BY s.section, s.row, s.seat");
+    let mut tickets: HashMap<GamePk, Vec<PricedTicket>> = HashMap::new();
+
+    let mut rows = Vec::new();
+    for line in lines {
+        let mut row = PricedRow {
+            line: line.clone(),
+            game_pk: None,
+            opponent: None,
+            tickets: Vec::new(),
+            problem: None,
+        };
+        let date = line.date.format("%Y-%m-%d").to_string();
+        let matches: Vec<&HomeGame> = games
+            .iter()
+            .filter(|g| g.official_date == date && names_team(&line.opponent, &g.away_team_name))
+            .filter(|g| line.game_number.is_none_or(|n| n == g.game_number))
+            .collect();
+        let game = match matches.as_slice() {
+            [game] => game,
+            [] => {
+                row.problem = Some("No home game on that date against that opponent".to_string());
+                rows.push(row);
+                continue;
+            }
+            _ => {
+                row.problem = Some("Doubleheader; add a game_number column".to_string());
+                rows.push(row);
+                continue;
+            }
+        };
+        row.game_pk = Some(game.game_pk);
+        row.opponent = Some(game.away_team_name.clone());
+        if closed.contains(&game.season) {
+            row.problem = Some(format!("The {} season is closed", game.season));
+            rows.push(row);
+            continue;
+        }
+        if let Entry::Vacant(entry) = tickets.entry(game.game_pk) {
+            entry.insert(
+                sqlx::query_as::<_, PricedTicket>(&tickets_sql)
+                    .bind(game.game_pk)
+                    .fetch_all(pool)
+                    .await?,
+            );
+        }
+        row.tickets = tickets[&game.game_pk]
+            .iter()
+            .filter(|t| line.section.as_ref().is_none_or(|s| *s == t.section))
+            .filter(|t| line.row.as_ref().is_none_or(|r| *r == t.row))
+            .filter(|t| line.seat.as_ref().is_none_or(|s| *s == t.seat))
+            .map(|t| t.id)
+            .collect();
+        if row.tickets.is_empty() {
+            row.problem = Some("No matching seats at that game".to_string());
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Write the price of every matched line to its tickets in one
+/// transaction, later lines winning where two price the same ticket, and
+/// audit each change as `actor_id`. Returns how many tickets' prices
+/// changed; tickets already at their price are left alone.
+pub async fn apply(pool: &AnyPool, rows: &[PricedRow], actor_id: UserId) -> Result<u64> {
+    let mut prices: HashMap<TicketId, i64> = HashMap::new();
+    for row in rows.iter().filter(|r| r.problem.is_none()) {
+        for ticket in &row.tickets {
+            prices.insert(*ticket, row.line.price);
+        }
+    }
+    let mut by_price: HashMap<i64, Vec<TicketId>> = HashMap::new();
+    for (ticket, price) in prices {
+        by_price.entry(price).or_default().push(ticket);
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut changed = 0;
+    for (price, tickets) in by_price {
+        for chunk in tickets.chunks(500) {
+            let sql = pg(&format!(
+                "SELECT id, cost FROM game_tickets \
+                 WHERE id IN ({}) AND (cost IS NULL OR cost <> ?)",
+                vec!["?"; chunk.len()].join(", ")
+            ));
+            let mut query = sqlx::query_as::<_, (TicketId, Option<i64>)>(&sql);
+            for ticket in chunk {
+                query = query.bind(*ticket);
+            }
+            let stale = query.bind(price).fetch_all(&mut *tx).await?;
+            let after = format_cents(price);
+            for (ticket, cost) in stale {
+                sqlx::query(&pg(
+                    "UPDATE game_tickets SET cost = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+                ))
+                .bind(price)
+                .bind(ticket)
+                .execute(&mut *tx)
+                .await?;
+                let before = cost.map(format_cents);
+                audit::record(
+                    &mut *tx,
+                    &AuditEvent {
+                        actor_id: Some(actor_id),
+                        action: audit::PRICE,
+                        game_ticket_id: Some(ticket),
+                        before: before.as_deref(),
+                        after: Some(&after),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+                changed += 1;
+            }
+        }
+    }
+    tx.commit().await?;
+    Ok(changed)
+}
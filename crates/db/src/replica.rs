@@ -0,0 +1,134 @@
+//! An optional read replica for list and report queries.
+//!
+//! Postgres deployments can point `db_read_url` at a streaming replica to
+//! take read-heavy pages off the primary. The replica is health-checked
+//! with [`ReadPool::check`]; while it is down, or if none is configured,
+//! reads go to the primary. A read through [`ReadPool::run`] that loses its
+//! replica connection is retried on the primary straight away. Replicas lag, so only reads that can tolerate
+//! a slightly stale view belong here — never a read that decides a write.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use sqlx::AnyPool;
+use tracing::{info, warn};
+
+use crate::{ConnectOptions, backend_for};
+
+/// Where pure reads go: the replica while it answers, else the primary.
+#[derive(Clone)]
+pub struct ReadPool {
+    primary: AnyPool,
+    replica: Option<AnyPool>,
+    healthy: Arc<AtomicBool>,
+}
+
+impl ReadPool {
+    /// Reads go to the primary; there is no replica.
+    pub fn primary_only(primary: AnyPool) -> Self {
+        Self {
+            primary,
+            replica: None,
+            healthy: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Add a replica at `url`, connecting lazily so an unreachable replica
+    /// doesn't stop startup. It is checked once before returning.
+    pub async fn with_replica(
+        primary: AnyPool,
+        url: &str,
+        options: &ConnectOptions,
+    ) -> Result<Self> {
+        sqlx::any::install_default_drivers();
+        let replica = options
+            .pool_options(backend_for(url)?, true)
+            .connect_lazy_with(options.connect_options(url)?);
+        let pool = Self {
+            primary,
+            replica: Some(replica),
+            healthy: Arc::new(AtomicBool::new(false)),
+        };
+        if !pool.check().await {
+            warn!("Read replica unreachable; reading from the primary until it answers");
+        }
+        Ok(pool)
+    }
+
+    /// The pool to read from right now.
+    pub fn pool(&self) -> &AnyPool {
+        match &self.replica {
+            Some(replica) if self.healthy.load(Ordering::Relaxed) => replica,
+            _ => &self.primary,
+        }
+    }
+
+    /// Run `read` on the pool to read from. If it was the replica and the
+    /// read failed to reach it, reads go to the primary until the next
+    /// [`check`](Self::check), and `read` runs again there.
+    pub async fn run<T, F, Fut>(&self, read: F) -> Result<T>
+    where
+        F: Fn(AnyPool) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let replica = match &self.replica {
+            Some(replica) if self.healthy.load(Ordering::Relaxed) => replica,
+            _ => return read(self.primary.clone()).await,
+        };
+        match read(replica.clone()).await {
+            Err(e) if is_connection_error(&e) => {
+                if self.healthy.swap(false, Ordering::Relaxed) {
+                    warn!(error = %e, "Read replica unavailable; reading from the primary");
+                }
+                read(self.primary.clone()).await
+            }
+            result => result,
+        }
+    }
+
+    /// Whether reads are currently going to the replica.
+    pub fn using_replica(&self) -> bool {
+        self.replica.is_some() && self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// Ping the replica and route reads by the result, logging when that
+    /// changes. Returns whether the replica answered.
+    pub async fn check(&self) -> bool {
+        let Some(replica) = &self.replica else {
+            return false;
+        };
+        let ok = match sqlx::query("SELECT 1").execute(replica).await {
+            Ok(_) => true,
+            Err(e) => {
+                if self.healthy.load(Ordering::Relaxed) {
+                    warn!(error = %e, "Read replica unavailable; reading from the primary");
+                }
+                false
+            }
+        };
+        let was = self.healthy.swap(ok, Ordering::Relaxed);
+        if ok && !was {
+            info!("Read replica available; routing reads to it");
+        }
+        ok
+    }
+}
+
+/// Whether `e` came from failing to reach the database rather than from
+/// the query.
+fn is_connection_error(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<sqlx::Error>(),
+            Some(
+                sqlx::Error::Io(_)
+                    | sqlx::Error::Tls(_)
+                    | sqlx::Error::PoolTimedOut
+                    | sqlx::Error::PoolClosed
+                    | sqlx::Error::WorkerCrashed
+            )
+        )
+    })
+}
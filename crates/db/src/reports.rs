@@ -0,0 +1,560 @@
+//! Season reports for admins.
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+use gtm_models::{
+    AllocationRound, AttendanceGrid, AttendanceMember, AttendanceRow, FairnessSeries,
+    FairnessTimeline, GamePk, GameTicketDetail, GameType, MemberHistory, RenewalMember,
+    RenewalReport, RenewalSeat, SeasonHistory, TicketCoverage, TicketRequest, Transparency,
+    TransparencyRow, UncoveredGame, User, UserId,
+};
+use sqlx::AnyPool;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::timing::timed;
+use crate::{OWN_ADMISSION, audit, game_type_placeholders, parse_timestamp, pg};
+
+/// A release this close to first pitch counts against the member as late.
+pub const LATE_RELEASE_HOURS: i64 = 48;
+
+/// Games with this admin tag count as premium on the transparency page.
+pub const PREMIUM_TAG: &str = "premium";
+
+/// Every member's assigned seats in `season`, cumulative by the week of the
+/// game, from the first ticketed game's week to the last.
+pub async fn fairness_timeline(pool: &AnyPool, season: &str) -> Result<FairnessTimeline> {
+    let sql = pg("SELECT gt.assigned_to, g.official_date, COUNT(*) \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         WHERE gt.assigned_to IS NOT NULL AND gt.status = 'assigned' AND g.season = ? \
+         GROUP BY gt.assigned_to, g.official_date");
+    let assigned: Vec<(UserId, String, i64)> = timed(
+        "fairness_timeline assigned",
+        sqlx::query_as(&sql).bind(season).fetch_all(pool),
+    )
+    .await?;
+    let sql = pg(
+        "SELECT MIN(g.official_date), MAX(g.official_date) FROM games g \
+         WHERE g.season = ? AND EXISTS (SELECT 1 FROM game_tickets gt WHERE gt.game_pk = g.game_pk)",
+    );
+    let (first, last): (Option<String>, Option<String>) = timed(
+        "fairness_timeline weeks",
+        sqlx::query_as(&sql).bind(season).fetch_one(pool),
+    )
+    .await?;
+
+    let mut weeks = Vec::new();
+    if let (Some(first), Some(last)) = (first, last) {
+        let mut week = week_start(&first)?;
+        let last = week_start(&last)?;
+        while week <= last {
+            weeks.push(week);
+            week += Duration::weeks(1);
+        }
+    }
+
+    let mut per_week: HashMap<UserId, Vec<i64>> = HashMap::new();
+    for (user_id, date, seats) in assigned {
+        let week = week_start(&date)?;
+        let Some(w) = weeks.iter().position(|d| *d == week) else {
+            continue;
+        };
+        per_week
+            .entry(user_id)
+            .or_insert_with(|| vec![0; weeks.len()])[w] += seats;
+    }
+    let members = crate::list_users(pool)
+        .await?
+        .into_iter()
+        .map(|u| {
+            let mut cumulative = per_week
+                .remove(&u.id)
+                .unwrap_or_else(|| vec![0; weeks.len()]);
+            for w in 1..cumulative.len() {
+                cumulative[w] += cumulative[w - 1];
+            }
+            FairnessSeries {
+                user_id: u.id,
+                user_name: u.name,
+                cumulative,
+            }
+        })
+        .collect();
+    Ok(FairnessTimeline {
+        season: season.to_string(),
+        weeks: weeks.iter().map(|d| d.to_string()).collect(),
+        members,
+    })
+}
+
+/// The Monday on or before an `official_date`.
+fn week_start(official_date: &str) -> Result<NaiveDate> {
+    let date = NaiveDate::parse_from_str(official_date, "%Y-%m-%d")?;
+    Ok(date - Duration::days(date.weekday().num_days_from_monday().into()))
+}
+
+/// Seat utilization, member spend and resale recovery for games in `season`
+/// played before `as_of` (YYYY-MM-DD), for the renewal decision.
+pub async fn renewal(pool: &AnyPool, season: &str, as_of: &str) -> Result<RenewalReport> {
+    let sql = pg(
+        "SELECT s.id AS seat_id, s.section, s.row, s.seat, s.face_value, \
+                COUNT(*) AS games, \
+                SUM(CASE WHEN gt.assigned_to IS NOT NULL AND gt.resale_amount IS NULL \
+                    THEN 1 ELSE 0 END) AS used, \
+                SUM(CASE WHEN gt.resale_amount IS NOT NULL THEN 1 ELSE 0 END) AS resold, \
+                SUM(CASE WHEN gt.assigned_to IS NULL AND gt.resale_amount IS NULL \
+                    THEN 1 ELSE 0 END) AS unused, \
+                SUM(COALESCE(gt.cost, s.face_value, 0)) AS cost, \
+                SUM(CASE WHEN gt.assigned_to IS NULL AND gt.resale_amount IS NULL \
+                    THEN COALESCE(gt.cost, s.face_value, 0) ELSE 0 END) AS unused_loss, \
+                SUM(COALESCE(gt.resale_amount, 0)) AS resale_total, \
+                SUM(CASE WHEN gt.resale_amount IS NOT NULL \
+                    THEN COALESCE(gt.cost, s.face_value, 0) ELSE 0 END) AS resold_cost \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         JOIN seats s ON s.id = gt.seat_id \
+         WHERE g.season = ? AND g.official_date < ? \
+         GROUP BY s.id, s.section, s.row, s.seat, s.face_value \
+         ORDER BY s.section, s.row, s.seat",
+    );
+    let mut seats = timed(
+        "renewal seats",
+        sqlx::query_as::<_, RenewalSeat>(&sql)
+            .bind(season)
+            .bind(as_of)
+            .fetch_all(pool),
+    )
+    .await?;
+    for seat in &mut seats {
+        seat.utilization_pct = percent(seat.used, seat.games).unwrap_or(0.0);
+    }
+
+    let sql = pg(
+        "SELECT gt.assigned_to, COUNT(DISTINCT gt.game_pk), COUNT(*), \
+                SUM(COALESCE(s.face_value, 0)) \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         JOIN seats s ON s.id = gt.seat_id \
+         WHERE gt.assigned_to IS NOT NULL AND g.season = ? AND g.official_date < ? \
+         GROUP BY gt.assigned_to",
+    );
+    let usage: Vec<(UserId, i64, i64, i64)> = timed(
+        "renewal usage",
+        sqlx::query_as(&sql)
+            .bind(season)
+            .bind(as_of)
+            .fetch_all(pool),
+    )
+    .await?;
+    let mut members: BTreeMap<UserId, RenewalMember> = BTreeMap::new();
+    let blank = |user_id| RenewalMember {
+        user_id,
+        user_name: String::new(),
+        games: 0,
+        seats: 0,
+        face_value: 0,
+        spend: 0,
+    };
+    for (user_id, games, seats, face_value) in usage {
+        let m = members.entry(user_id).or_insert_with(|| blank(user_id));
+        m.games = games;
+        m.seats = seats;
+        m.face_value = face_value;
+    }
+    for period in crate::ledger::cost_split(pool, season, None).await? {
+        for share in period.members {
+            for line in share
+                .lines
+                .iter()
+                .filter(|l| l.official_date.as_str() < as_of)
+            {
+                let m = members
+                    .entry(line.user_id)
+                    .or_insert_with(|| blank(line.user_id));
+                m.spend += line.amount;
+            }
+        }
+    }
+    for user in crate::list_users(pool).await? {
+        if let Some(m) = members.get_mut(&user.id) {
+            m.user_name = user.name;
+        }
+    }
+    let mut members: Vec<RenewalMember> = members.into_values().collect();
+    members.sort_by(|a, b| a.user_name.cmp(&b.user_name));
+
+    let resold_cost: i64 = seats.iter().map(|s| s.resold_cost).sum();
+    let resale_total: i64 = seats.iter().map(|s| s.resale_total).sum();
+    Ok(RenewalReport {
+        season: season.to_string(),
+        as_of: as_of.to_string(),
+        total_cost: seats.iter().map(|s| s.cost).sum(),
+        unused_loss: seats.iter().map(|s| s.unused_loss).sum(),
+        resold: seats.iter().map(|s| s.resold).sum(),
+        resale_total,
+        resale_recovery_pct: percent(resale_total, resold_cost),
+        seats,
+        members,
+    })
+}
+
+/// Game, date, opponent, holder (`None` for unheld seats), holder's name
+/// and seat count.
+type GridCell = (GamePk, String, String, Option<UserId>, Option<String>, i64);
+
+/// Seats each member holds at each of `season`'s games with tickets: games
+/// in date order down, members by name across.
+pub async fn attendance_grid(pool: &AnyPool, season: &str) -> Result<AttendanceGrid> {
+    let sql = pg(
+        "SELECT g.game_pk, g.official_date, g.away_team_name, u.id, u.name, COUNT(*) \
+         FROM game_tickets gt \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         LEFT JOIN users u ON u.id = gt.assigned_to AND gt.status = 'assigned' \
+         WHERE g.season = ? \
+         GROUP BY g.game_pk, g.official_date, g.game_date, g.away_team_name, u.id, u.name \
+         ORDER BY g.game_date, g.game_pk",
+    );
+    let cells: Vec<GridCell> = timed(
+        "attendance_grid",
+        sqlx::query_as(&sql).bind(season).fetch_all(pool),
+    )
+    .await?;
+
+    let mut members: Vec<AttendanceMember> = Vec::new();
+    for (_, _, _, user_id, name, _) in &cells {
+        if let (Some(user_id), Some(name)) = (user_id, name)
+            && !members.iter().any(|m| m.user_id == *user_id)
+        {
+            members.push(AttendanceMember {
+                user_id: *user_id,
+                name: name.clone(),
+            });
+        }
+    }
+    members.sort_by(|a, b| a.name.cmp(&b.name).then(a.user_id.cmp(&b.user_id)));
+    let column: HashMap<UserId, usize> = members
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.user_id, i))
+        .collect();
+
+    let mut games: Vec<AttendanceRow> = Vec::new();
+    for (game_pk, official_date, opponent, user_id, _, seats) in cells {
+        if games.last().is_none_or(|r| r.game_pk != game_pk) {
+            games.push(AttendanceRow {
+                game_pk,
+                official_date,
+                opponent,
+                seats: vec![0; members.len()],
+                unassigned: 0,
+            });
+        }
+        let row = games.last_mut().expect("pushed above");
+        match user_id {
+            Some(id) => row.seats[column[&id]] += seats,
+            None => row.unassigned += seats,
+        }
+    }
+    Ok(AttendanceGrid {
+        season: season.to_string(),
+        members,
+        games,
+    })
+}
+
+/// `part` as a percentage of `whole`, or `None` if `whole` is zero.
+fn percent(part: i64, whole: i64) -> Option<f64> {
+    (whole != 0).then(|| part as f64 * 100.0 / whole as f64)
+}
+
+/// Per-season request, allocation, attendance, no-show and late-release
+/// counts for one member, newest season first.
+pub async fn member_history(pool: &AnyPool, user: &User) -> Result<MemberHistory> {
+    let mut seasons: BTreeMap<String, SeasonHistory> = BTreeMap::new();
+
+    let sql = pg("SELECT g.season, COUNT(DISTINCT tr.game_pk) \
+         FROM ticket_requests tr JOIN games g ON g.game_pk = tr.game_pk \
+         WHERE tr.user_id = ? GROUP BY g.season");
+    let requested: Vec<(String, i64)> = timed(
+        "member_history requested",
+        sqlx::query_as(&sql).bind(user.id).fetch_all(pool),
+    )
+    .await?;
+    for (name, n) in requested {
+        season_for(&mut seasons, name).requested = n;
+    }
+
+    let sql = pg(&format!(
+        "SELECT g.season, COUNT(DISTINCT a.game_pk) \
+         FROM (SELECT game_pk FROM game_tickets WHERE assigned_to = ? \
+               UNION SELECT game_pk FROM audit_log \
+               WHERE user_id = ? AND action IN ('{}', '{}')) a \
+         JOIN games g ON g.game_pk = a.game_pk \
+         GROUP BY g.season",
+        audit::ASSIGN,
+        audit::RELEASE
+    ));
+    let allocated: Vec<(String, i64)> = timed(
+        "member_history allocated",
+        sqlx::query_as(&sql)
+            .bind(user.id)
+            .bind(user.id)
+            .fetch_all(pool),
+    )
+    .await?;
+    for (name, n) in allocated {
+        season_for(&mut seasons, name).allocated = n;
+    }
+
+    // Recorded attendance wins; unrecorded games count as attended if the
+    // member still held seats when they were played.
+    let sql = pg("SELECT g.season, \
+            COUNT(DISTINCT CASE WHEN a.attended = 1 THEN g.game_pk END), \
+            COUNT(DISTINCT CASE WHEN a.attended = 0 THEN g.game_pk END) \
+         FROM (SELECT game_pk, attended FROM game_attendance WHERE user_id = ? \
+               UNION SELECT gt.game_pk, 1 FROM game_tickets gt \
+               JOIN games pl ON pl.game_pk = gt.game_pk \
+               WHERE gt.assigned_to = ? AND pl.status_abstract = 'Final' \
+                 AND pl.status_detailed NOT LIKE 'Postponed%' \
+                 AND pl.status_detailed NOT LIKE 'Cancelled%' \
+                 AND NOT EXISTS (SELECT 1 FROM game_attendance ga \
+                                 WHERE ga.game_pk = gt.game_pk AND ga.user_id = ?)) a \
+         JOIN games g ON g.game_pk = a.game_pk \
+         GROUP BY g.season");
+    let attended: Vec<(String, i64, i64)> = timed(
+        "member_history attended",
+        sqlx::query_as(&sql)
+            .bind(user.id)
+            .bind(user.id)
+            .bind(user.id)
+            .fetch_all(pool),
+    )
+    .await?;
+    for (name, attended, missed) in attended {
+        let season = season_for(&mut seasons, name);
+        season.attended = attended;
+        season.missed = missed;
+    }
+
+    let sql = pg(
+        "SELECT g.season, g.game_pk, g.game_date, CAST(a.created_at AS TEXT) \
+         FROM audit_log a JOIN games g ON g.game_pk = a.game_pk \
+         WHERE a.user_id = ? AND a.action = ?",
+    );
+    let releases: Vec<(String, i64, String, String)> = timed(
+        "member_history releases",
+        sqlx::query_as(&sql)
+            .bind(user.id)
+            .bind(audit::RELEASE)
+            .fetch_all(pool),
+    )
+    .await?;
+    let mut late = HashSet::new();
+    for (name, game_pk, game_date, released_at) in releases {
+        let (Some(start), Some(released)) =
+            (parse_timestamp(&game_date), parse_timestamp(&released_at))
+        else {
+            continue;
+        };
+        if start - released < Duration::hours(LATE_RELEASE_HOURS) && late.insert(game_pk) {
+            season_for(&mut seasons, name).released_late += 1;
+        }
+    }
+
+    Ok(MemberHistory {
+        user_id: user.id,
+        user_name: user.name.clone(),
+        seasons: seasons.into_values().rev().collect(),
+    })
+}
+
+/// The history row for a season, added if new.
+fn season_for(seasons: &mut BTreeMap<String, SeasonHistory>, name: String) -> &mut SeasonHistory {
+    seasons
+        .entry(name.clone())
+        .or_insert_with(|| SeasonHistory {
+            season: name,
+            requested: 0,
+            allocated: 0,
+            attended: 0,
+            missed: 0,
+            released_late: 0,
+        })
+}
+
+/// Seats, premium games and declined requests per member in `season`, for
+/// members to compare among themselves. With `named` false, members other
+/// than `viewer` are numbered in the order listed instead of named.
+pub async fn transparency(
+    pool: &AnyPool,
+    season: &str,
+    viewer: UserId,
+    named: bool,
+) -> Result<Transparency> {
+    let sql = pg("SELECT u.id, u.name, \
+            (SELECT COUNT(*) FROM game_tickets gt JOIN games g ON g.game_pk = gt.game_pk \
+             WHERE gt.assigned_to = u.id AND gt.status = 'assigned' AND g.season = ?), \
+            (SELECT COUNT(DISTINCT gt.game_pk) FROM game_tickets gt \
+             JOIN games g ON g.game_pk = gt.game_pk \
+             WHERE gt.assigned_to = u.id AND gt.status = 'assigned' AND g.season = ?), \
+            (SELECT COUNT(DISTINCT gt.game_pk) FROM game_tickets gt \
+             JOIN games g ON g.game_pk = gt.game_pk \
+             JOIN game_tags t ON t.game_pk = gt.game_pk AND t.tag = ? \
+             WHERE gt.assigned_to = u.id AND gt.status = 'assigned' AND g.season = ?), \
+            (SELECT COUNT(*) FROM ticket_requests r JOIN games g ON g.game_pk = r.game_pk \
+             WHERE r.user_id = u.id AND r.status <> 'withdrawn' AND g.season = ?), \
+            (SELECT COUNT(*) FROM ticket_requests r JOIN games g ON g.game_pk = r.game_pk \
+             WHERE r.user_id = u.id AND r.status = 'declined' AND g.season = ?) \
+         FROM users u ORDER BY u.id");
+    let rows: Vec<(UserId, String, i64, i64, i64, i64, i64)> = timed(
+        "transparency",
+        sqlx::query_as(&sql)
+            .bind(season)
+            .bind(season)
+            .bind(PREMIUM_TAG)
+            .bind(season)
+            .bind(season)
+            .bind(season)
+            .fetch_all(pool),
+    )
+    .await?;
+
+    let mut rows: Vec<_> = rows.into_iter().filter(|r| r.2 > 0 || r.5 > 0).collect();
+    rows.sort_by_key(|r| (std::cmp::Reverse(r.2), std::cmp::Reverse(r.4), r.0));
+    let members = rows
+        .into_iter()
+        .enumerate()
+        .map(
+            |(i, (user_id, name, seats, games, premium, requests, denied))| {
+                let you = user_id == viewer;
+                let (user_id, name) = if named || you {
+                    (Some(user_id), name)
+                } else {
+                    (None, format!("Member {}", i + 1))
+                };
+                TransparencyRow {
+                    user_id,
+                    name,
+                    you,
+                    seats_received: seats,
+                    games_received: games,
+                    premium_games: premium,
+                    requests,
+                    requests_denied: denied,
+                }
+            },
+        )
+        .collect();
+    Ok(Transparency {
+        season: season.to_string(),
+        named,
+        members,
+    })
+}
+
+/// Team `team_id`'s home games of the `ticketed` types that have no
+/// tickets, in date order. The second game of a traditional double-header
+/// shares the first's tickets and isn't counted.
+pub async fn ticket_coverage(
+    pool: &AnyPool,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<TicketCoverage> {
+    let active_seats: i64 = timed(
+        "ticket_coverage seats",
+        sqlx::query_scalar("SELECT COUNT(*) FROM seats WHERE deleted_at IS NULL").fetch_one(pool),
+    )
+    .await?;
+    if ticketed.is_empty() {
+        return Ok(TicketCoverage {
+            active_seats,
+            home_games: 0,
+            uncovered: Vec::new(),
+        });
+    }
+    let home = format!(
+        "g.home_team_id = ? AND g.game_type IN ({}) AND {OWN_ADMISSION}",
+        game_type_placeholders(ticketed)
+    );
+
+    let sql = pg(&format!("SELECT COUNT(*) FROM games g WHERE {home}"));
+    let mut query = sqlx::query_scalar::<_, i64>(&sql).bind(i64::from(team_id));
+    for t in ticketed {
+        query = query.bind(t.code());
+    }
+    let home_games = timed("ticket_coverage home_games", query.fetch_one(pool)).await?;
+
+    let sql = pg(&format!(
+        "SELECT g.game_pk, g.official_date, g.game_type, g.away_team_name AS opponent, \
+                g.status_detailed \
+         FROM games g \
+         WHERE {home} \
+           AND NOT EXISTS (SELECT 1 FROM game_tickets gt WHERE gt.game_pk = g.game_pk) \
+         ORDER BY g.official_date, g.game_pk"
+    ));
+    let mut query = sqlx::query_as::<_, UncoveredGame>(&sql).bind(i64::from(team_id));
+    for t in ticketed {
+        query = query.bind(t.code());
+    }
+    let uncovered = timed("ticket_coverage uncovered", query.fetch_all(pool)).await?;
+
+    Ok(TicketCoverage {
+        active_seats,
+        home_games,
+        uncovered,
+    })
+}
+
+/// Every `season` game with tickets, in date order, with its tickets and
+/// requests (oldest first, whatever their status), for replaying the
+/// season's allocation.
+pub async fn allocation_rounds(pool: &AnyPool, season: &str) -> Result<Vec<AllocationRound>> {
+    let sql = pg(
+        "SELECT gt.id, gt.game_pk, gt.seat_id, s.section, s.row, s.seat, gt.status, gt.notes, gt.assigned_to \
+         FROM game_tickets gt \
+         JOIN seats s ON s.id = gt.seat_id \
+         JOIN games g ON g.game_pk = gt.game_pk \
+         WHERE g.season = ? \
+         ORDER BY g.official_date, g.game_date, g.game_pk, s.section, s.row, s.seat",
+    );
+    let tickets: Vec<GameTicketDetail> = timed(
+        "allocation_rounds tickets",
+        sqlx::query_as(&sql).bind(season).fetch_all(pool),
+    )
+    .await?;
+    let sql = pg(
+        "SELECT r.id, r.user_id, r.game_pk, r.seats_requested, r.status, r.notes, r.flag_reason \
+         FROM ticket_requests r \
+         JOIN games g ON g.game_pk = r.game_pk \
+         WHERE g.season = ? \
+         ORDER BY r.created_at, r.id",
+    );
+    let requests: Vec<TicketRequest> = timed(
+        "allocation_rounds requests",
+        sqlx::query_as(&sql).bind(season).fetch_all(pool),
+    )
+    .await?;
+
+    let mut rounds: Vec<AllocationRound> = Vec::new();
+    for ticket in tickets {
+        match rounds.last_mut() {
+            Some(round) if round.game_pk == ticket.game_pk => round.tickets.push(ticket),
+            _ => rounds.push(AllocationRound {
+                game_pk: ticket.game_pk,
+                tickets: vec![ticket],
+                requests: Vec::new(),
+            }),
+        }
+    }
+    let index: HashMap<GamePk, usize> = rounds
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (r.game_pk, i))
+        .collect();
+    for request in requests {
+        if let Some(&i) = index.get(&request.game_pk) {
+            rounds[i].requests.push(request);
+        }
+    }
+    Ok(rounds)
+}
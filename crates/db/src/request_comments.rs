@@ -0,0 +1,89 @@
+//! Comments on ticket requests: a thread between the member who made the
+//! request and the admins handling it.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, bail};
+use gtm_models::{RequestComment, UserId};
+use sqlx::AnyPool;
+
+use crate::compat::{self, Arg};
+use crate::pg;
+
+/// Longest comment accepted, in characters.
+pub const MAX_COMMENT_CHARS: usize = 2000;
+
+const COMMENT_SELECT: &str = "SELECT c.id, c.request_id, c.user_id, u.name AS author_name, \
+        c.from_admin, c.body, CAST(c.created_at AS TEXT) AS created_at \
+     FROM request_comments c JOIN users u ON u.id = c.user_id";
+
+/// A request's comments, oldest first.
+pub async fn list(pool: &AnyPool, request_id: i64) -> Result<Vec<RequestComment>> {
+    let sql = pg(&format!(
+        "{COMMENT_SELECT} WHERE c.request_id = ? ORDER BY c.id"
+    ));
+    let comments = sqlx::query_as::<_, RequestComment>(&sql)
+        .bind(request_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(comments)
+}
+
+pub async fn get(pool: &AnyPool, id: i64) -> Result<Option<RequestComment>> {
+    let sql = pg(&format!("{COMMENT_SELECT} WHERE c.id = ?"));
+    let comment = sqlx::query_as::<_, RequestComment>(&sql)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(comment)
+}
+
+/// Add a comment. The body is trimmed and must be non-empty and at most
+/// [`MAX_COMMENT_CHARS`].
+pub async fn create(
+    pool: &AnyPool,
+    request_id: i64,
+    user_id: UserId,
+    from_admin: bool,
+    body: &str,
+) -> Result<RequestComment> {
+    let body = body.trim();
+    if body.is_empty() {
+        bail!("Comment can't be empty");
+    }
+    if body.chars().count() > MAX_COMMENT_CHARS {
+        bail!("Comment is longer than {MAX_COMMENT_CHARS} characters");
+    }
+    let id = compat::insert_returning_id(
+        &mut *pool.acquire().await?,
+        "INSERT INTO request_comments (request_id, user_id, from_admin, body) \
+         VALUES (?, ?, ?, ?)",
+        &[
+            Arg::Int(request_id),
+            Arg::Int(user_id.0),
+            Arg::Int(i64::from(from_admin)),
+            Arg::Text(body),
+        ],
+    )
+    .await?;
+    get(pool, id).await?.context("Comment insert wrote no row")
+}
+
+/// The most recent comment on each of `request_ids` that has one.
+pub async fn latest(pool: &AnyPool, request_ids: &[i64]) -> Result<HashMap<i64, RequestComment>> {
+    if request_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let placeholders = vec!["?"; request_ids.len()].join(", ");
+    let sql = pg(&format!(
+        "{COMMENT_SELECT} WHERE c.id IN \
+            (SELECT MAX(id) FROM request_comments \
+             WHERE request_id IN ({placeholders}) GROUP BY request_id)"
+    ));
+    let mut query = sqlx::query_as::<_, RequestComment>(&sql);
+    for id in request_ids {
+        query = query.bind(*id);
+    }
+    let comments = query.fetch_all(pool).await?;
+    Ok(comments.into_iter().map(|c| (c.request_id, c)).collect())
+}
@@ -0,0 +1,108 @@
+//! Named game filters members save and apply by name.
+//!
+//! A view stores a [`GameFilter`] as JSON. Names are unique per member;
+//! saving under an existing name replaces that view's filter.
+
+use anyhow::{Result, bail};
+use gtm_models::UserId;
+use serde::Serialize;
+use sqlx::AnyPool;
+
+use crate::{GameFilter, pg};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedView {
+    pub id: i64,
+    pub user_id: UserId,
+    pub name: String,
+    pub filter: GameFilter,
+    pub created_at: String,
+}
+
+type ViewRow = (i64, UserId, String, String, String);
+
+const VIEW_SELECT: &str =
+    "SELECT id, user_id, name, filter, CAST(created_at AS TEXT) AS created_at FROM saved_views";
+
+fn from_row((id, user_id, name, filter, created_at): ViewRow) -> Result<SavedView> {
+    Ok(SavedView {
+        id,
+        user_id,
+        name,
+        filter: serde_json::from_str(&filter)?,
+        created_at,
+    })
+}
+
+/// A member's views, by name.
+pub async fn list(pool: &AnyPool, user_id: UserId) -> Result<Vec<SavedView>> {
+    let sql = pg(&format!("{VIEW_SELECT} WHERE user_id = ? ORDER BY name"));
+    let rows: Vec<ViewRow> = sqlx::query_as(&sql).bind(user_id).fetch_all(pool).await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+pub async fn get(pool: &AnyPool, id: i64) -> Result<Option<SavedView>> {
+    let sql = pg(&format!("{VIEW_SELECT} WHERE id = ?"));
+    let row: Option<ViewRow> = sqlx::query_as(&sql).bind(id).fetch_optional(pool).await?;
+    row.map(from_row).transpose()
+}
+
+/// Views called `name`, optionally only `user_id`'s. More than one means
+/// several members used the same name.
+pub async fn find_by_name(
+    pool: &AnyPool,
+    name: &str,
+    user_id: Option<UserId>,
+) -> Result<Vec<SavedView>> {
+    let mut sql = format!("{VIEW_SELECT} WHERE name = ?");
+    if user_id.is_some() {
+        sql.push_str(" AND user_id = ?");
+    }
+    let sql = pg(&sql);
+    let mut query = sqlx::query_as::<_, ViewRow>(&sql).bind(name);
+    if let Some(user_id) = user_id {
+        query = query.bind(user_id);
+    }
+    query
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(from_row)
+        .collect()
+}
+
+/// Save `filter` as the member's view `name`, replacing any view already
+/// called that.
+pub async fn save(
+    pool: &AnyPool,
+    user_id: UserId,
+    name: &str,
+    filter: &GameFilter,
+) -> Result<SavedView> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("View name can't be empty");
+    }
+    let json = serde_json::to_string(filter)?;
+    sqlx::query(&pg(
+        "INSERT INTO saved_views (user_id, name, filter) VALUES (?, ?, ?) \
+         ON CONFLICT (user_id, name) DO UPDATE SET filter = excluded.filter",
+    ))
+    .bind(user_id)
+    .bind(name)
+    .bind(&json)
+    .execute(pool)
+    .await?;
+    let mut views = find_by_name(pool, name, Some(user_id)).await?;
+    views.pop().ok_or(sqlx::Error::RowNotFound.into())
+}
+
+/// Delete one of the member's views. Returns false if they have no such view.
+pub async fn delete(pool: &AnyPool, user_id: UserId, id: i64) -> Result<bool> {
+    let result = sqlx::query(&pg("DELETE FROM saved_views WHERE id = ? AND user_id = ?"))
+        .bind(id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
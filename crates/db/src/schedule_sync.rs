@@ -0,0 +1,437 @@
+//! Schedule refresh with a changeset.
+//!
+//! [`sync_schedule`] compares a freshly scraped schedule against the games
+//! already stored, upserts it, and reports what changed so the caller can
+//! log it. Seats and requests for postponed games are moved to their makeup
+//! games by [`postponements::reconcile`]; pending requests for games that
+//! moved, were postponed or were cancelled are then carried over or flagged
+//! by [`carry_over_requests`].
+//!
+//! A scrape that comes back with far less than is stored is more likely an
+//! MLB API hiccup than a real change, so [`check_scrape`] compares it with
+//! the stored schedule first. The caller still applies a scrape it objects
+//! to with [`apply_schedule`], which only upserts what came back, but
+//! doesn't let it move members' seats and requests.
+
+use anyhow::Result;
+use gtm_models::{Game, GamePk, GameType, ScheduleData, UserId};
+use serde_json::json;
+use sqlx::{AnyConnection, AnyPool};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::jobs;
+use crate::postponements::{self, MakeupMove};
+use crate::{
+    GameFilter, ScheduleUpsert, get_game, list_games, list_requests_for_game, pg,
+    upsert_schedule_with, webhooks,
+};
+
+/// One difference between the stored and the scraped schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleChange {
+    /// A game we hadn't seen before.
+    Added { game_pk: GamePk, game_date: String },
+    /// The first pitch moved (`game_date`, UTC).
+    Rescheduled {
+        game_pk: GamePk,
+        from: String,
+        to: String,
+    },
+    /// `status_detailed` changed, e.g. "Scheduled" → "Postponed".
+    StatusChanged {
+        game_pk: GamePk,
+        from: String,
+        to: String,
+    },
+    /// Runs were scored (away, home).
+    ScoreChanged {
+        game_pk: GamePk,
+        away: Option<i64>,
+        home: Option<i64>,
+    },
+}
+
+impl ScheduleChange {
+    pub fn game_pk(&self) -> GamePk {
+        match self {
+            ScheduleChange::Added { game_pk, .. }
+            | ScheduleChange::Rescheduled { game_pk, .. }
+            | ScheduleChange::StatusChanged { game_pk, .. }
+            | ScheduleChange::ScoreChanged { game_pk, .. } => *game_pk,
+        }
+    }
+}
+
+impl fmt::Display for ScheduleChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleChange::Added { game_pk, game_date } => {
+                write!(f, "game {game_pk}: added ({game_date})")
+            }
+            ScheduleChange::Rescheduled { game_pk, from, to } => {
+                write!(f, "game {game_pk}: rescheduled {from} -> {to}")
+            }
+            ScheduleChange::StatusChanged { game_pk, from, to } => {
+                write!(f, "game {game_pk}: status {from} -> {to}")
+            }
+            ScheduleChange::ScoreChanged {
+                game_pk,
+                away,
+                home,
+            } => {
+                let score = |s: &Option<i64>| s.map_or("-".to_string(), |n| n.to_string());
+                write!(f, "game {game_pk}: score {}-{}", score(away), score(home))
+            }
+        }
+    }
+}
+
+/// Changes from `stored` to `scraped`, in scraped order. Games missing from
+/// the scrape aren't reported, since the upsert never deletes them.
+pub fn diff_schedule(stored: &[Game], scraped: &[Game]) -> Vec<ScheduleChange> {
+    let stored: HashMap<GamePk, &Game> = stored.iter().map(|g| (g.game_pk, g)).collect();
+    let mut changes = Vec::new();
+    for new in scraped {
+        let Some(old) = stored.get(&new.game_pk) else {
+            changes.push(ScheduleChange::Added {
+                game_pk: new.game_pk,
+                game_date: new.game_date.clone(),
+            });
+            continue;
+        };
+        if old.game_date != new.game_date {
+            changes.push(ScheduleChange::Rescheduled {
+                game_pk: new.game_pk,
+                from: old.game_date.clone(),
+                to: new.game_date.clone(),
+            });
+        }
+        if old.status_detailed != new.status_detailed {
+            changes.push(ScheduleChange::StatusChanged {
+                game_pk: new.game_pk,
+                from: old.status_detailed.clone(),
+                to: new.status_detailed.clone(),
+            });
+        }
+        if (old.away_score, old.home_score) != (new.away_score, new.home_score) {
+            changes.push(ScheduleChange::ScoreChanged {
+                game_pk: new.game_pk,
+                away: new.away_score,
+                home: new.home_score,
+            });
+        }
+    }
+    changes
+}
+
+/// What happened to a pending request when its game changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CarryoverOutcome {
+    /// The request follows the game; `from` is the old `game_date`.
+    Moved { from: String },
+    /// Postponed with no new date yet; the request stays open.
+    Postponed,
+    /// Left for an admin, with `flag_reason` set on the request.
+    Flagged { reason: String },
+}
+
+/// A pending request touched by a schedule change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestCarryover {
+    pub request_id: i64,
+    pub user_id: UserId,
+    pub game_pk: GamePk,
+    pub outcome: CarryoverOutcome,
+}
+
+/// Carry pending requests over to their game's new date, or flag them.
+///
+/// Requests are keyed by `game_pk`, so a moved game keeps its requests; this
+/// decides what each member should hear. A request is flagged instead when
+/// the new date is one of the member's blackouts, or when the game is
+/// cancelled outright.
+pub async fn carry_over_requests(
+    pool: &AnyPool,
+    changes: &[ScheduleChange],
+) -> Result<Vec<RequestCarryover>> {
+    // Collapse each game's changes: (old game_date if moved, new status)
+    let mut games: Vec<(GamePk, Option<&str>, Option<&str>)> = Vec::new();
+    for change in changes {
+        let (game_pk, moved, status) = match change {
+            ScheduleChange::Rescheduled { game_pk, from, .. } => (*game_pk, Some(from), None),
+            ScheduleChange::StatusChanged { game_pk, to, .. } => (*game_pk, None, Some(to)),
+            _ => continue,
+        };
+        match games.iter_mut().find(|(pk, _, _)| *pk == game_pk) {
+            Some(entry) => {
+                entry.1 = entry.1.or(moved.map(String::as_str));
+                entry.2 = entry.2.or(status.map(String::as_str));
+            }
+            None => games.push((
+                game_pk,
+                moved.map(String::as_str),
+                status.map(String::as_str),
+            )),
+        }
+    }
+
+    let mut carried = Vec::new();
+    for (game_pk, moved, status) in games {
+        let cancelled = status.is_some_and(|s| s.starts_with("Cancelled"));
+        let postponed = status.is_some_and(|s| s.starts_with("Postponed"));
+        if !cancelled && !postponed && moved.is_none() {
+            continue;
+        }
+        let Some(game) = get_game(pool, game_pk).await? else {
+            continue;
+        };
+        for req in list_requests_for_game(pool, game_pk).await? {
+            if req.status != "pending" {
+                continue;
+            }
+            let outcome = if cancelled {
+                CarryoverOutcome::Flagged {
+                    reason: "Game cancelled".to_string(),
+                }
+            } else if let Some(from) = moved {
+                if has_blackout(pool, req.user_id, &game.official_date).await? {
+                    CarryoverOutcome::Flagged {
+                        reason: format!("Rescheduled onto blackout date {}", game.official_date),
+                    }
+                } else {
+                    CarryoverOutcome::Moved {
+                        from: from.to_string(),
+                    }
+                }
+            } else {
+                CarryoverOutcome::Postponed
+            };
+            if let CarryoverOutcome::Flagged { reason } = &outcome {
+                flag_request(pool, req.id, reason).await?;
+            }
+            carried.push(RequestCarryover {
+                request_id: req.id,
+                user_id: req.user_id,
+                game_pk,
+                outcome,
+            });
+        }
+    }
+    Ok(carried)
+}
+
+/// Whether `date` is one of the member's blackouts.
+pub(crate) async fn has_blackout<'e, E>(executor: E, user_id: UserId, date: &str) -> Result<bool>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    let sql = pg("SELECT COUNT(*) FROM user_blackouts WHERE user_id = ? AND blackout_date = ?");
+    let n = sqlx::query_scalar::<_, i64>(&sql)
+        .bind(user_id)
+        .bind(date)
+        .fetch_one(executor)
+        .await?;
+    Ok(n > 0)
+}
+
+async fn flag_request(pool: &AnyPool, request_id: i64, reason: &str) -> Result<()> {
+    let sql = pg(
+        "UPDATE ticket_requests SET flag_reason = ?, updated_at = CURRENT_TIMESTAMP \
+         WHERE id = ?",
+    );
+    sqlx::query(&sql)
+        .bind(reason)
+        .bind(request_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Result of [`sync_schedule`].
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleSync {
+    pub upsert: ScheduleUpsert,
+    pub changes: Vec<ScheduleChange>,
+    pub carryovers: Vec<RequestCarryover>,
+    /// Members' seats and requests moved from postponed games to makeups.
+    pub makeups: Vec<MakeupMove>,
+    /// Why the scrape was only applied rather than synced, from
+    /// [`check_scrape`]; empty when it was synced.
+    pub anomalies: Vec<String>,
+}
+
+/// Limits a scrape must pass before it is synced. Zero turns a check off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncThresholds {
+    /// Fewest games a scrape may return once games are stored.
+    pub min_games: usize,
+    /// Drop in promotions, as a percentage of those stored, at which the
+    /// scrape is suspect.
+    pub promotion_drop_pct: u32,
+}
+
+/// Compare a scrape of `season`'s `game_types` games with what's stored for
+/// them, and describe anything that breaks `thresholds`. Promotions are
+/// only compared when `promotions_fetched`, since a scrape without the
+/// promotions hydration returns none.
+pub async fn check_scrape(
+    pool: &AnyPool,
+    data: &ScheduleData,
+    season: u32,
+    game_types: &[GameType],
+    promotions_fetched: bool,
+    thresholds: &SyncThresholds,
+) -> Result<Vec<String>> {
+    if game_types.is_empty() {
+        return Ok(Vec::new());
+    }
+    let stored_games = count_stored(pool, "games g", season, game_types).await?;
+    let stored_promotions = count_stored(
+        pool,
+        "promotions p JOIN games g ON g.game_pk = p.game_pk",
+        season,
+        game_types,
+    )
+    .await?;
+
+    let mut anomalies = Vec::new();
+    let games = data
+        .games
+        .iter()
+        .map(|g| g.game_pk)
+        .collect::<HashSet<_>>()
+        .len();
+    if stored_games > 0 && games < thresholds.min_games {
+        anomalies.push(format!(
+            "Scrape returned {games} game(s) for {season}; {stored_games} are stored"
+        ));
+    }
+    let promotions = data
+        .promotions
+        .iter()
+        .map(|p| (p.offer_id, p.game_pk))
+        .collect::<HashSet<_>>()
+        .len() as i64;
+    if promotions_fetched
+        && thresholds.promotion_drop_pct > 0
+        && stored_promotions > 0
+        && (stored_promotions - promotions) * 100
+            >= stored_promotions * i64::from(thresholds.promotion_drop_pct)
+    {
+        anomalies.push(format!(
+            "Scrape returned {promotions} promotion(s) for {season}; {stored_promotions} are stored"
+        ));
+    }
+    Ok(anomalies)
+}
+
+/// `anomalies` as one job-run detail, one per line.
+pub fn anomaly_detail(anomalies: &[String]) -> String {
+    anomalies.join("\n")
+}
+
+/// Those of `anomalies` the scheduled scrape's last run wasn't already
+/// suspect of, so admins hear about each anomaly once rather than nightly.
+pub async fn unreported_anomalies(pool: &AnyPool, anomalies: &[String]) -> Result<Vec<String>> {
+    let last = jobs::get(pool, jobs::SCHEDULE_SCRAPE).await?;
+    let reported: HashSet<&str> = last
+        .as_ref()
+        .filter(|run| run.status == jobs::SUSPECT)
+        .and_then(|run| run.detail.as_deref())
+        .map(|detail| detail.lines().collect())
+        .unwrap_or_default();
+    Ok(anomalies
+        .iter()
+        .filter(|a| !reported.contains(a.as_str()))
+        .cloned()
+        .collect())
+}
+
+/// Changes listed in one `schedule.updated` webhook payload; the rest are
+/// only counted.
+const MAX_WEBHOOK_CHANGES: usize = 50;
+
+/// Queue a `schedule.updated` webhook for games added, moved or with a new
+/// status. Score updates alone don't count: they arrive every few minutes
+/// during a game.
+async fn notify_webhooks(conn: &mut AnyConnection, changes: &[ScheduleChange]) -> Result<()> {
+    let changed: Vec<&ScheduleChange> = changes
+        .iter()
+        .filter(|c| !matches!(c, ScheduleChange::ScoreChanged { .. }))
+        .collect();
+    if changed.is_empty() {
+        return Ok(());
+    }
+    let mut game_pks: Vec<GamePk> = changed.iter().map(|c| c.game_pk()).collect();
+    game_pks.dedup();
+    let listed: Vec<String> = changed
+        .iter()
+        .take(MAX_WEBHOOK_CHANGES)
+        .map(|c| c.to_string())
+        .collect();
+    webhooks::enqueue(
+        conn,
+        webhooks::SCHEDULE_UPDATED,
+        json!({ "total": changed.len(), "game_pks": game_pks, "changes": listed }),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Rows of `from` (which names `games` as `g`) for `season`'s `game_types`.
+async fn count_stored(
+    pool: &AnyPool,
+    from: &str,
+    season: u32,
+    game_types: &[GameType],
+) -> Result<i64> {
+    let sql = pg(&format!(
+        "SELECT COUNT(*) FROM {from} WHERE g.season = ? AND g.game_type IN ({})",
+        vec!["?"; game_types.len()].join(", ")
+    ));
+    let mut query = sqlx::query_scalar::<_, i64>(&sql).bind(season.to_string());
+    for t in game_types {
+        query = query.bind(t.code());
+    }
+    Ok(query.fetch_one(pool).await?)
+}
+
+/// Diff `data` against the stored games, upsert it, move seats and requests
+/// from postponed games to their makeups, then carry pending requests over
+/// for any games that moved, and queue a webhook if anything but scores
+/// changed.
+pub async fn sync_schedule(
+    pool: &AnyPool,
+    data: &ScheduleData,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<ScheduleSync> {
+    let mut sync = apply_schedule(pool, data, team_id, ticketed).await?;
+    sync.makeups = postponements::reconcile(pool).await?;
+    sync.carryovers = carry_over_requests(pool, &sync.changes).await?;
+    Ok(sync)
+}
+
+/// Diff `data` against the stored games, upsert it and queue a webhook,
+/// leaving members' seats and requests alone. Games and promotions the
+/// scrape left out are kept, so this is safe for a scrape [`check_scrape`]
+/// objects to.
+pub async fn apply_schedule(
+    pool: &AnyPool,
+    data: &ScheduleData,
+    team_id: u32,
+    ticketed: &[GameType],
+) -> Result<ScheduleSync> {
+    let stored = list_games(pool, &GameFilter::default()).await?;
+    let changes = diff_schedule(&stored, &data.games);
+    let mut tx = pool.begin().await?;
+    let upsert = upsert_schedule_with(&mut tx, data, team_id, ticketed).await?;
+    notify_webhooks(&mut tx, &changes).await?;
+    tx.commit().await?;
+    Ok(ScheduleSync {
+        upsert,
+        changes,
+        ..Default::default()
+    })
+}
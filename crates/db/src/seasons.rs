@@ -0,0 +1,132 @@
+//! Seasons and closing them.
+//!
+//! Every season stays in the database: scraping next year's schedule adds
+//! games alongside last year's, and listings take an optional `season`
+//! filter. Once a season is over an admin closes it, which freezes its
+//! tickets and requests: the ticket and request mutations in this crate
+//! check the game's season first and fail with [`SeasonClosed`], and ticket
+//! generation skips closed seasons' games.
+
+use anyhow::Result;
+use gtm_models::{GamePk, Season, TicketId, UserId};
+use sqlx::AnyPool;
+
+use crate::{compat, pg};
+
+/// A change to a ticket or request in a closed season.
+#[derive(Debug)]
+pub struct SeasonClosed {
+    pub season: String,
+}
+
+impl std::fmt::Display for SeasonClosed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The {} season is closed; its tickets and requests can't be changed",
+            self.season
+        )
+    }
+}
+
+impl std::error::Error for SeasonClosed {}
+
+/// Every season with games, newest first.
+pub async fn list(pool: &AnyPool) -> Result<Vec<Season>> {
+    let rows = sqlx::query_as::<_, Season>(
+        "SELECT g.season, COUNT(*) AS games, \
+                MIN(g.official_date) AS first_date, MAX(g.official_date) AS last_date, \
+                (SELECT COUNT(*) FROM game_tickets gt JOIN games tg ON tg.game_pk = gt.game_pk \
+                 WHERE tg.season = g.season) AS tickets, \
+                (SELECT COUNT(*) FROM ticket_requests r JOIN games rg ON rg.game_pk = r.game_pk \
+                 WHERE rg.season = g.season) AS requests, \
+                (SELECT CAST(c.closed_at AS TEXT) FROM closed_seasons c \
+                 WHERE c.season = g.season) AS closed_at \
+         FROM games g GROUP BY g.season ORDER BY g.season DESC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Close `season`. Returns false if it was already closed.
+pub async fn close(pool: &AnyPool, season: &str, closed_by: Option<UserId>) -> Result<bool> {
+    let sql = pg(
+        "INSERT INTO closed_seasons (season, closed_by) VALUES (?, ?) \
+         ON CONFLICT (season) DO NOTHING",
+    );
+    let result = sqlx::query(&sql)
+        .bind(season)
+        .bind(closed_by)
+        .execute(pool)
+        .await?;
+    Ok(compat::wrote_row(&result))
+}
+
+/// Fail with [`SeasonClosed`] if the row of `from` whose `key` is `id` is a
+/// game in a closed season. `from` must join the row to its game as `g`.
+/// Run it on the transaction that does the write, so the check and the
+/// write see the same seasons.
+async fn ensure_open<'e, E>(executor: E, from: &str, key: &str, id: i64) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    let sql = pg(&format!(
+        "SELECT g.season FROM {from} \
+         JOIN closed_seasons c ON c.season = g.season WHERE {key} = ?"
+    ));
+    let closed = sqlx::query_scalar::<_, String>(&sql)
+        .bind(id)
+        .fetch_optional(executor)
+        .await?;
+    match closed {
+        Some(season) => Err(SeasonClosed { season }.into()),
+        None => Ok(()),
+    }
+}
+
+pub(crate) async fn ensure_game_open<'e, E>(executor: E, game_pk: GamePk) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    ensure_open(executor, "games g", "g.game_pk", game_pk.0).await
+}
+
+pub(crate) async fn ensure_ticket_open<'e, E>(executor: E, ticket_id: TicketId) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    ensure_open(
+        executor,
+        "game_tickets gt JOIN games g ON g.game_pk = gt.game_pk",
+        "gt.id",
+        ticket_id.0,
+    )
+    .await
+}
+
+pub(crate) async fn ensure_request_open<'e, E>(executor: E, request_id: i64) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    ensure_open(
+        executor,
+        "ticket_requests r JOIN games g ON g.game_pk = r.game_pk",
+        "r.id",
+        request_id,
+    )
+    .await
+}
+
+pub(crate) async fn ensure_poll_open<'e, E>(executor: E, poll_id: i64) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    ensure_open(
+        executor,
+        "polls p JOIN games g ON g.game_pk = p.game_pk",
+        "p.id",
+        poll_id,
+    )
+    .await
+}
@@ -0,0 +1,212 @@
+//! How many seats a member may request for a game.
+//!
+//! Rules cap requests by game type and tag. A game's tags are the labels
+//! admins give it (e.g. `premium`) plus `weekday`/`weekend` and `day`/`night`,
+//! which come from its date and start. The lowest cap among matching rules
+//! applies, an admin's per-game override beats the rules, and games no rule
+//! matches allow [`DEFAULT_MAX_SEATS`].
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, bail};
+use chrono::Datelike;
+use gtm_models::{Game, GamePk, SeatLimitRule};
+use sqlx::AnyPool;
+
+use crate::pg;
+
+/// Seats per request when no rule or override says otherwise.
+pub const DEFAULT_MAX_SEATS: i64 = 4;
+
+/// A rule to save with [`set_rules`].
+#[derive(Debug, Clone)]
+pub struct NewRule {
+    pub game_type: Option<String>,
+    pub tag: Option<String>,
+    pub max_seats: i64,
+}
+
+/// Everything needed to work out games' limits, loaded once.
+#[derive(Debug, Default)]
+pub struct SeatLimitPolicy {
+    rules: Vec<SeatLimitRule>,
+    tags: HashMap<GamePk, HashSet<String>>,
+    overrides: HashMap<GamePk, i64>,
+}
+
+impl SeatLimitPolicy {
+    pub async fn load(pool: &AnyPool) -> Result<Self> {
+        let tags = sqlx::query_as::<_, (GamePk, String)>("SELECT game_pk, tag FROM game_tags")
+            .fetch_all(pool)
+            .await?;
+        let overrides = sqlx::query_as::<_, (GamePk, i64)>(
+            "SELECT game_pk, max_seats_override FROM games WHERE max_seats_override IS NOT NULL",
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut policy = Self {
+            rules: rules(pool).await?,
+            overrides: overrides.into_iter().collect(),
+            ..Default::default()
+        };
+        for (game_pk, tag) in tags {
+            policy.tags.entry(game_pk).or_default().insert(tag);
+        }
+        Ok(policy)
+    }
+
+    /// Most seats one request may ask for `game`.
+    pub fn max_seats(&self, game: &Game) -> i64 {
+        if let Some(n) = self.overrides.get(&game.game_pk) {
+            return *n;
+        }
+        let tags = self.tags_for(game);
+        self.rules
+            .iter()
+            .filter(|r| r.game_type.as_ref().is_none_or(|t| *t == game.game_type))
+            .filter(|r| r.tag.as_ref().is_none_or(|t| tags.contains(t)))
+            .map(|r| r.max_seats)
+            .min()
+            .unwrap_or(DEFAULT_MAX_SEATS)
+    }
+
+    /// Admin labels plus the tags that follow from the game's date and start.
+    pub fn tags_for(&self, game: &Game) -> HashSet<String> {
+        let mut tags = self.tags.get(&game.game_pk).cloned().unwrap_or_default();
+        tags.extend(derived_tags(game).into_iter().map(String::from));
+        tags
+    }
+
+    /// Fill in `max_seats` on each game.
+    pub fn apply(&self, games: &mut [Game]) {
+        for game in games {
+            game.max_seats = Some(self.max_seats(game));
+        }
+    }
+}
+
+/// `weekday` or `weekend` from the game's date, and `day` or `night` from
+/// its start when the schedule says.
+pub fn derived_tags(game: &Game) -> Vec<&'static str> {
+    let mut tags = Vec::new();
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(&game.official_date, "%Y-%m-%d") {
+        tags.push(if date.weekday().number_from_monday() <= 5 {
+            "weekday"
+        } else {
+            "weekend"
+        });
+    }
+    match game.day_night.as_deref() {
+        Some("day") => tags.push("day"),
+        Some("night") => tags.push("night"),
+        _ => {}
+    }
+    tags
+}
+
+/// Most seats one request may ask for the game; the default if it isn't on
+/// the schedule.
+pub async fn max_seats_for_game(pool: &AnyPool, game_pk: GamePk) -> Result<i64> {
+    let Some(game) = crate::get_game(pool, game_pk).await? else {
+        return Ok(DEFAULT_MAX_SEATS);
+    };
+    Ok(SeatLimitPolicy::load(pool).await?.max_seats(&game))
+}
+
+pub async fn rules(pool: &AnyPool) -> Result<Vec<SeatLimitRule>> {
+    let rules = sqlx::query_as::<_, SeatLimitRule>(
+        "SELECT id, game_type, tag, max_seats FROM seat_limit_rules ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rules)
+}
+
+/// Tags are stored trimmed and lowercase; blank ones are dropped.
+fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Replace every rule with `new`.
+pub async fn set_rules(pool: &AnyPool, new: &[NewRule]) -> Result<Vec<SeatLimitRule>> {
+    if let Some(rule) = new.iter().find(|r| r.max_seats < 1) {
+        bail!("A seat limit must be at least 1 (got {})", rule.max_seats);
+    }
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM seat_limit_rules")
+        .execute(&mut *tx)
+        .await?;
+    let insert = pg("INSERT INTO seat_limit_rules (game_type, tag, max_seats) VALUES (?, ?, ?)");
+    for rule in new {
+        let game_type = rule
+            .game_type
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_uppercase);
+        sqlx::query(&insert)
+            .bind(game_type)
+            .bind(rule.tag.as_deref().and_then(normalize_tag))
+            .bind(rule.max_seats)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    rules(pool).await
+}
+
+/// A game's admin labels, sorted.
+pub async fn game_tags(pool: &AnyPool, game_pk: GamePk) -> Result<Vec<String>> {
+    let tags = sqlx::query_scalar(&pg(
+        "SELECT tag FROM game_tags WHERE game_pk = ? ORDER BY tag",
+    ))
+    .bind(game_pk)
+    .fetch_all(pool)
+    .await?;
+    Ok(tags)
+}
+
+/// Replace a game's admin labels.
+pub async fn set_game_tags(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    tags: &[String],
+) -> Result<Vec<String>> {
+    let tags: std::collections::BTreeSet<String> =
+        tags.iter().filter_map(|t| normalize_tag(t)).collect();
+    let mut tx = pool.begin().await?;
+    sqlx::query(&pg("DELETE FROM game_tags WHERE game_pk = ?"))
+        .bind(game_pk)
+        .execute(&mut *tx)
+        .await?;
+    for tag in &tags {
+        sqlx::query(&pg("INSERT INTO game_tags (game_pk, tag) VALUES (?, ?)"))
+            .bind(game_pk)
+            .bind(tag)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(tags.into_iter().collect())
+}
+
+/// Set or (with `None`) clear a game's own limit. Returns false if the game
+/// doesn't exist.
+pub async fn set_game_override(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    max_seats: Option<i64>,
+) -> Result<bool> {
+    if max_seats.is_some_and(|n| n < 1) {
+        bail!("A seat limit must be at least 1");
+    }
+    let result = sqlx::query(&pg(
+        "UPDATE games SET max_seats_override = ? WHERE game_pk = ?",
+    ))
+    .bind(max_seats)
+    .bind(game_pk)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
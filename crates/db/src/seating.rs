@@ -0,0 +1,174 @@
+//! Seating parties together.
+//!
+//! A party — the seats one member holds at a game — should sit side by
+//! side: same section and row, consecutive seat numbers. The allocation
+//! engine seats parties that way where it can. Every commit of
+//! assignments, and every transfer, checks the parties it changes in its
+//! own transaction and refuses any that would leave a member in more than
+//! one section, unless the admin overrides it; [`check_parties`] runs the
+//! same check for a dry run.
+
+use anyhow::Result;
+use gtm_models::{GamePk, GameTicketDetail, TicketId, UserId};
+use sqlx::{AnyConnection, AnyPool};
+use std::collections::{BTreeSet, HashMap};
+
+pub use gtm_alloc::Seating;
+
+use crate::{list_tickets_for_game, pg};
+
+/// Seats that would leave a member's party in more than one section.
+#[derive(Debug)]
+pub struct SplitParty {
+    pub game_pk: GamePk,
+    pub user_id: UserId,
+    pub sections: Vec<String>,
+    /// Free seats at the game that would seat the whole party together,
+    /// from [`find_adjacent_available`].
+    pub together: Option<Vec<TicketId>>,
+}
+
+impl std::fmt::Display for SplitParty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "User {}'s seats for game {} would be split across sections {}",
+            self.user_id,
+            self.game_pk,
+            self.sections.join(", ")
+        )?;
+        if self.together.is_some() {
+            write!(f, "; there are enough free seats together")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SplitParty {}
+
+/// `count` available seats at `game_pk` side by side, from the shortest run
+/// that fits. `None` if no run of free seats is long enough.
+pub async fn find_adjacent_available(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    count: usize,
+) -> Result<Option<Vec<TicketId>>> {
+    let tickets = list_tickets_for_game(pool, game_pk).await?;
+    Ok(gtm_alloc::find_adjacent(&tickets, count))
+}
+
+/// How `user_id`'s seats at `game_pk` would sit with `adding` assigned to
+/// them as well.
+pub async fn party_seating(
+    pool: &AnyPool,
+    game_pk: GamePk,
+    user_id: UserId,
+    adding: &[TicketId],
+) -> Result<Seating> {
+    let tickets = list_tickets_for_game(pool, game_pk).await?;
+    Ok(Seating::of(&party(&tickets, user_id, adding)))
+}
+
+/// `user_id`'s assigned seats among `tickets`, plus `adding`.
+fn party<'a>(
+    tickets: &'a [GameTicketDetail],
+    user_id: UserId,
+    adding: &[TicketId],
+) -> Vec<&'a GameTicketDetail> {
+    tickets
+        .iter()
+        .filter(|t| {
+            adding.contains(&t.id) || (t.status == "assigned" && t.assigned_to == Some(user_id))
+        })
+        .collect()
+}
+
+/// Check `(ticket, member)` assignments before they're made. Fails with
+/// [`SplitParty`] if any member's seats at a game would span sections,
+/// unless `allow_split`; otherwise returns each party that wouldn't sit
+/// together, and how it would sit. Unknown tickets are left for the
+/// assignment itself to report.
+pub async fn check_parties(
+    pool: &AnyPool,
+    assignments: &[(TicketId, UserId)],
+    allow_split: bool,
+) -> Result<Vec<(GamePk, UserId, Seating)>> {
+    check_parties_with(&mut *pool.acquire().await?, assignments, allow_split).await
+}
+
+/// [`check_parties`] on `conn`, so a commit can check the seats as its own
+/// transaction sees them. Reads every ticket of the games involved in one
+/// query.
+pub(crate) async fn check_parties_with(
+    conn: &mut AnyConnection,
+    assignments: &[(TicketId, UserId)],
+    allow_split: bool,
+) -> Result<Vec<(GamePk, UserId, Seating)>> {
+    if assignments.is_empty() {
+        return Ok(Vec::new());
+    }
+    let sql = pg(&format!(
+        "SELECT gt.id, gt.game_pk, gt.seat_id, s.section, s.row, s.seat, gt.status, gt.notes, gt.assigned_to \
+         FROM game_tickets gt \
+         JOIN seats s ON s.id = gt.seat_id \
+         WHERE gt.game_pk IN (SELECT game_pk FROM game_tickets WHERE id IN ({})) \
+         ORDER BY s.section, s.row, s.seat",
+        vec!["?"; assignments.len()].join(", ")
+    ));
+    let mut query = sqlx::query_as::<_, GameTicketDetail>(&sql);
+    for (ticket_id, _) in assignments {
+        query = query.bind(*ticket_id);
+    }
+    let mut games: HashMap<GamePk, Vec<GameTicketDetail>> = HashMap::new();
+    for ticket in query.fetch_all(&mut *conn).await? {
+        games.entry(ticket.game_pk).or_default().push(ticket);
+    }
+    let game_of: HashMap<TicketId, GamePk> = games
+        .values()
+        .flatten()
+        .map(|t| (t.id, t.game_pk))
+        .collect();
+
+    let mut parties: HashMap<(GamePk, UserId), Vec<TicketId>> = HashMap::new();
+    for (ticket_id, user_id) in assignments {
+        if let Some(game_pk) = game_of.get(ticket_id) {
+            parties
+                .entry((*game_pk, *user_id))
+                .or_default()
+                .push(*ticket_id);
+        }
+    }
+    let mut parties: Vec<_> = parties.into_iter().collect();
+    parties.sort_by_key(|(key, _)| *key);
+
+    let mut apart = Vec::new();
+    for ((game_pk, user_id), adding) in parties {
+        let tickets = &games[&game_pk];
+        let party = party(tickets, user_id, &adding);
+        let seating = Seating::of(&party);
+        if seating == Seating::Split && !allow_split {
+            let sections: BTreeSet<&str> = party.iter().map(|t| t.section.as_str()).collect();
+            return Err(SplitParty {
+                game_pk,
+                user_id,
+                sections: sections.into_iter().map(str::to_string).collect(),
+                together: gtm_alloc::find_adjacent(tickets, party.len()),
+            }
+            .into());
+        }
+        if seating != Seating::Together {
+            apart.push((game_pk, user_id, seating));
+        }
+    }
+    Ok(apart)
+}
+
+/// A warning for a party [`check_parties`] found not sitting together.
+pub fn warning((game_pk, user_id, seating): (GamePk, UserId, Seating)) -> String {
+    match seating {
+        Seating::Split => {
+            format!("User {user_id}'s seats for game {game_pk} are in more than one section")
+        }
+        _ => format!("User {user_id}'s seats for game {game_pk} aren't side by side"),
+    }
+}
@@ -0,0 +1,100 @@
+//! Statement timeouts and slow-query logging.
+//!
+//! Queries that can run long — reports and filtered lists — go through
+//! [`timed`] with a short name. One that outlasts the statement timeout is
+//! abandoned with a [`QueryTimeout`]; one past the slow threshold is logged
+//! with its name and duration and counted in [`slow_queries`]. A Postgres
+//! read replica also sets each connection's `statement_timeout`, so the
+//! server stops work the client gave up on; the primary doesn't, as it
+//! would cut off migrations and long writes too.
+//!
+//! Each [`timed`] query runs in a `query` span carrying its name, so with
+//! `log_queries` on the statements it runs are logged under that name.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tracing::{Instrument, info_span, warn};
+
+/// Milliseconds; 0 means no limit.
+static TIMEOUT_MS: AtomicU64 = AtomicU64::new(0);
+/// Milliseconds; 0 means nothing is logged as slow.
+static SLOW_MS: AtomicU64 = AtomicU64::new(0);
+static SLOW_QUERIES: AtomicU64 = AtomicU64::new(0);
+
+/// Postgres SQLSTATE for a statement cancelled by `statement_timeout`.
+const QUERY_CANCELED: &str = "57014";
+
+/// A query ran past the statement timeout.
+#[derive(Debug)]
+pub struct QueryTimeout {
+    pub name: String,
+    pub after: Duration,
+}
+
+impl std::fmt::Display for QueryTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Query {} timed out after {} ms",
+            self.name,
+            self.after.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for QueryTimeout {}
+
+/// Set the statement timeout and slow-query threshold (set automatically
+/// on connect). `None` turns either off.
+pub fn configure(timeout: Option<Duration>, slow: Option<Duration>) {
+    let ms = |d: Option<Duration>| d.map_or(0, |d| d.as_millis() as u64);
+    TIMEOUT_MS.store(ms(timeout), Ordering::Relaxed);
+    SLOW_MS.store(ms(slow), Ordering::Relaxed);
+}
+
+/// Slow queries seen since startup.
+pub fn slow_queries() -> u64 {
+    SLOW_QUERIES.load(Ordering::Relaxed)
+}
+
+/// Run `query` under the statement timeout, logging it as `name` if slow.
+pub async fn timed<T>(
+    name: &str,
+    query: impl Future<Output = Result<T, sqlx::Error>>,
+) -> Result<T> {
+    let start = Instant::now();
+    let timeout = Duration::from_millis(TIMEOUT_MS.load(Ordering::Relaxed));
+    // None when the timeout fired
+    let query = query.instrument(info_span!("query", name));
+    let result = if timeout.is_zero() {
+        Some(query.await)
+    } else {
+        tokio::time::timeout(timeout, query).await.ok()
+    };
+    let elapsed = start.elapsed();
+
+    let slow = SLOW_MS.load(Ordering::Relaxed);
+    if slow > 0 && elapsed >= Duration::from_millis(slow) {
+        SLOW_QUERIES.fetch_add(1, Ordering::Relaxed);
+        warn!(
+            query = name,
+            elapsed_ms = elapsed.as_millis() as u64,
+            "Slow query"
+        );
+    }
+
+    let timed_out = || QueryTimeout {
+        name: name.to_string(),
+        after: elapsed,
+    };
+    match result {
+        None => Err(timed_out().into()),
+        Some(Err(sqlx::Error::Database(e))) if e.code().as_deref() == Some(QUERY_CANCELED) => {
+            Err(timed_out().into())
+        }
+        Some(result) => Ok(result?),
+    }
+}
@@ -0,0 +1,219 @@
+//! Who uses the API, and for what.
+//!
+//! Each request bumps a counter for its member, endpoint (method and route
+//! pattern, e.g. `GET /api/games/{id}`) and day, so the tables grow with
+//! distinct combinations rather than with traffic. Requests to the public
+//! routes have no member and are counted in `api_public_usage`.
+//!
+//! Counting happens in memory in a [`UsageCounter`]; a background task
+//! writes the totals out with [`UsageCounter::flush`].
+
+use anyhow::Result;
+use gtm_models::UserId;
+use serde::Serialize;
+use sqlx::AnyPool;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::pg;
+use crate::timing::timed;
+
+/// Requests on one day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyUsage {
+    pub day: String,
+    pub requests: i64,
+    /// Distinct members who made them.
+    pub users: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserUsage {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub requests: i64,
+    /// Days with at least one request.
+    pub active_days: i64,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EndpointUsage {
+    pub endpoint: String,
+    pub requests: i64,
+    pub users: i64,
+}
+
+/// Usage between two days, inclusive, busiest first except `days`.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageReport {
+    pub from: String,
+    pub to: String,
+    /// Only days with requests, in date order.
+    pub days: Vec<DailyUsage>,
+    pub users: Vec<UserUsage>,
+    pub endpoints: Vec<EndpointUsage>,
+}
+
+/// Add `requests` to `user_id`'s count for `endpoint` on `day`
+/// (YYYY-MM-DD), or to the public count without a member.
+pub async fn record(
+    pool: &AnyPool,
+    day: &str,
+    user_id: Option<UserId>,
+    endpoint: &str,
+    requests: i64,
+) -> Result<()> {
+    match user_id {
+        Some(user_id) => {
+            sqlx::query(&pg(
+                "INSERT INTO api_usage (day, user_id, endpoint, requests) VALUES (?, ?, ?, ?) \
+                 ON CONFLICT (day, user_id, endpoint) \
+                 DO UPDATE SET requests = api_usage.requests + excluded.requests",
+            ))
+            .bind(day)
+            .bind(user_id)
+            .bind(endpoint)
+            .bind(requests)
+            .execute(pool)
+            .await?;
+        }
+        None => {
+            sqlx::query(&pg(
+                "INSERT INTO api_public_usage (day, endpoint, requests) VALUES (?, ?, ?) \
+                 ON CONFLICT (day, endpoint) \
+                 DO UPDATE SET requests = api_public_usage.requests + excluded.requests",
+            ))
+            .bind(day)
+            .bind(endpoint)
+            .bind(requests)
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Day, member (if any) and endpoint of a count.
+type UsageKey = (String, Option<UserId>, String);
+
+/// Request counts not yet written to the database. [`bump`](Self::bump)
+/// only touches memory, so it can run on every request.
+#[derive(Debug, Default)]
+pub struct UsageCounter {
+    counts: Mutex<HashMap<UsageKey, i64>>,
+}
+
+impl UsageCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bump(&self, day: &str, user_id: Option<UserId>, endpoint: &str) {
+        *self
+            .counts
+            .lock()
+            .unwrap()
+            .entry((day.to_string(), user_id, endpoint.to_string()))
+            .or_default() += 1;
+    }
+
+    /// Write the counts so far to the database. Counts that fail to write
+    /// are kept for the next flush. Returns how many requests were written.
+    pub async fn flush(&self, pool: &AnyPool) -> Result<i64> {
+        let counts = std::mem::take(&mut *self.counts.lock().unwrap());
+        let mut written = 0;
+        let mut counts = counts.into_iter();
+        while let Some(((day, user_id, endpoint), requests)) = counts.next() {
+            if let Err(e) = record(pool, &day, user_id, &endpoint, requests).await {
+                let mut pending = self.counts.lock().unwrap();
+                for (key, n) in std::iter::once(((day, user_id, endpoint), requests)).chain(counts)
+                {
+                    *pending.entry(key).or_default() += n;
+                }
+                return Err(e);
+            }
+            written += requests;
+        }
+        Ok(written)
+    }
+}
+
+/// Member and public counts together; `user_id` is NULL for public ones.
+const ALL_USAGE: &str = "SELECT day, user_id, endpoint, requests FROM api_usage \
+     UNION ALL SELECT day, NULL, endpoint, requests FROM api_public_usage";
+
+/// Daily, per-member and per-endpoint totals from `from` to `to`.
+pub async fn report(pool: &AnyPool, from: &str, to: &str) -> Result<UsageReport> {
+    let days: Vec<(String, i64, i64)> = timed(
+        "usage days",
+        sqlx::query_as(&pg(&format!(
+            "SELECT day, SUM(requests), COUNT(DISTINCT user_id) FROM ({ALL_USAGE}) a \
+         WHERE day >= ? AND day <= ? GROUP BY day ORDER BY day",
+        )))
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool),
+    )
+    .await?;
+
+    let users: Vec<(UserId, String, i64, i64, String)> = timed(
+        "usage users",
+        sqlx::query_as(&pg(
+            "SELECT u.id, u.name, SUM(a.requests), COUNT(DISTINCT a.day), MAX(a.day) \
+         FROM api_usage a JOIN users u ON u.id = a.user_id \
+         WHERE a.day >= ? AND a.day <= ? \
+         GROUP BY u.id, u.name ORDER BY SUM(a.requests) DESC, u.name",
+        ))
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool),
+    )
+    .await?;
+
+    let endpoints: Vec<(String, i64, i64)> = timed(
+        "usage endpoints",
+        sqlx::query_as(&pg(&format!(
+            "SELECT endpoint, SUM(requests), COUNT(DISTINCT user_id) FROM ({ALL_USAGE}) a \
+         WHERE day >= ? AND day <= ? \
+         GROUP BY endpoint ORDER BY SUM(requests) DESC, endpoint",
+        )))
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool),
+    )
+    .await?;
+
+    Ok(UsageReport {
+        from: from.to_string(),
+        to: to.to_string(),
+        days: days
+            .into_iter()
+            .map(|(day, requests, users)| DailyUsage {
+                day,
+                requests,
+                users,
+            })
+            .collect(),
+        users: users
+            .into_iter()
+            .map(
+                |(user_id, user_name, requests, active_days, last_seen)| UserUsage {
+                    user_id,
+                    user_name,
+                    requests,
+                    active_days,
+                    last_seen,
+                },
+            )
+            .collect(),
+        endpoints: endpoints
+            .into_iter()
+            .map(|(endpoint, requests, users)| EndpointUsage {
+                endpoint,
+                requests,
+                users,
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,42 @@
+//! Non-fatal problems collected while an operation runs.
+//!
+//! Service functions that can partly succeed (some seats skipped, an email
+//! that couldn't be queued) take a `&mut Warnings` and push a short,
+//! member-readable message for each thing they left undone. The API returns
+//! them next to the result so they aren't silently dropped.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Warnings(Vec<String>);
+
+impl Warnings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, message: impl Into<String>) {
+        self.0.push(message.into());
+    }
+
+    pub fn extend(&mut self, other: Warnings) {
+        self.0.extend(other.0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    pub fn into_vec(self) -> Vec<String> {
+        self.0
+    }
+}
@@ -0,0 +1,222 @@
+//! Outbound webhooks for external automations.
+//!
+//! Admins register URLs with the events they want. When one of those events
+//! happens, [`enqueue`] writes a delivery row per matching webhook, in the
+//! same transaction as the change where there is one, and a background task
+//! posts them later. Failed posts are retried with exponential backoff up to
+//! [`MAX_ATTEMPTS`] times before the delivery is marked `failed`.
+
+use anyhow::{Result, bail};
+use gtm_models::{QueuedWebhook, UserId, Webhook, WebhookDelivery};
+use serde_json::json;
+use sqlx::{AnyConnection, AnyPool};
+
+use crate::compat::{self, Arg};
+use crate::pg;
+
+pub const TICKET_ASSIGNED: &str = "ticket.assigned";
+pub const REQUEST_CREATED: &str = "request.created";
+pub const SCHEDULE_UPDATED: &str = "schedule.updated";
+
+/// Every event a webhook can subscribe to.
+pub const EVENTS: &[&str] = &[TICKET_ASSIGNED, REQUEST_CREATED, SCHEDULE_UPDATED];
+
+/// Delivery attempts before a delivery is given up on.
+pub const MAX_ATTEMPTS: i64 = 6;
+
+/// Minutes before the first retry; each later retry waits twice as long.
+const RETRY_BASE_MINUTES: i64 = 1;
+
+const WEBHOOK_SELECT: &str = "SELECT id, url, events, created_by, \
+        CAST(created_at AS TEXT) AS created_at \
+     FROM webhooks";
+
+type WebhookRow = (i64, String, String, Option<UserId>, String);
+
+fn from_row((id, url, events, created_by, created_at): WebhookRow) -> Webhook {
+    Webhook {
+        id,
+        url,
+        events: events.split(',').map(str::to_string).collect(),
+        created_by,
+        created_at,
+    }
+}
+
+fn now() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Register `url` for `events` with the signing `secret`.
+pub async fn create(
+    pool: &AnyPool,
+    url: &str,
+    events: &[String],
+    secret: &str,
+    created_by: Option<UserId>,
+) -> Result<Webhook> {
+    let url = url.trim();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        bail!("Webhook URL must start with https:// or http://");
+    }
+    if events.is_empty() {
+        bail!("Choose at least one event");
+    }
+    if let Some(unknown) = events.iter().find(|e| !EVENTS.contains(&e.as_str())) {
+        bail!("Unknown webhook event: {unknown}");
+    }
+    let id = compat::insert_returning_id(
+        &mut *pool.acquire().await?,
+        "INSERT INTO webhooks (url, events, secret, created_by) VALUES (?, ?, ?, ?)",
+        &[
+            Arg::Text(url),
+            Arg::Text(&events.join(",")),
+            Arg::Text(secret),
+            Arg::OptInt(created_by.map(i64::from)),
+        ],
+    )
+    .await?;
+    let sql = pg(&format!("{WEBHOOK_SELECT} WHERE id = ?"));
+    let row: WebhookRow = sqlx::query_as(&sql).bind(id).fetch_one(pool).await?;
+    Ok(from_row(row))
+}
+
+pub async fn list(pool: &AnyPool) -> Result<Vec<Webhook>> {
+    let sql = format!("{WEBHOOK_SELECT} ORDER BY id");
+    let rows: Vec<WebhookRow> = sqlx::query_as(&sql).fetch_all(pool).await?;
+    Ok(rows.into_iter().map(from_row).collect())
+}
+
+/// Remove a webhook and its delivery log. Returns false if there was none.
+pub async fn delete(pool: &AnyPool, id: i64) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(&pg("DELETE FROM webhook_deliveries WHERE webhook_id = ?"))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    let result = sqlx::query(&pg("DELETE FROM webhooks WHERE id = ?"))
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Queue `event` for every webhook subscribed to it. The payload posted is
+/// `{"event", "created_at", "data"}`. Returns the number of deliveries
+/// queued.
+pub async fn enqueue(
+    conn: &mut AnyConnection,
+    event: &str,
+    data: serde_json::Value,
+) -> Result<usize> {
+    let hooks: Vec<(i64, String)> = sqlx::query_as("SELECT id, events FROM webhooks")
+        .fetch_all(&mut *conn)
+        .await?;
+    let targets: Vec<i64> = hooks
+        .into_iter()
+        .filter(|(_, events)| events.split(',').any(|e| e == event))
+        .map(|(id, _)| id)
+        .collect();
+    if targets.is_empty() {
+        return Ok(0);
+    }
+    let payload = json!({ "event": event, "created_at": now(), "data": data }).to_string();
+    let sql = pg("INSERT INTO webhook_deliveries (webhook_id, event, payload) VALUES (?, ?, ?)");
+    for webhook_id in &targets {
+        sqlx::query(&sql)
+            .bind(webhook_id)
+            .bind(event)
+            .bind(&payload)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(targets.len())
+}
+
+/// Pending deliveries whose retry time has come, oldest first.
+pub async fn list_due(pool: &AnyPool, limit: i64) -> Result<Vec<QueuedWebhook>> {
+    let sql = pg(
+        "SELECT d.id, d.webhook_id, w.url, w.secret, d.event, d.payload, d.attempts \
+         FROM webhook_deliveries d JOIN webhooks w ON w.id = d.webhook_id \
+         WHERE d.status = 'pending' AND (d.next_attempt_at IS NULL OR d.next_attempt_at <= ?) \
+         ORDER BY d.id LIMIT ?",
+    );
+    let rows = sqlx::query_as::<_, QueuedWebhook>(&sql)
+        .bind(now())
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+pub async fn mark_delivered(pool: &AnyPool, id: i64, response_status: i64) -> Result<()> {
+    let sql = pg("UPDATE webhook_deliveries \
+         SET status = 'delivered', attempts = attempts + 1, response_status = ?, \
+             last_error = NULL, delivered_at = CURRENT_TIMESTAMP \
+         WHERE id = ?");
+    sqlx::query(&sql)
+        .bind(response_status)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Record a failed attempt, with the endpoint's HTTP status if it answered.
+/// The delivery is retried after [`RETRY_BASE_MINUTES`] doubled for each
+/// earlier attempt, until it has failed [`MAX_ATTEMPTS`] times.
+pub async fn mark_failed(
+    pool: &AnyPool,
+    id: i64,
+    response_status: Option<i64>,
+    error: &str,
+) -> Result<()> {
+    let attempts =
+        sqlx::query_scalar::<_, i64>(&pg("SELECT attempts FROM webhook_deliveries WHERE id = ?"))
+            .bind(id)
+            .fetch_one(pool)
+            .await?;
+    let delay = chrono::Duration::minutes(RETRY_BASE_MINUTES << attempts.clamp(0, 16));
+    let next_attempt_at = (chrono::Utc::now() + delay)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    let status = if attempts + 1 >= MAX_ATTEMPTS {
+        "failed"
+    } else {
+        "pending"
+    };
+    let sql = pg("UPDATE webhook_deliveries \
+         SET status = ?, attempts = ?, response_status = ?, last_error = ?, next_attempt_at = ? \
+         WHERE id = ?");
+    sqlx::query(&sql)
+        .bind(status)
+        .bind(attempts + 1)
+        .bind(response_status)
+        .bind(error)
+        .bind(next_attempt_at)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// A webhook's deliveries, newest first.
+pub async fn list_deliveries(
+    pool: &AnyPool,
+    webhook_id: i64,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>> {
+    let sql = pg(
+        "SELECT id, webhook_id, event, payload, status, attempts, response_status, \
+                last_error, CAST(created_at AS TEXT) AS created_at, \
+                CAST(delivered_at AS TEXT) AS delivered_at \
+         FROM webhook_deliveries WHERE webhook_id = ? ORDER BY id DESC LIMIT ?",
+    );
+    let rows = sqlx::query_as::<_, WebhookDelivery>(&sql)
+        .bind(webhook_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
@@ -1,6 +1,7 @@
 mod common;
 
-use common::{sample_game, test_pool};
+use common::{REGULAR, TEAM_ID, sample_game, test_pool};
+use gtm_models::{DoubleHeader, GamePk, TicketId, TicketStatus, UserId};
 
 // --- Request Lifecycle ---
 
@@ -14,7 +15,7 @@ async fn request_lifecycle_create_withdraw_recreate() {
         .unwrap();
 
     // Create
-    let req = gtm_db::create_ticket_request(&pool, user.id, 500001, 2, None)
+    let req = gtm_db::create_ticket_request(&pool, user.id, GamePk(500001), 2, None)
         .await
         .unwrap();
     assert_eq!(req.status, "pending");
@@ -26,13 +27,13 @@ async fn request_lifecycle_create_withdraw_recreate() {
     assert!(ok);
 
     // Verify withdrawn
-    let reqs = gtm_db::list_requests_for_user(&pool, user.id)
+    let reqs = gtm_db::list_requests_for_user(&pool, user.id, None)
         .await
         .unwrap();
     assert_eq!(reqs[0].status, "withdrawn");
 
     // Re-request recycles the withdrawn row back to pending
-    let req2 = gtm_db::create_ticket_request(&pool, user.id, 500001, 4, None)
+    let req2 = gtm_db::create_ticket_request(&pool, user.id, GamePk(500001), 4, None)
         .await
         .unwrap();
     assert_eq!(req2.id, req.id); // same row reused
@@ -55,33 +56,103 @@ async fn generate_and_assign_tickets() {
         .unwrap();
 
     // Generate tickets
-    let count = gtm_db::generate_tickets_for_seat(&pool, seat.id)
+    let count = gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
         .await
         .unwrap();
     assert_eq!(count, 1); // one home game
 
     // List tickets for game
-    let tickets = gtm_db::list_tickets_for_game(&pool, 500002).await.unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500002))
+        .await
+        .unwrap();
     assert_eq!(tickets.len(), 1);
     assert_eq!(tickets[0].status, "available");
     assert!(tickets[0].assigned_to.is_none());
 
     // Assign
-    let ok = gtm_db::assign_ticket(&pool, tickets[0].id, user.id)
+    let ok = gtm_db::assign_ticket(&pool, tickets[0].id, user.id, None)
         .await
         .unwrap();
     assert!(ok);
 
     // Verify assigned
-    let tickets = gtm_db::list_tickets_for_game(&pool, 500002).await.unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500002))
+        .await
+        .unwrap();
     assert_eq!(tickets[0].status, "assigned");
     assert_eq!(tickets[0].assigned_to, Some(user.id));
 
     // User's tickets
-    let my_tickets = gtm_db::list_tickets_for_user(&pool, user.id).await.unwrap();
+    let my_tickets = gtm_db::list_tickets_for_user(&pool, user.id, None)
+        .await
+        .unwrap();
     assert_eq!(my_tickets.len(), 1);
 }
 
+// --- Ticket status transitions ---
+
+#[tokio::test]
+async fn ticket_status_updates_follow_the_transition_rules() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(500030))
+        .await
+        .unwrap();
+    let seat = gtm_db::add_seat(&pool, "VR313", "T", "1", None)
+        .await
+        .unwrap();
+    let user = gtm_db::upsert_user(&pool, "auth0|tr1", "tr@example.com", "Transit")
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ticket = gtm_db::list_tickets_for_game(&pool, GamePk(500030))
+        .await
+        .unwrap()[0]
+        .id;
+
+    // A status update can't assign: that needs a holder
+    let err = gtm_db::update_ticket_status(&pool, ticket, TicketStatus::Assigned, None, None)
+        .await
+        .unwrap_err();
+    let invalid = err.downcast_ref::<gtm_db::InvalidTransition>().unwrap();
+    assert_eq!(invalid.from, "available");
+    assert_eq!(invalid.to, TicketStatus::Assigned);
+
+    // Notes can change without moving the ticket
+    assert!(
+        gtm_db::update_ticket_status(&pool, ticket, TicketStatus::Available, Some("aisle"), None)
+            .await
+            .unwrap()
+    );
+
+    // Making an assigned ticket available clears its holder
+    gtm_db::assign_ticket(&pool, ticket, user.id, None)
+        .await
+        .unwrap();
+    gtm_db::update_ticket_status(&pool, ticket, TicketStatus::Available, None, None)
+        .await
+        .unwrap();
+    let after = gtm_db::get_ticket(&pool, ticket).await.unwrap().unwrap();
+    assert_eq!(after.status, "available");
+    assert_eq!(after.assigned_to, None);
+
+    assert!(
+        !gtm_db::update_ticket_status(
+            &pool,
+            TicketId(999_999),
+            TicketStatus::Available,
+            None,
+            None
+        )
+        .await
+        .unwrap()
+    );
+    assert!(TicketStatus::Assigned.can_become(TicketStatus::Available));
+    assert!(!TicketStatus::Available.can_become(TicketStatus::Assigned));
+    assert!("sold".parse::<TicketStatus>().is_err());
+}
+
 // --- Release + Withdraw ---
 
 #[tokio::test]
@@ -97,40 +168,483 @@ async fn release_tickets_also_withdraws_request() {
         .unwrap();
 
     // Create request and assign ticket
-    let req = gtm_db::create_ticket_request(&pool, user.id, 500003, 1, None)
+    let req = gtm_db::create_ticket_request(&pool, user.id, GamePk(500003), 1, None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
         .await
         .unwrap();
-    gtm_db::generate_tickets_for_seat(&pool, seat.id)
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500003))
         .await
         .unwrap();
-    let tickets = gtm_db::list_tickets_for_game(&pool, 500003).await.unwrap();
-    gtm_db::assign_ticket(&pool, tickets[0].id, user.id)
+    gtm_db::assign_ticket(&pool, tickets[0].id, user.id, None)
         .await
         .unwrap();
 
     // Approve the request
-    gtm_db::update_request_approval(&pool, req.id, "approved")
+    gtm_db::update_request_approval(&pool, req.id, "approved", None)
         .await
         .unwrap();
 
     // Release tickets
-    let released = gtm_db::release_tickets_for_game(&pool, 500003, user.id)
+    let released = gtm_db::release_tickets_for_game(&pool, GamePk(500003), user.id, None)
         .await
         .unwrap();
     assert_eq!(released, 1);
 
     // Ticket is available again
-    let tickets = gtm_db::list_tickets_for_game(&pool, 500003).await.unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500003))
+        .await
+        .unwrap();
     assert_eq!(tickets[0].status, "available");
     assert!(tickets[0].assigned_to.is_none());
 
     // Request is withdrawn
-    let reqs = gtm_db::list_requests_for_user(&pool, user.id)
+    let reqs = gtm_db::list_requests_for_user(&pool, user.id, None)
         .await
         .unwrap();
     assert_eq!(reqs[0].status, "withdrawn");
 }
 
+// --- Transfer Tickets ---
+
+#[tokio::test]
+async fn transfer_ticket_moves_seats_and_records_history() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(500004))
+        .await
+        .unwrap();
+    for seat in ["1", "2"] {
+        gtm_db::add_seat(&pool, "VR313", "T", seat, None)
+            .await
+            .unwrap();
+    }
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ann = gtm_db::upsert_user(&pool, "auth0|ann", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bob = gtm_db::upsert_user(&pool, "auth0|bob", "bob@example.com", "Bob")
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500004))
+        .await
+        .unwrap();
+    for t in &tickets {
+        gtm_db::assign_ticket(&pool, t.id, ann.id, None)
+            .await
+            .unwrap();
+    }
+
+    // Only seats Ann holds can move; a stranger's id is skipped with a warning
+    let mut warnings = gtm_db::Warnings::new();
+    let moved = gtm_db::transfer_ticket(
+        &pool,
+        GamePk(500004),
+        ann.id,
+        bob.id,
+        Some(&[tickets[0].id, TicketId(999_999)]),
+        Some(ann.id),
+        &mut warnings,
+    )
+    .await
+    .unwrap();
+    assert_eq!(moved, vec![tickets[0].id]);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings.iter().next().unwrap().contains("999999"));
+
+    // Bob can't pass on seats he doesn't hold
+    let none = gtm_db::transfer_ticket(
+        &pool,
+        GamePk(500004),
+        bob.id,
+        ann.id,
+        Some(&[tickets[1].id]),
+        None,
+        &mut gtm_db::Warnings::new(),
+    )
+    .await
+    .unwrap();
+    assert!(none.is_empty());
+
+    let after = gtm_db::list_tickets_for_game(&pool, GamePk(500004))
+        .await
+        .unwrap();
+    assert_eq!(after[0].assigned_to, Some(bob.id));
+    assert_eq!(after[1].assigned_to, Some(ann.id));
+    assert_eq!(after[0].status, "assigned");
+
+    let history = gtm_db::list_ticket_transfers(&pool, Some(GamePk(500004)), None)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(
+        (
+            history[0].from_user_name.as_str(),
+            history[0].to_user_name.as_str()
+        ),
+        ("Ann", "Bob")
+    );
+    assert_eq!(history[0].seat, "1");
+
+    let audit = gtm_db::audit::list(&pool, Some(GamePk(500004)), None)
+        .await
+        .unwrap();
+    assert_eq!(audit[0].action, gtm_db::audit::TRANSFER);
+
+    // Two transfers of the same seat at once: only one of them moves it
+    let cy = gtm_db::upsert_user(&pool, "auth0|cy", "cy@example.com", "Cy")
+        .await
+        .unwrap();
+    let (mut w1, mut w2) = (gtm_db::Warnings::new(), gtm_db::Warnings::new());
+    let seat = [tickets[1].id];
+    let (to_bob, to_cy) = tokio::join!(
+        gtm_db::transfer_ticket(
+            &pool,
+            GamePk(500004),
+            ann.id,
+            bob.id,
+            Some(&seat),
+            None,
+            &mut w1
+        ),
+        gtm_db::transfer_ticket(
+            &pool,
+            GamePk(500004),
+            ann.id,
+            cy.id,
+            Some(&seat),
+            None,
+            &mut w2
+        ),
+    );
+    assert_eq!(to_bob.unwrap().len() + to_cy.unwrap().len(), 1);
+    assert_eq!(
+        gtm_db::list_ticket_transfers(&pool, Some(GamePk(500004)), None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+// --- Ledger ---
+
+#[tokio::test]
+async fn ledger_prices_assigned_seats_per_member() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(500005))
+        .await
+        .unwrap();
+    let mut later = sample_game(500006);
+    later.game_date = "2026-04-02T19:15:00Z".to_string();
+    gtm_db::upsert_game(&pool, &later).await.unwrap();
+    for seat in ["1", "2", "3"] {
+        gtm_db::add_seat(&pool, "VR313", "L", seat, None)
+            .await
+            .unwrap();
+    }
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(
+        gtm_db::update_seat_group_face_value(&pool, "VR313", "L", Some(4500))
+            .await
+            .unwrap(),
+        3
+    );
+    let ann = gtm_db::upsert_user(&pool, "auth0|ann", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bob = gtm_db::upsert_user(&pool, "auth0|bob", "bob@example.com", "Bob")
+        .await
+        .unwrap();
+
+    let first = gtm_db::list_tickets_for_game(&pool, GamePk(500005))
+        .await
+        .unwrap();
+    let second = gtm_db::list_tickets_for_game(&pool, GamePk(500006))
+        .await
+        .unwrap();
+    for t in &first[..2] {
+        gtm_db::assign_ticket(&pool, t.id, ann.id, None)
+            .await
+            .unwrap();
+    }
+    gtm_db::assign_ticket(&pool, second[0].id, ann.id, None)
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, second[1].id, bob.id, None)
+        .await
+        .unwrap();
+    // A premium game: the ticket's own cost wins over the seat's face value
+    assert!(
+        gtm_db::set_ticket_cost(&pool, second[0].id, Some(9000))
+            .await
+            .unwrap()
+    );
+
+    let ledgers = gtm_db::ledger::member_ledgers(&pool, "2026", None)
+        .await
+        .unwrap();
+    assert_eq!(ledgers.len(), 2);
+    let a = &ledgers[0];
+    assert_eq!(a.user_name, "Ann");
+    assert_eq!((a.seats, a.amount), (3, 4500 * 2 + 9000));
+    assert_eq!(a.games.len(), 2);
+    assert_eq!(
+        (a.games[0].game_pk, a.games[0].amount),
+        (GamePk(500005), 9000)
+    );
+    assert_eq!((ledgers[1].seats, ledgers[1].amount), (1, 4500));
+
+    let mine = gtm_db::ledger::member_ledgers(&pool, "2026", Some(bob.id))
+        .await
+        .unwrap();
+    assert_eq!(mine.len(), 1);
+    assert!(
+        gtm_db::ledger::member_ledgers(&pool, "2025", None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn split_rules_divide_owned_seats_and_usage_covers_the_rest() {
+    use gtm_db::ledger;
+
+    let pool = test_pool().await;
+    let mut april = sample_game(500007);
+    april.official_date = "2026-04-20".to_string();
+    let mut may = sample_game(500008);
+    may.official_date = "2026-05-02".to_string();
+    may.game_date = "2026-05-02T20:05:00Z".to_string();
+    gtm_db::upsert_game(&pool, &april).await.unwrap();
+    gtm_db::upsert_game(&pool, &may).await.unwrap();
+    let mut seats = Vec::new();
+    for n in ["1", "2", "3"] {
+        seats.push(
+            gtm_db::add_seat(&pool, "VR314", "A", n, None)
+                .await
+                .unwrap(),
+        );
+    }
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    gtm_db::update_seat_group_face_value(&pool, "VR314", "A", Some(4500))
+        .await
+        .unwrap();
+    let mut users = Vec::new();
+    for name in ["Ann", "Bob", "Cy"] {
+        let sub = format!("auth0|split{name}");
+        let email = format!("split{name}@example.com");
+        users.push(
+            gtm_db::upsert_user(&pool, &sub, &email, name)
+                .await
+                .unwrap(),
+        );
+    }
+    let (ann, bob, cy) = (users[0].id, users[1].id, users[2].id);
+
+    // Ann and Bob own seat 1 between them; seat 2 is pay-per-use
+    ledger::set_split_rules(&pool, Some(seats[0].id), &[(ann, 5000), (bob, 5000)])
+        .await
+        .unwrap();
+    let err = ledger::set_split_rules(&pool, None, &[(cy, 9000)])
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("90.00%"), "{err}");
+    let april_tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500007))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, april_tickets[1].id, cy, None)
+        .await
+        .unwrap();
+    let may_tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500008))
+        .await
+        .unwrap();
+    gtm_db::set_ticket_cost(&pool, may_tickets[0].id, Some(1001))
+        .await
+        .unwrap();
+
+    let periods = ledger::cost_split(&pool, "2026", None).await.unwrap();
+    let amounts = |p: &gtm_models::CostSplitPeriod| {
+        p.members
+            .iter()
+            .map(|m| (m.user_name.clone(), m.amount))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(periods.len(), 2);
+    assert_eq!(periods[0].period, "2026-04");
+    assert_eq!(
+        amounts(&periods[0]),
+        vec![
+            ("Ann".into(), 2250),
+            ("Bob".into(), 2250),
+            ("Cy".into(), 4500)
+        ]
+    );
+    assert_eq!((periods[0].unallocated, periods[0].total), (4500, 13500));
+    // The odd cent goes to the earlier of two equal shares
+    assert_eq!(
+        amounts(&periods[1]),
+        vec![("Ann".into(), 501), ("Bob".into(), 500)]
+    );
+    assert_eq!((periods[1].unallocated, periods[1].total), (9000, 10001));
+    assert_eq!(periods[1].members[0].lines[0].basis, "seat");
+
+    // A plan-wide rule picks up every seat without its own
+    ledger::set_split_rules(&pool, None, &[(cy, 10_000)])
+        .await
+        .unwrap();
+    let periods = ledger::cost_split(&pool, "2026", None).await.unwrap();
+    assert_eq!(periods[1].unallocated, 0);
+    assert_eq!(amounts(&periods[1])[2], ("Cy".into(), 9000));
+
+    let mine = ledger::cost_split(&pool, "2026", Some(bob)).await.unwrap();
+    assert_eq!(amounts(&mine[0]), vec![("Bob".into(), 2250)]);
+    assert_eq!(mine[0].total, 13500);
+}
+
+#[tokio::test]
+async fn invoices_bill_shares_and_track_payments_per_line() {
+    use gtm_db::invoices::{self, NewPayment};
+
+    let pool = test_pool().await;
+    let mut first = sample_game(500009);
+    first.official_date = "2026-06-03".to_string();
+    let mut second = sample_game(500010);
+    second.official_date = "2026-06-10".to_string();
+    second.game_date = "2026-06-10T20:05:00Z".to_string();
+    gtm_db::upsert_game(&pool, &first).await.unwrap();
+    gtm_db::upsert_game(&pool, &second).await.unwrap();
+    let seat = gtm_db::add_seat(&pool, "VR315", "A", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    gtm_db::update_seat_group_face_value(&pool, "VR315", "A", Some(4000))
+        .await
+        .unwrap();
+    let dee = gtm_db::upsert_user(&pool, "auth0|invDee", "dee@example.com", "Dee")
+        .await
+        .unwrap();
+    gtm_db::ledger::set_split_rules(&pool, Some(seat.id), &[(dee.id, 10_000)])
+        .await
+        .unwrap();
+
+    assert!(invoices::generate(&pool, "June").await.is_err());
+    let run = invoices::generate(&pool, "2026-06").await.unwrap();
+    assert_eq!((run.created, run.existing), (1, 0));
+    let run = invoices::generate(&pool, "2026-06").await.unwrap();
+    assert_eq!((run.created, run.existing), (0, 1));
+
+    let mine = invoices::for_user(&pool, dee.id).await.unwrap();
+    assert_eq!(mine.len(), 1);
+    let invoice = &mine[0];
+    assert_eq!(
+        (invoice.invoice.total, invoice.invoice.status.as_str()),
+        (8000, "open")
+    );
+    assert_eq!(invoice.lines.len(), 2);
+
+    // A partial payment with no lines named goes to the oldest line first
+    fn pay(amount: i64, recorded_by: UserId, line_ids: &[i64]) -> NewPayment<'_> {
+        NewPayment {
+            amount,
+            method: Some("venmo"),
+            note: None,
+            recorded_by: Some(recorded_by),
+            line_ids,
+        }
+    }
+    let after = invoices::record_payment(&pool, invoice.invoice.id, &pay(5000, dee.id, &[]))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(after.invoice.status, "partial");
+    assert_eq!((after.lines[0].paid, after.lines[1].paid), (4000, 1000));
+    assert_eq!(after.payments.len(), 2);
+    assert!(
+        invoices::record_payment(&pool, invoice.invoice.id, &pay(3001, dee.id, &[]))
+            .await
+            .is_err()
+    );
+    assert!(
+        !invoices::delete_unpaid(&pool, invoice.invoice.id)
+            .await
+            .unwrap()
+    );
+
+    let line = [after.lines[1].id];
+    let after = invoices::record_payment(&pool, invoice.invoice.id, &pay(1000, dee.id, &line))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(after.lines[1].paid, 2000);
+
+    // Online checkout pays at most what's owed, keeping the rest to refund,
+    // and a webhook retry is a no-op
+    for retry in [false, true] {
+        let online = invoices::record_online_payment(&pool, invoice.invoice.id, "cs_1", 4500)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((online.overpaid, online.duplicate), (2500, retry));
+        let paid = online.invoice;
+        assert_eq!(
+            (paid.invoice.status.as_str(), paid.invoice.paid),
+            ("paid", 8000)
+        );
+        assert_eq!(paid.payments.len(), 4);
+        assert_eq!(
+            paid.payments[3].method.as_deref(),
+            Some(invoices::ONLINE_METHOD)
+        );
+    }
+    assert!(
+        invoices::record_online_payment(&pool, 9999, "cs_2", 100)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        invoices::record_payment(&pool, 9999, &pay(1, dee.id, &[]))
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn concurrent_payments_never_exceed_the_invoice() {
+    use gtm_db::invoices::{self, NewPayment};
+
+    let pool = test_pool().await;
+    let (invoice_id, payer) = common::open_invoice(&pool, 500011).await;
+    let payment = NewPayment {
+        amount: 4000,
+        method: Some("venmo"),
+        note: None,
+        recorded_by: Some(payer),
+        line_ids: &[],
+    };
+
+    let pay = || invoices::record_payment(&pool, invoice_id, &payment);
+    let results = tokio::join!(pay(), pay(), pay(), pay());
+    let ok = [results.0, results.1, results.2, results.3]
+        .iter()
+        .filter(|r| r.is_ok())
+        .count();
+    assert_eq!(ok, 1);
+    let paid = invoices::detail(&pool, invoice_id).await.unwrap().unwrap();
+    assert_eq!((paid.invoice.paid, paid.payments.len()), (4000, 1));
+}
+
 // --- Revoke Ticket ---
 
 #[tokio::test]
@@ -145,18 +659,24 @@ async fn revoke_ticket_makes_available() {
         .await
         .unwrap();
 
-    gtm_db::generate_tickets_for_seat(&pool, seat.id)
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500004))
         .await
         .unwrap();
-    let tickets = gtm_db::list_tickets_for_game(&pool, 500004).await.unwrap();
-    gtm_db::assign_ticket(&pool, tickets[0].id, user.id)
+    gtm_db::assign_ticket(&pool, tickets[0].id, user.id, None)
         .await
         .unwrap();
 
-    let ok = gtm_db::revoke_ticket(&pool, tickets[0].id).await.unwrap();
+    let ok = gtm_db::revoke_ticket(&pool, tickets[0].id, None)
+        .await
+        .unwrap();
     assert!(ok);
 
-    let tickets = gtm_db::list_tickets_for_game(&pool, 500004).await.unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500004))
+        .await
+        .unwrap();
     assert_eq!(tickets[0].status, "available");
     assert!(tickets[0].assigned_to.is_none());
 }
@@ -176,10 +696,10 @@ async fn allocation_summary_counts() {
     let s2 = gtm_db::add_seat(&pool, "VR313", "D", "2", None)
         .await
         .unwrap();
-    gtm_db::generate_tickets_for_seat(&pool, s1.id)
+    gtm_db::generate_tickets_for_seat(&pool, s1.id, TEAM_ID, REGULAR)
         .await
         .unwrap();
-    gtm_db::generate_tickets_for_seat(&pool, s2.id)
+    gtm_db::generate_tickets_for_seat(&pool, s2.id, TEAM_ID, REGULAR)
         .await
         .unwrap();
 
@@ -187,24 +707,31 @@ async fn allocation_summary_counts() {
     let user = gtm_db::upsert_user(&pool, "auth0|sum1", "s@example.com", "Summer")
         .await
         .unwrap();
-    gtm_db::create_ticket_request(&pool, user.id, 500005, 3, None)
+    gtm_db::create_ticket_request(&pool, user.id, GamePk(500005), 3, None)
         .await
         .unwrap();
 
     // Assign one ticket
-    let tickets = gtm_db::list_tickets_for_game(&pool, 500005).await.unwrap();
-    gtm_db::assign_ticket(&pool, tickets[0].id, user.id)
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500005))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[0].id, user.id, None)
         .await
         .unwrap();
 
-    let summary = gtm_db::allocation_summary(&pool).await.unwrap();
+    let summary = gtm_db::allocation_summary(&pool, TEAM_ID, None)
+        .await
+        .unwrap();
     assert_eq!(summary.len(), 1);
-    let (game_pk, total, assigned, available, requested) = summary[0];
-    assert_eq!(game_pk, 500005);
-    assert_eq!(total, 2);
-    assert_eq!(assigned, 1);
-    assert_eq!(available, 1);
-    assert_eq!(requested, 3);
+    let row = &summary[0];
+    assert_eq!(row.game_pk, GamePk(500005));
+    assert_eq!(row.official_date, "2026-04-01");
+    assert_eq!(row.away_team_name, "Arizona Diamondbacks");
+    assert_eq!(row.total_seats, 2);
+    assert_eq!(row.assigned, 1);
+    assert_eq!(row.available, 1);
+    assert_eq!(row.total_requested, 3);
+    assert_eq!(row.oversubscribed, 1);
 }
 
 // --- Ticket Summary ---
@@ -217,16 +744,58 @@ async fn ticket_summary_per_game() {
     let seat = gtm_db::add_seat(&pool, "VR313", "E", "1", None)
         .await
         .unwrap();
-    gtm_db::generate_tickets_for_seat(&pool, seat.id)
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
         .await
         .unwrap();
 
-    let summary = gtm_db::ticket_summary_for_games(&pool).await.unwrap();
+    let summary = gtm_db::ticket_summary_for_games(&pool, None, None, None)
+        .await
+        .unwrap();
     assert_eq!(summary.len(), 1);
-    let (game_pk, total, available) = summary[0];
-    assert_eq!(game_pk, 500006);
-    assert_eq!(total, 1);
-    assert_eq!(available, 1);
+    let row = &summary[0];
+    assert_eq!(row.game_pk, GamePk(500006));
+    assert_eq!(row.official_date, "2026-04-01");
+    assert_eq!(row.opponent, "Arizona Diamondbacks");
+    assert_eq!(row.status_abstract, "Preview");
+    assert_eq!(row.total, 1);
+    assert_eq!(row.available, 1);
+}
+
+#[tokio::test]
+async fn ticket_summary_filters_by_month_and_start_date() {
+    let pool = test_pool().await;
+    let april = sample_game(500061);
+    let mut may = sample_game(500062);
+    may.official_date = "2026-05-10".to_string();
+    may.game_date = "2026-05-10T20:05:00Z".to_string();
+    gtm_db::upsert_game(&pool, &april).await.unwrap();
+    gtm_db::upsert_game(&pool, &may).await.unwrap();
+    gtm_db::add_seat(&pool, "VR313", "E", "2", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+
+    let all = gtm_db::ticket_summary_for_games(&pool, None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        all.iter().map(|r| r.game_pk).collect::<Vec<_>>(),
+        vec![GamePk(500061), GamePk(500062)]
+    );
+
+    let in_may = gtm_db::ticket_summary_for_games(&pool, None, Some(5), None)
+        .await
+        .unwrap();
+    assert_eq!(in_may.len(), 1);
+    assert_eq!(in_may[0].game_pk, GamePk(500062));
+
+    let upcoming = gtm_db::ticket_summary_for_games(&pool, None, None, Some("2026-04-02"))
+        .await
+        .unwrap();
+    assert_eq!(upcoming.len(), 1);
+    assert_eq!(upcoming[0].game_pk, GamePk(500062));
 }
 
 // --- Generate Tickets For All Seats ---
@@ -243,9 +812,4211 @@ async fn generate_tickets_for_all_seats() {
         .await
         .unwrap();
 
-    let count = gtm_db::generate_tickets_for_all_seats(&pool).await.unwrap();
-    assert_eq!(count, 2); // 1 game × 2 seats
+    let generated = gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(generated.created.len(), 2); // 1 game × 2 seats
+    assert_eq!(generated.skipped, 0);
+
+    // A second run creates nothing and reports both pairs as skipped
+    let again = gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert!(again.created.is_empty());
+    assert_eq!(again.skipped, 2);
 
-    let tickets = gtm_db::list_tickets_for_game(&pool, 500007).await.unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(500007))
+        .await
+        .unwrap();
     assert_eq!(tickets.len(), 2);
 }
+
+#[tokio::test]
+async fn ticket_generation_follows_the_configured_team() {
+    const ATHLETICS: u32 = 133;
+
+    let pool = test_pool().await;
+    let giants_home = sample_game(500081);
+    let mut athletics_home = sample_game(500082);
+    athletics_home.home_team_id = ATHLETICS.into();
+    athletics_home.home_team_name = "Athletics".to_string();
+    for g in [&giants_home, &athletics_home] {
+        gtm_db::upsert_game(&pool, g).await.unwrap();
+    }
+    let seat = gtm_db::add_seat(&pool, "101", "A", "1", None)
+        .await
+        .unwrap();
+
+    let generated = gtm_db::generate_tickets_for_all_seats(&pool, ATHLETICS, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(generated.created, vec![(GamePk(500082), seat.id)]);
+    assert_eq!(
+        gtm_db::list_tickets_for_game(&pool, GamePk(500082))
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    let summary = gtm_db::allocation_summary(&pool, ATHLETICS, None)
+        .await
+        .unwrap();
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].game_pk, GamePk(500082));
+    assert!(
+        gtm_db::allocation_summary(&pool, TEAM_ID, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn ticket_generation_respects_ticketed_game_types() {
+    use gtm_models::GameType;
+
+    let by_default: Vec<GameType> = GameType::ALL
+        .into_iter()
+        .filter(|t| t.ticketed_by_default())
+        .collect();
+    assert_eq!(by_default, vec![GameType::Regular]);
+    assert_eq!(GameType::label_for("F"), "Wild Card");
+    assert_eq!(GameType::label_for("X"), "X");
+
+    let pool = test_pool().await;
+    let regular = sample_game(500071);
+    let mut spring = sample_game(500072);
+    spring.game_type = "S".to_string();
+    let mut world_series = sample_game(500073);
+    world_series.game_type = "W".to_string();
+    for g in [&regular, &spring, &world_series] {
+        gtm_db::upsert_game(&pool, g).await.unwrap();
+    }
+    let seat = gtm_db::add_seat(&pool, "VR313", "F", "3", None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(
+        gtm_db::list_tickets_for_game(&pool, GamePk(500072))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Adding postseason later only fills in the new games
+    let generated = gtm_db::generate_tickets_for_all_seats(
+        &pool,
+        TEAM_ID,
+        &[GameType::Regular, GameType::WorldSeries],
+    )
+    .await
+    .unwrap();
+    assert_eq!(generated.created, vec![(GamePk(500073), seat.id)]);
+    assert_eq!(generated.skipped, 1);
+    assert_eq!(
+        gtm_db::list_tickets_for_game(&pool, GamePk(500073))
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, &[])
+            .await
+            .unwrap(),
+        gtm_db::TicketGeneration::default()
+    );
+}
+
+#[tokio::test]
+async fn ticket_coverage_lists_home_games_without_tickets() {
+    let pool = test_pool().await;
+    let covered = sample_game(500091);
+    let mut missed = sample_game(500092);
+    missed.official_date = "2026-04-02".to_string();
+    let mut spring = sample_game(500093);
+    spring.game_type = "S".to_string();
+    for g in [&covered, &missed, &spring] {
+        gtm_db::upsert_game(&pool, g).await.unwrap();
+    }
+    let seat = gtm_db::add_seat(&pool, "VR313", "G", "1", None)
+        .await
+        .unwrap();
+    // As if an import had stopped after the first game
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM game_tickets WHERE game_pk = 500092")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let coverage = gtm_db::reports::ticket_coverage(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(coverage.active_seats, 1);
+    assert_eq!(coverage.home_games, 2);
+    let uncovered: Vec<GamePk> = coverage.uncovered.iter().map(|g| g.game_pk).collect();
+    assert_eq!(uncovered, vec![GamePk(500092)]);
+    assert_eq!(coverage.uncovered[0].opponent, "Arizona Diamondbacks");
+
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let coverage = gtm_db::reports::ticket_coverage(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert!(coverage.uncovered.is_empty());
+}
+
+#[tokio::test]
+async fn traditional_double_headers_are_ticketed_once_and_split_ones_twice() {
+    let pool = test_pool().await;
+    // Game 2 listed first in game_pk order; listings go by game number
+    for (pk, code, number, date) in [
+        (500111, "Y", 2, "2026-04-10"),
+        (500112, "Y", 1, "2026-04-10"),
+        (500113, "S", 1, "2026-04-11"),
+        (500114, "S", 2, "2026-04-11"),
+    ] {
+        let mut g = sample_game(pk);
+        g.double_header = code.to_string();
+        g.game_number = number;
+        g.official_date = date.to_string();
+        g.game_date = format!("{date}T19:15:00Z");
+        gtm_db::upsert_game(&pool, &g).await.unwrap();
+    }
+    let seat = gtm_db::add_seat(&pool, "VR313", "G", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    gtm_db::add_seat(&pool, "VR313", "G", "2", None)
+        .await
+        .unwrap();
+    let generated = gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!((generated.created.len(), generated.skipped), (3, 3));
+
+    let summary = gtm_db::ticket_summary_for_games(&pool, None, None, None)
+        .await
+        .unwrap();
+    let rows: Vec<(i64, Option<String>, i64)> = summary
+        .iter()
+        .map(|r| (r.game_pk.0, r.double_header_label(), r.total))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            (500112, Some("Game 1 of 2".to_string()), 2),
+            (500113, Some("Game 1 of 2".to_string()), 2),
+            (500114, Some("Game 2 of 2".to_string()), 2),
+        ]
+    );
+    let coverage = gtm_db::reports::ticket_coverage(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!((coverage.home_games, coverage.uncovered.len()), (3, 0));
+
+    let games = gtm_db::list_games(&pool, &gtm_db::GameFilter::default())
+        .await
+        .unwrap();
+    let order: Vec<(i64, Option<String>)> = games
+        .iter()
+        .map(|g| (g.game_pk.0, g.double_header_label()))
+        .collect();
+    assert_eq!(order[0], (500112, Some("Game 1 of 2".to_string())));
+    assert_eq!(order[1], (500111, Some("Game 2 of 2".to_string())));
+    assert_eq!(games[1].double_header(), DoubleHeader::Traditional);
+}
+
+// --- Phone Verification ---
+
+#[tokio::test]
+async fn phone_verification_flow() {
+    let pool = test_pool().await;
+    let user = gtm_db::upsert_user(&pool, "auth0|sms1", "sms@example.com", "Texter")
+        .await
+        .unwrap();
+
+    let phone = gtm_db::set_user_phone(
+        &pool,
+        user.id,
+        "+14155550123",
+        "123456",
+        "2026-06-01T12:10:00Z",
+    )
+    .await
+    .unwrap();
+    assert_eq!(phone.verified, 0);
+    assert!(gtm_db::list_sms_recipients(&pool).await.unwrap().is_empty());
+
+    // Wrong code, then expired code
+    let ok = gtm_db::verify_user_phone(&pool, user.id, "000000", "2026-06-01T12:00:00Z")
+        .await
+        .unwrap();
+    assert!(!ok);
+    let ok = gtm_db::verify_user_phone(&pool, user.id, "123456", "2026-06-01T12:11:00Z")
+        .await
+        .unwrap();
+    assert!(!ok);
+
+    // Correct code within the window
+    let ok = gtm_db::verify_user_phone(&pool, user.id, "123456", "2026-06-01T12:05:00Z")
+        .await
+        .unwrap();
+    assert!(ok);
+    let recipients = gtm_db::list_sms_recipients(&pool).await.unwrap();
+    assert_eq!(recipients.len(), 1);
+    assert_eq!(recipients[0].phone, "+14155550123");
+
+    // Opting out removes them from recipients
+    gtm_db::set_sms_opt_in(&pool, user.id, false).await.unwrap();
+    assert!(gtm_db::list_sms_recipients(&pool).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn user_locale_is_set_listed_and_cleared() {
+    let pool = test_pool().await;
+    let user = gtm_db::upsert_user(&pool, "auth0|es1", "es@example.com", "Hablante")
+        .await
+        .unwrap();
+    let other = gtm_db::upsert_user(&pool, "auth0|en1", "en@example.com", "Speaker")
+        .await
+        .unwrap();
+    assert_eq!(gtm_db::get_user_locale(&pool, user.id).await.unwrap(), None);
+
+    gtm_db::update_user_locale(&pool, user.id, Some("es"))
+        .await
+        .unwrap();
+    assert_eq!(
+        gtm_db::get_user_locale(&pool, user.id).await.unwrap(),
+        Some("es".to_string())
+    );
+    let locales = gtm_db::list_user_locales(&pool).await.unwrap();
+    assert_eq!(locales.get(&user.id).map(String::as_str), Some("es"));
+    assert!(!locales.contains_key(&other.id));
+
+    gtm_db::update_user_locale(&pool, user.id, None)
+        .await
+        .unwrap();
+    assert!(gtm_db::list_user_locales(&pool).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn phone_verification_locks_after_too_many_attempts() {
+    let pool = test_pool().await;
+    let user = gtm_db::upsert_user(&pool, "auth0|sms2", "sms2@example.com", "Guesser")
+        .await
+        .unwrap();
+    gtm_db::set_user_phone(
+        &pool,
+        user.id,
+        "+14155550124",
+        "654321",
+        "2026-06-01T12:10:00Z",
+    )
+    .await
+    .unwrap();
+
+    for _ in 0..5 {
+        let ok = gtm_db::verify_user_phone(&pool, user.id, "111111", "2026-06-01T12:00:00Z")
+            .await
+            .unwrap();
+        assert!(!ok);
+    }
+    // Even the right code is rejected once the attempt limit is reached
+    let ok = gtm_db::verify_user_phone(&pool, user.id, "654321", "2026-06-01T12:00:00Z")
+        .await
+        .unwrap();
+    assert!(!ok);
+}
+
+// --- Schedule Import ---
+
+fn sample_promotion(offer_id: i64, game_pk: i64, display_order: i64) -> gtm_models::Promotion {
+    gtm_models::Promotion {
+        offer_id,
+        game_pk: GamePk(game_pk),
+        name: format!("Promo {offer_id}"),
+        offer_type: Some("Giveaway".to_string()),
+        description: None,
+        distribution: None,
+        presented_by: None,
+        alt_page_url: None,
+        ticket_link: None,
+        thumbnail_url: None,
+        image_url: None,
+        display_order,
+    }
+}
+
+#[tokio::test]
+async fn upsert_schedule_writes_games_promotions_and_tickets() {
+    let pool = test_pool().await;
+    gtm_db::add_seat(&pool, "VR313", "S", "1", None)
+        .await
+        .unwrap();
+    gtm_db::add_seat(&pool, "VR313", "S", "2", None)
+        .await
+        .unwrap();
+
+    // 40 games forces more than one multi-row batch
+    let mut data = gtm_models::ScheduleData::default();
+    for pk in 700001..=700040 {
+        data.games.push(sample_game(pk));
+    }
+    data.promotions.push(sample_promotion(1, 700001, 1));
+    data.promotions.push(sample_promotion(2, 700002, 1));
+    // Duplicate offer in the same payload keeps the last copy
+    data.promotions.push(sample_promotion(1, 700001, 5));
+
+    let result = gtm_db::upsert_schedule(&pool, &data, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(result.games, 40);
+    assert_eq!(result.promotions, 2);
+    assert_eq!(result.promotions_changed, 2);
+    assert_eq!(result.tickets, 80);
+
+    let promos = gtm_db::get_promotions_for_game(&pool, GamePk(700001))
+        .await
+        .unwrap();
+    assert_eq!(promos.len(), 1);
+    assert_eq!(promos[0].display_order, 5);
+
+    // Re-running is idempotent: no new tickets, games updated in place
+    data.games[0].status_detailed = "Final".to_string();
+    let again = gtm_db::upsert_schedule(&pool, &data, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(again.tickets, 0);
+    // Unchanged promotions aren't rewritten; a NULL becoming a value is a change
+    assert_eq!(again.promotions_changed, 0);
+    data.promotions[1].description = Some("First 20,000 fans".to_string());
+    let changed = gtm_db::upsert_schedule(&pool, &data, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(changed.promotions_changed, 1);
+    let game = gtm_db::get_game(&pool, GamePk(700001))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.status_detailed, "Final");
+    assert_eq!(
+        gtm_db::list_games(&pool, &Default::default())
+            .await
+            .unwrap()
+            .len(),
+        40
+    );
+}
+
+#[tokio::test]
+async fn upsert_schedule_rolls_back_on_failure() {
+    let pool = test_pool().await;
+    let mut data = gtm_models::ScheduleData::default();
+    data.games.push(sample_game(700101));
+    // References a game that doesn't exist — violates the promotions FK
+    data.promotions.push(sample_promotion(9, 999999, 1));
+
+    assert!(
+        gtm_db::upsert_schedule(&pool, &data, TEAM_ID, REGULAR)
+            .await
+            .is_err()
+    );
+    assert!(
+        gtm_db::list_games(&pool, &Default::default())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn sync_schedule_reports_changes() {
+    use gtm_db::schedule_sync::{ScheduleChange, sync_schedule};
+
+    let pool = test_pool().await;
+    let mut data = gtm_models::ScheduleData::default();
+    data.games.push(sample_game(700301));
+    data.games.push(sample_game(700302));
+    let first = sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+    assert_eq!(first.upsert.games, 2);
+    assert!(matches!(
+        first.changes[..],
+        [
+            ScheduleChange::Added {
+                game_pk: GamePk(700301),
+                ..
+            },
+            ScheduleChange::Added {
+                game_pk: GamePk(700302),
+                ..
+            }
+        ]
+    ));
+
+    // Unchanged games produce no diff
+    let again = sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+    assert!(again.changes.is_empty());
+
+    data.games[0].game_date = "2026-04-02T02:05:00Z".to_string();
+    data.games[1].status_detailed = "Final".to_string();
+    data.games[1].away_score = Some(2);
+    data.games[1].home_score = Some(5);
+    let sync = sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+    assert_eq!(
+        sync.changes,
+        vec![
+            ScheduleChange::Rescheduled {
+                game_pk: GamePk(700301),
+                from: "2026-04-01T19:15:00Z".to_string(),
+                to: "2026-04-02T02:05:00Z".to_string(),
+            },
+            ScheduleChange::StatusChanged {
+                game_pk: GamePk(700302),
+                from: "Scheduled".to_string(),
+                to: "Final".to_string(),
+            },
+            ScheduleChange::ScoreChanged {
+                game_pk: GamePk(700302),
+                away: Some(2),
+                home: Some(5),
+            },
+        ]
+    );
+    assert_eq!(sync.changes[2].to_string(), "game 700302: score 2-5");
+}
+
+#[tokio::test]
+async fn check_scrape_flags_empty_and_shrunken_scrapes() {
+    use gtm_db::schedule_sync::{SyncThresholds, check_scrape, sync_schedule};
+    use gtm_models::GameType;
+
+    let pool = test_pool().await;
+    let thresholds = SyncThresholds {
+        min_games: 1,
+        promotion_drop_pct: 40,
+    };
+    let regular = [GameType::Regular];
+    let mut data = gtm_models::ScheduleData::default();
+    data.games.push(sample_game(700401));
+    data.games.push(sample_game(700402));
+    for offer_id in 1..=5 {
+        data.promotions
+            .push(sample_promotion(offer_id, 700401 + offer_id % 2, offer_id));
+    }
+
+    // Nothing stored yet: even an empty scrape is fine
+    let empty = gtm_models::ScheduleData::default();
+    let anomalies = check_scrape(&pool, &empty, 2026, &regular, true, &thresholds)
+        .await
+        .unwrap();
+    assert!(anomalies.is_empty());
+    sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+
+    let anomalies = check_scrape(&pool, &empty, 2026, &regular, true, &thresholds)
+        .await
+        .unwrap();
+    assert_eq!(
+        anomalies,
+        vec![
+            "Scrape returned 0 game(s) for 2026; 2 are stored".to_string(),
+            "Scrape returned 0 promotion(s) for 2026; 5 are stored".to_string(),
+        ]
+    );
+
+    // Losing one promotion of five is 20%; losing two is 40%
+    data.promotions.pop();
+    assert!(
+        check_scrape(&pool, &data, 2026, &regular, true, &thresholds)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    data.promotions.pop();
+    assert_eq!(
+        check_scrape(&pool, &data, 2026, &regular, true, &thresholds)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    // Promotions weren't fetched, or the check is off
+    assert!(
+        check_scrape(&pool, &data, 2026, &regular, false, &thresholds)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let off = SyncThresholds {
+        min_games: 0,
+        promotion_drop_pct: 0,
+    };
+    assert!(
+        check_scrape(&pool, &empty, 2026, &regular, true, &off)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    // Another season's games aren't compared
+    assert!(
+        check_scrape(&pool, &empty, 2027, &regular, true, &thresholds)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn job_runs_keep_each_jobs_last_run() {
+    use gtm_db::jobs;
+
+    let pool = test_pool().await;
+    assert!(
+        jobs::get(&pool, jobs::SCHEDULE_SCRAPE)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    jobs::record(&pool, jobs::SCHEDULE_SCRAPE, jobs::OK, None)
+        .await
+        .unwrap();
+    let ok = jobs::get(&pool, jobs::SCHEDULE_SCRAPE)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(ok.last_ok_at.is_some());
+    jobs::record(
+        &pool,
+        jobs::SCHEDULE_SCRAPE,
+        jobs::SUSPECT,
+        Some("Scrape returned 0 game(s)"),
+    )
+    .await
+    .unwrap();
+    let runs = jobs::list(&pool).await.unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].status, jobs::SUSPECT);
+    assert_eq!(runs[0].detail.as_deref(), Some("Scrape returned 0 game(s)"));
+    assert!(!runs[0].finished_at.is_empty());
+    // A bad run keeps when the job last went well
+    assert_eq!(runs[0].last_ok_at, ok.last_ok_at);
+}
+
+#[tokio::test]
+async fn suspect_scrapes_update_games_without_moving_requests() {
+    use gtm_db::jobs;
+    use gtm_db::schedule_sync::{anomaly_detail, apply_schedule, unreported_anomalies};
+
+    let pool = test_pool().await;
+    let mut data = gtm_models::ScheduleData::default();
+    for pk in [700451, 700452] {
+        data.games.push(sample_game(pk));
+    }
+    apply_schedule(&pool, &data, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ann = gtm_db::upsert_user(&pool, "auth0|ann", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    gtm_db::create_ticket_request(&pool, ann.id, GamePk(700451), 2, None)
+        .await
+        .unwrap();
+
+    // A short scrape: 700451 cancelled, 700452 missing
+    data.games.truncate(1);
+    data.games[0].status_detailed = "Cancelled".to_string();
+    let sync = apply_schedule(&pool, &data, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(sync.changes.len(), 1);
+    assert!(sync.carryovers.is_empty() && sync.makeups.is_empty());
+    let game = gtm_db::get_game(&pool, GamePk(700451))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.status_detailed, "Cancelled");
+    assert!(
+        gtm_db::get_game(&pool, GamePk(700452))
+            .await
+            .unwrap()
+            .is_some()
+    );
+    let requests = gtm_db::list_requests_for_game(&pool, GamePk(700451))
+        .await
+        .unwrap();
+    assert_eq!(requests[0].flag_reason, None);
+
+    // Admins hear about each anomaly once
+    let anomalies = vec![
+        "Scrape returned 1 game(s) for 2026; 2 are stored".to_string(),
+        "Scrape returned 0 promotion(s) for 2026; 5 are stored".to_string(),
+    ];
+    assert_eq!(
+        unreported_anomalies(&pool, &anomalies).await.unwrap(),
+        anomalies
+    );
+    jobs::record(
+        &pool,
+        jobs::SCHEDULE_SCRAPE,
+        jobs::SUSPECT,
+        Some(&anomaly_detail(&anomalies[..1])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        unreported_anomalies(&pool, &anomalies).await.unwrap(),
+        anomalies[1..]
+    );
+    // A good run in between means the next anomaly is news again
+    jobs::record(&pool, jobs::SCHEDULE_SCRAPE, jobs::OK, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        unreported_anomalies(&pool, &anomalies[..1]).await.unwrap(),
+        anomalies[..1]
+    );
+}
+
+#[tokio::test]
+async fn schedule_changes_carry_over_or_flag_pending_requests() {
+    use gtm_db::schedule_sync::{CarryoverOutcome, sync_schedule};
+
+    let pool = test_pool().await;
+    let mut data = gtm_models::ScheduleData::default();
+    for pk in [700401, 700402, 700403] {
+        data.games.push(sample_game(pk));
+    }
+    sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+
+    let ann = gtm_db::upsert_user(&pool, "auth0|ann", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bob = gtm_db::upsert_user(&pool, "auth0|bob", "bob@example.com", "Bob")
+        .await
+        .unwrap();
+    for pk in [700401, 700402, 700403] {
+        gtm_db::create_ticket_request(&pool, ann.id, GamePk(pk), 2, None)
+            .await
+            .unwrap();
+    }
+    gtm_db::create_ticket_request(&pool, bob.id, GamePk(700401), 2, None)
+        .await
+        .unwrap();
+    // Bob can't make the makeup date
+    assert!(
+        gtm_db::add_blackout(&pool, bob.id, "2026-04-02")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !gtm_db::add_blackout(&pool, bob.id, "2026-04-02")
+            .await
+            .unwrap()
+    );
+
+    // 700401 rescheduled a day later, 700402 postponed, 700403 cancelled
+    data.games[0].game_date = "2026-04-02T20:05:00Z".to_string();
+    data.games[0].official_date = "2026-04-02".to_string();
+    data.games[1].status_detailed = "Postponed".to_string();
+    data.games[2].status_detailed = "Cancelled".to_string();
+    let sync = sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+
+    let outcome = |user_id: UserId, game_pk: i64| {
+        sync.carryovers
+            .iter()
+            .find(|c| c.user_id == user_id && c.game_pk == GamePk(game_pk))
+            .map(|c| c.outcome.clone())
+    };
+    assert_eq!(
+        outcome(ann.id, 700401),
+        Some(CarryoverOutcome::Moved {
+            from: "2026-04-01T19:15:00Z".to_string()
+        })
+    );
+    assert!(matches!(
+        outcome(bob.id, 700401),
+        Some(CarryoverOutcome::Flagged { .. })
+    ));
+    assert_eq!(outcome(ann.id, 700402), Some(CarryoverOutcome::Postponed));
+    assert!(matches!(
+        outcome(ann.id, 700403),
+        Some(CarryoverOutcome::Flagged { .. })
+    ));
+    assert_eq!(sync.carryovers.len(), 4);
+
+    // Requests stay pending on the same game; only flagged ones carry a reason
+    let anns = gtm_db::list_requests_for_user(&pool, ann.id, None)
+        .await
+        .unwrap();
+    assert!(anns.iter().all(|r| r.status == "pending"));
+    let flags: Vec<_> = anns.iter().map(|r| r.flag_reason.is_some()).collect();
+    assert_eq!(flags, vec![false, false, true]);
+    let bobs = gtm_db::list_requests_for_user(&pool, bob.id, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        bobs[0].flag_reason.as_deref(),
+        Some("Rescheduled onto blackout date 2026-04-02")
+    );
+
+    // Editing the request clears the flag
+    let edited = gtm_db::create_ticket_request(&pool, bob.id, GamePk(700401), 1, None)
+        .await
+        .unwrap();
+    assert_eq!(edited.flag_reason, None);
+
+    // A second identical sync changes nothing further
+    let again = sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+    assert!(again.carryovers.is_empty());
+}
+
+#[tokio::test]
+async fn postponed_games_move_seats_and_requests_to_their_makeup() {
+    use gtm_db::schedule_sync::sync_schedule;
+
+    let pool = test_pool().await;
+    for number in ["1", "2", "3"] {
+        gtm_db::add_seat(&pool, "VR313", "A", number, None)
+            .await
+            .unwrap();
+    }
+    let mut data = gtm_models::ScheduleData::default();
+    data.games.push(sample_game(700501));
+    let mut makeup = sample_game(700502);
+    makeup.game_date = "2026-04-02T20:05:00Z".to_string();
+    makeup.official_date = "2026-04-02".to_string();
+    data.games.push(makeup);
+    sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+
+    let user = |name: &str| {
+        let pool = pool.clone();
+        let name = name.to_string();
+        async move {
+            gtm_db::upsert_user(
+                &pool,
+                &format!("auth0|{name}"),
+                &format!("{name}@example.com"),
+                &name,
+            )
+            .await
+            .unwrap()
+        }
+    };
+    let (ann, bob, cat, dan) = (
+        user("ann").await,
+        user("bob").await,
+        user("cat").await,
+        user("dan").await,
+    );
+    let seat = |tickets: &[gtm_models::GameTicketDetail], number: &str| {
+        tickets.iter().find(|t| t.seat == number).unwrap().id
+    };
+    let before = gtm_db::list_tickets_for_game(&pool, GamePk(700501))
+        .await
+        .unwrap();
+    let after = gtm_db::list_tickets_for_game(&pool, GamePk(700502))
+        .await
+        .unwrap();
+    // Ann holds seats 1 and 2 and Cat seat 3, which Dan already holds at the makeup
+    for (number, member) in [("1", &ann), ("2", &ann), ("3", &cat)] {
+        assert!(
+            gtm_db::assign_ticket(&pool, seat(&before, number), member.id, None)
+                .await
+                .unwrap()
+        );
+    }
+    assert!(
+        gtm_db::assign_ticket(&pool, seat(&after, "3"), dan.id, None)
+            .await
+            .unwrap()
+    );
+    for member in [&ann, &bob, &cat] {
+        gtm_db::create_ticket_request(&pool, member.id, GamePk(700501), 2, None)
+            .await
+            .unwrap();
+    }
+    // Bob already asked for the makeup game
+    gtm_db::create_ticket_request(&pool, bob.id, GamePk(700502), 2, None)
+        .await
+        .unwrap();
+
+    data.games[0].status_detailed = "Postponed".to_string();
+    data.games[0].reschedule_date = Some("2026-04-02T20:05:00Z".to_string());
+    let sync = sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+    let moved = |member: &gtm_models::User| {
+        sync.makeups
+            .iter()
+            .find(|m| m.user_id == member.id)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(sync.makeups.len(), 3);
+    let anns = moved(&ann);
+    assert_eq!((anns.from, anns.to), (GamePk(700501), GamePk(700502)));
+    assert_eq!(anns.seats, 2);
+    assert!(anns.request_id.is_some());
+    assert_eq!(anns.problem, None);
+    let bobs = moved(&bob);
+    assert_eq!(bobs.request_id, None);
+    assert_eq!(
+        bobs.problem.as_deref(),
+        Some("Already requested makeup game 700502")
+    );
+    let cats = moved(&cat);
+    assert_eq!(cats.seats, 0);
+    assert!(cats.request_id.is_some());
+    assert!(cats.problem.as_deref().unwrap().contains("1 seat(s)"));
+
+    let postponed = gtm_db::get_game(&pool, GamePk(700501))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(postponed.rescheduled_to, Some(GamePk(700502)));
+    let holders = |tickets: Vec<gtm_models::GameTicketDetail>| {
+        let mut holders: Vec<_> = tickets
+            .iter()
+            .map(|t| (t.seat.clone(), t.assigned_to))
+            .collect();
+        holders.sort();
+        holders
+    };
+    assert_eq!(
+        holders(
+            gtm_db::list_tickets_for_game(&pool, GamePk(700502))
+                .await
+                .unwrap()
+        ),
+        vec![
+            ("1".to_string(), Some(ann.id)),
+            ("2".to_string(), Some(ann.id)),
+            ("3".to_string(), Some(dan.id)),
+        ]
+    );
+    // Cat's stranded seat stays on the postponed game
+    assert_eq!(
+        holders(
+            gtm_db::list_tickets_for_game(&pool, GamePk(700501))
+                .await
+                .unwrap()
+        ),
+        vec![
+            ("1".to_string(), None),
+            ("2".to_string(), None),
+            ("3".to_string(), Some(cat.id)),
+        ]
+    );
+
+    // Moved requests follow the member; Cat's and Bob's old one are flagged
+    let requests = |member: &gtm_models::User| {
+        let pool = pool.clone();
+        let id = member.id;
+        async move {
+            gtm_db::list_requests_for_user(&pool, id, None)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|r| (r.game_pk, r.flag_reason.is_some()))
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(requests(&ann).await, vec![(GamePk(700502), false)]);
+    assert_eq!(requests(&cat).await, vec![(GamePk(700502), true)]);
+    let mut bobs = requests(&bob).await;
+    bobs.sort();
+    assert_eq!(bobs, vec![(GamePk(700501), true), (GamePk(700502), false)]);
+
+    // A game is made up once
+    let again = sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+    assert!(again.makeups.is_empty());
+}
+
+#[tokio::test]
+async fn makeups_skip_the_regular_game_keep_flags_and_check_blackouts() {
+    use gtm_db::schedule_sync::sync_schedule;
+
+    let pool = test_pool().await;
+    let mut data = gtm_models::ScheduleData::default();
+    data.games.push(sample_game(700511));
+    // The day's regular game, then the makeup added as game 2 with no time yet
+    let mut regular = sample_game(700512);
+    regular.game_date = "2026-04-02T20:05:00Z".to_string();
+    regular.official_date = "2026-04-02".to_string();
+    data.games.push(regular);
+    let mut makeup = sample_game(700513);
+    makeup.game_date = "2026-04-03T03:33:00Z".to_string();
+    makeup.official_date = "2026-04-02".to_string();
+    makeup.start_time_tbd = 1;
+    makeup.double_header = "S".to_string();
+    makeup.game_number = 2;
+    data.games.push(makeup);
+    // A suspended game resumes under its own game_pk
+    let mut suspended = sample_game(700514);
+    suspended.game_date = "2026-04-04T20:05:00Z".to_string();
+    suspended.official_date = "2026-04-04".to_string();
+    data.games.push(suspended);
+    let mut next = sample_game(700515);
+    next.game_date = "2026-04-05T20:05:00Z".to_string();
+    next.official_date = "2026-04-05".to_string();
+    data.games.push(next);
+    sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+
+    let eve = gtm_db::upsert_user(&pool, "auth0|eve", "eve@example.com", "Eve")
+        .await
+        .unwrap();
+    let fay = gtm_db::upsert_user(&pool, "auth0|fay", "fay@example.com", "Fay")
+        .await
+        .unwrap();
+    gtm_db::add_blackout(&pool, eve.id, "2026-04-02")
+        .await
+        .unwrap();
+    for member in [&eve, &fay] {
+        gtm_db::create_ticket_request(&pool, member.id, GamePk(700511), 2, None)
+            .await
+            .unwrap();
+    }
+    let fays = gtm_db::create_ticket_request(&pool, fay.id, GamePk(700514), 2, None)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE ticket_requests SET flag_reason = 'Check with Fay' WHERE user_id = ?")
+        .bind(fay.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // The reschedule time is 7:05 PM at the ballpark, the next day in UTC
+    data.games[0].status_detailed = "Postponed".to_string();
+    data.games[0].reschedule_date = Some("2026-04-03T02:05:00Z".to_string());
+    data.games[0].reschedule_official_date = Some("2026-04-02".to_string());
+    data.games[3].status_detailed = "Suspended: Rain".to_string();
+    data.games[3].reschedule_date = Some("2026-04-05T20:05:00Z".to_string());
+    let sync = sync_schedule(&pool, &data, TEAM_ID, REGULAR).await.unwrap();
+
+    assert_eq!(sync.makeups.len(), 2);
+    assert!(sync.makeups.iter().all(|m| m.to == GamePk(700513)));
+    let eves = sync.makeups.iter().find(|m| m.user_id == eve.id).unwrap();
+    assert_eq!(
+        eves.problem.as_deref(),
+        Some("Rescheduled onto blackout date 2026-04-02")
+    );
+    let fays_move = sync.makeups.iter().find(|m| m.user_id == fay.id).unwrap();
+    assert_eq!(fays_move.problem, None);
+    let flags: Vec<(GamePk, Option<String>)> = gtm_db::list_requests_for_user(&pool, fay.id, None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.game_pk, r.flag_reason))
+        .collect();
+    assert!(flags.contains(&(GamePk(700513), Some("Check with Fay".to_string()))));
+
+    let suspended = gtm_db::get_game(&pool, GamePk(700514))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(suspended.rescheduled_to, None);
+    let still = gtm_db::get_ticket_request(&pool, fays.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(still.game_pk, GamePk(700514));
+}
+
+// --- Promotion Interest ---
+
+#[tokio::test]
+async fn promotion_interest_suggests_request_until_requested() {
+    let pool = test_pool().await;
+    gtm_db::add_seat(&pool, "VR313", "S", "1", None)
+        .await
+        .unwrap();
+    let mut data = gtm_models::ScheduleData::default();
+    data.games.push(sample_game(700201));
+    data.promotions.push(sample_promotion(21, 700201, 1));
+    gtm_db::upsert_schedule(&pool, &data, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(
+        gtm_db::promotion_game_pks(&pool, 21).await.unwrap(),
+        vec![GamePk(700201)]
+    );
+
+    let ann = gtm_db::upsert_user(&pool, "auth0|ann", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bob = gtm_db::upsert_user(&pool, "auth0|bob", "bob@example.com", "Bob")
+        .await
+        .unwrap();
+    assert!(
+        gtm_db::add_promotion_interest(&pool, ann.id, 21, GamePk(700201))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !gtm_db::add_promotion_interest(&pool, ann.id, 21, GamePk(700201))
+            .await
+            .unwrap()
+    );
+    assert!(
+        gtm_db::add_promotion_interest(&pool, bob.id, 21, GamePk(700201))
+            .await
+            .unwrap()
+    );
+
+    let mine = gtm_db::list_promotion_interests_for_user(&pool, ann.id, "2026-03-01")
+        .await
+        .unwrap();
+    assert_eq!(mine.len(), 1);
+    assert_eq!((mine[0].suggest_request, mine[0].has_request), (1, 0));
+
+    // Past games are never suggested
+    let past = gtm_db::list_promotion_interests_for_user(&pool, ann.id, "2026-05-01")
+        .await
+        .unwrap();
+    assert_eq!(past[0].suggest_request, 0);
+
+    // Once Ann has requested the game, the suggestion goes away
+    gtm_db::create_ticket_request(&pool, ann.id, GamePk(700201), 1, None)
+        .await
+        .unwrap();
+    let mine = gtm_db::list_promotion_interests_for_user(&pool, ann.id, "2026-03-01")
+        .await
+        .unwrap();
+    assert_eq!((mine[0].suggest_request, mine[0].has_request), (0, 1));
+
+    let counts = gtm_db::promotion_interest_counts(&pool).await.unwrap();
+    assert_eq!(counts.len(), 1);
+    assert_eq!((counts[0].interested, counts[0].requested), (2, 1));
+
+    // No seats left: Bob isn't nudged toward a request that can't be filled
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700201))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[0].id, ann.id, None)
+        .await
+        .unwrap();
+    let bobs = gtm_db::list_promotion_interests_for_user(&pool, bob.id, "2026-03-01")
+        .await
+        .unwrap();
+    assert_eq!(bobs[0].suggest_request, 0);
+
+    assert!(
+        gtm_db::remove_promotion_interest(&pool, bob.id, 21, GamePk(700201))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !gtm_db::remove_promotion_interest(&pool, bob.id, 21, GamePk(700201))
+            .await
+            .unwrap()
+    );
+}
+
+// --- Auto Allocation ---
+
+#[tokio::test]
+async fn commit_allocation_assigns_and_approves_atomically() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700201))
+        .await
+        .unwrap();
+    for n in ["1", "2", "3"] {
+        let seat = gtm_db::add_seat(&pool, "VR313", "C", n, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    let alice = gtm_db::upsert_user(&pool, "auth0|ca1", "ca1@example.com", "Alice")
+        .await
+        .unwrap();
+    let bob = gtm_db::upsert_user(&pool, "auth0|ca2", "ca2@example.com", "Bob")
+        .await
+        .unwrap();
+    let req_a = gtm_db::create_ticket_request(&pool, alice.id, GamePk(700201), 2, None)
+        .await
+        .unwrap();
+    let req_b = gtm_db::create_ticket_request(&pool, bob.id, GamePk(700201), 1, None)
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700201))
+        .await
+        .unwrap();
+
+    // Bob's ticket was already taken by an earlier manual assignment: skipped
+    gtm_db::assign_ticket(&pool, tickets[2].id, alice.id, None)
+        .await
+        .unwrap();
+    let mut warnings = gtm_db::Warnings::new();
+    let assigned = gtm_db::commit_allocation(
+        &pool,
+        &[
+            (tickets[0].id, alice.id, Some(req_a.id)),
+            (tickets[1].id, alice.id, Some(req_a.id)),
+            (tickets[2].id, bob.id, Some(req_b.id)),
+        ],
+        None,
+        false,
+        &mut warnings,
+    )
+    .await
+    .unwrap();
+    assert_eq!(assigned, 2);
+    assert_eq!(
+        warnings.into_vec(),
+        vec![format!(
+            "Ticket {} is no longer available; not assigned to user {}",
+            tickets[2].id, bob.id
+        )]
+    );
+
+    let reqs = gtm_db::list_requests_for_game(&pool, GamePk(700201))
+        .await
+        .unwrap();
+    let status = |id| reqs.iter().find(|r| r.id == id).unwrap().status.clone();
+    assert_eq!(status(req_a.id), "approved");
+    assert_eq!(status(req_b.id), "pending");
+
+    let counts = gtm_db::assigned_seat_counts_for_season(&pool, "2026")
+        .await
+        .unwrap();
+    assert_eq!(counts, vec![(alice.id, 3)]);
+}
+
+#[tokio::test]
+async fn responded_at_drives_pending_list_and_report() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700301))
+        .await
+        .unwrap();
+    let alice = gtm_db::upsert_user(&pool, "auth0|rt1", "rt1@example.com", "Alice")
+        .await
+        .unwrap();
+    let bob = gtm_db::upsert_user(&pool, "auth0|rt2", "rt2@example.com", "Bob")
+        .await
+        .unwrap();
+    let req_a = gtm_db::create_ticket_request(&pool, alice.id, GamePk(700301), 2, None)
+        .await
+        .unwrap();
+    let req_b = gtm_db::create_ticket_request(&pool, bob.id, GamePk(700301), 1, None)
+        .await
+        .unwrap();
+
+    // Backdate Bob's request so it sorts first and has a known response time
+    sqlx::query("UPDATE ticket_requests SET created_at = '2026-04-01 10:00:00' WHERE id = ?")
+        .bind(req_b.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let pending = gtm_db::list_pending_requests_oldest_first(&pool, None)
+        .await
+        .unwrap();
+    let ids: Vec<i64> = pending.iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec![req_b.id, req_a.id]);
+    assert!(gtm_db::parse_timestamp(&pending[0].created_at).is_some());
+
+    // Responding stamps responded_at once; a later status change keeps it
+    assert!(
+        gtm_db::update_request_approval(&pool, req_b.id, "approved", None)
+            .await
+            .unwrap()
+    );
+    sqlx::query("UPDATE ticket_requests SET responded_at = '2026-04-02 16:00:00' WHERE id = ?")
+        .bind(req_b.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    gtm_db::update_request_approval(&pool, req_b.id, "declined", None)
+        .await
+        .unwrap();
+
+    let pending = gtm_db::list_pending_requests_oldest_first(&pool, None)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+
+    let report = gtm_db::response_time_report(&pool).await.unwrap();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].month, "2026-04");
+    assert_eq!(report[0].responded, 1);
+    assert_eq!(report[0].median_hours, 30.0);
+}
+
+#[tokio::test]
+async fn unassigned_games_skip_requested_and_out_of_window() {
+    let pool = test_pool().await;
+    for (pk, date) in [
+        (700401, "2026-06-01"),
+        (700402, "2026-06-02"),
+        (700403, "2026-06-20"),
+    ] {
+        let mut game = sample_game(pk);
+        game.official_date = date.to_string();
+        game.game_date = format!("{date}T20:00:00Z");
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+    }
+    for n in ["1", "2"] {
+        let seat = gtm_db::add_seat(&pool, "VR313", "D", n, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    let alice = gtm_db::upsert_user(&pool, "auth0|ua1", "ua1@example.com", "Alice")
+        .await
+        .unwrap();
+    // 700402 has a pending request, so someone is already on it
+    gtm_db::create_ticket_request(&pool, alice.id, GamePk(700402), 1, None)
+        .await
+        .unwrap();
+    // One of 700401's seats is already assigned
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700401))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[0].id, alice.id, None)
+        .await
+        .unwrap();
+
+    let games = gtm_db::list_unassigned_games(&pool, TEAM_ID, "2026-06-01", "2026-06-04")
+        .await
+        .unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].game_pk, GamePk(700401));
+    assert_eq!(games[0].available, 1);
+}
+
+#[tokio::test]
+async fn ticket_mutations_are_audited_with_actor() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700501))
+        .await
+        .unwrap();
+    let seat = gtm_db::add_seat(&pool, "VR313", "E", "3", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let admin = gtm_db::upsert_user(&pool, "auth0|au1", "au1@example.com", "Admin")
+        .await
+        .unwrap();
+    let member = gtm_db::upsert_user(&pool, "auth0|au2", "au2@example.com", "Member")
+        .await
+        .unwrap();
+    let ticket = gtm_db::list_tickets_for_game(&pool, GamePk(700501))
+        .await
+        .unwrap()[0]
+        .clone();
+
+    gtm_db::assign_ticket(&pool, ticket.id, member.id, Some(admin.id))
+        .await
+        .unwrap();
+    gtm_db::release_tickets_for_game(&pool, GamePk(700501), member.id, Some(member.id))
+        .await
+        .unwrap();
+    // A revoke that finds nothing to revoke leaves no trace
+    gtm_db::revoke_ticket(&pool, ticket.id, Some(admin.id))
+        .await
+        .unwrap();
+
+    let entries = gtm_db::audit::list(&pool, Some(GamePk(700501)), None)
+        .await
+        .unwrap();
+    let actions: Vec<&str> = entries.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(actions, vec!["release", "assign"]);
+
+    let assign = &entries[1];
+    assert_eq!(assign.actor_id, Some(admin.id));
+    assert_eq!(assign.user_id, Some(member.id));
+    assert_eq!(assign.seat.as_deref(), Some("3"));
+    assert_eq!(assign.before_value.as_deref(), Some("available"));
+    assert_eq!(assign.after_value.as_deref(), Some("assigned"));
+
+    // Filtering by user matches either side of the change
+    let by_admin = gtm_db::audit::list(&pool, None, Some(admin.id))
+        .await
+        .unwrap();
+    assert_eq!(by_admin.len(), 1);
+    let by_member = gtm_db::audit::list(&pool, None, Some(member.id))
+        .await
+        .unwrap();
+    assert_eq!(by_member.len(), 2);
+}
+
+#[tokio::test]
+async fn release_prompts_answer_once_and_count_in_fairness() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700601))
+        .await
+        .unwrap();
+    let alice = gtm_db::upsert_user(&pool, "auth0|wr1", "wr1@example.com", "Alice")
+        .await
+        .unwrap();
+    let seat = gtm_db::add_seat(&pool, "VR313", "F", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ticket = gtm_db::list_tickets_for_game(&pool, GamePk(700601))
+        .await
+        .unwrap()[0]
+        .clone();
+    gtm_db::assign_ticket(&pool, ticket.id, alice.id, None)
+        .await
+        .unwrap();
+
+    assert!(
+        gtm_db::create_release_prompt(&pool, GamePk(700601), alice.id, 80, "tok-a")
+            .await
+            .unwrap()
+    );
+    // Only one prompt per member per game
+    assert!(
+        !gtm_db::create_release_prompt(&pool, GamePk(700601), alice.id, 90, "tok-b")
+            .await
+            .unwrap()
+    );
+
+    assert_eq!(
+        gtm_db::list_unanswered_release_prompts(&pool, GamePk(700601))
+            .await
+            .unwrap(),
+        vec![alice.id]
+    );
+
+    // Answering "release" gives the seats back in the same transaction
+    assert_eq!(
+        gtm_db::respond_release_prompt(&pool, "tok-a", "release")
+            .await
+            .unwrap(),
+        Some(1)
+    );
+    assert_eq!(
+        gtm_db::respond_release_prompt(&pool, "tok-a", "keep")
+            .await
+            .unwrap(),
+        None
+    );
+    let ticket = gtm_db::get_ticket(&pool, ticket.id).await.unwrap().unwrap();
+    assert_eq!(ticket.assigned_to, None);
+    assert!(
+        gtm_db::list_unanswered_release_prompts(&pool, GamePk(700601))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let prompt = gtm_db::get_release_prompt(&pool, "tok-a")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(prompt.response.as_deref(), Some("release"));
+    assert_eq!(prompt.rain_probability, 80);
+
+    let report = gtm_db::fairness_report(&pool, "2026").await.unwrap();
+    let row = report.iter().find(|r| r.user_id == alice.id).unwrap();
+    assert_eq!(row.weather_releases, 1);
+    assert_eq!(row.seats_assigned, 0);
+}
+
+#[tokio::test]
+async fn google_link_and_calendar_sync_state() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700701))
+        .await
+        .unwrap();
+    let alice = gtm_db::upsert_user(&pool, "auth0|gc1", "gc1@example.com", "Alice")
+        .await
+        .unwrap();
+
+    // OAuth state is single-use and expires
+    gtm_db::create_oauth_state(&pool, "st-1", alice.id, "2026-06-01T12:15:00Z")
+        .await
+        .unwrap();
+    gtm_db::create_oauth_state(&pool, "st-2", alice.id, "2026-06-01T12:15:00Z")
+        .await
+        .unwrap();
+    assert_eq!(
+        gtm_db::take_oauth_state(&pool, "st-1", "2026-06-01T12:00:00Z")
+            .await
+            .unwrap(),
+        Some(alice.id)
+    );
+    assert_eq!(
+        gtm_db::take_oauth_state(&pool, "st-1", "2026-06-01T12:00:00Z")
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        gtm_db::take_oauth_state(&pool, "st-2", "2026-06-01T12:30:00Z")
+            .await
+            .unwrap(),
+        None
+    );
+
+    gtm_db::upsert_google_account(&pool, alice.id, "refresh", "access", "2026-06-01T13:00:00Z")
+        .await
+        .unwrap();
+    let account = gtm_db::get_google_account(&pool, alice.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.calendar_id, "primary");
+    assert_eq!(
+        gtm_db::list_google_linked_user_ids(&pool).await.unwrap(),
+        vec![alice.id]
+    );
+
+    gtm_db::upsert_calendar_event(&pool, alice.id, GamePk(700701), "evt-1", "hash-a")
+        .await
+        .unwrap();
+    gtm_db::upsert_calendar_event(&pool, alice.id, GamePk(700701), "evt-1", "hash-b")
+        .await
+        .unwrap();
+    let events = gtm_db::list_calendar_events(&pool, alice.id).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].content_hash, "hash-b");
+
+    // Unlinking forgets both the tokens and the sync state
+    assert!(
+        gtm_db::delete_google_account(&pool, alice.id)
+            .await
+            .unwrap()
+    );
+    assert!(
+        gtm_db::list_calendar_events(&pool, alice.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+// --- Bulk Requests ---
+
+#[tokio::test]
+async fn bulk_requests_cover_matching_upcoming_home_games() {
+    let pool = test_pool().await;
+    let game = |pk, date: &str| {
+        let mut g = sample_game(pk);
+        g.official_date = date.to_string();
+        g.game_date = format!("{date}T20:05:00Z");
+        g
+    };
+    gtm_db::upsert_game(&pool, &game(700901, "2026-07-25")) // Sat, past
+        .await
+        .unwrap();
+    gtm_db::upsert_game(&pool, &game(700902, "2026-08-01")) // Sat
+        .await
+        .unwrap();
+    gtm_db::upsert_game(&pool, &game(700903, "2026-08-02")) // Sun
+        .await
+        .unwrap();
+    gtm_db::upsert_game(&pool, &game(700904, "2026-08-04")) // Tue
+        .await
+        .unwrap();
+    let mut away = game(700905, "2026-08-08"); // Sat, away
+    away.home_team_id = 119;
+    gtm_db::upsert_game(&pool, &away).await.unwrap();
+    let seat = gtm_db::add_seat(&pool, "VR315", "B", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    gtm_db::upsert_game(&pool, &game(700906, "2026-08-15")) // Sat, no tickets yet
+        .await
+        .unwrap();
+    let user = gtm_db::upsert_user(&pool, "auth0|bulk", "bulk@example.com", "Bulk")
+        .await
+        .unwrap();
+    gtm_db::create_ticket_request(&pool, user.id, GamePk(700903), 1, None)
+        .await
+        .unwrap();
+
+    let filter = gtm_db::GameFilter {
+        weekend: Some(true),
+        team_id: TEAM_ID,
+        ..Default::default()
+    };
+    let result = gtm_db::create_requests_for_games(
+        &pool,
+        user.id,
+        &filter,
+        2,
+        false,
+        Some("standing"),
+        "2026-07-28",
+    )
+    .await
+    .unwrap();
+    let created: Vec<(GamePk, i64)> = result
+        .created
+        .iter()
+        .map(|r| (r.game_pk, r.seats_requested))
+        .collect();
+    assert_eq!(created, vec![(GamePk(700902), 2)]);
+    assert_eq!(result.created[0].notes.as_deref(), Some("standing"));
+    let skipped: Vec<(GamePk, &str)> = result
+        .skipped
+        .iter()
+        .map(|s| (s.game_pk, s.reason.as_str()))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            (GamePk(700903), "Already requested"),
+            (GamePk(700906), "No tickets for this game"),
+        ]
+    );
+
+    // Running it again creates nothing new
+    let again =
+        gtm_db::create_requests_for_games(&pool, user.id, &filter, 2, false, None, "2026-07-28")
+            .await
+            .unwrap();
+    assert!(again.created.is_empty());
+    assert_eq!(again.skipped.len(), 3);
+
+    // Too many seats skips the game, unless capped at its limit
+    let other = gtm_db::upsert_user(&pool, "auth0|bulk2", "bulk2@example.com", "Bulk Two")
+        .await
+        .unwrap();
+    let over =
+        gtm_db::create_requests_for_games(&pool, other.id, &filter, 99, false, None, "2026-07-28")
+            .await
+            .unwrap();
+    assert!(over.created.is_empty());
+    assert!(over.skipped[0].reason.starts_with("At most"), "{over:?}");
+    let capped =
+        gtm_db::create_requests_for_games(&pool, other.id, &filter, 99, true, None, "2026-07-28")
+            .await
+            .unwrap();
+    assert_eq!(capped.created.len(), 2);
+    assert!(capped.created.iter().all(|r| r.seats_requested < 99));
+}
+
+// --- Notification Queue ---
+
+#[tokio::test]
+async fn availability_calendar_lays_out_every_day_of_the_month() {
+    let pool = test_pool().await;
+    let game = |pk, date: &str, game_number| {
+        let mut g = sample_game(pk);
+        g.official_date = date.to_string();
+        g.game_date = format!("{date}T{}:05:00Z", 18 + game_number);
+        g.game_number = game_number;
+        g
+    };
+    gtm_db::upsert_game(&pool, &game(701001, "2026-07-04", 1))
+        .await
+        .unwrap();
+    gtm_db::upsert_game(&pool, &game(701002, "2026-07-04", 2))
+        .await
+        .unwrap();
+    gtm_db::upsert_game(&pool, &game(701003, "2026-08-01", 1))
+        .await
+        .unwrap();
+    for n in ["1", "2", "3"] {
+        let seat = gtm_db::add_seat(&pool, "VR316", "C", n, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    let alice = gtm_db::upsert_user(&pool, "auth0|cal1", "cal1@example.com", "Alice")
+        .await
+        .unwrap();
+    let bob = gtm_db::upsert_user(&pool, "auth0|cal2", "cal2@example.com", "Bob")
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(701001))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[0].id, alice.id, None)
+        .await
+        .unwrap();
+    gtm_db::create_ticket_request(&pool, alice.id, GamePk(701002), 1, None)
+        .await
+        .unwrap();
+    gtm_db::create_ticket_request(&pool, bob.id, GamePk(701002), 2, None)
+        .await
+        .unwrap();
+    gtm_db::upsert_game_tag(&pool, alice.id, GamePk(701002), true, false)
+        .await
+        .unwrap();
+
+    let july = gtm_db::calendar::month(&pool, TEAM_ID, alice.id, "2026", 7)
+        .await
+        .unwrap();
+    assert_eq!(july.days.len(), 31);
+    assert_eq!(july.days[0].date, "2026-07-01");
+    assert_eq!(july.days[30].date, "2026-07-31");
+    assert_eq!(
+        july.days.iter().map(|d| d.games.len()).sum::<usize>(),
+        2,
+        "August's game stays out"
+    );
+    let games = &july.days[3].games;
+    assert_eq!(games.len(), 2);
+
+    let first = &games[0];
+    assert_eq!(first.game_pk, GamePk(701001));
+    assert_eq!((first.total, first.available, first.assigned), (3, 2, 1));
+    assert_eq!(first.claimable, 2);
+    assert_eq!(first.my_seats, 1);
+    assert_eq!(first.my_request_status, None);
+
+    // Three seats, three requested: nothing left to claim
+    let second = &games[1];
+    assert_eq!(second.claimable, 0);
+    assert_eq!(second.my_seats, 0);
+    assert_eq!(second.my_request_status.as_deref(), Some("pending"));
+    assert_eq!(second.my_seats_requested, Some(1));
+    assert_eq!((second.shortlist, second.cant_go), (1, 0));
+
+    // Bob sees the same counts and his own standing
+    let bobs = gtm_db::calendar::month(&pool, TEAM_ID, bob.id, "2026", 7)
+        .await
+        .unwrap();
+    let second = &bobs.days[3].games[1];
+    assert_eq!(second.my_seats_requested, Some(2));
+    assert_eq!(second.shortlist, 0);
+
+    assert!(
+        gtm_db::calendar::month(&pool, TEAM_ID, bob.id, "2026", 13)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn notification_queue_retries_then_gives_up() {
+    use gtm_db::notifications::{self, NewNotification};
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700801))
+        .await
+        .unwrap();
+    let user = gtm_db::upsert_user(&pool, "auth0|nq", "nq@example.com", "Queue")
+        .await
+        .unwrap();
+    let new = |kind| NewNotification {
+        user_id: Some(user.id),
+        game_pk: Some(GamePk(700801)),
+        kind,
+        channel: notifications::EMAIL,
+        recipient: "nq@example.com",
+        subject: "subject",
+        body: "body",
+    };
+
+    let assigned = notifications::enqueue(&pool, &new(notifications::TICKETS_ASSIGNED))
+        .await
+        .unwrap();
+    let full = notifications::enqueue(&pool, &new(notifications::GAME_FULLY_ALLOCATED))
+        .await
+        .unwrap();
+    let pending = notifications::list_pending(&pool, 10).await.unwrap();
+    assert_eq!(
+        pending.iter().map(|n| n.id).collect::<Vec<_>>(),
+        vec![assigned, full]
+    );
+    assert!(
+        notifications::already_queued(
+            &pool,
+            user.id,
+            GamePk(700801),
+            notifications::GAME_FULLY_ALLOCATED
+        )
+        .await
+        .unwrap()
+    );
+
+    notifications::mark_sent(&pool, assigned).await.unwrap();
+    for _ in 0..notifications::MAX_ATTEMPTS - 1 {
+        notifications::mark_failed(&pool, full, "smtp down")
+            .await
+            .unwrap();
+    }
+    let pending = notifications::list_pending(&pool, 10).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].attempts, notifications::MAX_ATTEMPTS - 1);
+
+    // The last failure marks it failed, which frees the game/kind for a retry
+    notifications::mark_failed(&pool, full, "smtp down")
+        .await
+        .unwrap();
+    assert!(
+        notifications::list_pending(&pool, 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        !notifications::already_queued(
+            &pool,
+            user.id,
+            GamePk(700801),
+            notifications::GAME_FULLY_ALLOCATED
+        )
+        .await
+        .unwrap()
+    );
+}
+
+// --- Allocation Batches ---
+
+#[tokio::test]
+async fn allocation_batches_keep_their_snapshot() {
+    use gtm_db::allocation_batches::{self, NewBatch};
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700821))
+        .await
+        .unwrap();
+    let admin = gtm_db::upsert_user(&pool, "auth0|ab", "ab@example.com", "Batcher")
+        .await
+        .unwrap();
+    let snapshot = serde_json::json!({
+        "request": { "assignments": [{ "game_ticket_id": 1, "user_id": admin.id.0 }] },
+        "proposal": null,
+        "assignments": [{ "game_ticket_id": 1, "assigned": true }],
+        "warnings": [],
+    });
+    let manual = allocation_batches::record(
+        &pool,
+        &NewBatch {
+            actor_id: Some(admin.id),
+            kind: allocation_batches::MANUAL,
+            game_pk: Some(GamePk(700821)),
+            assigned: 1,
+            snapshot: &snapshot,
+        },
+    )
+    .await
+    .unwrap();
+    let spanning = allocation_batches::record(
+        &pool,
+        &NewBatch {
+            actor_id: None,
+            kind: allocation_batches::AUTO,
+            game_pk: None,
+            assigned: 0,
+            snapshot: &serde_json::json!({}),
+        },
+    )
+    .await
+    .unwrap();
+
+    let all = allocation_batches::list(&pool, None).await.unwrap();
+    assert_eq!(
+        all.iter().map(|b| b.id).collect::<Vec<_>>(),
+        vec![spanning, manual]
+    );
+    let for_game = allocation_batches::list(&pool, Some(GamePk(700821)))
+        .await
+        .unwrap();
+    assert_eq!(for_game.len(), 1);
+    assert_eq!(for_game[0].kind, "manual");
+    assert_eq!(for_game[0].actor_id, Some(admin.id));
+
+    let (batch, stored) = allocation_batches::get(&pool, manual)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(batch.assigned, 1);
+    assert_eq!(stored, snapshot);
+    assert!(
+        allocation_batches::get(&pool, 9999)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn allocation_batches_are_recorded_with_their_assignments() {
+    use gtm_db::allocation_batches::{self, Draft};
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700822))
+        .await
+        .unwrap();
+    let seat = gtm_db::add_seat(&pool, "VR314", "A", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ticket = gtm_db::list_tickets_for_game(&pool, GamePk(700822))
+        .await
+        .unwrap()[0]
+        .id;
+    let member = gtm_db::upsert_user(&pool, "auth0|ac", "ac@example.com", "Committer")
+        .await
+        .unwrap();
+    let req = gtm_db::create_ticket_request(&pool, member.id, GamePk(700822), 1, None)
+        .await
+        .unwrap();
+
+    let draft = Draft {
+        kind: allocation_batches::MANUAL,
+        game_pk: None,
+        request: serde_json::json!({ "note": "by hand" }),
+        proposal: serde_json::Value::Null,
+    };
+    let mut warnings = gtm_db::Warnings::new();
+    let (assigned, batch_id) = gtm_db::commit_allocation_batch(
+        &pool,
+        &[(ticket, member.id, Some(req.id))],
+        None,
+        false,
+        &draft,
+        &mut warnings,
+    )
+    .await
+    .unwrap();
+    assert_eq!(assigned, 1);
+
+    let (batch, snapshot) = allocation_batches::get(&pool, batch_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(batch.game_pk, Some(GamePk(700822)));
+    assert_eq!(batch.assigned, 1);
+    assert_eq!(snapshot["request"]["note"], "by hand");
+    assert_eq!(snapshot["assignments"][0]["assigned"], true);
+    assert_eq!(snapshot["assignments"][0]["request_id"], req.id);
+}
+
+// --- Inbound Email Tokens ---
+
+#[tokio::test]
+async fn inbound_tokens_are_accepted_once() {
+    use gtm_db::inbound_tokens::{self, TOKEN_TTL_MINUTES};
+
+    let pool = test_pool().await;
+    let now = chrono::Utc::now();
+
+    assert!(inbound_tokens::claim(&pool, "tok-1", now).await.unwrap());
+    assert!(!inbound_tokens::claim(&pool, "tok-1", now).await.unwrap());
+    assert!(inbound_tokens::claim(&pool, "tok-2", now).await.unwrap());
+
+    // Remembered tokens are pruned once their signatures are long stale
+    let later = now + chrono::Duration::minutes(TOKEN_TTL_MINUTES + 1);
+    assert!(inbound_tokens::claim(&pool, "tok-1", later).await.unwrap());
+}
+
+// --- Idempotency Keys ---
+
+#[tokio::test]
+async fn idempotency_keys_replay_the_first_result() {
+    use gtm_db::idempotency::{self, Claim};
+
+    let pool = test_pool().await;
+    let user = gtm_db::upsert_user(&pool, "auth0|idem", "idem@example.com", "Idem")
+        .await
+        .unwrap();
+    let other = gtm_db::upsert_user(&pool, "auth0|idem2", "idem2@example.com", "Other")
+        .await
+        .unwrap();
+    let claim = |user_id, key: &'static str, request: &'static str| {
+        let pool = pool.clone();
+        async move {
+            idempotency::claim(&pool, user_id, "POST /admin/allocate", key, request)
+                .await
+                .unwrap()
+        }
+    };
+
+    assert_eq!(claim(user.id, "k1", "{}").await, Claim::New);
+    assert_eq!(claim(user.id, "k1", "{}").await, Claim::InProgress);
+    assert_eq!(claim(user.id, "k1", r#"{"x":1}"#).await, Claim::Mismatch);
+    // Keys are per member
+    assert_eq!(claim(other.id, "k1", "{}").await, Claim::New);
+
+    idempotency::complete(&pool, user.id, "POST /admin/allocate", "k1", "[3,[]]")
+        .await
+        .unwrap();
+    assert_eq!(
+        claim(user.id, "k1", "{}").await,
+        Claim::Replay("[3,[]]".into())
+    );
+
+    // An abandoned key runs again; a completed one isn't abandoned
+    idempotency::abandon(&pool, other.id, "POST /admin/allocate", "k1")
+        .await
+        .unwrap();
+    assert_eq!(claim(other.id, "k1", "{}").await, Claim::New);
+    idempotency::abandon(&pool, user.id, "POST /admin/allocate", "k1")
+        .await
+        .unwrap();
+    assert!(matches!(claim(user.id, "k1", "{}").await, Claim::Replay(_)));
+
+    assert!(
+        idempotency::claim(&pool, user.id, "POST /my/requests", "", "{}")
+            .await
+            .is_err()
+    );
+}
+
+// --- Webhooks ---
+
+#[tokio::test]
+async fn webhooks_queue_subscribed_events_and_back_off_on_failure() {
+    use gtm_db::webhooks;
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700811))
+        .await
+        .unwrap();
+    let seat = gtm_db::add_seat(&pool, "VR314", "W", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let user = gtm_db::upsert_user(&pool, "auth0|wh", "wh@example.com", "Hooked")
+        .await
+        .unwrap();
+    assert!(
+        webhooks::create(&pool, "ftp://x", &["ticket.assigned".into()], "s", None)
+            .await
+            .is_err()
+    );
+    assert!(
+        webhooks::create(&pool, "https://x", &["ticket.sold".into()], "s", None)
+            .await
+            .is_err()
+    );
+    let tickets = webhooks::create(
+        &pool,
+        "https://hooks.example.com/tickets",
+        &[webhooks::TICKET_ASSIGNED.into()],
+        "secret",
+        Some(user.id),
+    )
+    .await
+    .unwrap();
+    let both = webhooks::create(
+        &pool,
+        "https://hooks.example.com/all",
+        &[
+            webhooks::TICKET_ASSIGNED.into(),
+            webhooks::REQUEST_CREATED.into(),
+        ],
+        "secret",
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(webhooks::list(&pool).await.unwrap().len(), 2);
+
+    gtm_db::create_ticket_request(&pool, user.id, GamePk(700811), 1, None)
+        .await
+        .unwrap();
+    let ticket = gtm_db::list_tickets_for_game(&pool, GamePk(700811))
+        .await
+        .unwrap()[0]
+        .id;
+    gtm_db::assign_ticket(&pool, ticket, user.id, None)
+        .await
+        .unwrap();
+
+    let due = webhooks::list_due(&pool, 10).await.unwrap();
+    let events: Vec<(i64, &str)> = due
+        .iter()
+        .map(|d| (d.webhook_id, d.event.as_str()))
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            (both.id, webhooks::REQUEST_CREATED),
+            (tickets.id, webhooks::TICKET_ASSIGNED),
+            (both.id, webhooks::TICKET_ASSIGNED),
+        ]
+    );
+    let payload: serde_json::Value = serde_json::from_str(&due[1].payload).unwrap();
+    assert_eq!(payload["event"], "ticket.assigned");
+    assert_eq!(payload["data"]["game_pk"], 700811);
+    assert_eq!(payload["data"]["user_id"], user.id.0);
+
+    // A delivered event leaves the queue; a failed one waits for its retry
+    webhooks::mark_delivered(&pool, due[0].id, 200)
+        .await
+        .unwrap();
+    webhooks::mark_failed(&pool, due[1].id, Some(500), "HTTP 500")
+        .await
+        .unwrap();
+    let due_now = webhooks::list_due(&pool, 10).await.unwrap();
+    assert_eq!(
+        due_now.iter().map(|d| d.id).collect::<Vec<_>>(),
+        vec![due[2].id]
+    );
+    let log = webhooks::list_deliveries(&pool, tickets.id, 10)
+        .await
+        .unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].status, "pending");
+    assert_eq!(log[0].attempts, 1);
+    assert_eq!(log[0].response_status, Some(500));
+    let log = webhooks::list_deliveries(&pool, both.id, 10).await.unwrap();
+    assert_eq!(log[1].status, "delivered");
+    assert!(log[1].delivered_at.is_some());
+
+    // Running out of attempts marks the delivery failed
+    for _ in 1..webhooks::MAX_ATTEMPTS {
+        webhooks::mark_failed(&pool, due[1].id, None, "timed out")
+            .await
+            .unwrap();
+    }
+    let log = webhooks::list_deliveries(&pool, tickets.id, 10)
+        .await
+        .unwrap();
+    assert_eq!(log[0].status, "failed");
+    assert_eq!(log[0].attempts, webhooks::MAX_ATTEMPTS);
+
+    // Deleting a webhook takes its log with it
+    assert!(webhooks::delete(&pool, tickets.id).await.unwrap());
+    assert!(!webhooks::delete(&pool, tickets.id).await.unwrap());
+    assert!(
+        webhooks::list_deliveries(&pool, tickets.id, 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // A request whose webhook can't be queued isn't created either
+    sqlx::query("DROP TABLE webhook_deliveries")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let other = gtm_db::upsert_user(&pool, "auth0|wh2", "wh2@example.com", "Other")
+        .await
+        .unwrap();
+    assert!(
+        gtm_db::create_ticket_request(&pool, other.id, GamePk(700811), 1, None)
+            .await
+            .is_err()
+    );
+    assert!(
+        gtm_db::list_requests_for_game(&pool, GamePk(700811))
+            .await
+            .unwrap()
+            .iter()
+            .all(|r| r.user_id != other.id)
+    );
+}
+
+// --- Bulk Confirmations ---
+
+#[tokio::test]
+async fn bulk_confirmation_is_scoped_and_single_use() {
+    let pool = test_pool().await;
+    let expires = "2026-06-01T12:10:00Z";
+    let now = "2026-06-01T12:00:00Z";
+    gtm_db::create_bulk_confirmation(&pool, "tok-1", "seats.delete_group", "VR313/A", 12, expires)
+        .await
+        .unwrap();
+    gtm_db::create_bulk_confirmation(&pool, "tok-2", "seats.delete_group", "VR313/A", 12, expires)
+        .await
+        .unwrap();
+
+    // Wrong scope burns the token without confirming
+    assert_eq!(
+        gtm_db::take_bulk_confirmation(&pool, "tok-1", "seats.delete_group", "VR313/B", now)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        gtm_db::take_bulk_confirmation(&pool, "tok-1", "seats.delete_group", "VR313/A", now)
+            .await
+            .unwrap(),
+        None
+    );
+
+    assert_eq!(
+        gtm_db::take_bulk_confirmation(&pool, "tok-2", "seats.delete_group", "VR313/A", now)
+            .await
+            .unwrap(),
+        Some(12)
+    );
+    assert_eq!(
+        gtm_db::take_bulk_confirmation(&pool, "tok-2", "seats.delete_group", "VR313/A", now)
+            .await
+            .unwrap(),
+        None
+    );
+
+    // Expired
+    gtm_db::create_bulk_confirmation(&pool, "tok-3", "seats.delete_group", "VR313/A", 12, expires)
+        .await
+        .unwrap();
+    assert_eq!(
+        gtm_db::take_bulk_confirmation(
+            &pool,
+            "tok-3",
+            "seats.delete_group",
+            "VR313/A",
+            "2026-06-01T12:30:00Z"
+        )
+        .await
+        .unwrap(),
+        None
+    );
+}
+
+// --- Background Jobs ---
+
+#[tokio::test]
+async fn sqlite_job_leader_is_one_process_per_database_file() {
+    let pool = test_pool().await;
+    let key = gtm_db::leader::JOBS_LOCK_KEY;
+    // An in-memory database belongs to this process alone
+    for _ in 0..2 {
+        assert!(
+            gtm_db::leader::try_acquire(&pool, "sqlite::memory:", key)
+                .await
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    let path = std::env::temp_dir().join(format!("gtm-leader-{}.db", std::process::id()));
+    let url = format!("sqlite:{}?mode=rwc", path.display());
+    let mut first = gtm_db::leader::try_acquire(&pool, &url, key)
+        .await
+        .unwrap()
+        .expect("no one else leads yet");
+    assert!(first.is_held().await);
+    // A second worker on the same file stands by until the first goes
+    assert!(
+        gtm_db::leader::try_acquire(&pool, &url, key)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    drop(first);
+    assert!(
+        gtm_db::leader::try_acquire(&pool, &url, key)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn job_leader_retries_jobs_that_fail_to_start() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    let pool = test_pool().await;
+    let path = std::env::temp_dir().join(format!("gtm-lead-{}.db", std::process::id()));
+    let url = format!("sqlite:{}?mode=rwc", path.display());
+    let key = gtm_db::leader::JOBS_LOCK_KEY;
+    let check = Duration::from_millis(10);
+    let starts = AtomicU32::new(0);
+    let leading = gtm_db::leader::lead(&pool, &url, key, check, || {
+        if starts.fetch_add(1, Ordering::SeqCst) == 0 {
+            anyhow::bail!("bad scrape_cron");
+        }
+        Ok(vec![tokio::spawn(async {})])
+    });
+    // Leading never returns; stop watching once the retry has had time to run
+    let _ = tokio::time::timeout(Duration::from_millis(200), leading).await;
+    assert_eq!(starts.load(Ordering::SeqCst), 2);
+
+    // A standby never starts its jobs while another process leads
+    let held = gtm_db::leader::try_acquire(&pool, &url, key)
+        .await
+        .unwrap()
+        .unwrap();
+    let standby_starts = AtomicU32::new(0);
+    let standby = gtm_db::leader::lead(&pool, &url, key, check, || {
+        standby_starts.fetch_add(1, Ordering::SeqCst);
+        Ok(Vec::new())
+    });
+    let _ = tokio::time::timeout(Duration::from_millis(100), standby).await;
+    assert_eq!(standby_starts.load(Ordering::SeqCst), 0);
+    drop(held);
+}
+
+#[tokio::test]
+async fn with_lock_runs_once_and_recovers_stale_locks() {
+    use std::time::Duration;
+
+    let pool = test_pool().await;
+    let ttl = Duration::from_secs(60);
+    let outer = gtm_db::with_lock(&pool, "nightly", ttl, || async {
+        // A second caller is turned away while the lock is held...
+        let inner = gtm_db::with_lock(&pool, "nightly", ttl, || async { Ok(2) }).await?;
+        assert_eq!(inner, None);
+        // ...but other lock names are independent
+        let other = gtm_db::with_lock(&pool, "backup", ttl, || async { Ok(3) }).await?;
+        assert_eq!(other, Some(3));
+        Ok(1)
+    })
+    .await
+    .unwrap();
+    assert_eq!(outer, Some(1));
+
+    // Released afterwards, even when the job fails
+    let failed = gtm_db::with_lock(&pool, "nightly", ttl, || async {
+        Err::<(), _>(anyhow::anyhow!("job failed"))
+    })
+    .await;
+    assert!(failed.is_err());
+    assert_eq!(
+        gtm_db::with_lock(&pool, "nightly", ttl, || async { Ok(4) })
+            .await
+            .unwrap(),
+        Some(4)
+    );
+
+    // A lock left behind by a crashed process is taken over once expired
+    sqlx::query(
+        "INSERT INTO job_locks (name, holder, expires_at) VALUES ('sweep', 'dead', '2000-01-01T00:00:00Z')",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        gtm_db::with_lock(&pool, "sweep", ttl, || async { Ok(5) })
+            .await
+            .unwrap(),
+        Some(5)
+    );
+}
+
+#[tokio::test]
+async fn with_lock_stops_a_job_that_outlives_its_ttl() {
+    use std::time::Duration;
+
+    let pool = test_pool().await;
+    let stuck = gtm_db::with_lock(&pool, "stuck", Duration::from_millis(50), || async {
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        Ok(())
+    })
+    .await;
+    assert!(stuck.unwrap_err().to_string().contains("lock TTL"));
+
+    // The lock was let go, so the next run isn't turned away
+    let next = gtm_db::with_lock(&pool, "stuck", Duration::from_secs(60), || async { Ok(1) })
+        .await
+        .unwrap();
+    assert_eq!(next, Some(1));
+}
+
+// --- Export ---
+
+#[tokio::test]
+async fn season_export_renders_tickets_and_allocation_grid() {
+    use gtm_db::export;
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700301))
+        .await
+        .unwrap();
+    for n in ["1", "2"] {
+        let seat = gtm_db::add_seat(&pool, "VR313", "C", n, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    gtm_db::update_seat_group_face_value(&pool, "VR313", "C", Some(4250))
+        .await
+        .unwrap();
+    let user = gtm_db::upsert_user(&pool, "auth0|ex1", "ex@example.com", "Mays, Willie")
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700301))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[0].id, user.id, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        export::export_games(&pool, "2026").await.unwrap(),
+        vec![GamePk(700301)]
+    );
+    assert!(
+        export::export_games(&pool, "2025")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let rows = export::ticket_rows(&pool, GamePk(700301)).await.unwrap();
+    assert_eq!(
+        export::tickets_csv(&rows),
+        "2026-04-01,2026-04-01T19:15:00Z,Arizona Diamondbacks,700301,VR313,C,1,assigned,\
+         \"Mays, Willie\",ex@example.com,42.50,\r\n\
+         2026-04-01,2026-04-01T19:15:00Z,Arizona Diamondbacks,700301,VR313,C,2,available,,,42.50,\r\n"
+    );
+
+    let seats = export::seat_labels(&pool).await.unwrap();
+    assert_eq!(
+        export::allocation_csv_header(&seats),
+        "date,opponent,game_pk,VR313/C/1,VR313/C/2\r\n"
+    );
+    assert_eq!(
+        export::allocation_csv(&seats, &rows),
+        "2026-04-01,Arizona Diamondbacks,700301,\"Mays, Willie\",available\r\n"
+    );
+}
+
+#[test]
+fn export_cells_are_safe_for_spreadsheets() {
+    use gtm_db::export;
+
+    assert_eq!(
+        export::csv_field("=HYPERLINK(\"x\")"),
+        "\"'=HYPERLINK(\"\"x\"\")\""
+    );
+    assert_eq!(export::csv_field("+1 call me"), "'+1 call me");
+    assert_eq!(export::csv_field("@SUM(A1)"), "'@SUM(A1)");
+    assert_eq!(export::csv_field("-12.50"), "-12.50");
+    assert_eq!(export::csv_field("Aisle seat"), "Aisle seat");
+
+    let row = gtm_models::TicketExportRow {
+        ticket_id: gtm_models::TicketId(1),
+        game_pk: GamePk(700302),
+        official_date: "2026-04-01".into(),
+        game_date: "2026-04-01T19:15:00Z".into(),
+        away_team_name: "Arizona Diamondbacks".into(),
+        section: "VR313".into(),
+        row: "C".into(),
+        seat: "1".into(),
+        status: "available".into(),
+        assignee_name: None,
+        assignee_email: None,
+        cost: Some(-50),
+        notes: Some("-cancel".into()),
+    };
+    let mut refund = row.clone();
+    refund.cost = Some(-199);
+    assert_eq!(
+        export::tickets_csv(&[row, refund]),
+        "2026-04-01,2026-04-01T19:15:00Z,Arizona Diamondbacks,700302,VR313,C,1,available,,,-0.50,'-cancel\r\n\
+         2026-04-01,2026-04-01T19:15:00Z,Arizona Diamondbacks,700302,VR313,C,1,available,,,-1.99,'-cancel\r\n"
+    );
+}
+
+#[tokio::test]
+async fn fairness_timeline_accumulates_seats_by_week() {
+    let pool = test_pool().await;
+    // Weeks of Mon 2026-05-04, (none), and Mon 2026-05-18
+    let dates = ["2026-05-05", "2026-05-10", "2026-05-20"];
+    for (i, date) in dates.iter().enumerate() {
+        let mut game = sample_game(700501 + i as i64);
+        game.official_date = date.to_string();
+        game.game_date = format!("{date}T19:15:00Z");
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+    }
+    let seat = gtm_db::add_seat(&pool, "VR315", "E", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ann = gtm_db::upsert_user(&pool, "auth0|ft1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bo = gtm_db::upsert_user(&pool, "auth0|ft2", "bo@example.com", "Bo")
+        .await
+        .unwrap();
+    for (pk, user) in [(700501, ann.id), (700502, ann.id), (700503, bo.id)] {
+        let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(pk))
+            .await
+            .unwrap();
+        gtm_db::assign_ticket(&pool, tickets[0].id, user, None)
+            .await
+            .unwrap();
+    }
+
+    let timeline = gtm_db::reports::fairness_timeline(&pool, "2026")
+        .await
+        .unwrap();
+    assert_eq!(
+        timeline.weeks,
+        vec!["2026-05-04", "2026-05-11", "2026-05-18"]
+    );
+    let lines: Vec<(&str, &[i64])> = timeline
+        .members
+        .iter()
+        .map(|m| (m.user_name.as_str(), m.cumulative.as_slice()))
+        .collect();
+    assert_eq!(lines, vec![("Ann", &[2, 2, 2][..]), ("Bo", &[0, 0, 1][..])]);
+
+    let empty = gtm_db::reports::fairness_timeline(&pool, "2025")
+        .await
+        .unwrap();
+    assert!(empty.weeks.is_empty());
+    assert!(empty.members.iter().all(|m| m.cumulative.is_empty()));
+}
+
+#[tokio::test]
+async fn allocation_rounds_group_a_season_by_game_in_date_order() {
+    let pool = test_pool().await;
+    // Inserted out of date order; the last game has no tickets
+    for (pk, date) in [(700602, "2026-06-09"), (700601, "2026-06-02")] {
+        let mut game = sample_game(pk);
+        game.official_date = date.to_string();
+        game.game_date = format!("{date}T19:15:00Z");
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+    }
+    gtm_db::add_seat(&pool, "VR316", "A", "1", None)
+        .await
+        .unwrap();
+    gtm_db::add_seat(&pool, "VR316", "A", "2", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let mut late = sample_game(700603);
+    late.official_date = "2026-06-16".to_string();
+    late.game_date = "2026-06-16T19:15:00Z".to_string();
+    gtm_db::upsert_game(&pool, &late).await.unwrap();
+
+    let ann = gtm_db::upsert_user(&pool, "auth0|ar1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    for pk in [700601, 700602, 700603] {
+        gtm_db::create_ticket_request(&pool, ann.id, GamePk(pk), 2, None)
+            .await
+            .unwrap();
+    }
+
+    let rounds = gtm_db::reports::allocation_rounds(&pool, "2026")
+        .await
+        .unwrap();
+    let shape: Vec<(i64, usize, usize)> = rounds
+        .iter()
+        .map(|r| (r.game_pk.0, r.tickets.len(), r.requests.len()))
+        .collect();
+    assert_eq!(shape, vec![(700601, 2, 1), (700602, 2, 1)]);
+    assert!(
+        gtm_db::reports::allocation_rounds(&pool, "2025")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn transparency_ranks_members_and_hides_other_names() {
+    let pool = test_pool().await;
+    for pk in [700701, 700702] {
+        gtm_db::upsert_game(&pool, &sample_game(pk)).await.unwrap();
+    }
+    gtm_db::seat_limits::set_game_tags(&pool, GamePk(700702), &["Premium".to_string()])
+        .await
+        .unwrap();
+    for seat in ["1", "2"] {
+        gtm_db::add_seat(&pool, "VR317", "B", seat, None)
+            .await
+            .unwrap();
+    }
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ann = gtm_db::upsert_user(&pool, "auth0|tp1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bo = gtm_db::upsert_user(&pool, "auth0|tp2", "bo@example.com", "Bo")
+        .await
+        .unwrap();
+    gtm_db::upsert_user(&pool, "auth0|tp3", "cy@example.com", "Cy")
+        .await
+        .unwrap();
+
+    // Ann gets both seats at the premium game; Bo is declined there
+    let premium = gtm_db::list_tickets_for_game(&pool, GamePk(700702))
+        .await
+        .unwrap();
+    for t in &premium {
+        gtm_db::assign_ticket(&pool, t.id, ann.id, None)
+            .await
+            .unwrap();
+    }
+    let req = gtm_db::create_ticket_request(&pool, bo.id, GamePk(700702), 2, None)
+        .await
+        .unwrap();
+    gtm_db::update_request_approval(&pool, req.id, "declined", None)
+        .await
+        .unwrap();
+
+    let report = gtm_db::reports::transparency(&pool, "2026", bo.id, false)
+        .await
+        .unwrap();
+    let rows: Vec<(&str, bool, i64, i64, i64, i64)> = report
+        .members
+        .iter()
+        .map(|m| {
+            (
+                m.name.as_str(),
+                m.you,
+                m.seats_received,
+                m.premium_games,
+                m.requests,
+                m.requests_denied,
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        vec![("Member 1", false, 2, 1, 0, 0), ("Bo", true, 0, 0, 1, 1),]
+    );
+    assert_eq!(report.members[0].user_id, None);
+    assert_eq!(report.members[1].user_id, Some(bo.id));
+
+    let named = gtm_db::reports::transparency(&pool, "2026", bo.id, true)
+        .await
+        .unwrap();
+    assert_eq!(named.members[0].name, "Ann");
+    assert_eq!(named.members[0].games_received, 1);
+}
+
+#[tokio::test]
+async fn history_snapshot_undoes_audited_changes_after_the_cutoff() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700801))
+        .await
+        .unwrap();
+    for seat in ["1", "2"] {
+        gtm_db::add_seat(&pool, "VR318", "C", seat, None)
+            .await
+            .unwrap();
+    }
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let mut users = Vec::new();
+    for name in ["Ann", "Bo", "Cy", "Dee", "Eve"] {
+        let sub = format!("auth0|hist-{name}");
+        let email = format!("{name}@example.com");
+        users.push(
+            gtm_db::upsert_user(&pool, &sub, &email, name)
+                .await
+                .unwrap(),
+        );
+    }
+    let [ann, bo, cy, dee, eve] = &users[..] else {
+        unreachable!()
+    };
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700801))
+        .await
+        .unwrap();
+    let stamp = |sql: String| {
+        let pool = pool.clone();
+        async move { sqlx::query(&sql).execute(&pool).await.unwrap() }
+    };
+    stamp("UPDATE game_tickets SET created_at = '2026-05-01 12:00:00'".into()).await;
+
+    // Before the cutoff: Ann gets seat 1 and Dee asks for two
+    gtm_db::assign_ticket(&pool, tickets[0].id, ann.id, None)
+        .await
+        .unwrap();
+    let dee_req = gtm_db::create_ticket_request(&pool, dee.id, GamePk(700801), 2, None)
+        .await
+        .unwrap();
+    stamp("UPDATE audit_log SET created_at = '2026-06-01 12:00:00'".into()).await;
+    stamp("UPDATE ticket_requests SET created_at = '2026-05-20 12:00:00'".into()).await;
+
+    // After: Ann passes it to Bo, Cy gets seat 2, Dee is declined, Eve asks
+    let mut warnings = gtm_db::Warnings::new();
+    gtm_db::transfer_ticket(
+        &pool,
+        GamePk(700801),
+        ann.id,
+        bo.id,
+        None,
+        None,
+        &mut warnings,
+    )
+    .await
+    .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[1].id, cy.id, None)
+        .await
+        .unwrap();
+    gtm_db::update_request_approval(&pool, dee_req.id, "declined", None)
+        .await
+        .unwrap();
+    gtm_db::create_ticket_request(&pool, eve.id, GamePk(700801), 1, None)
+        .await
+        .unwrap();
+    stamp(
+        "UPDATE audit_log SET created_at = '2026-06-05 12:00:00' \
+         WHERE created_at <> '2026-06-01 12:00:00'"
+            .into(),
+    )
+    .await;
+    stamp(format!(
+        "UPDATE ticket_requests SET created_at = '2026-06-10 12:00:00' WHERE user_id = {}",
+        eve.id
+    ))
+    .await;
+
+    let snapshot = gtm_db::history::snapshot(&pool, "2026", "2026-06-03 07:00:00")
+        .await
+        .unwrap();
+    let holders: Vec<Option<UserId>> = snapshot.tickets.iter().map(|t| t.holder).collect();
+    assert_eq!(holders, vec![Some(ann.id), None]);
+    let requests: Vec<(UserId, &str)> = snapshot
+        .requests
+        .iter()
+        .map(|r| (r.user_id, r.status.as_str()))
+        .collect();
+    assert_eq!(requests, vec![(dee.id, "pending")]);
+
+    let summary = gtm_db::history::allocation_summary(&pool, &snapshot, TEAM_ID)
+        .await
+        .unwrap();
+    assert_eq!(
+        (
+            summary[0].assigned,
+            summary[0].available,
+            summary[0].total_requested,
+            summary[0].oversubscribed
+        ),
+        (1, 1, 2, 1)
+    );
+    let fairness = gtm_db::history::fairness_report(&pool, &snapshot)
+        .await
+        .unwrap();
+    let seats: Vec<(&str, i64)> = fairness
+        .iter()
+        .map(|f| (f.user_name.as_str(), f.seats_assigned))
+        .collect();
+    assert_eq!(
+        seats,
+        vec![("Ann", 1), ("Bo", 0), ("Cy", 0), ("Dee", 0), ("Eve", 0)]
+    );
+
+    // Now: Bo and Cy hold the seats
+    let now = gtm_db::history::snapshot(&pool, "2026", "2999-01-01 00:00:00")
+        .await
+        .unwrap();
+    let holders: Vec<Option<UserId>> = now.tickets.iter().map(|t| t.holder).collect();
+    assert_eq!(holders, vec![Some(bo.id), Some(cy.id)]);
+
+    // The cache serves the same snapshot, and refuses a cutoff it can't parse
+    let cache = gtm_db::history::SnapshotCache::new();
+    let cached = cache
+        .get(&pool, "2026", "2026-06-03 07:00:00")
+        .await
+        .unwrap();
+    assert_eq!(cached.tickets, snapshot.tickets);
+    assert!(cache.get(&pool, "2026", "June 3").await.is_err());
+}
+
+#[tokio::test]
+async fn member_history_counts_requests_allocations_attendance_and_late_releases() {
+    let pool = test_pool().await;
+    let soon = chrono::Utc::now() + chrono::Duration::hours(24);
+    let later = chrono::Utc::now() + chrono::Duration::days(10);
+    // A game already played, one a day out and one ten days out
+    let mut played = sample_game(700601);
+    played.status_abstract = "Final".to_string();
+    played.status_detailed = "Final".to_string();
+    let mut tomorrow = sample_game(700602);
+    tomorrow.game_date = soon.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let mut next_week = sample_game(700603);
+    next_week.game_date = later.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let mut last_year = sample_game(700604);
+    last_year.season = "2025".to_string();
+    for game in [&played, &tomorrow, &next_week, &last_year] {
+        gtm_db::upsert_game(&pool, game).await.unwrap();
+    }
+    let seat = gtm_db::add_seat(&pool, "VR316", "F", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let user = gtm_db::upsert_user(&pool, "auth0|hist1", "hist@example.com", "Hal")
+        .await
+        .unwrap();
+
+    for pk in [700601, 700602, 700603, 700604] {
+        gtm_db::create_ticket_request(&pool, user.id, GamePk(pk), 1, None)
+            .await
+            .unwrap();
+    }
+    for pk in [700601, 700602, 700603] {
+        let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(pk))
+            .await
+            .unwrap();
+        gtm_db::assign_ticket(&pool, tickets[0].id, user.id, None)
+            .await
+            .unwrap();
+    }
+    for pk in [700602, 700603] {
+        gtm_db::release_tickets_for_game(&pool, GamePk(pk), user.id, Some(user.id))
+            .await
+            .unwrap();
+    }
+
+    let history = gtm_db::reports::member_history(&pool, &user).await.unwrap();
+    assert_eq!(history.user_name, "Hal");
+    let seasons: Vec<(&str, i64, i64, i64, i64)> = history
+        .seasons
+        .iter()
+        .map(|s| {
+            (
+                s.season.as_str(),
+                s.requested,
+                s.allocated,
+                s.attended,
+                s.released_late,
+            )
+        })
+        .collect();
+    assert_eq!(seasons, vec![("2026", 3, 3, 1, 1), ("2025", 1, 0, 0, 0)]);
+}
+
+#[tokio::test]
+async fn attendance_recorded_by_members_and_admins_feeds_history() {
+    let pool = test_pool().await;
+    let later = chrono::Utc::now() + chrono::Duration::days(10);
+    let mut first = sample_game(700701);
+    first.status_abstract = "Final".to_string();
+    first.status_detailed = "Final".to_string();
+    let mut second = first.clone();
+    second.game_pk = GamePk(700702);
+    let mut upcoming = sample_game(700703);
+    upcoming.game_date = later.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    for game in [&first, &second, &upcoming] {
+        gtm_db::upsert_game(&pool, game).await.unwrap();
+    }
+    let seat = gtm_db::add_seat(&pool, "VR316", "F", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ann = gtm_db::upsert_user(&pool, "auth0|att1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bo = gtm_db::upsert_user(&pool, "auth0|att2", "bo@example.com", "Bo")
+        .await
+        .unwrap();
+    for pk in [700701, 700702, 700703] {
+        let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(pk))
+            .await
+            .unwrap();
+        gtm_db::assign_ticket(&pool, tickets[0].id, ann.id, None)
+            .await
+            .unwrap();
+    }
+
+    // Only members holding seats, and only once the game has started
+    assert!(
+        !gtm_db::attendance::upsert_own(&pool, &first, bo.id, true)
+            .await
+            .unwrap()
+    );
+    assert!(
+        gtm_db::attendance::upsert_own(&pool, &upcoming, ann.id, true)
+            .await
+            .is_err()
+    );
+    assert!(
+        gtm_db::attendance::upsert_own(&pool, &first, ann.id, false)
+            .await
+            .unwrap()
+    );
+
+    // An admin corrects the first game and records Bo at the second
+    gtm_db::attendance::upsert(&pool, GamePk(700701), ann.id, true, Some(bo.id))
+        .await
+        .unwrap();
+    gtm_db::attendance::upsert(&pool, GamePk(700702), bo.id, true, Some(bo.id))
+        .await
+        .unwrap();
+    let rows = gtm_db::attendance::for_game(&pool, GamePk(700701))
+        .await
+        .unwrap();
+    let rows: Vec<(&str, i64, Option<i64>, Option<UserId>)> = rows
+        .iter()
+        .map(|r| (r.user_name.as_str(), r.seats, r.attended, r.recorded_by))
+        .collect();
+    assert_eq!(rows, vec![("Ann", 1, Some(1), Some(bo.id))]);
+
+    // Every answer is audited, the correction with what it replaced
+    let log = gtm_db::audit::list(&pool, Some(GamePk(700701)), Some(ann.id))
+        .await
+        .unwrap();
+    let log: Vec<(Option<UserId>, Option<&str>, Option<&str>)> = log
+        .iter()
+        .filter(|e| e.action == gtm_db::audit::ATTENDANCE)
+        .map(|e| {
+            (
+                e.actor_id,
+                e.before_value.as_deref(),
+                e.after_value.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        log,
+        vec![
+            (Some(bo.id), Some("missed"), Some("attended")),
+            (Some(ann.id), None, Some("missed")),
+        ]
+    );
+
+    // Ann misses the second game; the upcoming one doesn't count yet
+    gtm_db::attendance::upsert_own(&pool, &second, ann.id, false)
+        .await
+        .unwrap();
+    let counts = |h: gtm_models::MemberHistory| {
+        h.seasons
+            .iter()
+            .map(|s| (s.season.clone(), s.attended, s.missed))
+            .collect::<Vec<_>>()
+    };
+    let ann_history = gtm_db::reports::member_history(&pool, &ann).await.unwrap();
+    assert_eq!(counts(ann_history), vec![("2026".to_string(), 1, 1)]);
+    let bo_history = gtm_db::reports::member_history(&pool, &bo).await.unwrap();
+    assert_eq!(counts(bo_history), vec![("2026".to_string(), 1, 0)]);
+}
+
+#[tokio::test]
+async fn forwarding_checklist_tracks_the_current_holder() {
+    use gtm_db::forwarding::{self, Forward};
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700801))
+        .await
+        .unwrap();
+    for seat in ["1", "2", "3"] {
+        let seat = gtm_db::add_seat(&pool, "VR317", "G", seat, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    let ann = gtm_db::upsert_user(&pool, "auth0|fw1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bo = gtm_db::upsert_user(&pool, "auth0|fw2", "bo@example.com", "Bo")
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700801))
+        .await
+        .unwrap();
+    for t in &tickets[..2] {
+        gtm_db::assign_ticket(&pool, t.id, ann.id, None)
+            .await
+            .unwrap();
+    }
+
+    assert!(Forward::new("2026-03-30", "carrier pigeon", None).is_err());
+    assert!(Forward::new("3/30/2026", "email", None).is_err());
+    let forward = Forward::new("2026-03-30", "Ballpark_App", Some(" TX-001 ")).unwrap();
+    assert_eq!(forward.method, "ballpark_app");
+    assert_eq!(forward.external_ticket_id.as_deref(), Some("TX-001"));
+
+    // Only assigned tickets are on the checklist, none forwarded yet
+    let list = forwarding::checklist(&pool, GamePk(700801)).await.unwrap();
+    assert_eq!(list.len(), 2);
+    assert!(list.iter().all(|t| t.forwarded == 0));
+    assert!(
+        !forwarding::record(&pool, tickets[2].id, &forward, Some(bo.id))
+            .await
+            .unwrap()
+    );
+
+    assert!(
+        forwarding::record(&pool, tickets[0].id, &forward, Some(bo.id))
+            .await
+            .unwrap()
+    );
+    let list = forwarding::checklist(&pool, GamePk(700801)).await.unwrap();
+    // Still to forward first
+    assert_eq!(list[0].ticket_id, tickets[1].id);
+    assert_eq!(list[0].forwarded, 0);
+    let done = &list[1];
+    assert_eq!(done.forwarded, 1);
+    assert_eq!(done.forwarded_to, Some(ann.id));
+    assert_eq!(done.forwarded_on.as_deref(), Some("2026-03-30"));
+    assert_eq!(done.forward_method.as_deref(), Some("ballpark_app"));
+    assert_eq!(done.forwarded_by, Some(bo.id));
+    assert_eq!(done.external_ticket_id.as_deref(), Some("TX-001"));
+
+    // The rest of the game at once, leaving the forwarded one alone
+    let email = Forward::new("2026-03-31", "email", None).unwrap();
+    assert_eq!(
+        forwarding::record_game(&pool, GamePk(700801), &email, None)
+            .await
+            .unwrap(),
+        1
+    );
+    let list = forwarding::checklist(&pool, GamePk(700801)).await.unwrap();
+    assert!(list.iter().all(|t| t.forwarded == 1));
+    assert_eq!(list[0].forward_method.as_deref(), Some("ballpark_app"));
+
+    // Reassigned: the new holder needs it forwarded again
+    gtm_db::update_ticket_status(&pool, tickets[0].id, TicketStatus::Available, None, None)
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[0].id, bo.id, None)
+        .await
+        .unwrap();
+    let list = forwarding::checklist(&pool, GamePk(700801)).await.unwrap();
+    assert_eq!(list[0].ticket_id, tickets[0].id);
+    assert_eq!(list[0].user_name, "Bo");
+    assert_eq!(list[0].forwarded, 0);
+    assert_eq!(list[0].forwarded_to, Some(ann.id));
+
+    // Clearing keeps the external id
+    assert!(forwarding::clear(&pool, tickets[1].id).await.unwrap());
+    let list = forwarding::checklist(&pool, GamePk(700801)).await.unwrap();
+    let cleared = list.iter().find(|t| t.ticket_id == tickets[1].id).unwrap();
+    assert_eq!(cleared.forwarded, 0);
+    assert!(cleared.forwarded_on.is_none());
+    assert!(!forwarding::clear(&pool, TicketId(999_999)).await.unwrap());
+}
+
+#[tokio::test]
+async fn price_import_matches_games_reports_misses_and_reruns_cleanly() {
+    let pool = test_pool().await;
+    let mut dodgers = sample_game(700801);
+    dodgers.official_date = "2026-05-01".to_string();
+    dodgers.game_date = "2026-05-01T19:15:00Z".to_string();
+    dodgers.away_team_name = "Los Angeles Dodgers".to_string();
+    let mut padres = sample_game(700802);
+    padres.official_date = "2026-05-02".to_string();
+    padres.game_date = "2026-05-02T17:05:00Z".to_string();
+    padres.away_team_name = "San Diego Padres".to_string();
+    let mut nightcap = padres.clone();
+    nightcap.game_pk = GamePk(700803);
+    nightcap.game_date = "2026-05-02T23:05:00Z".to_string();
+    nightcap.game_number = 2;
+    for game in [&dodgers, &padres, &nightcap] {
+        gtm_db::upsert_game(&pool, game).await.unwrap();
+    }
+    for seat in ["1", "2"] {
+        let seat = gtm_db::add_seat(&pool, "VR320", "A", seat, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+
+    let csv = "date,opponent,price,game_number,section,row,seat\n\
+               2026-05-01,vs. Dodgers,$85.00,,,,\n\
+               5/1/2026,Dodgers,95,,VR320,A,2\n\
+               2026-05-02,Padres,60,,,,\n\
+               2026-05-02,Padres,60,2,,,\n\
+               2026-05-03,Rockies,50,,,,\n";
+    let lines = gtm_db::prices::parse_csv(csv).unwrap();
+    assert_eq!(lines.len(), 5);
+    let rows = gtm_db::prices::resolve(&pool, TEAM_ID, &lines)
+        .await
+        .unwrap();
+    let summary: Vec<(usize, Option<GamePk>, usize, bool)> = rows
+        .iter()
+        .map(|r| (r.line.line, r.game_pk, r.tickets.len(), r.problem.is_some()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (2, Some(GamePk(700801)), 2, false),
+            (3, Some(GamePk(700801)), 1, false),
+            (4, None, 0, true),
+            (5, Some(GamePk(700803)), 2, false),
+            (6, None, 0, true),
+        ]
+    );
+
+    // The seat line overrides the game-wide price for its seat
+    let admin = gtm_db::upsert_user(&pool, "auth0|pr", "pr@example.com", "Pricer")
+        .await
+        .unwrap();
+    assert_eq!(
+        gtm_db::prices::apply(&pool, &rows, admin.id).await.unwrap(),
+        4
+    );
+    assert_eq!(
+        gtm_db::prices::apply(&pool, &rows, admin.id).await.unwrap(),
+        0
+    );
+    let audit = gtm_db::audit::list(&pool, Some(GamePk(700801)), None)
+        .await
+        .unwrap();
+    let mut changes: Vec<(Option<&str>, Option<&str>, Option<&str>)> = audit
+        .iter()
+        .filter(|e| e.action == gtm_db::audit::PRICE && e.actor_id == Some(admin.id))
+        .map(|e| {
+            (
+                e.seat.as_deref(),
+                e.before_value.as_deref(),
+                e.after_value.as_deref(),
+            )
+        })
+        .collect();
+    changes.sort();
+    assert_eq!(
+        changes,
+        vec![
+            (Some("1"), None, Some("85.00")),
+            (Some("2"), None, Some("95.00")),
+        ]
+    );
+
+    // Only the whole name or its trailing words name a team
+    let csv = "date,opponent,price\n\
+               2026-05-01,Los Angeles Dodgers,90\n\
+               2026-05-01,vs LOS ANGELES DODGERS,90\n\
+               2026-05-01,Dodg,90\n\
+               2026-05-01,s,90\n\
+               2026-05-01,Los Angeles,90\n";
+    let lines = gtm_db::prices::parse_csv(csv).unwrap();
+    let rows = gtm_db::prices::resolve(&pool, TEAM_ID, &lines)
+        .await
+        .unwrap();
+    let matched: Vec<bool> = rows.iter().map(|r| r.problem.is_none()).collect();
+    assert_eq!(matched, vec![true, true, false, false, false]);
+
+    let report = gtm_db::reports::renewal(&pool, "2026", "2026-06-01")
+        .await
+        .unwrap();
+    let costs: Vec<(&str, i64)> = report
+        .seats
+        .iter()
+        .map(|s| (s.seat.as_str(), s.cost))
+        .collect();
+    assert_eq!(costs, vec![("1", 14500), ("2", 15500)]);
+
+    assert!(gtm_db::prices::parse_csv("date,opponent\n2026-05-01,Dodgers\n").is_err());
+    assert!(gtm_db::prices::parse_csv("date,opponent,price\nMay 1,Dodgers,85\n").is_err());
+}
+
+#[tokio::test]
+async fn renewal_report_rolls_up_use_loss_and_resale() {
+    let pool = test_pool().await;
+    let dates = ["2026-05-01", "2026-05-02", "2026-05-03", "2026-06-20"];
+    for (i, date) in dates.iter().enumerate() {
+        let mut game = sample_game(700401 + i as i64);
+        game.official_date = date.to_string();
+        game.game_date = format!("{date}T19:15:00Z");
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+    }
+    let seat = gtm_db::add_seat(&pool, "VR314", "D", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    gtm_db::update_seat_group_face_value(&pool, "VR314", "D", Some(4000))
+        .await
+        .unwrap();
+    let user = gtm_db::upsert_user(&pool, "auth0|rn1", "rn@example.com", "Renee")
+        .await
+        .unwrap();
+    let mut tickets = Vec::new();
+    for pk in 700401..=700404 {
+        let game = gtm_db::list_tickets_for_game(&pool, GamePk(pk))
+            .await
+            .unwrap();
+        tickets.push(game[0].id);
+    }
+    // Used, resold at a discount, left unused (at a custom cost), and a game
+    // still to come that doesn't count yet
+    gtm_db::assign_ticket(&pool, tickets[0], user.id, None)
+        .await
+        .unwrap();
+    gtm_db::set_ticket_resale(&pool, tickets[1], Some(3000))
+        .await
+        .unwrap();
+    gtm_db::set_ticket_cost(&pool, tickets[2], Some(5000))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[3], user.id, None)
+        .await
+        .unwrap();
+
+    let report = gtm_db::reports::renewal(&pool, "2026", "2026-06-01")
+        .await
+        .unwrap();
+    assert_eq!(report.seats.len(), 1);
+    let row = &report.seats[0];
+    assert_eq!((row.games, row.used, row.resold, row.unused), (3, 1, 1, 1));
+    assert!((row.utilization_pct - 100.0 / 3.0).abs() < 1e-9);
+    assert_eq!(
+        (row.cost, row.unused_loss, row.resale_total),
+        (13000, 5000, 3000)
+    );
+    assert_eq!((report.total_cost, report.unused_loss), (13000, 5000));
+    assert_eq!(report.resale_recovery_pct, Some(75.0));
+
+    assert_eq!(report.members.len(), 1);
+    let member = &report.members[0];
+    assert_eq!(member.user_name, "Renee");
+    assert_eq!(
+        (member.games, member.seats, member.face_value, member.spend),
+        (1, 1, 4000, 4000)
+    );
+
+    let csv = gtm_db::export::renewal_csv(&report);
+    assert!(csv.contains("VR314/D/1,40.00,3,1,1,1,33.3,130.00,50.00,30.00\r\n"));
+    assert!(csv.contains("Renee,1,1,40.00,40.00\r\n"));
+    assert!(csv.ends_with("resale_recovery_pct,75.0\r\n"));
+}
+
+#[tokio::test]
+async fn attendance_grid_pivots_games_by_member() {
+    let pool = test_pool().await;
+    for (i, date) in ["2026-05-01", "2026-05-02"].iter().enumerate() {
+        let mut game = sample_game(700501 + i as i64);
+        game.official_date = date.to_string();
+        game.game_date = format!("{date}T19:15:00Z");
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+    }
+    for n in ["1", "2", "3"] {
+        let seat = gtm_db::add_seat(&pool, "VR314", "D", n, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    let zed = gtm_db::upsert_user(&pool, "auth0|ag1", "zed@example.com", "Zed")
+        .await
+        .unwrap();
+    let amy = gtm_db::upsert_user(&pool, "auth0|ag2", "amy@example.com", "Amy")
+        .await
+        .unwrap();
+    let first = gtm_db::list_tickets_for_game(&pool, GamePk(700501))
+        .await
+        .unwrap();
+    let second = gtm_db::list_tickets_for_game(&pool, GamePk(700502))
+        .await
+        .unwrap();
+    for (ticket, user) in [
+        (&first[0], zed.id),
+        (&first[1], zed.id),
+        (&second[0], amy.id),
+    ] {
+        gtm_db::assign_ticket(&pool, ticket.id, user, None)
+            .await
+            .unwrap();
+    }
+
+    let grid = gtm_db::reports::attendance_grid(&pool, "2026")
+        .await
+        .unwrap();
+    let names: Vec<&str> = grid.members.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["Amy", "Zed"]);
+    assert_eq!(grid.games.len(), 2);
+    assert_eq!(grid.games[0].official_date, "2026-05-01");
+    assert_eq!(
+        (grid.games[0].seats.clone(), grid.games[0].unassigned),
+        (vec![0, 2], 1)
+    );
+    assert_eq!(
+        (grid.games[1].seats.clone(), grid.games[1].unassigned),
+        (vec![1, 0], 2)
+    );
+
+    let csv = gtm_db::export::attendance_grid_csv(&grid);
+    let opponent = &grid.games[0].opponent;
+    assert_eq!(
+        csv,
+        format!(
+            "date,opponent,Amy,Zed,open\r\n\
+             2026-05-01,{opponent},,2,1\r\n\
+             2026-05-02,{opponent},1,,2\r\n"
+        )
+    );
+}
+
+// --- Seat import ---
+
+#[tokio::test]
+async fn seat_import_is_all_or_nothing() {
+    use gtm_db::import;
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700401))
+        .await
+        .unwrap();
+    gtm_db::upsert_game(&pool, &sample_game(700402))
+        .await
+        .unwrap();
+
+    let rows = import::parse_seats_csv(
+        "section,row,seat,notes,face_value\r\n\
+         VR313,D,1,\"Aisle, near stairs\",42.50\r\n\
+         VR313,D,2,,$42\r\n\
+         \r\n\
+         LB127,A,9,,\n",
+    )
+    .unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].notes.as_deref(), Some("Aisle, near stairs"));
+    assert_eq!(rows[0].face_value, Some(4250));
+    assert_eq!(rows[1].face_value, Some(4200));
+    assert_eq!(rows[2].face_value, None);
+
+    let imported = import::import_seats(&pool, &rows, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(imported.seats.len(), 3);
+    // Three seats across two home games, in one statement
+    assert_eq!(imported.tickets, 6);
+
+    // Listing a seat twice is caught before the database
+    let twice = import::parse_seats_csv("section,row,seat\nX,1,1\nX,1,1\n").unwrap();
+    let err = import::import_seats(&pool, &twice, TEAM_ID, REGULAR)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("already listed at entry 1"),
+        "{err}"
+    );
+
+    // One existing seat rolls back the new ones with it
+    let mixed = import::parse_seats_csv("row,seat,section\nE,1,VR314\nD,2,VR313\n").unwrap();
+    let err = import::import_seats(&pool, &mixed, TEAM_ID, REGULAR)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Section VR313 Row D Seat 2"),
+        "{err}"
+    );
+    assert_eq!(gtm_db::list_seats(&pool).await.unwrap().len(), 3);
+
+    // Bad headers and values are reported
+    assert!(import::parse_seats_csv("section,row\n1,A\n").is_err());
+    assert!(import::parse_seats_csv("section,row,seat,price\n").is_err());
+    let err = import::parse_seats_csv("section,row,seat,face_value\nA,1,1,cheap\n").unwrap_err();
+    assert!(err.to_string().starts_with("Line 2"), "{err}");
+}
+
+#[tokio::test]
+async fn allocation_plan_resolves_lines_and_commits_whole() {
+    use gtm_db::allocation_plan;
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700411))
+        .await
+        .unwrap();
+    for n in ["1", "2", "3"] {
+        let seat = gtm_db::add_seat(&pool, "VR313", "P", n, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    let alice = gtm_db::upsert_user(&pool, "auth0|ap1", "ap1@example.com", "Alice")
+        .await
+        .unwrap();
+    let bob = gtm_db::upsert_user(&pool, "auth0|ap2", "ap2@example.com", "Bob")
+        .await
+        .unwrap();
+    let req = gtm_db::create_ticket_request(&pool, alice.id, GamePk(700411), 2, None)
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700411))
+        .await
+        .unwrap();
+
+    let lines = allocation_plan::parse_csv(
+        "seats,game_pk,user_email\n2,700411,ap1@example.com\n\n2,700411,ap2@example.com\n",
+    )
+    .unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1].line, 4);
+
+    // Bob's line asks for more than Alice's leaves, so nothing is assigned
+    let rows = allocation_plan::resolve(&pool, &lines).await.unwrap();
+    assert_eq!(rows[0].tickets, vec![tickets[0].id, tickets[1].id]);
+    assert_eq!(rows[0].request_id, Some(req.id));
+    assert_eq!(rows[1].problem.as_deref(), Some("Only 1 seat(s) left"));
+    assert!(allocation_plan::assignments(&rows).is_err());
+
+    let lines = allocation_plan::parse_csv(
+        "game_pk,user_email,seats\n700411,ap1@example.com,2\n700411,ap2@example.com,1\n",
+    )
+    .unwrap();
+    let rows = allocation_plan::resolve(&pool, &lines).await.unwrap();
+    assert_eq!(rows[1].tickets, vec![tickets[2].id]);
+    assert_eq!(rows[1].request_id, None);
+    let batch = allocation_plan::assignments(&rows).unwrap();
+    let mut warnings = gtm_db::Warnings::new();
+    let assigned = gtm_db::commit_allocation(&pool, &batch, None, false, &mut warnings)
+        .await
+        .unwrap();
+    assert_eq!(assigned, 3);
+    let counts = gtm_db::assigned_seat_counts_for_season(&pool, "2026")
+        .await
+        .unwrap();
+    assert!(counts.contains(&(alice.id, 2)) && counts.contains(&(bob.id, 1)));
+    let reqs = gtm_db::list_requests_for_game(&pool, GamePk(700411))
+        .await
+        .unwrap();
+    assert_eq!(reqs[0].status, "approved");
+
+    // Unknown games and members are problems; malformed lines are errors
+    let lines = allocation_plan::parse_csv(
+        "game_pk,user_email,seats\n1,ap1@example.com,1\n700411,nobody@example.com,1\n",
+    )
+    .unwrap();
+    let rows = allocation_plan::resolve(&pool, &lines).await.unwrap();
+    assert_eq!(rows[0].problem.as_deref(), Some("No such game"));
+    assert_eq!(
+        rows[1].problem.as_deref(),
+        Some("No member with that email")
+    );
+    let err = allocation_plan::parse_csv("game_pk,user_email,seats\n700411,a@b.c,0\n").unwrap_err();
+    assert!(err.to_string().starts_with("Line 2"), "{err}");
+    assert!(allocation_plan::parse_csv("game_pk,user_email\n").is_err());
+}
+
+// --- Availability polls ---
+
+#[tokio::test]
+async fn closing_a_poll_files_requests_for_yes_answers() {
+    use gtm_db::polls;
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700501))
+        .await
+        .unwrap();
+    let admin = gtm_db::upsert_user(&pool, "auth0|pa", "pa@example.com", "Admin")
+        .await
+        .unwrap();
+    let mut members = Vec::new();
+    for name in ["Ann", "Bob", "Cy", "Di"] {
+        let sub = format!("auth0|p{name}");
+        let email = format!("{name}@example.com");
+        members.push(
+            gtm_db::upsert_user(&pool, &sub, &email, name)
+                .await
+                .unwrap(),
+        );
+    }
+
+    let poll = polls::create(&pool, GamePk(700501), Some("Opening Day?"), admin.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        polls::create(&pool, GamePk(700501), None, admin.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Di already asked for the game the usual way
+    gtm_db::create_ticket_request(&pool, members[3].id, GamePk(700501), 1, None)
+        .await
+        .unwrap();
+    polls::respond(&pool, poll.id, members[0].id, "no", 0)
+        .await
+        .unwrap();
+    polls::respond(&pool, poll.id, members[0].id, "yes", 2)
+        .await
+        .unwrap();
+    polls::respond(&pool, poll.id, members[1].id, "maybe", 3)
+        .await
+        .unwrap();
+    polls::respond(&pool, poll.id, members[2].id, "no", 0)
+        .await
+        .unwrap();
+    polls::respond(&pool, poll.id, members[3].id, "yes", 4)
+        .await
+        .unwrap();
+    assert!(
+        polls::respond(&pool, poll.id, members[2].id, "sure", 1)
+            .await
+            .is_err()
+    );
+
+    let results = polls::results(&pool, poll.id).await.unwrap().unwrap();
+    let s = &results.summary;
+    assert_eq!((s.yes, s.no, s.maybe), (2, 1, 1));
+    assert_eq!((s.yes_seats, s.maybe_seats), (6, 3));
+    assert_eq!(results.responses[0].answer, "yes");
+
+    let open = polls::open_for_user(&pool, members[0].id, "2000-01-01")
+        .await
+        .unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].answer.as_deref(), Some("yes"));
+    assert_eq!(open[0].seats, Some(2));
+
+    // Only Ann's "yes" becomes a new request; Di keeps hers
+    assert_eq!(polls::close(&pool, poll.id).await.unwrap(), Some(1));
+    assert_eq!(polls::close(&pool, poll.id).await.unwrap(), None);
+    let requests = gtm_db::list_requests_for_game(&pool, GamePk(700501))
+        .await
+        .unwrap();
+    let seats = |u: UserId| {
+        requests
+            .iter()
+            .find(|r| r.user_id == u)
+            .map(|r| r.seats_requested)
+    };
+    assert_eq!(seats(members[0].id), Some(2));
+    assert_eq!(seats(members[1].id), None);
+    assert_eq!(seats(members[3].id), Some(1));
+
+    // Closed polls take no more answers and drop off members' lists
+    assert!(
+        !polls::respond(&pool, poll.id, members[2].id, "yes", 1)
+            .await
+            .unwrap()
+    );
+    assert!(
+        polls::open_for_user(&pool, members[0].id, "2000-01-01")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn note_templates_apply_rendered_notes_and_count_uses() {
+    use gtm_db::note_templates::{self, NoteTarget};
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700501))
+        .await
+        .unwrap();
+    for seat in ["1", "2", "3"] {
+        let seat = gtm_db::add_seat(&pool, "VR314", "D", seat, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    let admin = gtm_db::upsert_user(&pool, "auth0|nt1", "nt@example.com", "Nora")
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700501))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[0].id, admin.id, None)
+        .await
+        .unwrap();
+
+    let template = note_templates::create(
+        &pool,
+        "Ballpark transfer",
+        "Transferred via Ballpark app {today} ({seat} vs {opponent}, {date})",
+    )
+    .await
+    .unwrap();
+    assert!(note_templates::create(&pool, " ", "x").await.is_err());
+
+    // Only the two tickets still available
+    let target = NoteTarget::Game {
+        game_pk: GamePk(700501),
+        status: Some("available"),
+    };
+    assert_eq!(
+        note_templates::count_targets(&pool, target).await.unwrap(),
+        2
+    );
+    let applied = note_templates::apply(&pool, template.id, target, Some(admin.id), "2026-06-01")
+        .await
+        .unwrap();
+    assert_eq!(applied, Some(2));
+
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(700501))
+        .await
+        .unwrap();
+    assert_eq!(tickets[0].notes, None);
+    assert_eq!(
+        tickets[1].notes.as_deref(),
+        Some("Transferred via Ballpark app 6/1 (VR314/D/2 vs Arizona Diamondbacks, 4/1)")
+    );
+
+    let ids = [tickets[0].id];
+    note_templates::apply(
+        &pool,
+        template.id,
+        NoteTarget::Tickets(&ids),
+        None,
+        "2026-06-02",
+    )
+    .await
+    .unwrap();
+    let listed = note_templates::list(&pool).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].applied, 3);
+    assert!(listed[0].last_applied_at.is_some());
+
+    // Applied templates are kept for reporting
+    assert!(note_templates::delete(&pool, template.id).await.is_err());
+    assert_eq!(
+        note_templates::apply(&pool, 999, target, None, "2026-06-01")
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn seat_limits_take_the_strictest_matching_rule_unless_overridden() {
+    use gtm_db::seat_limits::{self, NewRule, SeatLimitPolicy};
+
+    let pool = test_pool().await;
+    // A Wednesday night game, a Saturday one, and a spring training game
+    let mut games = Vec::new();
+    for (pk, date, game_type) in [
+        (700601, "2026-04-01", "R"),
+        (700602, "2026-04-04", "R"),
+        (700603, "2026-03-10", "S"),
+    ] {
+        let mut game = sample_game(pk);
+        game.official_date = date.to_string();
+        game.game_type = game_type.to_string();
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+        games.push(game);
+    }
+    let limits = |policy: &SeatLimitPolicy| -> Vec<i64> {
+        games.iter().map(|g| policy.max_seats(g)).collect()
+    };
+    let policy = SeatLimitPolicy::load(&pool).await.unwrap();
+    assert_eq!(limits(&policy), vec![4, 4, 4]);
+
+    let rule = |game_type: Option<&str>, tag: Option<&str>, max_seats| NewRule {
+        game_type: game_type.map(String::from),
+        tag: tag.map(String::from),
+        max_seats,
+    };
+    seat_limits::set_rules(
+        &pool,
+        &[
+            rule(None, Some("Premium "), 2),
+            rule(None, Some("weekend"), 3),
+            rule(Some("s"), None, 6),
+        ],
+    )
+    .await
+    .unwrap();
+    assert!(
+        seat_limits::set_rules(&pool, &[rule(None, None, 0)])
+            .await
+            .is_err()
+    );
+    let saved = seat_limits::rules(&pool).await.unwrap();
+    assert_eq!(saved[0].tag.as_deref(), Some("premium"));
+    assert_eq!(saved[2].game_type.as_deref(), Some("S"));
+
+    // Premium beats weekend on the Saturday game
+    seat_limits::set_game_tags(&pool, GamePk(700602), &["premium".to_string()])
+        .await
+        .unwrap();
+    let policy = SeatLimitPolicy::load(&pool).await.unwrap();
+    assert_eq!(limits(&policy), vec![4, 2, 6]);
+    assert!(policy.tags_for(&games[0]).contains("weekday"));
+    assert!(policy.tags_for(&games[0]).contains("night"));
+
+    assert!(
+        seat_limits::set_game_override(&pool, GamePk(700602), Some(5))
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        seat_limits::max_seats_for_game(&pool, GamePk(700602))
+            .await
+            .unwrap(),
+        5
+    );
+    seat_limits::set_game_override(&pool, GamePk(700602), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        seat_limits::max_seats_for_game(&pool, GamePk(700602))
+            .await
+            .unwrap(),
+        2
+    );
+}
+
+// --- Seasons ---
+
+#[tokio::test]
+async fn closed_season_is_listed_but_frozen() {
+    use gtm_db::seasons::{self, SeasonClosed};
+
+    let pool = test_pool().await;
+    let mut last_year = sample_game(700701);
+    last_year.season = "2025".to_string();
+    last_year.official_date = "2025-06-01".to_string();
+    last_year.game_date = "2025-06-01T20:05:00Z".to_string();
+    gtm_db::upsert_game(&pool, &last_year).await.unwrap();
+    gtm_db::upsert_game(&pool, &sample_game(700702))
+        .await
+        .unwrap();
+    gtm_db::add_seat(&pool, "VR313", "S", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let user = gtm_db::upsert_user(&pool, "auth0|season", "s@example.com", "Season")
+        .await
+        .unwrap();
+    let old = gtm_db::create_ticket_request(&pool, user.id, GamePk(700701), 1, None)
+        .await
+        .unwrap();
+    let current = gtm_db::create_ticket_request(&pool, user.id, GamePk(700702), 1, None)
+        .await
+        .unwrap();
+    let old_ticket = gtm_db::list_tickets_for_game(&pool, GamePk(700701))
+        .await
+        .unwrap()[0]
+        .id;
+    assert!(
+        gtm_db::assign_ticket(&pool, old_ticket, user.id, None)
+            .await
+            .unwrap()
+    );
+
+    let listed = seasons::list(&pool).await.unwrap();
+    assert_eq!(
+        listed
+            .iter()
+            .map(|s| (s.season.as_str(), s.games, s.tickets, s.requests))
+            .collect::<Vec<_>>(),
+        vec![("2026", 1, 1, 1), ("2025", 1, 1, 1)]
+    );
+    assert_eq!(listed[1].last_date, "2025-06-01");
+    assert!(listed.iter().all(|s| s.closed_at.is_none()));
+
+    // Listings filter by season
+    let summary = gtm_db::ticket_summary_for_games(&pool, Some("2025"), None, None)
+        .await
+        .unwrap();
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].season, "2025");
+    let requests = gtm_db::list_requests_for_user(&pool, user.id, Some("2025"))
+        .await
+        .unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].id, old.id);
+    let mine = gtm_db::list_tickets_for_user(&pool, user.id, Some("2026"))
+        .await
+        .unwrap();
+    assert!(mine.is_empty());
+
+    assert!(seasons::close(&pool, "2025", Some(user.id)).await.unwrap());
+    assert!(!seasons::close(&pool, "2025", Some(user.id)).await.unwrap());
+    let listed = seasons::list(&pool).await.unwrap();
+    assert!(listed[0].closed_at.is_none());
+    assert!(listed[1].closed_at.is_some());
+
+    // The closed season's tickets and requests can't change
+    let closed = |e: anyhow::Error| e.downcast_ref::<SeasonClosed>().map(|c| c.season.clone());
+    let err = gtm_db::release_tickets_for_game(&pool, GamePk(700701), user.id, None)
+        .await
+        .unwrap_err();
+    assert_eq!(closed(err).as_deref(), Some("2025"));
+    let err = gtm_db::revoke_ticket(&pool, old_ticket, None)
+        .await
+        .unwrap_err();
+    assert!(closed(err).is_some());
+    let err = gtm_db::update_ticket_request(&pool, old.id, user.id, 2)
+        .await
+        .unwrap_err();
+    assert!(closed(err).is_some());
+    let err = gtm_db::create_ticket_request(&pool, user.id, GamePk(700701), 2, None)
+        .await
+        .unwrap_err();
+    assert!(closed(err).is_some());
+    assert_eq!(
+        gtm_db::list_tickets_for_user(&pool, user.id, None)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // The open season carries on, and new seats only get its tickets
+    assert!(
+        gtm_db::update_ticket_request(&pool, current.id, user.id, 2)
+            .await
+            .unwrap()
+    );
+    gtm_db::add_seat(&pool, "VR313", "S", "2", None)
+        .await
+        .unwrap();
+    let generated = gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(generated.created.len(), 1);
+    assert_eq!(generated.created[0].0, GamePk(700702));
+}
+
+// --- Seating parties together ---
+
+#[tokio::test]
+async fn parties_split_across_sections_need_an_override() {
+    let pool = test_pool().await;
+    let game_pk = GamePk(500_090);
+    gtm_db::upsert_game(&pool, &sample_game(game_pk.0))
+        .await
+        .unwrap();
+    for (section, seat) in [
+        ("VR313", "1"),
+        ("VR313", "2"),
+        ("VR313", "4"),
+        ("VR314", "1"),
+    ] {
+        gtm_db::add_seat(&pool, section, "A", seat, None)
+            .await
+            .unwrap();
+    }
+    gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let ann = gtm_db::upsert_user(&pool, "auth0|ann", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, game_pk).await.unwrap();
+    let id = |section: &str, seat: &str| {
+        tickets
+            .iter()
+            .find(|t| t.section == section && t.seat == seat)
+            .unwrap()
+            .id
+    };
+
+    let pair = gtm_db::seating::find_adjacent_available(&pool, game_pk, 2)
+        .await
+        .unwrap();
+    assert_eq!(pair, Some(vec![id("VR313", "1"), id("VR313", "2")]));
+    assert_eq!(
+        gtm_db::seating::find_adjacent_available(&pool, game_pk, 3)
+            .await
+            .unwrap(),
+        None
+    );
+
+    // Side by side passes quietly; a gap in one section is reported
+    let together = [(id("VR313", "1"), ann.id), (id("VR313", "2"), ann.id)];
+    assert!(
+        gtm_db::seating::check_parties(&pool, &together, false)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let apart = [(id("VR313", "1"), ann.id), (id("VR313", "4"), ann.id)];
+    assert_eq!(
+        gtm_db::seating::check_parties(&pool, &apart, false)
+            .await
+            .unwrap(),
+        vec![(game_pk, ann.id, gtm_db::seating::Seating::Apart)]
+    );
+
+    // A seat in another section joins the one Ann already holds
+    gtm_db::assign_ticket(&pool, id("VR313", "1"), ann.id, None)
+        .await
+        .unwrap();
+    let split = [(id("VR314", "1"), ann.id)];
+    let err = gtm_db::seating::check_parties(&pool, &split, false)
+        .await
+        .unwrap_err();
+    let err = err
+        .downcast_ref::<gtm_db::seating::SplitParty>()
+        .expect("a SplitParty");
+    assert_eq!(err.sections, vec!["VR313", "VR314"]);
+    assert!(
+        gtm_db::assign_ticket(&pool, id("VR314", "1"), ann.id, None)
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        gtm_db::seating::check_parties(&pool, &split, true)
+            .await
+            .unwrap(),
+        vec![(game_pk, ann.id, gtm_db::seating::Seating::Split)]
+    );
+    assert_eq!(
+        gtm_db::seating::party_seating(&pool, game_pk, ann.id, &[id("VR313", "2")])
+            .await
+            .unwrap(),
+        gtm_db::seating::Seating::Together
+    );
+
+    // Committing checks too: the split seat isn't assigned without the
+    // override, and the batch is all or nothing
+    let batch = [
+        (id("VR313", "2"), ann.id, None),
+        (id("VR314", "1"), ann.id, None),
+    ];
+    let mut warnings = gtm_db::Warnings::new();
+    let err = gtm_db::commit_allocation(&pool, &batch, None, false, &mut warnings)
+        .await
+        .unwrap_err();
+    assert!(err.downcast_ref::<gtm_db::seating::SplitParty>().is_some());
+    assert_eq!(
+        gtm_db::get_ticket(&pool, id("VR313", "2"))
+            .await
+            .unwrap()
+            .unwrap()
+            .status,
+        "available"
+    );
+    let assigned = gtm_db::commit_allocation(&pool, &batch, None, true, &mut warnings)
+        .await
+        .unwrap();
+    assert_eq!(assigned, 2);
+    assert!(warnings.iter().any(|w| w.contains("more than one section")));
+
+    // A transfer that splits the receiver's party goes through, flagged
+    let bob = gtm_db::upsert_user(&pool, "auth0|bob", "bob@example.com", "Bob")
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, id("VR313", "4"), bob.id, None)
+        .await
+        .unwrap();
+    let mut warnings = gtm_db::Warnings::new();
+    let moved = gtm_db::transfer_ticket(
+        &pool,
+        game_pk,
+        ann.id,
+        bob.id,
+        Some(&[id("VR314", "1")]),
+        Some(ann.id),
+        &mut warnings,
+    )
+    .await
+    .unwrap();
+    assert_eq!(moved, vec![id("VR314", "1")]);
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w.contains(&format!("User {}'s seats", bob.id))),
+        "{warnings:?}"
+    );
+}
+
+#[tokio::test]
+async fn preferences_default_save_clear_and_mute_notifications() {
+    use gtm_db::notifications::{GAME_PACKET, TICKETS_ASSIGNED};
+    use gtm_db::preferences::{self, PreferencesUpdate};
+
+    let pool = test_pool().await;
+    let user = gtm_db::upsert_user(&pool, "auth0|pf1", "pf@example.com", "Pat")
+        .await
+        .unwrap();
+    let other = gtm_db::upsert_user(&pool, "auth0|pf2", "pf2@example.com", "Quinn")
+        .await
+        .unwrap();
+
+    // Nothing saved: no default, every notification on, account email
+    let prefs = preferences::get(&pool, user.id).await.unwrap();
+    assert_eq!(prefs.default_seats, None);
+    assert_eq!(prefs.notifications.len(), preferences::MUTABLE.len());
+    assert!(prefs.notifications.values().all(|on| *on));
+    assert_eq!(
+        preferences::email_for(&pool, &user, GAME_PACKET)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("pf@example.com")
+    );
+
+    let prefs = preferences::update(
+        &pool,
+        user.id,
+        &PreferencesUpdate {
+            default_seats: Some(Some(3)),
+            contact_email: Some(Some(" tickets@example.com ".into())),
+            confirm_token: Some("tok-1".into()),
+            notifications: [(GAME_PACKET.to_string(), false)].into(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(prefs.default_seats, Some(3));
+    assert_eq!(prefs.contact_email, None);
+    assert_eq!(
+        prefs.pending_contact_email.as_deref(),
+        Some("tickets@example.com")
+    );
+    assert_eq!(preferences::get(&pool, user.id).await.unwrap(), prefs);
+
+    // The new address is used once its link is followed, and only once
+    assert_eq!(
+        preferences::pending_contact_email(&pool, "tok-1")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("tickets@example.com")
+    );
+    assert_eq!(
+        preferences::confirm_contact_email(&pool, "wrong")
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        preferences::confirm_contact_email(&pool, "tok-1")
+            .await
+            .unwrap(),
+        Some((user.id, "tickets@example.com".to_string()))
+    );
+    assert_eq!(
+        preferences::confirm_contact_email(&pool, "tok-1")
+            .await
+            .unwrap(),
+        None
+    );
+    let prefs = preferences::get(&pool, user.id).await.unwrap();
+    assert_eq!(prefs.contact_email.as_deref(), Some("tickets@example.com"));
+    assert_eq!(prefs.pending_contact_email, None);
+    assert_eq!(
+        preferences::email_for(&pool, &user, GAME_PACKET)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        preferences::email_for(&pool, &user, TICKETS_ASSIGNED)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("tickets@example.com")
+    );
+
+    // Leaving a setting out keeps it; null clears it
+    let prefs = preferences::update(
+        &pool,
+        user.id,
+        &PreferencesUpdate {
+            contact_email: Some(None),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(prefs.default_seats, Some(3));
+    assert_eq!(prefs.contact_email, None);
+    assert!(!prefs.wants(GAME_PACKET));
+
+    for (changes, message) in [
+        (
+            PreferencesUpdate {
+                default_seats: Some(Some(0)),
+                ..Default::default()
+            },
+            "default_seats must be at least 1",
+        ),
+        (
+            PreferencesUpdate {
+                contact_email: Some(Some("nobody".into())),
+                ..Default::default()
+            },
+            "contact_email must be an email address",
+        ),
+        (
+            PreferencesUpdate {
+                contact_email: Some(Some(format!("{}@example.com", "x".repeat(250)))),
+                confirm_token: Some("tok-2".into()),
+                ..Default::default()
+            },
+            "contact_email must be at most 255 characters",
+        ),
+        (
+            PreferencesUpdate {
+                contact_email: Some(Some("other@example.com".into())),
+                ..Default::default()
+            },
+            "needs a confirmation token",
+        ),
+        (
+            PreferencesUpdate {
+                notifications: [("admin_digest".to_string(), false)].into(),
+                ..Default::default()
+            },
+            "Unknown notification `admin_digest`",
+        ),
+    ] {
+        let err = preferences::update(&pool, user.id, &changes)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(message), "{err}");
+    }
+    assert_eq!(
+        preferences::get(&pool, user.id)
+            .await
+            .unwrap()
+            .default_seats,
+        Some(3)
+    );
+
+    // Only members who saved preferences are listed
+    let all = preferences::list(&pool).await.unwrap();
+    assert_eq!(all.len(), 1);
+    assert!(!all[&user.id].wants(GAME_PACKET));
+    assert!(!all.contains_key(&other.id));
+}
+
+#[tokio::test]
+async fn requests_list_by_game_and_status() {
+    let pool = test_pool().await;
+    for pk in [700801, 700802] {
+        gtm_db::upsert_game(&pool, &sample_game(pk)).await.unwrap();
+    }
+    let ann = gtm_db::upsert_user(&pool, "auth0|rl1", "rl1@example.com", "Ann")
+        .await
+        .unwrap();
+    let ben = gtm_db::upsert_user(&pool, "auth0|rl2", "rl2@example.com", "Ben")
+        .await
+        .unwrap();
+    let first = gtm_db::create_ticket_request(&pool, ann.id, GamePk(700801), 2, None)
+        .await
+        .unwrap();
+    let second = gtm_db::create_ticket_request(&pool, ben.id, GamePk(700801), 1, None)
+        .await
+        .unwrap();
+    let third = gtm_db::create_ticket_request(&pool, ann.id, GamePk(700802), 3, None)
+        .await
+        .unwrap();
+    gtm_db::update_request_approval(&pool, second.id, "declined", None)
+        .await
+        .unwrap();
+
+    let ids = |requests: Vec<gtm_models::TicketRequest>| -> Vec<i64> {
+        requests.iter().map(|r| r.id).collect()
+    };
+    assert_eq!(
+        ids(gtm_db::list_requests(&pool, None, None).await.unwrap()),
+        [first.id, second.id, third.id]
+    );
+    assert_eq!(
+        ids(gtm_db::list_requests(&pool, None, Some("pending"))
+            .await
+            .unwrap()),
+        [first.id, third.id]
+    );
+    assert_eq!(
+        ids(
+            gtm_db::list_requests(&pool, Some(GamePk(700801)), Some("declined"))
+                .await
+                .unwrap()
+        ),
+        [second.id]
+    );
+    assert_eq!(
+        ids(gtm_db::list_requests(&pool, Some(GamePk(700802)), None)
+            .await
+            .unwrap()),
+        [third.id]
+    );
+}
+
+#[tokio::test]
+async fn admin_approvals_denials_and_revokes_are_audited_with_the_admin() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(700502))
+        .await
+        .unwrap();
+    let seat = gtm_db::add_seat(&pool, "VR313", "E", "4", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let admin = gtm_db::upsert_user(&pool, "auth0|au3", "au3@example.com", "Admin")
+        .await
+        .unwrap();
+    let member = gtm_db::upsert_user(&pool, "auth0|au4", "au4@example.com", "Member")
+        .await
+        .unwrap();
+    assert!(
+        !gtm_db::api_tokens::has_admin_role(&pool, admin.id)
+            .await
+            .unwrap()
+    );
+    gtm_db::api_tokens::record_role(&pool, admin.id, true)
+        .await
+        .unwrap();
+    assert!(
+        gtm_db::api_tokens::has_admin_role(&pool, admin.id)
+            .await
+            .unwrap()
+    );
+
+    let approved = gtm_db::create_ticket_request(&pool, member.id, GamePk(700502), 1, None)
+        .await
+        .unwrap();
+    let ticket = gtm_db::list_tickets_for_game(&pool, GamePk(700502))
+        .await
+        .unwrap()[0]
+        .clone();
+    let mut warnings = gtm_db::Warnings::new();
+    let assigned = gtm_db::commit_allocation(
+        &pool,
+        &[(ticket.id, member.id, Some(approved.id))],
+        Some(admin.id),
+        false,
+        &mut warnings,
+    )
+    .await
+    .unwrap();
+    assert_eq!(assigned, 1);
+    gtm_db::revoke_ticket(&pool, ticket.id, Some(admin.id))
+        .await
+        .unwrap();
+
+    let declined = gtm_db::create_ticket_request(&pool, member.id, GamePk(700502), 2, None)
+        .await
+        .unwrap();
+    gtm_db::update_request_approval(&pool, declined.id, "declined", Some(admin.id))
+        .await
+        .unwrap();
+    let declined = gtm_db::get_ticket_request(&pool, declined.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(declined.status, "declined");
+
+    let entries = gtm_db::audit::list(&pool, Some(GamePk(700502)), None)
+        .await
+        .unwrap();
+    let mut actions: Vec<&str> = entries.iter().map(|e| e.action.as_str()).collect();
+    actions.sort_unstable();
+    assert_eq!(
+        actions,
+        vec![
+            gtm_db::audit::ASSIGN,
+            gtm_db::audit::REQUEST_STATUS,
+            gtm_db::audit::REQUEST_STATUS,
+            gtm_db::audit::REVOKE,
+        ]
+    );
+    assert!(entries.iter().all(|e| e.actor_id == Some(admin.id)));
+}
@@ -1,4 +1,7 @@
-use gtm_models::Game;
+// Shared by several test binaries; not every one uses every helper.
+#![allow(dead_code)]
+
+use gtm_models::{Game, GamePk, GameType};
 use sqlx::AnyPool;
 
 /// Create a fresh in-memory SQLite pool with all migrations applied.
@@ -17,7 +20,7 @@ pub async fn test_pool() -> AnyPool {
 /// Build a minimal Game struct suitable for testing.
 pub fn sample_game(game_pk: i64) -> Game {
     Game {
-        game_pk,
+        game_pk: GamePk(game_pk),
         game_guid: Some(format!("guid-{game_pk}")),
         game_type: "R".to_string(),
         season: "2026".to_string(),
@@ -45,5 +48,52 @@ pub fn sample_game(game_pk: i64) -> Game {
         game_number: 1,
         scheduled_innings: 9,
         is_tie: 0,
+        current_inning: None,
+        inning_state: None,
+        away_probable_pitcher: None,
+        home_probable_pitcher: None,
+        weather_condition: None,
+        weather_temp: None,
+        weather_wind: None,
+        tv_broadcasts: None,
+        reschedule_date: None,
+        reschedule_official_date: None,
+        rescheduled_to: None,
+        max_seats: None,
+        game_type_label: None,
+        first_pitch_local: None,
+        time_zone: None,
     }
 }
+
+/// The home team in [`sample_game`].
+pub const TEAM_ID: u32 = 137;
+
+/// Ticketed game types for tests: regular season only.
+pub const REGULAR: &[GameType] = &[GameType::Regular];
+
+/// One home game with one $40 seat billed wholly to a new member, invoiced
+/// for 2026-06. Returns the invoice id and the member.
+pub async fn open_invoice(pool: &AnyPool, game_pk: i64) -> (i64, gtm_models::UserId) {
+    let mut game = sample_game(game_pk);
+    game.official_date = "2026-06-03".to_string();
+    gtm_db::upsert_game(pool, &game).await.unwrap();
+    let seat = gtm_db::add_seat(pool, "VR316", "A", "1", None)
+        .await
+        .unwrap();
+    gtm_db::generate_tickets_for_all_seats(pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    gtm_db::update_seat_group_face_value(pool, "VR316", "A", Some(4000))
+        .await
+        .unwrap();
+    let payer = gtm_db::upsert_user(pool, "auth0|payer", "payer@example.com", "Payer")
+        .await
+        .unwrap();
+    gtm_db::ledger::set_split_rules(pool, Some(seat.id), &[(payer.id, 10_000)])
+        .await
+        .unwrap();
+    gtm_db::invoices::generate(pool, "2026-06").await.unwrap();
+    let invoice = gtm_db::invoices::for_user(pool, payer.id).await.unwrap();
+    (invoice[0].invoice.id, payer.id)
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::{REGULAR, TEAM_ID, sample_game, test_pool};
+use gtm_db::compat;
+use gtm_models::{GamePk, UserId};
+
+#[test]
+fn sqlite_returning_version_check() {
+    assert!(compat::sqlite_supports_returning("3.35.0"));
+    assert!(compat::sqlite_supports_returning("3.45.1"));
+    assert!(!compat::sqlite_supports_returning("3.34.1"));
+    assert!(!compat::sqlite_supports_returning("3.8.11"));
+}
+
+/// The same writes give the same rows with and without RETURNING. One test
+/// covers both modes because the switch is process-wide.
+#[tokio::test]
+async fn inserts_read_back_rows_with_and_without_returning() {
+    for returning in [true, false] {
+        compat::set_returning_supported(returning);
+        let pool = test_pool().await;
+
+        let seat = gtm_db::add_seat(&pool, "VR313", "C", "1", Some("aisle"))
+            .await
+            .unwrap();
+        assert_eq!(
+            (seat.section.as_str(), seat.notes.as_deref()),
+            ("VR313", Some("aisle"))
+        );
+        // Duplicate seats are still rejected
+        assert!(
+            gtm_db::add_seat(&pool, "VR313", "C", "1", None)
+                .await
+                .is_err()
+        );
+
+        let user = gtm_db::upsert_user(&pool, "auth0|compat", "a@example.com", "A")
+            .await
+            .unwrap();
+        let again = gtm_db::upsert_user(&pool, "auth0|compat", "b@example.com", "B")
+            .await
+            .unwrap();
+        assert_eq!(again.id, user.id);
+        assert_eq!(again.email, "b@example.com");
+
+        let phone = gtm_db::set_user_phone(
+            &pool,
+            user.id,
+            "+14155550123",
+            "123456",
+            "2099-01-01T00:00:00Z",
+        )
+        .await
+        .unwrap();
+        assert_eq!(phone.phone, "+14155550123");
+
+        gtm_db::upsert_game(&pool, &sample_game(900001))
+            .await
+            .unwrap();
+        let req = gtm_db::create_ticket_request(&pool, user.id, GamePk(900001), 2, None)
+            .await
+            .unwrap();
+        let edited = gtm_db::create_ticket_request(&pool, user.id, GamePk(900001), 3, Some("late"))
+            .await
+            .unwrap();
+        assert_eq!(edited.id, req.id);
+        assert_eq!(edited.seats_requested, 3);
+
+        let first = gtm_db::notifications::enqueue(&pool, &notification(user.id))
+            .await
+            .unwrap();
+        let second = gtm_db::notifications::enqueue(&pool, &notification(user.id))
+            .await
+            .unwrap();
+        assert_eq!(second, first + 1, "returning = {returning}");
+
+        let generated = gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+        assert_eq!(generated.created, vec![(GamePk(900001), seat.id)]);
+        let again = gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+        assert_eq!((again.created.len(), again.skipped), (0, 1));
+    }
+    compat::set_returning_supported(true);
+}
+
+fn notification(user_id: UserId) -> gtm_db::notifications::NewNotification<'static> {
+    gtm_db::notifications::NewNotification {
+        user_id: Some(user_id),
+        game_pk: None,
+        kind: gtm_db::notifications::TICKETS_ASSIGNED,
+        channel: gtm_db::notifications::EMAIL,
+        recipient: "a@example.com",
+        subject: "s",
+        body: "b",
+    }
+}
@@ -0,0 +1,189 @@
+use gtm_db::ConnectOptions;
+use std::time::Duration;
+
+#[tokio::test]
+async fn sqlite_connections_get_wal_and_busy_timeout() {
+    let path = std::env::temp_dir().join(format!("gtm-connect-{}.db", std::process::id()));
+    let url = format!("sqlite:{}?mode=rwc", path.display());
+    let options = ConnectOptions {
+        max_connections: 3,
+        acquire_timeout: Duration::from_millis(200),
+        sqlite_busy_timeout: Duration::from_millis(2_500),
+        ..ConnectOptions::default()
+    };
+    let pool = gtm_db::connect(&url, &options).await.unwrap();
+    gtm_db::migrate(&pool, &url).await.unwrap();
+
+    // Every pooled connection runs the pragmas, not just the first
+    let mut conns = Vec::new();
+    for _ in 0..3 {
+        let mut conn = pool.acquire().await.unwrap();
+        let mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(mode, "wal");
+        let timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(timeout, 2_500);
+        conns.push(conn);
+    }
+
+    // The pool is full, so the next acquire times out
+    assert!(pool.acquire().await.is_err());
+    drop(conns);
+    pool.close().await;
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+    }
+}
+
+/// Capture log lines from here on. The subscriber is global since SQLite
+/// statements run on sqlx's worker thread, so only one test may call this.
+fn capture_logs() -> std::sync::Arc<std::sync::Mutex<Vec<u8>>> {
+    let logs = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_ansi(false)
+        .with_writer(move || CaptureWriter(writer.clone()))
+        .finish();
+    tracing::subscriber::set_global_default(subscriber).unwrap();
+    logs
+}
+
+struct CaptureWriter(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn statements_are_logged_only_when_asked() {
+    let logs = capture_logs();
+    let quiet = ConnectOptions {
+        max_connections: 1,
+        ..ConnectOptions::default()
+    };
+    let pool = gtm_db::connect("sqlite::memory:", &quiet).await.unwrap();
+    sqlx::query("SELECT 'quiet'").execute(&pool).await.unwrap();
+    assert!(!String::from_utf8_lossy(&logs.lock().unwrap()).contains("SELECT 'quiet'"));
+
+    let verbose = ConnectOptions {
+        log_queries: true,
+        ..quiet
+    };
+    let pool = gtm_db::connect("sqlite::memory:", &verbose).await.unwrap();
+    gtm_db::migrate(&pool, "sqlite::memory:").await.unwrap();
+    gtm_db::allocation_summary(&pool, 137, None).await.unwrap();
+    let logs = String::from_utf8_lossy(&logs.lock().unwrap()).to_string();
+    // Logged inside the timed query's span, with how long it took
+    let line = logs
+        .lines()
+        .find(|l| l.contains("query{name=\"allocation_summary\"}"))
+        .unwrap_or_else(|| panic!("no allocation_summary statement in:\n{logs}"));
+    assert!(line.contains("elapsed"), "{line}");
+}
+
+#[test]
+fn wal_can_be_turned_off() {
+    let options = ConnectOptions {
+        sqlite_wal: false,
+        ..ConnectOptions::default()
+    };
+    assert_eq!(options.sqlite_pragmas(), vec!["PRAGMA busy_timeout = 5000"]);
+}
+
+#[test]
+fn only_read_only_postgres_pools_get_a_statement_timeout() {
+    let options = ConnectOptions {
+        statement_timeout: Some(Duration::from_secs(60)),
+        ..ConnectOptions::default()
+    };
+    // The primary runs migrations and long writes
+    assert!(options.postgres_session(false).is_empty());
+    assert_eq!(
+        options.postgres_session(true),
+        vec!["SET statement_timeout = 60000"]
+    );
+    let unlimited = ConnectOptions {
+        statement_timeout: None,
+        ..options
+    };
+    assert!(unlimited.postgres_session(true).is_empty());
+}
+
+#[tokio::test]
+async fn reads_fall_back_to_the_primary_without_a_replica() {
+    let options = ConnectOptions {
+        max_connections: 1,
+        acquire_timeout: Duration::from_millis(200),
+        ..ConnectOptions::default()
+    };
+    let primary = gtm_db::connect("sqlite::memory:", &options).await.unwrap();
+    sqlx::query("CREATE TABLE marker (id INTEGER)")
+        .execute(&primary)
+        .await
+        .unwrap();
+    let has_marker = |pool: &sqlx::AnyPool| {
+        let pool = pool.clone();
+        async move {
+            sqlx::query("SELECT id FROM marker")
+                .fetch_all(&pool)
+                .await
+                .is_ok()
+        }
+    };
+
+    let read = gtm_db::replica::ReadPool::primary_only(primary.clone());
+    assert!(!read.using_replica());
+    assert!(has_marker(read.pool()).await);
+
+    // An unreachable replica doesn't stop startup; reads use the primary
+    let read = gtm_db::replica::ReadPool::with_replica(
+        primary.clone(),
+        "sqlite:/nonexistent-gtm-dir/replica.db",
+        &options,
+    )
+    .await
+    .unwrap();
+    assert!(!read.using_replica());
+    assert!(has_marker(read.pool()).await);
+
+    // A replica that answers takes the reads
+    let read = gtm_db::replica::ReadPool::with_replica(primary, "sqlite::memory:", &options)
+        .await
+        .unwrap();
+    assert!(read.check().await);
+    assert!(read.using_replica());
+    assert!(!has_marker(read.pool()).await);
+
+    // A query error is the caller's; it doesn't move reads off the replica
+    let marker = |pool: sqlx::AnyPool| async move {
+        Ok(sqlx::query("SELECT id FROM marker")
+            .fetch_all(&pool)
+            .await?
+            .len())
+    };
+    assert!(read.run(marker).await.is_err());
+    assert!(read.using_replica());
+
+    // Losing the replica mid-read retries on the primary
+    let lost_replica = |pool: sqlx::AnyPool| async move {
+        match sqlx::query("SELECT id FROM marker").fetch_all(&pool).await {
+            Ok(rows) => Ok(rows.len()),
+            Err(_) => Err(sqlx::Error::PoolTimedOut.into()),
+        }
+    };
+    assert_eq!(read.run(lost_replica).await.unwrap(), 0);
+    assert!(!read.using_replica());
+}
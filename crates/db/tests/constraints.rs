@@ -1,13 +1,14 @@
 mod common;
 
-use common::{sample_game, test_pool};
+use common::{REGULAR, TEAM_ID, sample_game, test_pool};
+use gtm_models::GamePk;
 
 // --- pg() helper ---
 
 #[test]
 fn pg_translates_placeholders_when_sqlite() {
-    // When IS_POSTGRES is not set (or false, as in SQLite tests),
-    // pg() should return the SQL unchanged.
+    // Before connecting to another backend (as in SQLite tests), pg()
+    // should return the SQL unchanged.
     let sql = "SELECT * FROM games WHERE game_pk = ? AND season = ?";
     let result = gtm_db::pg(sql);
     assert_eq!(result, sql);
@@ -30,19 +31,21 @@ async fn assign_already_assigned_returns_false() {
         .await
         .unwrap();
 
-    gtm_db::generate_tickets_for_seat(&pool, seat.id)
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(600001))
         .await
         .unwrap();
-    let tickets = gtm_db::list_tickets_for_game(&pool, 600001).await.unwrap();
 
     // First assign succeeds
-    let ok = gtm_db::assign_ticket(&pool, tickets[0].id, user1.id)
+    let ok = gtm_db::assign_ticket(&pool, tickets[0].id, user1.id, None)
         .await
         .unwrap();
     assert!(ok);
 
     // Second assign to different user fails (ticket not available)
-    let ok = gtm_db::assign_ticket(&pool, tickets[0].id, user2.id)
+    let ok = gtm_db::assign_ticket(&pool, tickets[0].id, user2.id, None)
         .await
         .unwrap();
     assert!(!ok);
@@ -59,7 +62,7 @@ async fn withdraw_non_pending_returns_false() {
         .await
         .unwrap();
 
-    let req = gtm_db::create_ticket_request(&pool, user.id, 600002, 2, None)
+    let req = gtm_db::create_ticket_request(&pool, user.id, GamePk(600002), 2, None)
         .await
         .unwrap();
 
@@ -87,13 +90,17 @@ async fn revoke_available_ticket_returns_false() {
         .await
         .unwrap();
 
-    gtm_db::generate_tickets_for_seat(&pool, seat.id)
+    gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(600003))
         .await
         .unwrap();
-    let tickets = gtm_db::list_tickets_for_game(&pool, 600003).await.unwrap();
 
     // Ticket is available, not assigned — revoke should return false
-    let ok = gtm_db::revoke_ticket(&pool, tickets[0].id).await.unwrap();
+    let ok = gtm_db::revoke_ticket(&pool, tickets[0].id, None)
+        .await
+        .unwrap();
     assert!(!ok);
 }
 
@@ -108,10 +115,10 @@ async fn duplicate_request_upserts() {
         .await
         .unwrap();
 
-    let req1 = gtm_db::create_ticket_request(&pool, user.id, 600004, 2, None)
+    let req1 = gtm_db::create_ticket_request(&pool, user.id, GamePk(600004), 2, None)
         .await
         .unwrap();
-    let req2 = gtm_db::create_ticket_request(&pool, user.id, 600004, 4, Some("updated"))
+    let req2 = gtm_db::create_ticket_request(&pool, user.id, GamePk(600004), 4, Some("updated"))
         .await
         .unwrap();
 
@@ -120,7 +127,7 @@ async fn duplicate_request_upserts() {
     assert_eq!(req2.seats_requested, 4);
 
     // Only one request exists
-    let reqs = gtm_db::list_requests_for_user(&pool, user.id)
+    let reqs = gtm_db::list_requests_for_user(&pool, user.id, None)
         .await
         .unwrap();
     assert_eq!(reqs.len(), 1);
@@ -140,7 +147,7 @@ async fn update_request_wrong_user_returns_false() {
         .await
         .unwrap();
 
-    let req = gtm_db::create_ticket_request(&pool, user1.id, 600005, 2, None)
+    let req = gtm_db::create_ticket_request(&pool, user1.id, GamePk(600005), 2, None)
         .await
         .unwrap();
 
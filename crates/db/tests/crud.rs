@@ -1,7 +1,7 @@
 mod common;
 
-use common::{sample_game, test_pool};
-use gtm_models::Promotion;
+use common::{REGULAR, TEAM_ID, sample_game, test_pool};
+use gtm_models::{GamePk, GamePreview, LiveGame, Promotion};
 
 // --- Games ---
 
@@ -11,9 +11,11 @@ async fn upsert_and_list_games() {
     let game = sample_game(100001);
     gtm_db::upsert_game(&pool, &game).await.unwrap();
 
-    let games = gtm_db::list_games(&pool, None).await.unwrap();
+    let games = gtm_db::list_games(&pool, &Default::default())
+        .await
+        .unwrap();
     assert_eq!(games.len(), 1);
-    assert_eq!(games[0].game_pk, 100001);
+    assert_eq!(games[0].game_pk, GamePk(100001));
     assert_eq!(games[0].away_team_name, "Arizona Diamondbacks");
 }
 
@@ -23,14 +25,46 @@ async fn get_game_by_pk() {
     let game = sample_game(100002);
     gtm_db::upsert_game(&pool, &game).await.unwrap();
 
-    let found = gtm_db::get_game(&pool, 100002).await.unwrap();
+    let found = gtm_db::get_game(&pool, GamePk(100002)).await.unwrap();
     assert!(found.is_some());
     assert_eq!(found.unwrap().venue_name, "Oracle Park");
 
-    let missing = gtm_db::get_game(&pool, 999999).await.unwrap();
+    let missing = gtm_db::get_game(&pool, GamePk(999999)).await.unwrap();
     assert!(missing.is_none());
 }
 
+#[tokio::test]
+async fn game_times_are_shown_in_the_display_time_zone() {
+    let pool = test_pool().await;
+    let mut game = sample_game(100004);
+    // 7:05 PM in San Francisco is after midnight UTC
+    game.game_date = "2026-04-02T02:05:00Z".to_string();
+    gtm_db::upsert_game(&pool, &game).await.unwrap();
+
+    let mut found = gtm_db::get_game(&pool, GamePk(100004))
+        .await
+        .unwrap()
+        .unwrap();
+    let tz = chrono_tz::America::Los_Angeles;
+    let local = found.local_first_pitch(tz).unwrap();
+    assert_eq!(
+        local.format("%Y-%m-%d %-I:%M %p %Z").to_string(),
+        "2026-04-01 7:05 PM PDT"
+    );
+    found.set_local_time(tz);
+    assert_eq!(
+        found.first_pitch_local.as_deref(),
+        Some("2026-04-01T19:05:00-07:00")
+    );
+    assert_eq!(found.time_zone.as_deref(), Some("America/Los_Angeles"));
+
+    // A TBD start has no time to show, only the zone
+    found.start_time_tbd = 1;
+    found.set_local_time(tz);
+    assert_eq!(found.first_pitch(), None);
+    assert_eq!(found.first_pitch_local, None);
+}
+
 #[tokio::test]
 async fn upsert_game_updates_existing() {
     let pool = test_pool().await;
@@ -42,16 +76,450 @@ async fn upsert_game_updates_existing() {
     game.away_score = Some(3);
     gtm_db::upsert_game(&pool, &game).await.unwrap();
 
-    let found = gtm_db::get_game(&pool, 100003).await.unwrap().unwrap();
+    let found = gtm_db::get_game(&pool, GamePk(100003))
+        .await
+        .unwrap()
+        .unwrap();
     assert_eq!(found.status_detailed, "Final");
     assert_eq!(found.home_score, Some(5));
     assert_eq!(found.away_score, Some(3));
 
     // Should still be one game, not two
-    let games = gtm_db::list_games(&pool, None).await.unwrap();
+    let games = gtm_db::list_games(&pool, &Default::default())
+        .await
+        .unwrap();
     assert_eq!(games.len(), 1);
 }
 
+#[tokio::test]
+async fn live_updates_survive_schedule_rescrape() {
+    let pool = test_pool().await;
+    let game = sample_game(100004);
+    gtm_db::upsert_game(&pool, &game).await.unwrap();
+
+    let live = LiveGame {
+        game_pk: GamePk(100004),
+        status_abstract: "Live".to_string(),
+        status_detailed: "In Progress".to_string(),
+        status_code: "I".to_string(),
+        away_score: Some(1),
+        away_is_winner: None,
+        home_score: Some(2),
+        home_is_winner: None,
+        current_inning: Some(6),
+        inning_state: Some("Bottom".to_string()),
+    };
+    assert!(gtm_db::update_live_game(&pool, &live).await.unwrap());
+    let unknown = LiveGame {
+        game_pk: GamePk(999),
+        ..live.clone()
+    };
+    assert!(!gtm_db::update_live_game(&pool, &unknown).await.unwrap());
+
+    // A schedule scrape carries no linescore and leaves the inning alone
+    let mut rescraped = sample_game(100004);
+    rescraped.status_abstract = "Live".to_string();
+    gtm_db::upsert_game(&pool, &rescraped).await.unwrap();
+
+    let found = gtm_db::get_game(&pool, GamePk(100004))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.status_abstract, "Live");
+    assert_eq!(found.current_inning, Some(6));
+    assert_eq!(found.inning_state.as_deref(), Some("Bottom"));
+}
+
+#[tokio::test]
+async fn unhydrated_scrapes_keep_game_details() {
+    let pool = test_pool().await;
+    let mut game = sample_game(100005);
+    game.home_probable_pitcher = Some("Logan Webb".to_string());
+    game.weather_condition = Some("Sunny".to_string());
+    game.weather_temp = Some("64".to_string());
+    game.tv_broadcasts = Some("NBCS-BA, FOX".to_string());
+    gtm_db::upsert_game(&pool, &game).await.unwrap();
+
+    // A scrape without those hydrations sends none of them
+    gtm_db::upsert_game(&pool, &sample_game(100005))
+        .await
+        .unwrap();
+    let found = gtm_db::get_game(&pool, GamePk(100005))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.home_probable_pitcher.as_deref(), Some("Logan Webb"));
+    assert_eq!(found.away_probable_pitcher, None);
+    assert_eq!(found.weather_temp.as_deref(), Some("64"));
+    assert_eq!(found.tv_broadcasts.as_deref(), Some("NBCS-BA, FOX"));
+
+    // A later announcement replaces the stored value
+    game.home_probable_pitcher = Some("Robbie Ray".to_string());
+    gtm_db::upsert_game(&pool, &game).await.unwrap();
+    let found = gtm_db::get_game(&pool, GamePk(100005))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.home_probable_pitcher.as_deref(), Some("Robbie Ray"));
+}
+
+#[tokio::test]
+async fn list_games_filters_and_pages() {
+    let pool = test_pool().await;
+    for (pk, date, away, home) in [
+        (
+            100101,
+            "2026-04-01",
+            "Arizona Diamondbacks",
+            "San Francisco Giants",
+        ),
+        (
+            100102,
+            "2026-04-02",
+            "Los Angeles Dodgers",
+            "San Francisco Giants",
+        ),
+        (
+            100103,
+            "2026-05-10",
+            "San Francisco Giants",
+            "Los Angeles Dodgers",
+        ),
+        (
+            100104,
+            "2026-05-11",
+            "Colorado Rockies",
+            "San Francisco Giants",
+        ),
+    ] {
+        let mut game = sample_game(pk);
+        game.official_date = date.to_string();
+        game.game_date = format!("{date}T19:15:00Z");
+        game.away_team_name = away.to_string();
+        game.home_team_name = home.to_string();
+        if pk == 100103 {
+            game.home_team_id = 119;
+        }
+        if pk == 100101 {
+            game.status_abstract = "Final".to_string();
+        }
+        if pk == 100104 {
+            game.game_type = "F".to_string();
+        }
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+    }
+    let pks = |games: Vec<gtm_models::Game>| games.iter().map(|g| g.game_pk.0).collect::<Vec<_>>();
+
+    let dodgers = gtm_db::GameFilter {
+        opponent: Some("dodgers".to_string()),
+        team_id: TEAM_ID,
+        ..Default::default()
+    };
+    assert_eq!(
+        pks(gtm_db::list_games(&pool, &dodgers).await.unwrap()),
+        vec![100102, 100103]
+    );
+    // Only the opponent's name is searched, and wildcards are literal
+    for opponent in ["giants", "%", "_"] {
+        let filter = gtm_db::GameFilter {
+            opponent: Some(opponent.to_string()),
+            team_id: TEAM_ID,
+            ..Default::default()
+        };
+        assert!(
+            gtm_db::list_games(&pool, &filter).await.unwrap().is_empty(),
+            "{opponent}"
+        );
+    }
+
+    let home_dodgers = gtm_db::GameFilter {
+        home_only: true,
+        team_id: TEAM_ID,
+        ..dodgers
+    };
+    assert_eq!(
+        pks(gtm_db::list_games(&pool, &home_dodgers).await.unwrap()),
+        vec![100102]
+    );
+
+    let range = gtm_db::GameFilter {
+        from: Some("2026-04-02".to_string()),
+        to: Some("2026-05-10".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        pks(gtm_db::list_games(&pool, &range).await.unwrap()),
+        vec![100102, 100103]
+    );
+
+    let scheduled = gtm_db::GameFilter {
+        status: Some("preview".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(gtm_db::count_games(&pool, &scheduled).await.unwrap(), 3);
+
+    let postseason = gtm_db::GameFilter {
+        game_type: Some("f, w".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        pks(gtm_db::list_games(&pool, &postseason).await.unwrap()),
+        vec![100104]
+    );
+
+    // Paging applies after filters; the count ignores it
+    let page = gtm_db::GameFilter {
+        home_only: true,
+        team_id: TEAM_ID,
+        limit: Some(2),
+        offset: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(
+        pks(gtm_db::list_games(&pool, &page).await.unwrap()),
+        vec![100102, 100104]
+    );
+    assert_eq!(gtm_db::count_games(&pool, &page).await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn game_filters_by_day_of_week_day_night_and_promotions() {
+    let pool = test_pool().await;
+    for (pk, date, day_night) in [
+        (100301, "2026-04-03", "night"), // Friday
+        (100302, "2026-04-04", "day"),   // Saturday
+        (100303, "2026-04-05", "night"), // Sunday
+        (100304, "2026-04-08", "day"),   // Wednesday
+    ] {
+        let mut game = sample_game(pk);
+        game.official_date = date.to_string();
+        game.game_date = format!("{date}T19:15:00Z");
+        game.day_night = Some(day_night.to_string());
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+    }
+    let promo = Promotion {
+        offer_id: 3,
+        game_pk: GamePk(100302),
+        name: "Bobblehead".to_string(),
+        offer_type: None,
+        description: None,
+        distribution: None,
+        presented_by: None,
+        alt_page_url: None,
+        ticket_link: None,
+        thumbnail_url: None,
+        image_url: None,
+        display_order: 1,
+    };
+    gtm_db::upsert_promotion(&pool, &promo).await.unwrap();
+    let pks = |games: Vec<gtm_models::Game>| games.iter().map(|g| g.game_pk.0).collect::<Vec<_>>();
+    let list =
+        async |filter: gtm_db::GameFilter| pks(gtm_db::list_games(&pool, &filter).await.unwrap());
+
+    let weekend = gtm_db::GameFilter {
+        weekend: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(list(weekend).await, vec![100302, 100303]);
+    let weekdays = gtm_db::GameFilter {
+        weekend: Some(false),
+        ..Default::default()
+    };
+    assert_eq!(list(weekdays).await, vec![100301, 100304]);
+
+    let fri_sat = gtm_db::GameFilter {
+        days: Some("fri, SATURDAY".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(fri_sat.days().unwrap(), vec![5, 6]);
+    assert_eq!(list(fri_sat).await, vec![100301, 100302]);
+
+    let day_games = gtm_db::GameFilter {
+        day_night: Some("Day".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(list(day_games).await, vec![100302, 100304]);
+
+    let with_promos = gtm_db::GameFilter {
+        has_promotions: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(list(with_promos).await, vec![100302]);
+    let without = gtm_db::GameFilter {
+        has_promotions: Some(false),
+        weekend: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(list(without).await, vec![100303]);
+
+    let bad = gtm_db::GameFilter {
+        days: Some("funday".to_string()),
+        ..Default::default()
+    };
+    assert!(bad.days().is_err());
+}
+
+#[tokio::test]
+async fn list_games_with_details_aggregates_promotions_and_tickets() {
+    let pool = test_pool().await;
+    for (pk, date) in [(100201, "2026-04-01"), (100202, "2026-04-02")] {
+        let mut game = sample_game(pk);
+        game.official_date = date.to_string();
+        game.game_date = format!("{date}T19:15:00Z");
+        gtm_db::upsert_game(&pool, &game).await.unwrap();
+    }
+    for (offer_id, name, display_order) in [(2, "Fireworks", 2), (1, "Bobblehead, \"Webb\"", 1)] {
+        let promo = Promotion {
+            offer_id,
+            game_pk: GamePk(100201),
+            name: name.to_string(),
+            offer_type: None,
+            description: None,
+            distribution: None,
+            presented_by: None,
+            alt_page_url: None,
+            ticket_link: None,
+            thumbnail_url: None,
+            image_url: None,
+            display_order,
+        };
+        gtm_db::upsert_promotion(&pool, &promo).await.unwrap();
+    }
+    for n in ["1", "2"] {
+        let seat = gtm_db::add_seat(&pool, "VR314", "D", n, None)
+            .await
+            .unwrap();
+        gtm_db::generate_tickets_for_seat(&pool, seat.id, TEAM_ID, REGULAR)
+            .await
+            .unwrap();
+    }
+    let user = gtm_db::upsert_user(&pool, "auth0|gd1", "gd@example.com", "Gus")
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(100202))
+        .await
+        .unwrap();
+    gtm_db::assign_ticket(&pool, tickets[0].id, user.id, None)
+        .await
+        .unwrap();
+
+    let games = gtm_db::list_games_with_details(&pool, &Default::default())
+        .await
+        .unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(
+        games[0].promotions.as_deref(),
+        Some(&["Bobblehead, \"Webb\"".to_string(), "Fireworks".to_string()][..])
+    );
+    assert_eq!(games[1].promotions.as_deref(), Some(&[][..]));
+    let counts = |total, available| Some(gtm_models::TicketCounts { total, available });
+    assert_eq!(games[0].tickets, counts(2, 2));
+    assert_eq!(games[1].tickets, counts(2, 1));
+
+    // Paging applies to the games, not the joined rows
+    let page = gtm_db::GameFilter {
+        limit: Some(1),
+        offset: Some(1),
+        ..Default::default()
+    };
+    let games = gtm_db::list_games_with_details(&pool, &page).await.unwrap();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0].game.game_pk, GamePk(100202));
+}
+
+#[tokio::test]
+async fn saved_views_round_trip_filters_by_name() {
+    use gtm_db::saved_views;
+
+    let pool = test_pool().await;
+    let ann = gtm_db::upsert_user(&pool, "auth0|sv1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bo = gtm_db::upsert_user(&pool, "auth0|sv2", "bo@example.com", "Bo")
+        .await
+        .unwrap();
+    let dodgers = gtm_db::GameFilter {
+        opponent: Some("dodgers".to_string()),
+        home_only: true,
+        ..Default::default()
+    };
+    let view = saved_views::save(&pool, ann.id, " rivals ", &dodgers)
+        .await
+        .unwrap();
+    assert_eq!(view.name, "rivals");
+    assert_eq!(view.filter.opponent.as_deref(), Some("dodgers"));
+    assert!(
+        saved_views::save(&pool, ann.id, "  ", &dodgers)
+            .await
+            .is_err()
+    );
+
+    // Saving under the same name replaces the filter
+    let may = gtm_db::GameFilter {
+        month: Some(5),
+        ..Default::default()
+    };
+    let again = saved_views::save(&pool, ann.id, "rivals", &may)
+        .await
+        .unwrap();
+    assert_eq!(again.id, view.id);
+    let fetched = saved_views::get(&pool, view.id).await.unwrap().unwrap();
+    assert_eq!(
+        (fetched.filter.month, fetched.filter.home_only),
+        (Some(5), false)
+    );
+
+    saved_views::save(&pool, bo.id, "rivals", &dodgers)
+        .await
+        .unwrap();
+    assert_eq!(
+        saved_views::find_by_name(&pool, "rivals", None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    let bos = saved_views::find_by_name(&pool, "rivals", Some(bo.id))
+        .await
+        .unwrap();
+    assert_eq!(bos.len(), 1);
+    assert!(bos[0].filter.home_only);
+
+    assert!(!saved_views::delete(&pool, bo.id, view.id).await.unwrap());
+    assert!(saved_views::delete(&pool, ann.id, view.id).await.unwrap());
+    assert!(saved_views::list(&pool, ann.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn list_home_games_on_date_includes_double_header() {
+    let pool = test_pool().await;
+    let mut g1 = sample_game(100004);
+    g1.official_date = "2026-06-12".to_string();
+    g1.double_header = "S".to_string();
+    let mut g2 = sample_game(100005);
+    g2.official_date = "2026-06-12".to_string();
+    g2.double_header = "S".to_string();
+    g2.game_number = 2;
+    let mut away = sample_game(100006);
+    away.official_date = "2026-06-13".to_string();
+    away.home_team_name = "Los Angeles Dodgers".to_string();
+    away.home_team_id = 119;
+    for g in [&g2, &g1, &away] {
+        gtm_db::upsert_game(&pool, g).await.unwrap();
+    }
+
+    let games = gtm_db::list_home_games_on_date(&pool, TEAM_ID, "2026-06-12")
+        .await
+        .unwrap();
+    let pks: Vec<i64> = games.iter().map(|g| g.game_pk.0).collect();
+    assert_eq!(pks, vec![100004, 100005]);
+
+    let none = gtm_db::list_home_games_on_date(&pool, TEAM_ID, "2026-06-13")
+        .await
+        .unwrap();
+    assert!(none.is_empty());
+}
+
 // --- Users ---
 
 #[tokio::test]
@@ -84,6 +552,24 @@ async fn get_user_by_sub() {
     assert!(missing.is_none());
 }
 
+#[tokio::test]
+async fn get_user_by_email_ignores_case() {
+    let pool = test_pool().await;
+    gtm_db::upsert_user(&pool, "auth0|mail1", "Carol@Example.com", "Carol")
+        .await
+        .unwrap();
+
+    let found = gtm_db::get_user_by_email(&pool, "carol@example.com")
+        .await
+        .unwrap();
+    assert_eq!(found.unwrap().name, "Carol");
+
+    let missing = gtm_db::get_user_by_email(&pool, "nobody@example.com")
+        .await
+        .unwrap();
+    assert!(missing.is_none());
+}
+
 #[tokio::test]
 async fn upsert_user_updates_existing() {
     let pool = test_pool().await;
@@ -103,6 +589,281 @@ async fn upsert_user_updates_existing() {
     assert_eq!(users.len(), 1);
 }
 
+#[tokio::test]
+async fn api_usage_aggregates_by_day_member_and_endpoint() {
+    use gtm_db::usage;
+
+    let pool = test_pool().await;
+    let ann = gtm_db::upsert_user(&pool, "auth0|use1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bo = gtm_db::upsert_user(&pool, "auth0|use2", "bo@example.com", "Bo")
+        .await
+        .unwrap();
+    for (day, user, endpoint) in [
+        ("2026-05-01", ann.id, "GET /api/games"),
+        ("2026-05-01", ann.id, "GET /api/games"),
+        ("2026-05-01", bo.id, "GET /api/games"),
+        ("2026-05-02", ann.id, "POST /api/requests"),
+        ("2026-06-01", bo.id, "GET /api/games"),
+    ] {
+        usage::record(&pool, day, Some(user), endpoint, 1)
+            .await
+            .unwrap();
+    }
+    // Public requests count towards days and endpoints but no member
+    let counter = usage::UsageCounter::new();
+    counter.bump("2026-05-02", None, "GET /api/games");
+    counter.bump("2026-05-02", None, "GET /api/games");
+    assert_eq!(counter.flush(&pool).await.unwrap(), 2);
+    assert_eq!(counter.flush(&pool).await.unwrap(), 0);
+
+    let report = usage::report(&pool, "2026-05-01", "2026-05-31")
+        .await
+        .unwrap();
+    let days: Vec<(&str, i64, i64)> = report
+        .days
+        .iter()
+        .map(|d| (d.day.as_str(), d.requests, d.users))
+        .collect();
+    assert_eq!(days, vec![("2026-05-01", 3, 2), ("2026-05-02", 3, 1)]);
+    assert_eq!(report.users[0].user_name, "Ann");
+    assert_eq!(report.users[0].requests, 3);
+    assert_eq!(report.users[0].active_days, 2);
+    assert_eq!(report.users[0].last_seen, "2026-05-02");
+    assert_eq!(report.users[1].requests, 1);
+    assert_eq!(report.users.len(), 2);
+    assert_eq!(report.endpoints[0].endpoint, "GET /api/games");
+    assert_eq!(report.endpoints[0].requests, 5);
+    assert_eq!(report.endpoints[0].users, 2);
+}
+
+#[tokio::test]
+async fn external_refs_map_entities_to_other_systems() {
+    use gtm_db::external_refs::{self, RefFilter};
+    use gtm_models::EntityKind;
+
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(100010))
+        .await
+        .unwrap();
+    gtm_db::upsert_game(&pool, &sample_game(100011))
+        .await
+        .unwrap();
+
+    let notion = external_refs::create(&pool, EntityKind::Game, 100010, " notion ", "page-1")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(notion.system, "notion");
+    assert!(
+        external_refs::create(&pool, EntityKind::Game, 999, "notion", "page-9")
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        external_refs::create(&pool, EntityKind::Game, 100010, "airtable", " ")
+            .await
+            .is_err()
+    );
+    // One ID per system per entity, and an ID names one entity
+    assert!(
+        external_refs::create(&pool, EntityKind::Game, 100010, "notion", "page-2")
+            .await
+            .is_err()
+    );
+    assert!(
+        external_refs::create(&pool, EntityKind::Game, 100011, "notion", "page-1")
+            .await
+            .is_err()
+    );
+
+    let found = external_refs::list(
+        &pool,
+        &RefFilter {
+            system: Some("notion".to_string()),
+            external_id: Some("page-1".to_string()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].entity_id, 100010);
+
+    let updated = external_refs::update(&pool, notion.id, "page-1b")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.external_id, "page-1b");
+
+    let games = gtm_db::list_games(&pool, &Default::default())
+        .await
+        .unwrap();
+    let games = external_refs::attach(&pool, EntityKind::Game, games, |g| g.game_pk.0)
+        .await
+        .unwrap();
+    let refs: Vec<usize> = games.iter().map(|g| g.external_refs.len()).collect();
+    assert_eq!(refs, vec![1, 0]);
+    let json = serde_json::to_value(&games[1]).unwrap();
+    assert_eq!(json["game_pk"], 100011);
+    assert!(json.get("external_refs").is_none());
+
+    assert!(external_refs::delete(&pool, notion.id).await.unwrap());
+    assert!(!external_refs::delete(&pool, notion.id).await.unwrap());
+}
+
+#[tokio::test]
+async fn api_tokens_authenticate_until_revoked() {
+    use gtm_db::api_tokens;
+
+    let pool = test_pool().await;
+    let ann = gtm_db::upsert_user(&pool, "auth0|tok1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let bo = gtm_db::upsert_user(&pool, "auth0|tok2", "bo@example.com", "Bo")
+        .await
+        .unwrap();
+    let now = chrono::Utc::now();
+    let later = "2099-01-01T00:00:00Z";
+    let token = "gtm_0123456789abcdef0123456789abcdef";
+    let minted = api_tokens::create(
+        &pool,
+        ann.id,
+        " laptop ",
+        api_tokens::MEMBER_SCOPE,
+        token,
+        later,
+    )
+    .await
+    .unwrap();
+    assert_eq!(minted.name, "laptop");
+    assert_eq!(minted.prefix, "gtm_0123");
+    assert!(minted.last_used_at.is_none());
+    assert!(
+        api_tokens::create(&pool, ann.id, "", api_tokens::MEMBER_SCOPE, "gtm_x", later)
+            .await
+            .is_err()
+    );
+    assert!(
+        api_tokens::create(&pool, ann.id, "root", "superuser", "gtm_y", later)
+            .await
+            .is_err()
+    );
+
+    let (user, scope) = api_tokens::authenticate(&pool, token, now)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.id, ann.id);
+    assert_eq!(scope, api_tokens::MEMBER_SCOPE);
+    assert!(
+        api_tokens::authenticate(&pool, "gtm_wrong", now)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let listed = api_tokens::list_for_user(&pool, ann.id).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert!(listed[0].last_used_at.is_some());
+
+    // Only the owner can revoke, and only once
+    assert!(!api_tokens::revoke(&pool, minted.id, bo.id).await.unwrap());
+    assert!(api_tokens::revoke(&pool, minted.id, ann.id).await.unwrap());
+    assert!(!api_tokens::revoke(&pool, minted.id, ann.id).await.unwrap());
+    assert!(
+        api_tokens::authenticate(&pool, token, now)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // Expired tokens stop working
+    let short = "gtm_short";
+    api_tokens::create(
+        &pool,
+        ann.id,
+        "ci",
+        api_tokens::MEMBER_SCOPE,
+        short,
+        "2026-01-01T00:00:00Z",
+    )
+    .await
+    .unwrap();
+    let before = chrono::DateTime::parse_from_rfc3339("2025-12-31T00:00:00Z")
+        .unwrap()
+        .to_utc();
+    assert!(
+        api_tokens::authenticate(&pool, short, before)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        api_tokens::authenticate(&pool, short, now)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn admin_tokens_follow_the_members_current_role() {
+    use gtm_db::api_tokens;
+
+    let pool = test_pool().await;
+    let now = chrono::Utc::now();
+    let ann = gtm_db::upsert_user(&pool, "auth0|adm1", "ann@example.com", "Ann")
+        .await
+        .unwrap();
+    let token = "gtm_admin";
+    api_tokens::create(
+        &pool,
+        ann.id,
+        "ops",
+        api_tokens::ADMIN_SCOPE,
+        token,
+        "2099-01-01T00:00:00Z",
+    )
+    .await
+    .unwrap();
+    let scope = |pool: sqlx::AnyPool| async move {
+        api_tokens::authenticate(&pool, token, now)
+            .await
+            .unwrap()
+            .unwrap()
+            .1
+    };
+
+    api_tokens::record_role(&pool, ann.id, true).await.unwrap();
+    assert_eq!(scope(pool.clone()).await, api_tokens::ADMIN_SCOPE);
+    // Demoted: the token still works, as a member's
+    api_tokens::record_role(&pool, ann.id, false).await.unwrap();
+    assert_eq!(scope(pool.clone()).await, api_tokens::MEMBER_SCOPE);
+
+    // Uses within a few minutes of the last recorded one aren't recorded
+    sqlx::query("UPDATE api_tokens SET last_used_at = '2026-05-01 12:00:00'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let at = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+    let last_used = |pool: sqlx::AnyPool| async move {
+        api_tokens::list_for_user(&pool, ann.id).await.unwrap()[0]
+            .last_used_at
+            .clone()
+            .unwrap()
+    };
+    api_tokens::authenticate(&pool, token, at("2026-05-01T12:03:00Z"))
+        .await
+        .unwrap();
+    assert_eq!(last_used(pool.clone()).await, "2026-05-01 12:00:00");
+    api_tokens::authenticate(&pool, token, at("2026-05-01T12:06:00Z"))
+        .await
+        .unwrap();
+    assert_ne!(last_used(pool.clone()).await, "2026-05-01 12:00:00");
+}
+
 // --- Seats ---
 
 #[tokio::test]
@@ -149,21 +910,67 @@ async fn create_and_list_ticket_requests() {
         .await
         .unwrap();
 
-    let req = gtm_db::create_ticket_request(&pool, user.id, 200001, 2, Some("please"))
+    let req = gtm_db::create_ticket_request(&pool, user.id, GamePk(200001), 2, Some("please"))
         .await
         .unwrap();
-    assert_eq!(req.game_pk, 200001);
+    assert_eq!(req.game_pk, GamePk(200001));
     assert_eq!(req.seats_requested, 2);
     assert_eq!(req.status, "pending");
     assert_eq!(req.notes.as_deref(), Some("please"));
 
-    let reqs = gtm_db::list_requests_for_user(&pool, user.id)
+    let reqs = gtm_db::list_requests_for_user(&pool, user.id, None)
         .await
         .unwrap();
     assert_eq!(reqs.len(), 1);
     assert_eq!(reqs[0].id, req.id);
 }
 
+#[tokio::test]
+async fn request_comments_thread() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(200002))
+        .await
+        .unwrap();
+    let member = gtm_db::upsert_user(&pool, "auth0|rc1", "rc@example.com", "Member")
+        .await
+        .unwrap();
+    let admin = gtm_db::upsert_user(&pool, "auth0|rc2", "rcadmin@example.com", "Admin")
+        .await
+        .unwrap();
+    let req = gtm_db::create_ticket_request(&pool, member.id, GamePk(200002), 2, None)
+        .await
+        .unwrap();
+    let quiet = gtm_db::create_ticket_request(&pool, member.id, GamePk(200002), 1, None)
+        .await
+        .unwrap();
+
+    let first = gtm_db::request_comments::create(&pool, req.id, member.id, false, "  Aisle? ")
+        .await
+        .unwrap();
+    assert_eq!(first.body, "Aisle?");
+    assert_eq!(first.author_name, "Member");
+    assert_eq!(first.from_admin, 0);
+    gtm_db::request_comments::create(&pool, req.id, admin.id, true, "Will try")
+        .await
+        .unwrap();
+    assert!(
+        gtm_db::request_comments::create(&pool, req.id, member.id, false, "   ")
+            .await
+            .is_err()
+    );
+
+    let thread = gtm_db::request_comments::list(&pool, req.id).await.unwrap();
+    assert_eq!(thread.len(), 2);
+    assert_eq!(thread[0].id, first.id);
+
+    let latest = gtm_db::request_comments::latest(&pool, &[req.id, quiet.id])
+        .await
+        .unwrap();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[&req.id].body, "Will try");
+    assert_eq!(latest[&req.id].from_admin, 1);
+}
+
 // --- Game Tags ---
 
 #[tokio::test]
@@ -176,7 +983,7 @@ async fn upsert_and_list_game_tags() {
         .unwrap();
 
     // Set shortlist
-    gtm_db::upsert_game_tag(&pool, user.id, 300001, true, false)
+    gtm_db::upsert_game_tag(&pool, user.id, GamePk(300001), true, false)
         .await
         .unwrap();
     let tags = gtm_db::list_game_tags_for_user(&pool, user.id)
@@ -187,7 +994,7 @@ async fn upsert_and_list_game_tags() {
     assert_eq!(tags[0].cant_go, 0);
 
     // Toggle to can't go
-    gtm_db::upsert_game_tag(&pool, user.id, 300001, false, true)
+    gtm_db::upsert_game_tag(&pool, user.id, GamePk(300001), false, true)
         .await
         .unwrap();
     let tags = gtm_db::list_game_tags_for_user(&pool, user.id)
@@ -198,7 +1005,7 @@ async fn upsert_and_list_game_tags() {
     assert_eq!(tags[0].cant_go, 1);
 
     // Both false deletes the row
-    gtm_db::upsert_game_tag(&pool, user.id, 300001, false, false)
+    gtm_db::upsert_game_tag(&pool, user.id, GamePk(300001), false, false)
         .await
         .unwrap();
     let tags = gtm_db::list_game_tags_for_user(&pool, user.id)
@@ -217,7 +1024,7 @@ async fn upsert_and_get_promotions() {
 
     let promo = Promotion {
         offer_id: 1,
-        game_pk: 400001,
+        game_pk: GamePk(400001),
         name: "Bobblehead Night".to_string(),
         offer_type: Some("Giveaway".to_string()),
         description: Some("First 20,000 fans".to_string()),
@@ -231,7 +1038,7 @@ async fn upsert_and_get_promotions() {
     };
     gtm_db::upsert_promotion(&pool, &promo).await.unwrap();
 
-    let promos = gtm_db::get_promotions_for_game(&pool, 400001)
+    let promos = gtm_db::get_promotions_for_game(&pool, GamePk(400001))
         .await
         .unwrap();
     assert_eq!(promos.len(), 1);
@@ -245,9 +1052,217 @@ async fn upsert_and_get_promotions() {
     gtm_db::upsert_promotion(&pool, &updated_promo)
         .await
         .unwrap();
-    let promos = gtm_db::get_promotions_for_game(&pool, 400001)
+    let promos = gtm_db::get_promotions_for_game(&pool, GamePk(400001))
         .await
         .unwrap();
     assert_eq!(promos.len(), 1);
     assert_eq!(promos[0].name, "Updated Bobblehead Night");
 }
+
+#[tokio::test]
+async fn search_promotions_filters_by_type_name_and_month() {
+    let pool = test_pool().await;
+    let mut june = sample_game(400201);
+    june.official_date = "2026-06-12".to_string();
+    let mut july = sample_game(400202);
+    july.official_date = "2026-07-03".to_string();
+    gtm_db::upsert_game(&pool, &june).await.unwrap();
+    gtm_db::upsert_game(&pool, &july).await.unwrap();
+
+    let promo =
+        |offer_id, game_pk, name: &str, offer_type: &str, distribution: Option<&str>| Promotion {
+            offer_id,
+            game_pk: GamePk(game_pk),
+            name: name.to_string(),
+            offer_type: Some(offer_type.to_string()),
+            description: None,
+            distribution: distribution.map(str::to_string),
+            presented_by: None,
+            alt_page_url: None,
+            ticket_link: None,
+            thumbnail_url: None,
+            image_url: None,
+            display_order: 1,
+        };
+    for p in [
+        promo(
+            1,
+            400201,
+            "Buster Posey Bobblehead",
+            "Giveaway",
+            Some("First 20,000 fans"),
+        ),
+        promo(2, 400201, "Pride Night", "Theme Game", None),
+        promo(3, 400202, "Fireworks", "Special Event", None),
+        promo(
+            4,
+            400202,
+            "Logan Webb Bobblehead",
+            "Giveaway",
+            Some("All fans"),
+        ),
+    ] {
+        gtm_db::upsert_promotion(&pool, &p).await.unwrap();
+    }
+
+    let search = |filter: gtm_db::PromotionFilter| {
+        let pool = pool.clone();
+        async move {
+            gtm_db::search_promotions(&pool, &filter)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|p| p.offer_id)
+                .collect::<Vec<_>>()
+        }
+    };
+    // `type` matches the name too, since most giveaways are just "Giveaway"
+    let bobbleheads = gtm_db::PromotionFilter {
+        offer_type: Some("BOBBLEHEAD".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(search(bobbleheads.clone()).await, vec![1, 4]);
+    assert_eq!(
+        search(gtm_db::PromotionFilter {
+            month: Some(6),
+            ..bobbleheads
+        })
+        .await,
+        vec![1]
+    );
+    assert_eq!(
+        search(gtm_db::PromotionFilter {
+            offer_type: Some("giveaway".to_string()),
+            distribution: Some("20,000".to_string()),
+            ..Default::default()
+        })
+        .await,
+        vec![1]
+    );
+    assert_eq!(
+        search(gtm_db::PromotionFilter {
+            name: Some("fireworks".to_string()),
+            ..Default::default()
+        })
+        .await,
+        vec![3]
+    );
+    assert_eq!(search(Default::default()).await.len(), 4);
+}
+
+#[tokio::test]
+async fn upsert_and_get_game_preview() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(400101))
+        .await
+        .unwrap();
+    assert!(
+        gtm_db::previews::get(&pool, GamePk(400101))
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let preview = GamePreview {
+        game_pk: GamePk(400101),
+        away_probable_pitcher: None,
+        home_probable_pitcher: Some("Logan Webb".to_string()),
+        away_record: Some("12-8".to_string()),
+        home_record: Some("10-10".to_string()),
+        away_streak: Some("W3".to_string()),
+        home_streak: None,
+        body: "Dodgers at Giants".to_string(),
+        generated_at: String::new(),
+    };
+    gtm_db::previews::upsert(&pool, &preview).await.unwrap();
+
+    // Regenerating replaces the stored preview
+    let regenerated = GamePreview {
+        away_probable_pitcher: Some("Yoshinobu Yamamoto".to_string()),
+        body: "Dodgers at Giants, Yamamoto vs Webb".to_string(),
+        ..preview
+    };
+    gtm_db::previews::upsert(&pool, &regenerated).await.unwrap();
+    let stored = gtm_db::previews::get(&pool, GamePk(400101))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        stored.away_probable_pitcher.as_deref(),
+        Some("Yoshinobu Yamamoto")
+    );
+    assert_eq!(stored.home_probable_pitcher.as_deref(), Some("Logan Webb"));
+    assert_eq!(stored.body, "Dodgers at Giants, Yamamoto vs Webb");
+    assert!(!stored.generated_at.is_empty());
+}
+
+#[tokio::test]
+async fn seat_group_delete_and_regenerate() {
+    let pool = test_pool().await;
+    gtm_db::upsert_game(&pool, &sample_game(900001))
+        .await
+        .unwrap();
+    let s1 = gtm_db::add_seat(&pool, "VR313", "G", "1", None)
+        .await
+        .unwrap();
+    let s2 = gtm_db::add_seat(&pool, "VR313", "G", "2", None)
+        .await
+        .unwrap();
+    gtm_db::add_seat(&pool, "VR313", "H", "1", None)
+        .await
+        .unwrap();
+
+    // Nothing generated yet: one ticket per seat
+    let regen = gtm_db::regenerate_seat_group_tickets(&pool, "VR313", "G", TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(regen.len(), 2);
+    assert!(regen.iter().all(|o| o.tickets_generated == 1));
+    let regen = gtm_db::regenerate_seat_group_tickets(&pool, "VR313", "G", TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert!(regen.iter().all(|o| o.tickets_generated == 0));
+
+    // An assigned ticket survives the group delete
+    let user = gtm_db::upsert_user(&pool, "auth0|sg", "sg@example.com", "Group")
+        .await
+        .unwrap();
+    let tickets = gtm_db::list_tickets_for_game(&pool, GamePk(900001))
+        .await
+        .unwrap();
+    let t1 = tickets.iter().find(|t| t.seat_id == s1.id).unwrap();
+    gtm_db::assign_ticket(&pool, t1.id, user.id, None)
+        .await
+        .unwrap();
+
+    let deleted = gtm_db::delete_seat_group(&pool, "VR313", "G")
+        .await
+        .unwrap();
+    assert_eq!(deleted.len(), 2);
+    let d1 = deleted.iter().find(|o| o.seat.id == s1.id).unwrap();
+    assert_eq!((d1.tickets_removed, d1.tickets_kept), (0, 1));
+    let d2 = deleted.iter().find(|o| o.seat.id == s2.id).unwrap();
+    assert_eq!((d2.tickets_removed, d2.tickets_kept), (1, 0));
+
+    let seats = gtm_db::list_seats(&pool).await.unwrap();
+    assert_eq!(seats.len(), 1);
+    assert_eq!(seats[0].row, "H");
+    assert!(
+        gtm_db::delete_seat_group(&pool, "VR313", "G")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Re-adding restores the same seat; adding it twice still fails
+    let restored = gtm_db::add_seat(&pool, "VR313", "G", "2", Some("back"))
+        .await
+        .unwrap();
+    assert_eq!(restored.id, s2.id);
+    assert_eq!(restored.notes.as_deref(), Some("back"));
+    assert!(
+        gtm_db::add_seat(&pool, "VR313", "G", "2", None)
+            .await
+            .is_err()
+    );
+}
@@ -0,0 +1,133 @@
+use gtm_db::Backend;
+use gtm_db::dialect::{adapt, day_of_week, quote_ident};
+
+#[test]
+fn backends_come_from_the_url_scheme() {
+    assert_eq!(Backend::from_url("sqlite:gtm.db"), Some(Backend::Sqlite));
+    assert_eq!(
+        Backend::from_url("postgres://db/gtm"),
+        Some(Backend::Postgres)
+    );
+    assert_eq!(
+        Backend::from_url("postgresql://db/gtm"),
+        Some(Backend::Postgres)
+    );
+    assert_eq!(Backend::from_url("mysql://db/gtm"), Some(Backend::MySql));
+    assert_eq!(Backend::from_url("mariadb://db/gtm"), Some(Backend::MySql));
+    assert_eq!(Backend::from_url("oracle://db/gtm"), None);
+    assert!(gtm_db::backend_for("oracle://db/gtm").is_err());
+}
+
+#[test]
+fn sqlite_is_unchanged_and_postgres_numbers_placeholders() {
+    let sql = "SELECT * FROM games WHERE game_pk = ? AND season = ?";
+    assert_eq!(adapt(sql, Backend::Sqlite), sql);
+    assert_eq!(
+        adapt(sql, Backend::Postgres),
+        "SELECT * FROM games WHERE game_pk = $1 AND season = $2"
+    );
+    assert_eq!(adapt(sql, Backend::MySql), sql);
+}
+
+#[test]
+fn mysql_quotes_reserved_columns_but_not_qualified_ones() {
+    assert_eq!(
+        adapt(
+            "SELECT s.row, row, system FROM seats s ORDER BY section, row",
+            Backend::MySql
+        ),
+        "SELECT s.row, `row`, `system` FROM seats s ORDER BY section, `row`"
+    );
+    assert_eq!(quote_ident("row", Backend::MySql), "`row`");
+    assert_eq!(quote_ident("row", Backend::Postgres), "\"row\"");
+}
+
+#[test]
+fn mysql_leaves_string_literals_alone() {
+    let sql = "SELECT 'row, system AS TEXT) ON CONFLICT DO NOTHING', 'it''s' FROM seats";
+    assert_eq!(adapt(sql, Backend::MySql), sql);
+}
+
+#[test]
+fn mysql_casts_text_and_sums() {
+    assert_eq!(
+        adapt(
+            "SELECT CAST(created_at AS TEXT), COALESCE(SUM(CASE WHEN a THEN 1 ELSE 0 END), 0) FROM t",
+            Backend::MySql
+        ),
+        "SELECT CAST(created_at AS CHAR(16383)), \
+         COALESCE(CAST(SUM(CASE WHEN a THEN 1 ELSE 0 END) AS SIGNED), 0) FROM t"
+    );
+}
+
+#[test]
+fn mysql_do_nothing_becomes_insert_ignore() {
+    assert_eq!(
+        adapt(
+            "INSERT INTO polls (game_pk) VALUES (?) ON CONFLICT (game_pk) DO NOTHING",
+            Backend::MySql
+        ),
+        "INSERT IGNORE INTO polls (game_pk) VALUES (?)"
+    );
+    assert_eq!(
+        adapt(
+            "INSERT INTO game_tickets (game_pk, seat_id) SELECT g.game_pk, s.id \
+             FROM games g CROSS JOIN seats s WHERE g.home_team_id = ? ON CONFLICT DO NOTHING",
+            Backend::MySql
+        ),
+        "INSERT IGNORE INTO game_tickets (game_pk, seat_id) SELECT g.game_pk, s.id \
+         FROM games g CROSS JOIN seats s WHERE g.home_team_id = ?"
+    );
+}
+
+#[test]
+fn mysql_do_update_becomes_on_duplicate_key() {
+    assert_eq!(
+        adapt(
+            "INSERT INTO users (auth0_sub, email, name) VALUES (?, ?, ?) \
+             ON CONFLICT(auth0_sub) DO UPDATE SET \
+                email = excluded.email, \
+                name = COALESCE(excluded.name, users.name), \
+                updated_at = CURRENT_TIMESTAMP",
+            Backend::MySql
+        ),
+        "INSERT INTO users (auth0_sub, email, name) VALUES (?, ?, ?) \
+         ON DUPLICATE KEY UPDATE email = VALUES(email), \
+         name = COALESCE(VALUES(name), users.name), updated_at = CURRENT_TIMESTAMP"
+    );
+}
+
+#[test]
+fn mysql_folds_an_update_condition_into_each_assignment() {
+    assert_eq!(
+        adapt(
+            "INSERT INTO seats (section, row, seat, notes) VALUES (?, ?, ?, ?) \
+             ON CONFLICT(section, row, seat) DO UPDATE SET \
+                notes = excluded.notes, \
+                deleted_at = NULL \
+             WHERE seats.deleted_at IS NOT NULL",
+            Backend::MySql
+        ),
+        "INSERT INTO seats (section, `row`, seat, notes) VALUES (?, ?, ?, ?) \
+         ON DUPLICATE KEY UPDATE \
+         notes = IF(seats.deleted_at IS NOT NULL, VALUES(notes), \
+            IF(LAST_INSERT_ID(-1), notes, notes)), \
+         deleted_at = IF(seats.deleted_at IS NOT NULL, NULL, deleted_at)"
+    );
+}
+
+#[test]
+fn day_of_week_counts_from_sunday_on_every_backend() {
+    assert_eq!(
+        day_of_week("official_date", Backend::Sqlite),
+        "CAST(strftime('%w', official_date) AS INTEGER)"
+    );
+    assert_eq!(
+        day_of_week("official_date", Backend::Postgres),
+        "CAST(EXTRACT(DOW FROM CAST(official_date AS DATE)) AS INTEGER)"
+    );
+    assert_eq!(
+        day_of_week("official_date", Backend::MySql),
+        "(DAYOFWEEK(official_date) - 1)"
+    );
+}
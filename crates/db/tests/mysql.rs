@@ -0,0 +1,167 @@
+//! MySQL runs only where `GTM_TEST_MYSQL_URL` points at an empty database
+//! (CI starts one); elsewhere these tests pass without doing anything.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{REGULAR, TEAM_ID, sample_game};
+use gtm_db::{ConnectOptions, GameFilter, maintenance};
+
+/// Table names created by a directory of migrations.
+fn created_tables(dir: &str) -> BTreeSet<String> {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(dir);
+    let mut tables = BTreeSet::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let sql = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        for rest in sql.split("CREATE TABLE ").skip(1) {
+            let name = rest
+                .trim_start_matches("IF NOT EXISTS ")
+                .split(|c: char| c.is_whitespace() || c == '(')
+                .next()
+                .unwrap_or_default();
+            tables.insert(name.to_string());
+        }
+    }
+    tables
+}
+
+#[tokio::test]
+async fn mysql_migrations_create_every_table() {
+    let pool = common::test_pool().await;
+    let sqlite: BTreeSet<String> = maintenance::tables(&pool, "sqlite::memory:")
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|t| t != "_sqlx_migrations")
+        .collect();
+    assert_eq!(created_tables("migrations-mysql"), sqlite);
+}
+
+#[tokio::test]
+async fn mysql_round_trip() {
+    let Ok(url) = std::env::var("GTM_TEST_MYSQL_URL") else {
+        return;
+    };
+    let pool = gtm_db::connect(&url, &ConnectOptions::default())
+        .await
+        .unwrap();
+    maintenance::reset(&pool, &url).await.unwrap();
+    assert!(
+        maintenance::pending_migrations(&pool, &url)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    gtm_db::upsert_game(&pool, &sample_game(900001))
+        .await
+        .unwrap();
+    let games = gtm_db::list_games(&pool, &GameFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(games.len(), 1);
+
+    // Re-adding a live seat is refused
+    gtm_db::add_seat(&pool, "VR313", "G", "1", None)
+        .await
+        .unwrap();
+    assert!(
+        gtm_db::add_seat(&pool, "VR313", "G", "1", None)
+            .await
+            .is_err()
+    );
+
+    let generated = gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!(generated.created.len(), 1);
+    let again = gtm_db::generate_tickets_for_all_seats(&pool, TEAM_ID, REGULAR)
+        .await
+        .unwrap();
+    assert_eq!((again.created.len(), again.skipped), (0, 1));
+
+    let user = gtm_db::upsert_user(&pool, "auth0|mysql", "m@example.com", "M")
+        .await
+        .unwrap();
+    let same = gtm_db::upsert_user(&pool, "auth0|mysql", "m@example.com", "M")
+        .await
+        .unwrap();
+    assert_eq!(user.id, same.id);
+
+    let details = gtm_db::list_games_with_details(&pool, &GameFilter::default())
+        .await
+        .unwrap();
+    let tickets = details[0].tickets.as_ref().unwrap();
+    assert_eq!((tickets.total, tickets.available), (1, 1));
+
+    let ran = gtm_db::with_lock(
+        &pool,
+        "mysql-test",
+        std::time::Duration::from_secs(5),
+        || async { Ok(()) },
+    )
+    .await
+    .unwrap();
+    assert!(ran.is_some());
+
+    // Each payment locks its invoice, so racing ones see it paid and fail
+    let (invoice_id, payer) = common::open_invoice(&pool, 900002).await;
+    let payment = gtm_db::invoices::NewPayment {
+        amount: 4000,
+        method: Some("venmo"),
+        note: None,
+        recorded_by: Some(payer),
+        line_ids: &[],
+    };
+    let pay = || gtm_db::invoices::record_payment(&pool, invoice_id, &payment);
+    let results = tokio::join!(pay(), pay(), pay(), pay());
+    let ok = [results.0, results.1, results.2, results.3]
+        .iter()
+        .filter(|r| r.is_ok())
+        .count();
+    assert_eq!(ok, 1);
+    let paid = gtm_db::invoices::detail(&pool, invoice_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((paid.invoice.paid, paid.payments.len()), (4000, 1));
+}
+
+#[tokio::test]
+async fn mysql_lock_is_released_when_its_job_is_cancelled() {
+    use std::time::Duration;
+
+    let Ok(url) = std::env::var("GTM_TEST_MYSQL_URL") else {
+        return;
+    };
+    let pool = gtm_db::connect(&url, &ConnectOptions::default())
+        .await
+        .unwrap();
+    let ttl = Duration::from_secs(60);
+    // Drop the job mid-run, as a shutdown or a caller's timeout would
+    let cancelled = tokio::time::timeout(
+        Duration::from_millis(200),
+        gtm_db::with_lock(&pool, "mysql-cancel", ttl, || async {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+            Ok(())
+        }),
+    )
+    .await;
+    assert!(cancelled.is_err());
+
+    // Its session is gone, so the lock is free; allow the server a moment
+    let mut ran = None;
+    for _ in 0..20 {
+        ran = gtm_db::with_lock(&pool, "mysql-cancel", ttl, || async { Ok(()) })
+            .await
+            .unwrap();
+        if ran.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(ran.is_some());
+}
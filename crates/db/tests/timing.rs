@@ -0,0 +1,52 @@
+mod common;
+
+use common::test_pool;
+use gtm_db::timing::{self, QueryTimeout, timed};
+use std::time::Duration;
+
+fn count_to(n: i64) -> String {
+    format!(
+        "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < {n}) \
+         SELECT COUNT(*) FROM c"
+    )
+}
+
+/// One test covers every setting because the limits are process-wide.
+#[tokio::test]
+async fn slow_queries_are_counted_and_runaways_time_out() {
+    let pool = test_pool().await;
+    timing::configure(
+        Some(Duration::from_millis(200)),
+        Some(Duration::from_millis(1)),
+    );
+
+    let before = timing::slow_queries();
+    let sql = count_to(200_000);
+    let n: i64 = timed("count", sqlx::query_scalar(&sql).fetch_one(&pool))
+        .await
+        .unwrap();
+    assert_eq!(n, 200_000);
+    assert_eq!(timing::slow_queries(), before + 1);
+
+    let sql = count_to(1_000_000_000);
+    let err = timed(
+        "runaway",
+        sqlx::query_scalar::<_, i64>(&sql).fetch_one(&pool),
+    )
+    .await
+    .unwrap_err();
+    let timeout = err.downcast_ref::<QueryTimeout>().unwrap();
+    assert_eq!(timeout.name, "runaway");
+    assert!(timeout.after >= Duration::from_millis(200));
+
+    // With both off, nothing is cut short or counted
+    timing::configure(None, None);
+    let before = timing::slow_queries();
+    let sql = count_to(200_000);
+    let pool = test_pool().await;
+    let n: i64 = timed("count", sqlx::query_scalar(&sql).fetch_one(&pool))
+        .await
+        .unwrap();
+    assert_eq!(n, 200_000);
+    assert_eq!(timing::slow_queries(), before);
+}
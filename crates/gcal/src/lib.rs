@@ -0,0 +1,247 @@
+//! Google Calendar integration: OAuth consent, token refresh, and event
+//! create/update/delete against a member's calendar.
+//!
+//! Only the REST calls live here; deciding which events should exist is the
+//! caller's job (see the sync-state table in `gtm_db`).
+
+use anyhow::{Result, bail};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const CALENDAR_API: &str = "https://www.googleapis.com/calendar/v3";
+/// Events only — GTM never reads or edits anything else on the calendar.
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar.events";
+
+/// OAuth client registered in the Google Cloud console.
+#[derive(Debug, Clone)]
+pub struct GoogleConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    /// Must match an authorized redirect URI on the OAuth client.
+    pub redirect_uri: String,
+    /// IANA time zone GTM writes event times in.
+    pub time_zone: String,
+}
+
+/// Tokens returned from a code exchange or refresh. Google only returns a
+/// `refresh_token` on the initial consent.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tokens {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// Seconds until `access_token` expires.
+    pub expires_in: i64,
+}
+
+/// The calendar event GTM wants for one game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSpec {
+    pub summary: String,
+    pub location: String,
+    pub description: String,
+    /// RFC 3339
+    pub start: String,
+    /// RFC 3339
+    pub end: String,
+    /// IANA time zone `start` and `end` are shown in.
+    pub time_zone: String,
+}
+
+impl EventSpec {
+    /// Stable fingerprint of the event's content, stored alongside the event
+    /// id so unchanged events aren't re-sent on every sync.
+    pub fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            &self.summary,
+            &self.location,
+            &self.description,
+            &self.start,
+            &self.end,
+            &self.time_zone,
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0u8]);
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "summary": self.summary,
+            "location": self.location,
+            "description": self.description,
+            "start": { "dateTime": self.start, "timeZone": self.time_zone },
+            "end": { "dateTime": self.end, "timeZone": self.time_zone },
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct InsertedEvent {
+    id: String,
+}
+
+/// Google Calendar client. Without a [`GoogleConfig`] the integration is
+/// disabled and members can't link an account.
+#[derive(Clone, Default)]
+pub struct GoogleCalendar {
+    client: reqwest::Client,
+    config: Option<GoogleConfig>,
+}
+
+impl GoogleCalendar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(mut self, config: GoogleConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    /// [`GoogleConfig::time_zone`], if enabled.
+    pub fn time_zone(&self) -> Option<&str> {
+        self.config.as_ref().map(|c| c.time_zone.as_str())
+    }
+
+    fn config(&self) -> Result<&GoogleConfig> {
+        match &self.config {
+            Some(c) => Ok(c),
+            None => bail!("Google Calendar integration is not configured"),
+        }
+    }
+
+    /// URL to send the member to for consent. `state` is echoed back to the
+    /// redirect URI and must be checked there.
+    pub fn authorize_url(&self, state: &str) -> Result<String> {
+        let cfg = self.config()?;
+        let url = reqwest::Url::parse_with_params(
+            AUTH_URL,
+            &[
+                ("client_id", cfg.client_id.as_str()),
+                ("redirect_uri", cfg.redirect_uri.as_str()),
+                ("response_type", "code"),
+                ("scope", SCOPE),
+                ("access_type", "offline"),
+                // Always re-consent so Google issues a fresh refresh token
+                ("prompt", "consent"),
+                ("state", state),
+            ],
+        )?;
+        Ok(url.into())
+    }
+
+    pub async fn exchange_code(&self, code: &str) -> Result<Tokens> {
+        let cfg = self.config()?;
+        let form = [
+            ("code", code),
+            ("client_id", cfg.client_id.as_str()),
+            ("client_secret", cfg.client_secret.as_str()),
+            ("redirect_uri", cfg.redirect_uri.as_str()),
+            ("grant_type", "authorization_code"),
+        ];
+        let tokens = self
+            .client
+            .post(TOKEN_URL)
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(tokens)
+    }
+
+    pub async fn refresh(&self, refresh_token: &str) -> Result<Tokens> {
+        let cfg = self.config()?;
+        let form = [
+            ("refresh_token", refresh_token),
+            ("client_id", cfg.client_id.as_str()),
+            ("client_secret", cfg.client_secret.as_str()),
+            ("grant_type", "refresh_token"),
+        ];
+        let tokens = self
+            .client
+            .post(TOKEN_URL)
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(tokens)
+    }
+
+    /// Create an event and return its Google event id.
+    pub async fn insert_event(
+        &self,
+        access_token: &str,
+        calendar_id: &str,
+        event: &EventSpec,
+    ) -> Result<String> {
+        let url = format!("{CALENDAR_API}/calendars/{calendar_id}/events");
+        let inserted: InsertedEvent = self
+            .client
+            .post(&url)
+            .bearer_auth(access_token)
+            .json(&event.to_json())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        info!(event_id = %inserted.id, summary = %event.summary, "Calendar event created");
+        Ok(inserted.id)
+    }
+
+    pub async fn update_event(
+        &self,
+        access_token: &str,
+        calendar_id: &str,
+        event_id: &str,
+        event: &EventSpec,
+    ) -> Result<()> {
+        let url = format!("{CALENDAR_API}/calendars/{calendar_id}/events/{event_id}");
+        self.client
+            .put(&url)
+            .bearer_auth(access_token)
+            .json(&event.to_json())
+            .send()
+            .await?
+            .error_for_status()?;
+        info!(event_id = %event_id, summary = %event.summary, "Calendar event updated");
+        Ok(())
+    }
+
+    /// Delete an event. An event the member already deleted counts as success.
+    pub async fn delete_event(
+        &self,
+        access_token: &str,
+        calendar_id: &str,
+        event_id: &str,
+    ) -> Result<()> {
+        let url = format!("{CALENDAR_API}/calendars/{calendar_id}/events/{event_id}");
+        let resp = self
+            .client
+            .delete(&url)
+            .bearer_auth(access_token)
+            .send()
+            .await?;
+        let status = resp.status();
+        if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::GONE {
+            return Ok(());
+        }
+        resp.error_for_status()?;
+        info!(event_id = %event_id, "Calendar event deleted");
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use gtm_gcal::{EventSpec, GoogleCalendar, GoogleConfig};
+
+fn event() -> EventSpec {
+    EventSpec {
+        summary: "Giants vs Dodgers".to_string(),
+        location: "Oracle Park".to_string(),
+        description: "VR313 Row A, seats 1, 2".to_string(),
+        start: "2026-06-12T02:15:00Z".to_string(),
+        end: "2026-06-12T05:15:00Z".to_string(),
+        time_zone: "America/Los_Angeles".to_string(),
+    }
+}
+
+#[test]
+fn content_hash_changes_only_with_content() {
+    let a = event();
+    assert_eq!(a.content_hash(), event().content_hash());
+
+    let mut moved = event();
+    moved.start = "2026-06-12T20:05:00Z".to_string();
+    assert_ne!(a.content_hash(), moved.content_hash());
+
+    // Field boundaries matter: shifting text between fields is a change
+    let mut shifted = event();
+    shifted.summary = "Giants vs DodgersOracle".to_string();
+    shifted.location = " Park".to_string();
+    assert_ne!(a.content_hash(), shifted.content_hash());
+}
+
+#[test]
+fn authorize_url_requires_config_and_requests_offline_access() {
+    assert!(GoogleCalendar::new().authorize_url("s").is_err());
+
+    let gcal = GoogleCalendar::new().with_config(GoogleConfig {
+        client_id: "cid".to_string(),
+        client_secret: "secret".to_string(),
+        redirect_uri: "https://gtm.example.com/api/google/callback".to_string(),
+        time_zone: "America/Los_Angeles".to_string(),
+    });
+    let url = gcal.authorize_url("abc123").unwrap();
+    assert!(url.starts_with("https://accounts.google.com/"));
+    assert!(url.contains("access_type=offline"));
+    assert!(url.contains("state=abc123"));
+    assert!(url.contains("redirect_uri=https%3A%2F%2Fgtm.example.com%2Fapi%2Fgoogle%2Fcallback"));
+    assert!(!url.contains("secret"));
+}
@@ -1,5 +1,8 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::collections::BTreeMap;
 
 /// Serde helper: store as i64 in DB (for SQLx Any compatibility) but
 /// serialize/deserialize as boolean in JSON.
@@ -83,9 +86,276 @@ mod option_bool_as_i64 {
     }
 }
 
+/// Declare an `i64` newtype for a table key. The wrapper is transparent to
+/// serde and sqlx, so JSON and columns keep plain integers, but the compiler
+/// stops a seat id being passed where a ticket id belongs.
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+            sqlx::Type,
+        )]
+        #[serde(transparent)]
+        #[sqlx(transparent)]
+        pub struct $name(pub i64);
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = std::num::ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.parse().map($name)
+            }
+        }
+
+        impl From<$name> for i64 {
+            fn from(id: $name) -> i64 {
+                id.0
+            }
+        }
+    };
+}
+
+id_type!(
+    /// MLB's `gamePk`, the key of `games`.
+    GamePk
+);
+id_type!(
+    /// `seats.id`: a physical seat, across all games.
+    SeatId
+);
+id_type!(
+    /// `game_tickets.id`: one seat at one game.
+    TicketId
+);
+id_type!(
+    /// `users.id`.
+    UserId
+);
+
+/// MLB Stats API `gameType` codes the scraper knows how to fetch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameType {
+    #[serde(rename = "R")]
+    Regular,
+    #[serde(rename = "S")]
+    SpringTraining,
+    #[serde(rename = "P")]
+    Postseason,
+    #[serde(rename = "F")]
+    WildCard,
+    #[serde(rename = "D")]
+    DivisionSeries,
+    #[serde(rename = "L")]
+    LeagueChampionship,
+    #[serde(rename = "W")]
+    WorldSeries,
+}
+
+impl GameType {
+    /// Every type, in the order they come in a season.
+    pub const ALL: [GameType; 7] = [
+        GameType::SpringTraining,
+        GameType::Regular,
+        GameType::WildCard,
+        GameType::DivisionSeries,
+        GameType::LeagueChampionship,
+        GameType::WorldSeries,
+        GameType::Postseason,
+    ];
+
+    /// The single-letter code used by the MLB API and stored in `games.game_type`.
+    pub fn code(self) -> &'static str {
+        match self {
+            GameType::Regular => "R",
+            GameType::SpringTraining => "S",
+            GameType::Postseason => "P",
+            GameType::WildCard => "F",
+            GameType::DivisionSeries => "D",
+            GameType::LeagueChampionship => "L",
+            GameType::WorldSeries => "W",
+        }
+    }
+
+    /// Name to show people, e.g. "Wild Card".
+    pub fn label(self) -> &'static str {
+        match self {
+            GameType::Regular => "Regular",
+            GameType::SpringTraining => "Spring",
+            GameType::Postseason => "Postseason",
+            GameType::WildCard => "Wild Card",
+            GameType::DivisionSeries => "Division Series",
+            GameType::LeagueChampionship => "LCS",
+            GameType::WorldSeries => "World Series",
+        }
+    }
+
+    /// The label for a stored `game_type` code, or the code itself if it
+    /// isn't one we know.
+    pub fn label_for(code: &str) -> &str {
+        match code.parse::<GameType>() {
+            Ok(game_type) => game_type.label(),
+            Err(_) => code,
+        }
+    }
+
+    /// Whether tickets are generated for this type when
+    /// `ticketed_game_types` isn't configured. Spring training isn't part of
+    /// a season plan and postseason seats are bought round by round, so only
+    /// the regular season is.
+    pub fn ticketed_by_default(self) -> bool {
+        self == GameType::Regular
+    }
+}
+
+impl std::fmt::Display for GameType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl std::str::FromStr for GameType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "R" => Ok(GameType::Regular),
+            "S" => Ok(GameType::SpringTraining),
+            "P" => Ok(GameType::Postseason),
+            "F" => Ok(GameType::WildCard),
+            "D" => Ok(GameType::DivisionSeries),
+            "L" => Ok(GameType::LeagueChampionship),
+            "W" => Ok(GameType::WorldSeries),
+            other => Err(format!(
+                "Unknown game type: {other} (expected one of R, S, P, F, D, L, W)"
+            )),
+        }
+    }
+}
+
+/// MLB's `doubleHeader` code. A traditional double-header is two games on
+/// one admission, so only the first is ticketed; a split double-header sells
+/// each game separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoubleHeader {
+    /// `N`: a single game.
+    No,
+    /// `Y`: both games on one ticket.
+    Traditional,
+    /// `S`: separate admission for each game.
+    Split,
+}
+
+impl DoubleHeader {
+    /// Unknown codes are treated as a single game.
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "Y" => DoubleHeader::Traditional,
+            "S" => DoubleHeader::Split,
+            _ => DoubleHeader::No,
+        }
+    }
+
+    pub fn code(self) -> &'static str {
+        match self {
+            DoubleHeader::No => "N",
+            DoubleHeader::Traditional => "Y",
+            DoubleHeader::Split => "S",
+        }
+    }
+
+    /// `game_number` of this kind of day, e.g. "Game 2 of 2", or `None` for
+    /// a single game.
+    pub fn label(self, game_number: i64) -> Option<String> {
+        match self {
+            DoubleHeader::No => None,
+            DoubleHeader::Traditional | DoubleHeader::Split => {
+                Some(format!("Game {game_number} of 2"))
+            }
+        }
+    }
+
+    /// Whether `game_number` needs tickets of its own: false only for the
+    /// second game of a traditional double-header.
+    pub fn ticketed(self, game_number: i64) -> bool {
+        !(self == DoubleHeader::Traditional && game_number > 1)
+    }
+}
+
+/// Optional sections the MLB schedule endpoint can add to each game
+/// (its `hydrate` parameter). Each makes the response heavier, so a scrape
+/// asks only for the ones it needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Hydration {
+    Promotions,
+    Tickets,
+    Venue,
+    Weather,
+    ProbablePitcher,
+    Broadcasts,
+}
+
+impl Hydration {
+    /// The value passed in `hydrate`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Hydration::Promotions => "game(promotions)",
+            Hydration::Tickets => "tickets",
+            Hydration::Venue => "venue",
+            Hydration::Weather => "weather",
+            Hydration::ProbablePitcher => "probablePitcher",
+            Hydration::Broadcasts => "broadcasts(all)",
+        }
+    }
+
+    /// The name used in config and requests.
+    pub fn name(self) -> &'static str {
+        match self {
+            Hydration::Promotions => "promotions",
+            Hydration::Tickets => "tickets",
+            Hydration::Venue => "venue",
+            Hydration::Weather => "weather",
+            Hydration::ProbablePitcher => "probablePitcher",
+            Hydration::Broadcasts => "broadcasts",
+        }
+    }
+}
+
+impl std::fmt::Display for Hydration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for Hydration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "promotions" => Ok(Hydration::Promotions),
+            "tickets" => Ok(Hydration::Tickets),
+            "venue" => Ok(Hydration::Venue),
+            "weather" => Ok(Hydration::Weather),
+            "probablepitcher" => Ok(Hydration::ProbablePitcher),
+            "broadcasts" => Ok(Hydration::Broadcasts),
+            other => Err(format!(
+                "Unknown hydration: {other} (expected one of promotions, tickets, venue, \
+                 weather, probablePitcher, broadcasts)"
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Game {
-    pub game_pk: i64,
+    pub game_pk: GamePk,
     pub game_guid: Option<String>,
     pub game_type: String,
     pub season: String,
@@ -117,12 +387,137 @@ pub struct Game {
     pub scheduled_innings: i64,
     #[serde(with = "bool_as_i64")]
     pub is_tie: i64,
+    /// Inning in progress (or last played), from the live linescore.
+    pub current_inning: Option<i64>,
+    /// "Top", "Middle", "Bottom" or "End" of `current_inning`.
+    pub inning_state: Option<String>,
+    /// Probable starters' names, from the `probablePitcher` hydration.
+    pub away_probable_pitcher: Option<String>,
+    pub home_probable_pitcher: Option<String>,
+    /// Forecast from the `weather` hydration, as the API words it, e.g.
+    /// "Partly Cloudy", "61" (°F) and "12 mph, Out To CF".
+    pub weather_condition: Option<String>,
+    pub weather_temp: Option<String>,
+    pub weather_wind: Option<String>,
+    /// TV networks carrying the game, comma-separated, from the
+    /// `broadcasts` hydration.
+    pub tv_broadcasts: Option<String>,
+    /// When a postponed or suspended game is to be made up (UTC), once MLB
+    /// has set a date.
+    pub reschedule_date: Option<String>,
+    /// The makeup's date at the ballpark (YYYY-MM-DD), alongside
+    /// `reschedule_date`.
+    pub reschedule_official_date: Option<String>,
+    /// The makeup game its tickets and requests were moved to, if it was
+    /// postponed and made up under a new `game_pk`.
+    pub rescheduled_to: Option<GamePk>,
+    /// Most seats one request may ask for, from the seat limit policy.
+    /// Filled in by the games API; `None` elsewhere.
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub max_seats: Option<i64>,
+    /// [`GameType::label`] of `game_type`. Filled in by the games API;
+    /// `None` elsewhere.
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub game_type_label: Option<String>,
+    /// [`Game::local_first_pitch`] as RFC 3339 with its offset, and the
+    /// time zone it's in. Filled in by the games API; `None` elsewhere, and
+    /// while the time is TBD.
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub first_pitch_local: Option<String>,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub time_zone: Option<String>,
+}
+
+/// A first pitch as MLB gives it and `games.game_date` stores it, RFC 3339
+/// in UTC (`2025-04-01T02:05:00Z`).
+pub fn parse_game_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+impl Game {
+    /// When the game starts, from `game_date`; `None` while the time is TBD
+    /// (MLB then gives a placeholder time of day).
+    pub fn first_pitch(&self) -> Option<DateTime<Utc>> {
+        if self.start_time_tbd != 0 {
+            return None;
+        }
+        parse_game_time(&self.game_date)
+    }
+
+    /// [`Game::first_pitch`] in `tz`, e.g. 7:05 PM in San Francisco for a
+    /// `game_date` of 02:05 UTC the next day.
+    pub fn local_first_pitch(&self, tz: Tz) -> Option<DateTime<Tz>> {
+        self.first_pitch().map(|t| t.with_timezone(&tz))
+    }
+
+    /// Fill in `first_pitch_local` and `time_zone` for `tz`.
+    pub fn set_local_time(&mut self, tz: Tz) {
+        self.first_pitch_local = self.local_first_pitch(tz).map(|t| t.to_rfc3339());
+        self.time_zone = Some(tz.name().to_string());
+    }
+
+    pub fn double_header(&self) -> DoubleHeader {
+        DoubleHeader::from_code(&self.double_header)
+    }
+
+    /// "Game 1 of 2" for either game of a double-header.
+    pub fn double_header_label(&self) -> Option<String> {
+        self.double_header().label(self.game_number)
+    }
+}
+
+/// A game with its promotion names and ticket counts, read in one query for
+/// listings that would otherwise look them up game by game. The games API
+/// leaves out whichever parts weren't asked for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameDetails {
+    #[serde(flatten)]
+    pub game: Game,
+    /// Promotion names in display order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promotions: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tickets: Option<TicketCounts>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketCounts {
+    pub total: i64,
+    pub available: i64,
+}
+
+/// Status and score for a game from the live linescore feed, applied to an
+/// existing `games` row while the game is played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiveGame {
+    pub game_pk: GamePk,
+    pub status_abstract: String,
+    pub status_detailed: String,
+    pub status_code: String,
+    pub away_score: Option<i64>,
+    pub away_is_winner: Option<i64>,
+    pub home_score: Option<i64>,
+    pub home_is_winner: Option<i64>,
+    pub current_inning: Option<i64>,
+    pub inning_state: Option<String>,
+}
+
+impl LiveGame {
+    pub fn is_final(&self) -> bool {
+        self.status_abstract == "Final"
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Promotion {
     pub offer_id: i64,
-    pub game_pk: i64,
+    pub game_pk: GamePk,
     pub name: String,
     pub offer_type: Option<String>,
     pub description: Option<String>,
@@ -135,28 +530,102 @@ pub struct Promotion {
     pub display_order: i64,
 }
 
+/// One season's schedule as fetched from the MLB Stats API.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleData {
+    pub games: Vec<Game>,
+    pub promotions: Vec<Promotion>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Seat {
-    pub id: i64,
+    pub id: SeatId,
     pub section: String,
     pub row: String,
     pub seat: String,
     pub notes: Option<String>,
+    /// What one game in this seat costs, in cents.
+    pub face_value: Option<i64>,
+}
+
+/// Result of soft-deleting one seat in a section/row group.
+#[derive(Debug, Clone, Serialize)]
+pub struct SeatDeleteOutcome {
+    #[serde(flatten)]
+    pub seat: Seat,
+    /// Unassigned tickets dropped with the seat.
+    pub tickets_removed: u64,
+    /// Tickets left in place because they were already handed out.
+    pub tickets_kept: u64,
+}
+
+/// Result of regenerating game tickets for one seat in a section/row group.
+#[derive(Debug, Clone, Serialize)]
+pub struct SeatTicketOutcome {
+    #[serde(flatten)]
+    pub seat: Seat,
+    pub tickets_generated: u64,
+}
+
+/// Where a game ticket stands, as stored in `game_tickets.status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TicketStatus {
+    Available,
+    Assigned,
+}
+
+impl TicketStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TicketStatus::Available => "available",
+            TicketStatus::Assigned => "assigned",
+        }
+    }
+
+    /// Whether a status update may move a ticket from `self` to `to`.
+    /// Staying put (to change notes) is always allowed, and an assigned
+    /// ticket may be made available again, which clears its holder. Nothing
+    /// becomes assigned this way: assigning names a holder, so it goes
+    /// through `assign_ticket`.
+    pub fn can_become(self, to: TicketStatus) -> bool {
+        self == to || (self, to) == (TicketStatus::Assigned, TicketStatus::Available)
+    }
+}
+
+impl std::fmt::Display for TicketStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for TicketStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "available" => Ok(TicketStatus::Available),
+            "assigned" => Ok(TicketStatus::Assigned),
+            other => Err(format!(
+                "Unknown ticket status: {other} (expected available or assigned)"
+            )),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct GameTicket {
-    pub id: i64,
-    pub game_pk: i64,
-    pub seat_id: i64,
+    pub id: TicketId,
+    pub game_pk: GamePk,
+    pub seat_id: SeatId,
     pub status: String,
     pub notes: Option<String>,
-    pub assigned_to: Option<i64>,
+    pub assigned_to: Option<UserId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct User {
-    pub id: i64,
+    pub id: UserId,
     pub auth0_sub: String,
     pub email: String,
     pub name: String,
@@ -164,31 +633,984 @@ pub struct User {
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct GameTicketDetail {
-    pub id: i64,
-    pub game_pk: i64,
-    pub seat_id: i64,
+    pub id: TicketId,
+    pub game_pk: GamePk,
+    pub seat_id: SeatId,
     pub section: String,
     pub row: String,
     pub seat: String,
     pub status: String,
     pub notes: Option<String>,
-    pub assigned_to: Option<i64>,
+    pub assigned_to: Option<UserId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct TicketRequest {
     pub id: i64,
-    pub user_id: i64,
-    pub game_pk: i64,
+    pub user_id: UserId,
+    pub game_pk: GamePk,
     pub seats_requested: i64,
     pub status: String,
     pub notes: Option<String>,
+    /// Set when a schedule change needs an admin to look at the request
+    /// (e.g. the game moved onto one of the member's blackout dates).
+    pub flag_reason: Option<String>,
+}
+
+/// A game's seats and every request for it, replayed by the allocation
+/// simulator.
+#[derive(Debug, Clone)]
+pub struct AllocationRound {
+    pub game_pk: GamePk,
+    pub tickets: Vec<GameTicketDetail>,
+    pub requests: Vec<TicketRequest>,
+}
+
+/// A committed allocation batch, as listed. Its JSON snapshot of what was
+/// submitted, proposed and assigned is fetched on its own.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AllocationBatch {
+    pub id: i64,
+    pub actor_id: Option<UserId>,
+    /// "manual" for hand-picked seats, "auto" for the allocation engine.
+    pub kind: String,
+    /// The game, when every seat in the batch was for one.
+    pub game_pk: Option<GamePk>,
+    pub assigned: i64,
+    pub created_at: String,
+}
+
+/// A notification waiting in the delivery queue.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QueuedNotification {
+    pub id: i64,
+    pub user_id: Option<UserId>,
+    pub game_pk: Option<GamePk>,
+    pub kind: String,
+    pub channel: String,
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    pub attempts: i64,
+}
+
+/// A member's linked Google account. Tokens never leave the server.
+#[derive(Debug, Clone, FromRow)]
+pub struct GoogleAccount {
+    pub user_id: UserId,
+    pub refresh_token: String,
+    pub access_token: Option<String>,
+    /// RFC 3339, UTC
+    pub access_expires_at: Option<String>,
+    pub calendar_id: String,
+}
+
+/// Sync state: the Google event GTM created for a member's game.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CalendarEventSync {
+    pub user_id: UserId,
+    pub game_pk: GamePk,
+    pub event_id: String,
+    pub content_hash: String,
+}
+
+/// A "rain is likely — release or keep?" prompt sent to an assigned member.
+/// `response` is `None` until they click one of the links.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ReleasePrompt {
+    pub id: i64,
+    pub token: String,
+    pub game_pk: GamePk,
+    pub user_id: UserId,
+    pub rain_probability: i64,
+    pub response: Option<String>,
+}
+
+/// Pre-game summary of a home game: probable pitchers, each team's record
+/// and streak, and promotion highlights. Regenerated daily as the game
+/// approaches; `body` is the rendered text used in the app and emails.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GamePreview {
+    pub game_pk: GamePk,
+    pub away_probable_pitcher: Option<String>,
+    pub home_probable_pitcher: Option<String>,
+    /// Win-loss, e.g. "12-8".
+    pub away_record: Option<String>,
+    pub home_record: Option<String>,
+    /// MLB streak code, e.g. "W3".
+    pub away_streak: Option<String>,
+    pub home_streak: Option<String>,
+    pub body: String,
+    pub generated_at: String,
+}
+
+/// Per-member season totals, with weather releases counted apart from
+/// ordinary ones.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FairnessRow {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub seats_assigned: i64,
+    pub weather_releases: i64,
+}
+
+/// One row of the ticket audit trail, with names and seat resolved.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub created_at: String,
+    pub actor_id: Option<UserId>,
+    pub actor_name: Option<String>,
+    pub action: String,
+    pub game_pk: Option<GamePk>,
+    pub game_ticket_id: Option<TicketId>,
+    pub request_id: Option<i64>,
+    pub user_id: Option<UserId>,
+    pub user_name: Option<String>,
+    pub section: Option<String>,
+    pub row: Option<String>,
+    pub seat: Option<String>,
+    pub before_value: Option<String>,
+    pub after_value: Option<String>,
+}
+
+/// A season with games in the database. `closed_at` is set once an admin
+/// has closed it, after which its tickets and requests are read-only.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Season {
+    pub season: String,
+    pub games: i64,
+    pub first_date: String,
+    pub last_date: String,
+    pub tickets: i64,
+    pub requests: i64,
+    pub closed_at: Option<String>,
+}
+
+/// An availability poll on a game, with its answers tallied. Admins open
+/// one before allocating a marquee game; `closed_at` is set once its "yes"
+/// answers have been turned into requests.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PollSummary {
+    pub id: i64,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub question: Option<String>,
+    pub created_at: String,
+    pub closed_at: Option<String>,
+    pub yes: i64,
+    pub no: i64,
+    pub maybe: i64,
+    /// Seats wanted across "yes" answers.
+    pub yes_seats: i64,
+    /// Seats wanted across "maybe" answers.
+    pub maybe_seats: i64,
+}
+
+/// One member's answer to a poll.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PollResponse {
+    pub user_id: UserId,
+    pub user_name: String,
+    /// "yes", "no" or "maybe".
+    pub answer: String,
+    pub seats: i64,
+    pub updated_at: String,
+}
+
+/// A poll's tallies and every answer, for admins.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollResults {
+    #[serde(flatten)]
+    pub summary: PollSummary,
+    pub responses: Vec<PollResponse>,
+}
+
+/// An open poll as a member sees it, with their answer if they've given one.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MemberPoll {
+    pub id: i64,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub question: Option<String>,
+    pub answer: Option<String>,
+    pub seats: Option<i64>,
+}
+
+/// One seat to import, from a CSV line or a JSON array entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeatImportRow {
+    pub section: String,
+    pub row: String,
+    pub seat: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// What one game in this seat costs, in cents.
+    #[serde(default)]
+    pub face_value: Option<i64>,
+}
+
+/// Seats created by an import, and the game tickets generated for them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatImport {
+    pub seats: Vec<Seat>,
+    pub tickets: u64,
+}
+
+/// One ticket in a season export: the game, the seat, and who holds it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TicketExportRow {
+    pub ticket_id: TicketId,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    /// First pitch, RFC 3339 UTC.
+    pub game_date: String,
+    pub away_team_name: String,
+    pub section: String,
+    pub row: String,
+    pub seat: String,
+    pub status: String,
+    pub assignee_name: Option<String>,
+    pub assignee_email: Option<String>,
+    /// The ticket's own cost, else the seat's face value, in cents.
+    pub cost: Option<i64>,
+    pub notes: Option<String>,
+}
+
+/// One game on a member's ledger. Amounts are in cents.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LedgerGame {
+    pub user_id: UserId,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub seats: i64,
+    /// Seats with neither a ticket cost nor a seat face value; counted as 0.
+    pub unpriced_seats: i64,
+    pub amount: i64,
+}
+
+/// What a member owes for a season: the seats they were assigned, game by
+/// game, with totals. Amounts are in cents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberLedger {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub season: String,
+    pub games: Vec<LedgerGame>,
+    pub seats: i64,
+    pub amount: i64,
+}
+
+/// One member's share of a seat's cost (`seat_id` set) or of every seat
+/// without its own rules (plan-wide, `seat_id` null). Shares are in basis
+/// points: 5000 is 50%.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SplitRule {
+    pub id: i64,
+    pub seat_id: Option<SeatId>,
+    pub user_id: UserId,
+    pub user_name: String,
+    pub basis_points: i64,
+}
+
+/// A cap on seats per request for games of `game_type` and with `tag`;
+/// either left `None` matches every game.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SeatLimitRule {
+    pub id: i64,
+    pub game_type: Option<String>,
+    pub tag: Option<String>,
+    pub max_seats: i64,
+}
+
+/// A member's API token, as listed; the token itself is only shown once,
+/// when minted.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiToken {
+    pub id: i64,
+    pub user_id: UserId,
+    pub name: String,
+    /// The token's first characters, to tell tokens apart.
+    pub prefix: String,
+    /// "member", or "admin" for tokens that carry admin access.
+    pub scope: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+    /// RFC 3339 UTC; the token stops working after this.
+    pub expires_at: Option<String>,
+}
+
+/// An admin-registered URL that receives signed event payloads. The signing
+/// secret is only shown once, when the webhook is registered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    /// Event names the webhook receives, e.g. "ticket.assigned".
+    pub events: Vec<String>,
+    pub created_by: Option<UserId>,
+    pub created_at: String,
+}
+
+/// One event queued for, or delivered to, a webhook.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    /// The JSON body posted.
+    pub payload: String,
+    /// "pending", "delivered", or "failed" once retries ran out.
+    pub status: String,
+    pub attempts: i64,
+    /// HTTP status of the last attempt, if the endpoint answered.
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+/// A webhook delivery due to be sent, with what's needed to send it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct QueuedWebhook {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub url: String,
+    pub secret: String,
+    pub event: String,
+    pub payload: String,
+    pub attempts: i64,
+}
+
+/// The kinds of GTM record an [`ExternalRef`] can point at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    Game,
+    Ticket,
+    User,
+}
+
+impl EntityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EntityKind::Game => "game",
+            EntityKind::Ticket => "ticket",
+            EntityKind::User => "user",
+        }
+    }
+}
+
+impl std::fmt::Display for EntityKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for EntityKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "game" => Ok(EntityKind::Game),
+            "ticket" => Ok(EntityKind::Ticket),
+            "user" => Ok(EntityKind::User),
+            other => Err(format!(
+                "Unknown entity type: {other} (expected game, ticket or user)"
+            )),
+        }
+    }
+}
+
+/// A GTM record's ID in another system, e.g. the Notion page mirroring a
+/// game. `entity_id` is the game's `game_pk`, or the ticket's or user's id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct ExternalRef {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: i64,
+    /// Free-form system name, e.g. "notion".
+    pub system: String,
+    pub external_id: String,
+    pub created_at: String,
+}
+
+/// An entity in an API response, with its external refs when it has any.
+#[derive(Debug, Clone, Serialize)]
+pub struct WithRefs<T> {
+    #[serde(flatten)]
+    pub entity: T,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_refs: Vec<ExternalRef>,
+}
+
+/// A priced ticket to split, for the cost calculator.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CostTicket {
+    pub ticket_id: TicketId,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub seat_id: SeatId,
+    pub assigned_to: Option<UserId>,
+    /// The ticket's own cost, else the seat's face value, in cents.
+    pub cost: i64,
+}
+
+/// What one member owes for one ticket.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostShareLine {
+    pub ticket_id: TicketId,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub seat_id: SeatId,
+    pub user_id: UserId,
+    pub amount: i64,
+    /// How the share was decided: "seat" or "plan" rules, or "usage" (the
+    /// member was assigned the ticket).
+    pub basis: String,
+}
+
+/// A member's share for one invoice period, with the lines behind it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberShare {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub amount: i64,
+    pub lines: Vec<CostShareLine>,
+}
+
+/// Everyone's shares for one invoice period (a calendar month, `YYYY-MM`).
+/// `unallocated` is the cost of pay-per-use tickets nobody was assigned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostSplitPeriod {
+    pub period: String,
+    pub members: Vec<MemberShare>,
+    pub unallocated: i64,
+    pub total: i64,
+}
+
+/// A member's bill for one invoice period, generated from their cost-split
+/// lines. `status` is "open", "partial" or "paid"; amounts are cents.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Invoice {
+    pub id: i64,
+    pub user_id: UserId,
+    pub user_name: String,
+    pub period: String,
+    pub status: String,
+    pub total: i64,
+    pub paid: i64,
+    pub created_at: String,
+    /// Latest online checkout link, when online payment is enabled.
+    pub payment_url: Option<String>,
+}
+
+/// One ticket's share on an invoice, with what has been paid against it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InvoiceLine {
+    pub id: i64,
+    pub invoice_id: i64,
+    pub ticket_id: TicketId,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub seat_id: SeatId,
+    /// As on [`CostShareLine`]: "seat", "plan" or "usage".
+    pub basis: String,
+    pub amount: i64,
+    pub paid: i64,
+}
+
+/// Money recorded against one invoice line.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct InvoicePayment {
+    pub id: i64,
+    pub invoice_id: i64,
+    pub line_id: i64,
+    pub amount: i64,
+    pub method: Option<String>,
+    pub note: Option<String>,
+    pub recorded_by: Option<UserId>,
+    pub created_at: String,
+}
+
+/// An invoice with its lines and payments.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceDetail {
+    #[serde(flatten)]
+    pub invoice: Invoice,
+    pub lines: Vec<InvoiceLine>,
+    pub payments: Vec<InvoicePayment>,
+}
+
+/// A member's record for one season, counted in games. `allocated` counts
+/// games they held seats for at any point, including ones later released;
+/// `attended` counts games recorded as attended, plus games played while
+/// they still held seats that nobody recorded; `missed` counts games
+/// recorded as not attended; a release is late when it came within
+/// `gtm_db::reports::LATE_RELEASE_HOURS` of first pitch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeasonHistory {
+    pub season: String,
+    pub requested: i64,
+    pub allocated: i64,
+    pub attended: i64,
+    pub missed: i64,
+    pub released_late: i64,
+}
+
+/// A member's attendance at a game: everyone who holds seats for it, and
+/// anyone with attendance recorded. `attended` is `None` until the member
+/// or an admin records it.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GameAttendance {
+    pub game_pk: GamePk,
+    pub user_id: UserId,
+    pub user_name: String,
+    pub seats: i64,
+    #[serde(with = "option_bool_as_i64")]
+    pub attended: Option<i64>,
+    pub recorded_by: Option<UserId>,
+    pub recorded_at: Option<String>,
+}
+
+/// An assigned ticket on a game's forwarding checklist: who holds it, and
+/// when and how it was last forwarded through the Ballpark app or
+/// otherwise. `forwarded` is true only once it went to the member holding
+/// it now; a ticket forwarded to an earlier holder needs forwarding again.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TicketForward {
+    pub ticket_id: TicketId,
+    pub game_pk: GamePk,
+    pub section: String,
+    pub row: String,
+    pub seat: String,
+    pub assigned_to: UserId,
+    pub user_name: String,
+    /// The ticket's id or barcode in MLB's system.
+    pub external_ticket_id: Option<String>,
+    pub forwarded_to: Option<UserId>,
+    /// YYYY-MM-DD.
+    pub forwarded_on: Option<String>,
+    pub forward_method: Option<String>,
+    pub forwarded_by: Option<UserId>,
+    #[serde(with = "bool_as_i64")]
+    pub forwarded: i64,
+}
+
+/// A member's [`SeasonHistory`], newest season first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberHistory {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub seasons: Vec<SeasonHistory>,
+}
+
+/// One member's season on the transparency page. Unless names are shown,
+/// every row but the viewer's is "Member N" with no `user_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransparencyRow {
+    pub user_id: Option<UserId>,
+    pub name: String,
+    /// The viewer's own row.
+    pub you: bool,
+    pub seats_received: i64,
+    pub games_received: i64,
+    /// Games received that an admin tagged `premium`.
+    pub premium_games: i64,
+    /// Requests not withdrawn, and how many of those were declined.
+    pub requests: i64,
+    pub requests_denied: i64,
+}
+
+/// Allocation stats for every member with requests or seats in a season,
+/// most seats first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transparency {
+    pub season: String,
+    pub named: bool,
+    pub members: Vec<TransparencyRow>,
+}
+
+/// Seats each member has been assigned, cumulative by week of the season,
+/// shaped for a line chart: `members[i].cumulative[w]` is member `i`'s total
+/// through the week starting `weeks[w]` (a Monday).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FairnessTimeline {
+    pub season: String,
+    pub weeks: Vec<String>,
+    pub members: Vec<FairnessSeries>,
+}
+
+/// One member's line on the [`FairnessTimeline`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FairnessSeries {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub cumulative: Vec<i64>,
+}
+
+/// One seat's season to date, for the renewal report. A ticket counts as
+/// used if someone was assigned it, resold if it has a resale amount, and
+/// unused otherwise; `unused_loss` is what the unused games cost. Cents.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RenewalSeat {
+    pub seat_id: SeatId,
+    pub section: String,
+    pub row: String,
+    pub seat: String,
+    pub face_value: Option<i64>,
+    pub games: i64,
+    pub used: i64,
+    pub resold: i64,
+    pub unused: i64,
+    pub cost: i64,
+    pub unused_loss: i64,
+    pub resale_total: i64,
+    /// Cost of the resold games, to set `resale_total` against.
+    pub resold_cost: i64,
+    /// Used games as a percentage of games played.
+    #[sqlx(skip)]
+    pub utilization_pct: f64,
+}
+
+/// What one member has paid so far this season against the face value of
+/// the seats they used. Cents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenewalMember {
+    pub user_id: UserId,
+    pub user_name: String,
+    pub games: i64,
+    pub seats: i64,
+    pub face_value: i64,
+    /// Their cost-split shares for games played.
+    pub spend: i64,
+}
+
+/// Who's going to which game: one row per game with tickets, one column
+/// per member who holds a seat that season.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttendanceGrid {
+    pub season: String,
+    /// Column order for each row's `seats`.
+    pub members: Vec<AttendanceMember>,
+    pub games: Vec<AttendanceRow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttendanceMember {
+    pub user_id: UserId,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttendanceRow {
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub opponent: String,
+    /// Seats each member holds, in `members` order.
+    pub seats: Vec<i64>,
+    /// Seats nobody holds.
+    pub unassigned: i64,
+}
+
+/// Season-to-date figures for deciding whether to renew the plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenewalReport {
+    pub season: String,
+    /// Games before this date count as played.
+    pub as_of: String,
+    pub seats: Vec<RenewalSeat>,
+    pub members: Vec<RenewalMember>,
+    pub total_cost: i64,
+    pub unused_loss: i64,
+    pub resold: i64,
+    pub resale_total: i64,
+    /// Resale takings as a percentage of the resold games' cost; `None`
+    /// when nothing has been resold.
+    pub resale_recovery_pct: Option<f64>,
+}
+
+/// Ticketed home games with no tickets at all, e.g. because seats were
+/// added while a schedule import was failing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TicketCoverage {
+    /// Seats that should have a ticket at every game.
+    pub active_seats: i64,
+    /// Ticketed home games on the schedule.
+    pub home_games: i64,
+    pub uncovered: Vec<UncoveredGame>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UncoveredGame {
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub game_type: String,
+    pub opponent: String,
+    pub status_detailed: String,
+}
+
+/// Reusable ticket note text, with how often it has been applied. The body
+/// may use `{today}`, `{date}`, `{opponent}` and `{seat}`, filled in per
+/// ticket when applied.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NoteTemplate {
+    pub id: i64,
+    pub name: String,
+    pub body: String,
+    pub created_at: String,
+    /// Tickets it has been applied to, counting repeats.
+    pub applied: i64,
+    pub last_applied_at: Option<String>,
+}
+
+/// A seat handed from one member to another, for the admin transfer history.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TicketTransfer {
+    pub id: i64,
+    pub created_at: String,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub game_ticket_id: TicketId,
+    pub section: String,
+    pub row: String,
+    pub seat: String,
+    pub from_user_id: UserId,
+    pub from_user_name: String,
+    pub to_user_id: UserId,
+    pub to_user_name: String,
+}
+
+/// Seat counts for one game, with enough of the game to render it without a
+/// separate `/api/games` lookup.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TicketSummaryRow {
+    pub game_pk: GamePk,
+    pub season: String,
+    pub official_date: String,
+    pub game_date: String,
+    /// Tickets only exist for home games, so this is the away team.
+    pub opponent: String,
+    pub status_abstract: String,
+    /// [`DoubleHeader`] code and game number, to tell a double-header's
+    /// games apart.
+    pub double_header: String,
+    pub game_number: i64,
+    pub total: i64,
+    pub available: i64,
+}
+
+impl TicketSummaryRow {
+    /// "Game 1 of 2" for either game of a double-header.
+    pub fn double_header_label(&self) -> Option<String> {
+        DoubleHeader::from_code(&self.double_header).label(self.game_number)
+    }
+}
+
+/// Per-home-game allocation totals for the admin dashboard, joined with the
+/// game's date and opponent.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AllocationSummaryRow {
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub double_header: String,
+    pub game_number: i64,
+    pub total_seats: i64,
+    pub assigned: i64,
+    pub available: i64,
+    /// Seats asked for by pending requests.
+    pub total_requested: i64,
+    #[serde(with = "bool_as_i64")]
+    pub oversubscribed: i64,
+}
+
+/// An upcoming home game with seats nobody has asked for.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UnassignedGame {
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub game_date: String,
+    pub away_team_name: String,
+    pub double_header: String,
+    pub game_number: i64,
+    pub available: i64,
+}
+
+/// A home game on the member's availability calendar: seat counts for
+/// everyone, then where the member stands.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CalendarGame {
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub game_date: String,
+    pub opponent: String,
+    pub status_abstract: String,
+    pub double_header: String,
+    pub game_number: i64,
+    pub total: i64,
+    pub available: i64,
+    pub assigned: i64,
+    /// Available seats left over after every pending request is met.
+    pub claimable: i64,
+    /// Seats assigned to the member.
+    pub my_seats: i64,
+    /// The member's request for the game, if any, whatever its status.
+    pub my_request_status: Option<String>,
+    pub my_seats_requested: Option<i64>,
+    pub shortlist: i64,
+    pub cant_go: i64,
+}
+
+/// One day of the availability calendar, with its home games (none on most
+/// days, two on a double-header).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarDay {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub games: Vec<CalendarGame>,
+}
+
+/// Every day of one month, for a month-view calendar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailabilityCalendar {
+    pub season: String,
+    pub month: u32,
+    pub days: Vec<CalendarDay>,
+}
+
+/// A pending request with its submission time (as stored, UTC).
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PendingRequest {
+    pub id: i64,
+    pub user_id: UserId,
+    pub game_pk: GamePk,
+    pub seats_requested: i64,
+    pub status: String,
+    pub notes: Option<String>,
+    pub flag_reason: Option<String>,
+    pub created_at: String,
+}
+
+/// A comment on a ticket request, from the member or an admin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, FromRow)]
+pub struct RequestComment {
+    pub id: i64,
+    pub request_id: i64,
+    pub user_id: UserId,
+    pub author_name: String,
+    /// 1 if posted by an admin through the admin endpoints.
+    pub from_admin: i64,
+    pub body: String,
+    pub created_at: String,
+}
+
+/// Median time from request to admin response, bucketed by request month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonthlyResponseTime {
+    /// YYYY-MM
+    pub month: String,
+    pub responded: i64,
+    pub median_hours: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct GameTag {
-    pub user_id: i64,
-    pub game_pk: i64,
+    pub user_id: UserId,
+    pub game_pk: GamePk,
     pub shortlist: i64,
     pub cant_go: i64,
 }
+
+/// A promotion in search results, with the game it runs at.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromotionListing {
+    pub offer_id: i64,
+    pub game_pk: GamePk,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub home_team_name: String,
+    pub name: String,
+    pub offer_type: Option<String>,
+    pub description: Option<String>,
+    pub distribution: Option<String>,
+    pub presented_by: Option<String>,
+}
+
+/// A promotion the member has flagged interest in.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromotionInterest {
+    pub offer_id: i64,
+    pub game_pk: GamePk,
+    pub name: String,
+    pub official_date: String,
+    pub away_team_name: String,
+    /// The member already has a pending or approved request for the game.
+    #[serde(with = "bool_as_i64")]
+    pub has_request: i64,
+    /// Seats are open for the game and the member hasn't asked for any yet.
+    #[serde(with = "bool_as_i64")]
+    pub suggest_request: i64,
+}
+
+/// How many members want a promotion, for the admin view.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PromotionInterestCount {
+    pub offer_id: i64,
+    pub game_pk: GamePk,
+    pub name: String,
+    pub official_date: String,
+    pub away_team_name: String,
+    pub interested: i64,
+    /// Interested members who also have a pending or approved request.
+    pub requested: i64,
+}
+
+/// A member's own settings, with the defaults for anything they haven't
+/// set: no default seat count, notifications to their account email, and
+/// every notification on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserPreferences {
+    /// Seats a new request asks for when it doesn't say.
+    pub default_seats: Option<i64>,
+    /// Where notification emails go instead of the account email.
+    pub contact_email: Option<String>,
+    /// A new contact address waiting for the member to follow the link
+    /// mailed to it; `contact_email` is used until then.
+    #[serde(default)]
+    pub pending_contact_email: Option<String>,
+    /// Each notification kind a member can turn off, and whether it's on.
+    pub notifications: BTreeMap<String, bool>,
+}
+
+impl UserPreferences {
+    /// Whether the member wants notifications of `kind`. Kinds they can't
+    /// turn off are always wanted.
+    pub fn wants(&self, kind: &str) -> bool {
+        self.notifications.get(kind).copied().unwrap_or(true)
+    }
+
+    /// The address for an email to a member whose account email is
+    /// `account_email`.
+    pub fn email<'a>(&'a self, account_email: &'a str) -> &'a str {
+        self.contact_email.as_deref().unwrap_or(account_email)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserPhone {
+    pub user_id: UserId,
+    pub phone: String,
+    #[serde(with = "bool_as_i64")]
+    pub verified: i64,
+    #[serde(with = "bool_as_i64")]
+    pub sms_opt_in: i64,
+}
@@ -0,0 +1,136 @@
+//! Parsing for inbound email replies (Mailgun routes / SES relays).
+//!
+//! Members can reply to any GTM email with one command per line:
+//!
+//! ```text
+//! RELEASE 2026-06-12
+//! REQUEST 6/14 2
+//! ```
+//!
+//! Dates may be `YYYY-MM-DD`, `M/D` or `M/D/YYYY`; a date without a year is
+//! taken to be in `default_year`. Lines that are not commands are ignored, and
+//! parsing stops at the first quoted line so the original email isn't re-read.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InboundCommand {
+    /// Give back any tickets held for games on this date.
+    Release { date: NaiveDate },
+    /// Request seats for games on this date.
+    Request { date: NaiveDate, seats: i64 },
+}
+
+/// A line that looked like a command but couldn't be understood.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: String,
+    pub reason: String,
+}
+
+/// Parse every command found in the subject and body of an inbound email.
+pub fn parse_commands(
+    subject: &str,
+    body: &str,
+    default_year: i32,
+) -> (Vec<InboundCommand>, Vec<ParseError>) {
+    let mut commands = Vec::new();
+    let mut errors = Vec::new();
+
+    // Replies usually carry "Re: ..." subjects; only treat the subject as a
+    // command when it actually starts with one.
+    let subject = subject.trim();
+    let lines = std::iter::once(subject).chain(body.lines());
+
+    for raw in lines {
+        let line = raw.trim();
+        if line.starts_with('>') || (line.starts_with("On ") && line.ends_with("wrote:")) {
+            break;
+        }
+        match parse_line(line, default_year) {
+            Some(Ok(cmd)) => commands.push(cmd),
+            Some(Err(reason)) => errors.push(ParseError {
+                line: line.to_string(),
+                reason,
+            }),
+            None => {}
+        }
+    }
+    (commands, errors)
+}
+
+/// Returns `None` for lines that aren't commands at all.
+fn parse_line(line: &str, default_year: i32) -> Option<Result<InboundCommand, String>> {
+    let mut words = line.split_whitespace();
+    let verb = words.next()?.to_ascii_uppercase();
+    let args: Vec<&str> = words.collect();
+
+    match verb.as_str() {
+        "RELEASE" => Some(match args.as_slice() {
+            [date] => parse_date(date, default_year).map(|date| InboundCommand::Release { date }),
+            _ => Err("expected: RELEASE <date>".to_string()),
+        }),
+        "REQUEST" => Some(match args.as_slice() {
+            [date] => parse_date(date, default_year)
+                .map(|date| InboundCommand::Request { date, seats: 1 }),
+            [date, seats] => {
+                let date = match parse_date(date, default_year) {
+                    Ok(d) => d,
+                    Err(e) => return Some(Err(e)),
+                };
+                match seats.parse::<i64>() {
+                    Ok(n) if n >= 1 => Ok(InboundCommand::Request { date, seats: n }),
+                    _ => Err(format!("seats must be a positive number (got {seats})")),
+                }
+            }
+            _ => Err("expected: REQUEST <date> [seats]".to_string()),
+        }),
+        _ => None,
+    }
+}
+
+fn parse_date(s: &str, default_year: i32) -> Result<NaiveDate, String> {
+    if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(d);
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(s, "%m/%d/%Y") {
+        return Ok(d);
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(&format!("{s}/{default_year}"), "%m/%d/%Y") {
+        return Ok(d);
+    }
+    Err(format!("unrecognized date '{s}' (use YYYY-MM-DD or M/D)"))
+}
+
+/// How far a Mailgun webhook's timestamp may be from `now`, in seconds.
+/// Older deliveries are refused so a captured request can't be replayed later.
+pub const MAX_SIGNATURE_AGE_SECS: i64 = 300;
+
+/// Verify a Mailgun webhook signature: hex(HMAC-SHA256(key, timestamp + token)),
+/// signed within [`MAX_SIGNATURE_AGE_SECS`] of `now`. The caller must still
+/// refuse a token it has seen before.
+pub fn verify_mailgun_signature(
+    signing_key: &str,
+    timestamp: &str,
+    token: &str,
+    signature: &str,
+    now: DateTime<Utc>,
+) -> bool {
+    let Ok(signed_at) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (now.timestamp() - signed_at).abs() > MAX_SIGNATURE_AGE_SECS {
+        return false;
+    }
+    let Ok(expected) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(signing_key.as_bytes()) else {
+        return false;
+    };
+    mac.update(timestamp.as_bytes());
+    mac.update(token.as_bytes());
+    mac.verify_slice(&expected).is_ok()
+}
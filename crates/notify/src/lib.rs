@@ -0,0 +1,199 @@
+use anyhow::Result;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tracing::info;
+
+pub mod inbound;
+pub mod packet;
+pub mod templates;
+pub mod webhooks;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Email,
+    Sms,
+}
+
+/// An outbound message to a single recipient. `to` is an email address for
+/// [`Channel::Email`] and an E.164 phone number for [`Channel::Sms`].
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub channel: Channel,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Notification {
+    pub fn email(to: &str, subject: &str, body: &str) -> Self {
+        Self {
+            channel: Channel::Email,
+            to: to.to_string(),
+            subject: subject.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    pub fn sms(to: &str, body: &str) -> Self {
+        Self {
+            channel: Channel::Sms,
+            to: to.to_string(),
+            subject: String::new(),
+            body: body.to_string(),
+        }
+    }
+}
+
+/// Mailgun HTTP API settings used for outbound email.
+#[derive(Debug, Clone)]
+pub struct MailgunConfig {
+    pub domain: String,
+    pub api_key: String,
+    pub from: String,
+}
+
+/// SMTP relay settings used for outbound email. Connects with STARTTLS on
+/// `port` (usually 587).
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub from: String,
+}
+
+/// Twilio Messaging API settings used for outbound SMS.
+#[derive(Debug, Clone)]
+pub struct TwilioConfig {
+    pub account_sid: String,
+    pub auth_token: String,
+    pub from_number: String,
+}
+
+/// Delivers notifications through whichever backends are configured.
+///
+/// A channel with no backend configured logs its messages instead of sending
+/// them, so local development never contacts real members. When both SMTP and
+/// Mailgun are configured, email goes over SMTP.
+#[derive(Clone, Default)]
+pub struct Notifier {
+    client: reqwest::Client,
+    smtp: Option<(SmtpConfig, AsyncSmtpTransport<Tokio1Executor>)>,
+    mailgun: Option<MailgunConfig>,
+    twilio: Option<TwilioConfig>,
+}
+
+impl Notifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_smtp(mut self, smtp: SmtpConfig) -> Result<Self> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host)?
+            .port(smtp.port)
+            .credentials(Credentials::new(
+                smtp.username.clone(),
+                smtp.password.clone(),
+            ))
+            .build();
+        self.smtp = Some((smtp, transport));
+        Ok(self)
+    }
+
+    pub fn with_mailgun(mut self, mailgun: MailgunConfig) -> Self {
+        self.mailgun = Some(mailgun);
+        self
+    }
+
+    pub fn with_twilio(mut self, twilio: TwilioConfig) -> Self {
+        self.twilio = Some(twilio);
+        self
+    }
+
+    /// True when the given channel has a real delivery backend.
+    pub fn is_enabled(&self, channel: Channel) -> bool {
+        match channel {
+            Channel::Email => self.smtp.is_some() || self.mailgun.is_some(),
+            Channel::Sms => self.twilio.is_some(),
+        }
+    }
+
+    pub async fn send(&self, n: &Notification) -> Result<()> {
+        if n.channel == Channel::Email
+            && let Some((cfg, transport)) = &self.smtp
+        {
+            return send_smtp(cfg, transport, n).await;
+        }
+        match (n.channel, &self.mailgun, &self.twilio) {
+            (Channel::Email, Some(mg), _) => self.send_mailgun(mg, n).await,
+            (Channel::Sms, _, Some(tw)) => self.send_twilio(tw, n).await,
+            (channel, _, _) => {
+                info!(?channel, to = %n.to, subject = %n.subject, "Notification (no backend configured)");
+                Ok(())
+            }
+        }
+    }
+
+    async fn send_mailgun(&self, mg: &MailgunConfig, n: &Notification) -> Result<()> {
+        let url = format!("https://api.mailgun.net/v3/{}/messages", mg.domain);
+        let form = [
+            ("from", mg.from.as_str()),
+            ("to", n.to.as_str()),
+            ("subject", n.subject.as_str()),
+            ("text", n.body.as_str()),
+        ];
+        self.client
+            .post(&url)
+            .basic_auth("api", Some(&mg.api_key))
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?;
+        info!(to = %n.to, subject = %n.subject, "Email sent via Mailgun");
+        Ok(())
+    }
+
+    async fn send_twilio(&self, tw: &TwilioConfig, n: &Notification) -> Result<()> {
+        let url = format!(
+            "https://api.twilio.com/2010-04-01/Accounts/{}/Messages.json",
+            tw.account_sid
+        );
+        let form = [
+            ("From", tw.from_number.as_str()),
+            ("To", n.to.as_str()),
+            ("Body", n.body.as_str()),
+        ];
+        self.client
+            .post(&url)
+            .basic_auth(&tw.account_sid, Some(&tw.auth_token))
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?;
+        info!(to = %n.to, "SMS sent via Twilio");
+        Ok(())
+    }
+}
+
+async fn send_smtp(
+    cfg: &SmtpConfig,
+    transport: &AsyncSmtpTransport<Tokio1Executor>,
+    n: &Notification,
+) -> Result<()> {
+    let message = Message::builder()
+        .from(cfg.from.parse()?)
+        .to(n.to.parse()?)
+        .subject(&n.subject)
+        .body(n.body.clone())?;
+    transport.send(message).await?;
+    info!(to = %n.to, subject = %n.subject, "Email sent via SMTP");
+    Ok(())
+}
+
+/// Loose E.164 check: `+` followed by 8–15 digits.
+pub fn is_valid_phone(phone: &str) -> bool {
+    phone
+        .strip_prefix('+')
+        .is_some_and(|d| (8..=15).contains(&d.len()) && d.chars().all(|c| c.is_ascii_digit()))
+}
@@ -0,0 +1,205 @@
+//! Game-day packets: what a member needs the night before a game, as an
+//! email or a one-page PDF, and a ZIP to hand an admin every packet at once.
+//!
+//! The PDF and ZIP writers cover only what packets need: a page of
+//! Helvetica text, and uncompressed archive entries.
+
+/// One member's packet for one game.
+#[derive(Debug, Clone)]
+pub struct Packet {
+    pub member: String,
+    /// Short game description, e.g. "6/12 vs Dodgers".
+    pub game: String,
+    /// First pitch as the member would read it, e.g. "Fri Jun 12, 7:15 PM".
+    pub start: String,
+    pub venue: String,
+    /// Seat labels like "Section VR313 Row A Seat 1".
+    pub seats: Vec<String>,
+    pub gate: Option<String>,
+    pub parking: Option<String>,
+    pub promotions: Vec<PacketPromotion>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PacketPromotion {
+    pub name: String,
+    /// Who gets it, e.g. "First 20,000 fans".
+    pub distribution: Option<String>,
+}
+
+impl Packet {
+    /// The packet as plain text lines, shared by the email and the PDF.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Game packet for {}", self.member),
+            String::new(),
+            self.game.clone(),
+            format!("First pitch: {}", self.start),
+            format!("Ballpark: {}", self.venue),
+            String::new(),
+            "Your seats:".to_string(),
+        ];
+        lines.extend(self.seats.iter().map(|s| format!("  {s}")));
+        if let Some(gate) = &self.gate {
+            lines.push(String::new());
+            lines.push(format!("Gate: {gate}"));
+        }
+        if let Some(parking) = &self.parking {
+            lines.push(String::new());
+            lines.push(format!("Parking: {parking}"));
+        }
+        if !self.promotions.is_empty() {
+            lines.push(String::new());
+            lines.push("Promotions:".to_string());
+            for p in &self.promotions {
+                lines.push(match &p.distribution {
+                    Some(d) => format!("  {} ({d})", p.name),
+                    None => format!("  {}", p.name),
+                });
+            }
+            lines.push("  Arrive early; giveaways are handed out at the gates.".to_string());
+        }
+        lines
+    }
+
+    pub fn email(&self) -> (String, String) {
+        let subject = format!("GTM: your game packet for {}", self.game);
+        let mut body = self.lines()[2..].join("\n");
+        body.push_str("\n\nCan't make it? Release your seats in the app so someone else can go.\n");
+        (subject, body)
+    }
+
+    pub fn pdf(&self) -> Vec<u8> {
+        pdf(&self.lines())
+    }
+}
+
+/// A single-page PDF of `lines` in 12pt Helvetica. Characters outside
+/// printable ASCII are replaced with `?`.
+pub fn pdf(lines: &[String]) -> Vec<u8> {
+    let mut content = String::from("BT\n/F1 12 Tf\n15 TL\n72 740 Td\n");
+    for line in lines {
+        let escaped: String = line
+            .chars()
+            .map(|c| {
+                if c == ' ' || c.is_ascii_graphic() {
+                    c
+                } else {
+                    '?'
+                }
+            })
+            .flat_map(|c| match c {
+                '(' | ')' | '\\' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect();
+        content.push_str(&format!("({escaped}) Tj T*\n"));
+    }
+    content.push_str("ET\n");
+
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+         /Resources << /Font << /F1 4 0 R >> >> /Contents 5 0 R >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        format!(
+            "<< /Length {} >>\nstream\n{content}endstream",
+            content.len()
+        ),
+    ];
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n{object}\nendobj\n", i + 1).as_bytes());
+    }
+    let xref = out.len();
+    out.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        out.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .as_bytes(),
+    );
+    out
+}
+
+/// A ZIP archive of `(name, contents)` files, stored without compression.
+pub fn zip(files: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in files {
+        let offset = out.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+        let name_len = name.len() as u16;
+
+        // Local file header
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        out.extend_from_slice(&0u16.to_le_bytes()); // flags
+        out.extend_from_slice(&0u16.to_le_bytes()); // stored
+        out.extend_from_slice(&0u16.to_le_bytes()); // mod time
+        out.extend_from_slice(&0x21u16.to_le_bytes()); // mod date: 1980-01-01
+        out.extend_from_slice(&crc.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(&name_len.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        // Central directory entry
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        central.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0x21u16.to_le_bytes());
+        central.extend_from_slice(&crc.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&name_len.to_le_bytes());
+        central.extend_from_slice(&[0; 8]); // extra, comment, disk, internal attrs
+        central.extend_from_slice(&0u32.to_le_bytes()); // external attrs
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+    let central_offset = out.len() as u32;
+    let count = files.len() as u16;
+    out.extend_from_slice(&central);
+    // End of central directory
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&[0; 4]); // disk numbers
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    out
+}
+
+/// CRC-32 (IEEE), as ZIP entries need.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
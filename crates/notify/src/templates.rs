@@ -0,0 +1,479 @@
+//! Message bodies for time-sensitive notifications.
+//!
+//! SMS bodies are kept under a single 160-character segment where possible.
+//!
+//! The allocation and reminder messages come in each [`Locale`] members can
+//! choose; their wording lives in [`VARIANTS`], keyed by [`Template`] and
+//! locale. A message without a variant in the member's locale goes out in
+//! English. Other messages are English only for now.
+
+/// Short game description used in SMS, e.g. "6/12 vs Dodgers".
+pub fn game_label(official_date: &str, opponent: &str) -> String {
+    let short_date = official_date
+        .get(5..)
+        .map(|md| {
+            let (m, d) = md.split_once('-').unwrap_or((md, ""));
+            format!(
+                "{}/{}",
+                m.trim_start_matches('0'),
+                d.trim_start_matches('0')
+            )
+        })
+        .unwrap_or_else(|| official_date.to_string());
+    format!("{short_date} vs {opponent}")
+}
+
+pub fn sms_verification_code(code: &str) -> String {
+    format!("GTM: your verification code is {code}. It expires in 10 minutes.")
+}
+
+/// Seats released on the day of the game — first come, first served.
+pub fn sms_day_of_release(locale: Locale, game: &str, seats: u64) -> String {
+    render(
+        Template::DayOfRelease,
+        locale,
+        &[("game", game), ("seats", &seats.to_string())],
+    )
+    .1
+}
+
+/// A pending offer of tickets is about to lapse.
+pub fn sms_offer_expiring(locale: Locale, game: &str, expires_in: &str) -> String {
+    render(
+        Template::OfferExpiring,
+        locale,
+        &[("game", game), ("expires_in", expires_in)],
+    )
+    .1
+}
+
+/// Admin/group digest of upcoming games with seats nobody has asked for.
+/// Each entry is `(game label, available seats)`.
+pub fn email_unassigned_seats(games: &[(String, i64)]) -> (String, String) {
+    let total: i64 = games.iter().map(|(_, n)| n).sum();
+    let subject = format!("GTM: {total} unclaimed seat(s) in the next few days");
+    let mut body =
+        String::from("These upcoming games still have seats and no pending requests:\n\n");
+    for (game, n) in games {
+        body.push_str(&format!("  {game}: {n} seat(s)\n"));
+    }
+    body.push_str("\nReply REQUEST <date> [seats] or use the app to claim them.\n");
+    (subject, body)
+}
+
+/// Tell admins a member paid online more than their invoice owed, so the
+/// difference can be refunded in Stripe.
+pub fn email_online_overpayment(
+    member: &str,
+    period: &str,
+    overpaid_cents: i64,
+) -> (String, String) {
+    let amount = format!("${}.{:02}", overpaid_cents / 100, overpaid_cents % 100);
+    let subject = format!("GTM: {member} overpaid their {period} invoice by {amount}");
+    let body = format!(
+        "{member} paid {amount} more online than their {period} invoice owed. \
+         Only what was owed was recorded against the invoice; refund the \
+         difference from the Stripe dashboard.\n"
+    );
+    (subject, body)
+}
+
+/// Admin alert that a schedule scrape looked suspect and was only applied.
+/// `anomalies` says what looked wrong with it.
+pub fn email_suspect_scrape(season: u32, anomalies: &[String]) -> (String, String) {
+    let subject = format!("GTM: {season} schedule scrape looks suspect");
+    let mut body = String::from(
+        "The schedule scrape returned much less than is stored, so it was only \
+         applied to the games it returned:\n\n",
+    );
+    for anomaly in anomalies {
+        body.push_str(&format!("  {anomaly}\n"));
+    }
+    body.push_str(
+        "\nNothing was removed, and no seats or requests were moved to makeups or \
+         carried over. If the change is real, run `gtm scrape-schedule --force`.\n",
+    );
+    (subject, body)
+}
+
+/// Sent to a member's new contact address; it's only used once they follow
+/// `confirm_url`.
+pub fn email_confirm_contact_email(confirm_url: &str) -> (String, String) {
+    let subject = "GTM: confirm your email address".to_string();
+    let body = format!(
+        "Someone asked for GTM's emails, including ticket links, to come to this \
+         address. If it was you, confirm here:\n\n  {confirm_url}\n\n\
+         If not, ignore this email and nothing will change.\n"
+    );
+    (subject, body)
+}
+
+/// Rain is likely for a game the member holds seats for. `preview` is the
+/// game's stored preview text, when there is one.
+pub fn email_rain_release_prompt(
+    locale: Locale,
+    game: &str,
+    rain_probability: i64,
+    release_url: &str,
+    keep_url: &str,
+    preview: Option<&str>,
+) -> (String, String) {
+    let (subject, mut body) = render(
+        Template::RainReleasePrompt,
+        locale,
+        &[
+            ("game", game),
+            ("rain", &rain_probability.to_string()),
+            ("release_url", release_url),
+            ("keep_url", keep_url),
+        ],
+    );
+    if let Some(preview) = preview {
+        body.push_str(&render(Template::GamePreview, locale, &[("preview", preview)]).1);
+    }
+    (subject, body)
+}
+
+/// Seats were assigned to the member. `seats` are labels like "VR313 Row A Seat 1".
+pub fn email_tickets_assigned(locale: Locale, game: &str, seats: &[String]) -> (String, String) {
+    render(
+        Template::TicketsAssigned,
+        locale,
+        &[
+            ("game", game),
+            ("count", &seats.len().to_string()),
+            ("seats", &seat_lines(seats)),
+        ],
+    )
+}
+
+/// Another member handed their seats to this one.
+pub fn email_tickets_transferred(
+    locale: Locale,
+    game: &str,
+    from: &str,
+    seats: &[String],
+) -> (String, String) {
+    render(
+        Template::TicketsTransferred,
+        locale,
+        &[
+            ("game", game),
+            ("from", from),
+            ("count", &seats.len().to_string()),
+            ("seats", &seat_lines(seats)),
+        ],
+    )
+}
+
+/// An admin took back seats the member held.
+pub fn email_tickets_revoked(locale: Locale, game: &str, seats: &[String]) -> (String, String) {
+    render(
+        Template::TicketsRevoked,
+        locale,
+        &[("game", game), ("seats", &seat_lines(seats))],
+    )
+}
+
+/// Every seat for a game the member asked for has been handed out.
+pub fn email_game_fully_allocated(locale: Locale, game: &str) -> (String, String) {
+    render(Template::GameFullyAllocated, locale, &[("game", game)])
+}
+
+/// A requested game moved; the member's request moved with it. `previous`
+/// is the old date as the member would recognise it.
+pub fn email_request_moved(game: &str, previous: &str) -> (String, String) {
+    let subject = format!("GTM: your requested game moved to {game}");
+    let body = format!(
+        "The game you requested on {previous} has been rescheduled to {game}.\n\n\
+         Your request has moved with it. If the new date doesn't work, withdraw it in the app.\n"
+    );
+    (subject, body)
+}
+
+/// A requested game was postponed and has no new date yet.
+pub fn email_request_postponed(game: &str) -> (String, String) {
+    let subject = format!("GTM: {game} was postponed");
+    let body = format!(
+        "{game} has been postponed. Your request stays open and will follow the game \
+         once a new date is announced.\n"
+    );
+    (subject, body)
+}
+
+/// A schedule change put the member's request on hold for an admin.
+pub fn email_request_flagged(game: &str, reason: &str) -> (String, String) {
+    let subject = format!("GTM: your request for {game} needs a look");
+    let body = format!(
+        "Your request for {game} has been set aside for an admin to review: {reason}.\n\n\
+         Update or withdraw it in the app if your plans have changed.\n"
+    );
+    (subject, body)
+}
+
+/// An admin declined the member's request.
+pub fn email_request_declined(game: &str) -> (String, String) {
+    let subject = format!("GTM: your request for {game} was declined");
+    let body = format!(
+        "Your request for {game} was declined, so no seats will be assigned for it.\n\n\
+         Ask an admin if you have questions.\n"
+    );
+    (subject, body)
+}
+
+/// A postponed game's makeup was scheduled under a new game, and the
+/// member's seats and request were moved to it. `problem` is what couldn't
+/// move, left for an admin.
+pub fn email_game_made_up(
+    game: &str,
+    postponed: &str,
+    seats: usize,
+    request_moved: bool,
+    problem: Option<&str>,
+) -> (String, String) {
+    let subject = format!("GTM: {postponed} was postponed; the makeup is {game}");
+    let mut body = format!("{postponed} was postponed. It will be made up as {game}.\n\n");
+    if seats > 0 {
+        body.push_str(&format!(
+            "Your {seats} seat(s) moved to the makeup game; they're the same seats.\n"
+        ));
+    }
+    if request_moved {
+        body.push_str("Your request moved to the makeup game.\n");
+    }
+    if let Some(problem) = problem {
+        body.push_str(&format!(
+            "\nNot everything could move ({problem}); an admin will sort it out.\n"
+        ));
+    }
+    body.push_str("\nIf the new date doesn't work, release your seats or withdraw in the app.\n");
+    (subject, body)
+}
+
+// --- Locales ---
+
+/// A language members can get their notifications in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Es];
+
+    /// The code stored on the member's profile.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+        }
+    }
+
+    /// A language tag such as `es`, `es-MX` or `ES_us`; `None` for a
+    /// language there are no templates in.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let language = tag.trim().split(['-', '_']).next().unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|l| l.code().eq_ignore_ascii_case(language))
+    }
+
+    /// A member's stored locale, English when it's unset or unknown.
+    pub fn for_member(stored: Option<&str>) -> Self {
+        stored.and_then(Self::parse).unwrap_or_default()
+    }
+}
+
+/// Messages with wording in more than one locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    TicketsAssigned,
+    TicketsTransferred,
+    TicketsRevoked,
+    GameFullyAllocated,
+    DayOfRelease,
+    OfferExpiring,
+    RainReleasePrompt,
+    /// Appended to the rain prompt when the game has a preview.
+    GamePreview,
+}
+
+/// One message's wording in one locale. `{name}` marks where a value goes;
+/// SMS variants have no subject.
+pub struct Variant {
+    pub template: Template,
+    pub locale: Locale,
+    pub subject: &'static str,
+    pub body: &'static str,
+}
+
+/// Every message's wording. English must have every template, since it's
+/// what the others fall back to. SMS wording sticks to the GSM character
+/// set (no accented vowels but é) so a message stays one 160-character
+/// segment instead of 70.
+pub const VARIANTS: &[Variant] = &[
+    Variant {
+        template: Template::TicketsAssigned,
+        locale: Locale::En,
+        subject: "GTM: you have {count} seat(s) for {game}",
+        body: "You've been assigned seats for {game}:\n\n{seats}\n\
+               Can't make it? Release them in the app so someone else can go.\n",
+    },
+    Variant {
+        template: Template::TicketsAssigned,
+        locale: Locale::Es,
+        subject: "GTM: tienes {count} asiento(s) para {game}",
+        body: "Se te asignaron asientos para {game}:\n\n{seats}\n\
+               ¿No puedes ir? Libéralos en la app para que otra persona pueda ir.\n",
+    },
+    Variant {
+        template: Template::TicketsTransferred,
+        locale: Locale::En,
+        subject: "GTM: {from} passed you {count} seat(s) for {game}",
+        body: "{from} transferred their seats for {game} to you:\n\n{seats}\n\
+               Can't make it? Release them in the app so someone else can go.\n",
+    },
+    Variant {
+        template: Template::TicketsTransferred,
+        locale: Locale::Es,
+        subject: "GTM: {from} te pasó {count} asiento(s) para {game}",
+        body: "{from} te transfirió sus asientos para {game}:\n\n{seats}\n\
+               ¿No puedes ir? Libéralos en la app para que otra persona pueda ir.\n",
+    },
+    Variant {
+        template: Template::TicketsRevoked,
+        locale: Locale::En,
+        subject: "GTM: your seats for {game} were reassigned",
+        body: "These seats for {game} are no longer assigned to you:\n\n{seats}\n\
+               Questions? Reply to this email.\n",
+    },
+    Variant {
+        template: Template::TicketsRevoked,
+        locale: Locale::Es,
+        subject: "GTM: tus asientos para {game} fueron reasignados",
+        body: "Estos asientos para {game} ya no están asignados a ti:\n\n{seats}\n\
+               ¿Preguntas? Responde a este correo.\n",
+    },
+    Variant {
+        template: Template::GameFullyAllocated,
+        locale: Locale::En,
+        subject: "GTM: {game} is fully allocated",
+        body: "All seats for {game} have been assigned, so we couldn't fill your request this time.\n\n\
+               If someone releases their seats you'll be able to claim them in the app.\n",
+    },
+    Variant {
+        template: Template::GameFullyAllocated,
+        locale: Locale::Es,
+        subject: "GTM: ya no quedan asientos para {game}",
+        body: "Todos los asientos para {game} ya fueron asignados, así que esta vez no pudimos \
+               atender tu solicitud.\n\n\
+               Si alguien libera sus asientos, podrás reclamarlos en la app.\n",
+    },
+    Variant {
+        template: Template::DayOfRelease,
+        locale: Locale::En,
+        subject: "",
+        body: "GTM: {seats} seat(s) just opened up for TODAY's game ({game}). Claim them in the app.",
+    },
+    Variant {
+        template: Template::DayOfRelease,
+        locale: Locale::Es,
+        subject: "",
+        body: "GTM: se liberaron {seats} asiento(s) para el partido de HOY ({game}). \
+               Puedes reclamarlos en la app.",
+    },
+    Variant {
+        template: Template::OfferExpiring,
+        locale: Locale::En,
+        subject: "",
+        body: "GTM: your ticket offer for {game} expires in {expires_in}. Accept or decline in the app.",
+    },
+    Variant {
+        template: Template::OfferExpiring,
+        locale: Locale::Es,
+        subject: "",
+        body: "GTM: tu oferta de boletos para {game} vence en {expires_in}. \
+               Puedes aceptarla o rechazarla en la app.",
+    },
+    Variant {
+        template: Template::RainReleasePrompt,
+        locale: Locale::En,
+        subject: "GTM: {rain}% chance of rain for {game}",
+        body: "The forecast shows a {rain}% chance of rain during {game}.\n\n\
+               If you'd rather not go, release your seats so someone else can use them:\n  {release_url}\n\n\
+               Still going? Let us know and we won't ask again:\n  {keep_url}\n",
+    },
+    Variant {
+        template: Template::RainReleasePrompt,
+        locale: Locale::Es,
+        subject: "GTM: {rain}% de probabilidad de lluvia para {game}",
+        body: "El pronóstico indica un {rain}% de probabilidad de lluvia durante {game}.\n\n\
+               Si prefieres no ir, libera tus asientos para que otra persona los use:\n  {release_url}\n\n\
+               ¿Sigues pensando ir? Avísanos y no volveremos a preguntar:\n  {keep_url}\n",
+    },
+    Variant {
+        template: Template::GamePreview,
+        locale: Locale::En,
+        subject: "",
+        body: "\nGame preview:\n{preview}\n",
+    },
+    Variant {
+        template: Template::GamePreview,
+        locale: Locale::Es,
+        subject: "",
+        body: "\nAvance del partido:\n{preview}\n",
+    },
+];
+
+/// `template`'s wording in `locale`, or in English if it has none.
+pub fn variant(template: Template, locale: Locale) -> &'static Variant {
+    let find = |locale| {
+        VARIANTS
+            .iter()
+            .find(|v| v.template == template && v.locale == locale)
+    };
+    find(locale)
+        .or_else(|| find(Locale::En))
+        .unwrap_or_else(|| panic!("{template:?} has no English wording"))
+}
+
+/// `template` in `locale` as `(subject, body)`, with each `{name}` replaced
+/// by its value in `args`. Placeholders without a value are left as is.
+pub fn render(template: Template, locale: Locale, args: &[(&str, &str)]) -> (String, String) {
+    let v = variant(template, locale);
+    (fill(v.subject, args), fill(v.body, args))
+}
+
+fn fill(text: &str, args: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after.find('}').and_then(|close| {
+            let name = &after[..close];
+            args.iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| (*v, close))
+        });
+        match value {
+            Some((value, close)) => {
+                out.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Seat labels, one indented line each.
+fn seat_lines(seats: &[String]) -> String {
+    seats.iter().map(|seat| format!("  {seat}\n")).collect()
+}
@@ -0,0 +1,118 @@
+//! Posting signed payloads to admin-registered webhooks.
+//!
+//! Each request carries the event name and a signature header in the Stripe
+//! style, `t=<unix>,v1=<hex>`, where the hex is HMAC-SHA256 of
+//! `"<t>.<body>"` keyed with the webhook's secret, so receivers can check
+//! both who sent it and when.
+//!
+//! Webhook URLs are admin input, so a post only goes to public addresses:
+//! the host is resolved and checked with [`resolve_target`] before each
+//! delivery, the connection is pinned to the addresses checked, and
+//! redirects aren't followed.
+
+use anyhow::{Context, Result, bail};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+pub const EVENT_HEADER: &str = "X-GTM-Event";
+pub const SIGNATURE_HEADER: &str = "X-GTM-Signature";
+
+/// How long an endpoint gets to answer before the attempt counts as failed.
+const TIMEOUT_SECS: u64 = 10;
+
+/// The signature header value for `body` sent at `timestamp` (unix seconds).
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!(
+        "t={timestamp},v1={}",
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Whether `ip` is on the public internet: not loopback, private, link-local,
+/// shared (carrier-grade NAT), documentation, multicast or unspecified.
+pub fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && b & 0xc0 == 64))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || first & 0xfe00 == 0xfc00
+                || first & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+/// Resolve `url`'s host and check that every address it has is public, so
+/// a webhook can't reach the server's own network. Returns the host and the
+/// addresses to connect to.
+pub async fn resolve_target(url: &str) -> Result<(String, Vec<SocketAddr>)> {
+    let parsed = reqwest::Url::parse(url).context("Invalid webhook URL")?;
+    if !matches!(parsed.scheme(), "https" | "http") {
+        bail!("Webhook URL must start with https:// or http://");
+    }
+    let host = parsed
+        .host_str()
+        .context("Webhook URL has no host")?
+        .to_string();
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<SocketAddr> =
+        tokio::net::lookup_host((host.trim_start_matches('[').trim_end_matches(']'), port))
+            .await
+            .with_context(|| format!("Could not resolve {host}"))?
+            .collect();
+    if addrs.is_empty() {
+        bail!("{host} has no addresses");
+    }
+    if let Some(addr) = addrs.iter().find(|a| !is_public(a.ip())) {
+        bail!(
+            "{host} resolves to {}, which isn't a public address",
+            addr.ip()
+        );
+    }
+    Ok((host, addrs))
+}
+
+/// POST `body` to `url`, signed with `secret`. Returns the HTTP status the
+/// endpoint answered with, whatever it was, a redirect included; errors mean
+/// it didn't answer or isn't public.
+pub async fn post(url: &str, secret: &str, event: &str, body: &str) -> Result<u16> {
+    let (host, addrs) = resolve_target(url).await?;
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve_to_addrs(&host, &addrs)
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .build()?;
+    let timestamp = chrono::Utc::now().timestamp();
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event)
+        .header(SIGNATURE_HEADER, signature(secret, timestamp, body))
+        .body(body.to_string())
+        .send()
+        .await?;
+    Ok(response.status().as_u16())
+}
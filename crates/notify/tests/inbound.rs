@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use gtm_notify::inbound::{
+    InboundCommand, MAX_SIGNATURE_AGE_SECS, parse_commands, verify_mailgun_signature,
+};
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[test]
+fn parses_release_and_request_lines() {
+    let body = "Hi,\nRELEASE 2026-06-12\nrequest 6/14 2\nThanks!";
+    let (cmds, errors) = parse_commands("Re: Your tickets", body, 2026);
+    assert!(errors.is_empty());
+    assert_eq!(
+        cmds,
+        vec![
+            InboundCommand::Release {
+                date: date(2026, 6, 12)
+            },
+            InboundCommand::Request {
+                date: date(2026, 6, 14),
+                seats: 2
+            },
+        ]
+    );
+}
+
+#[test]
+fn subject_can_carry_a_command() {
+    let (cmds, _) = parse_commands("RELEASE 7/4/2026", "", 2025);
+    assert_eq!(
+        cmds,
+        vec![InboundCommand::Release {
+            date: date(2026, 7, 4)
+        }]
+    );
+}
+
+#[test]
+fn stops_at_quoted_reply() {
+    let body = "RELEASE 6/12\n\nOn Mon, Jun 1, 2026 GTM wrote:\n> REQUEST 6/20 4";
+    let (cmds, _) = parse_commands("", body, 2026);
+    assert_eq!(cmds.len(), 1);
+}
+
+#[test]
+fn reports_malformed_commands() {
+    let (cmds, errors) = parse_commands("", "REQUEST 6/12 0\nRELEASE friday", 2026);
+    assert!(cmds.is_empty());
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].reason.contains("seats"));
+}
+
+#[test]
+fn seat_count_is_checked_against_the_game_not_the_parser() {
+    let (cmds, errors) = parse_commands("", "REQUEST 6/12 6", 2026);
+    assert!(errors.is_empty());
+    assert_eq!(
+        cmds,
+        vec![InboundCommand::Request {
+            date: date(2026, 6, 12),
+            seats: 6
+        }]
+    );
+}
+
+#[test]
+fn mailgun_signature_round_trip() {
+    // Mailgun signs hex(HMAC-SHA256(signing_key, timestamp + token))
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"key-secret").unwrap();
+    mac.update(b"1700000000abc123");
+    let sig = hex::encode(mac.finalize().into_bytes());
+    let now = chrono::DateTime::from_timestamp(1_700_000_060, 0).unwrap();
+
+    assert!(verify_mailgun_signature(
+        "key-secret",
+        "1700000000",
+        "abc123",
+        &sig,
+        now
+    ));
+    assert!(!verify_mailgun_signature(
+        "wrong-key",
+        "1700000000",
+        "abc123",
+        &sig,
+        now
+    ));
+}
+
+#[test]
+fn mailgun_signature_must_be_recent() {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"key-secret").unwrap();
+    mac.update(b"1700000000abc123");
+    let sig = hex::encode(mac.finalize().into_bytes());
+    let signed = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+    let verify = |now| verify_mailgun_signature("key-secret", "1700000000", "abc123", &sig, now);
+
+    assert!(verify(
+        signed + chrono::Duration::seconds(MAX_SIGNATURE_AGE_SECS)
+    ));
+    assert!(!verify(
+        signed + chrono::Duration::seconds(MAX_SIGNATURE_AGE_SECS + 1)
+    ));
+    assert!(!verify(signed - chrono::Duration::hours(1)));
+}
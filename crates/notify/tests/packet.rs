@@ -0,0 +1,52 @@
+use gtm_notify::packet::{self, Packet, PacketPromotion};
+
+fn sample_packet() -> Packet {
+    Packet {
+        member: "Renee".to_string(),
+        game: "6/12 vs Los Angeles Dodgers".to_string(),
+        start: "Fri Jun 12, 7:15 PM".to_string(),
+        venue: "Oracle Park".to_string(),
+        seats: vec![
+            "Section VR313 Row A Seat 1".to_string(),
+            "Section VR313 Row A Seat 2".to_string(),
+        ],
+        gate: Some("Willie Mays Gate".to_string()),
+        parking: None,
+        promotions: vec![PacketPromotion {
+            name: "Bobblehead (Buster Posey)".to_string(),
+            distribution: Some("First 20,000 fans".to_string()),
+        }],
+    }
+}
+
+#[test]
+fn packet_email_lists_seats_gate_and_promotions() {
+    let (subject, body) = sample_packet().email();
+    assert!(subject.contains("6/12 vs Los Angeles Dodgers"));
+    assert!(body.contains("Seat 1") && body.contains("Seat 2"));
+    assert!(body.contains("Gate: Willie Mays Gate"));
+    assert!(body.contains("First 20,000 fans"));
+    assert!(!body.contains("Parking"));
+}
+
+#[test]
+fn packet_pdf_escapes_text_and_ends_with_trailer() {
+    let pdf = String::from_utf8(sample_packet().pdf()).unwrap();
+    assert!(pdf.starts_with("%PDF-1.4"));
+    assert!(pdf.contains("(  Bobblehead \\(Buster Posey\\) \\(First 20,000 fans\\)) Tj"));
+    assert!(pdf.trim_end().ends_with("%%EOF"));
+}
+
+#[test]
+fn zip_stores_files_with_their_crc() {
+    let zip = packet::zip(&[
+        ("a.txt".to_string(), b"123456789".to_vec()),
+        ("b.txt".to_string(), Vec::new()),
+    ]);
+    assert_eq!(&zip[..4], b"PK\x03\x04");
+    // CRC-32 of "123456789" is the standard check value
+    assert_eq!(&zip[14..18], &0xCBF4_3926u32.to_le_bytes());
+    let eocd = &zip[zip.len() - 22..];
+    assert_eq!(&eocd[..4], b"PK\x05\x06");
+    assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 2);
+}
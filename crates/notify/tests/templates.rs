@@ -0,0 +1,172 @@
+use gtm_notify::templates::{self, Locale, Template, VARIANTS};
+
+#[test]
+fn game_label_shortens_date() {
+    assert_eq!(
+        templates::game_label("2026-06-05", "Los Angeles Dodgers"),
+        "6/5 vs Los Angeles Dodgers"
+    );
+}
+
+#[test]
+fn sms_templates_fit_one_segment() {
+    let game = templates::game_label("2026-09-28", "Arizona Diamondbacks");
+    assert!(templates::sms_day_of_release(Locale::En, &game, 4).len() <= 160);
+    assert!(templates::sms_offer_expiring(Locale::En, &game, "2 hours").len() <= 160);
+    assert!(templates::sms_verification_code("123456").len() <= 160);
+}
+
+#[test]
+fn allocation_emails_list_seats() {
+    let game = templates::game_label("2026-06-05", "Los Angeles Dodgers");
+    let seats = vec![
+        "Section VR313 Row A Seat 1".to_string(),
+        "Section VR313 Row A Seat 2".to_string(),
+    ];
+    let (subject, body) = templates::email_tickets_assigned(Locale::En, &game, &seats);
+    assert!(subject.contains("2 seat(s)"));
+    assert!(body.contains("Seat 1") && body.contains("Seat 2"));
+
+    let (subject, body) = templates::email_tickets_revoked(Locale::En, &game, &seats[..1]);
+    assert!(subject.contains(&game));
+    assert!(body.contains("Seat 1") && !body.contains("Seat 2"));
+}
+
+#[test]
+fn schedule_change_emails_name_the_game() {
+    let (subject, body) =
+        templates::email_request_moved("6/6 vs Los Angeles Dodgers", "6/5 vs Los Angeles Dodgers");
+    assert!(subject.contains("6/6"));
+    assert!(body.contains("6/5") && body.contains("6/6"));
+
+    let (_, body) =
+        templates::email_request_flagged("6/5 vs Los Angeles Dodgers", "Game cancelled");
+    assert!(body.contains("Game cancelled"));
+
+    let (subject, _) = templates::email_request_declined("6/5 vs Los Angeles Dodgers");
+    assert!(subject.contains("declined") && subject.contains("6/5"));
+}
+
+#[test]
+fn makeup_email_says_what_moved() {
+    let game = "6/6 vs Los Angeles Dodgers";
+    let postponed = "6/5 vs Los Angeles Dodgers";
+    let (subject, body) = templates::email_game_made_up(game, postponed, 2, true, None);
+    assert!(subject.contains("6/5") && subject.contains("6/6"));
+    assert!(body.contains("Your 2 seat(s)") && body.contains("Your request moved"));
+    assert!(!body.contains("admin"));
+
+    let (_, body) =
+        templates::email_game_made_up(game, postponed, 0, false, Some("seat 3 is taken"));
+    assert!(!body.contains("Your 2 seat(s)") && !body.contains("Your request moved"));
+    assert!(body.contains("seat 3 is taken"));
+}
+
+#[test]
+fn phone_validation() {
+    assert!(gtm_notify::is_valid_phone("+14155550123"));
+    assert!(!gtm_notify::is_valid_phone("4155550123"));
+    assert!(!gtm_notify::is_valid_phone("+1415555abcd"));
+}
+
+#[test]
+fn rain_prompt_includes_preview() {
+    let game = templates::game_label("2026-06-05", "Los Angeles Dodgers");
+    let (_, body) = templates::email_rain_release_prompt(Locale::En, &game, 70, "r", "k", None);
+    assert!(!body.contains("Game preview"));
+    let (_, body) = templates::email_rain_release_prompt(
+        Locale::En,
+        &game,
+        70,
+        "r",
+        "k",
+        Some("Probable pitchers: TBD vs Logan Webb"),
+    );
+    assert!(body.contains("Game preview:\nProbable pitchers: TBD vs Logan Webb"));
+}
+
+#[test]
+fn locales_parse_from_language_tags() {
+    assert_eq!(Locale::parse("es"), Some(Locale::Es));
+    assert_eq!(Locale::parse("es-MX"), Some(Locale::Es));
+    assert_eq!(Locale::parse("ES_us"), Some(Locale::Es));
+    assert_eq!(Locale::parse("en-GB"), Some(Locale::En));
+    assert_eq!(Locale::parse("fr"), None);
+    assert_eq!(Locale::for_member(Some("fr")), Locale::En);
+    assert_eq!(Locale::for_member(None), Locale::En);
+}
+
+#[test]
+fn spanish_variants_are_used_and_fit_one_segment() {
+    let game = templates::game_label("2026-06-05", "Los Angeles Dodgers");
+    let (subject, body) = templates::email_tickets_assigned(
+        Locale::Es,
+        &game,
+        &["Section VR313 Row A Seat 1".into()],
+    );
+    assert_eq!(
+        subject,
+        "GTM: tienes 1 asiento(s) para 6/5 vs Los Angeles Dodgers"
+    );
+    assert!(body.contains("  Section VR313 Row A Seat 1\n"));
+
+    let (_, body) = templates::email_rain_release_prompt(
+        Locale::Es,
+        &game,
+        70,
+        "r",
+        "k",
+        Some("Probable pitchers: TBD vs Logan Webb"),
+    );
+    assert!(body.contains("70% de probabilidad"));
+    assert!(body.ends_with("Avance del partido:\nProbable pitchers: TBD vs Logan Webb\n"));
+
+    for sms in [
+        templates::sms_day_of_release(Locale::Es, &game, 4),
+        templates::sms_offer_expiring(Locale::Es, &game, "2 horas"),
+    ] {
+        assert!(sms.len() <= 160, "{sms}");
+        assert!(sms.is_ascii(), "{sms}");
+    }
+}
+
+#[test]
+fn every_template_has_english_to_fall_back_to() {
+    for template in [
+        Template::TicketsAssigned,
+        Template::TicketsTransferred,
+        Template::TicketsRevoked,
+        Template::GameFullyAllocated,
+        Template::DayOfRelease,
+        Template::OfferExpiring,
+        Template::RainReleasePrompt,
+        Template::GamePreview,
+    ] {
+        assert!(
+            VARIANTS
+                .iter()
+                .any(|v| v.template == template && v.locale == Locale::En),
+            "{template:?}"
+        );
+        for locale in Locale::ALL {
+            assert_eq!(templates::variant(template, locale).template, template);
+        }
+    }
+}
+
+#[test]
+fn render_fills_each_placeholder_once() {
+    let (subject, _) = templates::render(
+        Template::TicketsTransferred,
+        Locale::En,
+        &[
+            ("from", "{game}"),
+            ("game", "6/5 vs Dodgers"),
+            ("count", "2"),
+        ],
+    );
+    assert_eq!(
+        subject,
+        "GTM: {game} passed you 2 seat(s) for 6/5 vs Dodgers"
+    );
+}
@@ -0,0 +1,67 @@
+use gtm_notify::webhooks::signature;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+#[test]
+fn signature_is_an_hmac_of_the_timestamp_and_body() {
+    let body = r#"{"event":"ticket.assigned"}"#;
+    let header = signature("whsec_test", 1_760_000_000, body);
+    let (t, v1) = header.split_once(',').unwrap();
+    assert_eq!(t, "t=1760000000");
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"whsec_test").unwrap();
+    mac.update(format!("1760000000.{body}").as_bytes());
+    mac.verify_slice(&hex::decode(v1.strip_prefix("v1=").unwrap()).unwrap())
+        .unwrap();
+
+    assert_ne!(signature("whsec_other", 1_760_000_000, body), header);
+    assert_ne!(signature("whsec_test", 1_760_000_001, body), header);
+}
+
+#[test]
+fn only_public_addresses_are_webhook_targets() {
+    use gtm_notify::webhooks::is_public;
+
+    for private in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.10",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "224.0.0.1",
+        "::1",
+        "::",
+        "fe80::1",
+        "fd00::1",
+        "::ffff:10.0.0.1",
+    ] {
+        assert!(!is_public(private.parse().unwrap()), "{private}");
+    }
+    for public in ["93.184.215.14", "8.8.8.8", "2606:4700::1111"] {
+        assert!(is_public(public.parse().unwrap()), "{public}");
+    }
+}
+
+#[tokio::test]
+async fn webhooks_to_private_hosts_are_refused_before_connecting() {
+    use gtm_notify::webhooks::{post, resolve_target};
+
+    for url in [
+        "http://127.0.0.1:8080/hook",
+        "http://localhost/hook",
+        "http://[::1]/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "ftp://93.184.215.14/hook",
+    ] {
+        assert!(resolve_target(url).await.is_err(), "{url}");
+        assert!(
+            post(url, "s", "ticket.assigned", "{}").await.is_err(),
+            "{url}"
+        );
+    }
+    let (host, addrs) = resolve_target("https://93.184.215.14/hook").await.unwrap();
+    assert_eq!(host, "93.184.215.14");
+    assert_eq!(addrs, vec!["93.184.215.14:443".parse().unwrap()]);
+}
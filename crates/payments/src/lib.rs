@@ -0,0 +1,228 @@
+//! Optional Stripe integration: a Checkout link to pay an invoice online,
+//! and verification of the webhook Stripe calls when a payment completes.
+//!
+//! Only the Stripe calls live here; which invoice is paid and how the
+//! payment is recorded is the caller's job (see `gtm_db::invoices`).
+
+use anyhow::{Context, Result, bail};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::time::Duration;
+use tracing::info;
+
+pub const DEFAULT_API_BASE: &str = "https://api.stripe.com/v1";
+
+/// Oldest webhook timestamp accepted, in seconds, against replays.
+pub const WEBHOOK_TOLERANCE_SECS: i64 = 300;
+
+/// Longest a call to the Stripe API may take.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The event a paid Checkout Session sends.
+pub const CHECKOUT_COMPLETED: &str = "checkout.session.completed";
+
+/// Stripe account settings.
+#[derive(Debug, Clone)]
+pub struct StripeConfig {
+    /// Secret API key (`sk_...`).
+    pub secret_key: String,
+    /// Signing secret of the webhook endpoint (`whsec_...`).
+    pub webhook_secret: String,
+    /// ISO currency code, lowercase, e.g. "usd".
+    pub currency: String,
+    /// Where Stripe sends the member after paying or giving up.
+    pub success_url: String,
+    pub cancel_url: String,
+}
+
+/// What to charge for one invoice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvoiceCharge {
+    pub invoice_id: i64,
+    /// Cents.
+    pub amount: i64,
+    /// Shown on the Stripe checkout page, e.g. "Season tickets, June 2026".
+    pub description: String,
+    pub email: Option<String>,
+}
+
+/// A Checkout Session created for an invoice.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckoutLink {
+    pub id: String,
+    pub url: String,
+}
+
+/// A completed payment from a webhook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedCheckout {
+    pub session_id: String,
+    pub invoice_id: i64,
+    /// Cents actually paid.
+    pub amount: i64,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    #[serde(rename = "type")]
+    kind: String,
+    data: EventData,
+}
+
+#[derive(Deserialize)]
+struct EventData {
+    object: Session,
+}
+
+#[derive(Deserialize)]
+struct Session {
+    id: String,
+    client_reference_id: Option<String>,
+    amount_total: Option<i64>,
+    payment_status: Option<String>,
+}
+
+/// Stripe client. Without a [`StripeConfig`] online payment is disabled.
+#[derive(Clone, Default)]
+pub struct Payments {
+    client: reqwest::Client,
+    config: Option<StripeConfig>,
+    api_base: Option<String>,
+}
+
+impl Payments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_stripe(mut self, config: StripeConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Point at another server, e.g. a local fake in tests.
+    pub fn with_api_base(mut self, api_base: &str) -> Self {
+        self.api_base = Some(api_base.trim_end_matches('/').to_string());
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    fn config(&self) -> Result<&StripeConfig> {
+        match &self.config {
+            Some(c) => Ok(c),
+            None => bail!("Online payment is not configured"),
+        }
+    }
+
+    /// Create a Checkout Session charging `charge.amount` for one invoice.
+    /// The invoice id rides along as `client_reference_id` so the webhook
+    /// can find it again.
+    pub async fn checkout_link(&self, charge: &InvoiceCharge) -> Result<CheckoutLink> {
+        let cfg = self.config()?;
+        if charge.amount <= 0 {
+            bail!("Nothing to pay on invoice {}", charge.invoice_id);
+        }
+        let base = self.api_base.as_deref().unwrap_or(DEFAULT_API_BASE);
+        let invoice_id = charge.invoice_id.to_string();
+        let amount = charge.amount.to_string();
+        let mut form = vec![
+            ("mode", "payment"),
+            ("client_reference_id", invoice_id.as_str()),
+            ("metadata[invoice_id]", invoice_id.as_str()),
+            ("success_url", cfg.success_url.as_str()),
+            ("cancel_url", cfg.cancel_url.as_str()),
+            ("line_items[0][quantity]", "1"),
+            ("line_items[0][price_data][currency]", cfg.currency.as_str()),
+            ("line_items[0][price_data][unit_amount]", amount.as_str()),
+            (
+                "line_items[0][price_data][product_data][name]",
+                charge.description.as_str(),
+            ),
+        ];
+        if let Some(email) = &charge.email {
+            form.push(("customer_email", email.as_str()));
+        }
+        let link: CheckoutLink = self
+            .client
+            .post(format!("{base}/checkout/sessions"))
+            .timeout(REQUEST_TIMEOUT)
+            .bearer_auth(&cfg.secret_key)
+            .form(&form)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        info!(invoice_id = charge.invoice_id, session = %link.id, "Stripe checkout created");
+        Ok(link)
+    }
+
+    /// Check a webhook's `Stripe-Signature` header against its raw body.
+    /// `now` is Unix seconds. Read the event with
+    /// [`parse_completed_checkout`] once it passes.
+    pub fn verify_webhook(&self, signature_header: &str, body: &[u8], now: i64) -> Result<()> {
+        let cfg = self.config()?;
+        verify_signature(&cfg.webhook_secret, signature_header, body, now)
+    }
+}
+
+/// Verify a `Stripe-Signature` header (`t=<unix>,v1=<hex>,...`): any `v1`
+/// must be hex(HMAC-SHA256(secret, "<t>.<body>")), and `t` no older than
+/// [`WEBHOOK_TOLERANCE_SECS`].
+pub fn verify_signature(secret: &str, header: &str, body: &[u8], now: i64) -> Result<()> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", t)) => timestamp = t.parse::<i64>().ok(),
+            Some(("v1", sig)) => signatures.push(sig),
+            _ => {}
+        }
+    }
+    let Some(timestamp) = timestamp else {
+        bail!("Signature header has no timestamp");
+    };
+    if (now - timestamp).abs() > WEBHOOK_TOLERANCE_SECS {
+        bail!("Signature timestamp is outside the tolerance");
+    }
+    for sig in signatures {
+        let Ok(expected) = hex::decode(sig) else {
+            continue;
+        };
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())?;
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        if mac.verify_slice(&expected).is_ok() {
+            return Ok(());
+        }
+    }
+    bail!("No valid signature")
+}
+
+/// The paid checkout in a webhook body, or `None` for any other event (or
+/// a session still awaiting payment).
+pub fn parse_completed_checkout(body: &[u8]) -> Result<Option<CompletedCheckout>> {
+    let event: Event = serde_json::from_slice(body).context("Malformed Stripe event")?;
+    if event.kind != CHECKOUT_COMPLETED {
+        return Ok(None);
+    }
+    let session = event.data.object;
+    if session.payment_status.as_deref() != Some("paid") {
+        return Ok(None);
+    }
+    let invoice_id = session
+        .client_reference_id
+        .as_deref()
+        .and_then(|id| id.parse().ok())
+        .context("Checkout session has no invoice reference")?;
+    Ok(Some(CompletedCheckout {
+        session_id: session.id,
+        invoice_id,
+        amount: session.amount_total.unwrap_or(0),
+    }))
+}
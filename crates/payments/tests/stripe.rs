@@ -0,0 +1,57 @@
+use gtm_payments::{CompletedCheckout, parse_completed_checkout, verify_signature};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+const SECRET: &str = "whsec_test";
+const NOW: i64 = 1_780_000_000;
+
+fn sign(body: &str, timestamp: i64) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!(
+        "t={timestamp},v1={}",
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+fn event(kind: &str, payment_status: &str) -> String {
+    format!(
+        r#"{{"id": "evt_1", "type": "{kind}", "data": {{"object": {{
+            "id": "cs_test_1", "client_reference_id": "42",
+            "amount_total": 4500, "payment_status": "{payment_status}"
+        }}}}}}"#
+    )
+}
+
+#[test]
+fn signatures_must_match_body_secret_and_time() {
+    let body = event("checkout.session.completed", "paid");
+    let header = sign(&body, NOW);
+    assert!(verify_signature(SECRET, &header, body.as_bytes(), NOW).is_ok());
+    // Extra schemes and a rotated-out v1 alongside the good one are fine
+    let rotated = format!("v0=abc,{header},v1=00ff");
+    assert!(verify_signature(SECRET, &rotated, body.as_bytes(), NOW + 10).is_ok());
+
+    assert!(verify_signature("whsec_other", &header, body.as_bytes(), NOW).is_err());
+    assert!(verify_signature(SECRET, &header, b"{}", NOW).is_err());
+    assert!(verify_signature(SECRET, &header, body.as_bytes(), NOW + 301).is_err());
+    assert!(verify_signature(SECRET, "v1=abc", body.as_bytes(), NOW).is_err());
+}
+
+#[test]
+fn only_paid_completed_checkouts_are_reported() {
+    let paid = event("checkout.session.completed", "paid");
+    assert_eq!(
+        parse_completed_checkout(paid.as_bytes()).unwrap(),
+        Some(CompletedCheckout {
+            session_id: "cs_test_1".to_string(),
+            invoice_id: 42,
+            amount: 4500,
+        })
+    );
+    let unpaid = event("checkout.session.completed", "unpaid");
+    assert_eq!(parse_completed_checkout(unpaid.as_bytes()).unwrap(), None);
+    let other = event("checkout.session.expired", "unpaid");
+    assert_eq!(parse_completed_checkout(other.as_bytes()).unwrap(), None);
+    assert!(parse_completed_checkout(b"not json").is_err());
+}
@@ -0,0 +1,221 @@
+//! Circuit breakers for upstream services.
+//!
+//! A breaker counts consecutive failed calls to one upstream (the MLB Stats
+//! API, Auth0's signing keys). After [`BreakerPolicy::failures`] in a row it
+//! opens, and calls fail at once with [`BreakerOpen`] instead of waiting on
+//! timeouts and retries, so callers can fall back to what they cached. Once
+//! the cooldown has passed one probe call is let through (half-open): if it
+//! succeeds the breaker closes, if it fails the breaker opens again for
+//! twice as long, up to [`BreakerPolicy::max_cooldown`].
+//!
+//! Breakers are shared by name through [`shared`], and [`statuses`] reports
+//! every one for the health check.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// When a breaker opens and for how long.
+#[derive(Debug, Clone)]
+pub struct BreakerPolicy {
+    /// Consecutive failures that open the breaker.
+    pub failures: u32,
+    /// How long it stays open the first time.
+    pub cooldown: Duration,
+    /// Longest it stays open after repeated failed probes.
+    pub max_cooldown: Duration,
+}
+
+impl Default for BreakerPolicy {
+    fn default() -> Self {
+        BreakerPolicy {
+            failures: 5,
+            cooldown: Duration::from_secs(30),
+            max_cooldown: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// A call refused because the upstream's breaker is open.
+#[derive(Debug)]
+pub struct BreakerOpen {
+    pub name: String,
+    pub retry_in: Duration,
+}
+
+impl std::fmt::Display for BreakerOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is unavailable; not retrying for {}s",
+            self.name,
+            self.retry_in.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for BreakerOpen {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// A breaker's state and counters since startup.
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerStatus {
+    pub name: String,
+    pub state: State,
+    pub consecutive_failures: u32,
+    /// Seconds until a probe is let through, while open.
+    pub retry_in_secs: Option<u64>,
+    /// Times the breaker has opened.
+    pub trips: u64,
+    /// Calls refused while open.
+    pub rejected: u64,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: State,
+    consecutive_failures: u32,
+    /// When the breaker last opened, or the probe started.
+    since: Instant,
+    open_for: Duration,
+    trips: u64,
+    rejected: u64,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: String,
+    policy: BreakerPolicy,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &str, policy: BreakerPolicy) -> Self {
+        CircuitBreaker {
+            name: name.to_string(),
+            inner: Mutex::new(Inner {
+                state: State::Closed,
+                consecutive_failures: 0,
+                since: Instant::now(),
+                open_for: policy.cooldown,
+                trips: 0,
+                rejected: 0,
+            }),
+            policy,
+        }
+    }
+
+    /// Whether a call may go ahead. Past the cooldown one probe is allowed;
+    /// a probe that never reports back is given up on after another
+    /// cooldown.
+    pub fn check(&self) -> Result<(), BreakerOpen> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == State::Closed {
+            return Ok(());
+        }
+        let elapsed = inner.since.elapsed();
+        if elapsed >= inner.open_for {
+            info!(breaker = %self.name, "Circuit half-open; probing");
+            inner.state = State::HalfOpen;
+            inner.since = Instant::now();
+            return Ok(());
+        }
+        inner.rejected += 1;
+        Err(BreakerOpen {
+            name: self.name.clone(),
+            retry_in: inner.open_for - elapsed,
+        })
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state != State::Closed {
+            info!(breaker = %self.name, "Circuit closed");
+        }
+        inner.state = State::Closed;
+        inner.consecutive_failures = 0;
+        inner.open_for = self.policy.cooldown;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        let open_for = match inner.state {
+            State::HalfOpen => (inner.open_for * 2).min(self.policy.max_cooldown),
+            State::Closed if inner.consecutive_failures >= self.policy.failures.max(1) => {
+                self.policy.cooldown
+            }
+            _ => return,
+        };
+        warn!(
+            breaker = %self.name,
+            failures = inner.consecutive_failures,
+            "Circuit open for {}s",
+            open_for.as_secs()
+        );
+        inner.state = State::Open;
+        inner.since = Instant::now();
+        inner.open_for = open_for;
+        inner.trips += 1;
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.inner.lock().unwrap();
+        BreakerStatus {
+            name: self.name.clone(),
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            retry_in_secs: (inner.state == State::Open).then(|| {
+                inner
+                    .open_for
+                    .saturating_sub(inner.since.elapsed())
+                    .as_secs()
+            }),
+            trips: inner.trips,
+            rejected: inner.rejected,
+        }
+    }
+}
+
+static POLICY: OnceLock<BreakerPolicy> = OnceLock::new();
+static BREAKERS: Mutex<BTreeMap<String, Arc<CircuitBreaker>>> = Mutex::new(BTreeMap::new());
+
+/// Set the policy for breakers made by [`shared`]. Only the first call
+/// takes effect, and only for breakers made after it.
+pub fn configure(policy: BreakerPolicy) {
+    if POLICY.set(policy).is_err() {
+        warn!("Circuit breakers already configured; ignoring new policy");
+    }
+}
+
+/// The breaker called `name`, made on first use.
+pub fn shared(name: &str) -> Arc<CircuitBreaker> {
+    BREAKERS
+        .lock()
+        .unwrap()
+        .entry(name.to_string())
+        .or_insert_with(|| {
+            let policy = POLICY.get_or_init(BreakerPolicy::default).clone();
+            Arc::new(CircuitBreaker::new(name, policy))
+        })
+        .clone()
+}
+
+/// Every shared breaker's status, by name.
+pub fn statuses() -> Vec<BreakerStatus> {
+    BREAKERS
+        .lock()
+        .unwrap()
+        .values()
+        .map(|b| b.status())
+        .collect()
+}
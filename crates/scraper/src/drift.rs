@@ -0,0 +1,76 @@
+//! Schema drift in MLB Stats API responses.
+//!
+//! Response types keep fields they don't declare in a flattened map. After
+//! a fetch, anything in those maps that isn't on the list of fields we know
+//! about and deliberately ignore is counted here, so a sync can report
+//! "new field `seriesStatus` seen on 162 games" before a change in shape
+//! reaches something we rely on.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Fields the schedule endpoint sends on each game that we don't use.
+pub(crate) const IGNORED_GAME_FIELDS: &[&str] = &[
+    "link",
+    "content",
+    "publicFacing",
+    "gamedayType",
+    "tiebreaker",
+    "calendarEventID",
+    "seasonDisplay",
+    "description",
+    "reverseHomeAwayStatus",
+    "inningBreakLength",
+    "recordSource",
+    "ifNecessary",
+    "ifNecessaryDescription",
+    "rescheduledFrom",
+    "rescheduledFromDate",
+    "resumedFrom",
+    "resumedFromDate",
+    // Hydrated sections we ask for but don't store
+    "tickets",
+];
+
+/// Fields sent on each promotion that we don't use.
+pub(crate) const IGNORED_PROMOTION_FIELDS: &[&str] = &[
+    "teamId",
+    "offerType",
+    "imageUrl",
+    "altPageUrl",
+    "tlinkDisplay",
+];
+
+/// Unknown fields seen in one fetch, by kind of object.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DriftReport {
+    /// `(objects, field)` → how many objects carried it, e.g.
+    /// `("games", "seriesStatus") → 162`.
+    pub fields: BTreeMap<(String, String), usize>,
+}
+
+impl DriftReport {
+    /// Count the fields in `extra` that aren't in `ignored`, for one object
+    /// of kind `objects` (plural, e.g. "games").
+    pub fn record(&mut self, objects: &str, extra: &HashMap<String, Value>, ignored: &[&str]) {
+        for field in extra.keys().filter(|f| !ignored.contains(&f.as_str())) {
+            *self
+                .fields
+                .entry((objects.to_string(), field.clone()))
+                .or_default() += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// One line per new field, for logs and the sync response.
+    pub fn lines(&self) -> Vec<String> {
+        self.fields
+            .iter()
+            .map(|((objects, field), n)| format!("new field `{field}` seen on {n} {objects}"))
+            .collect()
+    }
+}
@@ -0,0 +1,181 @@
+//! Shared HTTP client for upstream APIs.
+//!
+//! Every request goes through one `reqwest::Client` with connect and overall
+//! timeouts, and is retried with capped exponential backoff plus jitter on
+//! timeouts, connection errors, 429 and 5xx. Call [`configure`] once at
+//! startup to apply the policy from config; until then the defaults apply.
+
+use anyhow::{Result, bail};
+use rand::Rng;
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::warn;
+
+/// Timeouts and retry schedule for upstream requests.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub connect_timeout: Duration,
+    /// Whole request, including reading the body.
+    pub timeout: Duration,
+    /// Total attempts, including the first.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles on each one after.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based): the capped exponential
+    /// step scaled by `jitter` in `[0, 1]`, never less than half the step.
+    pub fn backoff(&self, retry: u32, jitter: f64) -> Duration {
+        let step = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+        step.mul_f64(0.5 + 0.5 * jitter.clamp(0.0, 1.0))
+    }
+}
+
+static HTTP: OnceLock<(Client, RetryPolicy)> = OnceLock::new();
+
+/// Set the policy for all upstream requests. Only the first call (or the
+/// first request, which installs the defaults) takes effect.
+pub fn configure(policy: RetryPolicy) -> Result<()> {
+    let client = build_client(&policy)?;
+    if HTTP.set((client, policy)).is_err() {
+        warn!("HTTP client already configured; ignoring new retry policy");
+    }
+    Ok(())
+}
+
+fn build_client(policy: &RetryPolicy) -> Result<Client> {
+    Ok(Client::builder()
+        .connect_timeout(policy.connect_timeout)
+        .timeout(policy.timeout)
+        .user_agent(concat!("gtm/", env!("CARGO_PKG_VERSION")))
+        .build()?)
+}
+
+pub(crate) fn shared() -> &'static (Client, RetryPolicy) {
+    HTTP.get_or_init(|| {
+        let policy = RetryPolicy::default();
+        let client = build_client(&policy).unwrap_or_default();
+        (client, policy)
+    })
+}
+
+/// GET `url` with the shared client and policy and decode the JSON body.
+pub async fn get_json<T: DeserializeOwned>(url: &str) -> Result<T> {
+    let (client, policy) = shared();
+    get_json_with(client, policy, url).await
+}
+
+/// [`get_json`] with an explicit client and policy.
+pub async fn get_json_with<T: DeserializeOwned>(
+    client: &Client,
+    policy: &RetryPolicy,
+    url: &str,
+) -> Result<T> {
+    get_with(client, policy, url, |resp| resp.json::<T>()).await
+}
+
+/// GET `url` and return the body, with its `Content-Type` if there was one,
+/// reading at most `max` bytes: `None` when the body is bigger, which a
+/// `Content-Length` tells before any of it is read.
+pub async fn get_bytes_capped(
+    client: &Client,
+    policy: &RetryPolicy,
+    url: &str,
+    max: usize,
+) -> Result<Option<(Vec<u8>, Option<String>)>> {
+    get_with(client, policy, url, |mut resp| async move {
+        if resp.content_length().is_some_and(|n| n > max as u64) {
+            return Ok(None);
+        }
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let mut body = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            if body.len() + chunk.len() > max {
+                return Ok(None);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(Some((body, content_type)))
+    })
+    .await
+}
+
+/// GET `url`, retrying as the policy says, and read a successful response
+/// with `read`.
+async fn get_with<T, F, Fut>(client: &Client, policy: &RetryPolicy, url: &str, read: F) -> Result<T>
+where
+    F: Fn(reqwest::Response) -> Fut,
+    Fut: std::future::Future<Output = reqwest::Result<T>>,
+{
+    let attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let error = match client.get(url).send().await {
+            Ok(resp) if resp.status().is_success() => match read(resp).await {
+                Ok(body) => return Ok(body),
+                // A body cut off by the timeout is worth another try; a
+                // malformed one isn't.
+                Err(e) if e.is_timeout() => e.into(),
+                Err(e) => return Err(e.into()),
+            },
+            Ok(resp) if retryable_status(resp.status()) => {
+                anyhow::anyhow!("{url} returned {}", resp.status())
+            }
+            Ok(resp) => bail!("{url} returned {}", resp.status()),
+            Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => e.into(),
+            Err(e) => return Err(e.into()),
+        };
+        if attempt >= attempts {
+            return Err(error.context(GaveUp { attempts }));
+        }
+        let delay = policy.backoff(attempt - 1, rand::thread_rng().r#gen());
+        warn!("Attempt {attempt}/{attempts} failed: {error:#}; retrying in {delay:?}");
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Every attempt at a request failed in a way worth retrying: the
+/// upstream is down or overloaded, rather than refusing the request.
+#[derive(Debug)]
+pub struct GaveUp {
+    pub attempts: u32,
+}
+
+impl std::fmt::Display for GaveUp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "giving up after {} attempts", self.attempts)
+    }
+}
+
+/// Whether `error` from [`get_json`] means the upstream is unavailable.
+pub fn is_unavailable(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<GaveUp>().is_some()
+}
+
+fn retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
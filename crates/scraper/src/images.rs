@@ -0,0 +1,189 @@
+//! Disk cache for promotion images.
+//!
+//! mlbstatic.com turns away some browser requests for promotion images, so
+//! the SPA loads them through the server instead. [`ImageCache::get`]
+//! fetches an image once and keeps it in the cache directory, reusing it
+//! until it is older than the TTL. When a refetch fails the stale copy is
+//! served rather than nothing.
+//!
+//! Each image is two files: the body, and `<key>.meta` holding its content
+//! type and the URL it came from, so a promotion whose image URL changes
+//! is fetched afresh.
+//!
+//! Images are served from our own origin, so only the raster types in
+//! [`IMAGE_TYPES`] are kept: an SVG could carry script.
+
+use anyhow::{Result, bail};
+use reqwest::Client;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use tracing::warn;
+
+use crate::http::{self, RetryPolicy};
+
+/// Largest image fetched; anything bigger isn't a thumbnail.
+pub const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Content types fetched and served.
+pub const IMAGE_TYPES: [&str; 5] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/avif",
+];
+
+/// `content_type` without parameters, lowercased, if it's one of
+/// [`IMAGE_TYPES`].
+fn image_type(content_type: &str) -> Option<&'static str> {
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    IMAGE_TYPES
+        .into_iter()
+        .find(|t| t.eq_ignore_ascii_case(essence))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedImage {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+pub struct ImageCache {
+    dir: PathBuf,
+    ttl: Duration,
+    client: Client,
+    policy: RetryPolicy,
+}
+
+impl ImageCache {
+    /// A cache in `dir` (made on first write) using the shared HTTP client
+    /// and policy.
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        let (client, policy) = http::shared();
+        ImageCache {
+            dir: dir.into(),
+            ttl,
+            client: client.clone(),
+            policy: policy.clone(),
+        }
+    }
+
+    /// Use `client` and `policy` instead of the shared ones.
+    pub fn with_client(mut self, client: Client, policy: RetryPolicy) -> Self {
+        self.client = client;
+        self.policy = policy;
+        self
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The image at `url`, cached under `key`, which must be a plain file
+    /// name (letters, digits, `-` and `_`).
+    pub async fn get(&self, key: &str, url: &str) -> Result<CachedImage> {
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!("Bad image cache key `{key}`");
+        }
+        let cached = self.read(key, url).await;
+        if let Some((image, age)) = &cached
+            && *age < self.ttl
+        {
+            return Ok(image.clone());
+        }
+        match self.fetch(url).await {
+            Ok(image) => {
+                if let Err(e) = self.write(key, url, &image).await {
+                    warn!(key, "Could not cache image: {e}");
+                }
+                Ok(image)
+            }
+            Err(e) => match cached {
+                Some((image, _)) => {
+                    warn!(key, "Serving stale image: {e:#}");
+                    Ok(image)
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    async fn fetch(&self, url: &str) -> Result<CachedImage> {
+        let Some((bytes, content_type)) =
+            http::get_bytes_capped(&self.client, &self.policy, url, MAX_IMAGE_BYTES).await?
+        else {
+            bail!("{url} is larger than {MAX_IMAGE_BYTES} bytes");
+        };
+        let content_type = content_type.unwrap_or_default();
+        let Some(content_type) = image_type(&content_type) else {
+            bail!("{url} is not an image we serve ({content_type:?})");
+        };
+        Ok(CachedImage {
+            bytes,
+            content_type: content_type.to_string(),
+        })
+    }
+
+    /// The cached copy of `url` and its age, if there is one.
+    async fn read(&self, key: &str, url: &str) -> Option<(CachedImage, Duration)> {
+        let meta = tokio::fs::read_to_string(self.meta_path(key)).await.ok()?;
+        let (content_type, cached_url) = meta.split_once('\n')?;
+        if cached_url != url {
+            return None;
+        }
+        let content_type = image_type(content_type)?;
+        let path = self.dir.join(key);
+        let modified = tokio::fs::metadata(&path).await.ok()?.modified().ok()?;
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+        let bytes = tokio::fs::read(&path).await.ok()?;
+        Some((
+            CachedImage {
+                bytes,
+                content_type: content_type.to_string(),
+            },
+            age,
+        ))
+    }
+
+    /// Write through temporary files so a reader never sees half an image.
+    async fn write(&self, key: &str, url: &str, image: &CachedImage) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let path = self.dir.join(key);
+        write_atomic(&path, &image.bytes).await?;
+        let meta = format!("{}\n{url}", image.content_type);
+        write_atomic(&self.meta_path(key), meta.as_bytes()).await
+    }
+
+    fn meta_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.meta"))
+    }
+}
+
+/// Temporary files written so far, to keep concurrent writes of one image
+/// apart.
+static WRITES: AtomicU64 = AtomicU64::new(0);
+
+async fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    if let Err(e) = tokio::fs::write(&tmp, bytes).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e.into());
+    }
+    if let Err(e) = tokio::fs::rename(&tmp, path).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
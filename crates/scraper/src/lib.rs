@@ -1,15 +1,27 @@
 use anyhow::Result;
-use gtm_models::{Game, Promotion};
+use gtm_models::{Game, GamePk, GameType, Hydration, LiveGame, Promotion};
+
+pub use gtm_models::ScheduleData;
 use serde::Deserialize;
+use std::collections::HashMap;
 use tracing::info;
 
-const GIANTS_TEAM_ID: u32 = 137;
-const MLB_SCHEDULE_URL: &str = "https://statsapi.mlb.com/api/v1/schedule";
+pub mod breaker;
+pub mod drift;
+pub mod http;
+pub mod images;
+pub mod live;
+pub mod preview;
+pub mod statsapi;
+pub mod weather;
+
+pub use drift::DriftReport;
+pub use statsapi::StatsApiClient;
 
 // --- MLB Stats API response types ---
 
 #[derive(Deserialize)]
-struct ScheduleResponse {
+pub(crate) struct ScheduleResponse {
     dates: Vec<DateEntry>,
 }
 
@@ -21,7 +33,7 @@ struct DateEntry {
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ApiGame {
-    game_pk: i64,
+    game_pk: GamePk,
     game_guid: Option<String>,
     game_type: String,
     season: String,
@@ -38,8 +50,25 @@ struct ApiGame {
     games_in_series: Option<i64>,
     series_game_number: Option<i64>,
     series_description: Option<String>,
+    /// Only sent when the promotions hydration was asked for.
     #[serde(default)]
     promotions: Vec<ApiPromotion>,
+    /// Only sent with the weather hydration, and then only close to game day.
+    weather: Option<ApiWeather>,
+    /// Only sent with the broadcasts hydration.
+    #[serde(default)]
+    broadcasts: Vec<ApiBroadcast>,
+    /// Set on a postponed game once its makeup date is known.
+    reschedule_date: Option<String>,
+    /// The makeup's `officialDate`, sent with `rescheduleDate`.
+    reschedule_game_date: Option<String>,
+    /// Set on a suspended game once it's known when play resumes.
+    resume_date: Option<String>,
+    /// The resumption's `officialDate`, sent with `resumeDate`.
+    resume_game_date: Option<String>,
+    /// Fields not declared above, for drift detection.
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Deserialize)]
@@ -57,6 +86,23 @@ struct ApiPromotion {
     image_url: Option<String>,
     #[serde(default)]
     order: i64,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct ApiWeather {
+    condition: Option<String>,
+    temp: Option<String>,
+    wind: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiBroadcast {
+    name: String,
+    /// "TV" or "AM"/"FM" for radio.
+    #[serde(rename = "type")]
+    kind: String,
 }
 
 #[derive(Deserialize)]
@@ -80,6 +126,14 @@ struct TeamSide {
     team: TeamInfo,
     score: Option<i64>,
     is_winner: Option<bool>,
+    /// Only sent with the probablePitcher hydration, once one is announced.
+    probable_pitcher: Option<Person>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Person {
+    full_name: String,
 }
 
 #[derive(Deserialize)]
@@ -98,6 +152,17 @@ struct Venue {
 
 impl From<ApiGame> for Game {
     fn from(g: ApiGame) -> Self {
+        let mut tv: Vec<String> = Vec::new();
+        for b in g.broadcasts.iter().filter(|b| b.kind == "TV") {
+            if !tv.contains(&b.name) {
+                tv.push(b.name.clone());
+            }
+        }
+        let weather = g.weather.unwrap_or(ApiWeather {
+            condition: None,
+            temp: None,
+            wind: None,
+        });
         Game {
             game_pk: g.game_pk,
             game_guid: g.game_guid,
@@ -131,14 +196,38 @@ impl From<ApiGame> for Game {
             game_number: g.game_number.unwrap_or(1),
             scheduled_innings: g.scheduled_innings.unwrap_or(9),
             is_tie: if g.is_tie.unwrap_or(false) { 1 } else { 0 },
+            current_inning: None,
+            inning_state: None,
+            away_probable_pitcher: g.teams.away.probable_pitcher.map(|p| p.full_name),
+            home_probable_pitcher: g.teams.home.probable_pitcher.map(|p| p.full_name),
+            weather_condition: weather.condition.filter(|s| !s.is_empty()),
+            weather_temp: weather.temp.filter(|s| !s.is_empty()),
+            weather_wind: weather.wind.filter(|s| !s.is_empty()),
+            tv_broadcasts: (!tv.is_empty()).then(|| tv.join(", ")),
+            reschedule_date: g.reschedule_date.or(g.resume_date),
+            reschedule_official_date: g.reschedule_game_date.or(g.resume_game_date),
+            rescheduled_to: None,
+            max_seats: None,
+            game_type_label: None,
+            first_pitch_local: None,
+            time_zone: None,
         }
     }
 }
 
-fn convert_promotions(game_pk: i64, api_promos: Vec<ApiPromotion>) -> Vec<Promotion> {
+/// The API sometimes lists an offer twice, or gives the same offers
+/// different `order` values from one hydration to the next. Keep each
+/// offer once (its lowest `order`), sort by `order` then offer id, and
+/// number them from 0, so an unchanged lineup converts identically every
+/// time.
+fn convert_promotions(game_pk: GamePk, mut api_promos: Vec<ApiPromotion>) -> Vec<Promotion> {
+    api_promos.sort_by_key(|p| (p.order, p.offer_id));
+    let mut seen = std::collections::HashSet::new();
+    api_promos.retain(|p| seen.insert(p.offer_id));
     api_promos
         .into_iter()
-        .map(|p| Promotion {
+        .zip(0..)
+        .map(|(p, display_order)| Promotion {
             offer_id: p.offer_id,
             game_pk,
             name: p.name,
@@ -150,43 +239,82 @@ fn convert_promotions(game_pk: i64, api_promos: Vec<ApiPromotion>) -> Vec<Promot
             ticket_link: p.tlink,
             thumbnail_url: p.thumbnail_url,
             image_url: p.image_url,
-            display_order: p.order,
+            display_order,
         })
         .collect()
 }
 
-// --- Public API ---
+impl ScheduleResponse {
+    /// Unknown fields across the response's games and promotions.
+    pub(crate) fn drift(&self) -> DriftReport {
+        let mut report = DriftReport::default();
+        for game in self.dates.iter().flat_map(|d| &d.games) {
+            report.record("games", &game.extra, drift::IGNORED_GAME_FIELDS);
+            for promo in &game.promotions {
+                report.record("promotions", &promo.extra, drift::IGNORED_PROMOTION_FIELDS);
+            }
+        }
+        report
+    }
+}
 
-pub struct ScheduleData {
-    pub games: Vec<Game>,
-    pub promotions: Vec<Promotion>,
+impl From<ScheduleResponse> for ScheduleData {
+    fn from(resp: ScheduleResponse) -> Self {
+        let mut games = Vec::new();
+        let mut promotions = Vec::new();
+        for date_entry in resp.dates {
+            for mut api_game in date_entry.games {
+                let game_pk = api_game.game_pk;
+                let promos = std::mem::take(&mut api_game.promotions);
+                promotions.extend(convert_promotions(game_pk, promos));
+                games.push(Game::from(api_game));
+            }
+        }
+        ScheduleData { games, promotions }
+    }
 }
 
-pub async fn fetch_schedule(season: u32) -> Result<ScheduleData> {
-    info!("Fetching {season} Giants schedule from MLB Stats API\u{2026}");
+// --- Public API ---
 
-    let url = format!(
-        "{MLB_SCHEDULE_URL}?teamId={GIANTS_TEAM_ID}&season={season}&sportId=1&gameType=R&hydrate=game(promotions)"
+/// Fetch team `team_id`'s schedule for `season`, limited to `game_types`
+/// (regular season only when empty), with the `hydrate` sections.
+pub async fn fetch_schedule(
+    team_id: u32,
+    season: u32,
+    game_types: &[GameType],
+    hydrate: &[Hydration],
+) -> Result<ScheduleData> {
+    let data = StatsApiClient::shared()
+        .schedule(team_id, season, game_types, hydrate)
+        .await?;
+    info!(
+        "Fetched {} games, {} promotions for {season} season",
+        data.games.len(),
+        data.promotions.len()
     );
+    Ok(data)
+}
 
-    let resp: ScheduleResponse = reqwest::get(&url).await?.json().await?;
-
-    let mut games = Vec::new();
-    let mut promotions = Vec::new();
-
-    for date_entry in resp.dates {
-        for mut api_game in date_entry.games {
-            let game_pk = api_game.game_pk;
-            let promos = std::mem::take(&mut api_game.promotions);
-            promotions.extend(convert_promotions(game_pk, promos));
-            games.push(Game::from(api_game));
-        }
-    }
-
+/// [`fetch_schedule`], also reporting fields the API sent that we don't know.
+pub async fn fetch_schedule_with_drift(
+    team_id: u32,
+    season: u32,
+    game_types: &[GameType],
+    hydrate: &[Hydration],
+) -> Result<(ScheduleData, DriftReport)> {
+    let (data, drift) = StatsApiClient::shared()
+        .schedule_with_drift(team_id, season, game_types, hydrate)
+        .await?;
     info!(
         "Fetched {} games, {} promotions for {season} season",
-        games.len(),
-        promotions.len()
+        data.games.len(),
+        data.promotions.len()
     );
-    Ok(ScheduleData { games, promotions })
+    Ok((data, drift))
+}
+
+/// Team `team_id`'s games on `date` (YYYY-MM-DD) as they stand right now:
+/// status, score and current inning.
+pub async fn fetch_live_games(team_id: u32, date: &str) -> Result<Vec<LiveGame>> {
+    StatsApiClient::shared().live_games(team_id, date).await
 }
@@ -0,0 +1,79 @@
+//! Live status and score for the day's games, from the schedule endpoint
+//! hydrated with each game's linescore.
+
+use anyhow::Result;
+use gtm_models::{GamePk, LiveGame};
+use serde::Deserialize;
+
+use crate::{GameStatus, StatsApiClient, Teams};
+
+#[derive(Deserialize)]
+struct LiveResponse {
+    dates: Vec<LiveDate>,
+}
+
+#[derive(Deserialize)]
+struct LiveDate {
+    games: Vec<LiveApiGame>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LiveApiGame {
+    game_pk: GamePk,
+    status: GameStatus,
+    teams: Teams,
+    linescore: Option<Linescore>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Linescore {
+    current_inning: Option<i64>,
+    inning_state: Option<String>,
+}
+
+impl From<LiveApiGame> for LiveGame {
+    fn from(g: LiveApiGame) -> Self {
+        let linescore = g.linescore.unwrap_or(Linescore {
+            current_inning: None,
+            inning_state: None,
+        });
+        LiveGame {
+            game_pk: g.game_pk,
+            status_abstract: g.status.abstract_game_state,
+            status_detailed: g.status.detailed_state,
+            status_code: g.status.status_code,
+            away_score: g.teams.away.score,
+            away_is_winner: g.teams.away.is_winner.map(i64::from),
+            home_score: g.teams.home.score,
+            home_is_winner: g.teams.home.is_winner.map(i64::from),
+            current_inning: linescore.current_inning,
+            inning_state: linescore.inning_state,
+        }
+    }
+}
+
+impl StatsApiClient {
+    /// A team's games on `date` (YYYY-MM-DD) with their current status,
+    /// score and inning.
+    pub async fn live_games(&self, team_id: u32, date: &str) -> Result<Vec<LiveGame>> {
+        let resp: LiveResponse = self
+            .get(
+                "schedule",
+                &[
+                    ("teamId", team_id.to_string()),
+                    ("sportId", "1".to_string()),
+                    ("date", date.to_string()),
+                    ("hydrate", "linescore".to_string()),
+                ],
+            )
+            .await?;
+        Ok(resp
+            .dates
+            .into_iter()
+            .flat_map(|d| d.games)
+            .map(LiveGame::from)
+            .collect())
+    }
+}
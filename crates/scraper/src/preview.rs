@@ -0,0 +1,143 @@
+//! Stats API lookups for game previews: probable pitchers and standings.
+
+use anyhow::Result;
+use gtm_models::GamePk;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::StatsApiClient;
+
+/// Announced starters for one game; either side may not be named yet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProbablePitchers {
+    pub away: Option<String>,
+    pub home: Option<String>,
+}
+
+/// A team's season record and current streak.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamStanding {
+    pub wins: i64,
+    pub losses: i64,
+    /// MLB streak code, e.g. "W3" or "L1".
+    pub streak: Option<String>,
+}
+
+impl TeamStanding {
+    /// "12-8".
+    pub fn record(&self) -> String {
+        format!("{}-{}", self.wins, self.losses)
+    }
+}
+
+#[derive(Deserialize)]
+struct PitchersResponse {
+    dates: Vec<PitchersDate>,
+}
+
+#[derive(Deserialize)]
+struct PitchersDate {
+    games: Vec<PitchersGame>,
+}
+
+#[derive(Deserialize)]
+struct PitchersGame {
+    teams: PitchersTeams,
+}
+
+#[derive(Deserialize)]
+struct PitchersTeams {
+    away: PitchersSide,
+    home: PitchersSide,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PitchersSide {
+    probable_pitcher: Option<Person>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Person {
+    full_name: String,
+}
+
+#[derive(Deserialize)]
+struct StandingsResponse {
+    records: Vec<DivisionRecord>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DivisionRecord {
+    team_records: Vec<TeamRecord>,
+}
+
+#[derive(Deserialize)]
+struct TeamRecord {
+    team: TeamRef,
+    wins: i64,
+    losses: i64,
+    streak: Option<Streak>,
+}
+
+#[derive(Deserialize)]
+struct TeamRef {
+    id: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Streak {
+    streak_code: String,
+}
+
+impl StatsApiClient {
+    /// Probable starters for `game_pk`, as announced so far.
+    pub async fn probable_pitchers(&self, game_pk: GamePk) -> Result<ProbablePitchers> {
+        let resp: PitchersResponse = self
+            .get(
+                "schedule",
+                &[
+                    ("gamePk", game_pk.to_string()),
+                    ("hydrate", "probablePitcher".to_string()),
+                ],
+            )
+            .await?;
+        let Some(game) = resp.dates.into_iter().flat_map(|d| d.games).next() else {
+            return Ok(ProbablePitchers::default());
+        };
+        Ok(ProbablePitchers {
+            away: game.teams.away.probable_pitcher.map(|p| p.full_name),
+            home: game.teams.home.probable_pitcher.map(|p| p.full_name),
+        })
+    }
+
+    /// Regular-season standings for both leagues, by team id.
+    pub async fn standings(&self, season: u32) -> Result<HashMap<i64, TeamStanding>> {
+        let resp: StandingsResponse = self
+            .get(
+                "standings",
+                &[
+                    ("leagueId", "103,104".to_string()),
+                    ("season", season.to_string()),
+                    ("standingsTypes", "regularSeason".to_string()),
+                ],
+            )
+            .await?;
+        Ok(resp
+            .records
+            .into_iter()
+            .flat_map(|r| r.team_records)
+            .map(|t| {
+                let standing = TeamStanding {
+                    wins: t.wins,
+                    losses: t.losses,
+                    streak: t.streak.map(|s| s.streak_code),
+                };
+                (t.team.id, standing)
+            })
+            .collect())
+    }
+}
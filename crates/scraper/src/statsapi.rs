@@ -0,0 +1,234 @@
+//! Client for the MLB Stats API (`statsapi.mlb.com`).
+//!
+//! [`StatsApiClient`] owns the base URL, HTTP client and retry policy, and
+//! optionally caches decoded responses for a while, so each endpoint is one
+//! typed method rather than its own URL building and request handling.
+
+use anyhow::Result;
+use gtm_models::{GameType, Hydration, ScheduleData};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::DriftReport;
+use crate::breaker::{self, CircuitBreaker};
+use crate::http::{self, RetryPolicy};
+
+pub const DEFAULT_BASE_URL: &str = "https://statsapi.mlb.com/api/v1";
+
+/// How long [`StatsApiClient::shared`] reuses a response: less than the
+/// live score poll interval, so each poll sees new scores.
+const SHARED_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// How long a response is kept to fall back on while the API is down.
+const STALE_FOR: Duration = Duration::from_secs(24 * 60 * 60);
+
+static SHARED: OnceLock<StatsApiClient> = OnceLock::new();
+
+/// Name of the Stats API's shared circuit breaker.
+pub const BREAKER: &str = "mlb_stats_api";
+
+pub struct StatsApiClient {
+    base_url: String,
+    client: Client,
+    policy: RetryPolicy,
+    cache: Option<ResponseCache>,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl Default for StatsApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsApiClient {
+    /// A client for the public API using the shared HTTP client and policy
+    /// (see [`http::configure`]) and the shared [`BREAKER`], without
+    /// caching.
+    pub fn new() -> Self {
+        let (client, policy) = http::shared();
+        StatsApiClient {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            client: client.clone(),
+            policy: policy.clone(),
+            cache: None,
+            breaker: breaker::shared(BREAKER),
+        }
+    }
+
+    /// The process-wide client: [`new`](Self::new) with a short cache, so
+    /// stale responses are there to fall back on.
+    pub fn shared() -> &'static StatsApiClient {
+        SHARED.get_or_init(|| StatsApiClient::new().with_cache(SHARED_CACHE_TTL))
+    }
+
+    /// Point at another server, e.g. a local fake in tests.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_client(mut self, client: Client, policy: RetryPolicy) -> Self {
+        self.client = client;
+        self.policy = policy;
+        self
+    }
+
+    /// Reuse responses for the same URL for `ttl`. Older responses are
+    /// kept too, and served while the API is unavailable.
+    pub fn with_cache(mut self, ttl: Duration) -> Self {
+        self.cache = Some(ResponseCache::new(ttl));
+        self
+    }
+
+    /// Use `breaker` instead of the shared one, e.g. a fresh one in tests.
+    pub fn with_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// GET `path` (relative to the base URL) with `query` and decode the
+    /// JSON body, from the cache when it's fresh. While the API is
+    /// unavailable, or its breaker is open, a stale cached response is
+    /// returned if there is one.
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let url = self.url(path, query);
+        if let Some(cached) = self.cache.as_ref().and_then(|c| c.get(&url)) {
+            debug!("Stats API cache hit: {url}");
+            return Ok(serde_json::from_value(cached)?);
+        }
+        let fetched = match self.breaker.check() {
+            Ok(()) => {
+                let fetched = http::get_json_with(&self.client, &self.policy, &url).await;
+                // A 404 or a body that doesn't decode says nothing about
+                // whether the API is up, so it leaves the breaker as it is
+                match &fetched {
+                    Ok(_) => self.breaker.record_success(),
+                    Err(e) if http::is_unavailable(e) => self.breaker.record_failure(),
+                    Err(_) => {}
+                }
+                fetched
+            }
+            Err(open) => Err(open.into()),
+        };
+        let body: serde_json::Value = match fetched {
+            Ok(body) => body,
+            Err(e) if http::is_unavailable(&e) || e.is::<breaker::BreakerOpen>() => {
+                let Some(stale) = self.cache.as_ref().and_then(|c| c.get_stale(&url)) else {
+                    return Err(e);
+                };
+                warn!("Stats API unavailable ({e:#}); using a stale response for {url}");
+                return Ok(serde_json::from_value(stale)?);
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(cache) = &self.cache {
+            cache.put(url, body.clone());
+        }
+        Ok(serde_json::from_value(body)?)
+    }
+
+    /// A team's schedule for `season`, limited to `game_types` (regular
+    /// season only when empty), with the `hydrate` sections on each game.
+    /// Promotions are only filled in when asked for.
+    pub async fn schedule(
+        &self,
+        team_id: u32,
+        season: u32,
+        game_types: &[GameType],
+        hydrate: &[Hydration],
+    ) -> Result<ScheduleData> {
+        let (data, _) = self
+            .schedule_with_drift(team_id, season, game_types, hydrate)
+            .await?;
+        Ok(data)
+    }
+
+    /// [`schedule`](Self::schedule) plus the fields the response carried
+    /// that we neither use nor know to ignore.
+    pub async fn schedule_with_drift(
+        &self,
+        team_id: u32,
+        season: u32,
+        game_types: &[GameType],
+        hydrate: &[Hydration],
+    ) -> Result<(ScheduleData, DriftReport)> {
+        let codes = if game_types.is_empty() {
+            GameType::Regular.code().to_string()
+        } else {
+            game_types
+                .iter()
+                .map(|t| t.code())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        info!("Fetching {season} schedule for team {team_id} ({codes}) from MLB Stats API\u{2026}");
+        let mut query = vec![
+            ("teamId", team_id.to_string()),
+            ("season", season.to_string()),
+            ("sportId", "1".to_string()),
+            ("gameType", codes),
+        ];
+        if !hydrate.is_empty() {
+            let sections: Vec<&str> = hydrate.iter().map(|h| h.code()).collect();
+            query.push(("hydrate", sections.join(",")));
+        }
+        let resp: crate::ScheduleResponse = self.get("schedule", &query).await?;
+        let drift = resp.drift();
+        Ok((resp.into(), drift))
+    }
+
+    fn url(&self, path: &str, query: &[(&str, String)]) -> String {
+        let mut url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
+        for (i, (key, value)) in query.iter().enumerate() {
+            url.push(if i == 0 { '?' } else { '&' });
+            url.push_str(key);
+            url.push('=');
+            url.push_str(value);
+        }
+        url
+    }
+}
+
+/// Decoded responses by URL, each fresh for `ttl` and kept after that as a
+/// fallback.
+struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, serde_json::Value)>>,
+}
+
+impl ResponseCache {
+    fn new(ttl: Duration) -> Self {
+        ResponseCache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, url: &str) -> Option<serde_json::Value> {
+        match self.entries.lock().unwrap().get(url) {
+            Some((at, value)) if at.elapsed() < self.ttl => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    /// The last response for `url`, however old.
+    fn get_stale(&self, url: &str) -> Option<serde_json::Value> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(url).map(|(_, value)| value.clone())
+    }
+
+    fn put(&self, url: String, value: serde_json::Value) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (at, _)| at.elapsed() < STALE_FOR);
+        entries.insert(url, (Instant::now(), value));
+    }
+}
@@ -0,0 +1,54 @@
+//! Rain forecast for the home ballpark from the Open-Meteo API (no key required).
+//!
+//! Open-Meteo publishes an hourly forecast up to 16 days out; games further
+//! away than that have no forecast yet.
+
+use anyhow::Result;
+use serde::Deserialize;
+
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+/// Hours from first pitch that count toward the game's rain chance.
+const GAME_HOURS: usize = 3;
+
+#[derive(Debug, Deserialize)]
+struct ForecastResponse {
+    hourly: Hourly,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hourly {
+    /// `YYYY-MM-DDTHH:MM` in UTC.
+    time: Vec<String>,
+    precipitation_probability: Vec<Option<i64>>,
+}
+
+/// Highest hourly chance of rain (percent) over the first few hours of a
+/// game starting at `game_date` (RFC 3339, UTC) at the ballpark at
+/// `latitude`, `longitude`. `None` when the game is outside the forecast
+/// window.
+pub async fn rain_probability(
+    latitude: f64,
+    longitude: f64,
+    game_date: &str,
+) -> Result<Option<i64>> {
+    let url = format!(
+        "{OPEN_METEO_URL}?latitude={latitude}&longitude={longitude}\
+         &hourly=precipitation_probability&timezone=UTC&forecast_days=16"
+    );
+    let resp: ForecastResponse = crate::http::get_json(&url).await?;
+    Ok(max_over_game(&resp.hourly, game_date))
+}
+
+fn max_over_game(hourly: &Hourly, game_date: &str) -> Option<i64> {
+    // Hourly slots are keyed by "YYYY-MM-DDTHH"
+    let hour = game_date.get(..13)?;
+    let start = hourly.time.iter().position(|t| t.starts_with(hour))?;
+    hourly
+        .precipitation_probability
+        .iter()
+        .skip(start)
+        .take(GAME_HOURS)
+        .flatten()
+        .copied()
+        .max()
+}
@@ -0,0 +1,80 @@
+use gtm_scraper::breaker::{BreakerPolicy, CircuitBreaker, State};
+use std::time::Duration;
+
+fn breaker(cooldown_ms: u64) -> CircuitBreaker {
+    CircuitBreaker::new(
+        "upstream",
+        BreakerPolicy {
+            failures: 3,
+            cooldown: Duration::from_millis(cooldown_ms),
+            max_cooldown: Duration::from_millis(cooldown_ms * 3),
+        },
+    )
+}
+
+#[test]
+fn opens_after_consecutive_failures_only() {
+    let b = breaker(60_000);
+    b.record_failure();
+    b.record_failure();
+    b.record_success();
+    b.record_failure();
+    b.record_failure();
+    assert!(b.check().is_ok());
+    assert_eq!(b.status().state, State::Closed);
+
+    b.record_failure();
+    let status = b.status();
+    assert_eq!(status.state, State::Open);
+    assert_eq!(status.consecutive_failures, 3);
+    assert_eq!(status.trips, 1);
+    assert!(status.retry_in_secs.is_some());
+    let open = b.check().unwrap_err();
+    assert!(
+        open.to_string().starts_with("upstream is unavailable"),
+        "{open}"
+    );
+    assert_eq!(b.status().rejected, 1);
+}
+
+#[test]
+fn failed_probes_back_off_until_one_succeeds() {
+    let b = breaker(100);
+    for _ in 0..3 {
+        b.record_failure();
+    }
+    std::thread::sleep(Duration::from_millis(110));
+
+    // One probe after the cooldown; others wait for its result
+    assert!(b.check().is_ok());
+    assert_eq!(b.status().state, State::HalfOpen);
+    assert!(b.check().is_err());
+
+    // A failed probe reopens for twice as long
+    b.record_failure();
+    assert_eq!(b.status().state, State::Open);
+    std::thread::sleep(Duration::from_millis(120));
+    assert!(b.check().is_err(), "still inside the doubled cooldown");
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(b.check().is_ok());
+
+    b.record_success();
+    let status = b.status();
+    assert_eq!(status.state, State::Closed);
+    assert_eq!(status.consecutive_failures, 0);
+    assert_eq!(status.trips, 2);
+}
+
+#[test]
+fn shared_breakers_are_listed_by_name() {
+    let a = gtm_scraper::breaker::shared("b-upstream");
+    let again = gtm_scraper::breaker::shared("b-upstream");
+    gtm_scraper::breaker::shared("a-upstream");
+    a.record_failure();
+    assert_eq!(again.status().consecutive_failures, 1);
+    let names: Vec<String> = gtm_scraper::breaker::statuses()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(names, vec!["a-upstream", "b-upstream"]);
+}
@@ -0,0 +1,38 @@
+// Shared by several test binaries; not every one uses every helper.
+#![allow(dead_code)]
+
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Serve one canned response per connection, in order. An empty response
+/// hangs until the client times out. Returns the server's base URL.
+pub async fn serve<S: Into<String>>(responses: Vec<S>) -> String {
+    let responses: Vec<String> = responses.into_iter().map(Into::into).collect();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let _ = sock.read(&mut buf).await;
+                if response.is_empty() {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    return;
+                }
+                let _ = sock.write_all(response.as_bytes()).await;
+                let _ = sock.shutdown().await;
+            });
+        }
+    });
+    format!("http://{addr}/")
+}
+
+/// A 200 response carrying `body` as JSON.
+pub fn json_response(body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::serve;
+use gtm_scraper::http::{RetryPolicy, get_json_with};
+use std::time::Duration;
+
+fn fast_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        connect_timeout: Duration::from_secs(1),
+        timeout: Duration::from_millis(500),
+        max_attempts,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+    }
+}
+
+const UNAVAILABLE: &str =
+    "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const NOT_FOUND: &str = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const OK: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 8\r\nConnection: close\r\n\r\n{\"n\": 7}";
+
+#[derive(Debug, serde::Deserialize)]
+struct Body {
+    n: i64,
+}
+
+#[test]
+fn backoff_doubles_up_to_the_cap() {
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(1000),
+        ..RetryPolicy::default()
+    };
+    assert_eq!(policy.backoff(0, 1.0), Duration::from_millis(100));
+    assert_eq!(policy.backoff(1, 1.0), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3, 1.0), Duration::from_millis(800));
+    assert_eq!(policy.backoff(4, 1.0), Duration::from_millis(1000));
+    assert_eq!(policy.backoff(40, 1.0), Duration::from_millis(1000));
+    // Jitter scales the step down to half
+    assert_eq!(policy.backoff(1, 0.0), Duration::from_millis(100));
+    assert_eq!(policy.backoff(1, 0.5), Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn retries_server_errors_and_timeouts() {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(500))
+        .build()
+        .unwrap();
+    let url = serve(vec![UNAVAILABLE, "", OK]).await;
+    let body: Body = get_json_with(&client, &fast_policy(3), &url).await.unwrap();
+    assert_eq!(body.n, 7);
+
+    let url = serve(vec![UNAVAILABLE, UNAVAILABLE]).await;
+    let err = get_json_with::<Body>(&client, &fast_policy(2), &url)
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("after 2 attempts"), "{err:#}");
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let client = reqwest::Client::new();
+    // A second attempt would find no listener for a response
+    let url = serve(vec![NOT_FOUND]).await;
+    let err = get_json_with::<Body>(&client, &fast_policy(3), &url)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("404"), "{err:#}");
+}
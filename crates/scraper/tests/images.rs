@@ -0,0 +1,148 @@
+mod common;
+
+use common::{json_response, serve};
+use gtm_scraper::http::RetryPolicy;
+use gtm_scraper::images::{ImageCache, MAX_IMAGE_BYTES};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const PNG: &str = "\u{89}PNG fake";
+
+fn png_response() -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{PNG}",
+        PNG.len()
+    )
+}
+
+const UNAVAILABLE: &str =
+    "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+/// A fresh, empty cache directory for one test.
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gtm-images-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn cache(dir: &Path, ttl: Duration) -> ImageCache {
+    let policy = RetryPolicy {
+        connect_timeout: Duration::from_secs(1),
+        timeout: Duration::from_millis(500),
+        max_attempts: 1,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+    };
+    ImageCache::new(dir, ttl).with_client(reqwest::Client::new(), policy)
+}
+
+#[tokio::test]
+async fn fetches_once_then_serves_from_disk() {
+    let dir = cache_dir("fresh");
+    // One response only: a second fetch would fail to connect
+    let base = serve(vec![png_response()]).await;
+    let url = format!("{base}bobblehead.png");
+    let images = cache(&dir, Duration::from_secs(3600));
+
+    let first = images.get("42-image", &url).await.unwrap();
+    assert_eq!(first.content_type, "image/png");
+    assert_eq!(first.bytes, PNG.as_bytes());
+    let second = images.get("42-image", &url).await.unwrap();
+    assert_eq!(second, first);
+    assert!(dir.join("42-image").exists());
+    assert!(dir.join("42-image.meta").exists());
+
+    // A changed URL isn't answered from the cache
+    assert!(
+        images
+            .get("42-image", &format!("{base}other.png"))
+            .await
+            .is_err()
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn serves_a_stale_copy_when_the_refetch_fails() {
+    let dir = cache_dir("stale");
+    let base = serve(vec![png_response(), UNAVAILABLE.to_string()]).await;
+    let url = format!("{base}cap.png");
+    let images = cache(&dir, Duration::ZERO);
+
+    images.get("7-thumbnail", &url).await.unwrap();
+    let stale = images.get("7-thumbnail", &url).await.unwrap();
+    assert_eq!(stale.bytes, PNG.as_bytes());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn rejects_non_images_and_bad_keys() {
+    let dir = cache_dir("reject");
+    let base = serve(vec![json_response("{}")]).await;
+    let images = cache(&dir, Duration::from_secs(3600));
+
+    let err = images
+        .get("9-image", &format!("{base}promo"))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not an image we serve"), "{err}");
+    assert!(!dir.join("9-image").exists());
+
+    let err = images
+        .get("../escape", &format!("{base}promo"))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Bad image cache key"), "{err}");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn rejects_oversized_and_script_capable_images() {
+    let dir = cache_dir("oversized");
+    let announced = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{PNG}",
+        MAX_IMAGE_BYTES + 1
+    );
+    // No Content-Length: the cap applies while reading
+    let unannounced = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nConnection: close\r\n\r\n{}",
+        "x".repeat(MAX_IMAGE_BYTES + 1)
+    );
+    let svg = "HTTP/1.1 200 OK\r\nContent-Type: image/svg+xml\r\nContent-Length: 6\r\nConnection: close\r\n\r\n<svg/>";
+    let base = serve(vec![announced, unannounced, svg.to_string()]).await;
+    let images = cache(&dir, Duration::from_secs(3600));
+
+    for _ in 0..2 {
+        let err = images
+            .get("11-image", &format!("{base}big.png"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("is larger than"), "{err}");
+    }
+    let err = images
+        .get("11-image", &format!("{base}logo.svg"))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not an image we serve"), "{err}");
+    assert!(!dir.join("11-image").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn concurrent_fetches_of_one_image_both_cache_it() {
+    let dir = cache_dir("concurrent");
+    let base = serve(vec![png_response(), png_response()]).await;
+    let url = format!("{base}bobblehead.png");
+    let images = cache(&dir, Duration::from_secs(3600));
+
+    let (a, b) = tokio::join!(images.get("12-image", &url), images.get("12-image", &url));
+    assert_eq!(a.unwrap().bytes, PNG.as_bytes());
+    assert_eq!(b.unwrap().bytes, PNG.as_bytes());
+    let mut files: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, vec!["12-image", "12-image.meta"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
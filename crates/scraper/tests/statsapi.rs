@@ -0,0 +1,340 @@
+mod common;
+
+use common::{json_response, serve};
+use gtm_models::{GamePk, GameType, Hydration};
+use gtm_scraper::StatsApiClient;
+use gtm_scraper::breaker::{BreakerOpen, BreakerPolicy, CircuitBreaker};
+use gtm_scraper::http::RetryPolicy;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SCHEDULE: &str = r#"{"dates": [{"games": [{
+    "gamePk": 778899,
+    "gameType": "R",
+    "season": "2026",
+    "gameDate": "2026-04-03T20:35:00Z",
+    "officialDate": "2026-04-03",
+    "status": {"abstractGameState": "Preview", "detailedState": "Scheduled", "statusCode": "S"},
+    "teams": {
+        "away": {"team": {"id": 119, "name": "Los Angeles Dodgers"}},
+        "home": {"team": {"id": 137, "name": "San Francisco Giants"}}
+    },
+    "venue": {"id": 2395, "name": "Oracle Park"},
+    "promotions": [{"offerId": 55, "name": "Magnet Schedule"}]
+}]}]}"#;
+
+fn client(base_url: &str) -> StatsApiClient {
+    let policy = RetryPolicy {
+        max_attempts: 1,
+        ..RetryPolicy::default()
+    };
+    StatsApiClient::new()
+        .with_client(reqwest::Client::new(), policy)
+        .with_base_url(base_url)
+        .with_breaker(Arc::new(CircuitBreaker::new(
+            "test",
+            BreakerPolicy::default(),
+        )))
+}
+
+#[tokio::test]
+async fn schedule_decodes_games_and_promotions() {
+    let url = serve(vec![json_response(SCHEDULE)]).await;
+    let data = client(&url)
+        .schedule(137, 2026, &[GameType::Regular], &[Hydration::Promotions])
+        .await
+        .unwrap();
+    assert_eq!(data.games.len(), 1);
+    assert_eq!(data.games[0].game_pk, GamePk(778899));
+    assert_eq!(data.games[0].double_header, "N");
+    assert_eq!(data.promotions.len(), 1);
+    assert_eq!(data.promotions[0].game_pk, GamePk(778899));
+}
+
+#[tokio::test]
+async fn duplicate_promotions_are_collapsed_into_a_stable_order() {
+    let body = SCHEDULE.replace(
+        r#""promotions": [{"offerId": 55, "name": "Magnet Schedule"}]"#,
+        r#""promotions": [
+            {"offerId": 55, "name": "Magnet Schedule", "order": 7},
+            {"offerId": 12, "name": "Bobblehead", "order": 3},
+            {"offerId": 55, "name": "Magnet Schedule", "order": 9}
+        ]"#,
+    );
+    let url = serve(vec![json_response(&body)]).await;
+    let data = client(&url)
+        .schedule(137, 2026, &[GameType::Regular], &[Hydration::Promotions])
+        .await
+        .unwrap();
+    let order: Vec<(i64, i64)> = data
+        .promotions
+        .iter()
+        .map(|p| (p.offer_id, p.display_order))
+        .collect();
+    assert_eq!(order, vec![(12, 0), (55, 1)]);
+}
+
+#[tokio::test]
+async fn schedules_without_hydrated_sections_still_decode() {
+    let body = SCHEDULE.replace(
+        r#",
+    "promotions": [{"offerId": 55, "name": "Magnet Schedule"}]"#,
+        "",
+    );
+    let url = serve(vec![json_response(&body)]).await;
+    let (data, drift) = client(&url)
+        .schedule_with_drift(137, 2026, &[], &[])
+        .await
+        .unwrap();
+    assert_eq!(data.games.len(), 1);
+    assert!(data.promotions.is_empty());
+    assert!(drift.is_empty(), "{:?}", drift.lines());
+
+    // Hydrated sections we don't store aren't drift
+    let body = SCHEDULE.replace(
+        r#""gamePk": 778899,"#,
+        r#""gamePk": 778899, "weather": {"temp": "61"}, "tickets": [],"#,
+    );
+    let url = serve(vec![json_response(&body)]).await;
+    let (_, drift) = client(&url)
+        .schedule_with_drift(137, 2026, &[], &[Hydration::Weather, Hydration::Tickets])
+        .await
+        .unwrap();
+    assert!(drift.is_empty(), "{:?}", drift.lines());
+}
+
+#[tokio::test]
+async fn hydrated_game_details_are_decoded() {
+    let body = SCHEDULE
+        .replace(
+            r#""home": {"team": {"id": 137, "name": "San Francisco Giants"}}"#,
+            r#""home": {"team": {"id": 137, "name": "San Francisco Giants"},
+                "probablePitcher": {"id": 657277, "fullName": "Logan Webb"}}"#,
+        )
+        .replace(
+            r#""gamePk": 778899,"#,
+            r#""gamePk": 778899,
+            "weather": {"condition": "Partly Cloudy", "temp": "61", "wind": "12 mph, Out To CF"},
+            "broadcasts": [
+                {"name": "NBCS-BA", "type": "TV"},
+                {"name": "KNBR 680", "type": "AM"},
+                {"name": "NBCS-BA", "type": "TV"},
+                {"name": "FOX", "type": "TV"}
+            ],"#,
+        );
+    let url = serve(vec![json_response(&body)]).await;
+    let (data, drift) = client(&url)
+        .schedule_with_drift(
+            137,
+            2026,
+            &[],
+            &[
+                Hydration::ProbablePitcher,
+                Hydration::Weather,
+                Hydration::Broadcasts,
+            ],
+        )
+        .await
+        .unwrap();
+    assert!(drift.is_empty(), "{:?}", drift.lines());
+    let game = &data.games[0];
+    assert_eq!(game.home_probable_pitcher.as_deref(), Some("Logan Webb"));
+    assert_eq!(game.away_probable_pitcher, None);
+    assert_eq!(game.weather_condition.as_deref(), Some("Partly Cloudy"));
+    assert_eq!(game.weather_temp.as_deref(), Some("61"));
+    assert_eq!(game.tv_broadcasts.as_deref(), Some("NBCS-BA, FOX"));
+}
+
+#[test]
+fn hydrations_parse_from_config_names() {
+    assert_eq!(
+        "probablepitcher".parse::<Hydration>(),
+        Ok(Hydration::ProbablePitcher)
+    );
+    assert_eq!(
+        " Promotions ".parse::<Hydration>(),
+        Ok(Hydration::Promotions)
+    );
+    assert_eq!(Hydration::Promotions.code(), "game(promotions)");
+    assert_eq!(Hydration::ProbablePitcher.to_string(), "probablePitcher");
+    assert_eq!(Hydration::Broadcasts.code(), "broadcasts(all)");
+    assert!("lineups".parse::<Hydration>().is_err());
+}
+
+#[tokio::test]
+async fn cached_responses_skip_the_server() {
+    // Only one response is served; a second request would fail
+    let url = serve(vec![json_response(SCHEDULE)]).await;
+    let api = client(&url).with_cache(Duration::from_secs(60));
+    let first = api.schedule(137, 2026, &[], &[]).await.unwrap();
+    let second = api.schedule(137, 2026, &[], &[]).await.unwrap();
+    assert_eq!(first.games.len(), second.games.len());
+
+    let uncached = client(&url);
+    assert!(uncached.schedule(137, 2026, &[], &[]).await.is_err());
+}
+
+#[tokio::test]
+async fn unknown_fields_are_reported_as_drift() {
+    let url = serve(vec![json_response(SCHEDULE)]).await;
+    let (_, drift) = client(&url)
+        .schedule_with_drift(137, 2026, &[], &[Hydration::Promotions])
+        .await
+        .unwrap();
+    assert!(drift.is_empty(), "{:?}", drift.lines());
+
+    // A new field on the game, one we deliberately ignore, and a new
+    // field on the promotion
+    let body = SCHEDULE
+        .replace(
+            r#""gamePk": 778899,"#,
+            r#""gamePk": 778899, "seriesStatus": {"result": "1-0"}, "link": "/game","#,
+        )
+        .replace(r#""offerId": 55,"#, r#""offerId": 55, "sponsor": "Acme","#);
+    let url = serve(vec![json_response(&body)]).await;
+    let (data, drift) = client(&url)
+        .schedule_with_drift(137, 2026, &[], &[Hydration::Promotions])
+        .await
+        .unwrap();
+    assert_eq!(data.games.len(), 1);
+    assert_eq!(
+        drift.lines(),
+        vec![
+            "new field `seriesStatus` seen on 1 games",
+            "new field `sponsor` seen on 1 promotions",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn preview_lookups_decode_pitchers_and_streaks() {
+    let pitchers = r#"{"dates": [{"games": [{"teams": {
+        "away": {"team": {"id": 119}},
+        "home": {"team": {"id": 137}, "probablePitcher": {"id": 657277, "fullName": "Logan Webb"}}
+    }}]}]}"#;
+    let standings = r#"{"records": [{"teamRecords": [
+        {"team": {"id": 137}, "wins": 10, "losses": 10, "streak": {"streakCode": "L1"}},
+        {"team": {"id": 119}, "wins": 12, "losses": 8}
+    ]}]}"#;
+    let url = serve(vec![json_response(pitchers), json_response(standings)]).await;
+    let api = client(&url);
+
+    let probable = api.probable_pitchers(GamePk(778899)).await.unwrap();
+    assert_eq!(probable.away, None);
+    assert_eq!(probable.home.as_deref(), Some("Logan Webb"));
+
+    let table = api.standings(2026).await.unwrap();
+    assert_eq!(table[&137].record(), "10-10");
+    assert_eq!(table[&137].streak.as_deref(), Some("L1"));
+    assert_eq!(table[&119].streak, None);
+}
+
+#[tokio::test]
+async fn live_games_decode_linescore() {
+    let live = r#"{"dates": [{"games": [{
+        "gamePk": 778899,
+        "status": {"abstractGameState": "Live", "detailedState": "In Progress", "statusCode": "I"},
+        "teams": {
+            "away": {"team": {"id": 119, "name": "Los Angeles Dodgers"}, "score": 2},
+            "home": {"team": {"id": 137, "name": "San Francisco Giants"}, "score": 3}
+        },
+        "linescore": {"currentInning": 7, "inningState": "Top", "isTopInning": true}
+    }, {
+        "gamePk": 778900,
+        "status": {"abstractGameState": "Preview", "detailedState": "Scheduled", "statusCode": "S"},
+        "teams": {
+            "away": {"team": {"id": 119, "name": "Los Angeles Dodgers"}},
+            "home": {"team": {"id": 137, "name": "San Francisco Giants"}}
+        }
+    }]}]}"#;
+    let url = serve(vec![json_response(live)]).await;
+
+    let games = client(&url).live_games(137, "2026-04-03").await.unwrap();
+    assert_eq!(games.len(), 2);
+    assert_eq!(games[0].game_pk, GamePk(778899));
+    assert_eq!(
+        (games[0].away_score, games[0].home_score),
+        (Some(2), Some(3))
+    );
+    assert_eq!(games[0].current_inning, Some(7));
+    assert_eq!(games[0].inning_state.as_deref(), Some("Top"));
+    assert!(!games[0].is_final());
+    assert_eq!(games[1].current_inning, None);
+}
+
+#[tokio::test]
+async fn stale_responses_cover_an_outage_and_an_open_breaker() {
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    let url = serve(vec![
+        json_response(SCHEDULE),
+        UNAVAILABLE.to_string(),
+        UNAVAILABLE.to_string(),
+    ])
+    .await;
+    let breaker = Arc::new(CircuitBreaker::new(
+        "stats",
+        BreakerPolicy {
+            failures: 1,
+            cooldown: Duration::from_secs(60),
+            max_cooldown: Duration::from_secs(60),
+        },
+    ));
+    // Expires at once, so every call goes to the server while it's up
+    let api = client(&url)
+        .with_cache(Duration::ZERO)
+        .with_breaker(breaker.clone());
+    assert_eq!(
+        api.schedule(137, 2026, &[], &[]).await.unwrap().games.len(),
+        1
+    );
+
+    // The server fails: the stale schedule is served and the breaker opens
+    assert_eq!(
+        api.schedule(137, 2026, &[], &[]).await.unwrap().games.len(),
+        1
+    );
+    let status = breaker.status();
+    assert_eq!(status.state, gtm_scraper::breaker::State::Open);
+    assert_eq!(status.trips, 1);
+
+    // While open the server isn't asked; there's nothing stale for 2027
+    assert_eq!(
+        api.schedule(137, 2026, &[], &[]).await.unwrap().games.len(),
+        1
+    );
+    let err = api.schedule(137, 2027, &[], &[]).await.unwrap_err();
+    assert!(err.is::<BreakerOpen>(), "{err:#}");
+    assert_eq!(breaker.status().rejected, 2);
+}
+
+#[tokio::test]
+async fn errors_that_arent_outages_leave_the_breaker_alone() {
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const NOT_FOUND: &str =
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    let url = serve(vec![UNAVAILABLE, NOT_FOUND, UNAVAILABLE, NOT_FOUND]).await;
+    let breaker = Arc::new(CircuitBreaker::new(
+        "stats",
+        BreakerPolicy {
+            failures: 2,
+            cooldown: Duration::ZERO,
+            max_cooldown: Duration::ZERO,
+        },
+    ));
+    let api = client(&url).with_breaker(breaker.clone());
+
+    // The 404 doesn't reset the count, so the second 503 opens the breaker
+    for _ in 0..3 {
+        assert!(api.schedule(137, 2026, &[], &[]).await.is_err());
+    }
+    assert_eq!(breaker.status().state, gtm_scraper::breaker::State::Open);
+
+    // Nor does a 404 to the half-open probe close it
+    assert!(api.schedule(137, 2026, &[], &[]).await.is_err());
+    assert_eq!(
+        breaker.status().state,
+        gtm_scraper::breaker::State::HalfOpen
+    );
+}
@@ -1,8 +1,8 @@
-import { useEffect, useMemo, useState, useCallback } from 'react';
+import { useEffect, useMemo, useState, useCallback, useRef } from 'react';
 import { useAuth0 } from '@auth0/auth0-react';
 import { BarChart3, Ticket, Check, X, Lock, ChevronDown, ChevronRight, User, CalendarDays } from 'lucide-react';
 import type { UserAllocationSection, UserAllocationEntry, UserTicketInfo, GameAllocationDetail, GameTicketWithUser } from './types';
-import { fetchAllocationByUsers, fetchGameAllocation, allocateTickets, revokeTicket, fetchMe } from './api';
+import { ApiError, fetchAllocationByUsers, fetchGameAllocation, allocateTickets, revokeTicket, fetchMe } from './api';
 import useAutoRefresh from './useAutoRefresh';
 
 function seatLabel(t: { section: string; row: string; seat: string }) {
@@ -97,7 +97,11 @@ export default function AllocationDashboard() {
       .finally(() => setModalLoading(false));
   };
 
+  // One key per picker session, so a double-clicked save assigns once
+  const saveKey = useRef(crypto.randomUUID());
+
   const closePicker = () => {
+    saveKey.current = crypto.randomUUID();
     setPickerUserId(null);
     setPickerRequestId(null);
     setPickerEntry(null);
@@ -136,7 +140,16 @@ export default function AllocationDashboard() {
           user_id: pickerUserId,
           request_id: pickerRequestId ?? undefined,
         }));
-      if (assigns.length > 0) await allocateTickets(assigns);
+      if (assigns.length > 0) {
+        try {
+          await allocateTickets(assigns, saveKey.current);
+        } catch (err: unknown) {
+          // Seats in more than one section need the admin's say-so
+          if (!(err instanceof ApiError && err.code === 'split_party')) throw err;
+          if (!window.confirm(`${err.message}. Assign them anyway?`)) return;
+          await allocateTickets(assigns, saveKey.current, true);
+        }
+      }
       closePicker();
       // Refresh all data
       fetchAllocationByUsers().then(setSections).catch(() => {});
@@ -2,12 +2,13 @@ import { useEffect, useRef, useState } from 'react'
 import { BrowserRouter, Routes, Route, NavLink } from 'react-router-dom'
 import { useAuth0 } from '@auth0/auth0-react'
 import { CalendarDays, Armchair, LogIn, LogOut, Ticket, BarChart3, ShieldCheck, Menu, KeyRound } from 'lucide-react'
-import { setTokenGetter, fetchMe } from './api'
+import { setTokenGetter, fetchMe, getLocalUser, setLocalUser } from './api'
 import SchedulePage from './SchedulePage'
 import SeatAdmin from './SeatAdmin'
 import MyRequests from './MyRequests'
 import AllocationDashboard from './AllocationDashboard'
 import MyAllocations from './MyAllocations'
+import LocalUserPicker from './LocalUserPicker'
 
 function App() {
   const { isAuthenticated, isLoading, user, loginWithRedirect, logout, getAccessTokenSilently } = useAuth0()
@@ -17,6 +18,20 @@ function App() {
   const [passwordMsg, setPasswordMsg] = useState<string | null>(null)
   const menuRef = useRef<HTMLDivElement>(null)
 
+  const gtmConfig = (window as any).__GTM_CONFIG__ || {
+    auth0_domain: import.meta.env.VITE_AUTH0_DOMAIN,
+    auth0_client_id: import.meta.env.VITE_AUTH0_CLIENT_ID,
+  };
+  // With auth_mode = "none" there is no login: the member picks who to act as
+  const localMode = gtmConfig.auth_mode === 'none'
+  const [localUser, setLocalUserState] = useState<number | null>(() => localMode ? getLocalUser() : null)
+  const signedIn = localMode ? localUser !== null : isAuthenticated
+
+  const pickLocalUser = (id: number | null) => {
+    setLocalUser(id)
+    setLocalUserState(id)
+  }
+
   useEffect(() => {
     setTokenGetter(async () => {
       try {
@@ -28,11 +43,11 @@ function App() {
   }, [getAccessTokenSilently]);
 
   useEffect(() => {
-    if (!isAuthenticated) { setUserRole(null); return; }
+    if (!signedIn) { setUserRole(null); return; }
     fetchMe()
       .then((me) => setUserRole(me.role))
       .catch(() => setUserRole(null));
-  }, [isAuthenticated]);
+  }, [signedIn, localUser]);
 
   useEffect(() => {
     fetch('/api/health').then(r => r.json()).then(d => setAppVersion(d.version)).catch(() => {});
@@ -47,11 +62,6 @@ function App() {
     return () => document.removeEventListener('mousedown', handler);
   }, [showMenu]);
 
-  const gtmConfig = (window as any).__GTM_CONFIG__ || {
-    auth0_domain: import.meta.env.VITE_AUTH0_DOMAIN,
-    auth0_client_id: import.meta.env.VITE_AUTH0_CLIENT_ID,
-  };
-
   const handleChangePassword = async () => {
     if (!user?.email) return;
     try {
@@ -116,7 +126,7 @@ function App() {
                   <CalendarDays className="w-4 h-4" />
                   Schedule
                 </NavLink>
-                {isAuthenticated && (
+                {signedIn && (
                   <>
                     <NavLink
                       to="/my/requests"
@@ -178,7 +188,17 @@ function App() {
                 )}
               </nav>
               <div className="border-l border-gray-700 pl-4">
-                {isLoading ? (
+                {localMode ? (
+                  <div className="flex items-center gap-3">
+                    <LocalUserPicker value={localUser} onChange={pickLocalUser} />
+                    {userRole === 'admin' && (
+                      <span className="flex items-center gap-1 px-1.5 py-0.5 rounded text-[10px] font-bold uppercase bg-orange-600/20 text-orange-400 border border-orange-800/50">
+                        <ShieldCheck className="w-3 h-3" />
+                        Admin
+                      </span>
+                    )}
+                  </div>
+                ) : isLoading ? (
                   <span className="text-gray-500 text-sm">…</span>
                 ) : isAuthenticated ? (
                   <div className="flex items-center gap-3">
@@ -241,6 +261,12 @@ function App() {
           </div>
         </header>
 
+        {localMode && (
+          <div className="bg-red-900/80 border-b border-red-700 text-red-100 text-sm text-center px-4 py-2">
+            Login is turned off: anyone who can reach this server can act as any member.
+          </div>
+        )}
+
         {/* Toast notification */}
         {passwordMsg && (
           <div className={`fixed top-4 right-4 z-[100] max-w-sm rounded-lg border px-4 py-3 shadow-lg text-sm flex items-start gap-3 ${
@@ -0,0 +1,119 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Send, Undo2 } from 'lucide-react';
+import { clearForwarded, fetchForwarding, recordForwarded } from './api';
+import type { ForwardMethod, TicketForward } from './types';
+
+const METHODS: { value: ForwardMethod; label: string }[] = [
+  { value: 'ballpark_app', label: 'Ballpark app' },
+  { value: 'email', label: 'Email' },
+  { value: 'print', label: 'Print' },
+  { value: 'other', label: 'Other' },
+];
+
+/** Which of a game's assigned tickets still need forwarding to their holders. */
+export default function ForwardingChecklist({ gamePk, refreshKey }: { gamePk: number; refreshKey?: unknown }) {
+  const [rows, setRows] = useState<TicketForward[]>([]);
+  const [method, setMethod] = useState<ForwardMethod>('ballpark_app');
+  const [externalIds, setExternalIds] = useState<Record<number, string>>({});
+  const [error, setError] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  const load = useCallback(() => {
+    fetchForwarding(gamePk)
+      .then(setRows)
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)));
+  }, [gamePk]);
+
+  useEffect(() => load(), [load, refreshKey]);
+
+  const run = async (action: () => Promise<void>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+      load();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  if (rows.length === 0) return null;
+  const pending = rows.filter((r) => !r.forwarded).length;
+
+  return (
+    <div className="mt-8">
+      <div className="flex items-center justify-between mb-2">
+        <h3 className="text-sm font-bold">
+          Forwarding <span className="text-gray-500 font-normal">· {pending} of {rows.length} to send</span>
+        </h3>
+        <div className="flex items-center gap-2">
+          <select
+            value={method}
+            onChange={(e) => setMethod(e.target.value as ForwardMethod)}
+            className="bg-gray-900 border border-gray-700 rounded px-2 py-1 text-xs text-white"
+          >
+            {METHODS.map((m) => <option key={m.value} value={m.value}>{m.label}</option>)}
+          </select>
+          <button
+            disabled={busy || pending === 0}
+            onClick={() => run(() => recordForwarded({ gamePk }, method))}
+            className="px-2.5 py-1 rounded text-xs font-medium bg-orange-600 text-white hover:bg-orange-500 disabled:opacity-50"
+          >
+            Mark all sent
+          </button>
+        </div>
+      </div>
+      {error && <p className="text-xs text-red-400 mb-2">{error}</p>}
+      <table className="w-full text-sm">
+        <tbody>
+          {rows.map((r) => (
+            <tr key={r.ticket_id} className="border-b border-gray-800/50">
+              <td className="py-1.5 px-3 font-mono text-xs">{r.section}:{r.row}{r.seat}</td>
+              <td className="py-1.5 px-3">{r.user_name}</td>
+              <td className="py-1.5 px-3">
+                <input
+                  value={externalIds[r.ticket_id] ?? r.external_ticket_id ?? ''}
+                  onChange={(e) => setExternalIds({ ...externalIds, [r.ticket_id]: e.target.value })}
+                  placeholder="MLB ticket ID"
+                  className="bg-gray-900 border border-gray-700 rounded px-2 py-0.5 text-xs w-36"
+                />
+              </td>
+              <td className="py-1.5 px-3 text-xs">
+                {r.forwarded ? (
+                  <span className="text-green-400">Sent {r.forwarded_on} via {METHODS.find((m) => m.value === r.forward_method)?.label ?? r.forward_method}</span>
+                ) : r.forwarded_to != null ? (
+                  <span className="text-yellow-400">Sent to a previous holder</span>
+                ) : (
+                  <span className="text-gray-500">Not sent</span>
+                )}
+              </td>
+              <td className="py-1.5 px-3 text-right">
+                {r.forwarded ? (
+                  <button
+                    disabled={busy}
+                    onClick={() => run(() => clearForwarded(r.ticket_id))}
+                    className="p-1 rounded text-gray-400 hover:text-white hover:bg-gray-800"
+                    title="Undo"
+                  >
+                    <Undo2 className="w-3.5 h-3.5" />
+                  </button>
+                ) : (
+                  <button
+                    disabled={busy}
+                    onClick={() => run(() => recordForwarded({ ticketId: r.ticket_id }, method, externalIds[r.ticket_id]))}
+                    className="p-1 rounded text-orange-400 hover:bg-orange-900/20"
+                    title="Mark sent"
+                  >
+                    <Send className="w-3.5 h-3.5" />
+                  </button>
+                )}
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+    </div>
+  );
+}
@@ -1,10 +1,11 @@
-import { useEffect, useState, useMemo, useCallback } from 'react';
+import { useEffect, useState, useMemo, useCallback, useRef } from 'react';
 import { useAuth0 } from '@auth0/auth0-react';
 import { useParams, useNavigate } from 'react-router-dom';
 import { ArrowLeft, Ticket, Check, X, Lock } from 'lucide-react';
 import type { GameAllocationDetail, GameTicketWithUser, RequestWithUser } from './types';
-import { fetchGameAllocation, allocateTickets, revokeTicket, fetchMe } from './api';
+import { ApiError, fetchGameAllocation, allocateTickets, revokeTicket, fetchMe } from './api';
 import useAutoRefresh from './useAutoRefresh';
+import ForwardingChecklist from './ForwardingChecklist';
 
 function seatLabel(t: GameTicketWithUser) {
   return `${t.section}:${t.row}${t.seat}`;
@@ -75,7 +76,11 @@ export default function GameAllocation() {
     setPendingChanges({});
   };
 
+  // One key per picker session, so a double-clicked save assigns once
+  const saveKey = useRef(crypto.randomUUID());
+
   const closePicker = () => {
+    saveKey.current = crypto.randomUUID();
     setPickerUserId(null);
     setPickerRequestId(null);
     setPendingChanges({});
@@ -127,7 +132,14 @@ export default function GameAllocation() {
           request_id: pickerRequestId ?? undefined,
         }));
       if (assigns.length > 0) {
-        await allocateTickets(assigns);
+        try {
+          await allocateTickets(assigns, saveKey.current);
+        } catch (err: unknown) {
+          // Seats in more than one section need the admin's say-so
+          if (!(err instanceof ApiError && err.code === 'split_party')) throw err;
+          if (!window.confirm(`${err.message}. Assign them anyway?`)) return;
+          await allocateTickets(assigns, saveKey.current, true);
+        }
       }
       closePicker();
       load();
@@ -250,6 +262,8 @@ export default function GameAllocation() {
         </div>
       )}
 
+      <ForwardingChecklist gamePk={game.game_pk} refreshKey={data} />
+
       {/* ===== Seat Picker Modal ===== */}
       {pickerUserId != null && (() => {
         const pickerUser = requests.find((r) => r.user_id === pickerUserId);
@@ -0,0 +1,42 @@
+import { useEffect, useState } from 'react'
+import { UserRound } from 'lucide-react'
+import { fetchLocalUsers } from './api'
+import type { LocalUser } from './types'
+
+/** Choose which member to act as when the server runs without a login. */
+export default function LocalUserPicker({ value, onChange }: {
+  value: number | null;
+  onChange: (id: number | null) => void;
+}) {
+  const [users, setUsers] = useState<LocalUser[]>([])
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    fetchLocalUsers()
+      .then(setUsers)
+      .catch((err) => setError(err instanceof Error ? err.message : String(err)))
+  }, [])
+
+  if (error) return <span className="text-sm text-red-400">{error}</span>
+  if (users.length === 0) {
+    return <span className="text-sm text-gray-500">No users yet — add one with <code>gtm add-user</code></span>
+  }
+
+  return (
+    <label className="flex items-center gap-2 text-sm text-gray-300">
+      <UserRound className="w-4 h-4 text-gray-500" />
+      <select
+        value={value ?? ''}
+        onChange={(e) => onChange(e.target.value ? Number(e.target.value) : null)}
+        className="bg-gray-900 border border-gray-700 rounded px-2 py-1 text-sm text-white"
+      >
+        <option value="">Pick a user…</option>
+        {users.map((u) => (
+          <option key={u.id} value={u.id}>
+            {u.name}{u.admin ? ' (admin)' : ''}
+          </option>
+        ))}
+      </select>
+    </label>
+  )
+}
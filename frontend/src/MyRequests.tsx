@@ -1,13 +1,12 @@
 import { useEffect, useState, useMemo, useCallback } from 'react';
 import { useAuth0 } from '@auth0/auth0-react';
 import { Ticket, Trash2, Edit3, Check, X, Gift, Plus, Minus, Send, Sun, Moon, Clock, AlertTriangle, Star, Ban } from 'lucide-react';
-import type { TicketRequest, TicketSummary, Game, GameTicketDetail, Promotion } from './types';
+import type { TicketRequest, TicketSummary, Game, GameTicketDetail } from './types';
 import {
   fetchMyRequests,
   fetchMyGames,
   fetchGames,
   fetchTicketSummary,
-  fetchPromotions,
   fetchMyGameTags,
   setGameTag,
   createRequests,
@@ -16,8 +15,8 @@ import {
   releaseGameTickets,
 } from './api';
 import useAutoRefresh from './useAutoRefresh';
+import useTeam from './useTeam';
 
-const GIANTS_TEAM_NAME = 'San Francisco Giants';
 
 function formatDate(d: string) {
   const date = new Date(d + 'T00:00:00');
@@ -27,7 +26,7 @@ function formatDate(d: string) {
 function formatTime(game: Game): string {
   if (game.start_time_tbd) return 'TBD';
   const d = new Date(game.game_date);
-  return d.toLocaleTimeString('en-US', { hour: 'numeric', minute: '2-digit' });
+  return d.toLocaleTimeString('en-US', { hour: 'numeric', minute: '2-digit', timeZone: game.time_zone ?? undefined });
 }
 
 export default function MyRequests() {
@@ -41,7 +40,6 @@ export default function MyRequests() {
   const [error, setError] = useState<string | null>(null);
   const [editingId, setEditingId] = useState<number | null>(null);
   const [editSeats, setEditSeats] = useState(1);
-  const [promoMap, setPromoMap] = useState<Record<number, Promotion[]>>({});
 
   // Game tags (shortlist / can't go)
   const [tagsMap, setTagsMap] = useState<Record<number, { shortlist: boolean; cantGo: boolean }>>({});
@@ -59,7 +57,7 @@ export default function MyRequests() {
   const load = useCallback((silent = false) => {
     if (!isAuthenticated) return;
     if (!silent) setLoading(true);
-    Promise.all([fetchMyRequests(), fetchGames(), fetchTicketSummary(), fetchMyGames(), fetchMyGameTags()])
+    Promise.all([fetchMyRequests(), fetchGames({}, ['promotions']), fetchTicketSummary(), fetchMyGames(), fetchMyGameTags()])
       .then(([reqs, gameList, summaryList, myGameTickets, gameTags]) => {
         setRequests(reqs.filter((r) => r.status !== 'withdrawn'));
         setAllGames(gameList);
@@ -87,47 +85,21 @@ export default function MyRequests() {
   useEffect(() => load(), [load]);
   useAutoRefresh(() => load(true));
 
-  // Load promos for all relevant games (requested + available)
-  useEffect(() => {
-    const requestedPks = requests.map((r) => r.game_pk);
-    const availablePks = allGames
-      .filter((g) => g.home_team_name === GIANTS_TEAM_NAME)
-      .map((g) => g.game_pk);
-    const gamePks = [...new Set([...requestedPks, ...availablePks])];
-    if (gamePks.length === 0) return;
-    let cancelled = false;
-    Promise.all(
-      gamePks.map(async (pk) => {
-        try {
-          const promos = await fetchPromotions(pk);
-          return [pk, promos] as const;
-        } catch {
-          return [pk, []] as const;
-        }
-      }),
-    ).then((results) => {
-      if (cancelled) return;
-      const map: Record<number, Promotion[]> = {};
-      for (const [pk, promos] of results) map[pk] = promos as Promotion[];
-      setPromoMap(map);
-    });
-    return () => { cancelled = true; };
-  }, [requests, allGames]);
-
   // Upcoming home games with no existing request
+  const teamId = useTeam();
   const requestedPks = useMemo(() => new Set(requests.map((r) => r.game_pk)), [requests]);
   const availableGames = useMemo(() => {
     const today = new Date().toISOString().slice(0, 10);
     return allGames
       .filter(
         (g) =>
-          g.home_team_name === GIANTS_TEAM_NAME &&
+          g.home_team_id === teamId &&
           g.official_date >= today &&
           g.status_detailed !== 'Final' &&
           !requestedPks.has(g.game_pk),
       )
       .sort((a, b) => a.official_date.localeCompare(b.official_date));
-  }, [allGames, requestedPks]);
+  }, [allGames, requestedPks, teamId]);
 
   const filteredGames = useMemo(() => {
     return availableGames.filter((g) => {
@@ -323,8 +295,7 @@ export default function MyRequests() {
                       <td className="py-2 px-3">{game?.away_team_name ?? '—'}</td>
                       <td className="py-2 px-3 text-gray-300 max-w-[250px]">
                         {(() => {
-                          const promos = promoMap[r.game_pk];
-                          const names = promos?.map((p) => p.name).join(', ');
+                          const names = game?.promotions?.join(', ');
                           return names ? (
                             <span className="flex items-center gap-1">
                               <Gift className="w-3.5 h-3.5 text-orange-400 flex-shrink-0" />
@@ -342,7 +313,7 @@ export default function MyRequests() {
                             onChange={(e) => setEditSeats(Number(e.target.value))}
                             className="bg-gray-800 border border-gray-700 rounded px-2 py-0.5 text-white text-sm"
                           >
-                            {[1, 2, 3, 4].map((n) => <option key={n} value={n}>{n}</option>)}
+                            {Array.from({ length: game?.max_seats ?? 4 }, (_, i) => i + 1).map((n) => <option key={n} value={n}>{n}</option>)}
                           </select>
                         ) : (
                           r.seats_requested
@@ -597,8 +568,7 @@ export default function MyRequests() {
                         </td>
                         <td className="py-2 px-3 text-gray-300 max-w-[250px]">
                           {(() => {
-                            const promos = promoMap[g.game_pk];
-                            const names = promos?.map((p) => p.name).join(', ');
+                            const names = g.promotions?.join(', ');
                             return names ? (
                               <span className="flex items-center gap-1">
                                 <Gift className="w-3.5 h-3.5 text-orange-400 flex-shrink-0" />
@@ -1,7 +1,8 @@
-import { useState, useMemo } from 'react';
+import { useState, useMemo, useEffect } from 'react';
 import { Send, Plus, Minus, X } from 'lucide-react';
 import type { Game, TicketRequest } from './types';
-import { createRequests } from './api';
+import { createRequests, fetchMyPreferences } from './api';
+import useTeam from './useTeam';
 
 interface Props {
   games: Game[];
@@ -10,12 +11,21 @@ interface Props {
   onSubmitted: () => void;
 }
 
-const GIANTS_TEAM_NAME = 'San Francisco Giants';
+const DEFAULT_MAX_SEATS = 4;
+const DEFAULT_SEATS = 2;
 
 export default function RequestPanel({ games, existingRequests, onClose, onSubmitted }: Props) {
   const [selections, setSelections] = useState<Record<number, number>>({});
   const [submitting, setSubmitting] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [defaultSeats, setDefaultSeats] = useState(DEFAULT_SEATS);
+  const teamId = useTeam();
+
+  useEffect(() => {
+    fetchMyPreferences()
+      .then((prefs) => setDefaultSeats(prefs.default_seats ?? DEFAULT_SEATS))
+      .catch(() => {});
+  }, []);
 
   const existingMap = useMemo(() => {
     const map: Record<number, TicketRequest> = {};
@@ -25,25 +35,28 @@ export default function RequestPanel({ games, existingRequests, onClose, onSubmi
 
   const homeGames = useMemo(
     () => games
-      .filter((g) => g.home_team_name === GIANTS_TEAM_NAME && g.status_detailed !== 'Final')
+      .filter((g) => g.home_team_id === teamId && g.status_detailed !== 'Final')
       .sort((a, b) => a.official_date.localeCompare(b.official_date)),
-    [games],
+    [games, teamId],
   );
 
+  const maxSeats = (gamePk: number) =>
+    games.find((g) => g.game_pk === gamePk)?.max_seats ?? DEFAULT_MAX_SEATS;
+
   const toggleGame = (gamePk: number) => {
     setSelections((prev) => {
       const next = { ...prev };
       if (next[gamePk] !== undefined) {
         delete next[gamePk];
       } else {
-        next[gamePk] = 2; // default 2 seats
+        next[gamePk] = Math.min(defaultSeats, maxSeats(gamePk));
       }
       return next;
     });
   };
 
   const setSeats = (gamePk: number, seats: number) => {
-    setSelections((prev) => ({ ...prev, [gamePk]: Math.max(1, Math.min(4, seats)) }));
+    setSelections((prev) => ({ ...prev, [gamePk]: Math.max(1, Math.min(maxSeats(gamePk), seats)) }));
   };
 
   const selectedCount = Object.keys(selections).length;
@@ -98,7 +111,7 @@ export default function RequestPanel({ games, existingRequests, onClose, onSubmi
       )}
 
       <p className="text-sm text-gray-500 mb-3">
-        Select upcoming home games and choose how many seats you'd like (1–4). Already-requested games are marked.
+        Select upcoming home games and choose how many seats you'd like (up to 4, fewer for some games). Already-requested games are marked.
       </p>
 
       <div className="max-h-[400px] overflow-y-auto space-y-1">
@@ -153,7 +166,7 @@ export default function RequestPanel({ games, existingRequests, onClose, onSubmi
                   >
                     <Plus className="w-3.5 h-3.5" />
                   </button>
-                  <span className="text-xs text-gray-500 ml-1">seats</span>
+                  <span className="text-xs text-gray-500 ml-1">seats (max {maxSeats(g.game_pk)})</span>
                 </div>
               )}
             </div>
@@ -21,9 +21,9 @@ import {
   Clock,
 } from 'lucide-react';
 import type { Game, Promotion, TicketSummary, TicketRequest, GameTicketDetail, GameTicketWithUser } from './types';
-import { fetchPromotions, fetchGameAllocation, createRequests } from './api';
+import { fetchPromotions, fetchGameAllocation, createRequests, promotionImageUrl } from './api';
+import useTeam from './useTeam';
 
-const GIANTS_TEAM_NAME = 'San Francisco Giants';
 
 const MONTHS = [
   'All',
@@ -66,10 +66,17 @@ function formatTime(game: Game): string {
   return d.toLocaleTimeString('en-US', {
     hour: 'numeric',
     minute: '2-digit',
+    timeZone: game.time_zone ?? undefined,
     timeZoneName: 'short',
   });
 }
 
+/** "Game 1 of 2" for either game of a double-header ('Y' one admission, 'S' split). */
+function doubleHeaderLabel(game: { double_header: string; game_number: number }): string | null {
+  if (game.double_header !== 'Y' && game.double_header !== 'S') return null;
+  return `Game ${game.game_number} of 2`;
+}
+
 function statusColor(status: string): string {
   switch (status) {
     case 'Final':
@@ -85,12 +92,11 @@ function statusColor(status: string): string {
   }
 }
 
-function scoreDisplay(game: Game): { text: string; colorClass: string } {
+function scoreDisplay(game: Game, isHome: boolean): { text: string; colorClass: string } {
   if (game.away_score === null && game.home_score === null)
     return { text: '—', colorClass: '' };
   const text = `${game.away_score ?? 0} - ${game.home_score ?? 0}`;
   if (game.status_abstract !== 'Final') return { text, colorClass: '' };
-  const isHome = game.home_team_name === GIANTS_TEAM_NAME;
   const giantsWon = isHome ? game.home_is_winner : game.away_is_winner;
   return { text, colorClass: giantsWon ? 'text-green-400' : 'text-red-400' };
 }
@@ -134,6 +140,7 @@ export default function ScheduleTable({
   const [loadingPromos, setLoadingPromos] = useState<number | null>(null);
   const [homeOnly, setHomeOnly] = useState(false);
   const [myGamesOnly, setMyGamesOnly] = useState(false);
+  const teamId = useTeam();
 
   const myGamePks = useMemo(
     () => new Set(myGames.map((t) => t.game_pk)),
@@ -183,20 +190,20 @@ export default function ScheduleTable({
     for (const g of games) {
       if (g.status_abstract !== 'Final') continue;
       played++;
-      const isHome = g.home_team_name === GIANTS_TEAM_NAME;
+      const isHome = g.home_team_id === teamId;
       const giantsWon = isHome ? g.home_is_winner : g.away_is_winner;
       if (giantsWon) wins++;
       else losses++;
     }
     return { wins, losses, played, remaining: games.length - played };
-  }, [games]);
+  }, [games, teamId]);
 
   const monthNum = MONTH_NUMBERS[selectedMonth];
   const filtered = games.filter((g) => {
     const monthMatch =
       monthNum === null ||
       parseInt(g.official_date.split('-')[1], 10) === monthNum;
-    const homeMatch = !homeOnly || g.home_team_name === GIANTS_TEAM_NAME;
+    const homeMatch = !homeOnly || g.home_team_id === teamId;
     const myGamesMatch = !myGamesOnly || myGamePks.has(g.game_pk);
     return monthMatch && homeMatch && myGamesMatch;
   });
@@ -209,11 +216,11 @@ export default function ScheduleTable({
         break;
       case 'opponent': {
         const oppA =
-          a.home_team_name === GIANTS_TEAM_NAME
+          a.home_team_id === teamId
             ? a.away_team_name
             : a.home_team_name;
         const oppB =
-          b.home_team_name === GIANTS_TEAM_NAME
+          b.home_team_id === teamId
             ? b.away_team_name
             : b.home_team_name;
         cmp = oppA.localeCompare(oppB);
@@ -428,7 +435,7 @@ export default function ScheduleTable({
           </thead>
           <tbody>
             {sorted.map((g) => {
-              const isHome = g.home_team_name === GIANTS_TEAM_NAME;
+              const isHome = g.home_team_id === teamId;
               const opponent = isHome ? g.away_team_name : g.home_team_name;
               const isExpanded = expandedGame === g.game_pk;
               const gamePromos = promoMap[g.game_pk] ?? [];
@@ -522,9 +529,22 @@ function ScheduleRow({
             </span>
           )}
         </td>
-        <td className="px-3 py-2 font-medium whitespace-nowrap">{opponent}</td>
-        <td className={`px-3 py-2 whitespace-nowrap tabular-nums ${scoreDisplay(game).colorClass}`}>
-          {scoreDisplay(game).text}
+        <td className="px-3 py-2 font-medium whitespace-nowrap">
+          {opponent}
+          {game.game_type !== 'R' && game.game_type_label && (
+            <span className="ml-2 text-xs font-normal text-orange-400">{game.game_type_label}</span>
+          )}
+          {doubleHeaderLabel(game) && (
+            <span
+              className="ml-2 text-xs font-normal text-gray-400"
+              title={game.double_header === 'Y' ? 'Double-header, one ticket for both games' : 'Split double-header'}
+            >
+              {doubleHeaderLabel(game)}
+            </span>
+          )}
+        </td>
+        <td className={`px-3 py-2 whitespace-nowrap tabular-nums ${scoreDisplay(game, isHome).colorClass}`}>
+          {scoreDisplay(game, isHome).text}
         </td>
         <td
           className={`px-3 py-2 whitespace-nowrap ${statusColor(game.status_detailed)}`}
@@ -768,6 +788,23 @@ function GameDetail({
           label="Day/Night"
           value={game.day_night === 'night' ? 'Night' : game.day_night === 'day' ? 'Day' : '—'}
         />
+        <InfoCard
+          label="Probable Pitchers"
+          value={
+            game.away_probable_pitcher || game.home_probable_pitcher
+              ? `${game.away_probable_pitcher ?? 'TBD'} vs ${game.home_probable_pitcher ?? 'TBD'}`
+              : '—'
+          }
+        />
+        <InfoCard
+          label="Weather"
+          value={
+            [game.weather_condition, game.weather_temp && `${game.weather_temp}°F`, game.weather_wind]
+              .filter(Boolean)
+              .join(' · ') || '—'
+          }
+        />
+        <InfoCard label="TV" value={game.tv_broadcasts ?? '—'} />
       </div>
 
       {/* Tickets — admin view */}
@@ -853,7 +890,7 @@ function GameDetail({
               >
                 {p.thumbnail_url && (
                   <img
-                    src={p.thumbnail_url}
+                    src={promotionImageUrl(p.offer_id, 'thumbnail')}
                     alt={p.name}
                     className="w-14 h-14 rounded object-cover flex-shrink-0"
                   />
@@ -1,7 +1,7 @@
 import { useState, useEffect, useMemo } from 'react';
 import { Plus, Trash2, Armchair, AlertCircle, Loader2, Pencil, Check, X } from 'lucide-react';
 import type { Seat } from './types';
-import { fetchSeats, addSeatBatch, deleteSeat, updateSeatGroupNotes } from './api';
+import { fetchSeats, addSeatBatch, deleteSeat, deleteSeatGroup, previewDeleteSeat, previewDeleteSeatGroup, updateSeatGroupNotes } from './api';
 
 interface SeatGroup {
   section: string;
@@ -99,7 +99,14 @@ export default function SeatAdmin() {
   const handleDeleteSeat = async (seatId: number) => {
     setDeletingIds((prev) => new Set(prev).add(seatId));
     try {
-      await deleteSeat(seatId);
+      const preview = await previewDeleteSeat(seatId);
+      if (
+        preview.confirm_token &&
+        !window.confirm(`This deletes the seat's ${preview.affected} game tickets, assigned ones included. Delete it?`)
+      ) {
+        return;
+      }
+      await deleteSeat(seatId, preview.confirm_token ?? undefined);
       setSeats((prev) => prev.filter((s) => s.id !== seatId));
     } catch (err: unknown) {
       setError(err instanceof Error ? err.message : 'Failed to delete seat');
@@ -149,9 +156,14 @@ export default function SeatAdmin() {
       return next;
     });
     try {
-      for (const id of ids) {
-        await deleteSeat(id);
+      const preview = await previewDeleteSeatGroup(group.section, group.row);
+      if (
+        preview.confirm_token &&
+        !window.confirm(`Delete all ${preview.affected} seats in Section ${group.section} Row ${group.row}?`)
+      ) {
+        return;
       }
+      await deleteSeatGroup(group.section, group.row, preview.confirm_token ?? undefined);
       setSeats((prev) => prev.filter((s) => !ids.includes(s.id)));
     } catch (err: unknown) {
       setError(err instanceof Error ? err.message : 'Failed to delete group');
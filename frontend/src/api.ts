@@ -1,4 +1,4 @@
-import type { Game, GameTag, GameTicketDetail, Promotion, Seat, TicketSummary, TicketRequest, AllocationSummaryRow, GameAllocationDetail, UserAllocationSection, MeResponse } from './types';
+import type { ApiToken, EntityKind, ForwardMethod, TicketForward, ExternalRef, Game, GamePreview, GameTag, GameType, GameTicketDetail, AvailabilityCalendar, Hydration, Promotion, Seat, SeatDeleteOutcome, SeatImport, SeatTicketOutcome, BulkPreview, TicketCoverage, TicketSummary, UsageReport, TicketSummaryFilter, TicketRequest, RequestComment, AllocationSummaryRow, AllocationBatch, BulkRequests, AllocationPolicy, AllocationSimulation, GameAllocationDetail, UserAllocationSection, MeResponse, AppConfig, LocalUser, AdminPendingRequest, MonthlyResponseTime, UnassignedGame, AuditEntry, FairnessRow, FairnessTimeline, MemberHistory, Transparency, RenewalReport, AttendanceGrid, PromotionInterest, PromotionInterestCount, TicketTransfer, NoteTemplate, NoteTarget, MemberLedger, AdminLedger, Envelope, MemberPoll, PollAnswer, PollSummary, PollResults, Season, PromotionFilter, PromotionListing, CostSplit, SplitRule, SeatLimitRule, GameSeatTags, Invoice, InvoiceDetail, Webhook, WebhookDelivery, WebhookEvent, ApiErrorBody, UserPreferences, PreferencesUpdate } from './types';
 
 // --- Auth-aware fetch ---
 
@@ -10,31 +10,202 @@ export function setTokenGetter(fn: GetTokenFn) {
   _getToken = fn;
 }
 
+// --- Local users (auth_mode = "none") ---
+
+const LOCAL_USER_KEY = 'gtm_local_user';
+
+/** The local user requests act as, remembered across reloads. */
+export function getLocalUser(): number | null {
+  const id = Number(localStorage.getItem(LOCAL_USER_KEY));
+  return id > 0 ? id : null;
+}
+
+export function setLocalUser(id: number | null) {
+  if (id === null) localStorage.removeItem(LOCAL_USER_KEY);
+  else localStorage.setItem(LOCAL_USER_KEY, String(id));
+}
+
+export async function fetchLocalUsers(): Promise<LocalUser[]> {
+  const res = await fetch('/api/auth/local-users');
+  if (!res.ok) throw new Error(`Failed to fetch local users: ${res.statusText}`);
+  return res.json();
+}
+
+// --- Config ---
+
+let _config: Promise<AppConfig> | null = null;
+
+/** The club's team, fetched once per page load. */
+export function fetchConfig(): Promise<AppConfig> {
+  _config ??= fetch('/api/config').then((res) => {
+    if (!res.ok) throw new Error(`Failed to fetch config: ${res.statusText}`);
+    return res.json();
+  });
+  _config.catch(() => { _config = null; });
+  return _config;
+}
+
+type WarningHandler = (warnings: string[]) => void;
+
+let _onWarnings: WarningHandler = (warnings) => {
+  for (const w of warnings) console.warn(w);
+};
+
+export function setWarningHandler(fn: WarningHandler) {
+  _onWarnings = fn;
+}
+
+/** Unwrap a POST/PATCH response envelope, passing any warnings to the handler. */
+async function unwrap<T>(res: Response): Promise<T> {
+  const body: Envelope<T> = await res.json();
+  if (body.warnings.length > 0) _onWarnings(body.warnings);
+  return body.data;
+}
+
+/** A failed API call, with the server's error `code` (e.g. "validation", "conflict"). */
+export class ApiError extends Error {
+  constructor(
+    message: string,
+    readonly status: number,
+    readonly code: string,
+    readonly details?: unknown,
+  ) {
+    super(message);
+    this.name = 'ApiError';
+  }
+}
+
+/** Read an error response body (`{ code, message, details? }`) into an ApiError. */
+async function apiError(res: Response): Promise<ApiError> {
+  const text = await res.text();
+  try {
+    const body: ApiErrorBody = JSON.parse(text);
+    return new ApiError(body.message, res.status, body.code, body.details);
+  } catch {
+    return new ApiError(text || res.statusText, res.status, 'unknown');
+  }
+}
+
 async function authFetch(url: string, init?: RequestInit): Promise<Response> {
   const token = await _getToken();
   const headers = new Headers(init?.headers);
   if (token) {
     headers.set('Authorization', `Bearer ${token}`);
   }
+  const localUser = getLocalUser();
+  if (localUser !== null) {
+    headers.set('X-GTM-User', String(localUser));
+  }
   return fetch(url, { ...init, headers });
 }
 
 // --- API functions ---
 
-export async function fetchGames(): Promise<Game[]> {
-  const res = await authFetch('/api/games');
+export interface GameFilter {
+  season?: string;
+  /** Comma-separated game type codes, e.g. `S` or `F,D,L,W`. */
+  game_type?: string;
+  month?: number;
+  from?: string;
+  to?: string;
+  opponent?: string;
+  home_only?: boolean;
+  status?: string;
+  /** Comma-separated days of the week, e.g. `fri,sat`. */
+  days?: string;
+  weekend?: boolean;
+  day_night?: 'day' | 'night';
+  has_promotions?: boolean;
+  limit?: number;
+  offset?: number;
+  /** One of the signed-in member's saved views; its filter replaces the fields above except paging. */
+  view?: number;
+}
+
+export interface GameSearch {
+  q?: string;
+  after?: string;
+  before?: string;
+  season?: string;
+  days?: string;
+  weekend?: boolean;
+  day_night?: 'day' | 'night';
+  promotions?: boolean;
+  home_only?: boolean;
+  limit?: number;
+}
+
+export interface SavedView {
+  id: number;
+  user_id: number;
+  name: string;
+  filter: GameFilter;
+  created_at: string;
+}
+
+/** Games matching `filter`; `include` adds promotion names and/or ticket counts to each. */
+export async function fetchGames(filter: GameFilter = {}, include: ('promotions' | 'tickets')[] = []): Promise<Game[]> {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(filter)) {
+    if (value !== undefined) params.set(key, String(value));
+  }
+  if (include.length > 0) params.set('include', include.join(','));
+  const qs = params.toString();
+  const res = await authFetch(`/api/games${qs ? `?${qs}` : ''}`);
   if (!res.ok) throw new Error(`Failed to fetch games: ${res.statusText}`);
   return res.json();
 }
 
+/** Games worth requesting: opponent substring `q`, inclusive `after`/`before` dates, days of the week, day/night and promotions. */
+export async function searchGames(search: GameSearch = {}): Promise<Game[]> {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(search)) {
+    if (value !== undefined && value !== '') params.set(key, String(value));
+  }
+  const qs = params.toString();
+  const res = await authFetch(`/api/games/search${qs ? `?${qs}` : ''}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
 export async function fetchPromotions(gamePk: number): Promise<Promotion[]> {
   const res = await authFetch(`/api/games/${gamePk}/promotions`);
   if (!res.ok) throw new Error(`Failed to fetch promotions: ${res.statusText}`);
   return res.json();
 }
 
-export async function fetchTicketSummary(): Promise<TicketSummary[]> {
-  const res = await authFetch('/api/tickets/summary');
+/** A promotion's image, served through the server's image cache. */
+export function promotionImageUrl(offerId: number, size: 'image' | 'thumbnail' = 'image'): string {
+  return `/api/promotions/${offerId}/image${size === 'thumbnail' ? '?size=thumbnail' : ''}`;
+}
+
+/** The game's stored preview, or null until one has been generated. */
+export async function fetchGamePreview(gamePk: number): Promise<GamePreview | null> {
+  const res = await authFetch(`/api/games/${gamePk}/preview`);
+  if (res.status === 404) return null;
+  if (!res.ok) throw new Error(`Failed to fetch game preview: ${res.statusText}`);
+  return res.json();
+}
+
+/** Promotions across the schedule; `type` matches the promotion type or name. */
+export async function searchPromotions(filter: PromotionFilter = {}): Promise<PromotionListing[]> {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(filter)) {
+    if (value !== undefined && value !== '') params.set(key, String(value));
+  }
+  const qs = params.toString();
+  const res = await authFetch(`/api/promotions${qs ? `?${qs}` : ''}`);
+  if (!res.ok) throw new Error(`Failed to search promotions: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchTicketSummary(filter: TicketSummaryFilter = {}): Promise<TicketSummary[]> {
+  const params = new URLSearchParams();
+  if (filter.future_only) params.set('future_only', 'true');
+  if (filter.season) params.set('season', filter.season);
+  if (filter.month !== undefined) params.set('month', String(filter.month));
+  const qs = params.toString();
+  const res = await authFetch(`/api/tickets/summary${qs ? `?${qs}` : ''}`);
   if (!res.ok) throw new Error(`Failed to fetch ticket summary: ${res.statusText}`);
   return res.json();
 }
@@ -52,17 +223,37 @@ export async function addSeatBatch(section: string, row: string, seatStart: numb
     body: JSON.stringify({ section, row, seat_start: seatStart, seat_end: seatEnd, notes: notes || null }),
   });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+/** Add many seats at once from CSV text or a JSON array; all or nothing. */
+export async function importSeats(body: string, format: 'csv' | 'json'): Promise<SeatImport> {
+  const res = await authFetch('/api/seats/import', {
+    method: 'POST',
+    headers: { 'Content-Type': format === 'csv' ? 'text/csv' : 'application/json' },
+    body,
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+export async function previewDeleteSeat(seatId: number): Promise<BulkPreview> {
+  const res = await authFetch(`/api/seats/${seatId}?dry_run=true`, { method: 'DELETE' });
+  if (!res.ok) {
+    throw await apiError(res);
   }
   return res.json();
 }
 
-export async function deleteSeat(seatId: number): Promise<void> {
-  const res = await authFetch(`/api/seats/${seatId}`, { method: 'DELETE' });
+export async function deleteSeat(seatId: number, confirmToken?: string): Promise<void> {
+  const query = confirmToken ? `?confirm_token=${encodeURIComponent(confirmToken)}` : '';
+  const res = await authFetch(`/api/seats/${seatId}${query}`, { method: 'DELETE' });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
 }
 
@@ -73,12 +264,47 @@ export async function updateSeatGroupNotes(section: string, row: string, notes:
     body: JSON.stringify({ section, row, notes }),
   });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+export async function previewDeleteSeatGroup(section: string, row: string): Promise<BulkPreview> {
+  const res = await authFetch('/api/seats/group', {
+    method: 'DELETE',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ section, row, dry_run: true }),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
   }
   return res.json();
 }
 
+export async function deleteSeatGroup(section: string, row: string, confirmToken?: string): Promise<SeatDeleteOutcome[]> {
+  const res = await authFetch('/api/seats/group', {
+    method: 'DELETE',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ section, row, confirm_token: confirmToken ?? null }),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return res.json();
+}
+
+export async function regenerateSeatGroupTickets(section: string, row: string): Promise<SeatTicketOutcome[]> {
+  const res = await authFetch('/api/seats/group/regenerate-tickets', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ section, row }),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
 export async function fetchSeats(): Promise<Seat[]> {
   const res = await authFetch('/api/seats');
   if (!res.ok) throw new Error(`Failed to fetch seats: ${res.statusText}`);
@@ -93,25 +319,66 @@ export async function fetchMe(): Promise<MeResponse> {
   return res.json();
 }
 
+/** Language for allocation and reminder notifications; null for English. */
+export async function setMyLocale(locale: string | null): Promise<void> {
+  const res = await authFetch('/api/my/locale', {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ locale }),
+  });
+  if (!res.ok) throw new Error(`Failed to set language: ${res.statusText}`);
+}
+
+export async function fetchMyPreferences(): Promise<UserPreferences> {
+  const res = await authFetch('/api/my/preferences');
+  if (!res.ok) throw new Error(`Failed to fetch preferences: ${res.statusText}`);
+  return res.json();
+}
+
+export async function updateMyPreferences(changes: PreferencesUpdate): Promise<UserPreferences> {
+  const res = await authFetch('/api/my/preferences', {
+    method: 'PATCH',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(changes),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
 // --- Ticket Requests ---
 
-export async function fetchMyRequests(): Promise<TicketRequest[]> {
-  const res = await authFetch('/api/my/requests');
+export async function fetchMyRequests(season?: string): Promise<TicketRequest[]> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/my/requests${qs}`);
   if (!res.ok) throw new Error(`Failed to fetch requests: ${res.statusText}`);
   return res.json();
 }
 
-export async function createRequests(requests: { game_pk: number; seats_requested: number; notes?: string }[]): Promise<TicketRequest[]> {
-  const res = await authFetch('/api/my/requests', {
+/** Request the same seats at every upcoming home game matching `filter`. */
+export async function createBulkRequests(filter: Omit<GameFilter, 'limit' | 'offset' | 'view' | 'home_only'>, seatsRequested: number, notes?: string): Promise<BulkRequests> {
+  const res = await authFetch('/api/my/requests/bulk', {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ filter, seats_requested: seatsRequested, notes }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+/** Retrying with the same `idempotencyKey` returns the first call's result
+ *  instead of submitting again. */
+export async function createRequests(requests: { game_pk: number; seats_requested: number; notes?: string }[], idempotencyKey: string = crypto.randomUUID()): Promise<TicketRequest[]> {
+  const res = await authFetch('/api/my/requests', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', 'Idempotency-Key': idempotencyKey },
     body: JSON.stringify({ requests }),
   });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
-  return res.json();
+  return unwrap(res);
 }
 
 export async function updateRequest(requestId: number, seatsRequested: number): Promise<void> {
@@ -121,44 +388,97 @@ export async function updateRequest(requestId: number, seatsRequested: number):
     body: JSON.stringify({ seats_requested: seatsRequested }),
   });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
+  await unwrap(res);
 }
 
 export async function withdrawRequest(requestId: number): Promise<void> {
   const res = await authFetch(`/api/my/requests/${requestId}`, { method: 'DELETE' });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
 }
 
+export async function fetchMyRequestComments(requestId: number): Promise<RequestComment[]> {
+  const res = await authFetch(`/api/my/requests/${requestId}/comments`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function addMyRequestComment(requestId: number, body: string): Promise<RequestComment> {
+  const res = await authFetch(`/api/my/requests/${requestId}/comments`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ body }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
 // --- My Games ---
 
-export async function fetchMyGames(): Promise<GameTicketDetail[]> {
-  const res = await authFetch('/api/my/games');
+export async function fetchMyGames(season?: string): Promise<GameTicketDetail[]> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/my/games${qs}`);
   if (!res.ok) throw new Error(`Failed to fetch my games: ${res.statusText}`);
   return res.json();
 }
 
+export async function fetchAvailabilityCalendar(month: number, season?: string): Promise<AvailabilityCalendar> {
+  const params = new URLSearchParams({ month: String(month) });
+  if (season) params.set('season', season);
+  const res = await authFetch(`/api/availability/calendar?${params}`);
+  if (!res.ok) throw new Error(`Failed to fetch availability calendar: ${res.statusText}`);
+  return res.json();
+}
+
 export async function releaseGameTickets(gamePk: number): Promise<{ released: number }> {
   const res = await authFetch(`/api/my/games/${gamePk}/release`, { method: 'POST' });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
-  return res.json();
+  return unwrap(res);
 }
 
-// --- Admin: Allocation ---
+export async function transferGameTickets(gamePk: number, toUserId: number, ticketIds?: number[]): Promise<{ transferred: number }> {
+  const res = await authFetch(`/api/my/games/${gamePk}/transfer`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ to_user_id: toUserId, ticket_ids: ticketIds }),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+// --- Seasons ---
 
-export async function fetchAllocationSummary(): Promise<AllocationSummaryRow[]> {
-  const res = await authFetch('/api/admin/allocation');
+export async function fetchSeasons(): Promise<Season[]> {
+  const res = await authFetch('/api/seasons');
+  if (!res.ok) throw new Error(`Failed to fetch seasons: ${res.statusText}`);
+  return res.json();
+}
+
+export async function closeSeason(season: string): Promise<{ season: string }> {
+  const res = await authFetch(`/api/admin/seasons/${encodeURIComponent(season)}/close`, { method: 'POST' });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || `Failed to fetch allocation: ${res.statusText}`);
+    throw await apiError(res);
   }
+  return unwrap(res);
+}
+
+// --- Admin: Allocation ---
+
+/** `asOf` (YYYY-MM-DD) shows allocations as they stood at the end of that day. */
+export async function fetchAllocationSummary(season?: string, asOf?: string): Promise<AllocationSummaryRow[]> {
+  const params = new URLSearchParams();
+  if (season) params.set('season', season);
+  if (asOf) params.set('as_of', asOf);
+  const qs = params.toString() ? `?${params}` : '';
+  const res = await authFetch(`/api/admin/allocation${qs}`);
+  if (!res.ok) throw await apiError(res);
   return res.json();
 }
 
@@ -168,27 +488,55 @@ export async function fetchGameAllocation(gamePk: number): Promise<GameAllocatio
   return res.json();
 }
 
-export async function allocateTickets(assignments: { game_ticket_id: number; user_id: number; request_id?: number }[]): Promise<{ assigned: number }> {
+/**
+ * Saving twice with the same `idempotencyKey` applies the assignments once.
+ * Fails with code `split_party` if a member's seats would span sections,
+ * unless `allowSplit`.
+ */
+export async function allocateTickets(assignments: { game_ticket_id: number; user_id: number; request_id?: number }[], idempotencyKey: string = crypto.randomUUID(), allowSplit = false): Promise<{ assigned: number; batch_id: number | null }> {
   const res = await authFetch('/api/admin/allocate', {
     method: 'POST',
-    headers: { 'Content-Type': 'application/json' },
-    body: JSON.stringify({ assignments }),
+    headers: { 'Content-Type': 'application/json', 'Idempotency-Key': idempotencyKey },
+    body: JSON.stringify({ assignments, allow_split: allowSplit }),
   });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
-  return res.json();
+  return unwrap(res);
 }
 
 export async function revokeTicket(gameTicketId: number): Promise<void> {
   const res = await authFetch(`/api/admin/allocate/${gameTicketId}`, { method: 'DELETE' });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
 }
 
+export async function simulateAllocation(
+  policy: Partial<AllocationPolicy> & { season?: string; seed?: number },
+): Promise<AllocationSimulation> {
+  const res = await authFetch('/api/admin/allocation/simulate', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(policy),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function fetchAllocationBatches(gamePk?: number): Promise<AllocationBatch[]> {
+  const qs = gamePk !== undefined ? `?game_pk=${gamePk}` : '';
+  const res = await authFetch(`/api/admin/allocation/batches${qs}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function fetchAllocationBatch(id: number): Promise<AllocationBatch> {
+  const res = await authFetch(`/api/admin/allocation/batches/${id}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
 export async function fetchAllocationByUsers(): Promise<UserAllocationSection[]> {
   const res = await authFetch('/api/admin/allocation/by-users');
   if (!res.ok) throw new Error(`Failed to fetch allocation by users: ${res.statusText}`);
@@ -201,25 +549,672 @@ export async function fetchAllocationByUser(userId: number): Promise<GameTicketD
   return res.json();
 }
 
-export async function fetchAdminRequests(): Promise<TicketRequest[]> {
-  const res = await authFetch('/api/admin/requests');
+export async function fetchGoogleCalendarStatus(): Promise<{ enabled: boolean; connected: boolean }> {
+  const res = await authFetch('/api/my/google');
+  if (!res.ok) throw new Error(`Failed to fetch Google Calendar status: ${res.statusText}`);
+  return res.json();
+}
+
+/** Returns the Google consent URL; the caller should navigate to it. */
+export async function connectGoogleCalendar(): Promise<string> {
+  const res = await authFetch('/api/my/google/connect', { method: 'POST' });
+  if (!res.ok) throw new Error(`Failed to start Google Calendar link: ${res.statusText}`);
+  const body = await unwrap<{ url: string }>(res);
+  return body.url;
+}
+
+export async function disconnectGoogleCalendar(): Promise<void> {
+  const res = await authFetch('/api/my/google', { method: 'DELETE' });
+  if (!res.ok) throw new Error(`Failed to unlink Google Calendar: ${res.statusText}`);
+}
+
+export async function fetchAdminRequests(season?: string): Promise<AdminPendingRequest[]> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/admin/requests${qs}`);
   if (!res.ok) throw new Error(`Failed to fetch admin requests: ${res.statusText}`);
   return res.json();
 }
 
-export async function scrapeSchedule(season?: number): Promise<{ games: number; promotions: number; tickets: number }> {
-  const res = await authFetch('/api/admin/scrape-schedule', {
+export async function fetchRequestComments(requestId: number): Promise<RequestComment[]> {
+  const res = await authFetch(`/api/admin/requests/${requestId}/comments`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function addRequestComment(requestId: number, body: string): Promise<RequestComment> {
+  const res = await authFetch(`/api/admin/requests/${requestId}/comments`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
-    body: JSON.stringify({ season: season ?? null }),
+    body: JSON.stringify({ body }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function fetchPromotionInterestCounts(): Promise<PromotionInterestCount[]> {
+  const res = await authFetch('/api/admin/promotions/interest');
+  if (!res.ok) throw new Error(`Failed to fetch promotion interest: ${res.statusText}`);
+  return res.json();
+}
+
+export async function addPromotionInterest(offerId: number, gamePk?: number): Promise<void> {
+  const qs = gamePk !== undefined ? `?game_pk=${gamePk}` : '';
+  const res = await authFetch(`/api/promotions/${offerId}/interest${qs}`, { method: 'POST' });
+  if (!res.ok) throw new Error(`Failed to flag promotion: ${res.statusText}`);
+  await unwrap(res);
+}
+
+export async function removePromotionInterest(offerId: number, gamePk?: number): Promise<void> {
+  const qs = gamePk !== undefined ? `?game_pk=${gamePk}` : '';
+  const res = await authFetch(`/api/promotions/${offerId}/interest${qs}`, { method: 'DELETE' });
+  if (!res.ok) throw new Error(`Failed to unflag promotion: ${res.statusText}`);
+}
+
+export async function fetchMyBlackouts(): Promise<string[]> {
+  const res = await authFetch('/api/my/blackouts');
+  if (!res.ok) throw new Error(`Failed to fetch blackout dates: ${res.statusText}`);
+  return res.json();
+}
+
+export async function addBlackout(date: string): Promise<void> {
+  const res = await authFetch(`/api/my/blackouts/${date}`, { method: 'PUT' });
+  if (!res.ok) throw new Error(`Failed to add blackout date: ${res.statusText}`);
+}
+
+export async function removeBlackout(date: string): Promise<void> {
+  const res = await authFetch(`/api/my/blackouts/${date}`, { method: 'DELETE' });
+  if (!res.ok) throw new Error(`Failed to remove blackout date: ${res.statusText}`);
+}
+
+export async function fetchSavedViews(): Promise<SavedView[]> {
+  const res = await authFetch('/api/my/saved-views');
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+/** Save `filter` as the view `name`, replacing any view already called that. */
+export async function saveView(name: string, filter: GameFilter): Promise<SavedView> {
+  const res = await authFetch('/api/my/saved-views', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ name, filter }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function deleteSavedView(id: number): Promise<void> {
+  const res = await authFetch(`/api/my/saved-views/${id}`, { method: 'DELETE' });
+  if (!res.ok) throw await apiError(res);
+}
+
+// --- Availability polls ---
+
+export async function fetchMyPolls(): Promise<MemberPoll[]> {
+  const res = await authFetch('/api/my/polls');
+  if (!res.ok) throw new Error(`Failed to fetch polls: ${res.statusText}`);
+  return res.json();
+}
+
+export async function answerPoll(pollId: number, answer: PollAnswer, seats: number): Promise<void> {
+  const res = await authFetch(`/api/polls/${pollId}/response`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ answer, seats }),
   });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
+  await unwrap(res);
+}
+
+export async function fetchAdminPolls(): Promise<PollSummary[]> {
+  const res = await authFetch('/api/admin/polls');
+  if (!res.ok) throw new Error(`Failed to fetch polls: ${res.statusText}`);
   return res.json();
 }
 
+export async function createPoll(gamePk: number, question?: string): Promise<PollSummary> {
+  const res = await authFetch('/api/admin/polls', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ game_pk: gamePk, question: question || null }),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+export async function fetchPollResults(pollId: number): Promise<PollResults> {
+  const res = await authFetch(`/api/admin/polls/${pollId}`);
+  if (!res.ok) throw new Error(`Failed to fetch poll results: ${res.statusText}`);
+  return res.json();
+}
+
+/** Close a poll; each "yes" answer becomes a pending request. */
+export async function closePoll(pollId: number): Promise<{ requests_created: number }> {
+  const res = await authFetch(`/api/admin/polls/${pollId}/close`, { method: 'POST' });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+export async function fetchMyPromotionInterests(): Promise<PromotionInterest[]> {
+  const res = await authFetch('/api/my/promotion-interests');
+  if (!res.ok) throw new Error(`Failed to fetch promotion interests: ${res.statusText}`);
+  return res.json();
+}
+
+/** `asOf` (YYYY-MM-DD) shows the report as it stood at the end of that day. */
+export async function fetchFairnessReport(season?: string, asOf?: string): Promise<FairnessRow[]> {
+  const params = new URLSearchParams();
+  if (season) params.set('season', season);
+  if (asOf) params.set('as_of', asOf);
+  const qs = params.toString() ? `?${params}` : '';
+  const res = await authFetch(`/api/admin/reports/fairness${qs}`);
+  if (!res.ok) throw new Error(`Failed to fetch fairness report: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchMyHistory(): Promise<MemberHistory> {
+  const res = await authFetch('/api/my/history');
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function fetchTransparency(season?: string): Promise<Transparency> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/transparency${qs}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function fetchUserHistory(userId: number): Promise<MemberHistory> {
+  const res = await authFetch(`/api/admin/users/${userId}/history`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function fetchFairnessTimeline(season?: string): Promise<FairnessTimeline> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/admin/reports/fairness-timeline${qs}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function fetchRenewalReport(season?: string): Promise<RenewalReport> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/admin/reports/renewal${qs}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function fetchMyTokens(): Promise<ApiToken[]> {
+  const res = await authFetch('/api/my/tokens');
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+/** Mint a token; the returned `token` value can't be fetched again. */
+export async function mintToken(name: string, admin = false, expiresInDays?: number): Promise<ApiToken & { token: string }> {
+  const res = await authFetch('/api/my/tokens', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ name, admin, expires_in_days: expiresInDays }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function revokeToken(id: number): Promise<void> {
+  const res = await authFetch(`/api/my/tokens/${id}`, { method: 'DELETE' });
+  if (!res.ok) throw await apiError(res);
+}
+
+export async function fetchExternalRefs(filter: { entity_type?: EntityKind; entity_id?: number; system?: string; external_id?: string } = {}): Promise<ExternalRef[]> {
+  const params = new URLSearchParams();
+  for (const [key, value] of Object.entries(filter)) {
+    if (value !== undefined) params.set(key, String(value));
+  }
+  const qs = params.toString();
+  const res = await authFetch(`/api/admin/external-refs${qs ? `?${qs}` : ''}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function createExternalRef(entityType: EntityKind, entityId: number, system: string, externalId: string): Promise<ExternalRef> {
+  const res = await authFetch('/api/admin/external-refs', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ entity_type: entityType, entity_id: entityId, system, external_id: externalId }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function updateExternalRef(id: number, externalId: string): Promise<ExternalRef> {
+  const res = await authFetch(`/api/admin/external-refs/${id}`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ external_id: externalId }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function deleteExternalRef(id: number): Promise<void> {
+  const res = await authFetch(`/api/admin/external-refs/${id}`, { method: 'DELETE' });
+  if (!res.ok) throw await apiError(res);
+}
+
+export async function fetchWebhooks(): Promise<Webhook[]> {
+  const res = await authFetch('/api/admin/webhooks');
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function createWebhook(url: string, events: WebhookEvent[]): Promise<Webhook & { secret: string }> {
+  const res = await authFetch('/api/admin/webhooks', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ url, events }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function deleteWebhook(id: number): Promise<void> {
+  const res = await authFetch(`/api/admin/webhooks/${id}`, { method: 'DELETE' });
+  if (!res.ok) throw await apiError(res);
+}
+
+export async function fetchWebhookDeliveries(id: number): Promise<WebhookDelivery[]> {
+  const res = await authFetch(`/api/admin/webhooks/${id}/deliveries`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function fetchUsageReport(from?: string, to?: string): Promise<UsageReport> {
+  const params = new URLSearchParams();
+  if (from) params.set('from', from);
+  if (to) params.set('to', to);
+  const qs = params.toString();
+  const res = await authFetch(`/api/admin/usage${qs ? `?${qs}` : ''}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function fetchTicketCoverage(): Promise<TicketCoverage> {
+  const res = await authFetch('/api/admin/tickets/coverage');
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+/** The renewal report as a CSV blob. */
+export async function downloadRenewalReport(season?: string): Promise<Blob> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/admin/reports/renewal.csv${qs}`);
+  if (!res.ok) throw await apiError(res);
+  return res.blob();
+}
+
+/** ZIP of one PDF game packet per member assigned seats for the game. */
+export async function downloadGamePackets(gamePk: number): Promise<Blob> {
+  const res = await authFetch(`/api/admin/games/${gamePk}/packets`);
+  if (!res.ok) throw await apiError(res);
+  return res.blob();
+}
+
+/** Email each assigned member their game packet. */
+export async function sendGamePackets(gamePk: number): Promise<{ queued: number }> {
+  const res = await authFetch(`/api/admin/games/${gamePk}/packets`, { method: 'POST' });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function fetchMyLedger(season?: string): Promise<MemberLedger> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/my/ledger${qs}`);
+  if (!res.ok) throw new Error(`Failed to fetch ledger: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchAdminLedger(season?: string): Promise<AdminLedger> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/admin/ledger${qs}`);
+  if (!res.ok) throw new Error(`Failed to fetch ledger: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchMyCostSplit(season?: string): Promise<CostSplit> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/my/cost-split${qs}`);
+  if (!res.ok) throw new Error(`Failed to fetch cost split: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchAdminCostSplit(season?: string): Promise<CostSplit> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/admin/cost-split${qs}`);
+  if (!res.ok) throw new Error(`Failed to fetch cost split: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchSplitRules(): Promise<SplitRule[]> {
+  const res = await authFetch('/api/admin/split-rules');
+  if (!res.ok) throw new Error(`Failed to fetch split rules: ${res.statusText}`);
+  return res.json();
+}
+
+/** Replace one seat's split (or the plan-wide one when seatId is null). Percents must total 100. */
+export async function setSplitRules(seatId: number | null, shares: { user_id: number; percent: number }[]): Promise<SplitRule[]> {
+  const res = await authFetch('/api/admin/split-rules', {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ seat_id: seatId, shares }),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+// --- Seat limits ---
+
+export async function fetchSeatLimits(): Promise<SeatLimitRule[]> {
+  const res = await authFetch('/api/admin/seat-limits');
+  if (!res.ok) throw new Error(`Failed to fetch seat limits: ${res.statusText}`);
+  return res.json();
+}
+
+/** Replace every seat limit rule. */
+export async function setSeatLimits(rules: Omit<SeatLimitRule, 'id'>[]): Promise<SeatLimitRule[]> {
+  const res = await authFetch('/api/admin/seat-limits', {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(rules),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+/** Override the rules for one game; null goes back to them. */
+export async function setGameSeatLimit(gamePk: number, maxSeats: number | null): Promise<void> {
+  const res = await authFetch(`/api/admin/games/${gamePk}/seat-limit`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ max_seats: maxSeats }),
+  });
+  if (!res.ok) throw await apiError(res);
+}
+
+export async function fetchGameSeatTags(gamePk: number): Promise<GameSeatTags> {
+  const res = await authFetch(`/api/admin/games/${gamePk}/tags`);
+  if (!res.ok) throw new Error(`Failed to fetch game tags: ${res.statusText}`);
+  return res.json();
+}
+
+export async function setGameSeatTags(gamePk: number, tags: string[]): Promise<GameSeatTags> {
+  const res = await authFetch(`/api/admin/games/${gamePk}/tags`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ tags }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function fetchForwarding(gamePk: number): Promise<TicketForward[]> {
+  const res = await authFetch(`/api/admin/games/${gamePk}/forwarding`);
+  if (!res.ok) throw new Error(`Failed to fetch forwarding checklist: ${res.statusText}`);
+  return res.json();
+}
+
+/** Record tickets as forwarded: one ticket, or every one still to forward at a game. */
+export async function recordForwarded(
+  target: { ticketId: number } | { gamePk: number },
+  method: ForwardMethod,
+  externalTicketId?: string,
+): Promise<void> {
+  const url = 'ticketId' in target
+    ? `/api/admin/tickets/${target.ticketId}/forwarding`
+    : `/api/admin/games/${target.gamePk}/forwarding`;
+  const res = await authFetch(url, {
+    method: 'ticketId' in target ? 'PUT' : 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ method, external_ticket_id: externalTicketId || null }),
+  });
+  if (!res.ok) throw await apiError(res);
+}
+
+export async function clearForwarded(ticketId: number): Promise<void> {
+  const res = await authFetch(`/api/admin/tickets/${ticketId}/forwarding`, { method: 'DELETE' });
+  if (!res.ok) throw await apiError(res);
+}
+
+export async function fetchMyInvoices(): Promise<InvoiceDetail[]> {
+  const res = await authFetch('/api/my/invoices');
+  if (!res.ok) throw new Error(`Failed to fetch invoices: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchAdminInvoices(period?: string): Promise<Invoice[]> {
+  const qs = period ? `?period=${encodeURIComponent(period)}` : '';
+  const res = await authFetch(`/api/admin/invoices${qs}`);
+  if (!res.ok) throw new Error(`Failed to fetch invoices: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchAdminInvoice(id: number): Promise<InvoiceDetail> {
+  const res = await authFetch(`/api/admin/invoices/${id}`);
+  if (!res.ok) throw new Error(`Failed to fetch invoice: ${res.statusText}`);
+  return res.json();
+}
+
+/** Invoice every member with a share in `period` (YYYY-MM) who isn't invoiced yet. */
+export async function generateInvoices(period: string): Promise<{ period: string; created: number; existing: number }> {
+  const res = await authFetch('/api/admin/invoices', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ period }),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+export async function deleteInvoice(id: number): Promise<void> {
+  const res = await authFetch(`/api/admin/invoices/${id}`, { method: 'DELETE' });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+}
+
+/** Record a payment in cents; without line_ids it pays the oldest unpaid lines first. */
+export async function recordInvoicePayment(
+  id: number,
+  payment: { amount: number; method?: string; note?: string; line_ids?: number[] },
+): Promise<InvoiceDetail> {
+  const res = await authFetch(`/api/admin/invoices/${id}/payments`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(payment),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
+/** Start paying one of your invoices online; resolves to the checkout URL to redirect to. */
+export async function payInvoice(id: number): Promise<string> {
+  const res = await authFetch(`/api/my/invoices/${id}/pay`, { method: 'POST' });
+  if (!res.ok) throw await apiError(res);
+  const body: { url: string } = await unwrap(res);
+  return body.url;
+}
+
+/** An online checkout link for any invoice, e.g. to send to the member. */
+export async function createInvoicePaymentLink(id: number): Promise<string> {
+  const res = await authFetch(`/api/admin/invoices/${id}/payment-link`, { method: 'POST' });
+  if (!res.ok) throw await apiError(res);
+  const body: { url: string } = await unwrap(res);
+  return body.url;
+}
+
+/** Season export as a CSV blob: one line per ticket, or the allocation grid. */
+export async function downloadSeasonExport(kind: 'tickets' | 'allocation', season?: string): Promise<Blob> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const path = kind === 'tickets' ? '/api/admin/tickets/export.csv' : '/api/admin/allocation/export.csv';
+  const res = await authFetch(`${path}${qs}`);
+  if (!res.ok) throw new Error(`Failed to export ${kind}: ${res.statusText}`);
+  return res.blob();
+}
+
+export async function fetchAttendanceGrid(season?: string): Promise<AttendanceGrid> {
+  const qs = season ? `?season=${encodeURIComponent(season)}` : '';
+  const res = await authFetch(`/api/admin/export/attendance-grid${qs}`);
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+/** The attendance grid as a CSV blob: games down, members across. */
+export async function downloadAttendanceGrid(season?: string): Promise<Blob> {
+  const params = new URLSearchParams({ format: 'csv' });
+  if (season) params.set('season', season);
+  const res = await authFetch(`/api/admin/export/attendance-grid?${params}`);
+  if (!res.ok) throw await apiError(res);
+  return res.blob();
+}
+
+export async function setSeatGroupFaceValue(section: string, row: string, faceValue: number | null): Promise<void> {
+  const res = await authFetch('/api/admin/seats/group/face-value', {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ section, row, face_value: faceValue }),
+  });
+  if (!res.ok) throw new Error(`Failed to set face value: ${res.statusText}`);
+}
+
+export async function setTicketCost(ticketId: number, cost: number | null): Promise<void> {
+  const res = await authFetch(`/api/admin/tickets/${ticketId}/cost`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ cost }),
+  });
+  if (!res.ok) throw new Error(`Failed to set ticket cost: ${res.statusText}`);
+}
+
+export async function setTicketResale(ticketId: number, amount: number | null): Promise<void> {
+  const res = await authFetch(`/api/admin/tickets/${ticketId}/resale`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ amount }),
+  });
+  if (!res.ok) throw await apiError(res);
+}
+
+// --- Note templates ---
+
+export async function fetchNoteTemplates(): Promise<NoteTemplate[]> {
+  const res = await authFetch('/api/admin/note-templates');
+  if (!res.ok) throw new Error(`Failed to fetch note templates: ${res.statusText}`);
+  return res.json();
+}
+
+export async function createNoteTemplate(name: string, body: string): Promise<NoteTemplate> {
+  const res = await authFetch('/api/admin/note-templates', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ name, body }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function updateNoteTemplate(id: number, name: string, body: string): Promise<NoteTemplate> {
+  const res = await authFetch(`/api/admin/note-templates/${id}`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ name, body }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function deleteNoteTemplate(id: number): Promise<void> {
+  const res = await authFetch(`/api/admin/note-templates/${id}`, { method: 'DELETE' });
+  if (!res.ok) throw await apiError(res);
+}
+
+export async function previewApplyNoteTemplate(id: number, target: NoteTarget): Promise<BulkPreview> {
+  const res = await authFetch(`/api/admin/note-templates/${id}/apply`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ ...target, dry_run: true }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return res.json();
+}
+
+export async function applyNoteTemplate(id: number, target: NoteTarget, confirmToken?: string): Promise<{ applied: number }> {
+  const res = await authFetch(`/api/admin/note-templates/${id}/apply`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ ...target, confirm_token: confirmToken ?? null }),
+  });
+  if (!res.ok) throw await apiError(res);
+  return unwrap(res);
+}
+
+export async function fetchTransferHistory(gamePk?: number, season?: string): Promise<TicketTransfer[]> {
+  const params = new URLSearchParams();
+  if (gamePk !== undefined) params.set('game_pk', String(gamePk));
+  if (season) params.set('season', season);
+  const qs = params.toString();
+  const res = await authFetch(`/api/admin/transfers${qs ? `?${qs}` : ''}`);
+  if (!res.ok) throw new Error(`Failed to fetch transfer history: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchAuditLog(filter: { game_pk?: number; user_id?: number } = {}): Promise<AuditEntry[]> {
+  const params = new URLSearchParams();
+  if (filter.game_pk !== undefined) params.set('game_pk', String(filter.game_pk));
+  if (filter.user_id !== undefined) params.set('user_id', String(filter.user_id));
+  const qs = params.toString();
+  const res = await authFetch(`/api/admin/audit${qs ? `?${qs}` : ''}`);
+  if (!res.ok) throw new Error(`Failed to fetch audit log: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchUnassignedAlerts(days?: number): Promise<UnassignedGame[]> {
+  const qs = days !== undefined ? `?days=${days}` : '';
+  const res = await authFetch(`/api/admin/alerts/unassigned${qs}`);
+  if (!res.ok) throw new Error(`Failed to fetch unassigned alerts: ${res.statusText}`);
+  return res.json();
+}
+
+export async function fetchResponseTimeReport(): Promise<MonthlyResponseTime[]> {
+  const res = await authFetch('/api/admin/reports/response-times');
+  if (!res.ok) throw new Error(`Failed to fetch response times: ${res.statusText}`);
+  return res.json();
+}
+
+export async function scrapeSchedule(season?: number, gameTypes?: GameType[], hydrate?: Hydration[]): Promise<{ games: number; promotions: number; tickets: number; changes: string[]; drift: string[] }> {
+  const res = await authFetch('/api/admin/scrape-schedule', {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ season: season ?? null, game_types: gameTypes ?? null, hydrate: hydrate ?? null }),
+  });
+  if (!res.ok) {
+    throw await apiError(res);
+  }
+  return unwrap(res);
+}
+
 // --- Game Tags ---
 
 export async function fetchMyGameTags(): Promise<GameTag[]> {
@@ -235,7 +1230,6 @@ export async function setGameTag(gamePk: number, shortlist: boolean, cantGo: boo
     body: JSON.stringify({ shortlist, cant_go: cantGo }),
   });
   if (!res.ok) {
-    const text = await res.text();
-    throw new Error(text || res.statusText);
+    throw await apiError(res);
   }
 }
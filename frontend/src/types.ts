@@ -1,3 +1,9 @@
+/** MLB gameType code: R regular, S spring, P/F/D/L/W postseason rounds. */
+export type GameType = 'R' | 'S' | 'P' | 'F' | 'D' | 'L' | 'W';
+
+/** Optional schedule sections a scrape can fetch. */
+export type Hydration = 'promotions' | 'tickets' | 'venue' | 'weather' | 'probablePitcher' | 'broadcasts';
+
 export interface Game {
   game_pk: number;
   game_guid: string | null;
@@ -27,6 +33,100 @@ export interface Game {
   game_number: number;
   scheduled_innings: number;
   is_tie: boolean;
+  current_inning: number | null;
+  inning_state: string | null;
+  /** Probable starters, once announced. */
+  away_probable_pitcher: string | null;
+  home_probable_pitcher: string | null;
+  /** Forecast close to game day; temp is °F. */
+  weather_condition: string | null;
+  weather_temp: string | null;
+  weather_wind: string | null;
+  /** TV networks carrying the game, comma-separated. */
+  tv_broadcasts: string | null;
+  /** When a postponed game is made up, and its makeup game once linked. */
+  reschedule_date: string | null;
+  reschedule_official_date: string | null;
+  rescheduled_to: number | null;
+  /** Most seats one request may ask for; set by the games API. */
+  max_seats?: number | null;
+  /** `game_type` spelled out, e.g. "Wild Card"; set by the games API. */
+  game_type_label?: string | null;
+  /** First pitch with its offset in `time_zone` (null while TBD); set by the games API. */
+  first_pitch_local?: string | null;
+  /** IANA time zone the server shows game times in; set by the games API. */
+  time_zone?: string | null;
+  /** Promotion names in display order, with `include: ['promotions']`. */
+  promotions?: string[];
+  /** Ticket counts, with `include: ['tickets']`. */
+  tickets?: { total: number; available: number };
+  external_refs?: ExternalRef[];
+}
+
+/** A member's API token for scripts; the value is only shown when minted. */
+export interface ApiToken {
+  id: number;
+  user_id: number;
+  name: string;
+  prefix: string;
+  scope: 'member' | 'admin';
+  created_at: string;
+  last_used_at: string | null;
+  revoked_at: string | null;
+  /** The token stops working after this. */
+  expires_at: string | null;
+}
+
+/** A member's own settings; notification kinds map to on/off. */
+export interface UserPreferences {
+  default_seats: number | null;
+  contact_email: string | null;
+  /** A new contact address waiting for the member to follow the link mailed to it. */
+  pending_contact_email: string | null;
+  notifications: Record<string, boolean>;
+}
+
+/** Changes for `PATCH /my/preferences`; leave a field out to keep it, null to clear it. */
+export interface PreferencesUpdate {
+  default_seats?: number | null;
+  contact_email?: string | null;
+  notifications?: Record<string, boolean>;
+}
+
+export type EntityKind = 'game' | 'ticket' | 'user';
+
+/** A GTM record's ID in another system (e.g. a Notion page). */
+export interface ExternalRef {
+  id: number;
+  entity_type: EntityKind;
+  entity_id: number;
+  system: string;
+  external_id: string;
+  created_at: string;
+}
+
+export type WebhookEvent = 'ticket.assigned' | 'request.created' | 'schedule.updated';
+
+/** An outbound webhook; its signing secret is only shown when registered. */
+export interface Webhook {
+  id: number;
+  url: string;
+  events: WebhookEvent[];
+  created_by: number | null;
+  created_at: string;
+}
+
+export interface WebhookDelivery {
+  id: number;
+  webhook_id: number;
+  event: WebhookEvent;
+  payload: string;
+  status: 'pending' | 'delivered' | 'failed';
+  attempts: number;
+  response_status: number | null;
+  last_error: string | null;
+  created_at: string;
+  delivered_at: string | null;
 }
 
 export interface Seat {
@@ -35,14 +135,44 @@ export interface Seat {
   row: string;
   seat: string;
   notes: string | null;
+  face_value: number | null;
+}
+
+export interface SeatDeleteOutcome extends Seat {
+  tickets_removed: number;
+  tickets_kept: number;
+}
+
+export interface BulkPreview {
+  operation: string;
+  affected: number;
+  threshold: number;
+  confirm_token: string | null;
+}
+
+export interface SeatTicketOutcome extends Seat {
+  tickets_generated: number;
 }
 
 export interface TicketSummary {
   game_pk: number;
+  season: string;
+  official_date: string;
+  game_date: string;
+  opponent: string;
+  status_abstract: string;
+  double_header: string;
+  game_number: number;
   total: number;
   available: number;
 }
 
+export interface TicketSummaryFilter {
+  future_only?: boolean;
+  season?: string;
+  month?: number;
+}
+
 export interface GameTicketDetail {
   id: number;
   game_pk: number;
@@ -53,6 +183,7 @@ export interface GameTicketDetail {
   status: string;
   notes: string | null;
   assigned_to: number | null;
+  external_refs?: ExternalRef[];
 }
 
 export interface TicketRequest {
@@ -62,12 +193,470 @@ export interface TicketRequest {
   seats_requested: number;
   status: string;
   notes: string | null;
+  flag_reason: string | null;
+  latest_comment?: RequestComment | null;
+}
+
+export interface RequestComment {
+  id: number;
+  request_id: number;
+  user_id: number;
+  author_name: string;
+  from_admin: number;
+  body: string;
+  created_at: string;
+}
+
+export interface AdminPendingRequest extends TicketRequest {
+  created_at: string;
+  pending_hours: number;
+  respond_by: string | null;
+  overdue: boolean;
+}
+
+export interface FairnessRow {
+  user_id: number;
+  user_name: string;
+  seats_assigned: number;
+  weather_releases: number;
+}
+
+/** One season of a member's record, counted in games. */
+export interface SeasonHistory {
+  season: string;
+  requested: number;
+  allocated: number;
+  attended: number;
+  missed: number;
+  released_late: number;
+}
+
+export type ForwardMethod = 'ballpark_app' | 'email' | 'print' | 'other';
+
+/** An assigned ticket on a game's forwarding checklist. */
+export interface TicketForward {
+  ticket_id: number;
+  game_pk: number;
+  section: string;
+  row: string;
+  seat: string;
+  assigned_to: number;
+  user_name: string;
+  external_ticket_id: string | null;
+  forwarded_to: number | null;
+  forwarded_on: string | null;
+  forward_method: ForwardMethod | null;
+  forwarded_by: number | null;
+  /** Sent to the member holding it now. */
+  forwarded: boolean;
+}
+
+export interface MemberHistory {
+  user_id: number;
+  user_name: string;
+  seasons: SeasonHistory[];
+}
+
+/** A member's season on the transparency page; others are "Member N" unless names are shown. */
+export interface TransparencyRow {
+  user_id: number | null;
+  name: string;
+  you: boolean;
+  seats_received: number;
+  games_received: number;
+  premium_games: number;
+  requests: number;
+  requests_denied: number;
+}
+
+export interface Transparency {
+  season: string;
+  named: boolean;
+  members: TransparencyRow[];
+}
+
+export interface FairnessSeries {
+  user_id: number;
+  user_name: string;
+  cumulative: number[];
+}
+
+/** `members[i].cumulative[w]` is member i's seats through the week starting `weeks[w]`. */
+export interface FairnessTimeline {
+  season: string;
+  weeks: string[];
+  members: FairnessSeries[];
+}
+
+export interface RenewalSeat {
+  seat_id: number;
+  section: string;
+  row: string;
+  seat: string;
+  face_value: number | null;
+  games: number;
+  used: number;
+  resold: number;
+  unused: number;
+  cost: number;
+  unused_loss: number;
+  resale_total: number;
+  resold_cost: number;
+  utilization_pct: number;
+}
+
+export interface RenewalMember {
+  user_id: number;
+  user_name: string;
+  games: number;
+  seats: number;
+  face_value: number;
+  spend: number;
+}
+
+export interface AttendanceGrid {
+  season: string;
+  members: { user_id: number; name: string }[];
+  games: {
+    game_pk: number;
+    official_date: string;
+    opponent: string;
+    /** Seats per member, in `members` order. */
+    seats: number[];
+    unassigned: number;
+  }[];
+}
+
+export interface RenewalReport {
+  season: string;
+  as_of: string;
+  seats: RenewalSeat[];
+  members: RenewalMember[];
+  total_cost: number;
+  unused_loss: number;
+  resold: number;
+  resale_total: number;
+  resale_recovery_pct: number | null;
+}
+
+/** API requests between two days, from `GET /api/admin/usage`. */
+export interface UsageReport {
+  from: string;
+  to: string;
+  days: { day: string; requests: number; users: number }[];
+  users: { user_id: number; user_name: string; requests: number; active_days: number; last_seen: string }[];
+  endpoints: { endpoint: string; requests: number; users: number }[];
+}
+
+/** Ticketed home games with no tickets generated. */
+export interface TicketCoverage {
+  active_seats: number;
+  home_games: number;
+  uncovered: UncoveredGame[];
+}
+
+export interface UncoveredGame {
+  game_pk: number;
+  official_date: string;
+  game_type: GameType;
+  opponent: string;
+  status_detailed: string;
+}
+
+export interface PromotionInterest {
+  offer_id: number;
+  game_pk: number;
+  name: string;
+  official_date: string;
+  away_team_name: string;
+  has_request: boolean;
+  suggest_request: boolean;
+}
+
+export interface PromotionInterestCount {
+  offer_id: number;
+  game_pk: number;
+  name: string;
+  official_date: string;
+  away_team_name: string;
+  interested: number;
+  requested: number;
+}
+
+export interface AuditEntry {
+  id: number;
+  created_at: string;
+  actor_id: number | null;
+  actor_name: string | null;
+  action: string;
+  game_pk: number | null;
+  game_ticket_id: number | null;
+  request_id: number | null;
+  user_id: number | null;
+  user_name: string | null;
+  section: string | null;
+  row: string | null;
+  seat: string | null;
+  before_value: string | null;
+  after_value: string | null;
+}
+
+/** Ledger amounts are in cents. */
+export interface LedgerGame {
+  user_id: number;
+  game_pk: number;
+  official_date: string;
+  away_team_name: string;
+  seats: number;
+  unpriced_seats: number;
+  amount: number;
+}
+
+export interface MemberLedger {
+  user_id: number;
+  user_name: string;
+  season: string;
+  games: LedgerGame[];
+  seats: number;
+  amount: number;
+}
+
+export interface AdminLedger {
+  season: string;
+  members: MemberLedger[];
+  seats: number;
+  amount: number;
+}
+
+/** Basis points: 5000 is 50%. A null seat_id is the plan-wide split. */
+export interface SplitRule {
+  id: number;
+  seat_id: number | null;
+  user_id: number;
+  user_name: string;
+  basis_points: number;
+}
+
+/** Caps seats per request for games of game_type with tag; null matches every game. */
+export interface SeatLimitRule {
+  id: number;
+  game_type: string | null;
+  tag: string | null;
+  max_seats: number;
+}
+
+export interface GameSeatTags {
+  /** Tags set by admins. */
+  tags: string[];
+  /** Those plus weekday/weekend and day/night, as seat limit rules see them. */
+  effective: string[];
+  max_seats: number;
+}
+
+export interface CostShareLine {
+  ticket_id: number;
+  game_pk: number;
+  official_date: string;
+  away_team_name: string;
+  seat_id: number;
+  user_id: number;
+  amount: number;
+  basis: 'seat' | 'plan' | 'usage';
+}
+
+export interface CostSplitPeriod {
+  period: string;
+  members: { user_id: number; user_name: string; amount: number; lines: CostShareLine[] }[];
+  unallocated: number;
+  total: number;
+}
+
+export interface CostSplit {
+  season: string;
+  periods: CostSplitPeriod[];
+}
+
+/** Body of every API error response. */
+export interface ApiErrorBody {
+  code: string;
+  message: string;
+  details?: unknown;
+}
+
+/** A member's bill for one period; amounts in cents. */
+export interface Invoice {
+  id: number;
+  user_id: number;
+  user_name: string;
+  period: string;
+  status: 'open' | 'partial' | 'paid';
+  total: number;
+  paid: number;
+  created_at: string;
+  /** Latest online checkout link, when online payment is enabled. */
+  payment_url: string | null;
+}
+
+export interface InvoiceLine {
+  id: number;
+  invoice_id: number;
+  ticket_id: number;
+  game_pk: number;
+  official_date: string;
+  away_team_name: string;
+  seat_id: number;
+  basis: 'seat' | 'plan' | 'usage';
+  amount: number;
+  paid: number;
+}
+
+export interface InvoicePayment {
+  id: number;
+  invoice_id: number;
+  line_id: number;
+  amount: number;
+  method: string | null;
+  note: string | null;
+  recorded_by: number | null;
+  created_at: string;
+}
+
+export interface InvoiceDetail extends Invoice {
+  lines: InvoiceLine[];
+  payments: InvoicePayment[];
+}
+
+export interface NoteTemplate {
+  id: number;
+  name: string;
+  /** May use {today}, {date}, {opponent} and {seat}. */
+  body: string;
+  created_at: string;
+  applied: number;
+  last_applied_at: string | null;
+}
+
+/** Tickets to apply a note template to: explicit ids, or a game (optionally one status). */
+export type NoteTarget =
+  | { ticket_ids: number[] }
+  | { game_pk: number; status?: string };
+
+export interface TicketTransfer {
+  id: number;
+  created_at: string;
+  game_pk: number;
+  official_date: string;
+  away_team_name: string;
+  game_ticket_id: number;
+  section: string;
+  row: string;
+  seat: string;
+  from_user_id: number;
+  from_user_name: string;
+  to_user_id: number;
+  to_user_name: string;
+}
+
+export interface UnassignedGame {
+  game_pk: number;
+  official_date: string;
+  game_date: string;
+  away_team_name: string;
+  double_header: string;
+  game_number: number;
+  available: number;
+}
+
+export interface CalendarGame {
+  game_pk: number;
+  official_date: string;
+  game_date: string;
+  opponent: string;
+  status_abstract: string;
+  double_header: string;
+  game_number: number;
+  total: number;
+  available: number;
+  assigned: number;
+  /** Available seats left over after every pending request is met. */
+  claimable: number;
+  my_seats: number;
+  my_request_status: string | null;
+  my_seats_requested: number | null;
+  shortlist: number;
+  cant_go: number;
+}
+
+export interface CalendarDay {
+  date: string;
+  games: CalendarGame[];
+}
+
+export interface AvailabilityCalendar {
+  season: string;
+  month: number;
+  days: CalendarDay[];
+}
+
+export interface MonthlyResponseTime {
+  month: string;
+  responded: number;
+  median_hours: number;
+}
+
+export type AllocationStrategy = 'round-robin' | 'weighted-lottery' | 'seniority';
+
+export interface AllocationPolicy {
+  strategy: AllocationStrategy;
+  max_seats_per_game: number | null;
+  max_seats_per_season: number | null;
+}
+
+export interface SimulatedMember {
+  user_id: number;
+  user_name: string;
+  requests: number;
+  seats_requested: number;
+  actual_seats: number;
+  simulated_seats: number;
+}
+
+/** A season's requests replayed under a hypothetical policy. */
+export interface AllocationSimulation {
+  season: string;
+  policy: AllocationPolicy;
+  seed: number;
+  members: SimulatedMember[];
+}
+
+/** What a standing (bulk) request created, and the matching games it skipped. */
+export interface BulkRequests {
+  created: TicketRequest[];
+  skipped: { game_pk: number; official_date: string; reason: string }[];
+}
+
+/** A committed allocation batch; `snapshot` is only on the single-batch fetch. */
+export interface AllocationBatch {
+  id: number;
+  actor_id: number | null;
+  kind: 'manual' | 'auto';
+  game_pk: number | null;
+  assigned: number;
+  created_at: string;
+  snapshot?: {
+    request: unknown;
+    proposal: unknown | null;
+    assignments: { game_ticket_id: number; user_id: number; request_id: number | null; assigned: boolean }[];
+    warnings: string[];
+  };
 }
 
 export interface AllocationSummaryRow {
   game_pk: number;
   official_date: string;
   away_team_name: string;
+  double_header: string;
+  game_number: number;
   total_seats: number;
   assigned: number;
   available: number;
@@ -106,6 +695,20 @@ export interface User {
   auth0_sub: string;
   email: string;
   name: string;
+  external_refs?: ExternalRef[];
+}
+
+/** A user to act as when the server runs with `auth_mode = "none"`. */
+export interface LocalUser {
+  id: number;
+  name: string;
+  email: string;
+  admin: boolean;
+}
+
+export interface AppConfig {
+  team_id: number;
+  team_name: string;
 }
 
 export interface MeResponse {
@@ -114,6 +717,7 @@ export interface MeResponse {
   email: string;
   name: string;
   role: string;
+  locale: string | null;
 }
 
 export interface UserTicketInfo {
@@ -162,9 +766,103 @@ export interface Promotion {
   display_order: number;
 }
 
+export interface GamePreview {
+  game_pk: number;
+  away_probable_pitcher: string | null;
+  home_probable_pitcher: string | null;
+  away_record: string | null;
+  home_record: string | null;
+  away_streak: string | null;
+  home_streak: string | null;
+  body: string;
+  generated_at: string;
+}
+
+export interface SeatImport {
+  seats: Seat[];
+  tickets: number;
+}
+
+export interface Season {
+  season: string;
+  games: number;
+  first_date: string;
+  last_date: string;
+  tickets: number;
+  requests: number;
+  /** Set once an admin has closed the season; its tickets and requests are then read-only. */
+  closed_at: string | null;
+}
+
+export type PollAnswer = 'yes' | 'no' | 'maybe';
+
+export interface PollSummary {
+  id: number;
+  game_pk: number;
+  official_date: string;
+  away_team_name: string;
+  question: string | null;
+  created_at: string;
+  closed_at: string | null;
+  yes: number;
+  no: number;
+  maybe: number;
+  yes_seats: number;
+  maybe_seats: number;
+}
+
+export interface PollResults extends PollSummary {
+  responses: {
+    user_id: number;
+    user_name: string;
+    answer: PollAnswer;
+    seats: number;
+    updated_at: string;
+  }[];
+}
+
+export interface MemberPoll {
+  id: number;
+  game_pk: number;
+  official_date: string;
+  away_team_name: string;
+  question: string | null;
+  answer: PollAnswer | null;
+  seats: number | null;
+}
+
+export interface PromotionListing {
+  offer_id: number;
+  game_pk: number;
+  official_date: string;
+  away_team_name: string;
+  home_team_name: string;
+  name: string;
+  offer_type: string | null;
+  description: string | null;
+  distribution: string | null;
+  presented_by: string | null;
+}
+
+export interface PromotionFilter {
+  type?: string;
+  name?: string;
+  distribution?: string;
+  season?: string;
+  month?: number;
+  from?: string;
+}
+
 export interface GameTag {
   user_id: number;
   game_pk: number;
   shortlist: boolean;
   cant_go: boolean;
 }
+
+/** Body of every POST/PATCH response. */
+export interface Envelope<T> {
+  data: T;
+  /** Things the request only partly did, e.g. seats that were skipped. */
+  warnings: string[];
+}
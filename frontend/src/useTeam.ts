@@ -0,0 +1,15 @@
+import { useEffect, useState } from 'react';
+import { fetchConfig } from './api';
+
+/** The club's team id from `/api/config`; null until it loads. */
+export default function useTeam(): number | null {
+  const [teamId, setTeamId] = useState<number | null>(null);
+
+  useEffect(() => {
+    fetchConfig()
+      .then((config) => setTeamId(config.team_id))
+      .catch(() => {});
+  }, []);
+
+  return teamId;
+}
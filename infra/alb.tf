@@ -52,7 +52,7 @@ resource "aws_lb_target_group" "gtm" {
   target_type = "ip"
 
   health_check {
-    path                = "/api/health"
+    path                = "/api/health/ready"
     protocol            = "HTTP"
     healthy_threshold   = 2
     unhealthy_threshold = 3
@@ -120,6 +120,7 @@ resource "aws_ecs_task_definition" "gtm" {
       { name = "GTM_UTC", value = "true" },
       { name = "GTM_LOG_JSON", value = "true" },
       { name = "GTM_DB_URL", value = local.db_url },
+      { name = "GTM_AUTO_MIGRATE", value = "true" },
     ]
 
     secrets = [
@@ -0,0 +1,487 @@
+-- MySQL/MariaDB schema, equivalent to every SQLite and Postgres migration
+-- up to 20261015128000. Text is VARCHAR and timestamps are UTC strings
+-- ('YYYY-MM-DD HH:MM:SS'), as in SQLite; the Any driver can't decode
+-- TEXT or DATETIME columns.
+
+CREATE TABLE games (
+    game_pk               BIGINT NOT NULL,
+    game_guid             VARCHAR(255),
+    game_type             VARCHAR(255) NOT NULL,
+    season                VARCHAR(255) NOT NULL,
+    game_date             VARCHAR(255) NOT NULL,
+    official_date         VARCHAR(255) NOT NULL,
+    status_abstract       VARCHAR(255) NOT NULL,
+    status_detailed       VARCHAR(255) NOT NULL,
+    status_code           VARCHAR(255) NOT NULL,
+    start_time_tbd        BIGINT NOT NULL DEFAULT 0,
+    away_team_id          BIGINT NOT NULL,
+    away_team_name        VARCHAR(255) NOT NULL,
+    away_score            BIGINT,
+    away_is_winner        BIGINT,
+    home_team_id          BIGINT NOT NULL,
+    home_team_name        VARCHAR(255) NOT NULL,
+    home_score            BIGINT,
+    home_is_winner        BIGINT,
+    venue_id              BIGINT NOT NULL,
+    venue_name            VARCHAR(255) NOT NULL,
+    day_night             VARCHAR(255),
+    series_description    VARCHAR(255),
+    series_game_number    BIGINT,
+    games_in_series       BIGINT,
+    double_header         VARCHAR(255) NOT NULL DEFAULT 'N',
+    game_number           BIGINT NOT NULL DEFAULT 1,
+    scheduled_innings     BIGINT NOT NULL DEFAULT 9,
+    is_tie                BIGINT NOT NULL DEFAULT 0,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    current_inning        BIGINT,
+    inning_state          VARCHAR(255),
+    max_seats_override    BIGINT,
+    away_probable_pitcher VARCHAR(255),
+    home_probable_pitcher VARCHAR(255),
+    weather_condition     VARCHAR(255),
+    weather_temp          VARCHAR(255),
+    weather_wind          VARCHAR(255),
+    tv_broadcasts         VARCHAR(4000),
+    PRIMARY KEY (game_pk)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE promotions (
+    offer_id              BIGINT NOT NULL,
+    game_pk               BIGINT NOT NULL,
+    name                  VARCHAR(255) NOT NULL,
+    offer_type            VARCHAR(255),
+    description           VARCHAR(4000),
+    distribution          VARCHAR(255),
+    presented_by          VARCHAR(255),
+    alt_page_url          VARCHAR(1000),
+    ticket_link           VARCHAR(1000),
+    thumbnail_url         VARCHAR(1000),
+    image_url             VARCHAR(1000),
+    display_order         BIGINT NOT NULL DEFAULT 0,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    UNIQUE (offer_id, game_pk),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE seats (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    section               VARCHAR(255) NOT NULL,
+    `row`                 VARCHAR(255) NOT NULL,
+    seat                  VARCHAR(255) NOT NULL,
+    notes                 VARCHAR(4000),
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    deleted_at            VARCHAR(32),
+    face_value            BIGINT,
+    PRIMARY KEY (id),
+    UNIQUE (section, `row`, seat)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE users (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    auth0_sub             VARCHAR(255) NOT NULL,
+    email                 VARCHAR(255) NOT NULL,
+    name                  VARCHAR(255) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    UNIQUE (auth0_sub)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE game_tickets (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    game_pk               BIGINT NOT NULL,
+    seat_id               BIGINT NOT NULL,
+    status                VARCHAR(255) NOT NULL DEFAULT 'available',
+    notes                 VARCHAR(4000),
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    assigned_to           BIGINT,
+    cost                  BIGINT,
+    resale_amount         BIGINT,
+    PRIMARY KEY (id),
+    UNIQUE (game_pk, seat_id),
+    FOREIGN KEY (assigned_to) REFERENCES users(id),
+    FOREIGN KEY (seat_id) REFERENCES seats(id),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE ticket_requests (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    user_id               BIGINT NOT NULL,
+    game_pk               BIGINT NOT NULL,
+    seats_requested       BIGINT NOT NULL,
+    status                VARCHAR(255) NOT NULL DEFAULT 'pending',
+    notes                 VARCHAR(4000),
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    responded_at          VARCHAR(32),
+    flag_reason           VARCHAR(4000),
+    PRIMARY KEY (id),
+    UNIQUE (user_id, game_pk),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE user_game_tags (
+    user_id               BIGINT NOT NULL,
+    game_pk               BIGINT NOT NULL,
+    shortlist             BIGINT NOT NULL DEFAULT 0,
+    cant_go               BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, game_pk),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE user_phones (
+    user_id               BIGINT NOT NULL,
+    phone                 VARCHAR(255) NOT NULL,
+    verified              BIGINT NOT NULL DEFAULT 0,
+    sms_opt_in            BIGINT NOT NULL DEFAULT 1,
+    verification_code     VARCHAR(255),
+    code_expires_at       VARCHAR(32),
+    verify_attempts       BIGINT NOT NULL DEFAULT 0,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (user_id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE audit_log (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    actor_id              BIGINT,
+    action                VARCHAR(255) NOT NULL,
+    game_pk               BIGINT,
+    game_ticket_id        BIGINT,
+    request_id            BIGINT,
+    user_id               BIGINT,
+    before_value          VARCHAR(4000),
+    after_value           VARCHAR(4000),
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    FOREIGN KEY (actor_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_audit_log_user_id ON audit_log(user_id);
+
+CREATE INDEX idx_audit_log_game_pk ON audit_log(game_pk);
+
+CREATE TABLE release_prompts (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    token                 VARCHAR(255) NOT NULL,
+    game_pk               BIGINT NOT NULL,
+    user_id               BIGINT NOT NULL,
+    rain_probability      BIGINT NOT NULL,
+    response              VARCHAR(255),
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    responded_at          VARCHAR(32),
+    PRIMARY KEY (id),
+    UNIQUE (game_pk, user_id),
+    UNIQUE (token),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE google_accounts (
+    user_id               BIGINT NOT NULL,
+    refresh_token         VARCHAR(4000) NOT NULL,
+    access_token          VARCHAR(4000),
+    access_expires_at     VARCHAR(32),
+    calendar_id           VARCHAR(255) NOT NULL DEFAULT 'primary',
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (user_id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE google_calendar_events (
+    user_id               BIGINT NOT NULL,
+    game_pk               BIGINT NOT NULL,
+    event_id              VARCHAR(255) NOT NULL,
+    content_hash          VARCHAR(255) NOT NULL,
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (user_id, game_pk),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE oauth_states (
+    state                 VARCHAR(255) NOT NULL,
+    user_id               BIGINT NOT NULL,
+    expires_at            VARCHAR(32) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (state),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE notifications (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    user_id               BIGINT,
+    game_pk               BIGINT,
+    kind                  VARCHAR(255) NOT NULL,
+    channel               VARCHAR(255) NOT NULL,
+    recipient             VARCHAR(255) NOT NULL,
+    subject               VARCHAR(255) NOT NULL,
+    body                  VARCHAR(4000) NOT NULL,
+    status                VARCHAR(255) NOT NULL DEFAULT 'pending',
+    attempts              BIGINT NOT NULL DEFAULT 0,
+    last_error            VARCHAR(4000),
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    sent_at               VARCHAR(32),
+    PRIMARY KEY (id),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_notifications_status ON notifications(status);
+
+CREATE TABLE bulk_confirmations (
+    token                 VARCHAR(255) NOT NULL,
+    operation             VARCHAR(255) NOT NULL,
+    scope                 VARCHAR(255) NOT NULL,
+    affected              BIGINT NOT NULL,
+    expires_at            VARCHAR(32) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (token)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE promotion_interests (
+    user_id               BIGINT NOT NULL,
+    offer_id              BIGINT NOT NULL,
+    game_pk               BIGINT NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (user_id, offer_id, game_pk),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE user_blackouts (
+    user_id               BIGINT NOT NULL,
+    blackout_date         VARCHAR(255) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (user_id, blackout_date),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE ticket_transfers (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    game_pk               BIGINT NOT NULL,
+    game_ticket_id        BIGINT NOT NULL,
+    from_user_id          BIGINT NOT NULL,
+    to_user_id            BIGINT NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    FOREIGN KEY (to_user_id) REFERENCES users(id),
+    FOREIGN KEY (from_user_id) REFERENCES users(id),
+    FOREIGN KEY (game_ticket_id) REFERENCES game_tickets(id),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_ticket_transfers_game ON ticket_transfers(game_pk);
+
+CREATE TABLE job_locks (
+    name                  VARCHAR(255) NOT NULL,
+    holder                VARCHAR(255) NOT NULL,
+    expires_at            VARCHAR(32) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE game_previews (
+    game_pk               BIGINT NOT NULL,
+    away_probable_pitcher VARCHAR(255),
+    home_probable_pitcher VARCHAR(255),
+    away_record           VARCHAR(255),
+    home_record           VARCHAR(255),
+    away_streak           VARCHAR(255),
+    home_streak           VARCHAR(255),
+    body                  VARCHAR(4000) NOT NULL,
+    generated_at          VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (game_pk),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE polls (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    game_pk               BIGINT NOT NULL,
+    question              VARCHAR(4000),
+    created_by            BIGINT,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    closed_at             VARCHAR(32),
+    PRIMARY KEY (id),
+    UNIQUE (game_pk),
+    FOREIGN KEY (created_by) REFERENCES users(id),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE poll_responses (
+    poll_id               BIGINT NOT NULL,
+    user_id               BIGINT NOT NULL,
+    answer                VARCHAR(255) NOT NULL,
+    seats                 BIGINT NOT NULL DEFAULT 0,
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (poll_id, user_id),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (poll_id) REFERENCES polls(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE cost_split_rules (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    seat_id               BIGINT,
+    user_id               BIGINT NOT NULL,
+    basis_points          BIGINT NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (seat_id) REFERENCES seats(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE invoices (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    user_id               BIGINT NOT NULL,
+    period                VARCHAR(255) NOT NULL,
+    total                 BIGINT NOT NULL,
+    status                VARCHAR(255) NOT NULL DEFAULT 'open',
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    checkout_session_id   VARCHAR(255),
+    payment_url           VARCHAR(1000),
+    PRIMARY KEY (id),
+    UNIQUE (user_id, period),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE invoice_lines (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    invoice_id            BIGINT NOT NULL,
+    ticket_id             BIGINT NOT NULL,
+    game_pk               BIGINT NOT NULL,
+    official_date         VARCHAR(255) NOT NULL,
+    away_team_name        VARCHAR(255) NOT NULL,
+    seat_id               BIGINT NOT NULL,
+    basis                 VARCHAR(255) NOT NULL,
+    amount                BIGINT NOT NULL,
+    PRIMARY KEY (id),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk),
+    FOREIGN KEY (invoice_id) REFERENCES invoices(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_invoice_lines_invoice ON invoice_lines(invoice_id);
+
+CREATE TABLE invoice_payments (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    invoice_id            BIGINT NOT NULL,
+    line_id               BIGINT NOT NULL,
+    amount                BIGINT NOT NULL,
+    method                VARCHAR(255),
+    note                  VARCHAR(4000),
+    recorded_by           BIGINT,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    FOREIGN KEY (recorded_by) REFERENCES users(id),
+    FOREIGN KEY (line_id) REFERENCES invoice_lines(id),
+    FOREIGN KEY (invoice_id) REFERENCES invoices(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_invoice_payments_invoice ON invoice_payments(invoice_id);
+
+CREATE TABLE saved_views (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    user_id               BIGINT NOT NULL,
+    name                  VARCHAR(255) NOT NULL,
+    filter                VARCHAR(4000) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    UNIQUE (user_id, name),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE note_templates (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    name                  VARCHAR(255) NOT NULL,
+    body                  VARCHAR(4000) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    UNIQUE (name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE note_applications (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    template_id           BIGINT NOT NULL,
+    game_ticket_id        BIGINT NOT NULL,
+    note                  VARCHAR(4000) NOT NULL,
+    applied_by            BIGINT,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    FOREIGN KEY (applied_by) REFERENCES users(id),
+    FOREIGN KEY (template_id) REFERENCES note_templates(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_note_applications_template_id ON note_applications(template_id);
+
+CREATE TABLE seat_limit_rules (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    game_type             VARCHAR(255),
+    tag                   VARCHAR(255),
+    max_seats             BIGINT NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE game_tags (
+    game_pk               BIGINT NOT NULL,
+    tag                   VARCHAR(255) NOT NULL,
+    PRIMARY KEY (game_pk, tag)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE api_usage (
+    day                   VARCHAR(255) NOT NULL,
+    user_id               BIGINT NOT NULL,
+    endpoint              VARCHAR(255) NOT NULL,
+    requests              BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, user_id, endpoint),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE external_refs (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    entity_type           VARCHAR(255) NOT NULL,
+    entity_id             BIGINT NOT NULL,
+    `system`              VARCHAR(255) NOT NULL,
+    external_id           VARCHAR(255) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    UNIQUE (`system`, entity_type, external_id),
+    UNIQUE (entity_type, entity_id, `system`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE api_tokens (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    user_id               BIGINT NOT NULL,
+    name                  VARCHAR(255) NOT NULL,
+    token_hash            VARCHAR(255) NOT NULL,
+    prefix                VARCHAR(255) NOT NULL,
+    scope                 VARCHAR(255) NOT NULL DEFAULT 'member',
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    last_used_at          VARCHAR(32),
+    revoked_at            VARCHAR(32),
+    PRIMARY KEY (id),
+    UNIQUE (token_hash),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_api_tokens_user_id ON api_tokens(user_id);
+
+CREATE TABLE request_comments (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    request_id            BIGINT NOT NULL,
+    user_id               BIGINT NOT NULL,
+    body                  VARCHAR(4000) NOT NULL,
+    from_admin            BIGINT NOT NULL DEFAULT 0,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (request_id) REFERENCES ticket_requests(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_request_comments_request_id ON request_comments(request_id, id);
@@ -0,0 +1,8 @@
+-- Seasons an admin has closed; their tickets and requests can no longer change
+CREATE TABLE closed_seasons (
+    season                VARCHAR(255) NOT NULL,
+    closed_by             BIGINT NULL,
+    closed_at             VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (season),
+    FOREIGN KEY (closed_by) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
@@ -0,0 +1,30 @@
+-- Outbound webhooks: admin-registered URLs and the events queued for them
+CREATE TABLE webhooks (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    url                   VARCHAR(2000) NOT NULL,
+    events                VARCHAR(255) NOT NULL,
+    secret                VARCHAR(255) NOT NULL,
+    created_by            BIGINT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    FOREIGN KEY (created_by) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE TABLE webhook_deliveries (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    webhook_id            BIGINT NOT NULL,
+    event                 VARCHAR(255) NOT NULL,
+    payload               VARCHAR(8000) NOT NULL,
+    status                VARCHAR(255) NOT NULL DEFAULT 'pending',
+    attempts              BIGINT NOT NULL DEFAULT 0,
+    response_status       BIGINT,
+    last_error            VARCHAR(2000),
+    next_attempt_at       VARCHAR(32),
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    delivered_at          VARCHAR(32),
+    PRIMARY KEY (id),
+    FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_webhook_deliveries_status ON webhook_deliveries(status);
+CREATE INDEX idx_webhook_deliveries_webhook_id ON webhook_deliveries(webhook_id);
@@ -0,0 +1,15 @@
+-- Idempotency-Key results, so a retried request replays instead of re-running
+CREATE TABLE idempotency_keys (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    user_id               BIGINT NOT NULL,
+    endpoint              VARCHAR(255) NOT NULL,
+    idempotency_key       VARCHAR(255) NOT NULL,
+    request_hash          VARCHAR(255) NOT NULL,
+    response              VARCHAR(8000),
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    UNIQUE (user_id, endpoint, idempotency_key),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_idempotency_keys_created_at ON idempotency_keys(created_at);
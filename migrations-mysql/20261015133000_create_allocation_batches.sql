@@ -0,0 +1,15 @@
+-- What an admin submitted, was proposed and got assigned, per committed allocation batch
+CREATE TABLE allocation_batches (
+    id                    BIGINT NOT NULL AUTO_INCREMENT,
+    actor_id              BIGINT NULL,
+    kind                  VARCHAR(32) NOT NULL,
+    game_pk               BIGINT NULL,
+    assigned              BIGINT NOT NULL DEFAULT 0,
+    snapshot              VARCHAR(16000) NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (id),
+    FOREIGN KEY (actor_id) REFERENCES users(id),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_allocation_batches_game_pk ON allocation_batches(game_pk);
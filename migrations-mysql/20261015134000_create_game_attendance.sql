@@ -0,0 +1,14 @@
+-- Whether a member made it to a game they held seats for, as they or an admin recorded it
+CREATE TABLE game_attendance (
+    game_pk               BIGINT NOT NULL,
+    user_id               BIGINT NOT NULL,
+    attended              BIGINT NOT NULL,
+    recorded_by           BIGINT NULL,
+    recorded_at           VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (game_pk, user_id),
+    FOREIGN KEY (game_pk) REFERENCES games(game_pk),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (recorded_by) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
+
+CREATE INDEX idx_game_attendance_user_id ON game_attendance(user_id);
@@ -0,0 +1,7 @@
+ALTER TABLE game_tickets ADD COLUMN external_ticket_id VARCHAR(255);
+ALTER TABLE game_tickets ADD COLUMN forwarded_to BIGINT;
+ALTER TABLE game_tickets ADD COLUMN forwarded_on VARCHAR(10);
+ALTER TABLE game_tickets ADD COLUMN forward_method VARCHAR(32);
+ALTER TABLE game_tickets ADD COLUMN forwarded_by BIGINT;
+ALTER TABLE game_tickets ADD FOREIGN KEY (forwarded_to) REFERENCES users(id);
+ALTER TABLE game_tickets ADD FOREIGN KEY (forwarded_by) REFERENCES users(id);
@@ -0,0 +1,8 @@
+-- How each background job's last run went, for the job status API
+CREATE TABLE job_runs (
+    job                   VARCHAR(64) NOT NULL,
+    status                VARCHAR(16) NOT NULL,
+    detail                TEXT NULL,
+    finished_at           VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (job)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
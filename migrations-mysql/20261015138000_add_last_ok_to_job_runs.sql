@@ -0,0 +1,2 @@
+-- When each job last finished ok, for the readiness check
+ALTER TABLE job_runs ADD COLUMN last_ok_at VARCHAR(32) NULL;
@@ -0,0 +1,10 @@
+-- Each member's defaults and notification choices
+CREATE TABLE user_preferences (
+    user_id               BIGINT NOT NULL,
+    default_seats         BIGINT NULL,
+    contact_email         VARCHAR(255) NULL,
+    muted_notifications   VARCHAR(1024) NOT NULL DEFAULT '',
+    updated_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (user_id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
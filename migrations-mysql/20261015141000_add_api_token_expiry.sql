@@ -0,0 +1,8 @@
+-- API tokens expire; existing ones get the default 90 days from now.
+ALTER TABLE api_tokens ADD COLUMN expires_at VARCHAR(255) NULL;
+UPDATE api_tokens
+   SET expires_at = DATE_FORMAT(UTC_TIMESTAMP() + INTERVAL 90 DAY, '%Y-%m-%dT%H:%i:%sZ');
+
+-- Whether the member last signed in with the admin role. Admin tokens only
+-- act as admin while this is set.
+ALTER TABLE users ADD COLUMN is_admin BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+-- One row per Stripe checkout session recorded, so a webhook Stripe
+-- delivers twice pays the invoice once. `applied` is what was recorded
+-- against the invoice; the rest of `amount` is owed back to the member.
+CREATE TABLE stripe_checkouts (
+    session_id            VARCHAR(255) NOT NULL,
+    invoice_id            BIGINT NOT NULL,
+    amount                BIGINT NOT NULL,
+    applied               BIGINT NOT NULL,
+    created_at            VARCHAR(32) NOT NULL DEFAULT (CAST(CURRENT_TIMESTAMP AS CHAR)),
+    PRIMARY KEY (session_id),
+    FOREIGN KEY (invoice_id) REFERENCES invoices(id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
@@ -0,0 +1,7 @@
+-- Mailgun webhook tokens already acted on, so a captured inbound email
+-- can't be replayed. `seen_at` is 'YYYY-MM-DD HH:MM:SS' UTC.
+CREATE TABLE inbound_tokens (
+    token    VARCHAR(255) NOT NULL,
+    seen_at  VARCHAR(32) NOT NULL,
+    PRIMARY KEY (token)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
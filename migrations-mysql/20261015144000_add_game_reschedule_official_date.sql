@@ -0,0 +1,3 @@
+-- The makeup's date at the ballpark, from the schedule's
+-- rescheduleGameDate/resumeGameDate, to match it against games' official_date.
+ALTER TABLE games ADD COLUMN reschedule_official_date VARCHAR(255) NULL;
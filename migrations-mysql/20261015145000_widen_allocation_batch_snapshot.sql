@@ -0,0 +1,2 @@
+-- A large game's snapshot overflows VARCHAR(16000)
+ALTER TABLE allocation_batches MODIFY snapshot LONGTEXT NOT NULL;
@@ -0,0 +1,4 @@
+-- A new contact address waits here, with a SHA-256 of its confirmation
+-- link's token, until the member follows the link
+ALTER TABLE user_preferences ADD COLUMN pending_contact_email VARCHAR(255) NULL;
+ALTER TABLE user_preferences ADD COLUMN contact_email_token VARCHAR(64) NULL;
@@ -0,0 +1,8 @@
+-- Requests per endpoint per day to the public routes, which don't look up
+-- the member. Member and admin routes are counted per member in api_usage.
+CREATE TABLE api_public_usage (
+    day       VARCHAR(255) NOT NULL,
+    endpoint  VARCHAR(255) NOT NULL,
+    requests  BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, endpoint)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_bin;
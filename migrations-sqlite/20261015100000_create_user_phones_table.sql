@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS user_phones (
+    user_id             INTEGER NOT NULL PRIMARY KEY REFERENCES users(id),
+    phone               TEXT    NOT NULL,
+    verified            INTEGER NOT NULL DEFAULT 0,
+    sms_opt_in          INTEGER NOT NULL DEFAULT 1,
+    verification_code   TEXT,
+    code_expires_at     TEXT,
+    verify_attempts     INTEGER NOT NULL DEFAULT 0,
+    created_at          DATETIME NOT NULL DEFAULT (datetime('now')),
+    updated_at          DATETIME NOT NULL DEFAULT (datetime('now'))
+);
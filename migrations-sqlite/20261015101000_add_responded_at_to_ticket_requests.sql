@@ -0,0 +1 @@
+ALTER TABLE ticket_requests ADD COLUMN responded_at DATETIME;
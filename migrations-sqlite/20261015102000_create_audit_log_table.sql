@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS audit_log (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor_id        INTEGER REFERENCES users(id),
+    action          TEXT    NOT NULL,
+    game_pk         INTEGER,
+    game_ticket_id  INTEGER,
+    request_id      INTEGER,
+    user_id         INTEGER,
+    before_value    TEXT,
+    after_value     TEXT,
+    created_at      DATETIME NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_audit_log_game_pk ON audit_log(game_pk);
+CREATE INDEX IF NOT EXISTS idx_audit_log_user_id ON audit_log(user_id);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS release_prompts (
+    id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+    token               TEXT    NOT NULL UNIQUE,
+    game_pk             INTEGER NOT NULL REFERENCES games(game_pk),
+    user_id             INTEGER NOT NULL REFERENCES users(id),
+    rain_probability    INTEGER NOT NULL,
+    response            TEXT,
+    created_at          DATETIME NOT NULL DEFAULT (datetime('now')),
+    responded_at        DATETIME,
+    UNIQUE(game_pk, user_id)
+);
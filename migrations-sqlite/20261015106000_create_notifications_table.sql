@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS notifications (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id     INTEGER REFERENCES users(id),
+    game_pk     INTEGER REFERENCES games(game_pk),
+    kind        TEXT    NOT NULL,
+    channel     TEXT    NOT NULL,
+    recipient   TEXT    NOT NULL,
+    subject     TEXT    NOT NULL,
+    body        TEXT    NOT NULL,
+    status      TEXT    NOT NULL DEFAULT 'pending',
+    attempts    INTEGER NOT NULL DEFAULT 0,
+    last_error  TEXT,
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now')),
+    sent_at     DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_status ON notifications(status);
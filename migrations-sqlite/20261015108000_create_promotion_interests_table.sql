@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS promotion_interests (
+    user_id     INTEGER NOT NULL REFERENCES users(id),
+    offer_id    INTEGER NOT NULL,
+    game_pk     INTEGER NOT NULL REFERENCES games(game_pk),
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (user_id, offer_id, game_pk)
+);
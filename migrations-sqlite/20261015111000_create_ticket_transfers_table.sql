@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS ticket_transfers (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    game_pk         INTEGER NOT NULL REFERENCES games(game_pk),
+    game_ticket_id  INTEGER NOT NULL REFERENCES game_tickets(id),
+    from_user_id    INTEGER NOT NULL REFERENCES users(id),
+    to_user_id      INTEGER NOT NULL REFERENCES users(id),
+    created_at      DATETIME NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_ticket_transfers_game ON ticket_transfers(game_pk);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS job_locks (
+    name        TEXT PRIMARY KEY,
+    holder      TEXT NOT NULL,
+    expires_at  TEXT NOT NULL,
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS game_previews (
+    game_pk                 INTEGER PRIMARY KEY REFERENCES games(game_pk),
+    away_probable_pitcher   TEXT,
+    home_probable_pitcher   TEXT,
+    away_record             TEXT,
+    home_record             TEXT,
+    away_streak             TEXT,
+    home_streak             TEXT,
+    body                    TEXT    NOT NULL,
+    generated_at            DATETIME NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS polls (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    game_pk     INTEGER NOT NULL UNIQUE REFERENCES games(game_pk),
+    question    TEXT,
+    created_by  INTEGER REFERENCES users(id),
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now')),
+    closed_at   DATETIME
+);
+
+CREATE TABLE IF NOT EXISTS poll_responses (
+    poll_id     INTEGER NOT NULL REFERENCES polls(id),
+    user_id     INTEGER NOT NULL REFERENCES users(id),
+    answer      TEXT    NOT NULL,
+    seats       INTEGER NOT NULL DEFAULT 0,
+    updated_at  DATETIME NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (poll_id, user_id)
+);
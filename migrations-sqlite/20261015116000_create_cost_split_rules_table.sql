@@ -0,0 +1,10 @@
+-- Who pays for what. Rules on a seat split every game in it by share;
+-- rules with no seat apply the same split to every seat without its own.
+-- Seats covered by neither are paid by whoever is assigned each game.
+CREATE TABLE IF NOT EXISTS cost_split_rules (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    seat_id         INTEGER REFERENCES seats(id),
+    user_id         INTEGER NOT NULL REFERENCES users(id),
+    basis_points    INTEGER NOT NULL,
+    created_at      DATETIME NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,2 @@
+ALTER TABLE games ADD COLUMN current_inning INTEGER;
+ALTER TABLE games ADD COLUMN inning_state TEXT;
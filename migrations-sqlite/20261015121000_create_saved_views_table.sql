@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS saved_views (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id     INTEGER NOT NULL REFERENCES users(id),
+    name        TEXT    NOT NULL,
+    filter      TEXT    NOT NULL,
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (user_id, name)
+);
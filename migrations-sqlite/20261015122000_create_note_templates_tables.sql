@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS note_templates (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    name        TEXT    NOT NULL UNIQUE,
+    body        TEXT    NOT NULL,
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS note_applications (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    template_id     INTEGER NOT NULL REFERENCES note_templates(id),
+    game_ticket_id  INTEGER NOT NULL,
+    note            TEXT    NOT NULL,
+    applied_by      INTEGER REFERENCES users(id),
+    created_at      DATETIME NOT NULL DEFAULT (datetime('now'))
+);
+CREATE INDEX IF NOT EXISTS idx_note_applications_template_id ON note_applications(template_id);
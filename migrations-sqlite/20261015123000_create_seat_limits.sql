@@ -0,0 +1,19 @@
+-- Most seats one request may ask for. A rule applies to games of its
+-- game_type and with its tag (NULL matches any game); the strictest
+-- matching rule wins, and a game's max_seats_override beats them all.
+CREATE TABLE IF NOT EXISTS seat_limit_rules (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    game_type   TEXT,
+    tag         TEXT,
+    max_seats   INTEGER NOT NULL,
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Labels admins put on games (e.g. premium) for seat limit rules to match.
+CREATE TABLE IF NOT EXISTS game_tags (
+    game_pk     INTEGER NOT NULL,
+    tag         TEXT    NOT NULL,
+    PRIMARY KEY (game_pk, tag)
+);
+
+ALTER TABLE games ADD COLUMN max_seats_override INTEGER;
@@ -0,0 +1,12 @@
+-- IDs other systems (a Notion database, an Airtable base) use for GTM
+-- games, tickets and users, so sync scripts can find a record either way.
+CREATE TABLE IF NOT EXISTS external_refs (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity_type  TEXT    NOT NULL,
+    entity_id    INTEGER NOT NULL,
+    system       TEXT    NOT NULL,
+    external_id  TEXT    NOT NULL,
+    created_at   DATETIME NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (entity_type, entity_id, system),
+    UNIQUE (system, entity_type, external_id)
+);
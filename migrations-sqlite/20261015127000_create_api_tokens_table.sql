@@ -0,0 +1,16 @@
+-- Long-lived tokens members use to script against the API
+-- (`Authorization: Token <value>`). Only a SHA-256 of each token is kept.
+CREATE TABLE IF NOT EXISTS api_tokens (
+    id            INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id       INTEGER NOT NULL REFERENCES users(id),
+    name          TEXT    NOT NULL,
+    token_hash    TEXT    NOT NULL UNIQUE,
+    -- First characters of the token, to tell tokens apart in listings
+    prefix        TEXT    NOT NULL,
+    -- 'member', or 'admin' for tokens minted by an admin
+    scope         TEXT    NOT NULL DEFAULT 'member',
+    created_at    DATETIME NOT NULL DEFAULT (datetime('now')),
+    last_used_at  DATETIME,
+    revoked_at    DATETIME
+);
+CREATE INDEX IF NOT EXISTS idx_api_tokens_user_id ON api_tokens(user_id);
@@ -0,0 +1,12 @@
+-- What an admin submitted, was proposed and got assigned, per committed allocation batch
+CREATE TABLE IF NOT EXISTS allocation_batches (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    actor_id    INTEGER REFERENCES users(id),
+    kind        TEXT    NOT NULL,
+    game_pk     INTEGER REFERENCES games(game_pk),
+    assigned    INTEGER NOT NULL DEFAULT 0,
+    snapshot    TEXT    NOT NULL,
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_allocation_batches_game_pk ON allocation_batches(game_pk);
@@ -0,0 +1,11 @@
+-- Whether a member made it to a game they held seats for, as they or an admin recorded it
+CREATE TABLE IF NOT EXISTS game_attendance (
+    game_pk     INTEGER NOT NULL REFERENCES games(game_pk),
+    user_id     INTEGER NOT NULL REFERENCES users(id),
+    attended    INTEGER NOT NULL,
+    recorded_by INTEGER REFERENCES users(id),
+    recorded_at DATETIME NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY (game_pk, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_attendance_user_id ON game_attendance(user_id);
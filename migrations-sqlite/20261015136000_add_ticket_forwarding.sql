@@ -0,0 +1,5 @@
+ALTER TABLE game_tickets ADD COLUMN external_ticket_id TEXT;
+ALTER TABLE game_tickets ADD COLUMN forwarded_to INTEGER REFERENCES users(id);
+ALTER TABLE game_tickets ADD COLUMN forwarded_on TEXT;
+ALTER TABLE game_tickets ADD COLUMN forward_method TEXT;
+ALTER TABLE game_tickets ADD COLUMN forwarded_by INTEGER REFERENCES users(id);
@@ -0,0 +1,5 @@
+-- When a postponed or suspended game is to be made up, from the schedule's
+-- rescheduleDate/resumeDate, and the makeup game once its tickets and
+-- requests have been moved there.
+ALTER TABLE games ADD COLUMN reschedule_date TEXT;
+ALTER TABLE games ADD COLUMN rescheduled_to INTEGER REFERENCES games(game_pk);
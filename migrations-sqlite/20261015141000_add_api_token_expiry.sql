@@ -0,0 +1,7 @@
+-- API tokens expire; existing ones get the default 90 days from now.
+ALTER TABLE api_tokens ADD COLUMN expires_at TEXT;
+UPDATE api_tokens SET expires_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '+90 days');
+
+-- Whether the member last signed in with the admin role. Admin tokens only
+-- act as admin while this is set.
+ALTER TABLE users ADD COLUMN is_admin INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+-- One row per Stripe checkout session recorded, so a webhook Stripe
+-- delivers twice pays the invoice once. `applied` is what was recorded
+-- against the invoice; the rest of `amount` is owed back to the member.
+CREATE TABLE IF NOT EXISTS stripe_checkouts (
+    session_id  TEXT    NOT NULL PRIMARY KEY,
+    invoice_id  INTEGER NOT NULL REFERENCES invoices(id),
+    amount      INTEGER NOT NULL,
+    applied     INTEGER NOT NULL,
+    created_at  DATETIME NOT NULL DEFAULT (datetime('now'))
+);
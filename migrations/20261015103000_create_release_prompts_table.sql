@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS release_prompts (
+    id                  SERIAL PRIMARY KEY,
+    token               TEXT    NOT NULL UNIQUE,
+    game_pk             INTEGER NOT NULL REFERENCES games(game_pk),
+    user_id             INTEGER NOT NULL REFERENCES users(id),
+    rain_probability    INTEGER NOT NULL,
+    response            TEXT,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    responded_at        TIMESTAMPTZ,
+    UNIQUE(game_pk, user_id)
+);
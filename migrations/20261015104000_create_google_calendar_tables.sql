@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS google_accounts (
+    user_id             INTEGER NOT NULL PRIMARY KEY REFERENCES users(id),
+    refresh_token       TEXT    NOT NULL,
+    access_token        TEXT,
+    access_expires_at   TEXT,
+    calendar_id         TEXT    NOT NULL DEFAULT 'primary',
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS google_calendar_events (
+    user_id             INTEGER NOT NULL REFERENCES users(id),
+    game_pk             INTEGER NOT NULL REFERENCES games(game_pk),
+    event_id            TEXT    NOT NULL,
+    content_hash        TEXT    NOT NULL,
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, game_pk)
+);
+
+CREATE TABLE IF NOT EXISTS oauth_states (
+    state               TEXT    NOT NULL PRIMARY KEY,
+    user_id             INTEGER NOT NULL REFERENCES users(id),
+    expires_at          TEXT    NOT NULL,
+    created_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
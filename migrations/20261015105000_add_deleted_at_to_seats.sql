@@ -0,0 +1 @@
+ALTER TABLE seats ADD COLUMN deleted_at TIMESTAMPTZ;
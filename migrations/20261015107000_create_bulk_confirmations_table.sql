@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS bulk_confirmations (
+    token       TEXT    NOT NULL PRIMARY KEY,
+    operation   TEXT    NOT NULL,
+    scope       TEXT    NOT NULL,
+    affected    INTEGER NOT NULL,
+    expires_at  TEXT    NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
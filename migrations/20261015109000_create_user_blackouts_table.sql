@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS user_blackouts (
+    user_id       INTEGER NOT NULL REFERENCES users(id),
+    blackout_date TEXT NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, blackout_date)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE seats ADD COLUMN face_value INTEGER;
+ALTER TABLE game_tickets ADD COLUMN cost INTEGER;
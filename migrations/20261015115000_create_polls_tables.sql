@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS polls (
+    id          SERIAL PRIMARY KEY,
+    game_pk     INTEGER NOT NULL UNIQUE REFERENCES games(game_pk),
+    question    TEXT,
+    created_by  INTEGER REFERENCES users(id),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_at   TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS poll_responses (
+    poll_id     INTEGER NOT NULL REFERENCES polls(id),
+    user_id     INTEGER NOT NULL REFERENCES users(id),
+    answer      TEXT    NOT NULL,
+    seats       INTEGER NOT NULL DEFAULT 0,
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (poll_id, user_id)
+);
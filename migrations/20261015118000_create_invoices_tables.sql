@@ -0,0 +1,36 @@
+CREATE TABLE IF NOT EXISTS invoices (
+    id          SERIAL PRIMARY KEY,
+    user_id     INTEGER NOT NULL REFERENCES users(id),
+    period      TEXT    NOT NULL,
+    total       INTEGER NOT NULL,
+    status      TEXT    NOT NULL DEFAULT 'open',
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, period)
+);
+
+CREATE TABLE IF NOT EXISTS invoice_lines (
+    id              SERIAL PRIMARY KEY,
+    invoice_id      INTEGER NOT NULL REFERENCES invoices(id),
+    ticket_id       INTEGER NOT NULL,
+    game_pk         INTEGER NOT NULL REFERENCES games(game_pk),
+    official_date   TEXT    NOT NULL,
+    away_team_name  TEXT    NOT NULL,
+    seat_id         INTEGER NOT NULL,
+    basis           TEXT    NOT NULL,
+    amount          INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoice_lines_invoice ON invoice_lines(invoice_id);
+
+CREATE TABLE IF NOT EXISTS invoice_payments (
+    id          SERIAL PRIMARY KEY,
+    invoice_id  INTEGER NOT NULL REFERENCES invoices(id),
+    line_id     INTEGER NOT NULL REFERENCES invoice_lines(id),
+    amount      INTEGER NOT NULL,
+    method      TEXT,
+    note        TEXT,
+    recorded_by INTEGER REFERENCES users(id),
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoice_payments_invoice ON invoice_payments(invoice_id);
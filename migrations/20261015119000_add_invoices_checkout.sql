@@ -0,0 +1,2 @@
+ALTER TABLE invoices ADD COLUMN checkout_session_id TEXT;
+ALTER TABLE invoices ADD COLUMN payment_url TEXT;
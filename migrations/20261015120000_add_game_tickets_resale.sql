@@ -0,0 +1 @@
+ALTER TABLE game_tickets ADD COLUMN resale_amount INTEGER;
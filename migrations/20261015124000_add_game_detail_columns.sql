@@ -0,0 +1,8 @@
+-- Game-day context from the schedule's probablePitcher, weather and
+-- broadcasts hydrations. NULL until a scrape fetches them.
+ALTER TABLE games ADD COLUMN away_probable_pitcher TEXT;
+ALTER TABLE games ADD COLUMN home_probable_pitcher TEXT;
+ALTER TABLE games ADD COLUMN weather_condition TEXT;
+ALTER TABLE games ADD COLUMN weather_temp TEXT;
+ALTER TABLE games ADD COLUMN weather_wind TEXT;
+ALTER TABLE games ADD COLUMN tv_broadcasts TEXT;
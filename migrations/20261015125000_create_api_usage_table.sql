@@ -0,0 +1,9 @@
+-- Requests per member per endpoint per day, counted by the member and
+-- admin route layers.
+CREATE TABLE IF NOT EXISTS api_usage (
+    day         TEXT    NOT NULL,
+    user_id     INTEGER NOT NULL REFERENCES users(id),
+    endpoint    TEXT    NOT NULL,
+    requests    INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, user_id, endpoint)
+);
@@ -0,0 +1,11 @@
+-- Conversation between a member and the admins about one ticket request
+CREATE TABLE IF NOT EXISTS request_comments (
+    id          SERIAL PRIMARY KEY,
+    request_id  INTEGER NOT NULL REFERENCES ticket_requests(id) ON DELETE CASCADE,
+    user_id     INTEGER NOT NULL REFERENCES users(id),
+    body        TEXT    NOT NULL,
+    -- 1 when an admin wrote it on the admin side, 0 for the member
+    from_admin  INTEGER NOT NULL DEFAULT 0,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS idx_request_comments_request_id ON request_comments(request_id, id);
@@ -0,0 +1,6 @@
+-- Seasons an admin has closed; their tickets and requests can no longer change
+CREATE TABLE IF NOT EXISTS closed_seasons (
+    season      TEXT PRIMARY KEY,
+    closed_by   INTEGER REFERENCES users(id),
+    closed_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
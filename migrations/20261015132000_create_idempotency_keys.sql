@@ -0,0 +1,13 @@
+-- Idempotency-Key results, so a retried request replays instead of re-running
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    id               SERIAL PRIMARY KEY,
+    user_id          INTEGER NOT NULL REFERENCES users(id),
+    endpoint         TEXT    NOT NULL,
+    idempotency_key  TEXT    NOT NULL,
+    request_hash     TEXT    NOT NULL,
+    response         TEXT,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, endpoint, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
@@ -0,0 +1,7 @@
+-- How each background job's last run went, for the job status API
+CREATE TABLE IF NOT EXISTS job_runs (
+    job         TEXT PRIMARY KEY,
+    status      TEXT NOT NULL,
+    detail      TEXT,
+    finished_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,8 @@
+-- Each member's defaults and notification choices
+CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id             INTEGER NOT NULL PRIMARY KEY REFERENCES users(id),
+    default_seats       INTEGER,
+    contact_email       TEXT,
+    muted_notifications TEXT    NOT NULL DEFAULT '',
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
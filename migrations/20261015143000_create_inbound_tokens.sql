@@ -0,0 +1,6 @@
+-- Mailgun webhook tokens already acted on, so a captured inbound email
+-- can't be replayed. `seen_at` is 'YYYY-MM-DD HH:MM:SS' UTC.
+CREATE TABLE IF NOT EXISTS inbound_tokens (
+    token    TEXT NOT NULL PRIMARY KEY,
+    seen_at  TEXT NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Requests per endpoint per day to the public routes, which don't look up
+-- the member. Member and admin routes are counted per member in api_usage.
+CREATE TABLE IF NOT EXISTS api_public_usage (
+    day         TEXT    NOT NULL,
+    endpoint    TEXT    NOT NULL,
+    requests    INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, endpoint)
+);